@@ -314,6 +314,22 @@ impl_runtime_apis! {
 		fn metadata() -> OpaqueMetadata {
 			Runtime::metadata().into()
 		}
+
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata> {
+			Runtime::metadata_at_version(version).map(Into::into)
+		}
+
+		fn metadata_versions() -> Vec<u32> {
+			Runtime::metadata_versions()
+		}
+
+		fn constant(pallet: Vec<u8>, name: Vec<u8>) -> Option<OpaqueMetadata> {
+			Runtime::constant_metadata(&pallet, &name).map(Into::into)
+		}
+
+		fn storage_entry(pallet: Vec<u8>, name: Vec<u8>) -> Option<OpaqueMetadata> {
+			Runtime::storage_entry_metadata(&pallet, &name).map(Into::into)
+		}
 	}
 
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
@@ -113,6 +113,10 @@ macro_rules! new_full_start {
 				};
 				rpc_setup = Some((shared_voter_state));
 				Ok(node_rpc::create_full(deps))
+			})?
+			.with_extrinsic_outcome_resolver(|builder| {
+				let client = builder.client().clone();
+				Ok(Arc::new(substrate_frame_rpc_system::ExtrinsicOutcomes::new(client)) as Arc<_>)
 			})?;
 
 		(builder, import_setup, inherent_data_providers, rpc_setup)
@@ -33,7 +33,9 @@ use std::{sync::Arc, fmt};
 
 use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash};
 use node_runtime::UncheckedExtrinsic;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ProvideRuntimeApi, Core, Metadata};
+use sp_block_builder::BlockBuilder;
+use sc_client_api::BlockBackend;
 use sp_transaction_pool::TransactionPool;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_consensus::SelectChain;
@@ -95,8 +97,10 @@ pub fn create_full<C, P, M, SC>(
 ) -> jsonrpc_core::IoHandler<M> where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error=BlockChainError> + 'static,
-	C: Send + Sync + 'static,
+	C: BlockBackend<Block> + Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
+	C::Api: substrate_frame_rpc_system::ExtrinsicOutcomeApi<Block>,
+	C::Api: BlockBuilder<Block> + Core<Block> + Metadata<Block>,
 	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
 	C::Api: BabeApi<Block>,
@@ -66,3 +66,64 @@ pub mod time {
 	pub const HOURS: BlockNumber = MINUTES * 60;
 	pub const DAYS: BlockNumber = HOURS * 24;
 }
+
+/// Voter bags.
+pub mod voter_bags {
+	/// The upper thresholds of the voter bags used by the bags list.
+	///
+	/// There are 200 bags, growing geometrically by a factor of about 1.2497 from 1 to
+	/// `u64::max_value()`, so that the order of the voters within a bag is off by less than 25%.
+	/// Changing these values requires a migration of the bags list.
+	pub const THRESHOLDS: [u64; 200] = [
+		1, 2, 3, 4,
+		5, 6, 7, 8,
+		9, 10, 11, 12,
+		15, 19, 23, 29,
+		36, 45, 56, 70,
+		87, 108, 135, 169,
+		211, 264, 329, 412,
+		514, 643, 803, 1003,
+		1254, 1567, 1958, 2447,
+		3057, 3821, 4775, 5967,
+		7457, 9319, 11646, 14555,
+		18189, 22731, 28408, 35502,
+		44367, 55446, 69293, 86596,
+		108221, 135246, 169020, 211229,
+		263977, 329898, 412281, 515237,
+		643903, 804700, 1005652, 1256786,
+		1570633, 1962856, 2453026, 3065602,
+		3831152, 4787878, 5983520, 7477740,
+		9345102, 11678786, 14595244, 18240008,
+		22794953, 28487370, 35601314, 44491771,
+		55602377, 69487554, 86840175, 108526141,
+		135627584, 169496874, 211824094, 264721384,
+		330828330, 413443681, 516689964, 645719189,
+		806969944, 1008488677, 1260331217, 1575064562,
+		1968393975, 2459946681, 3074251268, 3841961671,
+		4801386807, 6000402201, 7498838987, 9371469489,
+		11711738383, 14636425600, 18291473678, 22859270319,
+		28567749584, 35701765843, 44617308078, 55759263811,
+		69683619086, 87085202298, 108832356279, 136010268803,
+		169975123689, 212421774674, 265468318990, 331761790877,
+		414610249180, 518147850212, 647541143062, 809246881536,
+		1011334217588, 1263887353787, 1579508747239, 1973947974976,
+		2466887641314, 3082925544145, 3852802110468, 4814934350464,
+		6017332874765, 7519997634492, 9397911932032, 11744784104337,
+		14677723589570, 18343084713858, 22923769804390, 28648356055819,
+		35802501582607, 44743199821834, 55916593584320, 69880237679117,
+		87330921021255, 109139436552029, 136394033998511, 170454723774559,
+		213021141799929, 266217361706923, 332697886582346, 415780109256042,
+		519609850933568, 649368238587238, 811530244332884, 1014187787964185,
+		1267453525532157, 1583965473108837, 1979517646572080, 2473848186475603,
+		3091624295608816, 3863673137847569, 4828520120419482, 6034311320207643,
+		7541215983588508, 9424428985074950, 11777923068111844, 14719138105666136,
+		18394841375747347, 22988451280897409, 28729189966864992, 35903521558151525,
+		44869446780901263, 56074367277965219, 70077411049394230, 87577333062757677,
+		109447383279886291, 136778882022251218, 170935677095306745, 213622200094292486,
+		266968517916140095, 333636623566656405, 416953270197665966, 521075976821815518,
+		651200489426762497, 813820049844027100, 1017049409946152863, 1271029759551959798,
+		1588434774031524587, 1985103033497804601, 2480828371441822165, 3100347591382334193,
+		3874574838808294178, 4842144223845835713, 6051337671860535047, 7562494202164505183,
+		9451020858366312295, 11811155536451161718, 14760669476541103939, 18446744073709551615,
+	];
+}
@@ -27,7 +27,10 @@ use frame_support::{
 		Weight,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 	},
-	traits::{Currency, Imbalance, KeyOwnerProofSystem, OnUnbalanced, Randomness, LockIdentifier},
+	traits::{
+		Currency, Imbalance, KeyOwnerProofSystem, OnUnbalanced, Randomness, LockIdentifier,
+		ModuleLayout,
+	},
 };
 use sp_core::{
 	crypto::KeyTypeId,
@@ -38,7 +41,7 @@ pub use node_primitives::{AccountId, Signature};
 use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
 use sp_api::impl_runtime_apis;
 use sp_runtime::{
-	Permill, Perbill, Perquintill, Percent, ApplyExtrinsicResult,
+	Permill, Perbill, Perquintill, Percent, ApplyExtrinsicResult, DispatchResult,
 	impl_opaque_keys, generic, create_runtime_str, ModuleId,
 };
 use sp_runtime::curve::PiecewiseLinear;
@@ -90,10 +93,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 247,
+	spec_version: 263,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// Native version.
@@ -194,6 +197,21 @@ impl pallet_babe::Trait for Runtime {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
 	type EpochChangeTrigger = pallet_babe::ExternalTrigger;
+
+	type KeyOwnerProofSystem = Historical;
+
+	type KeyOwnerProof = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		pallet_babe::AuthorityId,
+	)>>::Proof;
+
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		pallet_babe::AuthorityId,
+	)>>::IdentificationTuple;
+
+	type HandleEquivocation =
+		pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences>;
 }
 
 parameter_types! {
@@ -305,6 +323,7 @@ parameter_types! {
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const ElectionLookahead: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominatorsInElection: u32 = 22_500;
 	pub const MaxIterations: u32 = 5;
 }
 
@@ -329,6 +348,35 @@ impl pallet_staking::Trait for Runtime {
 	type MaxIterations = MaxIterations;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = StakingUnsignedPriority;
+	type OnStakerSlash = NominationPools;
+	type SortedListProvider = BagsList;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
+}
+
+parameter_types! {
+	pub const BagThresholds: &'static [u64] = &constants::voter_bags::THRESHOLDS;
+}
+
+impl pallet_bags_list::Trait for Runtime {
+	type Event = Event;
+	type VoteWeightProvider = Staking;
+	type BagThresholds = BagThresholds;
+	type WeightInfo = pallet_bags_list::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const NominationPoolsModuleId: ModuleId = ModuleId(*b"py/nopls");
+	pub const PostUnbondingPoolsWindow: u32 = 4;
+	pub const MaxUnbonding: u32 = 8;
+}
+
+impl pallet_nomination_pools::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type StakingInterface = Staking;
+	type ModuleId = NominationPoolsModuleId;
+	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
+	type MaxUnbonding = MaxUnbonding;
 }
 
 parameter_types! {
@@ -443,10 +491,6 @@ parameter_types! {
 	pub const ProposalBondMinimum: Balance = 1 * DOLLARS;
 	pub const SpendPeriod: BlockNumber = 1 * DAYS;
 	pub const Burn: Permill = Permill::from_percent(50);
-	pub const TipCountdown: BlockNumber = 1 * DAYS;
-	pub const TipFindersFee: Percent = Percent::from_percent(20);
-	pub const TipReportDepositBase: Balance = 1 * DOLLARS;
-	pub const TipReportDepositPerByte: Balance = 1 * CENTS;
 	pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
 }
 
@@ -454,11 +498,6 @@ impl pallet_treasury::Trait for Runtime {
 	type Currency = Balances;
 	type ApproveOrigin = pallet_collective::EnsureMembers<_4, AccountId, CouncilCollective>;
 	type RejectOrigin = pallet_collective::EnsureMembers<_2, AccountId, CouncilCollective>;
-	type Tippers = Elections;
-	type TipCountdown = TipCountdown;
-	type TipFindersFee = TipFindersFee;
-	type TipReportDepositBase = TipReportDepositBase;
-	type TipReportDepositPerByte = TipReportDepositPerByte;
 	type Event = Event;
 	type ProposalRejection = ();
 	type ProposalBond = ProposalBond;
@@ -466,6 +505,44 @@ impl pallet_treasury::Trait for Runtime {
 	type SpendPeriod = SpendPeriod;
 	type Burn = Burn;
 	type ModuleId = TreasuryModuleId;
+	type SpendFunds = Bounties;
+}
+
+parameter_types! {
+	pub const BountyDepositBase: Balance = 1 * DOLLARS;
+	pub const BountyDepositPayoutDelay: BlockNumber = 8 * DAYS;
+	pub const BountyUpdatePeriod: BlockNumber = 90 * DAYS;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: Balance = 10 * DOLLARS;
+	pub const DataDepositPerByte: Balance = 1 * CENTS;
+	pub const MaximumReasonLength: u32 = 16384;
+}
+
+impl pallet_bounties::Trait for Runtime {
+	type Event = Event;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
+	type DataDepositPerByte = DataDepositPerByte;
+	type MaximumReasonLength = MaximumReasonLength;
+}
+
+parameter_types! {
+	pub const TipCountdown: BlockNumber = 1 * DAYS;
+	pub const TipFindersFee: Percent = Percent::from_percent(20);
+	pub const TipReportDepositBase: Balance = 1 * DOLLARS;
+	pub const TipReportDepositPerByte: Balance = 1 * CENTS;
+}
+
+impl pallet_tips::Trait for Runtime {
+	type Event = Event;
+	type Tippers = Elections;
+	type TipCountdown = TipCountdown;
+	type TipFindersFee = TipFindersFee;
+	type TipReportDepositBase = TipReportDepositBase;
+	type TipReportDepositPerByte = TipReportDepositPerByte;
 }
 
 parameter_types! {
@@ -695,7 +772,7 @@ construct_runtime!(
 	{
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
 		Utility: pallet_utility::{Module, Call, Storage, Event<T>},
-		Babe: pallet_babe::{Module, Call, Storage, Config, Inherent(Timestamp)},
+		Babe: pallet_babe::{Module, Call, Storage, Config, Inherent(Timestamp), ValidateUnsigned},
 		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
 		Authorship: pallet_authorship::{Module, Call, Storage, Inherent},
 		Indices: pallet_indices::{Module, Call, Storage, Config<T>, Event<T>},
@@ -723,6 +800,10 @@ construct_runtime!(
 		Recovery: pallet_recovery::{Module, Call, Storage, Event<T>},
 		Vesting: pallet_vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
+		BagsList: pallet_bags_list::{Module, Call, Storage, Event<T>},
+		NominationPools: pallet_nomination_pools::{Module, Call, Storage, Config<T>, Event<T>},
+		Bounties: pallet_bounties::{Module, Call, Storage, Event<T>},
+		Tips: pallet_tips::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -752,8 +833,64 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+
+/// The modules of the runtime, with the prefix of their storage, in their `construct_runtime!`
+/// order before this runtime started recording its modules layout.
+const PREVIOUS_MODULES: &[(&str, Option<&str>)] = &[
+	("System", Some("System")),
+	("Utility", Some("Utility")),
+	("Babe", Some("Babe")),
+	("Timestamp", Some("Timestamp")),
+	("Authorship", Some("Authorship")),
+	("Indices", Some("Indices")),
+	("Balances", Some("Balances")),
+	("TransactionPayment", Some("TransactionPayment")),
+	("Staking", Some("Staking")),
+	("Session", Some("Session")),
+	("Democracy", Some("Democracy")),
+	("Council", Some("Instance1Collective")),
+	("TechnicalCommittee", Some("Instance2Collective")),
+	("Elections", Some("PhragmenElection")),
+	("TechnicalMembership", Some("Instance1Membership")),
+	("FinalityTracker", None),
+	("Grandpa", Some("GrandpaFinality")),
+	("Treasury", Some("Treasury")),
+	("Contracts", Some("Contracts")),
+	("Sudo", Some("Sudo")),
+	("ImOnline", Some("ImOnline")),
+	("AuthorityDiscovery", None),
+	("Offences", Some("Offences")),
+	("Historical", None),
+	("RandomnessCollectiveFlip", Some("RandomnessCollectiveFlip")),
+	("Identity", Some("Identity")),
+	("Society", Some("Society")),
+	("Recovery", Some("Recovery")),
+	("Vesting", Some("Vesting")),
+	("Scheduler", Some("Scheduler")),
+];
+
+parameter_types! {
+	/// The layout of the modules before this runtime started recording it, so that the first
+	/// upgrade recording it reports the modules that moved since.
+	pub PreviousModulesLayout: Vec<ModuleLayout> = PREVIOUS_MODULES.iter()
+		.enumerate()
+		.map(|(index, (name, storage_prefix))| ModuleLayout {
+			name: name.as_bytes().to_vec(),
+			index: index as u8,
+			storage_prefix: storage_prefix.map(|prefix| prefix.as_bytes().to_vec()),
+		})
+		.collect();
+}
+
 /// Executive: handles dispatch to the various modules.
-pub type Executive = frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllModules>;
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllModules,
+	frame_system::SeedModulesLayout<Runtime, PreviousModulesLayout>,
+>;
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -774,6 +911,22 @@ impl_runtime_apis! {
 		fn metadata() -> OpaqueMetadata {
 			Runtime::metadata().into()
 		}
+
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata> {
+			Runtime::metadata_at_version(version).map(Into::into)
+		}
+
+		fn metadata_versions() -> Vec<u32> {
+			Runtime::metadata_versions()
+		}
+
+		fn constant(pallet: Vec<u8>, name: Vec<u8>) -> Option<OpaqueMetadata> {
+			Runtime::constant_metadata(&pallet, &name).map(Into::into)
+		}
+
+		fn storage_entry(pallet: Vec<u8>, name: Vec<u8>) -> Option<OpaqueMetadata> {
+			Runtime::storage_entry_metadata(&pallet, &name).map(Into::into)
+		}
 	}
 
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
@@ -865,6 +1018,29 @@ impl_runtime_apis! {
 		fn current_epoch_start() -> sp_consensus_babe::SlotNumber {
 			Babe::current_epoch_start()
 		}
+
+		fn generate_key_ownership_proof(
+			_slot_number: sp_consensus_babe::SlotNumber,
+			authority_id: sp_consensus_babe::AuthorityId,
+		) -> Option<sp_consensus_babe::OpaqueKeyOwnershipProof> {
+			use codec::Encode;
+
+			Historical::prove((sp_consensus_babe::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(sp_consensus_babe::OpaqueKeyOwnershipProof::new)
+		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: sp_consensus_babe::EquivocationProof<<Block as BlockT>::Header>,
+			key_owner_proof: sp_consensus_babe::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Babe::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
+		}
 	}
 
 	impl sp_authority_discovery::AuthorityDiscoveryApi<Block> for Runtime {
@@ -879,6 +1055,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::ExtrinsicOutcomeApi<Block> for Runtime {
+		fn extrinsic_outcome(index: u32) -> Option<DispatchResult> {
+			System::extrinsic_outcome(index)
+		}
+	}
+
 	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
 		for Runtime
 	{
@@ -961,11 +1143,14 @@ impl_runtime_apis! {
 			let mut batches = Vec::<BenchmarkBatch>::new();
 			let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat);
 
+			add_benchmark!(params, batches, b"bags-list", BagsList);
 			add_benchmark!(params, batches, b"balances", Balances);
+			add_benchmark!(params, batches, b"bounties", Bounties);
 			add_benchmark!(params, batches, b"collective", Council);
 			add_benchmark!(params, batches, b"democracy", Democracy);
 			add_benchmark!(params, batches, b"identity", Identity);
 			add_benchmark!(params, batches, b"im-online", ImOnline);
+			add_benchmark!(params, batches, b"nomination-pools", NominationPools);
 			add_benchmark!(params, batches, b"offences", OffencesBench::<Runtime>);
 			add_benchmark!(params, batches, b"scheduler", Scheduler);
 			add_benchmark!(params, batches, b"session", SessionBench::<Runtime>);
@@ -973,6 +1158,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, b"system", SystemBench::<Runtime>);
 			add_benchmark!(params, batches, b"timestamp", Timestamp);
 			add_benchmark!(params, batches, b"treasury", Treasury);
+			add_benchmark!(params, batches, b"tips", Tips);
 			add_benchmark!(params, batches, b"utility", Utility);
 			add_benchmark!(params, batches, b"vesting", Vesting);
 
@@ -995,4 +1181,21 @@ mod tests {
 
 		is_submit_signed_transaction::<Runtime>();
 	}
+
+	#[test]
+	fn previous_modules_layout_is_kept_by_the_current_one() {
+		use frame_support::traits::ModuleToIndex as _;
+
+		// The modules added since the layout was recorded are appended, so that none of the
+		// previous modules moves.
+		let layout = ModuleToIndex::modules_layout();
+		let previous = PreviousModulesLayout::get();
+		assert_eq!(previous.len(), 30);
+		assert_eq!(layout[..previous.len()], previous[..]);
+
+		let added: Vec<_> = layout[previous.len()..].iter()
+			.map(|module| &module.name[..])
+			.collect();
+		assert_eq!(added, vec![&b"BagsList"[..], b"NominationPools", b"Bounties", b"Tips"]);
+	}
 }
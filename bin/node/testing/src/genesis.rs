@@ -117,5 +117,6 @@ pub fn config_endowed(
 			max_members: 999,
 		}),
 		pallet_vesting: Some(Default::default()),
+		pallet_nomination_pools: Some(Default::default()),
 	}
 }
@@ -88,6 +88,37 @@ pub struct RemoteReadChildRequest<Header: HeaderT> {
 	pub retry_count: Option<usize>,
 }
 
+/// Remote storage range read request.
+///
+/// Requests the entries of the storage, or of a child storage, whose key starts with `prefix`, in
+/// lexicographic order of the keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RemoteReadRangeRequest<Header: HeaderT> {
+	/// Read at state of given block.
+	pub block: Header::Hash,
+	/// Header of block at which read is performed.
+	pub header: Header,
+	/// Storage key for child, or `None` to read the top storage.
+	pub storage_key: Option<PrefixedStorageKey>,
+	/// Prefix of the keys to read.
+	pub prefix: Vec<u8>,
+	/// Read the keys that come after this one, or from `prefix` if `None`.
+	pub start_at: Option<Vec<u8>>,
+	/// Maximum number of entries to read.
+	pub count: u32,
+	/// Number of times to retry request. None means that default RETRY_COUNT is used.
+	pub retry_count: Option<usize>,
+}
+
+/// Entries of a storage range, as returned by a remote storage range read.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageRange {
+	/// Key-value pairs, in lexicographic order of the keys.
+	pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+	/// `true` if there is no other entry with the requested prefix after the last one.
+	pub complete: bool,
+}
+
 /// Remote key changes read request.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RemoteChangesRequest<Header: HeaderT> {
@@ -149,6 +180,11 @@ pub trait Fetcher<Block: BlockT>: Send + Sync {
 		HashMap<Vec<u8>, Option<Vec<u8>>>,
 		ClientError,
 	>> + Unpin + Send + 'static;
+	/// Remote storage range read future.
+	type RemoteReadRangeResult: Future<Output = Result<
+		StorageRange,
+		ClientError,
+	>> + Unpin + Send + 'static;
 	/// Remote call result future.
 	type RemoteCallResult: Future<Output = Result<
 		Vec<u8>,
@@ -177,6 +213,11 @@ pub trait Fetcher<Block: BlockT>: Send + Sync {
 		&self,
 		request: RemoteReadChildRequest<Block::Header>
 	) -> Self::RemoteReadResult;
+	/// Fetch remote storage range.
+	fn remote_read_range(
+		&self,
+		request: RemoteReadRangeRequest<Block::Header>
+	) -> Self::RemoteReadRangeResult;
 	/// Fetch remote call result.
 	fn remote_call(&self, request: RemoteCallRequest<Block::Header>) -> Self::RemoteCallResult;
 	/// Fetch remote changes ((block number, extrinsic index)) where given key has been changed
@@ -210,6 +251,14 @@ pub trait FetchChecker<Block: BlockT>: Send + Sync {
 		request: &RemoteReadChildRequest<Block::Header>,
 		remote_proof: StorageProof,
 	) -> ClientResult<HashMap<Vec<u8>, Option<Vec<u8>>>>;
+	/// Check remote storage range read proof.
+	///
+	/// Fails if the proof doesn't cover all the entries of the range.
+	fn check_read_range_proof(
+		&self,
+		request: &RemoteReadRangeRequest<Block::Header>,
+		remote_proof: StorageProof,
+	) -> ClientResult<StorageRange>;
 	/// Check remote method execution proof.
 	fn check_execution_proof(
 		&self,
@@ -336,6 +385,7 @@ pub mod tests {
 	impl Fetcher<Block> for OkCallFetcher {
 		type RemoteHeaderResult = Ready<Result<Header, ClientError>>;
 		type RemoteReadResult = Ready<Result<HashMap<Vec<u8>, Option<Vec<u8>>>, ClientError>>;
+		type RemoteReadRangeResult = Ready<Result<StorageRange, ClientError>>;
 		type RemoteCallResult = Ready<Result<Vec<u8>, ClientError>>;
 		type RemoteChangesResult = Ready<Result<Vec<(NumberFor<Block>, u32)>, ClientError>>;
 		type RemoteBodyResult = Ready<Result<Vec<Extrinsic>, ClientError>>;
@@ -352,6 +402,10 @@ pub mod tests {
 			not_implemented_in_tests()
 		}
 
+		fn remote_read_range(&self, _request: RemoteReadRangeRequest<Header>) -> Self::RemoteReadRangeResult {
+			not_implemented_in_tests()
+		}
+
 		fn remote_call(&self, _request: RemoteCallRequest<Header>) -> Self::RemoteCallResult {
 			futures::future::ready(Ok((*self.lock()).clone()))
 		}
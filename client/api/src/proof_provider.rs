@@ -39,6 +39,18 @@ pub trait ProofProvider<Block: BlockT> {
 		keys: &mut dyn Iterator<Item=&[u8]>,
 	) -> sp_blockchain::Result<StorageProof>;
 
+	/// Reads at most `count` entries of the storage, or of the child storage if `child_info` is
+	/// `Some`, at a given block, whose key starts with `prefix` and comes after `start_at`,
+	/// returning a proof of the whole range.
+	fn read_range_proof(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		count: u32,
+	) -> sp_blockchain::Result<StorageProof>;
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
@@ -64,15 +64,7 @@ arg_enum! {
 	pub enum TracingReceiver {
 		Log,
 		Telemetry,
-	}
-}
-
-impl Into<sc_tracing::TracingReceiver> for TracingReceiver {
-	fn into(self) -> sc_tracing::TracingReceiver {
-		match self {
-			TracingReceiver::Log => sc_tracing::TracingReceiver::Log,
-			TracingReceiver::Telemetry => sc_tracing::TracingReceiver::Telemetry,
-		}
+		ChromeTrace,
 	}
 }
 
@@ -19,6 +19,7 @@ mod check_block_cmd;
 mod export_blocks_cmd;
 mod export_state_cmd;
 mod import_blocks_cmd;
+mod peer_store_cmd;
 mod purge_chain_cmd;
 mod revert_cmd;
 mod run_cmd;
@@ -27,6 +28,7 @@ pub use self::build_spec_cmd::BuildSpecCmd;
 pub use self::check_block_cmd::CheckBlockCmd;
 pub use self::export_blocks_cmd::ExportBlocksCmd;
 pub use self::import_blocks_cmd::ImportBlocksCmd;
+pub use self::peer_store_cmd::PeerStoreCmd;
 pub use self::purge_chain_cmd::PurgeChainCmd;
 pub use self::revert_cmd::RevertCmd;
 pub use self::run_cmd::RunCmd;
@@ -61,6 +63,9 @@ pub enum Subcommand {
 
 	/// Export state as raw chain spec.
 	ExportState(ExportStateCmd),
+
+	/// Inspect or clear the peers known from previous runs.
+	PeerStore(PeerStoreCmd),
 }
 
 // TODO: move to config.rs?
@@ -406,6 +411,7 @@ macro_rules! substrate_cli_subcommands {
 }
 
 substrate_cli_subcommands!(
-	Subcommand => BuildSpec, ExportBlocks, ImportBlocks, CheckBlock, Revert, PurgeChain, ExportState
+	Subcommand => BuildSpec, ExportBlocks, ImportBlocks, CheckBlock, Revert, PurgeChain, ExportState,
+	PeerStore
 );
 
@@ -0,0 +1,79 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::SharedParams;
+use crate::CliConfiguration;
+use sc_network::peer_store::{self, PeerStore};
+use sc_service::{Configuration, ServiceBuilderCommand};
+use std::fmt::Debug;
+use structopt::StructOpt;
+
+/// The `peer-store` command used to inspect or clear the peers known from previous runs.
+#[derive(Debug, StructOpt, Clone)]
+pub struct PeerStoreCmd {
+	/// Remove all the known peers instead of listing them.
+	#[structopt(long = "clear")]
+	pub clear: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl PeerStoreCmd {
+	/// Run the peer-store command
+	pub fn run<B, BC, BB>(&self, config: Configuration, builder: B) -> error::Result<()>
+	where
+		B: FnOnce(Configuration) -> Result<BC, sc_service::error::Error>,
+		BC: ServiceBuilderCommand<Block = BB> + Unpin,
+		BB: sp_runtime::traits::Block + Debug,
+	{
+		let backend = builder(config)?.peer_store();
+
+		if self.clear {
+			PeerStore::clear(&*backend)?;
+			println!("Peer store cleared.");
+			return Ok(());
+		}
+
+		let now = peer_store::unix_time();
+		let mut peers = PeerStore::load(&*backend)?.peers.into_iter().collect::<Vec<_>>();
+		peers.sort_by(|(_, a), (_, b)| b.last_seen.cmp(&a.last_seen));
+
+		for (peer_id, peer) in peers {
+			println!("{}", peer_id);
+			println!("  reputation: {} (now {})", peer.reputation, peer.reputation_at(now));
+			println!("  last seen: {}s ago", now.saturating_sub(peer.last_seen));
+			match peer.banned_until() {
+				Some(until) if until > now => println!("  banned for another {}s", until - now),
+				Some(_) => println!("  ban expired"),
+				None => {},
+			}
+			for address in &peer.addresses {
+				println!("  address: {}", address);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for PeerStoreCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
@@ -26,9 +26,9 @@ use app_dirs::{AppDataType, AppInfo};
 use names::{Generator, Name};
 use sc_client_api::execution_extensions::ExecutionStrategies;
 use sc_service::config::{
-	Configuration, DatabaseConfig, ExtTransport, KeystoreConfig, NetworkConfiguration,
-	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
-	TaskType, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
+	Configuration, DatabaseConfig, DeterministicStackLimit, ExtTransport, KeystoreConfig,
+	NetworkConfiguration, NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role,
+	RpcMethods, TaskType, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 };
 use sc_service::{ChainSpec, TracingReceiver};
 use std::future::Future;
@@ -43,6 +43,9 @@ pub(crate) const NODE_NAME_MAX_LENGTH: usize = 32;
 /// default sub directory to store network config
 pub(crate) const DEFAULT_NETWORK_CONFIG_PATH: &'static str = "network";
 
+/// default sub directory to store compiled wasm runtimes
+pub(crate) const DEFAULT_WASM_RUNTIME_CACHE_PATH: &'static str = "wasmtime";
+
 /// A trait that allows converting an object to a Configuration
 pub trait CliConfiguration: Sized {
 	/// Get the SharedParams for this object
@@ -241,6 +244,16 @@ pub trait CliConfiguration: Sized {
 			.unwrap_or(Default::default()))
 	}
 
+	/// Get the deterministic stack limit of the Wasm runtime.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its
+	/// `DeterministicStackLimit::default()`.
+	fn wasm_runtime_stack_limit(&self) -> Result<Option<DeterministicStackLimit>> {
+		Ok(self.import_params()
+			.map(|x| x.wasm_runtime_stack_limit())
+			.unwrap_or(Some(Default::default())))
+	}
+
 	/// Get the execution strategies.
 	///
 	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its
@@ -465,6 +478,8 @@ pub trait CliConfiguration: Sized {
 			tracing_receiver: self.tracing_receiver()?,
 			chain_spec,
 			max_runtime_instances,
+			wasm_runtime_cache_path: Some(config_dir.join(DEFAULT_WASM_RUNTIME_CACHE_PATH)),
+			wasm_runtime_stack_limit: self.wasm_runtime_stack_limit()?,
 			announce_block: self.announce_block()?,
 			role,
 		})
@@ -22,6 +22,8 @@ use crate::arg_enums::{
 use crate::params::DatabaseParams;
 use crate::params::PruningParams;
 use sc_client_api::execution_extensions::ExecutionStrategies;
+use sc_service::config::DeterministicStackLimit;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// Parameters for block import.
@@ -53,6 +55,17 @@ pub struct ImportParams {
 	)]
 	pub wasm_method: WasmExecutionMethod,
 
+	/// Maximum of the logical stack of the Wasm runtime, in values.
+	///
+	/// A runtime that exceeds it traps deterministically, so all nodes that need to agree on the
+	/// outcome of a block must use the same value. `0` disables the limit.
+	#[structopt(
+		long = "wasm-runtime-stack-limit",
+		value_name = "VALUES",
+		default_value = "65536"
+	)]
+	pub wasm_runtime_stack_limit: u32,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub execution_strategies: ExecutionStrategiesParams,
@@ -78,12 +91,39 @@ pub struct ImportParams {
 		default_value = "Log"
 	)]
 	pub tracing_receiver: TracingReceiver,
+
+	/// Directory to write the traces to when the `ChromeTrace` receiver is used.
+	#[structopt(
+		long = "tracing-chrome-trace-dir",
+		value_name = "PATH",
+		parse(from_os_str),
+		default_value = "chrome-traces"
+	)]
+	pub tracing_chrome_trace_dir: PathBuf,
+
+	/// Size in bytes after which a new trace file is started when the `ChromeTrace` receiver is
+	/// used.
+	#[structopt(
+		long = "tracing-chrome-trace-max-file-size",
+		value_name = "Bytes",
+		default_value = "104857600"
+	)]
+	pub tracing_chrome_trace_max_file_size: u64,
 }
 
 impl ImportParams {
 	/// Receiver to process tracing messages.
 	pub fn tracing_receiver(&self) -> sc_service::TracingReceiver {
-		self.tracing_receiver.clone().into()
+		match self.tracing_receiver {
+			TracingReceiver::Log => sc_service::TracingReceiver::Log,
+			TracingReceiver::Telemetry => sc_service::TracingReceiver::Telemetry,
+			TracingReceiver::ChromeTrace => sc_service::TracingReceiver::ChromeTrace(
+				sc_tracing::ChromeTraceConfig {
+					path: self.tracing_chrome_trace_dir.clone(),
+					max_file_size: self.tracing_chrome_trace_max_file_size,
+				}
+			),
+		}
 	}
 
 	/// Comma separated list of targets for tracing.
@@ -101,6 +141,14 @@ impl ImportParams {
 		self.wasm_method.into()
 	}
 
+	/// Get the deterministic stack limit of the Wasm runtime, `None` if it is disabled.
+	pub fn wasm_runtime_stack_limit(&self) -> Option<DeterministicStackLimit> {
+		match self.wasm_runtime_stack_limit {
+			0 => None,
+			logical_max => Some(DeterministicStackLimit { logical_max }),
+		}
+	}
+
 	/// Get execution strategies for the parameters
 	pub fn execution_strategies(
 		&self,
@@ -16,7 +16,9 @@
 
 use crate::params::node_key_params::NodeKeyParams;
 use sc_network::{
-	config::{NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, TransportConfig},
+	config::{
+		BandwidthLimit, NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, TransportConfig,
+	},
 	multiaddr::Protocol,
 };
 use sc_service::{ChainSpec, config::{Multiaddr, MultiaddrWithPeerId}};
@@ -105,6 +107,18 @@ pub struct NetworkParams {
 	/// This option will be removed in the future.
 	#[structopt(long)]
 	pub legacy_network_protocol: bool,
+
+	/// Limit the outgoing bandwidth of the legacy substream or of a notifications protocol.
+	///
+	/// Given as `PROTOCOL=BYTES_PER_SEC[:BURST]`, e.g. `/dot/transactions/1=100000:500000`, or
+	/// `/substrate/dot=1000000` for the legacy substream. The burst defaults to one second worth
+	/// of bytes. Messages exceeding the limit are dropped. Can be passed once per protocol.
+	#[structopt(
+		long = "bandwidth-limit",
+		value_name = "PROTOCOL=BYTES_PER_SEC[:BURST]",
+		parse(try_from_str = parse_bandwidth_limit)
+	)]
+	pub bandwidth_limits: Vec<(String, BandwidthLimit)>,
 }
 
 impl NetworkParams {
@@ -147,6 +161,8 @@ impl NetworkParams {
 			listen_addresses,
 			public_addresses,
 			notifications_protocols: Vec::new(),
+			request_response_protocols: Vec::new(),
+			bandwidth_limits: self.bandwidth_limits.iter().cloned().collect(),
 			node_key,
 			node_name: node_name.to_string(),
 			client_version: client_id.to_string(),
@@ -164,3 +180,45 @@ impl NetworkParams {
 		}
 	}
 }
+
+fn parse_bandwidth_limit(s: &str) -> Result<(String, BandwidthLimit), String> {
+	let pos = s.rfind('=').ok_or_else(|| format!("Missing bandwidth limit in {:?}", s))?;
+	let (protocol, limit) = (&s[..pos], &s[pos + 1..]);
+	if protocol.is_empty() {
+		return Err(format!("Missing protocol name in {:?}", s));
+	}
+
+	let mut parts = limit.splitn(2, ':');
+	let bytes_per_sec = parts.next().unwrap_or_default().parse::<u64>()
+		.map_err(|e| format!("Invalid bytes per second in {:?}: {}", s, e))?;
+	let burst = match parts.next() {
+		Some(burst) => burst.parse::<u64>()
+			.map_err(|e| format!("Invalid burst in {:?}: {}", s, e))?,
+		None => bytes_per_sec,
+	};
+
+	Ok((protocol.to_owned(), BandwidthLimit { bytes_per_sec, burst }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_bandwidth_limits() {
+		assert_eq!(
+			parse_bandwidth_limit("/dot/transactions/1=1000:5000"),
+			Ok((
+				"/dot/transactions/1".to_owned(),
+				BandwidthLimit { bytes_per_sec: 1000, burst: 5000 },
+			)),
+		);
+		assert_eq!(
+			parse_bandwidth_limit("/sup/gossip=1000"),
+			Ok(("/sup/gossip".to_owned(), BandwidthLimit { bytes_per_sec: 1000, burst: 1000 })),
+		);
+		assert!(parse_bandwidth_limit("/dot/transactions/1").is_err());
+		assert!(parse_bandwidth_limit("=1000").is_err());
+		assert!(parse_bandwidth_limit("/sup/gossip=1000:x").is_err());
+	}
+}
@@ -199,6 +199,7 @@ impl<C: SubstrateCli> Runner<C> {
 			Subcommand::Revert(cmd) => cmd.run(self.config, builder),
 			Subcommand::PurgeChain(cmd) => cmd.run(self.config),
 			Subcommand::ExportState(cmd) => cmd.run(self.config, builder),
+			Subcommand::PeerStore(cmd) => cmd.run(self.config, builder),
 		}
 	}
 
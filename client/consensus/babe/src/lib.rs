@@ -711,8 +711,9 @@ pub struct BabeVerifier<Block: BlockT, Client> {
 impl<Block, Client> BabeVerifier<Block, Client>
 	where
 		Block: BlockT,
-		Client: HeaderBackend<Block> + HeaderMetadata<Block> + ProvideRuntimeApi<Block>,
-		Client::Api: BlockBuilderApi<Block, Error = sp_blockchain::Error>,
+		Client: AuxStore + HeaderBackend<Block> + HeaderMetadata<Block> + ProvideRuntimeApi<Block>,
+		Client::Api: BlockBuilderApi<Block, Error = sp_blockchain::Error>
+			+ BabeApi<Block, Error = sp_blockchain::Error>,
 {
 	fn check_inherents(
 		&self,
@@ -737,6 +738,77 @@ impl<Block, Client> BabeVerifier<Block, Client>
 			Ok(())
 		}
 	}
+
+	fn check_and_report_equivocation(
+		&self,
+		slot_now: SlotNumber,
+		slot: SlotNumber,
+		header: &Block::Header,
+		author: &AuthorityId,
+		origin: &BlockOrigin,
+	) -> Result<(), Error<Block>> {
+		// don't report any equivocations during initial sync
+		// as they are most likely stale.
+		if *origin == BlockOrigin::NetworkInitialSync {
+			return Ok(());
+		}
+
+		// check if authorship of this header is an equivocation and return a proof if so.
+		let equivocation_proof =
+			match check_equivocation(&*self.client, slot_now, slot, header, author)
+				.map_err(Error::Client)?
+			{
+				Some(proof) => proof,
+				None => return Ok(()),
+			};
+
+		info!(
+			"Slot author {:?} is equivocating at slot {} with headers {:?} and {:?}",
+			author,
+			slot,
+			equivocation_proof.fst_header().hash(),
+			equivocation_proof.snd_header().hash(),
+		);
+
+		// get the best block on which we will build and send the equivocation report.
+		let best_id = BlockId::Hash(self.client.info().best_hash);
+
+		// generate a key ownership proof. the proof is generated at the best
+		// block, so the key ownership of the offender must still be provable
+		// in the current session.
+		let key_owner_proof = match self.client
+			.runtime_api()
+			.generate_key_ownership_proof(&best_id, slot, author.clone())
+			.map_err(Error::Client)?
+		{
+			Some(proof) => proof,
+			None => {
+				debug!(target: "babe", "Equivocation offender is not part of the authority set.");
+				return Ok(());
+			}
+		};
+
+		let equivocation_proof = sp_consensus_babe::EquivocationProof {
+			offender: author.clone(),
+			slot_number: slot,
+			first_header: equivocation_proof.fst_header().clone(),
+			second_header: equivocation_proof.snd_header().clone(),
+		};
+
+		// submit equivocation report at best block.
+		self.client
+			.runtime_api()
+			.submit_report_equivocation_unsigned_extrinsic(
+				&best_id,
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(Error::Client)?;
+
+		info!(target: "babe", "Submitted equivocation report for author {:?}", author);
+
+		Ok(())
+	}
 }
 
 impl<Block, Client> Verifier<Block> for BabeVerifier<Block, Client> where
@@ -807,24 +879,17 @@ impl<Block, Client> Verifier<Block> for BabeVerifier<Block, Client> where
 
 				let slot_number = babe_pre_digest.slot_number();
 
-				let author = verified_info.author;
-
 				// the header is valid but let's check if there was something else already
-				// proposed at the same slot by the given author
-				if let Some(equivocation_proof) = check_equivocation(
-					&*self.client,
+				// proposed at the same slot by the given author. if there was, we will
+				// report the equivocation to the runtime.
+				if let Err(err) = self.check_and_report_equivocation(
 					slot_now,
-					babe_pre_digest.slot_number(),
+					slot_number,
 					&header,
-					&author,
-				).map_err(|e| e.to_string())? {
-					info!(
-						"Slot author {:?} is equivocating at slot {} with headers {:?} and {:?}",
-						author,
-						babe_pre_digest.slot_number(),
-						equivocation_proof.fst_header().hash(),
-						equivocation_proof.snd_header().hash(),
-					);
+					&verified_info.author,
+					&origin,
+				) {
+					warn!(target: "babe", "Error checking/reporting BABE equivocation: {:?}", err);
 				}
 
 				// if the body is passed through, we need to use the runtime
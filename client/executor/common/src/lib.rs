@@ -20,5 +20,6 @@
 
 pub mod error;
 pub mod sandbox;
+pub mod stack_limit;
 pub mod util;
 pub mod wasm_runtime;
@@ -0,0 +1,66 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deterministic limiting of the stack depth of a wasm runtime.
+//!
+//! How deep a runtime can recurse natively depends on the executor and on the machine. To make
+//! running out of stack deterministic, the code is instrumented so that every function has a
+//! logical stack cost, which is the number of its locals plus the maximal height of its operand
+//! stack. The cost is added to a global counter on entry and subtracted on exit, and the runtime
+//! traps with `unreachable` once the counter would exceed the configured maximum.
+//!
+//! The counter is a mutable global of the module, so it is reset together with all the other
+//! globals when an instance is reused.
+
+use crate::error::WasmError;
+use parity_wasm::elements::{deserialize_buffer, serialize, Module as RawModule};
+
+/// The default maximum of the logical stack.
+///
+/// This is low enough to not exhaust the native stack of either executor before the logical
+/// limit is hit.
+pub const DEFAULT_LOGICAL_STACK_MAX: u32 = 65536;
+
+/// The deterministic limit of the stack depth of a wasm runtime.
+///
+/// Nodes that need to agree on the outcome of executing a runtime must use the same limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeterministicStackLimit {
+	/// The maximum of the logical stack, in values.
+	///
+	/// A runtime trying to exceed it traps.
+	pub logical_max: u32,
+}
+
+impl Default for DeterministicStackLimit {
+	fn default() -> Self {
+		DeterministicStackLimit { logical_max: DEFAULT_LOGICAL_STACK_MAX }
+	}
+}
+
+/// Instrument the given wasm `code`, so that it traps once its logical stack would exceed the
+/// given `limit`.
+pub fn inject_stack_depth_metering(
+	code: &[u8],
+	limit: &DeterministicStackLimit,
+) -> Result<Vec<u8>, WasmError> {
+	let module: RawModule = deserialize_buffer(code).map_err(|_| WasmError::InvalidModule)?;
+
+	let module = pwasm_utils::stack_height::inject_limiter(module, limit.logical_max)
+		.map_err(|e| WasmError::Other(format!("cannot inject the stack limiter: {:?}", e)))?;
+
+	serialize(module).map_err(|e| WasmError::Other(format!("cannot serialize module: {}", e)))
+}
@@ -46,6 +46,8 @@ fn call_in_wasm<E: Externalities>(
 		Some(1024),
 		HostFunctions::host_functions(),
 		8,
+		None,
+		None,
 	);
 	executor.call_in_wasm(
 		&WASM_BINARY[..],
@@ -532,6 +534,8 @@ fn should_trap_when_heap_exhausted(wasm_method: WasmExecutionMethod) {
 		Some(17),  // `17` is the initial number of pages compiled into the binary.
 		HostFunctions::host_functions(),
 		8,
+		None,
+		None,
 	);
 	executor.call_in_wasm(
 		&WASM_BINARY[..],
@@ -552,6 +556,8 @@ fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 		&WASM_BINARY[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
+		None,
 	).expect("Creates runtime");
 
 	let instance = runtime.new_instance().unwrap();
@@ -586,6 +592,8 @@ fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 		&WASM_BINARY[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -606,6 +614,8 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 		&WASM_BINARY[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -643,3 +653,133 @@ fn parallel_execution(wasm_method: WasmExecutionMethod) {
 		t.join().unwrap();
 	}
 }
+
+#[test]
+#[cfg(feature = "wasmtime")]
+fn compiled_runtime_is_stored_in_cache_path() {
+	let cache_dir = tempfile::tempdir().unwrap();
+	let host_functions = HostFunctions::host_functions();
+
+	crate::wasm_runtime::precompile_wasm_runtime(
+		WasmExecutionMethod::Compiled,
+		&WASM_BINARY[..],
+		&host_functions,
+		cache_dir.path(),
+		None,
+	).expect("Compiles the runtime");
+	assert!(std::fs::read_dir(cache_dir.path()).unwrap().next().is_some());
+
+	// A runtime created afterwards uses the same cache.
+	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
+		WasmExecutionMethod::Compiled,
+		1024,
+		&WASM_BINARY[..],
+		host_functions,
+		true,
+		Some(cache_dir.path()),
+		None,
+	).expect("Creates runtime");
+	let instance = runtime.new_instance().unwrap();
+
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+	let res = crate::native_executor::with_externalities_safe(
+		&mut ext,
+		move || instance.call("test_empty_return", &[]),
+	).unwrap().unwrap();
+	assert_eq!(res, vec![0u8; 0]);
+}
+
+/// A runtime exporting `test_recursion`, which recurses as deep as the `u32` given as input.
+fn recursion_runtime() -> Vec<u8> {
+	wabt::wat2wasm(r#"
+		(module
+			(memory (export "memory") 17)
+			(global (export "__heap_base") i32 (i32.const 1024))
+
+			(func $recurse (param $depth i32)
+				(if (i32.eqz (get_local $depth))
+					(then (return))
+				)
+				(call $recurse (i32.sub (get_local $depth) (i32.const 1)))
+			)
+
+			(func (export "test_recursion") (param $ptr i32) (param $len i32) (result i64)
+				(call $recurse (i32.load (get_local $ptr)))
+				(i64.const 0)
+			)
+		)
+	"#).unwrap()
+}
+
+/// Returns whether recursing `depth` deep in the given `runtime` succeeds.
+fn recursion_succeeds(runtime: &dyn sc_executor_common::wasm_runtime::WasmModule, depth: u32) -> bool {
+	runtime.new_instance().unwrap().call("test_recursion", &depth.encode()).is_ok()
+}
+
+/// Returns the deepest recursion that succeeds, searching up to `max`.
+fn max_recursion_depth(wasm_method: WasmExecutionMethod, logical_max: u32, max: u32) -> u32 {
+	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
+		wasm_method,
+		1024,
+		&recursion_runtime(),
+		HostFunctions::host_functions(),
+		true,
+		None,
+		Some(sc_executor_common::stack_limit::DeterministicStackLimit { logical_max }),
+	).expect("Creates runtime");
+
+	let (mut low, mut high) = (0, max);
+	assert!(recursion_succeeds(&*runtime, low));
+	assert!(!recursion_succeeds(&*runtime, high));
+	while high - low > 1 {
+		let mid = low + (high - low) / 2;
+		if recursion_succeeds(&*runtime, mid) {
+			low = mid;
+		} else {
+			high = mid;
+		}
+	}
+	low
+}
+
+#[test_case(WasmExecutionMethod::Interpreted)]
+#[cfg_attr(feature = "wasmtime", test_case(WasmExecutionMethod::Compiled))]
+fn stack_depth_limit_is_enforced(wasm_method: WasmExecutionMethod) {
+	let max_depth = max_recursion_depth(wasm_method, 1024, 1024);
+	assert!(max_depth > 0);
+
+	// The limit scales with the configured maximum.
+	assert!(max_recursion_depth(wasm_method, 2048, 2048) > max_depth);
+}
+
+#[test_case(WasmExecutionMethod::Interpreted)]
+#[cfg_attr(feature = "wasmtime", test_case(WasmExecutionMethod::Compiled))]
+fn stack_depth_limit_is_reset_after_trap(wasm_method: WasmExecutionMethod) {
+	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
+		wasm_method,
+		1024,
+		&recursion_runtime(),
+		HostFunctions::host_functions(),
+		true,
+		None,
+		Some(sc_executor_common::stack_limit::DeterministicStackLimit { logical_max: 1024 }),
+	).expect("Creates runtime");
+	let instance = runtime.new_instance().unwrap();
+
+	assert!(instance.call("test_recursion", &10u32.encode()).is_ok());
+	assert!(instance.call("test_recursion", &100_000u32.encode()).is_err());
+	// The instance is reused, so the stack depth must have been reset.
+	assert!(instance.call("test_recursion", &10u32.encode()).is_ok());
+}
+
+#[test]
+#[cfg(feature = "wasmtime")]
+fn stack_depth_limit_is_the_same_for_all_execution_methods() {
+	for logical_max in &[256, 1024, 4096] {
+		assert_eq!(
+			max_recursion_depth(WasmExecutionMethod::Interpreted, *logical_max, *logical_max),
+			max_recursion_depth(WasmExecutionMethod::Compiled, *logical_max, *logical_max),
+		);
+	}
+}
@@ -43,9 +43,9 @@ pub use codec::Codec;
 pub use sp_core::traits::{Externalities, CallInWasm};
 #[doc(hidden)]
 pub use sp_wasm_interface;
-pub use wasm_runtime::WasmExecutionMethod;
+pub use wasm_runtime::{WasmExecutionMethod, embedded_runtime_codes};
 
-pub use sc_executor_common::{error, sandbox};
+pub use sc_executor_common::{error, sandbox, stack_limit};
 
 /// Provides runtime information.
 pub trait RuntimeInfo {
@@ -78,6 +78,8 @@ mod tests {
 			Some(8),
 			sp_io::SubstrateHostFunctions::host_functions(),
 			8,
+			None,
+			None,
 		);
 		let res = executor.call_in_wasm(
 			&WASM_BINARY[..],
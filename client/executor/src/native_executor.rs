@@ -15,7 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	RuntimeInfo, error::{Error, Result},
+	RuntimeInfo, error::{Error, Result, WasmError},
 	wasm_runtime::{RuntimeCache, WasmExecutionMethod},
 };
 use sp_version::{NativeVersion, RuntimeVersion};
@@ -24,9 +24,9 @@ use sp_core::{
 	NativeOrEncoded, traits::{CodeExecutor, Externalities, RuntimeCode, MissingHostFunctions},
 };
 use log::trace;
-use std::{result, panic::{UnwindSafe, AssertUnwindSafe}, sync::Arc};
+use std::{result, panic::{UnwindSafe, AssertUnwindSafe}, path::PathBuf, sync::Arc};
 use sp_wasm_interface::{HostFunctions, Function};
-use sc_executor_common::wasm_runtime::WasmInstance;
+use sc_executor_common::{wasm_runtime::WasmInstance, stack_limit::DeterministicStackLimit};
 
 /// Default num of pages for the heap
 const DEFAULT_HEAP_PAGES: u64 = 1024;
@@ -87,6 +87,10 @@ pub struct WasmExecutor {
 	cache: Arc<RuntimeCache>,
 	/// The size of the instances cache.
 	max_runtime_instances: usize,
+	/// The directory for the compiled runtimes stored on disk.
+	cache_path: Option<PathBuf>,
+	/// The deterministic limit of the stack depth of the runtimes.
+	stack_limit: Option<DeterministicStackLimit>,
 }
 
 impl WasmExecutor {
@@ -98,18 +102,52 @@ impl WasmExecutor {
 	///
 	/// `default_heap_pages` - Number of 64KB pages to allocate for Wasm execution.
 	/// 	Defaults to `DEFAULT_HEAP_PAGES` if `None` is provided.
+	///
+	/// `host_functions` - The set of host functions to be available for import provided by this
+	/// 	executor.
+	///
+	/// `max_runtime_instances` - The number of runtime instances to keep in memory ready for reuse.
+	///
+	/// `cache_path` - A path to a directory where the executor can place its files for purposes of
+	/// 	caching compiled runtimes. Compiled runtimes are then reused across restarts of the node.
+	/// 	Only used by the compiled execution method.
+	///
+	/// `stack_limit` - The deterministic limit of the stack depth of the runtimes. If it is given,
+	/// 	runtimes trap at the same depth with every execution method and on every machine.
 	pub fn new(
 		method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		host_functions: Vec<&'static dyn Function>,
 		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
+		stack_limit: Option<DeterministicStackLimit>,
 	) -> Self {
 		WasmExecutor {
 			method,
 			default_heap_pages: default_heap_pages.unwrap_or(DEFAULT_HEAP_PAGES),
 			host_functions: Arc::new(host_functions),
-			cache: Arc::new(RuntimeCache::new(max_runtime_instances)),
+			cache: Arc::new(RuntimeCache::new(max_runtime_instances, cache_path.clone(), stack_limit)),
 			max_runtime_instances,
+			cache_path,
+			stack_limit,
+		}
+	}
+
+	/// Compile the given runtime `code` ahead of time, e.g. a pending runtime upgrade.
+	///
+	/// The compiled code is stored in the on-disk cache, so that it is not compiled again once the
+	/// runtime is needed. This does nothing if no cache path was given or the execution method does
+	/// not compile the code.
+	pub fn precompile_runtime(&self, code: &[u8]) -> std::result::Result<(), WasmError> {
+		match self.cache_path {
+			Some(ref cache_path) => crate::wasm_runtime::precompile_wasm_runtime(
+				self.method,
+				code,
+				&*self.host_functions,
+				cache_path,
+				self.stack_limit,
+			),
+			None => Ok(()),
 		}
 	}
 
@@ -190,6 +228,8 @@ impl sp_core::traits::CallInWasm for WasmExecutor {
 				&wasm_code,
 				self.host_functions.to_vec(),
 				allow_missing_host_functions,
+				self.cache_path.as_deref(),
+				self.stack_limit,
 			)
 				.map_err(|e| format!("Failed to create module: {:?}", e))?;
 
@@ -233,6 +273,26 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 		fallback_method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		max_runtime_instances: usize,
+	) -> Self {
+		Self::new_with_wasm_cache_path(
+			fallback_method,
+			default_heap_pages,
+			max_runtime_instances,
+			None,
+			Some(DeterministicStackLimit::default()),
+		)
+	}
+
+	/// Create new instance which stores the compiled runtimes below `cache_path` and limits their
+	/// stack depth with the given `stack_limit`, if any.
+	///
+	/// See [`WasmExecutor::new`] for the meaning of the parameters.
+	pub fn new_with_wasm_cache_path(
+		fallback_method: WasmExecutionMethod,
+		default_heap_pages: Option<u64>,
+		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
+		stack_limit: Option<DeterministicStackLimit>,
 	) -> Self {
 		let mut host_functions = sp_io::SubstrateHostFunctions::host_functions();
 
@@ -243,6 +303,8 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			default_heap_pages,
 			host_functions,
 			max_runtime_instances,
+			cache_path,
+			stack_limit,
 		);
 
 		NativeExecutor {
@@ -251,6 +313,31 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			wasm: wasm_executor,
 		}
 	}
+
+	/// Compile the given runtime `code` ahead of time, e.g. a pending runtime upgrade.
+	///
+	/// See [`WasmExecutor::precompile_runtime`].
+	pub fn precompile_runtime(&self, code: &[u8]) -> std::result::Result<(), WasmError> {
+		self.wasm.precompile_runtime(code)
+	}
+
+	/// Compile every runtime code that is embedded in the SCALE encoded `data` ahead of time.
+	///
+	/// This is the entry point for runtime upgrades that are known before they are enacted, e.g. a
+	/// `set_code` extrinsic in the transaction pool or in an imported block. Returns the number of
+	/// runtime codes that were found.
+	///
+	/// See [`embedded_runtime_codes`](crate::embedded_runtime_codes).
+	pub fn precompile_embedded_runtimes(
+		&self,
+		data: &[u8],
+	) -> std::result::Result<usize, WasmError> {
+		let codes = crate::wasm_runtime::embedded_runtime_codes(data);
+		for code in &codes {
+			self.precompile_runtime(code)?;
+		}
+		Ok(codes.len())
+	}
 }
 
 impl<D: NativeExecutionDispatch> RuntimeInfo for NativeExecutor<D> {
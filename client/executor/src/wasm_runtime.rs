@@ -19,14 +19,17 @@
 //! The primary means of accessing the runtimes is through a cache which saves the reusable
 //! components of the runtime that are expensive to initialize.
 
-use std::sync::Arc;
+use std::{borrow::Cow, path::{Path, PathBuf}, sync::Arc};
 use crate::error::{Error, WasmError};
 use parking_lot::Mutex;
 use codec::Decode;
 use sp_core::traits::{Externalities, RuntimeCode, FetchRuntimeCode};
 use sp_version::RuntimeVersion;
 use std::panic::AssertUnwindSafe;
-use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance};
+use sc_executor_common::{
+	wasm_runtime::{WasmModule, WasmInstance},
+	stack_limit::{DeterministicStackLimit, inject_stack_depth_metering},
+};
 
 use sp_wasm_interface::Function;
 
@@ -142,6 +145,10 @@ const MAX_RUNTIMES: usize = 2;
 /// request.
 ///
 /// The size of cache is equal to `MAX_RUNTIMES`.
+///
+/// When using the Wasmtime compiled execution method and a cache path is given, the compiled
+/// runtimes are additionally stored on disk, so that they don't need to be compiled again after
+/// they were dropped from this cache or after a restart.
 pub struct RuntimeCache {
 	/// A cache of runtimes along with metadata.
 	///
@@ -149,14 +156,30 @@ pub struct RuntimeCache {
 	runtimes: Mutex<[Option<Arc<VersionedRuntime>>; MAX_RUNTIMES]>,
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
+	/// The directory for the compiled runtimes stored on disk.
+	cache_path: Option<PathBuf>,
+	/// The deterministic limit of the stack depth of the runtimes.
+	stack_limit: Option<DeterministicStackLimit>,
 }
 
 impl RuntimeCache {
 	/// Creates a new instance of a runtimes cache.
-	pub fn new(max_runtime_instances: usize) -> RuntimeCache {
+	///
+	/// `cache_path` is the directory to store compiled runtimes in. If it is `None`, runtimes are
+	/// only cached in memory.
+	///
+	/// `stack_limit` is the deterministic limit of the stack depth the runtimes are instrumented
+	/// with. If it is `None`, the stack depth is only limited by the executor.
+	pub fn new(
+		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
+		stack_limit: Option<DeterministicStackLimit>,
+	) -> RuntimeCache {
 		RuntimeCache {
 			runtimes: Default::default(),
 			max_runtime_instances,
+			cache_path,
+			stack_limit,
 		}
 	}
 
@@ -231,6 +254,8 @@ impl RuntimeCache {
 					host_functions.into(),
 					allow_missing_func_imports,
 					self.max_runtime_instances,
+					self.cache_path.as_deref(),
+					self.stack_limit,
 				);
 				if let Err(ref err) = result {
 					log::warn!(target: "wasm-runtime", "Cannot create a runtime: {:?}", err);
@@ -261,13 +286,22 @@ impl RuntimeCache {
 }
 
 /// Create a wasm runtime with the given `code`.
+///
+/// `cache_path` is only used by the compiled execution method, to store the compiled code on disk.
+/// If `stack_limit` is given, the code is instrumented to trap at the same stack depth with every
+/// execution method.
 pub fn create_wasm_runtime_with_code(
 	wasm_method: WasmExecutionMethod,
 	heap_pages: u64,
 	code: &[u8],
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	cache_path: Option<&Path>,
+	stack_limit: Option<DeterministicStackLimit>,
 ) -> Result<Box<dyn WasmModule>, WasmError> {
+	let code = instrument_code(code, stack_limit)?;
+	let code = &code[..];
+
 	match wasm_method {
 		WasmExecutionMethod::Interpreted =>
 			sc_executor_wasmi::create_runtime(
@@ -282,11 +316,99 @@ pub fn create_wasm_runtime_with_code(
 				code,
 				heap_pages,
 				host_functions,
-				allow_missing_func_imports
+				allow_missing_func_imports,
+				cache_path,
 			).map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
 	}
 }
 
+/// Compile the given `code` ahead of time and store the result below `cache_path`.
+///
+/// This is a no-op for execution methods that don't compile the code. A runtime created
+/// afterwards with the same `cache_path` and host functions reuses the stored result.
+#[cfg_attr(not(feature = "wasmtime"), allow(unused_variables))]
+pub fn precompile_wasm_runtime(
+	wasm_method: WasmExecutionMethod,
+	code: &[u8],
+	host_functions: &[&'static dyn Function],
+	cache_path: &Path,
+	stack_limit: Option<DeterministicStackLimit>,
+) -> Result<(), WasmError> {
+	match wasm_method {
+		WasmExecutionMethod::Interpreted => Ok(()),
+		#[cfg(feature = "wasmtime")]
+		WasmExecutionMethod::Compiled => sc_executor_wasmtime::prepare_runtime_artifact(
+			&instrument_code(code, stack_limit)?,
+			host_functions,
+			cache_path,
+		),
+	}
+}
+
+/// The magic number and version that every wasm module starts with.
+const WASM_MODULE_PREFIX: &[u8] = b"\0asm\x01\0\0\0";
+
+/// Find the runtime codes that are embedded in the SCALE encoded `data`, e.g. an extrinsic.
+///
+/// `set_code` takes the new code as a `Vec<u8>`, so the code is found wherever the call is
+/// nested: dispatched directly, wrapped by `sudo` or stored as the preimage of a proposal that is
+/// enacted later. Only wasm modules that export `Core_version` are returned, so that e.g. the code
+/// of a smart contract is skipped.
+pub fn embedded_runtime_codes(data: &[u8]) -> Vec<&[u8]> {
+	let mut codes = Vec::new();
+	let mut offset = 0;
+
+	while let Some(position) = data[offset..]
+		.windows(WASM_MODULE_PREFIX.len())
+		.position(|window| window == WASM_MODULE_PREFIX)
+	{
+		let start = offset + position;
+		// The compact encoded length in front of the code is 1, 2 or 4 bytes long.
+		let code = [1, 2, 4].iter()
+			.filter(|prefix_len| **prefix_len <= start)
+			.filter_map(|prefix_len| {
+				let mut prefix = &data[start - prefix_len..start];
+				let len = codec::Compact::<u32>::decode(&mut prefix).ok()?.0 as usize;
+				if !prefix.is_empty() {
+					return None
+				}
+				data.get(start..start.checked_add(len)?)
+			})
+			.find(|code| is_runtime_code(code));
+
+		match code {
+			Some(code) => {
+				codes.push(code);
+				offset = start + code.len();
+			},
+			None => offset = start + 1,
+		}
+	}
+
+	codes
+}
+
+/// Returns whether the given wasm `code` is a runtime, i.e. exports `Core_version`.
+fn is_runtime_code(code: &[u8]) -> bool {
+	parity_wasm::deserialize_buffer::<parity_wasm::elements::Module>(code)
+		.ok()
+		.and_then(|module| module.export_section().map(|section| {
+			section.entries().iter().any(|entry| entry.field() == "Core_version")
+		}))
+		.unwrap_or(false)
+}
+
+/// Instrument the `code` with the given `stack_limit`, if any.
+fn instrument_code(
+	code: &[u8],
+	stack_limit: Option<DeterministicStackLimit>,
+) -> Result<Cow<[u8]>, WasmError> {
+	match stack_limit {
+		Some(limit) => inject_stack_depth_metering(code, &limit).map(Cow::Owned),
+		None => Ok(Cow::Borrowed(code)),
+	}
+}
+
 fn decode_version(version: &[u8]) -> Result<RuntimeVersion, WasmError> {
 	let v: RuntimeVersion = sp_api::OldRuntimeVersion::decode(&mut &version[..])
 		.map_err(|_|
@@ -315,6 +437,8 @@ fn create_versioned_wasm_runtime(
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
 	max_instances: usize,
+	cache_path: Option<&Path>,
+	stack_limit: Option<DeterministicStackLimit>,
 ) -> Result<VersionedRuntime, WasmError> {
 	#[cfg(not(target_os = "unknown"))]
 	let time = std::time::Instant::now();
@@ -324,6 +448,8 @@ fn create_versioned_wasm_runtime(
 		&code,
 		host_functions,
 		allow_missing_func_imports,
+		cache_path,
+		stack_limit,
 	)?;
 
 	// Call to determine runtime version.
@@ -409,6 +535,26 @@ mod tests {
 		decode_version(&old_runtime_version.encode()).unwrap_err();
 	}
 
+	#[test]
+	fn embedded_runtime_codes_finds_runtime_code_in_encoded_calls() {
+		let code = substrate_test_runtime::WASM_BINARY;
+		// E.g. `sudo(set_code(code))` followed by another argument.
+		let call = (0u8, 1u8, 2u8, code.to_vec(), 42u64).encode();
+		assert_eq!(embedded_runtime_codes(&call), vec![code]);
+
+		let two_calls = (code.to_vec(), 7u32, code.to_vec()).encode();
+		assert_eq!(embedded_runtime_codes(&two_calls), vec![code, code]);
+
+		// Wasm modules that are not a runtime are skipped.
+		let contract = wabt::wat2wasm("(module (func (export \"call\")))").unwrap();
+		assert!(embedded_runtime_codes(&(1u8, contract).encode()).is_empty());
+
+		// The length prefix has to match the code.
+		let mut truncated = (code.to_vec(), 0u8).encode();
+		truncated.truncate(truncated.len() - 2);
+		assert!(embedded_runtime_codes(&truncated).is_empty());
+	}
+
 	#[test]
 	fn new_runtime_version_decodes() {
 		let old_runtime_version = sp_api::RuntimeVersion {
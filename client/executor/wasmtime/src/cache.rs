@@ -0,0 +1,110 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-disk cache of compiled runtime artifacts.
+//!
+//! Compiled modules are stored by the wasmtime compilation cache. Its entries are keyed by the
+//! hash of the code, the wasmtime version and the compiler settings, and they are validated when
+//! loaded: an entry that cannot be decoded is treated as a miss and the module is compiled again.
+//! A background worker of wasmtime evicts the least recently used entries once the limits below
+//! are exceeded.
+//!
+//! The artifacts are only valid for the set of host functions they were compiled against, so
+//! every set of host functions gets its own directory below the given cache path.
+
+use std::{fs, path::{Path, PathBuf}};
+use sp_core::{hashing::twox_128, hexdisplay::HexDisplay};
+use sp_wasm_interface::Function;
+use wasmtime::Config;
+
+/// The name of the wasmtime cache configuration file.
+const CACHE_CONFIG_FILE: &str = "cache-config.toml";
+
+/// The soft limit of the total size of all cached artifacts of one set of host functions.
+const FILES_TOTAL_SIZE_SOFT_LIMIT: &str = "1Gi";
+
+/// The soft limit of the number of cached artifacts of one set of host functions.
+const FILE_COUNT_SOFT_LIMIT: u64 = 32;
+
+/// Returns the directory for the artifacts compiled against `host_functions`.
+pub fn cache_dir(cache_path: &Path, host_functions: &[&'static dyn Function]) -> PathBuf {
+	let signatures = host_functions
+		.iter()
+		.map(|f| format!("{}{:?}", f.name(), f.signature()))
+		.collect::<Vec<_>>()
+		.join(";");
+
+	cache_path.join(format!("host-{}", HexDisplay::from(&twox_128(signatures.as_bytes()))))
+}
+
+/// Enable the on-disk cache in `config`, storing artifacts for `host_functions` below
+/// `cache_path`.
+pub fn setup_caching(
+	config: &mut Config,
+	cache_path: &Path,
+	host_functions: &[&'static dyn Function],
+) -> Result<(), String> {
+	let dir = cache_dir(cache_path, host_functions);
+	fs::create_dir_all(&dir)
+		.map_err(|e| format!("cannot create the wasmtime cache directory {:?}: {}", dir, e))?;
+
+	let config_path = dir.join(CACHE_CONFIG_FILE);
+	let config_content = format!(
+		"[cache]\n\
+		enabled = true\n\
+		directory = {}\n\
+		files-total-size-soft-limit = '{}'\n\
+		file-count-soft-limit = {}\n",
+		toml_string(&dir.join("artifacts").to_string_lossy()),
+		FILES_TOTAL_SIZE_SOFT_LIMIT,
+		FILE_COUNT_SOFT_LIMIT,
+	);
+	fs::write(&config_path, config_content)
+		.map_err(|e| format!("cannot write the wasmtime cache config {:?}: {}", config_path, e))?;
+
+	config.cache_config_load(&config_path)
+		.map_err(|e| format!("cannot load the wasmtime cache config {:?}: {}", config_path, e))?;
+
+	Ok(())
+}
+
+/// Quote `value` as a TOML basic string, escaping the characters that may not appear verbatim.
+fn toml_string(value: &str) -> String {
+	let mut quoted = String::with_capacity(value.len() + 2);
+	quoted.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => quoted.push_str("\\\""),
+			'\\' => quoted.push_str("\\\\"),
+			c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+			c => quoted.push(c),
+		}
+	}
+	quoted.push('"');
+	quoted
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn toml_string_escapes_quotes_and_backslashes() {
+		assert_eq!(toml_string("/tmp/node's cache"), "\"/tmp/node's cache\"");
+		assert_eq!(toml_string(r#"C:\cache\"x""#), r#""C:\\cache\\\"x\"""#);
+		assert_eq!(toml_string("a\tb"), "\"a\\u0009b\"");
+	}
+}
@@ -21,6 +21,7 @@ use crate::imports::{Imports, resolve_imports};
 use crate::instance_wrapper::{ModuleWrapper, InstanceWrapper, GlobalsSnapshot};
 use crate::state_holder;
 
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 use sc_executor_common::{
@@ -113,18 +114,17 @@ impl WasmInstance for WasmtimeInstance {
 
 /// Create a new `WasmtimeRuntime` given the code. This function performs translation from Wasm to
 /// machine code, which can be computationally heavy.
+///
+/// If `cache_path` is given, the compiled machine code is stored below it and reused from there by
+/// later calls with the same code and host functions, also across restarts of the node.
 pub fn create_runtime(
 	code: &[u8],
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	cache_path: Option<&Path>,
 ) -> std::result::Result<WasmtimeRuntime, WasmError> {
-	// Create the engine, store and finally the module from the given code.
-	let mut config = Config::new();
-	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
-
-	let engine = Engine::new(&config);
-	let store = Store::new(&engine);
+	let store = create_store(&host_functions, cache_path);
 
 	let module_wrapper = ModuleWrapper::new(&store, code)
 		.map_err(|e| WasmError::Other(format!("cannot create module: {}", e)))?;
@@ -137,6 +137,42 @@ pub fn create_runtime(
 	})
 }
 
+/// Compile the given code and store the machine code below `cache_path`, without creating a
+/// runtime.
+///
+/// This allows to compile a runtime upgrade ahead of its enactment, so that `create_runtime` finds
+/// the compiled code in the cache once it is needed.
+pub fn prepare_runtime_artifact(
+	code: &[u8],
+	host_functions: &[&'static dyn Function],
+	cache_path: &Path,
+) -> std::result::Result<(), WasmError> {
+	let store = create_store(host_functions, Some(cache_path));
+
+	wasmtime::Module::new(&store, code)
+		.map(|_| ())
+		.map_err(|e| WasmError::Other(format!("cannot compile module: {}", e)))
+}
+
+/// Create the engine and the store used to compile the code.
+fn create_store(
+	host_functions: &[&'static dyn Function],
+	cache_path: Option<&Path>,
+) -> Store {
+	let mut config = Config::new();
+	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
+
+	if let Some(cache_path) = cache_path {
+		// The cache is an optimization, so compile without it if it cannot be set up.
+		if let Err(e) = crate::cache::setup_caching(&mut config, cache_path, host_functions) {
+			log::warn!(target: "wasm-runtime", "Cannot set up the wasmtime cache: {}", e);
+		}
+	}
+
+	let engine = Engine::new(&config);
+	Store::new(&engine)
+}
+
 fn perform_call(
 	data: &[u8],
 	instance_wrapper: Rc<InstanceWrapper>,
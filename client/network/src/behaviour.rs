@@ -18,10 +18,13 @@ use crate::{
 	config::{ProtocolId, Role}, block_requests, light_client_handler, finality_requests,
 	debug_info, discovery::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryOut},
 	protocol::{message::{self, Roles}, CustomMessageOutcome, Protocol},
+	protocol_bandwidth::ProtocolBandwidth,
+	request_responses::{self, RequestFailure},
 	Event, ObservedRole, DhtEvent, ExHashT,
 };
 
 use codec::Encode as _;
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, stream::FuturesUnordered};
 use libp2p::NetworkBehaviour;
 use libp2p::core::{Multiaddr, PeerId, PublicKey};
 use libp2p::kad::record;
@@ -29,7 +32,9 @@ use libp2p::swarm::{NetworkBehaviourAction, NetworkBehaviourEventProcess, PollPa
 use log::debug;
 use sp_consensus::{BlockOrigin, import_queue::{IncomingBlock, Origin}};
 use sp_runtime::{traits::{Block as BlockT, NumberFor}, ConsensusEngineId, Justification};
-use std::{borrow::Cow, collections::VecDeque, iter, task::{Context, Poll}, time::Duration};
+use std::{
+	borrow::Cow, collections::{HashMap, VecDeque}, iter, pin::Pin, task::{Context, Poll}, time::Duration,
+};
 
 /// General behaviour of the network. Combines all protocols together.
 #[derive(NetworkBehaviour)]
@@ -42,13 +47,25 @@ pub struct Behaviour<B: BlockT, H: ExHashT> {
 	debug_info: debug_info::DebugInfoBehaviour,
 	/// Discovers nodes of the network.
 	discovery: DiscoveryBehaviour,
-	/// Block request handling.
-	block_requests: block_requests::BlockRequests<B>,
-	/// Finality proof request handling.
-	finality_proof_requests: finality_requests::FinalityProofRequests<B>,
+	/// Generic request-response protocols, including the block and finality proof requests.
+	request_responses: request_responses::RequestResponsesBehaviour,
 	/// Light client request handling.
 	light_client_handler: light_client_handler::LightClientHandler<B>,
 
+	/// Name of the block request protocol.
+	#[behaviour(ignore)]
+	block_requests_protocol: String,
+	/// Block requests that we have sent, with the response for each of them. There is at most
+	/// one block request per peer.
+	#[behaviour(ignore)]
+	pending_block_requests: HashMap<PeerId, PendingBlockRequest<B>>,
+	/// Name of the finality proof request protocol.
+	#[behaviour(ignore)]
+	finality_requests_protocol: String,
+	/// Finality proof requests that we have sent, resolving to the response.
+	#[behaviour(ignore)]
+	pending_finality_requests: FuturesUnordered<BoxFuture<'static, FinalityRequestOutcome<B>>>,
+
 	/// Queue of events to produce for the outside.
 	#[behaviour(ignore)]
 	events: VecDeque<BehaviourOut<B>>,
@@ -58,6 +75,20 @@ pub struct Behaviour<B: BlockT, H: ExHashT> {
 	role: Role,
 }
 
+/// A block request that we have sent and whose response hasn't been processed yet.
+struct PendingBlockRequest<B: BlockT> {
+	/// The original request, passed back to the `Protocol` with the response.
+	request: message::BlockRequest<B>,
+	response: oneshot::Receiver<Result<Vec<u8>, RequestFailure>>,
+}
+
+/// Outcome of a finality proof request that we have sent.
+struct FinalityRequestOutcome<B: BlockT> {
+	peer: PeerId,
+	block_hash: B::Hash,
+	result: Result<Vec<u8>, RequestFailure>,
+}
+
 /// Event generated by `Behaviour`.
 pub enum BehaviourOut<B: BlockT> {
 	BlockImport(BlockOrigin, Vec<IncomingBlock<B>>),
@@ -106,21 +137,28 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		role: Role,
 		user_agent: String,
 		local_public_key: PublicKey,
-		block_requests: block_requests::BlockRequests<B>,
-		finality_proof_requests: finality_requests::FinalityProofRequests<B>,
+		protocol_id: &ProtocolId,
 		light_client_handler: light_client_handler::LightClientHandler<B>,
 		disco_config: DiscoveryConfig,
-	) -> Self {
-		Behaviour {
+		request_response_protocols: Vec<request_responses::ProtocolConfig>,
+		protocol_bandwidth: ProtocolBandwidth,
+	) -> Result<Self, request_responses::RegisterError> {
+		Ok(Behaviour {
 			substrate,
 			debug_info: debug_info::DebugInfoBehaviour::new(user_agent, local_public_key.clone()),
 			discovery: disco_config.finish(),
-			block_requests,
-			finality_proof_requests,
+			request_responses: request_responses::RequestResponsesBehaviour::new(
+				request_response_protocols.into_iter(),
+				protocol_bandwidth,
+			)?,
 			light_client_handler,
+			block_requests_protocol: block_requests::generate_protocol_name(protocol_id),
+			pending_block_requests: HashMap::new(),
+			finality_requests_protocol: finality_requests::generate_protocol_name(protocol_id),
+			pending_finality_requests: FuturesUnordered::new(),
 			events: VecDeque::new(),
 			role,
-		}
+		})
 	}
 
 	/// Returns the list of nodes that we know exist in the network.
@@ -157,6 +195,19 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		self.debug_info.node(peer_id)
 	}
 
+	/// Initiates sending a request on one of the request-response protocols.
+	///
+	/// The response, or the reason of the failure, is sent on `pending_response`.
+	pub fn send_request(
+		&mut self,
+		target: &PeerId,
+		protocol: &str,
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) {
+		self.request_responses.send_request(target, protocol, request, pending_response)
+	}
+
 	/// Registers a new notifications protocol.
 	///
 	/// After that, you can call `write_notifications`.
@@ -247,30 +298,44 @@ Behaviour<B, H> {
 			CustomMessageOutcome::FinalityProofImport(origin, hash, nb, proof) =>
 				self.events.push_back(BehaviourOut::FinalityProofImport(origin, hash, nb, proof)),
 			CustomMessageOutcome::BlockRequest { target, request } => {
-				match self.block_requests.send_request(&target, request) {
-					block_requests::SendRequestOutcome::Ok => {
-						self.events.push_back(BehaviourOut::RequestStarted {
-							peer: target,
-							protocol: self.block_requests.protocol_name().to_vec(),
-						});
-					},
-					block_requests::SendRequestOutcome::Replaced { request_duration, .. } => {
-						self.events.push_back(BehaviourOut::RequestFinished {
-							peer: target.clone(),
-							protocol: self.block_requests.protocol_name().to_vec(),
-							request_duration,
-						});
-						self.events.push_back(BehaviourOut::RequestStarted {
-							peer: target,
-							protocol: self.block_requests.protocol_name().to_vec(),
-						});
+				let buf = match block_requests::build_protobuf_block_request(&request) {
+					Ok(buf) => buf,
+					Err(err) => {
+						log::warn!(
+							target: "sync",
+							"Failed to encode block request {:?}: {:?}",
+							request,
+							err
+						);
+						return;
 					}
-					block_requests::SendRequestOutcome::NotConnected |
-					block_requests::SendRequestOutcome::EncodeError(_) => {},
-				}
+				};
+
+				log::trace!(target: "sync", "Enqueueing block request to {:?}: {:?}", target, request);
+				let (tx, rx) = oneshot::channel();
+				// Any previous request to the same peer is replaced, and its response discarded.
+				self.pending_block_requests.insert(target.clone(), PendingBlockRequest {
+					request,
+					response: rx,
+				});
+				self.request_responses.send_request(&target, &self.block_requests_protocol, buf, tx);
 			},
 			CustomMessageOutcome::FinalityProofRequest { target, block_hash, request } => {
-				self.finality_proof_requests.send_request(&target, block_hash, request);
+				let buf = match finality_requests::build_protobuf_finality_request::<B>(&block_hash, request) {
+					Ok(buf) => buf,
+					Err(err) => {
+						log::warn!(target: "sync", "Failed to encode finality proof request: {:?}", err);
+						return;
+					}
+				};
+
+				log::trace!(target: "sync", "Enqueueing finality proof request to {:?}", target);
+				let (tx, rx) = oneshot::channel();
+				self.request_responses.send_request(&target, &self.finality_requests_protocol, buf, tx);
+				self.pending_finality_requests.push(async move {
+					let result = rx.await.unwrap_or(Err(RequestFailure::ConnectionClosed));
+					FinalityRequestOutcome { peer: target, block_hash, result }
+				}.boxed());
 			},
 			CustomMessageOutcome::NotificationStreamOpened { remote, protocols, roles } => {
 				let role = reported_roles_to_observed_role(&self.role, &remote, roles);
@@ -301,55 +366,42 @@ Behaviour<B, H> {
 	}
 }
 
-impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<block_requests::Event<B>> for Behaviour<B, H> {
-	fn inject_event(&mut self, event: block_requests::Event<B>) {
+impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<request_responses::Event> for Behaviour<B, H> {
+	fn inject_event(&mut self, event: request_responses::Event) {
 		match event {
-			block_requests::Event::AnsweredRequest { peer, total_handling_time } => {
-				self.events.push_back(BehaviourOut::AnsweredRequest {
+			request_responses::Event::InboundRequest { peer, protocol, result } => {
+				match result {
+					Ok(build_time) => self.events.push_back(BehaviourOut::AnsweredRequest {
+						peer,
+						protocol: protocol.as_bytes().to_vec(),
+						build_time,
+					}),
+					Err(err) => debug!(
+						target: "sub-libp2p",
+						"Failed to answer request from {} on {}: {}",
+						peer,
+						protocol,
+						err
+					),
+				}
+			}
+			request_responses::Event::RequestStarted { peer, protocol } => {
+				self.events.push_back(BehaviourOut::RequestStarted {
 					peer,
-					protocol: self.block_requests.protocol_name().to_vec(),
-					build_time: total_handling_time,
+					protocol: protocol.as_bytes().to_vec(),
 				});
-			},
-			block_requests::Event::Response { peer, original_request, response, request_duration } => {
-				self.events.push_back(BehaviourOut::RequestFinished {
-					peer: peer.clone(),
-					protocol: self.block_requests.protocol_name().to_vec(),
-					request_duration,
-				});
-				let ev = self.substrate.on_block_response(peer, original_request, response);
-				self.inject_event(ev);
 			}
-			block_requests::Event::RequestCancelled { peer, request_duration, .. } |
-			block_requests::Event::RequestTimeout { peer, request_duration, .. } => {
-				// There doesn't exist any mechanism to report cancellations or timeouts yet, so
-				// we process them by disconnecting the node.
+			request_responses::Event::RequestFinished { peer, protocol, duration, .. } => {
 				self.events.push_back(BehaviourOut::RequestFinished {
-					peer: peer.clone(),
-					protocol: self.block_requests.protocol_name().to_vec(),
-					request_duration,
+					peer,
+					protocol: protocol.as_bytes().to_vec(),
+					request_duration: duration,
 				});
-				self.substrate.on_block_request_failed(&peer);
 			}
-		}
-	}
-}
-
-impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<finality_requests::Event<B>> for Behaviour<B, H> {
-	fn inject_event(&mut self, event: finality_requests::Event<B>) {
-		match event {
-			finality_requests::Event::Response { peer, block_hash, proof } => {
-				let response = message::FinalityProofResponse {
-					id: 0,
-					block: block_hash,
-					proof: if !proof.is_empty() {
-						Some(proof)
-					} else {
-						None
-					},
-				};
-				let ev = self.substrate.on_finality_proof_response(peer, response);
-				self.inject_event(ev);
+			request_responses::Event::ReputationChanges { peer, changes } => {
+				for change in changes {
+					self.substrate.report_peer(peer.clone(), change);
+				}
 			}
 		}
 	}
@@ -408,7 +460,88 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<DiscoveryOut>
 }
 
 impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
-	fn poll<TEv>(&mut self, _: &mut Context, _: &mut impl PollParameters) -> Poll<NetworkBehaviourAction<TEv, BehaviourOut<B>>> {
+	/// Handles the response to a block request that we have sent.
+	fn on_block_response(
+		&mut self,
+		peer: PeerId,
+		request: message::BlockRequest<B>,
+		result: Result<Vec<u8>, RequestFailure>,
+	) {
+		let response = match result {
+			Ok(response) => response,
+			// There was no request to process in the first place.
+			Err(RequestFailure::NotConnected) => return,
+			Err(err) => {
+				// There doesn't exist any mechanism to report cancellations or timeouts yet, so
+				// we process them by disconnecting the node.
+				debug!(target: "sync", "Block request to {} failed: {}", peer, err);
+				self.substrate.on_block_request_failed(&peer);
+				return;
+			}
+		};
+
+		match block_requests::decode_block_response(&request, &response) {
+			Ok(response) => {
+				log::trace!(
+					target: "sync",
+					"Received block response from peer {} with {} blocks",
+					peer, response.blocks.len()
+				);
+				let ev = self.substrate.on_block_response(peer, request, response);
+				self.inject_event(ev);
+			}
+			Err(err) => {
+				debug!(target: "sync", "Failed to decode block response from peer {}: {}", peer, err);
+				self.substrate.on_block_request_failed(&peer);
+			}
+		}
+	}
+
+	/// Handles the response to a finality proof request that we have sent.
+	fn on_finality_response(&mut self, outcome: FinalityRequestOutcome<B>) {
+		let FinalityRequestOutcome { peer, block_hash, result } = outcome;
+		let proof = match result.map(|r| finality_requests::decode_finality_response(&r)) {
+			Ok(Ok(proof)) => proof,
+			Ok(Err(err)) => {
+				debug!(target: "sync", "Failed to decode finality proof response from {}: {}", peer, err);
+				return;
+			}
+			Err(err) => {
+				debug!(target: "sync", "Finality proof request to {} failed: {}", peer, err);
+				return;
+			}
+		};
+
+		let response = message::FinalityProofResponse {
+			id: 0,
+			block: block_hash,
+			proof: if !proof.is_empty() {
+				Some(proof)
+			} else {
+				None
+			},
+		};
+		let ev = self.substrate.on_finality_proof_response(peer, response);
+		self.inject_event(ev);
+	}
+
+	fn poll<TEv>(&mut self, cx: &mut Context, _: &mut impl PollParameters) -> Poll<NetworkBehaviourAction<TEv, BehaviourOut<B>>> {
+		let finished_block_requests = self.pending_block_requests.iter_mut()
+			.filter_map(|(peer, pending)| match Pin::new(&mut pending.response).poll(cx) {
+				Poll::Ready(result) => Some((peer.clone(), result.unwrap_or(Err(RequestFailure::ConnectionClosed)))),
+				Poll::Pending => None,
+			})
+			.collect::<Vec<_>>();
+		for (peer, result) in finished_block_requests {
+			if let Some(pending) = self.pending_block_requests.remove(&peer) {
+				self.on_block_response(peer, pending.request, result);
+			}
+		}
+
+		while let Poll::Ready(Some(outcome)) = self.pending_finality_requests.poll_next_unpin(cx) {
+			self.on_finality_response(outcome);
+		}
+
 		if let Some(event) = self.events.pop_front() {
 			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(event))
 		}
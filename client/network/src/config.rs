@@ -21,6 +21,13 @@
 
 pub use crate::chain::{Client, FinalityProofProvider};
 pub use crate::on_demand_layer::{AlwaysBadChecker, OnDemand};
+pub use crate::peer_store::PeerStoreBackend;
+pub use crate::protocol_bandwidth::BandwidthLimit;
+pub use crate::request_responses::{
+	IncomingRequest,
+	OutgoingResponse,
+	ProtocolConfig as RequestResponseConfig,
+};
 pub use libp2p::{identity, core::PublicKey, wasm_ext::ExtTransport, build_multiaddr};
 
 // Note: this re-export shouldn't be part of the public API of the crate and will be removed in
@@ -101,6 +108,9 @@ pub struct Params<B: BlockT, H: ExHashT> {
 
 	/// Registry for recording prometheus metrics to.
 	pub metrics_registry: Option<Registry>,
+
+	/// Database where the known peers are saved across restarts, if any.
+	pub peer_store: Option<Arc<dyn PeerStoreBackend>>,
 }
 
 /// Role of the local node.
@@ -392,6 +402,13 @@ pub struct NetworkConfiguration {
 	/// List of notifications protocols that the node supports. Must also include a
 	/// `ConsensusEngineId` for backwards-compatibility.
 	pub notifications_protocols: Vec<(ConsensusEngineId, Cow<'static, [u8]>)>,
+	/// List of request-response protocols that the node supports, in addition to the built-in
+	/// block and finality proof requests.
+	pub request_response_protocols: Vec<RequestResponseConfig>,
+	/// Limits of the outgoing bandwidth of the legacy substream (e.g. `/substrate/dot`) and of
+	/// notifications protocols (e.g. `/dot/transactions/1`), indexed by protocol name. Messages
+	/// exceeding the limit are dropped. Protocols without an entry are not limited.
+	pub bandwidth_limits: HashMap<String, BandwidthLimit>,
 	/// Maximum allowed number of incoming connections.
 	pub in_peers: u32,
 	/// Number of outgoing connections we're trying to maintain.
@@ -430,6 +447,8 @@ impl NetworkConfiguration {
 			boot_nodes: Vec::new(),
 			node_key,
 			notifications_protocols: Vec::new(),
+			request_response_protocols: Vec::new(),
+			bandwidth_limits: HashMap::new(),
 			in_peers: 25,
 			out_peers: 75,
 			reserved_nodes: Vec::new(),
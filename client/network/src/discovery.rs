@@ -74,6 +74,7 @@ use sp_core::hexdisplay::HexDisplay;
 pub struct DiscoveryConfig {
 	local_peer_id: PeerId,
 	user_defined: Vec<(PeerId, Multiaddr)>,
+	stored: Vec<(PeerId, Multiaddr)>,
 	allow_private_ipv4: bool,
 	allow_non_globals_in_dht: bool,
 	discovery_only_if_under_num: u64,
@@ -87,6 +88,7 @@ impl DiscoveryConfig {
 		DiscoveryConfig {
 			local_peer_id: local_public_key.into_peer_id(),
 			user_defined: Vec::new(),
+			stored: Vec::new(),
 			allow_private_ipv4: true,
 			allow_non_globals_in_dht: false,
 			discovery_only_if_under_num: std::u64::MAX,
@@ -115,6 +117,21 @@ impl DiscoveryConfig {
 		self
 	}
 
+	/// Set nodes known from a previous run. Contrary to the user-defined nodes, they are only
+	/// added to the Kademlia routing table and expire like discovered nodes.
+	pub fn with_stored<I>(&mut self, stored: I) -> &mut Self
+	where
+		I: IntoIterator<Item = (PeerId, Multiaddr)>
+	{
+		for (peer_id, addr) in stored {
+			for kad in self.kademlias.values_mut() {
+				kad.add_address(&peer_id, addr.clone())
+			}
+			self.stored.push((peer_id, addr))
+		}
+		self
+	}
+
 	/// Should private IPv4 addresses be reported?
 	pub fn allow_private_ipv4(&mut self, value: bool) -> &mut Self {
 		self.allow_private_ipv4 = value;
@@ -163,7 +180,7 @@ impl DiscoveryConfig {
 		let store = MemoryStore::new(self.local_peer_id.clone());
 		let mut kad = Kademlia::with_config(self.local_peer_id.clone(), store, config);
 
-		for (peer_id, addr) in &self.user_defined {
+		for (peer_id, addr) in self.user_defined.iter().chain(self.stored.iter()) {
 			kad.add_address(peer_id, addr.clone());
 		}
 
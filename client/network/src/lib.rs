@@ -134,13 +134,20 @@
 //! - **`/<protocol-id>/transactions/1`** is a notifications protocol (see below) where
 //! transactions are pushed to other nodes. The handshake is empty on both sides. The message
 //! format is a SCALE-encoded list of transactions, where each transaction is an opaque list of
-//! bytes.
+//! bytes. Transactions are only pushed this way to nodes whose legacy substream version is
+//! lower than 7, and in response to requests of announced transactions.
+//! - **`/<protocol-id>/transaction-announces/1`** is a notifications protocol (see below) where
+//! the hashes of transactions are announced to other nodes, which request the transactions they
+//! don't have on the same protocol. The handshake is empty on both sides. The message format is a
+//! SCALE-encoded `TransactionAnnounce`.
 //! - **`/<protocol-id>/block-announces/1`** is a notifications protocol (see below) where
 //! block announces are pushed to other nodes. The handshake is empty on both sides. The message
 //! format is a SCALE-encoded tuple containing a block header followed with an opaque list of
 //! bytes containing some data associated with this block announcement, e.g. a candidate message.
 //! - Notifications protocols that are registered using the `register_notifications_protocol`
 //! method. For example: `/paritytech/grandpa/1`. See below for more information.
+//! - Request-response protocols that are passed through
+//! `NetworkConfiguration::request_response_protocols`. See below for more information.
 //!
 //! ## The legacy Substrate substream
 //!
@@ -186,6 +193,10 @@
 //!
 //! Each request is performed in a new separate substream.
 //!
+//! Additional request-response protocols can be registered through
+//! `NetworkConfiguration::request_response_protocols`. Incoming requests are delivered on the
+//! channel of the protocol configuration, and requests are sent with `NetworkService::request`.
+//!
 //! ## Notifications protocols
 //!
 //! A so-called notifications protocol is defined as follow:
@@ -250,6 +261,8 @@ mod finality_requests;
 mod light_client_handler;
 mod on_demand_layer;
 mod protocol;
+mod protocol_bandwidth;
+mod request_responses;
 mod schema;
 mod service;
 mod transport;
@@ -258,11 +271,13 @@ mod utils;
 pub mod config;
 pub mod error;
 pub mod network_state;
+pub mod peer_store;
 
 pub use service::{NetworkService, NetworkWorker};
 pub use protocol::PeerInfo;
 pub use protocol::event::{Event, DhtEvent, ObservedRole};
 pub use protocol::sync::SyncState;
+pub use request_responses::RequestFailure;
 pub use libp2p::{Multiaddr, PeerId};
 #[doc(inline)]
 pub use libp2p::multiaddr;
@@ -279,9 +294,13 @@ pub use sc_peerset::ReputationChange;
 const MAX_CONNECTIONS_PER_PEER: usize = 2;
 
 /// Minimum Requirements for a Hash within Networking
-pub trait ExHashT: std::hash::Hash + Eq + std::fmt::Debug + Clone + Send + Sync + 'static {}
+///
+/// The hashes of transactions are encoded in the transaction announcements sent to peers.
+pub trait ExHashT:
+	std::hash::Hash + Eq + std::fmt::Debug + Clone + Send + Sync + codec::Codec + 'static {}
 
-impl<T> ExHashT for T where T: std::hash::Hash + Eq + std::fmt::Debug + Clone + Send + Sync + 'static
+impl<T> ExHashT for T where
+	T: std::hash::Hash + Eq + std::fmt::Debug + Clone + Send + Sync + codec::Codec + 'static
 {}
 
 /// A cloneable handle for reporting cost/benefits of peers.
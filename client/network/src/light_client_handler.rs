@@ -74,6 +74,7 @@ use sp_runtime::{
 	generic::BlockId,
 };
 use std::{
+	cmp,
 	collections::{BTreeMap, VecDeque, HashMap},
 	iter,
 	io,
@@ -87,6 +88,10 @@ use wasm_timer::Instant;
 /// Reputation change for a peer when a request timed out.
 pub(crate) const TIMEOUT_REPUTATION_CHANGE: i32 = -(1 << 8);
 
+/// Maximum number of entries covered by the proof of a remote storage range read. Requests for
+/// more entries are served with a proof of the first ones only, which the requester rejects.
+const MAX_READ_RANGE_ENTRIES: u32 = 1000;
+
 /// Configuration options for `LightClientHandler` behaviour.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -214,6 +219,10 @@ pub enum Request<B: Block> {
 		request: light::RemoteReadChildRequest<B::Header>,
 		sender: oneshot::Sender<Result<HashMap<Vec<u8>, Option<Vec<u8>>>, ClientError>>
 	},
+	ReadRange {
+		request: light::RemoteReadRangeRequest<B::Header>,
+		sender: oneshot::Sender<Result<light::StorageRange, ClientError>>
+	},
 	Call {
 		request: light::RemoteCallRequest<B::Header>,
 		sender: oneshot::Sender<Result<Vec<u8>, ClientError>>
@@ -234,6 +243,7 @@ enum Reply<B: Block> {
 	VecU8(Vec<u8>),
 	VecNumberU32(Vec<(<B::Header as Header>::Number, u32)>),
 	MapVecU8OptVecU8(HashMap<Vec<u8>, Option<Vec<u8>>>),
+	StorageRange(light::StorageRange),
 	Header(B::Header),
 	Extrinsics(Vec<B::Extrinsic>),
 }
@@ -462,6 +472,11 @@ where
 						let reply = self.checker.check_read_child_proof(&request, proof)?;
 						Ok(Reply::MapVecU8OptVecU8(reply))
 					}
+					Request::ReadRange { request, .. } => {
+						let proof = Decode::decode(&mut response.proof.as_ref())?;
+						let reply = self.checker.check_read_range_proof(&request, proof)?;
+						Ok(Reply::StorageRange(reply))
+					}
 					_ => Err(Error::UnexpectedResponse)
 				}
 			Some(Response::RemoteChangesResponse(response)) =>
@@ -653,6 +668,65 @@ where
 		Ok(schema::v1::light::Response { response: Some(response) })
 	}
 
+	fn on_remote_read_range_request
+		( &mut self
+		, peer: &PeerId
+		, request: &schema::v1::light::RemoteReadRangeRequest
+		) -> Result<schema::v1::light::Response, Error>
+	{
+		if request.count == 0 {
+			log::debug!("invalid remote read range request sent by {}", peer);
+			return Err(Error::BadRequest("remote read range request without entries"))
+		}
+
+		log::trace!("remote read range request from {} ({} {} after {} at {:?})",
+			peer,
+			HexDisplay::from(&request.storage_key),
+			HexDisplay::from(&request.prefix),
+			HexDisplay::from(&request.start_at),
+			request.block);
+
+		let block = Decode::decode(&mut request.block.as_ref())?;
+
+		let child_info = if request.storage_key.is_empty() {
+			Ok(None)
+		} else {
+			let prefixed_key = PrefixedStorageKey::new_ref(&request.storage_key);
+			match ChildType::from_prefixed_key(prefixed_key) {
+				Some((ChildType::ParentKeyId, storage_key)) => Ok(Some(ChildInfo::new_default(storage_key))),
+				None => Err("Invalid child storage key".into()),
+			}
+		};
+		let start_at = if request.start_at.is_empty() { None } else { Some(&request.start_at[..]) };
+		let count = cmp::min(request.count, MAX_READ_RANGE_ENTRIES);
+		let proof = match child_info.and_then(|child_info| self.chain.read_range_proof(
+			&BlockId::Hash(block),
+			child_info.as_ref(),
+			&request.prefix,
+			start_at,
+			count,
+		)) {
+			Ok(proof) => proof,
+			Err(error) => {
+				log::trace!("remote read range request from {} ({} {} after {} at {:?}) failed with: {}",
+					peer,
+					HexDisplay::from(&request.storage_key),
+					HexDisplay::from(&request.prefix),
+					HexDisplay::from(&request.start_at),
+					request.block,
+					error);
+				StorageProof::empty()
+			}
+		};
+
+		let response = {
+			let r = schema::v1::light::RemoteReadResponse { proof: proof.encode() };
+			schema::v1::light::response::Response::RemoteReadResponse(r)
+		};
+
+		Ok(schema::v1::light::Response { response: Some(response) })
+	}
+
 	fn on_remote_header_request
 		( &mut self
 		, peer: &PeerId
@@ -830,6 +904,8 @@ where
 						self.on_remote_header_request(&peer, r),
 					Some(schema::v1::light::request::Request::RemoteReadChildRequest(r)) =>
 						self.on_remote_read_child_request(&peer, r),
+					Some(schema::v1::light::request::Request::RemoteReadRangeRequest(r)) =>
+						self.on_remote_read_range_request(&peer, r),
 					Some(schema::v1::light::request::Request::RemoteChangesRequest(r)) =>
 						self.on_remote_changes_request(&peer, r),
 					None => {
@@ -1040,6 +1116,7 @@ fn required_block<B: Block>(request: &Request<B>) -> NumberFor<B> {
 		Request::Header { request, .. } => request.block,
 		Request::Read { request, .. } => *request.header.number(),
 		Request::ReadChild { request, .. } => *request.header.number(),
+		Request::ReadRange { request, .. } => *request.header.number(),
 		Request::Call { request, .. } => *request.header.number(),
 		Request::Changes { request, .. } => request.max_block.0,
 	}
@@ -1051,6 +1128,7 @@ fn retries<B: Block>(request: &Request<B>) -> usize {
 		Request::Header { request, .. } => request.retry_count,
 		Request::Read { request, .. } => request.retry_count,
 		Request::ReadChild { request, .. } => request.retry_count,
+		Request::ReadRange { request, .. } => request.retry_count,
 		Request::Call { request, .. } => request.retry_count,
 		Request::Changes { request, .. } => request.retry_count,
 	};
@@ -1090,6 +1168,17 @@ fn serialize_request<B: Block>(request: &Request<B>) -> Result<Vec<u8>, prost::E
 			};
 			schema::v1::light::request::Request::RemoteReadChildRequest(r)
 		}
+		Request::ReadRange { request, .. } => {
+			let r = schema::v1::light::RemoteReadRangeRequest {
+				block: request.block.encode(),
+				storage_key: request.storage_key.clone().map(|s| s.into_inner())
+					.unwrap_or_default(),
+				prefix: request.prefix.clone(),
+				start_at: request.start_at.clone().unwrap_or_default(),
+				count: request.count,
+			};
+			schema::v1::light::request::Request::RemoteReadRangeRequest(r)
+		}
 		Request::Call { request, .. } => {
 			let r = schema::v1::light::RemoteCallRequest {
 				block: request.block.encode(),
@@ -1143,6 +1232,11 @@ fn send_reply<B: Block>(result: Result<Reply<B>, ClientError>, request: Request<
 			Ok(Reply::MapVecU8OptVecU8(x)) => send(Ok(x), sender),
 			reply => log::error!("invalid reply for read child request: {:?}, {:?}", reply, request),
 		}
+		Request::ReadRange { request, sender } => match result {
+			Err(e) => send(Err(e), sender),
+			Ok(Reply::StorageRange(x)) => send(Ok(x), sender),
+			reply => log::error!("invalid reply for read range request: {:?}, {:?}", reply, request),
+		}
 		Request::Call { request, sender } => match result {
 			Err(e) => send(Err(e), sender),
 			Ok(Reply::VecU8(x)) => send(Ok(x), sender),
@@ -1320,7 +1414,7 @@ mod tests {
 		swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters},
 		yamux
 	};
-	use sc_client_api::{StorageProof, RemoteReadChildRequest, FetchChecker};
+	use sc_client_api::{StorageProof, RemoteReadChildRequest, RemoteReadRangeRequest, FetchChecker};
 	use sp_blockchain::{Error as ClientError};
 	use sp_core::storage::ChildInfo;
 	use std::{
@@ -1409,6 +1503,20 @@ mod tests {
 			}
 		}
 
+		fn check_read_range_proof(
+			&self,
+			request: &RemoteReadRangeRequest<B::Header>,
+			_: StorageProof,
+		) -> Result<light::StorageRange, ClientError> {
+			match self.ok {
+				true => Ok(light::StorageRange {
+					entries: vec![(request.prefix.clone(), vec![42])],
+					complete: true,
+				}),
+				false => Err(ClientError::Backend("Test error".into())),
+			}
+		}
+
 		fn check_execution_proof(
 			&self,
 			_: &RemoteCallRequest<B::Header>,
@@ -1488,6 +1596,7 @@ mod tests {
 			bootnodes: Vec::new(),
 			reserved_only: false,
 			priority_groups: Vec::new(),
+			known_peers: Vec::new(),
 		};
 		sc_peerset::Peerset::from_config(cfg)
 	}
@@ -1819,6 +1928,12 @@ mod tests {
 					response: Some(schema::v1::light::response::Response::RemoteReadResponse(r)),
 				}
 			}
+			Request::ReadRange{..} => {
+				let r = schema::v1::light::RemoteReadResponse { proof: empty_proof() };
+				schema::v1::light::Response {
+					response: Some(schema::v1::light::response::Response::RemoteReadResponse(r)),
+				}
+			}
 			Request::Call{..} => {
 				let r = schema::v1::light::RemoteCallResponse { proof: empty_proof() };
 				schema::v1::light::Response {
@@ -1897,6 +2012,23 @@ mod tests {
 		assert_matches!(chan.1.try_recv(), Ok(Some(Ok(_))))
 	}
 
+	#[test]
+	fn receives_remote_read_range_response() {
+		let mut chan = oneshot::channel();
+		let child_info = ChildInfo::new_default(&b":child_storage:default:sub"[..]);
+		let request = light::RemoteReadRangeRequest {
+			header: dummy_header(),
+			block: Default::default(),
+			storage_key: Some(child_info.prefixed_storage_key()),
+			prefix: b":key".to_vec(),
+			start_at: None,
+			count: 10,
+			retry_count: None,
+		};
+		issue_request(Request::ReadRange { request, sender: chan.0 });
+		assert_matches!(chan.1.try_recv(), Ok(Some(Ok(_))))
+	}
+
 	#[test]
 	fn receives_remote_header_response() {
 		let mut chan = oneshot::channel();
@@ -1999,6 +2131,26 @@ mod tests {
 		//                   ^--- from `DummyFetchChecker::check_read_child_proof`
 	}
 
+	#[test]
+	fn send_receive_read_range() {
+		let chan = oneshot::channel();
+		let request = light::RemoteReadRangeRequest {
+			header: dummy_header(),
+			block: Default::default(),
+			storage_key: None,
+			prefix: b":key".to_vec(),
+			start_at: Some(b":key1".to_vec()),
+			count: 10,
+			retry_count: None,
+		};
+		send_receive(Request::ReadRange { request, sender: chan.0 });
+		assert_eq!(
+			vec![(b":key".to_vec(), vec![42])],
+			task::block_on(chan.1).unwrap().unwrap().entries,
+		);
+		// ^--- from `DummyFetchChecker::check_read_range_proof`
+	}
+
 	#[test]
 	fn send_receive_header() {
 		let _ = env_logger::try_init();
@@ -45,10 +45,28 @@ pub struct NetworkState {
 	pub average_download_per_sec: u64,
 	/// Uploaded bytes per second averaged over the past few seconds.
 	pub average_upload_per_sec: u64,
+	/// Bandwidth used by every protocol since the node has started, indexed by protocol name.
+	pub protocols_bandwidth: HashMap<String, ProtocolBandwidthStats>,
 	/// State of the peerset manager.
 	pub peerset: serde_json::Value,
 }
 
+/// Part of the `NetworkState` struct. Unstable.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolBandwidthStats {
+	/// Total number of bytes received.
+	pub bytes_in: u64,
+	/// Total number of bytes sent.
+	pub bytes_out: u64,
+	/// Total number of messages received.
+	pub messages_in: u64,
+	/// Total number of messages sent.
+	pub messages_out: u64,
+	/// Total number of outgoing messages dropped because of the bandwidth limit.
+	pub messages_dropped: u64,
+}
+
 /// Part of the `NetworkState` struct. Unstable.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
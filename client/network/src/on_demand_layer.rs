@@ -22,7 +22,8 @@ use futures::{channel::oneshot, prelude::*};
 use parking_lot::Mutex;
 use sc_client_api::{
 	FetchChecker, Fetcher, RemoteBodyRequest, RemoteCallRequest, RemoteChangesRequest,
-	RemoteHeaderRequest, RemoteReadChildRequest, RemoteReadRequest, StorageProof, ChangesProof,
+	RemoteHeaderRequest, RemoteReadChildRequest, RemoteReadRangeRequest, RemoteReadRequest,
+	StorageProof, StorageRange, ChangesProof,
 };
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_blockchain::Error as ClientError;
@@ -82,6 +83,14 @@ impl<Block: BlockT> FetchChecker<Block> for AlwaysBadChecker {
 		Err(ClientError::Msg("AlwaysBadChecker".into()))
 	}
 
+	fn check_read_range_proof(
+		&self,
+		_request: &RemoteReadRangeRequest<Block::Header>,
+		_remote_proof: StorageProof,
+	) -> Result<StorageRange, ClientError> {
+		Err(ClientError::Msg("AlwaysBadChecker".into()))
+	}
+
 	fn check_execution_proof(
 		&self,
 		_request: &RemoteCallRequest<Block::Header>,
@@ -149,6 +158,7 @@ where
 {
 	type RemoteHeaderResult = RemoteResponse<B::Header>;
 	type RemoteReadResult = RemoteResponse<HashMap<Vec<u8>, Option<Vec<u8>>>>;
+	type RemoteReadRangeResult = RemoteResponse<StorageRange>;
 	type RemoteCallResult = RemoteResponse<Vec<u8>>;
 	type RemoteChangesResult = RemoteResponse<Vec<(NumberFor<B>, u32)>>;
 	type RemoteBodyResult = RemoteResponse<Vec<B::Extrinsic>>;
@@ -180,6 +190,17 @@ where
 		RemoteResponse { receiver }
 	}
 
+	fn remote_read_range(
+		&self,
+		request: RemoteReadRangeRequest<B::Header>,
+	) -> Self::RemoteReadRangeResult {
+		let (sender, receiver) = oneshot::channel();
+		let _ = self
+			.requests_send
+			.unbounded_send(light_client_handler::Request::ReadRange { request, sender });
+		RemoteResponse { receiver }
+	}
+
 	fn remote_call(&self, request: RemoteCallRequest<B::Header>) -> Self::RemoteCallResult {
 		let (sender, receiver) = oneshot::channel();
 		let _ = self
@@ -0,0 +1,346 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+//
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage of the known peers across restarts of the node.
+//!
+//! The `NetworkWorker` periodically saves the addresses, reputation and last time we were
+//! connected to every peer known by the peerset manager to the auxiliary storage of the node's
+//! database, and restores them on startup. The writes happen on a dedicated thread, so that they
+//! never block the network. The reputations keep decaying while the node is offline, at the same
+//! rate as when it is running, which means that bans expire as well.
+
+use libp2p::{Multiaddr, PeerId};
+use sc_client_api::backend::AuxStore;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	io,
+	sync::{mpsc, Arc},
+	thread,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Key of the peer store in the auxiliary storage of the database.
+pub const PEER_STORE_KEY: &[u8] = b"sc_network_peer_store";
+
+/// Peers we haven't been connected to for this number of seconds are forgotten, unless they are
+/// banned.
+const MAX_AGE_SECS: u64 = 7 * 24 * 3600;
+
+/// Maximum number of peers kept in the store. The ones we have been connected to most recently
+/// are kept.
+const MAX_PEERS: usize = 1000;
+
+/// Database the peer store is saved to.
+///
+/// Implemented for every `AuxStore`, such as the backend of the client.
+pub trait PeerStoreBackend: Send + Sync {
+	/// Returns the encoded peer store, or `None` if none was saved.
+	fn load_peer_store(&self) -> sp_blockchain::Result<Option<Vec<u8>>>;
+
+	/// Replaces the encoded peer store, or removes it if `encoded` is `None`.
+	fn save_peer_store(&self, encoded: Option<&[u8]>) -> sp_blockchain::Result<()>;
+}
+
+impl<T: AuxStore + Send + Sync> PeerStoreBackend for T {
+	fn load_peer_store(&self) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		self.get_aux(PEER_STORE_KEY)
+	}
+
+	fn save_peer_store(&self, encoded: Option<&[u8]>) -> sp_blockchain::Result<()> {
+		match encoded {
+			Some(encoded) => self.insert_aux(&[(PEER_STORE_KEY, encoded)], &[]),
+			None => self.insert_aux(&[], &[&PEER_STORE_KEY]),
+		}
+	}
+}
+
+/// Known peers, indexed by their base58-encoded `PeerId`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerStore {
+	/// Known peers, indexed by their base58-encoded `PeerId`.
+	pub peers: HashMap<String, StoredPeer>,
+}
+
+/// Part of the `PeerStore`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredPeer {
+	/// Addresses of the peer, as known by the discovery mechanisms.
+	pub addresses: Vec<Multiaddr>,
+	/// Reputation of the peer at the time `sampled_at`.
+	pub reputation: i32,
+	/// UNIX timestamp, in seconds, at which `reputation` was sampled.
+	pub sampled_at: u64,
+	/// UNIX timestamp, in seconds, of the last time we were connected to the peer, or of when we
+	/// discovered it if we were never connected.
+	pub last_seen: u64,
+}
+
+impl StoredPeer {
+	/// Returns the reputation of the peer at the UNIX timestamp `now`, taking its decay into
+	/// account.
+	pub fn reputation_at(&self, now: u64) -> i32 {
+		sc_peerset::decay_reputation(self.reputation, now.saturating_sub(self.sampled_at))
+	}
+
+	/// Returns the UNIX timestamp at which the peer is no longer banned, or `None` if it wasn't
+	/// banned at `sampled_at`.
+	pub fn banned_until(&self) -> Option<u64> {
+		let mut reputation = self.reputation;
+		let mut until = self.sampled_at;
+		while reputation < sc_peerset::BANNED_THRESHOLD {
+			reputation = sc_peerset::decay_reputation(reputation, 1);
+			until += 1;
+		}
+
+		if until == self.sampled_at {
+			None
+		} else {
+			Some(until)
+		}
+	}
+}
+
+impl PeerStore {
+	/// Loads the peer store from `backend`. Returns an empty store if none was saved.
+	pub fn load(backend: &dyn PeerStoreBackend) -> sp_blockchain::Result<Self> {
+		match backend.load_peer_store()? {
+			Some(encoded) => serde_json::from_slice(&encoded).map_err(|err| {
+				sp_blockchain::Error::Backend(format!("Invalid peer store: {}", err))
+			}),
+			None => Ok(Default::default()),
+		}
+	}
+
+	/// Saves the peer store to `backend`, replacing the previous one.
+	pub fn save(&self, backend: &dyn PeerStoreBackend) -> sp_blockchain::Result<()> {
+		let encoded = serde_json::to_vec(self).map_err(|err| {
+			sp_blockchain::Error::Backend(format!("Cannot encode the peer store: {}", err))
+		})?;
+		backend.save_peer_store(Some(&encoded))
+	}
+
+	/// Removes the peer store from `backend`.
+	pub fn clear(backend: &dyn PeerStoreBackend) -> sp_blockchain::Result<()> {
+		backend.save_peer_store(None)
+	}
+
+	/// Inserts the peer, replacing the previous entry, if any.
+	pub fn insert(&mut self, peer_id: &PeerId, peer: StoredPeer) {
+		self.peers.insert(peer_id.to_base58(), peer);
+	}
+
+	/// Removes the peers that are too old to be useful at the UNIX timestamp `now`, and the
+	/// least recently seen peers if there are too many of them.
+	pub fn prune(&mut self, now: u64) {
+		self.peers.retain(|_, peer| {
+			peer.last_seen.saturating_add(MAX_AGE_SECS) >= now ||
+				peer.banned_until().map_or(false, |until| until > now)
+		});
+
+		if self.peers.len() > MAX_PEERS {
+			let mut last_seen = self.peers.values().map(|p| p.last_seen).collect::<Vec<_>>();
+			last_seen.sort_unstable_by(|a, b| b.cmp(a));
+			let oldest_kept = last_seen[MAX_PEERS - 1];
+			self.peers.retain(|_, peer| peer.last_seen >= oldest_kept);
+		}
+	}
+
+	/// Returns the peers to restore at the UNIX timestamp `now`, with their current reputation
+	/// and addresses.
+	///
+	/// Entries with an invalid `PeerId` are ignored.
+	pub fn restore(mut self, now: u64) -> Vec<(PeerId, i32, Vec<Multiaddr>)> {
+		self.prune(now);
+		self.peers.into_iter().filter_map(|(peer_id, peer)| {
+			let reputation = peer.reputation_at(now);
+			match peer_id.parse::<PeerId>() {
+				Ok(peer_id) => Some((peer_id, reputation, peer.addresses)),
+				Err(_) => {
+					log::warn!(target: "sub-libp2p", "Ignoring invalid peer {} in peer store", peer_id);
+					None
+				}
+			}
+		}).collect()
+	}
+}
+
+/// Returns the current UNIX timestamp, in seconds.
+pub fn unix_time() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Saves the peer store to a `PeerStoreBackend` on a dedicated thread.
+///
+/// If several stores are queued while the thread is busy, only the most recent one is saved.
+pub struct PeerStoreWriter {
+	to_thread: mpsc::Sender<PeerStore>,
+}
+
+impl PeerStoreWriter {
+	/// Starts the thread saving the peer store to `backend`.
+	pub fn new(backend: Arc<dyn PeerStoreBackend>) -> io::Result<Self> {
+		let (to_thread, from_writer) = mpsc::channel::<PeerStore>();
+		thread::Builder::new()
+			.name("peer-store".into())
+			.spawn(move || {
+				// The loop ends once the `PeerStoreWriter` is dropped and the queue is empty.
+				while let Ok(mut store) = from_writer.recv() {
+					while let Ok(newer) = from_writer.try_recv() {
+						store = newer;
+					}
+					if let Err(err) = store.save(&*backend) {
+						log::warn!(target: "sub-libp2p", "Failed to save the peer store: {}", err);
+					} else {
+						log::trace!(target: "sub-libp2p", "Saved {} peers", store.peers.len());
+					}
+				}
+			})?;
+
+		Ok(PeerStoreWriter { to_thread })
+	}
+
+	/// Queues `store` to be saved, replacing the previous one.
+	pub fn save(&self, store: PeerStore) {
+		// Can only fail if the thread panicked, in which case there is nothing we can do.
+		let _ = self.to_thread.send(store);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use parking_lot::Mutex;
+
+	/// Auxiliary storage kept in memory.
+	#[derive(Default)]
+	struct MemoryAux(Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+	impl AuxStore for MemoryAux {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item=&'a(&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item=&'a &'b [u8]>,
+		>(&self, insert: I, delete: D) -> sp_blockchain::Result<()> {
+			let mut storage = self.0.lock();
+			for (key, value) in insert {
+				storage.insert(key.to_vec(), value.to_vec());
+			}
+			for key in delete {
+				storage.remove(*key);
+			}
+			Ok(())
+		}
+
+		fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+			Ok(self.0.lock().get(key).cloned())
+		}
+	}
+
+	fn stored_peer(reputation: i32, sampled_at: u64, last_seen: u64) -> StoredPeer {
+		StoredPeer {
+			addresses: vec!["/ip4/127.0.0.1/tcp/30333".parse().unwrap()],
+			reputation,
+			sampled_at,
+			last_seen,
+		}
+	}
+
+	#[test]
+	fn reputation_decays_while_offline() {
+		let peer = stored_peer(1000, 100, 50);
+		assert_eq!(peer.reputation_at(100), 1000);
+		assert_eq!(peer.reputation_at(101), 980);
+		assert_eq!(peer.reputation_at(100 + 3600), 0);
+		assert_eq!(peer.banned_until(), None);
+	}
+
+	#[test]
+	fn bans_expire() {
+		let peer = stored_peer(i32::min_value(), 100, 50);
+		let until = peer.banned_until().unwrap();
+		assert!(until > 100);
+		assert!(peer.reputation_at(until - 1) < sc_peerset::BANNED_THRESHOLD);
+		assert!(peer.reputation_at(until) >= sc_peerset::BANNED_THRESHOLD);
+	}
+
+	#[test]
+	fn prune_keeps_recent_and_banned_peers() {
+		let now = 10 * MAX_AGE_SECS;
+		let mut store = PeerStore::default();
+		let (recent, old, banned) = (PeerId::random(), PeerId::random(), PeerId::random());
+		store.insert(&recent, stored_peer(10, now, now - 10));
+		store.insert(&old, stored_peer(10, now, now - MAX_AGE_SECS - 1));
+		store.insert(&banned, stored_peer(i32::min_value(), now - 1, now - MAX_AGE_SECS - 1));
+
+		store.prune(now);
+		assert!(store.peers.contains_key(&recent.to_base58()));
+		assert!(!store.peers.contains_key(&old.to_base58()));
+		assert!(store.peers.contains_key(&banned.to_base58()));
+	}
+
+	#[test]
+	fn save_and_restore() {
+		let backend = MemoryAux::default();
+		assert_eq!(PeerStore::load(&backend).unwrap(), PeerStore::default());
+
+		let peer_id = PeerId::random();
+		let mut store = PeerStore::default();
+		store.insert(&peer_id, stored_peer(1000, 100, 100));
+		store.peers.insert("invalid".into(), stored_peer(1000, 100, 100));
+		store.save(&backend).unwrap();
+
+		let restored = PeerStore::load(&backend).unwrap().restore(101);
+		assert_eq!(restored, vec![(peer_id, 980, vec!["/ip4/127.0.0.1/tcp/30333".parse().unwrap()])]);
+
+		PeerStore::clear(&backend).unwrap();
+		assert_eq!(PeerStore::load(&backend).unwrap(), PeerStore::default());
+	}
+
+	#[test]
+	fn bans_survive_restarts() {
+		let backend = Arc::new(MemoryAux::default());
+		let now = 10 * MAX_AGE_SECS;
+
+		// The peer was banned right before the node stopped, an hour after we were last
+		// connected to it.
+		let banned = PeerId::random();
+		let mut store = PeerStore::default();
+		store.insert(&banned, stored_peer(i32::min_value(), now, now - 3600));
+		let writer = PeerStoreWriter::new(backend.clone()).unwrap();
+		writer.save(store);
+		drop(writer);
+
+		let mut saved = PeerStore::default();
+		for _ in 0..100 {
+			saved = PeerStore::load(&*backend).unwrap();
+			if !saved.peers.is_empty() {
+				break;
+			}
+			thread::sleep(std::time::Duration::from_millis(10));
+		}
+
+		// The ban only decays from the time the reputation was sampled, on restart.
+		let restored = saved.restore(now + 1);
+		assert_eq!(restored.len(), 1);
+		assert_eq!(restored[0].0, banned);
+		assert!(restored[0].1 < sc_peerset::BANNED_THRESHOLD);
+	}
+}
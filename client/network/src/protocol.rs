@@ -19,6 +19,7 @@ use crate::{
 	chain::{Client, FinalityProofProvider},
 	config::{BoxFinalityProofRequestBuilder, ProtocolId, TransactionPool, TransactionImportFuture, TransactionImport},
 	error,
+	protocol_bandwidth::ProtocolBandwidth,
 	utils::interval
 };
 
@@ -80,9 +81,23 @@ const MAX_KNOWN_EXTRINSICS: usize = 4096; // ~128kb per peer + overhead
 
 /// Maximim number of transaction validation request we keep at any moment.
 const MAX_PENDING_TRANSACTIONS: usize = 8192;
+/// Maximum number of transactions requested from, or sent to, a peer at once after an
+/// announcement.
+const MAX_TRANSACTIONS_PER_REQUEST: usize = 256;
+/// Time after which we request an announced transaction from another peer that announced it.
+const TRANSACTION_REQUEST_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+/// Maximum number of times we request an announced transaction after a timeout.
+const MAX_TRANSACTION_REQUEST_RETRIES: usize = 2;
+/// Interval over which the transactions requested by a peer are limited.
+const TRANSACTION_REQUESTS_INTERVAL: time::Duration = time::Duration::from_secs(1);
+/// Maximum number of transactions a peer can request from us per
+/// `TRANSACTION_REQUESTS_INTERVAL`.
+const MAX_REQUESTED_TRANSACTIONS_PER_INTERVAL: usize = 4 * MAX_TRANSACTIONS_PER_REQUEST;
 
 /// Current protocol version.
-pub(crate) const CURRENT_VERSION: u32 = 6;
+pub(crate) const CURRENT_VERSION: u32 = 7;
+/// Lowest version of the peers to which we announce transactions instead of sending them.
+const TRANSACTION_ANNOUNCE_VERSION: u32 = 7;
 /// Lowest version we support
 pub(crate) const MIN_VERSION: u32 = 3;
 
@@ -115,6 +130,9 @@ mod rep {
 	pub const GOOD_EXTRINSIC: Rep = Rep::new(1 << 7, "Good extrinsic");
 	/// Reputation change when a peer sends us a bad extrinsic.
 	pub const BAD_EXTRINSIC: Rep = Rep::new(-(1 << 12), "Bad extrinsic");
+	/// Reputation change when a peer requests more transactions than allowed.
+	pub const TOO_MANY_TRANSACTION_REQUESTS: Rep =
+		Rep::new(-(1 << 10), "Too many transaction requests");
 	/// We sent an RPC query to the given node, but it failed.
 	pub const RPC_FAILED: Rep = Rep::new(-(1 << 12), "Remote call failed");
 	/// We received a message that failed to decode.
@@ -220,6 +238,8 @@ pub struct Protocol<B: BlockT, H: ExHashT> {
 	pending_messages: VecDeque<CustomMessageOutcome<B>>,
 	/// Pending extrinsic verification tasks.
 	pending_transactions: FuturesUnordered<PendingTransaction>,
+	/// Announced transactions we have requested and not received yet.
+	requested_transactions: HashMap<H, TransactionRequest>,
 	config: ProtocolConfig,
 	genesis_hash: B::Hash,
 	sync: ChainSync<B>,
@@ -242,6 +262,8 @@ pub struct Protocol<B: BlockT, H: ExHashT> {
 	legacy_equiv_by_name: HashMap<Cow<'static, [u8]>, Fallback>,
 	/// Name of the protocol used for transactions.
 	transactions_protocol: Cow<'static, [u8]>,
+	/// Name of the protocol used for transaction announcements.
+	transaction_announces_protocol: Cow<'static, [u8]>,
 	/// Name of the protocol used for block announces.
 	block_announces_protocol: Cow<'static, [u8]>,
 	/// Prometheus metrics.
@@ -261,6 +283,16 @@ struct PacketStats {
 	count_out: u64,
 }
 
+/// An announced transaction that we have requested from a peer.
+struct TransactionRequest {
+	/// Peer the transaction has been requested from.
+	peer: PeerId,
+	/// When the transaction has been requested.
+	timestamp: Instant,
+	/// Number of times the transaction has already been requested from other peers.
+	retries: usize,
+}
+
 /// A peer that we are connected to
 /// and from whom we have not yet received a Status message.
 struct HandshakingPeer {
@@ -277,6 +309,13 @@ struct Peer<B: BlockT, H: ExHashT> {
 	obsolete_requests: HashMap<message::RequestId, Instant>,
 	/// Holds a set of transactions known to this peer.
 	known_extrinsics: LruHashSet<H>,
+	/// Holds a set of transactions announced by this peer.
+	announced_extrinsics: LruHashSet<H>,
+	/// Announced transactions that have not been requested from this peer yet.
+	queued_extrinsic_requests: VecDeque<H>,
+	/// Start of the current interval of transaction requests of this peer, and the number of
+	/// transactions it requested since.
+	transaction_requests: (Instant, usize),
 	/// Holds a set of blocks known to this peer.
 	known_blocks: LruHashSet<B::Hash>,
 	/// Request counter,
@@ -354,6 +393,8 @@ enum Fallback {
 	Consensus(ConsensusEngineId),
 	/// The message is the bytes encoding of a `Transactions<E>` (which is itself defined as a `Vec<E>`).
 	Transactions,
+	/// The message is the bytes encoding of a `TransactionAnnounce<H>`.
+	TransactionAnnounce,
 	/// The message is the bytes encoding of a `BlockAnnounce<H>`.
 	BlockAnnounce,
 }
@@ -373,6 +414,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		boot_node_ids: Arc<HashSet<PeerId>>,
 		use_new_block_requests_protocol: bool,
 		queue_size_report: Option<HistogramVec>,
+		bandwidth: ProtocolBandwidth,
 	) -> error::Result<(Protocol<B, H>, sc_peerset::PeersetHandle)> {
 		let info = chain.info();
 		let sync = ChainSync::new(
@@ -395,7 +437,13 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 
 		let (peerset, peerset_handle) = sc_peerset::Peerset::from_config(peerset_config);
 		let versions = &((MIN_VERSION as u8)..=(CURRENT_VERSION as u8)).collect::<Vec<u8>>();
-		let mut behaviour = GenericProto::new(protocol_id.clone(), versions, peerset, queue_size_report);
+		let mut behaviour = GenericProto::new(
+			protocol_id.clone(),
+			versions,
+			peerset,
+			queue_size_report,
+			bandwidth,
+		);
 
 		let mut legacy_equiv_by_name = HashMap::new();
 
@@ -408,6 +456,18 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		behaviour.register_notif_protocol(transactions_protocol.clone(), Vec::new());
 		legacy_equiv_by_name.insert(transactions_protocol.clone(), Fallback::Transactions);
 
+		let transaction_announces_protocol: Cow<'static, [u8]> = Cow::from({
+			let mut proto = b"/".to_vec();
+			proto.extend(protocol_id.as_bytes());
+			proto.extend(b"/transaction-announces/1");
+			proto
+		});
+		behaviour.register_notif_protocol(transaction_announces_protocol.clone(), Vec::new());
+		legacy_equiv_by_name.insert(
+			transaction_announces_protocol.clone(),
+			Fallback::TransactionAnnounce,
+		);
+
 		let block_announces_protocol: Cow<'static, [u8]> = Cow::from({
 			let mut proto = b"/".to_vec();
 			proto.extend(protocol_id.as_bytes());
@@ -425,6 +485,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			propagate_timeout: Box::pin(interval(PROPAGATE_TIMEOUT)),
 			pending_messages: VecDeque::new(),
 			pending_transactions: FuturesUnordered::new(),
+			requested_transactions: HashMap::new(),
 			config,
 			context_data: ContextData {
 				peers: HashMap::new(),
@@ -442,6 +503,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			protocol_name_by_engine: HashMap::new(),
 			legacy_equiv_by_name,
 			transactions_protocol,
+			transaction_announces_protocol,
 			block_announces_protocol,
 			metrics: if let Some(r) = metrics_registry {
 				Some(Metrics::register(r)?)
@@ -490,6 +552,12 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		self.behaviour.peerset_debug_info()
 	}
 
+	/// Returns the nodes known by the peerset manager, with their reputation and when we were last
+	/// connected to them.
+	pub fn peerset_known_peers(&mut self) -> Vec<(PeerId, i32, Instant)> {
+		self.behaviour.peerset_known_peers()
+	}
+
 	/// Returns the number of peers we're connected to.
 	pub fn num_connected_peers(&self) -> usize {
 		self.context_data.peers.values().count()
@@ -608,6 +676,14 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			},
 			GenericMessage::Transactions(m) =>
 				self.on_extrinsics(who, m),
+			GenericMessage::TransactionAnnounce(data) =>
+				match message::TransactionAnnounce::decode(&mut data.as_ref()) {
+					Ok(m) => self.on_transaction_announce(who, m),
+					Err(err) => {
+						debug!(target: "sync", "Couldn't decode transaction announce from {}: {:?}", who, err);
+						self.peerset_handle.report_peer(who, rep::BAD_MESSAGE);
+					}
+				},
 			GenericMessage::RemoteCallRequest(request) => self.on_remote_call_request(who, request),
 			GenericMessage::RemoteCallResponse(_) =>
 				warn!(target: "sub-libp2p", "Received unexpected RemoteCallResponse"),
@@ -911,6 +987,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 	/// > **Note**: This method normally doesn't have to be called except for testing purposes.
 	pub fn tick(&mut self) {
 		self.maintain_peers();
+		self.maintain_transaction_requests(Instant::now());
 		self.report_metrics()
 	}
 
@@ -1046,6 +1123,10 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 				block_request: None,
 				known_extrinsics: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_EXTRINSICS)
 					.expect("Constant is nonzero")),
+				announced_extrinsics: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_EXTRINSICS)
+					.expect("Constant is nonzero")),
+				queued_extrinsic_requests: VecDeque::new(),
+				transaction_requests: (Instant::now(), 0),
 				known_blocks: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_BLOCKS)
 					.expect("Constant is nonzero")),
 				next_request_id: 0,
@@ -1168,6 +1249,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 				}
 
 				let hash = self.transaction_pool.hash_of(&t);
+				self.requested_transactions.remove(&hash);
 				peer.known_extrinsics.insert(hash);
 
 				self.peerset_handle.report_peer(who.clone(), rep::ANY_EXTRINSIC);
@@ -1180,6 +1262,186 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		}
 	}
 
+	/// Called when a peer announces transactions, or requests transactions we announced.
+	fn on_transaction_announce(&mut self, who: PeerId, message: message::TransactionAnnounce<H>) {
+		// announcing extrinsics to light node is considered a bad behavior
+		if !self.config.roles.is_full() {
+			trace!(target: "sync", "Peer {} is trying to announce extrinsics to the light node", who);
+			self.behaviour.disconnect_peer(&who);
+			self.peerset_handle.report_peer(who, rep::UNEXPECTED_EXTRINSICS);
+			return;
+		}
+
+		match message {
+			message::TransactionAnnounce::Announce(hashes) => self.on_transactions_announced(who, hashes),
+			message::TransactionAnnounce::Request(hashes) => self.on_transactions_requested(who, hashes),
+		}
+	}
+
+	/// Requests the announced transactions that we don't know about.
+	fn on_transactions_announced(&mut self, who: PeerId, hashes: Vec<H>) {
+		// Accept extrinsics only when fully synced
+		if self.sync.status().state != SyncState::Idle {
+			trace!(target: "sync", "{} Ignoring extrinsics announce while syncing", who);
+			return;
+		}
+		trace!(target: "sync", "{} announced {} extrinsics", who, hashes.len());
+
+		let peer = match self.context_data.peers.get_mut(&who) {
+			Some(peer) => peer,
+			None => return,
+		};
+
+		for hash in hashes {
+			peer.known_extrinsics.insert(hash.clone());
+			peer.announced_extrinsics.insert(hash.clone());
+			// The announcements exceeding the limit are only kept for retries.
+			if peer.queued_extrinsic_requests.len() < MAX_KNOWN_EXTRINSICS {
+				peer.queued_extrinsic_requests.push_back(hash);
+			}
+		}
+
+		self.request_queued_transactions(&who, Instant::now());
+	}
+
+	/// Requests from `who` the next transactions it announced that we don't know about, up to
+	/// `MAX_TRANSACTIONS_PER_REQUEST`. The others stay queued until the next call.
+	fn request_queued_transactions(&mut self, who: &PeerId, now: Instant) {
+		let peer = match self.context_data.peers.get_mut(who) {
+			Some(peer) => peer,
+			None => return,
+		};
+
+		let mut to_request = Vec::new();
+		while to_request.len() < MAX_TRANSACTIONS_PER_REQUEST
+			&& self.requested_transactions.len() < MAX_PENDING_TRANSACTIONS
+		{
+			let hash = match peer.queued_extrinsic_requests.pop_front() {
+				Some(hash) => hash,
+				None => break,
+			};
+			if self.requested_transactions.contains_key(&hash)
+				|| self.transaction_pool.transaction(&hash).is_some()
+			{
+				continue;
+			}
+
+			self.requested_transactions.insert(hash.clone(), TransactionRequest {
+				peer: who.clone(),
+				timestamp: now,
+				retries: 0,
+			});
+			to_request.push(hash);
+		}
+
+		if !to_request.is_empty() {
+			self.request_transactions(who, to_request);
+		}
+	}
+
+	/// Sends the requested transactions that are in our pool.
+	fn on_transactions_requested(&mut self, who: PeerId, hashes: Vec<H>) {
+		let peer = match self.context_data.peers.get_mut(&who) {
+			Some(peer) => peer,
+			None => return,
+		};
+
+		let now = Instant::now();
+		if now - peer.transaction_requests.0 > TRANSACTION_REQUESTS_INTERVAL {
+			peer.transaction_requests = (now, 0);
+		}
+		let allowed = MAX_REQUESTED_TRANSACTIONS_PER_INTERVAL
+			.saturating_sub(peer.transaction_requests.1)
+			.min(MAX_TRANSACTIONS_PER_REQUEST);
+		if allowed == 0 {
+			trace!(target: "sync", "{} requested too many transactions, ignoring", who);
+			self.peerset_handle.report_peer(who, rep::TOO_MANY_TRANSACTION_REQUESTS);
+			return;
+		}
+		let hashes = hashes.into_iter().take(allowed).collect::<Vec<_>>();
+		peer.transaction_requests.1 += hashes.len();
+
+		let transaction_pool = &self.transaction_pool;
+		let to_send = hashes
+			.into_iter()
+			.filter_map(|hash| {
+				let extrinsic = transaction_pool.transaction(&hash)?;
+				peer.known_extrinsics.insert(hash);
+				Some(extrinsic)
+			})
+			.collect::<Vec<_>>();
+
+		if !to_send.is_empty() {
+			trace!(target: "sync", "Sending {} requested transactions to {}", to_send.len(), who);
+			let encoded = to_send.encode();
+			send_message::<B> (
+				&mut self.behaviour,
+				&mut self.context_data.stats,
+				&who,
+				Some((self.transactions_protocol.clone(), encoded)),
+				GenericMessage::Transactions(to_send)
+			)
+		}
+	}
+
+	fn request_transactions(&mut self, who: &PeerId, hashes: Vec<H>) {
+		trace!(target: "sync", "Requesting {} transactions from {}", hashes.len(), who);
+		let encoded = message::TransactionAnnounce::Request(hashes).encode();
+		send_message::<B> (
+			&mut self.behaviour,
+			&mut self.context_data.stats,
+			who,
+			Some((self.transaction_announces_protocol.clone(), encoded.clone())),
+			GenericMessage::TransactionAnnounce(encoded)
+		)
+	}
+
+	/// Requests the transactions whose request timed out from another peer that announced them,
+	/// and the transactions that are still queued.
+	fn maintain_transaction_requests(&mut self, now: Instant) {
+		let timed_out = self.requested_transactions.iter()
+			.filter(|(_, request)| now - request.timestamp > TRANSACTION_REQUEST_TIMEOUT)
+			.map(|(hash, _)| hash.clone())
+			.collect::<Vec<_>>();
+
+		let mut to_request = HashMap::<PeerId, Vec<H>>::new();
+		for hash in timed_out {
+			let request = match self.requested_transactions.remove(&hash) {
+				Some(request) => request,
+				None => continue,
+			};
+			if request.retries >= MAX_TRANSACTION_REQUEST_RETRIES
+				|| self.transaction_pool.transaction(&hash).is_some()
+			{
+				continue;
+			}
+
+			let next_peer = self.context_data.peers.iter()
+				.find(|(who, peer)| **who != request.peer
+					&& peer.info.protocol_version >= TRANSACTION_ANNOUNCE_VERSION
+					&& peer.announced_extrinsics.contains(&hash)
+				)
+				.map(|(who, _)| who.clone());
+			if let Some(who) = next_peer {
+				self.requested_transactions.insert(hash.clone(), TransactionRequest {
+					peer: who.clone(),
+					timestamp: now,
+					retries: request.retries + 1,
+				});
+				to_request.entry(who).or_default().push(hash);
+			}
+		}
+
+		for (who, hashes) in to_request {
+			self.request_transactions(&who, hashes);
+		}
+
+		let peers = self.context_data.peers.keys().cloned().collect::<Vec<_>>();
+		for who in peers {
+			self.request_queued_transactions(&who, now);
+		}
+	}
+
 	fn on_handle_extrinsic_import(&mut self, who: PeerId, import: TransactionImport) {
 		match import {
 			TransactionImport::KnownGood => self.peerset_handle.report_peer(who, rep::ANY_EXTRINSIC_REFUND),
@@ -1223,21 +1485,33 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 				.unzip();
 
 			if !to_send.is_empty() {
-				for hash in hashes {
+				for hash in &hashes {
 					propagated_to
-						.entry(hash)
+						.entry(hash.clone())
 						.or_insert_with(Vec::new)
 						.push(who.to_base58());
 				}
-				trace!(target: "sync", "Sending {} transactions to {}", to_send.len(), who);
-				let encoded = to_send.encode();
-				send_message::<B> (
-					&mut self.behaviour,
-					&mut self.context_data.stats,
-					&who,
-					Some((self.transactions_protocol.clone(), encoded)),
-					GenericMessage::Transactions(to_send)
-				)
+				if peer.info.protocol_version >= TRANSACTION_ANNOUNCE_VERSION {
+					trace!(target: "sync", "Announcing {} transactions to {}", hashes.len(), who);
+					let encoded = message::TransactionAnnounce::Announce(hashes).encode();
+					send_message::<B> (
+						&mut self.behaviour,
+						&mut self.context_data.stats,
+						&who,
+						Some((self.transaction_announces_protocol.clone(), encoded.clone())),
+						GenericMessage::TransactionAnnounce(encoded)
+					)
+				} else {
+					trace!(target: "sync", "Sending {} transactions to {}", to_send.len(), who);
+					let encoded = to_send.encode();
+					send_message::<B> (
+						&mut self.behaviour,
+						&mut self.context_data.stats,
+						&who,
+						Some((self.transactions_protocol.clone(), encoded)),
+						GenericMessage::Transactions(to_send)
+					)
+				}
 			}
 		}
 
@@ -2096,6 +2370,14 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviour for Protocol<B, H> {
 						}
 						CustomMessageOutcome::None
 					}
+					Some(Fallback::TransactionAnnounce) => {
+						if let Ok(m) = message::TransactionAnnounce::decode(&mut message.as_ref()) {
+							self.on_transaction_announce(peer_id, m);
+						} else {
+							warn!(target: "sub-libp2p", "Failed to decode transaction announce");
+						}
+						CustomMessageOutcome::None
+					}
 					Some(Fallback::BlockAnnounce) => {
 						if let Ok(announce) = message::BlockAnnounce::decode(&mut message.as_ref()) {
 							let outcome = self.on_block_announce(peer_id.clone(), announce);
@@ -2173,12 +2455,18 @@ impl<B: BlockT, H: ExHashT> Drop for Protocol<B, H> {
 mod tests {
 	use crate::PeerId;
 	use crate::config::EmptyTransactionPool;
-	use super::{CustomMessageOutcome, Protocol, ProtocolConfig};
+	use super::{
+		CustomMessageOutcome, Protocol, ProtocolConfig, Peer, PeerInfo, Roles, LruHashSet,
+		CURRENT_VERSION, MAX_KNOWN_BLOCKS, MAX_KNOWN_EXTRINSICS, MAX_TRANSACTIONS_PER_REQUEST,
+		MAX_REQUESTED_TRANSACTIONS_PER_INTERVAL, TRANSACTION_REQUEST_TIMEOUT,
+		TRANSACTION_REQUESTS_INTERVAL,
+	};
 
 	use sp_consensus::block_validation::DefaultBlockAnnounceValidator;
-	use std::sync::Arc;
+	use std::{collections::{HashMap, VecDeque}, num::NonZeroUsize, sync::Arc, time::Duration};
 	use substrate_test_runtime_client::{TestClientBuilder, TestClientBuilderExt};
 	use substrate_test_runtime_client::runtime::{Block, Hash};
+	use wasm_timer::Instant;
 
 	#[test]
 	fn no_handshake_no_notif_closed() {
@@ -2197,12 +2485,14 @@ mod tests {
 				bootnodes: Vec::new(),
 				reserved_only: false,
 				priority_groups: Vec::new(),
+				known_peers: Vec::new(),
 			},
 			Box::new(DefaultBlockAnnounceValidator::new(client.clone())),
 			None,
 			Default::default(),
 			true,
 			None,
+			Default::default(),
 		).unwrap();
 
 		let dummy_peer_id = PeerId::random();
@@ -2212,4 +2502,145 @@ mod tests {
 			_ => panic!()
 		};
 	}
+
+	fn test_protocol() -> Protocol<Block, Hash> {
+		let client = Arc::new(TestClientBuilder::with_default_backend().build_with_longest_chain().0);
+
+		Protocol::<Block, Hash>::new(
+			ProtocolConfig::default(),
+			client.clone(),
+			Arc::new(EmptyTransactionPool),
+			None,
+			None,
+			From::from(&b"test"[..]),
+			sc_peerset::PeersetConfig {
+				in_peers: 10,
+				out_peers: 10,
+				bootnodes: Vec::new(),
+				reserved_only: false,
+				priority_groups: Vec::new(),
+				known_peers: Vec::new(),
+			},
+			Box::new(DefaultBlockAnnounceValidator::new(client.clone())),
+			None,
+			Default::default(),
+			true,
+			None,
+			Default::default(),
+		).unwrap().0
+	}
+
+	/// Adds a peer that completed the handshake, without opening any substream.
+	fn add_peer(protocol: &mut Protocol<Block, Hash>) -> PeerId {
+		let who = PeerId::random();
+		protocol.context_data.peers.insert(who.clone(), Peer {
+			info: PeerInfo {
+				roles: Roles::FULL,
+				protocol_version: CURRENT_VERSION,
+				best_hash: Default::default(),
+				best_number: 0,
+			},
+			block_request: None,
+			obsolete_requests: HashMap::new(),
+			known_extrinsics: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_EXTRINSICS).unwrap()),
+			announced_extrinsics: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_EXTRINSICS).unwrap()),
+			queued_extrinsic_requests: VecDeque::new(),
+			transaction_requests: (Instant::now(), 0),
+			known_blocks: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_BLOCKS).unwrap()),
+			next_request_id: 0,
+		});
+		who
+	}
+
+	fn transaction_requests_sent(protocol: &Protocol<Block, Hash>) -> u64 {
+		protocol.context_data.stats.get("TransactionAnnounce").map_or(0, |stats| stats.count_out)
+	}
+
+	#[test]
+	fn announced_transactions_beyond_the_request_limit_stay_queued() {
+		let mut protocol = test_protocol();
+		let who = add_peer(&mut protocol);
+		let hashes = (0..MAX_TRANSACTIONS_PER_REQUEST as u64 + 10)
+			.map(Hash::from_low_u64_be)
+			.collect::<Vec<_>>();
+
+		protocol.on_transactions_announced(who.clone(), hashes.clone());
+		assert_eq!(protocol.requested_transactions.len(), MAX_TRANSACTIONS_PER_REQUEST);
+		assert_eq!(protocol.context_data.peers[&who].queued_extrinsic_requests.len(), 10);
+		assert_eq!(transaction_requests_sent(&protocol), 1);
+
+		// The remaining hashes are requested on the next tick.
+		protocol.maintain_transaction_requests(Instant::now());
+		assert!(hashes.iter().all(|hash| protocol.requested_transactions[hash].peer == who));
+		assert!(protocol.context_data.peers[&who].queued_extrinsic_requests.is_empty());
+		assert_eq!(transaction_requests_sent(&protocol), 2);
+
+		// Announcing the same transactions again doesn't request them again.
+		protocol.on_transactions_announced(who.clone(), hashes);
+		assert_eq!(transaction_requests_sent(&protocol), 2);
+	}
+
+	#[test]
+	fn transaction_requests_are_rate_limited_per_peer() {
+		let mut protocol = test_protocol();
+		let (who, other) = (add_peer(&mut protocol), add_peer(&mut protocol));
+		let requested = |protocol: &Protocol<Block, Hash>, who: &PeerId|
+			protocol.context_data.peers[who].transaction_requests.1;
+		let hashes = (0..MAX_TRANSACTIONS_PER_REQUEST as u64 + 10)
+			.map(Hash::from_low_u64_be)
+			.collect::<Vec<_>>();
+
+		// At most `MAX_TRANSACTIONS_PER_REQUEST` hashes of a request are looked up.
+		protocol.on_transactions_requested(who.clone(), hashes.clone());
+		assert_eq!(requested(&protocol, &who), MAX_TRANSACTIONS_PER_REQUEST);
+
+		for _ in 0..MAX_REQUESTED_TRANSACTIONS_PER_INTERVAL / MAX_TRANSACTIONS_PER_REQUEST {
+			protocol.on_transactions_requested(who.clone(), hashes.clone());
+		}
+		assert_eq!(requested(&protocol, &who), MAX_REQUESTED_TRANSACTIONS_PER_INTERVAL);
+
+		// Other peers are not affected.
+		protocol.on_transactions_requested(other.clone(), hashes.clone());
+		assert_eq!(requested(&protocol, &other), MAX_TRANSACTIONS_PER_REQUEST);
+
+		// The limit is reset once the interval passed.
+		let peer = protocol.context_data.peers.get_mut(&who).unwrap();
+		peer.transaction_requests.0 -= TRANSACTION_REQUESTS_INTERVAL + Duration::from_secs(1);
+		protocol.on_transactions_requested(who.clone(), hashes);
+		assert_eq!(requested(&protocol, &who), MAX_TRANSACTIONS_PER_REQUEST);
+	}
+
+	#[test]
+	fn timed_out_transaction_requests_are_retried_from_announcing_peers() {
+		let mut protocol = test_protocol();
+		let (first, second, other) =
+			(add_peer(&mut protocol), add_peer(&mut protocol), add_peer(&mut protocol));
+		let hash = Hash::from_low_u64_be(1);
+
+		// `other` knows the transaction because we announced it, so it can't be asked for it.
+		protocol.context_data.peers.get_mut(&other).unwrap().known_extrinsics.insert(hash);
+		protocol.on_transactions_announced(first.clone(), vec![hash]);
+		protocol.on_transactions_announced(second.clone(), vec![hash]);
+		assert_eq!(protocol.requested_transactions[&hash].peer, first);
+		assert_eq!(transaction_requests_sent(&protocol), 1);
+
+		let timeout = TRANSACTION_REQUEST_TIMEOUT + Duration::from_secs(1);
+		let mut now = Instant::now();
+		now += timeout;
+		protocol.maintain_transaction_requests(now);
+		assert_eq!(protocol.requested_transactions[&hash].peer, second);
+		assert_eq!(protocol.requested_transactions[&hash].retries, 1);
+
+		now += timeout;
+		protocol.maintain_transaction_requests(now);
+		assert_eq!(protocol.requested_transactions[&hash].peer, first);
+		assert_eq!(protocol.requested_transactions[&hash].retries, 2);
+		assert_eq!(transaction_requests_sent(&protocol), 3);
+
+		// We give up after `MAX_TRANSACTION_REQUEST_RETRIES` retries.
+		now += timeout;
+		protocol.maintain_transaction_requests(now);
+		assert!(protocol.requested_transactions.is_empty());
+		assert_eq!(transaction_requests_sent(&protocol), 3);
+	}
 }
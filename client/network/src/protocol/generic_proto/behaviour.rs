@@ -17,6 +17,7 @@
 use crate::config::ProtocolId;
 use crate::protocol::generic_proto::handler::{NotifsHandlerProto, NotifsHandlerOut, NotifsHandlerIn};
 use crate::protocol::generic_proto::upgrade::RegisteredProtocol;
+use crate::protocol_bandwidth::ProtocolBandwidth;
 
 use bytes::BytesMut;
 use fnv::FnvHashMap;
@@ -136,6 +137,12 @@ pub struct GenericProto {
 
 	/// If `Some`, report the message queue sizes on this `Histogram`.
 	queue_size_report: Option<HistogramVec>,
+
+	/// Bandwidth used by the legacy substream and every notifications protocol.
+	bandwidth: ProtocolBandwidth,
+
+	/// Name under which the bandwidth of the legacy substream is reported.
+	legacy_protocol_name: String,
 }
 
 /// State of a peer we're connected to.
@@ -320,12 +327,18 @@ impl GenericProto {
 	///
 	/// The `queue_size_report` is an optional Prometheus metric that can report the size of the
 	/// messages queue. If passed, it must have one label for the protocol name.
+	///
+	/// The messages sent and received are reported to `bandwidth`, which also enforces the
+	/// bandwidth limits of the legacy substream and of the notifications protocols.
 	pub fn new(
 		protocol: impl Into<ProtocolId>,
 		versions: &[u8],
 		peerset: sc_peerset::Peerset,
 		queue_size_report: Option<HistogramVec>,
+		bandwidth: ProtocolBandwidth,
 	) -> Self {
+		let protocol = protocol.into();
+		let legacy_protocol_name = format!("/substrate/{}", String::from_utf8_lossy(protocol.as_bytes()));
 		let legacy_protocol = RegisteredProtocol::new(protocol, versions);
 
 		GenericProto {
@@ -337,6 +350,8 @@ impl GenericProto {
 			next_incoming_index: sc_peerset::IncomingIndex(0),
 			events: SmallVec::new(),
 			queue_size_report,
+			bandwidth,
+			legacy_protocol_name,
 		}
 	}
 
@@ -546,13 +561,26 @@ impl GenericProto {
 			target,
 			str::from_utf8(&protocol_name)
 		);
+
+		let message = message.into();
+		let protocol_str = String::from_utf8_lossy(&protocol_name);
+		if !self.bandwidth.try_report_out(&protocol_str, message.len()) {
+			trace!(
+				target: "sub-libp2p",
+				"Dropping notification to {:?} on {}: bandwidth limit exceeded",
+				target,
+				protocol_str
+			);
+			return
+		}
+
 		trace!(target: "sub-libp2p", "Handler({:?}) <= Packet", target);
 
 		self.events.push(NetworkBehaviourAction::NotifyHandler {
 			peer_id: target.clone(),
 			handler: NotifyHandler::One(conn),
 			event: NotifsHandlerIn::SendNotification {
-				message: message.into(),
+				message,
 				encoded_fallback_message,
 				protocol_name,
 			},
@@ -565,6 +593,8 @@ impl GenericProto {
 	///
 	/// Also note that even we have a valid open substream, it may in fact be already closed
 	/// without us knowing, in which case the packet will not be received.
+	///
+	/// The packet is dropped if it exceeds the bandwidth limit of the legacy substream.
 	pub fn send_packet(&mut self, target: &PeerId, message: Vec<u8>) {
 		let conn = match self.peers.get(target).and_then(|p| p.get_open()) {
 			None => {
@@ -577,6 +607,15 @@ impl GenericProto {
 		};
 
 		trace!(target: "sub-libp2p", "External API => Packet for {:?}", target);
+		if !self.bandwidth.try_report_out(&self.legacy_protocol_name, message.len()) {
+			trace!(
+				target: "sub-libp2p",
+				"Dropping packet to {:?}: bandwidth limit exceeded",
+				target
+			);
+			return
+		}
+
 		trace!(target: "sub-libp2p", "Handler({:?}) <= Packet", target);
 		self.events.push(NetworkBehaviourAction::NotifyHandler {
 			peer_id: target.clone(),
@@ -592,6 +631,12 @@ impl GenericProto {
 		self.peerset.debug_info()
 	}
 
+	/// Returns the nodes known by the peerset manager, with their reputation and when we were last
+	/// connected to them.
+	pub fn peerset_known_peers(&mut self) -> Vec<(PeerId, i32, Instant)> {
+		self.peerset.known_peers()
+	}
+
 	/// Function that is called when the peerset wants us to connect to a peer.
 	fn peerset_report_connect(&mut self, peer_id: PeerId) {
 		let mut occ_entry = match self.peers.entry(peer_id) {
@@ -1250,6 +1295,7 @@ impl NetworkBehaviour for GenericProto {
 				debug_assert!(self.is_open(&source));
 				trace!(target: "sub-libp2p", "Handler({:?}) => Message", source);
 				trace!(target: "sub-libp2p", "External API <= Message({:?})", source);
+				self.bandwidth.report_in(&self.legacy_protocol_name, message.len());
 				let event = GenericProtoOut::LegacyMessage {
 					peer_id: source,
 					message,
@@ -1267,6 +1313,7 @@ impl NetworkBehaviour for GenericProto {
 					str::from_utf8(&protocol_name)
 				);
 				trace!(target: "sub-libp2p", "External API <= Message({:?}, {:?})", protocol_name, source);
+				self.bandwidth.report_in(&String::from_utf8_lossy(&protocol_name), message.len());
 				let event = GenericProtoOut::Notification {
 					peer_id: source,
 					protocol_name,
@@ -28,6 +28,7 @@ use std::{error, io, task::Context, task::Poll, time::Duration};
 use std::collections::HashSet;
 use crate::protocol::message::{generic::BlockResponse, Message};
 use crate::protocol::generic_proto::{GenericProto, GenericProtoOut};
+use crate::protocol_bandwidth::ProtocolBandwidth;
 use sp_test_primitives::Block;
 
 /// Builds two nodes that have each other as bootstrap nodes.
@@ -82,7 +83,13 @@ fn build_nodes() -> (Swarm<CustomProtoWithAddr>, Swarm<CustomProtoWithAddr>) {
 		});
 
 		let behaviour = CustomProtoWithAddr {
-			inner: GenericProto::new(&b"test"[..], &[1], peerset, None),
+			inner: GenericProto::new(
+				&b"test"[..],
+				&[1],
+				peerset,
+				None,
+				ProtocolBandwidth::default(),
+			),
 			addrs: addrs
 				.iter()
 				.enumerate()
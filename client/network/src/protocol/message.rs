@@ -68,6 +68,19 @@ pub type BlockResponse<B> = generic::BlockResponse<
 /// A set of transactions.
 pub type Transactions<E> = Vec<E>;
 
+/// Announcement of transactions, or request of announced transactions.
+///
+/// Only exchanged with peers whose protocol version is at least 7. Older peers are sent the
+/// `Transactions` themselves.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+pub enum TransactionAnnounce<H> {
+	/// Hashes of transactions that are in the pool of the sender.
+	Announce(Vec<H>),
+	/// Hashes of announced transactions that the sender wants. They are sent back in a
+	/// `Transactions` message.
+	Request(Vec<H>),
+}
+
 // Bits of block data and associated artifacts to request.
 bitflags! {
 	/// Node roles bitmask.
@@ -276,6 +289,8 @@ pub mod generic {
 		FinalityProofResponse(FinalityProofResponse<Hash>),
 		/// Batch of consensus protocol messages.
 		ConsensusBatch(Vec<ConsensusMessage>),
+		/// Encoded `TransactionAnnounce`, whose hash type is unknown to this message.
+		TransactionAnnounce(Vec<u8>),
 	}
 
 	impl<Header, Hash, Number, Extrinsic> Message<Header, Hash, Number, Extrinsic> {
@@ -300,6 +315,7 @@ pub mod generic {
 				Message::FinalityProofRequest(_) => "FinalityProofRequest",
 				Message::FinalityProofResponse(_) => "FinalityProofResponse",
 				Message::ConsensusBatch(_) => "ConsensusBatch",
+				Message::TransactionAnnounce(_) => "TransactionAnnounce",
 			}
 		}
 	}
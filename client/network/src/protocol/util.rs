@@ -46,6 +46,11 @@ impl<T: Hash + Eq> LruHashSet<T> {
 		}
 		false
 	}
+
+	/// Returns `true` if the set contains the element.
+	pub(crate) fn contains(&self, e: &T) -> bool {
+		self.set.contains(e)
+	}
 }
 
 #[cfg(test)]
@@ -72,5 +77,7 @@ mod tests {
 		// We reached the limit. The next element forces the oldest one out.
 		assert!(set.insert(3));
 		assert_eq!(vec![&1, &3], set.set.iter().collect::<Vec<_>>());
+		assert!(set.contains(&1));
+		assert!(!set.contains(&2));
 	}
 }
@@ -0,0 +1,279 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+//
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounting of the bandwidth used by every protocol.
+//!
+//! The bytes and messages sent and received are counted for the legacy substream, every
+//! notifications protocol and every request-response protocol. The outgoing messages of the
+//! legacy substream and of notifications protocols can additionally be limited with a token
+//! bucket, in which case the messages exceeding the limit are dropped.
+
+use crate::network_state::ProtocolBandwidthStats;
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
+use wasm_timer::Instant;
+
+/// Limit of the outgoing bandwidth of a protocol.
+///
+/// Tokens are added to the bucket at a rate of `bytes_per_sec`, up to `burst` tokens, and every
+/// message consumes as many tokens as its size. A message larger than `burst` is only sent if the
+/// bucket is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandwidthLimit {
+	/// Sustained number of bytes per second.
+	pub bytes_per_sec: u64,
+	/// Maximum number of bytes that can be sent at once after the protocol has been idle.
+	pub burst: u64,
+}
+
+/// Token bucket enforcing a `BandwidthLimit`.
+#[derive(Debug)]
+struct TokenBucket {
+	limit: BandwidthLimit,
+	tokens: u64,
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn new(limit: BandwidthLimit) -> Self {
+		TokenBucket { limit, tokens: limit.burst, last_refill: Instant::now() }
+	}
+
+	/// Consumes the tokens for a message of `bytes` bytes. Returns `false` if the message
+	/// exceeds the limit.
+	fn try_consume(&mut self, bytes: u64, now: Instant) -> bool {
+		let elapsed = if now > self.last_refill { now - self.last_refill } else { Duration::from_secs(0) };
+		let refill = elapsed.as_micros() * u128::from(self.limit.bytes_per_sec) / 1_000_000;
+		// The refill time is only moved forward if tokens are added, in order not to lose the
+		// fractions of tokens on frequent calls.
+		if refill > 0 {
+			let refill = u64::try_from(refill).unwrap_or(u64::max_value());
+			self.tokens = self.limit.burst.min(self.tokens.saturating_add(refill));
+			self.last_refill = now;
+		}
+
+		if bytes <= self.tokens {
+			self.tokens -= bytes;
+			true
+		} else if self.tokens == self.limit.burst {
+			self.tokens = 0;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+/// Prometheus metrics of the bandwidth used by every protocol.
+struct Metrics {
+	bytes_total: CounterVec<U64>,
+	messages_total: CounterVec<U64>,
+	messages_dropped_total: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Metrics {
+			bytes_total: register(CounterVec::new(
+				Opts::new(
+					"sub_libp2p_protocol_bytes_total",
+					"Total number of bytes sent and received, by protocol"
+				),
+				&["direction", "protocol"]
+			)?, registry)?,
+			messages_total: register(CounterVec::new(
+				Opts::new(
+					"sub_libp2p_protocol_messages_total",
+					"Total number of messages sent and received, by protocol"
+				),
+				&["direction", "protocol"]
+			)?, registry)?,
+			messages_dropped_total: register(CounterVec::new(
+				Opts::new(
+					"sub_libp2p_protocol_messages_dropped_total",
+					"Total number of outgoing messages dropped because of the bandwidth limit, by protocol"
+				),
+				&["protocol"]
+			)?, registry)?,
+		})
+	}
+}
+
+/// Bandwidth used by a protocol and its limit, if any.
+struct ProtocolEntry {
+	stats: ProtocolBandwidthStats,
+	bucket: Option<TokenBucket>,
+}
+
+struct Inner {
+	protocols: HashMap<String, ProtocolEntry>,
+	limits: HashMap<String, BandwidthLimit>,
+	metrics: Option<Metrics>,
+}
+
+impl Inner {
+	fn entry(&mut self, protocol: &str) -> &mut ProtocolEntry {
+		if !self.protocols.contains_key(protocol) {
+			let entry = ProtocolEntry {
+				stats: Default::default(),
+				bucket: self.limits.get(protocol).cloned().map(TokenBucket::new),
+			};
+			self.protocols.insert(protocol.to_owned(), entry);
+		}
+		self.protocols.get_mut(protocol).expect("inserted above if missing; qed")
+	}
+}
+
+/// Bandwidth used by every protocol.
+///
+/// Shared between the behaviours sending and receiving the messages, and the `NetworkWorker`
+/// reporting the bandwidth.
+#[derive(Clone)]
+pub struct ProtocolBandwidth {
+	inner: Arc<Mutex<Inner>>,
+}
+
+impl ProtocolBandwidth {
+	/// Creates a new `ProtocolBandwidth` enforcing the given limits, indexed by protocol name.
+	///
+	/// The metrics are registered in `registry`, if any.
+	pub fn new(
+		limits: HashMap<String, BandwidthLimit>,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		let metrics = registry.map(Metrics::register).transpose()?;
+		Ok(ProtocolBandwidth {
+			inner: Arc::new(Mutex::new(Inner { protocols: HashMap::new(), limits, metrics })),
+		})
+	}
+
+	/// Counts a message of `bytes` bytes received on `protocol`.
+	pub fn report_in(&self, protocol: &str, bytes: usize) {
+		let mut inner = self.inner.lock();
+		let stats = &mut inner.entry(protocol).stats;
+		stats.bytes_in += bytes as u64;
+		stats.messages_in += 1;
+		if let Some(metrics) = &inner.metrics {
+			metrics.bytes_total.with_label_values(&["in", protocol]).inc_by(bytes as u64);
+			metrics.messages_total.with_label_values(&["in", protocol]).inc();
+		}
+	}
+
+	/// Counts a message of `bytes` bytes sent on `protocol`, regardless of its limit.
+	pub fn report_out(&self, protocol: &str, bytes: usize) {
+		let mut inner = self.inner.lock();
+		let stats = &mut inner.entry(protocol).stats;
+		stats.bytes_out += bytes as u64;
+		stats.messages_out += 1;
+		if let Some(metrics) = &inner.metrics {
+			metrics.bytes_total.with_label_values(&["out", protocol]).inc_by(bytes as u64);
+			metrics.messages_total.with_label_values(&["out", protocol]).inc();
+		}
+	}
+
+	/// Checks the limit of `protocol` for a message of `bytes` bytes, and counts it if it can be
+	/// sent.
+	///
+	/// Returns `false` if the message exceeds the limit and must be dropped.
+	pub fn try_report_out(&self, protocol: &str, bytes: usize) -> bool {
+		let mut inner = self.inner.lock();
+		let entry = inner.entry(protocol);
+		let allowed = entry.bucket.as_mut()
+			.map_or(true, |bucket| bucket.try_consume(bytes as u64, Instant::now()));
+		if allowed {
+			drop(inner);
+			self.report_out(protocol, bytes);
+		} else {
+			entry.stats.messages_dropped += 1;
+			if let Some(metrics) = &inner.metrics {
+				metrics.messages_dropped_total.with_label_values(&[protocol]).inc();
+			}
+		}
+		allowed
+	}
+
+	/// Returns the bandwidth used so far by every protocol, indexed by protocol name.
+	pub fn stats(&self) -> HashMap<String, ProtocolBandwidthStats> {
+		self.inner.lock().protocols.iter()
+			.map(|(protocol, entry)| (protocol.clone(), entry.stats.clone()))
+			.collect()
+	}
+}
+
+impl Default for ProtocolBandwidth {
+	fn default() -> Self {
+		ProtocolBandwidth::new(HashMap::new(), None)
+			.expect("no metrics are registered without a registry; qed")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn token_bucket_limits_bandwidth() {
+		let mut bucket = TokenBucket::new(BandwidthLimit { bytes_per_sec: 1000, burst: 500 });
+		let start = bucket.last_refill;
+
+		assert!(bucket.try_consume(400, start));
+		assert!(!bucket.try_consume(200, start));
+		// 200 tokens are added after 200ms.
+		assert!(bucket.try_consume(200, start + Duration::from_millis(200)));
+		assert!(!bucket.try_consume(200, start + Duration::from_millis(200)));
+		// The bucket never holds more than `burst` tokens.
+		assert!(bucket.try_consume(400, start + Duration::from_secs(10)));
+		assert!(!bucket.try_consume(101, start + Duration::from_secs(10)));
+		assert!(bucket.try_consume(100, start + Duration::from_secs(10)));
+	}
+
+	#[test]
+	fn oversized_messages_only_pass_with_full_bucket() {
+		let mut bucket = TokenBucket::new(BandwidthLimit { bytes_per_sec: 100, burst: 100 });
+		let start = bucket.last_refill;
+
+		assert!(bucket.try_consume(1000, start));
+		// The oversized message drained the bucket.
+		assert!(!bucket.try_consume(1, start));
+		assert!(!bucket.try_consume(1000, start + Duration::from_millis(500)));
+		assert!(bucket.try_consume(1000, start + Duration::from_secs(1)));
+	}
+
+	#[test]
+	fn counts_and_drops_messages() {
+		let mut limits = HashMap::new();
+		limits.insert("/limited".to_string(), BandwidthLimit { bytes_per_sec: 0, burst: 10 });
+		let bandwidth = ProtocolBandwidth::new(limits, None).unwrap();
+
+		bandwidth.report_in("/free", 100);
+		assert!(bandwidth.try_report_out("/free", 1000));
+		assert!(bandwidth.try_report_out("/limited", 10));
+		assert!(!bandwidth.try_report_out("/limited", 10));
+
+		let stats = bandwidth.stats();
+		assert_eq!(stats["/free"], ProtocolBandwidthStats {
+			bytes_in: 100,
+			bytes_out: 1000,
+			messages_in: 1,
+			messages_out: 1,
+			messages_dropped: 0,
+		});
+		assert_eq!(stats["/limited"].messages_out, 1);
+		assert_eq!(stats["/limited"].messages_dropped, 1);
+	}
+}
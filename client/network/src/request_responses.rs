@@ -0,0 +1,764 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+//
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collection of request-response protocols.
+//!
+//! The [`RequestResponsesBehaviour`] struct defined in this module provides support for zero or
+//! more so-called "request-response" protocols.
+//!
+//! A request-response protocol works in the following way:
+//!
+//! - For every emitted request, a new substream is open and the protocol is negotiated. If the
+//! remote supports the protocol, the size of the request is sent as a LEB128 number, followed
+//! with the request itself. The remote then sends the size of the response as a LEB128 number,
+//! followed with the response.
+//!
+//! - Requests have a certain time limit before they time out. This time includes the time it
+//! takes to send/receive the request and response.
+//!
+//! - If provided, a ["requests processing"](ProtocolConfig::inbound_queue) channel is used to
+//! handle incoming requests.
+//!
+//! > **Note**: As with the previous block and finality proof request handlers, a remote that
+//! > doesn't support a protocol, or that answers a request improperly, is disconnected.
+
+use crate::{protocol_bandwidth::ProtocolBandwidth, ReputationChange};
+use futures::{channel::{mpsc, oneshot}, future::BoxFuture, prelude::*, stream::FuturesUnordered};
+use futures_timer::Delay;
+use libp2p::{
+	core::{
+		ConnectedPoint, Multiaddr, PeerId,
+		connection::ConnectionId,
+		upgrade::{InboundUpgrade, OutboundUpgrade, ReadOneError, UpgradeInfo, read_one, write_one},
+	},
+	swarm::{
+		NegotiatedSubstream, NetworkBehaviour, NetworkBehaviourAction, NotifyHandler, OneShotHandler,
+		OneShotHandlerConfig, PollParameters, SubstreamProtocol,
+	},
+};
+use std::{
+	borrow::Cow, collections::{hash_map::Entry, HashMap, VecDeque}, convert::TryFrom as _, io, iter,
+	pin::Pin, task::{Context, Poll}, time::Duration,
+};
+use wasm_timer::Instant;
+
+/// Configuration for a single request-response protocol.
+#[derive(Debug, Clone)]
+pub struct ProtocolConfig {
+	/// Name of the protocol on the wire. Should be something like `/foo/bar`.
+	pub name: Cow<'static, str>,
+
+	/// Maximum allowed size, in bytes, of a request.
+	///
+	/// Any request larger than this value will be declined as a way to avoid allocating too
+	/// much memory for it.
+	pub max_request_size: u64,
+
+	/// Maximum allowed size, in bytes, of a response.
+	///
+	/// Any response larger than this value will be declined as a way to avoid allocating too
+	/// much memory for it.
+	pub max_response_size: u64,
+
+	/// Duration after which emitted requests are considered timed out.
+	///
+	/// If you expect the response to come back quickly, you should set this to a smaller value.
+	pub request_timeout: Duration,
+
+	/// Channel on which the networking service will send incoming requests.
+	///
+	/// Every time a peer sends a request to the local node using this protocol, the networking
+	/// service will push an element on this channel. The receiving side of this channel then has
+	/// to pull this element, process the request, and send back the response to send back to the
+	/// peer.
+	///
+	/// The size of the channel has to be carefully chosen. If the channel is full, the networking
+	/// service will discard the incoming request and send back an error to the peer. Consequently,
+	/// the channel being full is an indicator that the node is overloaded.
+	///
+	/// If this is `None`, then the local node will not advertise support for this protocol towards
+	/// other peers. If this is `Some` but the channel is closed, then the local node will
+	/// advertise support for this protocol, but any incoming request will lead to an error being
+	/// sent back.
+	pub inbound_queue: Option<mpsc::Sender<IncomingRequest>>,
+}
+
+/// A single request received by a peer on a request-response protocol.
+#[derive(Debug)]
+pub struct IncomingRequest {
+	/// Who sent the request.
+	pub peer: PeerId,
+
+	/// Request sent by the remote. Will always be smaller than
+	/// [`ProtocolConfig::max_request_size`].
+	pub payload: Vec<u8>,
+
+	/// Channel to send back the response to.
+	///
+	/// Dropping this sender, or sending an `Err(())` as result, declines the request.
+	pub pending_response: oneshot::Sender<OutgoingResponse>,
+}
+
+/// Response for an incoming request to be send by a request protocol handler.
+#[derive(Debug)]
+pub struct OutgoingResponse {
+	/// The payload of the response.
+	///
+	/// `Err(())` if none is available e.g. due an error while handling the request.
+	pub result: Result<Vec<u8>, ()>,
+
+	/// Reputation changes accrued while handling the request. To be applied to the reputation of
+	/// the peer sending the request.
+	pub reputation_changes: Vec<ReputationChange>,
+}
+
+/// Event generated by the [`RequestResponsesBehaviour`].
+#[derive(Debug)]
+pub enum Event {
+	/// A remote sent a request and either we have successfully answered it or an error happened.
+	///
+	/// This event is generated for statistics purposes.
+	InboundRequest {
+		/// Peer which has emitted the request.
+		peer: PeerId,
+		/// Name of the protocol in question.
+		protocol: Cow<'static, str>,
+		/// If `Ok`, contains the time elapsed between when we received the request and when we
+		/// sent back the response. If `Err`, the error that happened.
+		result: Result<Duration, ResponseFailure>,
+	},
+
+	/// A request has been sent to a remote.
+	///
+	/// This event is generated for statistics purposes.
+	RequestStarted {
+		/// Peer that we send the request to.
+		peer: PeerId,
+		/// Name of the protocol in question.
+		protocol: Cow<'static, str>,
+	},
+
+	/// A request initiated using [`RequestResponsesBehaviour::send_request`] has succeeded or
+	/// failed.
+	///
+	/// This event is generated for statistics purposes.
+	RequestFinished {
+		/// Peer that we send a request to.
+		peer: PeerId,
+		/// Name of the protocol in question.
+		protocol: Cow<'static, str>,
+		/// Duration the request took.
+		duration: Duration,
+		/// Result of the request.
+		result: Result<(), RequestFailure>,
+	},
+
+	/// A request protocol handler issued reputation changes for the given peer.
+	ReputationChanges {
+		/// Peer whose reputation needs to be changed.
+		peer: PeerId,
+		/// The reputation changes to apply.
+		changes: Vec<ReputationChange>,
+	},
+}
+
+/// Error when registering a protocol.
+#[derive(Debug, thiserror::Error)]
+pub enum RegisterError {
+	/// A protocol has been specified multiple times.
+	#[error("request-response protocol {0} registered multiple times")]
+	DuplicateProtocol(Cow<'static, str>),
+}
+
+/// Error in a request.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum RequestFailure {
+	/// We are not currently connected to the requested peer.
+	#[error("not connected to the peer")]
+	NotConnected,
+	/// Given protocol hasn't been registered.
+	#[error("protocol has not been registered")]
+	UnknownProtocol,
+	/// The request is larger than the maximum allowed size of the protocol.
+	#[error("request is larger than the maximum allowed size")]
+	RequestTooLarge,
+	/// The request didn't get a response in time.
+	#[error("request timed out")]
+	Timeout,
+	/// The connection the request was sent on was closed before the response arrived, e.g. because
+	/// the remote declined the request or doesn't support the protocol.
+	#[error("connection closed before the response arrived")]
+	ConnectionClosed,
+}
+
+/// Error when processing a request sent by a remote.
+#[derive(Debug, thiserror::Error)]
+pub enum ResponseFailure {
+	/// The queue of incoming requests of the protocol is full.
+	#[error("too many pending requests")]
+	Busy,
+	/// The request protocol handler declined the request.
+	#[error("request declined")]
+	Refused,
+	/// Failed to send the response back to the remote.
+	#[error("network error: {0}")]
+	Network(#[from] io::Error),
+}
+
+/// Identifier of a request emitted by the local node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RequestId(u64);
+
+/// A request that we have sent and whose response hasn't arrived yet.
+struct PendingRequest {
+	peer: PeerId,
+	connection: ConnectionId,
+	protocol: Cow<'static, str>,
+	/// `Instant` when the request has been emitted.
+	started: Instant,
+	timeout: Delay,
+	pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+}
+
+/// Implementation of `NetworkBehaviour` that provides support for request-response protocols.
+pub struct RequestResponsesBehaviour {
+	/// The configuration of every registered protocol, indexed by protocol name.
+	protocols: HashMap<Cow<'static, str>, ProtocolConfig>,
+	/// List of all active connections of every peer.
+	connections: HashMap<PeerId, Vec<ConnectionId>>,
+	/// Requests that we have sent and whose response hasn't arrived yet.
+	pending_requests: HashMap<RequestId, PendingRequest>,
+	/// Identifier of the next request that we send.
+	next_request_id: RequestId,
+	/// Futures sending back the responses to incoming requests, and returning the event to
+	/// generate once done.
+	pending_responses: FuturesUnordered<BoxFuture<'static, Vec<Event>>>,
+	/// Events to return as soon as possible from `poll`.
+	pending_events: VecDeque<NetworkBehaviourAction<OutboundProtocol, Event>>,
+	/// Bandwidth used by every protocol, to which the requests and responses are reported.
+	bandwidth: ProtocolBandwidth,
+}
+
+impl RequestResponsesBehaviour {
+	/// Creates a new behaviour. Must be passed a list of supported protocols. Returns an error if
+	/// the same protocol is passed twice.
+	///
+	/// The size of the requests and responses is reported to `bandwidth`.
+	pub fn new(
+		list: impl Iterator<Item = ProtocolConfig>,
+		bandwidth: ProtocolBandwidth,
+	) -> Result<Self, RegisterError> {
+		let mut protocols = HashMap::new();
+		for protocol in list {
+			match protocols.entry(protocol.name.clone()) {
+				Entry::Vacant(e) => e.insert(protocol),
+				Entry::Occupied(e) => return Err(RegisterError::DuplicateProtocol(e.key().clone())),
+			};
+		}
+
+		Ok(RequestResponsesBehaviour {
+			protocols,
+			connections: HashMap::new(),
+			pending_requests: HashMap::new(),
+			next_request_id: RequestId(0),
+			pending_responses: FuturesUnordered::new(),
+			pending_events: VecDeque::new(),
+			bandwidth,
+		})
+	}
+
+	/// Initiates sending a request.
+	///
+	/// An error is sent on `pending_response` if we're not connected to the target, if the
+	/// protocol is unknown, or if the request doesn't get a response in time.
+	pub fn send_request(
+		&mut self,
+		target: &PeerId,
+		protocol: &str,
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) {
+		let config = match self.protocols.get(protocol) {
+			Some(config) => config,
+			None => {
+				log::debug!(target: "sub-libp2p", "Request to {} on unknown protocol {}", target, protocol);
+				let _ = pending_response.send(Err(RequestFailure::UnknownProtocol));
+				return;
+			}
+		};
+
+		if request.len() as u64 > config.max_request_size {
+			let _ = pending_response.send(Err(RequestFailure::RequestTooLarge));
+			return;
+		}
+
+		let connection = match self.connections.get(target).and_then(|c| c.first()) {
+			Some(connection) => *connection,
+			None => {
+				let _ = pending_response.send(Err(RequestFailure::NotConnected));
+				return;
+			}
+		};
+
+		let request_id = self.next_request_id;
+		self.next_request_id = RequestId(request_id.0.wrapping_add(1));
+
+		self.pending_requests.insert(request_id, PendingRequest {
+			peer: target.clone(),
+			connection,
+			protocol: config.name.clone(),
+			started: Instant::now(),
+			timeout: Delay::new(config.request_timeout),
+			pending_response,
+		});
+
+		log::trace!(target: "sub-libp2p", "Enqueueing request to {} on {}", target, config.name);
+		self.bandwidth.report_out(&config.name, request.len());
+		self.pending_events.push_back(NetworkBehaviourAction::NotifyHandler {
+			peer_id: target.clone(),
+			handler: NotifyHandler::One(connection),
+			event: OutboundProtocol {
+				protocol: config.name.clone(),
+				request_id,
+				request,
+				max_response_size: usize::try_from(config.max_response_size).unwrap_or(usize::max_value()),
+			},
+		});
+		self.pending_events.push_back(NetworkBehaviourAction::GenerateEvent(Event::RequestStarted {
+			peer: target.clone(),
+			protocol: config.name.clone(),
+		}));
+	}
+
+	/// Removes the pending request, answers it with `result` and generates the corresponding
+	/// `RequestFinished` event.
+	fn finish_request(&mut self, request_id: RequestId, result: Result<Vec<u8>, RequestFailure>) {
+		let request = match self.pending_requests.remove(&request_id) {
+			Some(request) => request,
+			None => return,
+		};
+
+		if let Ok(response) = &result {
+			self.bandwidth.report_in(&request.protocol, response.len());
+		}
+
+		let event_result = result.as_ref().map(|_| ()).map_err(Clone::clone);
+		// The requester might have lost interest in the response in the meantime.
+		let _ = request.pending_response.send(result);
+
+		self.pending_events.push_back(NetworkBehaviourAction::GenerateEvent(Event::RequestFinished {
+			peer: request.peer,
+			protocol: request.protocol,
+			duration: request.started.elapsed(),
+			result: event_result,
+		}));
+	}
+
+	/// Callback, invoked when a new request has been received from remote.
+	fn on_request(
+		&mut self,
+		peer: PeerId,
+		protocol: Cow<'static, str>,
+		payload: Vec<u8>,
+		mut substream: NegotiatedSubstream,
+		started: Instant,
+	) {
+		self.bandwidth.report_in(&protocol, payload.len());
+
+		let inbound_queue = match self.protocols.get_mut(&protocol).and_then(|p| p.inbound_queue.as_mut()) {
+			Some(queue) => queue,
+			None => {
+				log::error!(
+					target: "sub-libp2p",
+					"State inconsistency: request on protocol {} without inbound queue",
+					protocol
+				);
+				return;
+			}
+		};
+
+		let (tx, rx) = oneshot::channel();
+		let incoming = IncomingRequest { peer: peer.clone(), payload, pending_response: tx };
+		if let Err(err) = inbound_queue.try_send(incoming) {
+			log::debug!(
+				target: "sub-libp2p",
+				"Declining request from {} on {}: queue {}",
+				peer,
+				protocol,
+				if err.is_full() { "full" } else { "closed" }
+			);
+			let result = Err(if err.is_full() { ResponseFailure::Busy } else { ResponseFailure::Refused });
+			self.pending_events.push_back(NetworkBehaviourAction::GenerateEvent(
+				Event::InboundRequest { peer, protocol, result }
+			));
+			return;
+		}
+
+		let bandwidth = self.bandwidth.clone();
+		self.pending_responses.push(async move {
+			let (result, reputation_changes) = match rx.await {
+				Ok(OutgoingResponse { result: Ok(response), reputation_changes }) => {
+					bandwidth.report_out(&protocol, response.len());
+					let result = write_one(&mut substream, response).await
+						.map(|()| started.elapsed())
+						.map_err(ResponseFailure::from);
+					(result, reputation_changes)
+				},
+				Ok(OutgoingResponse { result: Err(()), reputation_changes }) =>
+					(Err(ResponseFailure::Refused), reputation_changes),
+				Err(oneshot::Canceled) => (Err(ResponseFailure::Refused), Vec::new()),
+			};
+
+			let mut events = Vec::with_capacity(2);
+			if !reputation_changes.is_empty() {
+				events.push(Event::ReputationChanges { peer: peer.clone(), changes: reputation_changes });
+			}
+			events.push(Event::InboundRequest { peer, protocol, result });
+			events
+		}.boxed());
+	}
+}
+
+impl NetworkBehaviour for RequestResponsesBehaviour {
+	type ProtocolsHandler = OneShotHandler<InboundProtocol, OutboundProtocol, HandlerEvent>;
+	type OutEvent = Event;
+
+	fn new_handler(&mut self) -> Self::ProtocolsHandler {
+		let protocols = self.protocols.values()
+			.filter(|p| p.inbound_queue.is_some())
+			.map(|p| (
+				p.name.clone(),
+				usize::try_from(p.max_request_size).unwrap_or(usize::max_value()),
+			))
+			.collect();
+		let mut cfg = OneShotHandlerConfig::default();
+		cfg.substream_timeout = self.protocols.values()
+			.map(|p| p.request_timeout)
+			.max()
+			.unwrap_or(cfg.substream_timeout);
+		OneShotHandler::new(SubstreamProtocol::new(InboundProtocol { protocols }), cfg)
+	}
+
+	fn addresses_of_peer(&mut self, _: &PeerId) -> Vec<Multiaddr> {
+		Vec::new()
+	}
+
+	fn inject_connected(&mut self, _peer: &PeerId) {
+	}
+
+	fn inject_disconnected(&mut self, _peer: &PeerId) {
+	}
+
+	fn inject_connection_established(&mut self, peer_id: &PeerId, id: &ConnectionId, _: &ConnectedPoint) {
+		self.connections.entry(peer_id.clone()).or_default().push(*id);
+	}
+
+	fn inject_connection_closed(&mut self, peer_id: &PeerId, id: &ConnectionId, _: &ConnectedPoint) {
+		if let Entry::Occupied(mut entry) = self.connections.entry(peer_id.clone()) {
+			entry.get_mut().retain(|c| c != id);
+			if entry.get().is_empty() {
+				entry.remove();
+			}
+		} else {
+			log::error!(
+				target: "sub-libp2p",
+				"State inconsistency: peer_id not found in list of connections"
+			);
+		}
+
+		let closed = self.pending_requests.iter()
+			.filter(|(_, request)| request.peer == *peer_id && request.connection == *id)
+			.map(|(request_id, _)| *request_id)
+			.collect::<Vec<_>>();
+		for request_id in closed {
+			self.finish_request(request_id, Err(RequestFailure::ConnectionClosed));
+		}
+	}
+
+	fn inject_event(&mut self, peer: PeerId, _: ConnectionId, event: HandlerEvent) {
+		match event {
+			HandlerEvent::Request { protocol, payload, substream, started } =>
+				self.on_request(peer, protocol, payload, substream, started),
+			HandlerEvent::Response { request_id, response } => {
+				if self.pending_requests.contains_key(&request_id) {
+					self.finish_request(request_id, Ok(response));
+				} else {
+					// Requests are removed once they time out, so this can legitimately happen.
+					log::trace!(
+						target: "sub-libp2p",
+						"Response from {} discarded because it concerns an obsolete request",
+						peer
+					);
+				}
+			}
+		}
+	}
+
+	fn poll(&mut self, cx: &mut Context, _: &mut impl PollParameters)
+		-> Poll<NetworkBehaviourAction<OutboundProtocol, Event>>
+	{
+		// Check the request timeouts.
+		let timed_out = self.pending_requests.iter_mut()
+			.filter_map(|(request_id, request)| match Pin::new(&mut request.timeout).poll(cx) {
+				Poll::Ready(()) => Some(*request_id),
+				Poll::Pending => None,
+			})
+			.collect::<Vec<_>>();
+		for request_id in timed_out {
+			log::debug!(target: "sub-libp2p", "Request timeout: {:?}", request_id);
+			self.finish_request(request_id, Err(RequestFailure::Timeout));
+		}
+
+		while let Poll::Ready(Some(events)) = self.pending_responses.poll_next_unpin(cx) {
+			self.pending_events.extend(events.into_iter().map(NetworkBehaviourAction::GenerateEvent));
+		}
+
+		if let Some(ev) = self.pending_events.pop_front() {
+			return Poll::Ready(ev);
+		}
+
+		Poll::Pending
+	}
+}
+
+/// Output type of inbound and outbound substream upgrades.
+#[derive(Debug)]
+pub enum HandlerEvent {
+	/// Incoming request from remote, substream to use for the response, and when we started
+	/// handling this request.
+	Request {
+		protocol: Cow<'static, str>,
+		payload: Vec<u8>,
+		substream: NegotiatedSubstream,
+		started: Instant,
+	},
+	/// Incoming response from remote.
+	Response {
+		request_id: RequestId,
+		response: Vec<u8>,
+	},
+}
+
+/// Substream upgrade protocol.
+///
+/// Reads an incoming request on any of the protocols that have an inbound queue, which will be
+/// handed over to the queue by the `RequestResponsesBehaviour`.
+#[derive(Debug, Clone)]
+pub struct InboundProtocol {
+	/// The names of the protocols to accept, with their max. request length in bytes.
+	protocols: Vec<(Cow<'static, str>, usize)>,
+}
+
+impl UpgradeInfo for InboundProtocol {
+	type Info = ProtocolName;
+	type InfoIter = std::vec::IntoIter<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		self.protocols.iter().map(|(name, _)| ProtocolName(name.clone())).collect::<Vec<_>>().into_iter()
+	}
+}
+
+impl InboundUpgrade<NegotiatedSubstream> for InboundProtocol {
+	type Output = HandlerEvent;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_inbound(self, mut s: NegotiatedSubstream, info: Self::Info) -> Self::Future {
+		// This `Instant` will be passed around until the processing of this request is done.
+		let started = Instant::now();
+		let max_request_size = self.protocols.iter()
+			.find(|(name, _)| *name == info.0)
+			.map(|(_, max)| *max)
+			.unwrap_or(0);
+
+		async move {
+			let payload = read_one(&mut s, max_request_size).await?;
+			Ok(HandlerEvent::Request { protocol: info.0, payload, substream: s, started })
+		}.boxed()
+	}
+}
+
+/// Substream upgrade protocol.
+///
+/// Sends a request to remote and awaits the response.
+#[derive(Debug, Clone)]
+pub struct OutboundProtocol {
+	/// The protocol to use for upgrade negotiation.
+	protocol: Cow<'static, str>,
+	/// Identifier of the request, passed back with the response.
+	request_id: RequestId,
+	/// The request to send.
+	request: Vec<u8>,
+	/// The max. response length in bytes.
+	max_response_size: usize,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+	type Info = ProtocolName;
+	type InfoIter = iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		iter::once(ProtocolName(self.protocol.clone()))
+	}
+}
+
+impl OutboundUpgrade<NegotiatedSubstream> for OutboundProtocol {
+	type Output = HandlerEvent;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_outbound(self, mut s: NegotiatedSubstream, _: Self::Info) -> Self::Future {
+		async move {
+			write_one(&mut s, &self.request).await?;
+			let response = read_one(&mut s, self.max_response_size).await?;
+			Ok(HandlerEvent::Response { request_id: self.request_id, response })
+		}.boxed()
+	}
+}
+
+/// Name of a request-response protocol, as negotiated on the wire.
+#[derive(Debug, Clone)]
+pub struct ProtocolName(Cow<'static, str>);
+
+impl AsRef<[u8]> for ProtocolName {
+	fn as_ref(&self) -> &[u8] {
+		self.0.as_bytes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::{channel::{mpsc, oneshot}, executor::LocalPool, prelude::*, task::Spawn};
+	use libp2p::identity::Keypair;
+	use libp2p::Multiaddr;
+	use libp2p::core::upgrade;
+	use libp2p::core::transport::{Transport, MemoryTransport};
+	use libp2p::core::upgrade::{InboundUpgradeExt, OutboundUpgradeExt};
+	use libp2p::swarm::{Swarm, SwarmEvent};
+	use std::{iter, time::Duration};
+	use crate::protocol_bandwidth::ProtocolBandwidth;
+	use super::{Event, IncomingRequest, OutgoingResponse, ProtocolConfig, RequestResponsesBehaviour};
+
+	fn build_swarm(list: impl Iterator<Item = ProtocolConfig>) -> (Swarm<RequestResponsesBehaviour>, Multiaddr) {
+		let keypair = Keypair::generate_ed25519();
+		let keypair2 = keypair.clone();
+
+		let transport = MemoryTransport
+			.and_then(move |out, endpoint| {
+				let secio = libp2p::secio::SecioConfig::new(keypair2);
+				libp2p::core::upgrade::apply(
+					out,
+					secio,
+					endpoint,
+					upgrade::Version::V1
+				)
+			})
+			.and_then(move |(peer_id, stream), endpoint| {
+				let peer_id2 = peer_id.clone();
+				let upgrade = libp2p::yamux::Config::default()
+					.map_inbound(move |muxer| (peer_id, muxer))
+					.map_outbound(move |muxer| (peer_id2, muxer));
+				upgrade::apply(stream, upgrade, endpoint, upgrade::Version::V1)
+			});
+
+		let behaviour = RequestResponsesBehaviour::new(list, ProtocolBandwidth::default()).unwrap();
+
+		let mut swarm = Swarm::new(transport, behaviour, keypair.public().into_peer_id());
+		let listen_addr: Multiaddr = format!("/memory/{}", rand::random::<u64>()).parse().unwrap();
+
+		Swarm::listen_on(&mut swarm, listen_addr.clone()).unwrap();
+		(swarm, listen_addr)
+	}
+
+	#[test]
+	fn basic_request_response_works() {
+		let protocol_name = "/test/req-resp/1";
+		let mut pool = LocalPool::new();
+
+		// Build swarms whose behaviour is `RequestResponsesBehaviour`.
+		let mut swarms = (0..2)
+			.map(|_| {
+				let (tx, mut rx) = mpsc::channel::<IncomingRequest>(64);
+
+				pool.spawner().spawn_obj(async move {
+					while let Some(rq) = rx.next().await {
+						assert_eq!(rq.payload, b"this is a request");
+						let _ = rq.pending_response.send(OutgoingResponse {
+							result: Ok(b"this is a response".to_vec()),
+							reputation_changes: Vec::new(),
+						});
+					}
+				}.boxed().into()).unwrap();
+
+				let protocol_config = ProtocolConfig {
+					name: From::from(protocol_name),
+					max_request_size: 1024,
+					max_response_size: 1024 * 1024,
+					request_timeout: Duration::from_secs(30),
+					inbound_queue: Some(tx),
+				};
+
+				build_swarm(iter::once(protocol_config))
+			})
+			.collect::<Vec<_>>();
+
+		// Ask `swarm[0]` to dial `swarm[1]`. There isn't any discovery mechanism in place in
+		// this test, so they wouldn't connect to each other.
+		{
+			let dial_addr = swarms[1].1.clone();
+			Swarm::dial_addr(&mut swarms[0].0, dial_addr).unwrap();
+		}
+
+		// Running `swarm[0]` in the background.
+		pool.spawner().spawn_obj({
+			let (mut swarm, _) = swarms.remove(0);
+			async move {
+				loop {
+					match swarm.next_event().await {
+						SwarmEvent::Behaviour(Event::InboundRequest { result, .. }) => {
+							result.unwrap();
+						},
+						_ => {}
+					}
+				}
+			}.boxed().into()
+		}).unwrap();
+
+		// Remove and run the remaining swarm.
+		let (mut swarm, _) = swarms.remove(0);
+		pool.run_until(async move {
+			let mut response_receiver = None;
+
+			loop {
+				match swarm.next_event().await {
+					SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+						let (sender, receiver) = oneshot::channel();
+						swarm.send_request(&peer_id, protocol_name, b"this is a request".to_vec(), sender);
+						assert!(response_receiver.is_none());
+						response_receiver = Some(receiver);
+					}
+					SwarmEvent::Behaviour(Event::RequestFinished { result, .. }) => {
+						result.unwrap();
+						break;
+					}
+					_ => {}
+				}
+			}
+
+			assert_eq!(response_receiver.unwrap().await.unwrap().unwrap(), b"this is a response");
+		});
+	}
+}
@@ -36,10 +36,13 @@ use crate::{
 	},
 	on_demand_layer::AlwaysBadChecker,
 	light_client_handler, block_requests, finality_requests,
+	peer_store::{self, PeerStore, PeerStoreWriter, StoredPeer},
 	protocol::{self, event::Event, LegacyConnectionKillError, sync::SyncState, PeerInfo, Protocol},
-	transport, ReputationChange,
+	protocol_bandwidth::ProtocolBandwidth,
+	request_responses::RequestFailure,
+	transport, utils::interval, ReputationChange,
 };
-use futures::prelude::*;
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, stream::FuturesUnordered};
 use libp2p::{PeerId, Multiaddr};
 use libp2p::core::{ConnectedPoint, Executor, connection::{ConnectionError, PendingConnectionError}, either::EitherError};
 use libp2p::kad::record;
@@ -60,7 +63,7 @@ use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnbound
 use std::{
 	borrow::Cow,
 	collections::HashSet,
-	fs, io,
+	fs, io, iter,
 	marker::PhantomData,
 	pin::Pin,
 	str,
@@ -69,12 +72,17 @@ use std::{
 		Arc,
 	},
 	task::Poll,
+	time::Duration,
 };
+use wasm_timer::Instant;
 
 mod out_events;
 #[cfg(test)]
 mod tests;
 
+/// Interval at which the known peers are saved to the peer store.
+const PEER_STORE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
 /// Substrate network service. Handles network IO and manages connectivity.
 pub struct NetworkService<B: BlockT + 'static, H: ExHashT> {
 	/// Number of peers we're connected to.
@@ -110,6 +118,19 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			fs::create_dir_all(&path)?;
 		}
 
+		// Peers known from a previous run, with their reputation and addresses.
+		let stored_peers = match &params.peer_store {
+			Some(backend) => match PeerStore::load(&**backend) {
+				Ok(store) => store.restore(peer_store::unix_time()),
+				Err(err) => {
+					warn!(target: "sub-libp2p", "Failed to load the peer store: {}", err);
+					Vec::new()
+				}
+			},
+			None => Vec::new(),
+		};
+		let peer_store = params.peer_store.map(PeerStoreWriter::new).transpose()?;
+
 		// List of multiaddresses that we know in the network.
 		let mut known_addresses = Vec::new();
 		let mut bootnodes = Vec::new();
@@ -178,6 +199,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			bootnodes,
 			reserved_only: params.network_config.non_reserved_mode == NonReservedPeerMode::Deny,
 			priority_groups,
+			known_peers: stored_peers.iter()
+				.map(|(peer_id, reputation, _)| (peer_id.clone(), *reputation))
+				.collect(),
 		};
 
 		// Private and public keys configuration.
@@ -191,6 +215,10 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			Some(registry) => Some(Metrics::register(&registry)?),
 			None => None
 		};
+		let protocol_bandwidth = ProtocolBandwidth::new(
+			params.network_config.bandwidth_limits.clone(),
+			params.metrics_registry.as_ref(),
+		)?;
 
 		let checker = params.on_demand.as_ref()
 			.map(|od| od.checker().clone())
@@ -214,8 +242,13 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			boot_node_ids.clone(),
 			params.network_config.use_new_block_requests_protocol,
 			metrics.as_ref().map(|m| m.notifications_queues_size.clone()),
+			protocol_bandwidth.clone(),
 		)?;
 
+		// Handlers of the incoming requests of the built-in request-response protocols, polled by
+		// the worker.
+		let request_handlers = FuturesUnordered::new();
+
 		// Build the swarm.
 		let (mut swarm, bandwidth): (Swarm<B, H>, _) = {
 			let user_agent = format!(
@@ -224,12 +257,24 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				params.network_config.node_name
 			);
 			let block_requests = {
-				let config = block_requests::Config::new(&params.protocol_id);
-				block_requests::BlockRequests::new(config, params.chain.clone())
+				let (handler, config) = block_requests::BlockRequestHandler::new(
+					&params.protocol_id,
+					params.chain.clone(),
+				);
+				request_handlers.push(handler.run().boxed());
+				config
 			};
-			let finality_proof_requests = {
-				let config = finality_requests::Config::new(&params.protocol_id);
-				finality_requests::FinalityProofRequests::new(config, params.finality_proof_provider.clone())
+			let finality_proof_requests = match params.finality_proof_provider.clone() {
+				Some(provider) => {
+					let (handler, config) = finality_requests::FinalityProofRequestHandler::new(
+						&params.protocol_id,
+						provider,
+					);
+					request_handlers.push(handler.run().boxed());
+					config
+				}
+				// Without a provider, we only send finality proof requests.
+				None => finality_requests::generate_protocol_config(&params.protocol_id),
 			};
 			let light_client_handler = {
 				let config = light_client_handler::Config::new(&params.protocol_id);
@@ -244,6 +289,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			let discovery_config = {
 				let mut config = DiscoveryConfig::new(local_public.clone());
 				config.with_user_defined(known_addresses);
+				config.with_stored(stored_peers.into_iter().flat_map(|(peer_id, _, addresses)| {
+					addresses.into_iter().map(move |addr| (peer_id.clone(), addr))
+				}));
 				config.discovery_limit(u64::from(params.network_config.out_peers) + 15);
 				config.add_protocol(params.protocol_id.clone());
 				config.allow_non_globals_in_dht(params.network_config.allow_non_globals_in_dht);
@@ -262,16 +310,25 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				config
 			};
 
+			let request_response_protocols = params.network_config.request_response_protocols.iter()
+				.cloned()
+				.chain(iter::once(block_requests))
+				.chain(iter::once(finality_proof_requests))
+				.collect();
+
 			let mut behaviour = Behaviour::new(
 				protocol,
 				params.role,
 				user_agent,
 				local_public,
-				block_requests,
-				finality_proof_requests,
+				&params.protocol_id,
 				light_client_handler,
-				discovery_config
-			);
+				discovery_config,
+				request_response_protocols,
+				protocol_bandwidth.clone(),
+			).map_err(|crate::request_responses::RegisterError::DuplicateProtocol(protocol)| {
+				Error::DuplicateRequestResponseProtocol { protocol }
+			})?;
 
 			for (engine_id, protocol_name) in &params.network_config.notifications_protocols {
 				behaviour.register_notifications_protocol(*engine_id, protocol_name.clone());
@@ -335,6 +392,10 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			event_streams: out_events::OutChannels::new(params.metrics_registry.as_ref())?,
 			metrics,
 			boot_node_ids,
+			request_handlers,
+			protocol_bandwidth,
+			peer_store,
+			peer_store_interval: Box::pin(interval(PEER_STORE_INTERVAL)),
 		})
 	}
 
@@ -476,6 +537,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			external_addresses: Swarm::<B, H>::external_addresses(&swarm).cloned().collect(),
 			average_download_per_sec: self.service.bandwidth.average_download_per_sec(),
 			average_upload_per_sec: self.service.bandwidth.average_upload_per_sec(),
+			protocols_bandwidth: self.protocol_bandwidth.stats(),
 			connected_peers,
 			not_connected_peers,
 			peerset: swarm.user_protocol_mut().peerset_debug_info(),
@@ -543,6 +605,40 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		rx
 	}
 
+	/// Sends a single targeted request to a specific peer. On success, returns the response of
+	/// the peer.
+	///
+	/// The protocol must have been registered through
+	/// [`NetworkConfiguration::request_response_protocols`](crate::config::NetworkConfiguration::request_response_protocols).
+	///
+	/// Request-response protocols are a way to complement notifications protocols, but
+	/// notifications should remain the default ways of communicating information. For example, a
+	/// peer can announce something through a notification, after which the recipient can obtain
+	/// more information by performing a request.
+	/// As such, this function is meant to be called only with peers we are already connected to.
+	/// Calling this method with a `target` we are not connected to will *not* attempt to connect
+	/// to said peer.
+	pub async fn request(
+		&self,
+		target: PeerId,
+		protocol: impl Into<Cow<'static, str>>,
+		request: Vec<u8>,
+	) -> Result<Vec<u8>, RequestFailure> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Request {
+			target,
+			protocol: protocol.into(),
+			request,
+			pending_response: tx,
+		});
+
+		match rx.await {
+			Ok(result) => result,
+			// The networking has shut down before the request could be answered.
+			Err(oneshot::Canceled) => Err(RequestFailure::ConnectionClosed),
+		}
+	}
+
 	/// Registers a new notifications protocol.
 	///
 	/// After that, you can call `write_notifications`.
@@ -750,6 +846,12 @@ enum ServiceToWorkerMsg<B: BlockT, H: ExHashT> {
 	AddKnownAddress(PeerId, Multiaddr),
 	SyncFork(Vec<PeerId>, B::Hash, NumberFor<B>),
 	EventStream(out_events::Sender),
+	Request {
+		target: PeerId,
+		protocol: Cow<'static, str>,
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	},
 	WriteNotification {
 		message: Vec<u8>,
 		engine_id: ConsensusEngineId,
@@ -789,6 +891,48 @@ pub struct NetworkWorker<B: BlockT + 'static, H: ExHashT> {
 	metrics: Option<Metrics>,
 	/// The `PeerId`'s of all boot nodes.
 	boot_node_ids: Arc<HashSet<PeerId>>,
+	/// Handlers answering the incoming requests of the block and finality proof protocols.
+	request_handlers: FuturesUnordered<BoxFuture<'static, ()>>,
+	/// Bandwidth used by every protocol, shared with the behaviours.
+	protocol_bandwidth: ProtocolBandwidth,
+	/// Saves the known peers to the database, if any.
+	peer_store: Option<PeerStoreWriter>,
+	/// Stream that fires when the known peers must be saved.
+	peer_store_interval: Pin<Box<dyn Stream<Item = ()> + Send>>,
+}
+
+impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
+	/// Queues the peers known by the peerset manager, along with their addresses, to be saved to
+	/// the peer store.
+	fn save_peer_store(&mut self) {
+		let writer = match &self.peer_store {
+			Some(writer) => writer,
+			None => return,
+		};
+
+		let now = Instant::now();
+		let unix_now = peer_store::unix_time();
+		let swarm = &mut self.network_service;
+		let mut store = PeerStore::default();
+		for (peer_id, reputation, last_connected) in swarm.user_protocol_mut().peerset_known_peers() {
+			let addresses = NetworkBehaviour::addresses_of_peer(&mut **swarm, &peer_id);
+			let elapsed = if now > last_connected { (now - last_connected).as_secs() } else { 0 };
+			store.insert(&peer_id, StoredPeer {
+				addresses,
+				reputation,
+				sampled_at: unix_now,
+				last_seen: unix_now.saturating_sub(elapsed),
+			});
+		}
+		store.prune(unix_now);
+		writer.save(store);
+	}
+}
+
+impl<B: BlockT + 'static, H: ExHashT> Drop for NetworkWorker<B, H> {
+	fn drop(&mut self) {
+		self.save_peer_store();
+	}
 }
 
 struct Metrics {
@@ -1034,6 +1178,13 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 			protocol: &mut this.network_service,
 		});
 
+		// Answer the incoming requests of the built-in request-response protocols.
+		while let Poll::Ready(Some(())) = this.request_handlers.poll_next_unpin(cx) {}
+
+		while let Poll::Ready(Some(())) = this.peer_store_interval.poll_next_unpin(cx) {
+			this.save_peer_store();
+		}
+
 		// Check for new incoming light client requests.
 		if let Some(light_client_rqs) = this.light_client_rqs.as_mut() {
 			while let Poll::Ready(Some(rq)) = light_client_rqs.poll_next_unpin(cx) {
@@ -1074,6 +1225,8 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 					this.network_service.user_protocol_mut().set_sync_fork_request(peer_ids, &hash, number),
 				ServiceToWorkerMsg::EventStream(sender) =>
 					this.event_streams.push(sender),
+				ServiceToWorkerMsg::Request { target, protocol, request, pending_response } =>
+					this.network_service.send_request(&target, &protocol, request, pending_response),
 				ServiceToWorkerMsg::WriteNotification { message, engine_id, target } => {
 					if let Some(metrics) = this.metrics.as_ref() {
 						metrics.notifications_sizes
@@ -103,6 +103,7 @@ fn build_test_full_node(config: config::NetworkConfiguration)
 			sp_consensus::block_validation::DefaultBlockAnnounceValidator::new(client.clone()),
 		),
 		metrics_registry: None,
+		peer_store: None,
 	})
 	.unwrap();
 
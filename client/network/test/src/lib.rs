@@ -641,6 +641,7 @@ pub trait TestNetFactory: Sized {
 			import_queue,
 			block_announce_validator: Box::new(DefaultBlockAnnounceValidator::new(client.clone())),
 			metrics_registry: None,
+			peer_store: None,
 		}).unwrap();
 
 		self.mut_peers(|peers| {
@@ -719,6 +720,7 @@ pub trait TestNetFactory: Sized {
 			import_queue,
 			block_announce_validator: Box::new(DefaultBlockAnnounceValidator::new(client.clone())),
 			metrics_registry: None,
+			peer_store: None,
 		}).unwrap();
 
 		self.mut_peers(|peers| {
@@ -30,7 +30,7 @@ use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender, TracingUnbounded
 pub use libp2p::PeerId;
 
 /// We don't accept nodes whose reputation is under this value.
-const BANNED_THRESHOLD: i32 = 82 * (i32::min_value() / 100);
+pub const BANNED_THRESHOLD: i32 = 82 * (i32::min_value() / 100);
 /// Reputation change for a node when we get disconnected from it.
 const DISCONNECT_REPUTATION_CHANGE: i32 = -256;
 /// Reserved peers group ID
@@ -172,6 +172,37 @@ pub struct PeersetConfig {
 	/// > **Note**: Keep in mind that the networking has to know an address for these nodes,
 	/// >			otherwise it will not be able to connect to them.
 	pub priority_groups: Vec<(String, HashSet<PeerId>)>,
+
+	/// Nodes known from a previous run, with their reputation.
+	///
+	/// > **Note**: As with the bootnodes, the networking has to know an address for these nodes.
+	pub known_peers: Vec<(PeerId, i32)>,
+}
+
+/// Moves the reputation of a node towards zero, as happens every second.
+///
+/// If we multiply each second the reputation by `k` (where `k` is between 0 and 1), it takes
+/// `ln(0.5) / ln(k)` seconds to reduce the reputation by half. We use `k = 0.98`, so we divide by
+/// `50`. With that value, it takes 34.3 seconds to reduce the reputation by half.
+fn reput_tick(reput: i32) -> i32 {
+	let mut diff = reput / 50;
+	if diff == 0 && reput < 0 {
+		diff = -1;
+	} else if diff == 0 && reput > 0 {
+		diff = 1;
+	}
+	reput.saturating_sub(diff)
+}
+
+/// Returns the reputation of a node after `secs` seconds of decay.
+pub fn decay_reputation(mut reputation: i32, secs: u64) -> i32 {
+	for _ in 0..secs {
+		if reputation == 0 {
+			break
+		}
+		reputation = reput_tick(reputation);
+	}
+	reputation
 }
 
 /// Side of the peer set manager owned by the network. In other words, the "receiving" side.
@@ -228,6 +259,14 @@ impl Peerset {
 			}
 		}
 
+		for (peer_id, reputation) in config.known_peers {
+			match peerset.data.peer(&peer_id) {
+				peersstate::Peer::Unknown(entry) => entry.discover().set_reputation(reputation),
+				peersstate::Peer::NotConnected(mut entry) => entry.set_reputation(reputation),
+				peersstate::Peer::Connected(_) => {},
+			}
+		}
+
 		peerset.alloc_slots();
 		(peerset, handle)
 	}
@@ -329,22 +368,8 @@ impl Peerset {
 		};
 
 		// For each elapsed second, move the node reputation towards zero.
-		// If we multiply each second the reputation by `k` (where `k` is between 0 and 1), it
-		// takes `ln(0.5) / ln(k)` seconds to reduce the reputation by half. Use this formula to
-		// empirically determine a value of `k` that looks correct.
 		for _ in 0..secs_diff {
 			for peer_id in self.data.peers().cloned().collect::<Vec<_>>() {
-				// We use `k = 0.98`, so we divide by `50`. With that value, it takes 34.3 seconds
-				// to reduce the reputation by half.
-				fn reput_tick(reput: i32) -> i32 {
-					let mut diff = reput / 50;
-					if diff == 0 && reput < 0 {
-						diff = -1;
-					} else if diff == 0 && reput > 0 {
-						diff = 1;
-					}
-					reput.saturating_sub(diff)
-				}
 				match self.data.peer(&peer_id) {
 					peersstate::Peer::Connected(mut peer) => {
 						let before = peer.reputation();
@@ -522,6 +547,25 @@ impl Peerset {
 		})
 	}
 
+	/// Returns the list of all the nodes we know of, with their reputation and when we were last
+	/// connected to them. The latter is now for the nodes we are connected to.
+	pub fn known_peers(&mut self) -> Vec<(PeerId, i32, Instant)> {
+		self.update_time();
+
+		let now = Instant::now();
+		self.data.peers().cloned().collect::<Vec<_>>().into_iter().map(|peer_id| {
+			match self.data.peer(&peer_id) {
+				peersstate::Peer::Connected(entry) => (peer_id, entry.reputation(), now),
+				peersstate::Peer::NotConnected(entry) => {
+					let last_connected = entry.last_connected_or_discovered();
+					(peer_id, entry.reputation(), last_connected)
+				}
+				peersstate::Peer::Unknown(_) =>
+					unreachable!("We iterate over the known peers; QED")
+			}
+		}).collect()
+	}
+
 	/// Returns the number of peers that we have discovered.
 	pub fn num_discovered_peers(&self) -> usize {
 		self.data.peers().len()
@@ -602,6 +646,7 @@ mod tests {
 			bootnodes: vec![bootnode],
 			reserved_only: true,
 			priority_groups: Vec::new(),
+			known_peers: Vec::new(),
 		};
 
 		let (peerset, handle) = Peerset::from_config(config);
@@ -630,6 +675,7 @@ mod tests {
 			bootnodes: vec![bootnode.clone()],
 			reserved_only: false,
 			priority_groups: Vec::new(),
+			known_peers: Vec::new(),
 		};
 
 		let (mut peerset, _handle) = Peerset::from_config(config);
@@ -657,6 +703,7 @@ mod tests {
 			bootnodes: vec![bootnode.clone()],
 			reserved_only: false,
 			priority_groups: vec![],
+			known_peers: Vec::new(),
 		};
 
 		let (mut peerset, _handle) = Peerset::from_config(config);
@@ -678,6 +725,7 @@ mod tests {
 			bootnodes: vec![],
 			reserved_only: false,
 			priority_groups: vec![],
+			known_peers: Vec::new(),
 		});
 
 		// We ban a node by setting its reputation under the threshold.
@@ -710,5 +758,41 @@ mod tests {
 
 		futures::executor::block_on(fut);
 	}
+
+	#[test]
+	fn test_peerset_known_peers_restored() {
+		let banned = PeerId::random();
+		let trusted = PeerId::random();
+		let (mut peerset, _handle) = Peerset::from_config(PeersetConfig {
+			in_peers: 25,
+			out_peers: 25,
+			bootnodes: vec![],
+			reserved_only: false,
+			priority_groups: vec![],
+			known_peers: vec![(banned.clone(), BANNED_THRESHOLD - 1), (trusted.clone(), 1000)],
+		});
+
+		// The banned node is neither dialed nor accepted.
+		peerset.incoming(banned.clone(), IncomingIndex(1));
+		assert_eq!(peerset.message_queue.iter().collect::<Vec<_>>(), vec![
+			&Message::Connect(trusted.clone()),
+			&Message::Reject(IncomingIndex(1)),
+		]);
+
+		let known = peerset.known_peers();
+		assert_eq!(known.len(), 2);
+		assert!(known.iter().any(|(p, r, _)| *p == banned && *r < BANNED_THRESHOLD));
+		assert!(known.iter().any(|(p, r, _)| *p == trusted && *r > 0));
+	}
+
+	#[test]
+	fn decay_reputation_moves_towards_zero() {
+		assert_eq!(decay_reputation(0, 100), 0);
+		assert_eq!(decay_reputation(1000, 0), 1000);
+		assert_eq!(decay_reputation(1000, 1), 980);
+		assert_eq!(decay_reputation(-1000, 1), -980);
+		assert_eq!(decay_reputation(i32::min_value(), 3600), 0);
+		assert!(decay_reputation(BANNED_THRESHOLD - 1, 20) > BANNED_THRESHOLD);
+	}
 }
 
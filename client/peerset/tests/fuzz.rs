@@ -55,6 +55,7 @@ fn test_once() {
 		reserved_only: Uniform::new_inclusive(0, 10).sample(&mut rng) == 0,
 		in_peers: Uniform::new_inclusive(0, 25).sample(&mut rng),
 		out_peers: Uniform::new_inclusive(0, 25).sample(&mut rng),
+		known_peers: Vec::new(),
 	});
 
 	futures::executor::block_on(futures::future::poll_fn(move |cx| {
@@ -18,12 +18,14 @@
 
 pub mod error;
 pub mod hash;
+pub mod outcome;
 
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use sp_core::Bytes;
 use sp_transaction_pool::TransactionStatus;
 use self::error::{FutureResult, Result};
+use self::outcome::ExtrinsicStatus;
 
 pub use self::gen_client::Client as AuthorClient;
 
@@ -99,4 +101,30 @@ pub trait AuthorApi<Hash, BlockHash> {
 		metadata: Option<Self::Metadata>,
 		id: SubscriptionId
 	) -> Result<bool>;
+
+	/// Submit an extrinsic to watch, along with the outcome of its dispatch.
+	///
+	/// Sends the same statuses as `author_submitAndWatchExtrinsic`. If the node resolves dispatch
+	/// outcomes, the `inBlock` status is followed by the outcome of the dispatch of the extrinsic.
+	#[pubsub(
+		subscription = "author_extrinsicOutcomeUpdate",
+		subscribe,
+		name = "author_submitAndWatchExtrinsicWithOutcome"
+	)]
+	fn watch_extrinsic_with_outcome(&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<ExtrinsicStatus<Hash, BlockHash>>,
+		bytes: Bytes
+	);
+
+	/// Unsubscribe from extrinsic watching with dispatch outcomes.
+	#[pubsub(
+		subscription = "author_extrinsicOutcomeUpdate",
+		unsubscribe,
+		name = "author_unwatchExtrinsicWithOutcome"
+	)]
+	fn unwatch_extrinsic_with_outcome(&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId
+	) -> Result<bool>;
 }
@@ -0,0 +1,50 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dispatch outcomes of the extrinsics watched through the author RPC module.
+
+use serde::{Serialize, Deserialize};
+use sp_runtime::traits::Block as BlockT;
+use sp_transaction_pool::TransactionStatus;
+
+/// A notification of `author_submitAndWatchExtrinsicWithOutcome`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExtrinsicStatus<Hash, BlockHash> {
+	/// The status of the extrinsic in the transaction pool.
+	Status(TransactionStatus<Hash, BlockHash>),
+	/// The outcome of the dispatch of the extrinsic, sent right after its `inBlock` status.
+	Dispatched {
+		/// The block the extrinsic was included in.
+		dispatched: BlockHash,
+		/// The outcome of the dispatch, as given by the `ExtrinsicOutcomeResolver`.
+		outcome: serde_json::Value,
+	},
+}
+
+/// Resolves the outcome of the dispatch of an extrinsic included in a block.
+///
+/// The dispatch outcome is only known by the runtime, so the node provides the resolver to the
+/// author RPC module.
+pub trait ExtrinsicOutcomeResolver<Block: BlockT>: Send + Sync {
+	/// Returns the outcome of the dispatch of `extrinsic` in the block with the given hash, or
+	/// `None` if it could not be resolved.
+	fn extrinsic_outcome(
+		&self,
+		block: &Block::Hash,
+		extrinsic: &Block::Extrinsic,
+	) -> Option<serde_json::Value>;
+}
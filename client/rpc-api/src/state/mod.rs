@@ -26,11 +26,15 @@ use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use sp_core::Bytes;
 use sp_core::storage::{StorageKey, StorageData, StorageChangeSet};
 use sp_version::RuntimeVersion;
+use sp_rpc::tracing::BlockTrace;
 use self::error::FutureResult;
 
 pub use self::gen_client::Client as StateClient;
 pub use self::helpers::ReadProof;
 
+/// The targets traced by `state_traceBlock` if none are given.
+pub const DEFAULT_TRACE_TARGETS: &str = "pallet,frame,state,wasm_tracing";
+
 /// Substrate state API
 #[rpc]
 pub trait StateApi<Hash> {
@@ -78,6 +82,36 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getMetadata")]
 	fn metadata(&self, hash: Option<Hash>) -> FutureResult<Bytes>;
 
+	/// Returns the runtime metadata at the given metadata version as an opaque blob.
+	///
+	/// Returns `None` if the runtime does not support this metadata version.
+	#[rpc(name = "state_getMetadataAtVersion")]
+	fn metadata_at_version(&self, version: u32, hash: Option<Hash>) -> FutureResult<Option<Bytes>>;
+
+	/// Returns the metadata versions supported by the runtime.
+	#[rpc(name = "state_getMetadataVersions")]
+	fn metadata_versions(&self, hash: Option<Hash>) -> FutureResult<Vec<u32>>;
+
+	/// Returns the metadata of a pallet constant, along with the types it refers to, as an
+	/// opaque blob.
+	#[rpc(name = "state_getConstantMetadata")]
+	fn constant_metadata(
+		&self,
+		pallet: String,
+		name: String,
+		hash: Option<Hash>
+	) -> FutureResult<Option<Bytes>>;
+
+	/// Returns the metadata of a pallet storage entry, along with the types it refers to, as an
+	/// opaque blob.
+	#[rpc(name = "state_getStorageEntryMetadata")]
+	fn storage_entry_metadata(
+		&self,
+		pallet: String,
+		name: String,
+		hash: Option<Hash>
+	) -> FutureResult<Option<Bytes>>;
+
 	/// Get the runtime version.
 	#[rpc(name = "state_getRuntimeVersion", alias("chain_getRuntimeVersion"))]
 	fn runtime_version(&self, hash: Option<Hash>) -> FutureResult<RuntimeVersion>;
@@ -106,6 +140,13 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getReadProof")]
 	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> FutureResult<ReadProof<Hash>>;
 
+	/// Re-execute the given block with tracing enabled for the given comma separated `targets`.
+	///
+	/// Returns the recorded spans with their timings, the events and all storage accesses. If no
+	/// targets are given, `DEFAULT_TRACE_TARGETS` are traced. This method is unsafe.
+	#[rpc(name = "state_traceBlock")]
+	fn trace_block(&self, block: Hash, targets: Option<String>) -> FutureResult<BlockTrace>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
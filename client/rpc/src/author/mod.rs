@@ -35,7 +35,7 @@ use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use codec::{Encode, Decode};
 use sp_core::{Bytes, traits::BareCryptoStorePtr};
 use sp_api::ProvideRuntimeApi;
-use sp_runtime::generic;
+use sp_runtime::{generic, traits::Block as BlockT};
 use sp_transaction_pool::{
 	TransactionPool, InPoolTransaction, TransactionStatus, TransactionSource,
 	BlockHash, TxHash, TransactionFor, error::IntoPoolError,
@@ -45,9 +45,10 @@ use sp_session::SessionKeys;
 /// Re-export the API for backward compatibility.
 pub use sc_rpc_api::author::*;
 use self::error::{Error, FutureResult, Result};
+use self::outcome::{ExtrinsicStatus, ExtrinsicOutcomeResolver};
 
 /// Authoring API
-pub struct Author<P, Client> {
+pub struct Author<P: TransactionPool, Client> {
 	/// Substrate client
 	client: Arc<Client>,
 	/// Transactions pool
@@ -58,9 +59,11 @@ pub struct Author<P, Client> {
 	keystore: BareCryptoStorePtr,
 	/// Whether to deny unsafe calls
 	deny_unsafe: DenyUnsafe,
+	/// Resolves the dispatch outcomes of the watched extrinsics
+	outcome_resolver: Option<Arc<dyn ExtrinsicOutcomeResolver<P::Block>>>,
 }
 
-impl<P, Client> Author<P, Client> {
+impl<P: TransactionPool, Client> Author<P, Client> {
 	/// Create new instance of Authoring API.
 	pub fn new(
 		client: Arc<Client>,
@@ -75,8 +78,19 @@ impl<P, Client> Author<P, Client> {
 			subscriptions,
 			keystore,
 			deny_unsafe,
+			outcome_resolver: None,
 		}
 	}
+
+	/// Sends the dispatch outcome of the extrinsics watched with their outcome, as resolved by the
+	/// given resolver, once they are included in a block.
+	pub fn with_outcome_resolver(
+		mut self,
+		outcome_resolver: Arc<dyn ExtrinsicOutcomeResolver<P::Block>>,
+	) -> Self {
+		self.outcome_resolver = Some(outcome_resolver);
+		self
+	}
 }
 
 
@@ -236,4 +250,89 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 	fn unwatch_extrinsic(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
 		Ok(self.subscriptions.cancel(id))
 	}
+
+	fn watch_extrinsic_with_outcome(&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<ExtrinsicStatus<TxHash<P>, BlockHash<P>>>,
+		xt: Bytes,
+	) {
+		let submit = || -> Result<_> {
+			let best_block_hash = self.client.info().best_hash;
+			let dxt = TransactionFor::<P>::decode(&mut &xt[..])
+				.map_err(error::Error::from)?;
+			let extrinsic = dxt.clone();
+			Ok(
+				self.pool
+					.submit_and_watch(&generic::BlockId::hash(best_block_hash), TX_SOURCE, dxt)
+					.map_err(|e| e.into_pool_error()
+						.map(error::Error::from)
+						.unwrap_or_else(|e| error::Error::Verification(Box::new(e)).into())
+					)
+					.map_ok(move |watcher| (extrinsic, watcher))
+			)
+		};
+
+		let subscriptions = self.subscriptions.clone();
+		let outcome_resolver = self.outcome_resolver.clone();
+		let future = ready(submit())
+			.and_then(|res| res)
+			// convert the watcher into a `Stream` of notifications
+			.map(move |res| res.map(move |(extrinsic, stream)| stream
+				.flat_map(move |status| futures::stream::iter(extrinsic_statuses(
+					outcome_resolver.as_deref(),
+					&extrinsic,
+					status,
+				)))
+				.map(|v| Ok::<_, ()>(Ok(v)))
+			))
+			// now handle the import result,
+			// start a new subscrition
+			.map(move |result| match result {
+				Ok(watcher) => {
+					subscriptions.add(subscriber, move |sink| {
+						sink
+							.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+							.send_all(Compat::new(watcher))
+							.map(|_| ())
+					});
+				},
+				Err(err) => {
+					warn!("Failed to submit extrinsic: {}", err);
+					// reject the subscriber (ignore errors - we don't care if subscriber is no
+					// longer there).
+					let _ = subscriber.reject(err.into());
+				},
+			});
+
+		let res = self.subscriptions.executor()
+			.execute(Box::new(Compat::new(future.map(|_| Ok(())))));
+		if res.is_err() {
+			warn!("Error spawning subscription RPC task.");
+		}
+	}
+
+	fn unwatch_extrinsic_with_outcome(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
+
+/// Returns the notifications sent for the given status of a watched extrinsic, which are
+/// followed by the dispatch outcome of the extrinsic once it is included in a block.
+fn extrinsic_statuses<Block: BlockT, Hash>(
+	outcome_resolver: Option<&dyn ExtrinsicOutcomeResolver<Block>>,
+	extrinsic: &Block::Extrinsic,
+	status: TransactionStatus<Hash, Block::Hash>,
+) -> Vec<ExtrinsicStatus<Hash, Block::Hash>> {
+	let outcome = match (&status, outcome_resolver) {
+		(TransactionStatus::InBlock(block), Some(outcome_resolver)) => outcome_resolver
+			.extrinsic_outcome(block, extrinsic)
+			.map(|outcome| ExtrinsicStatus::Dispatched { dispatched: *block, outcome }),
+		_ => None,
+	};
+
+	std::iter::once(ExtrinsicStatus::Status(status)).chain(outcome).collect()
 }
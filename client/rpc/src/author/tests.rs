@@ -84,6 +84,7 @@ impl TestSetup {
 			subscriptions: Subscriptions::new(Arc::new(self.runtime.executor())),
 			keystore: self.keystore.clone(),
 			deny_unsafe: DenyUnsafe::No,
+			outcome_resolver: None,
 		}
 	}
 }
@@ -154,6 +155,49 @@ fn should_watch_extrinsic() {
 	);
 }
 
+#[test]
+fn should_watch_extrinsic_with_outcome() {
+	let mut setup = TestSetup::default();
+	let p = setup.author();
+
+	let (subscriber, id_rx, data) = jsonrpc_pubsub::typed::Subscriber::new_test("test");
+	let xt = uxt(AccountKeyring::Alice, 0).encode().into();
+	p.watch_extrinsic_with_outcome(Default::default(), subscriber, xt);
+
+	// the statuses are sent as they are by `author_submitAndWatchExtrinsic`
+	let ready = r#"{"jsonrpc":"2.0","method":"test","params":{"result":"ready","subscription":1}}"#;
+	assert_eq!(setup.runtime.block_on(id_rx), Ok(Ok(1.into())));
+	assert_eq!(setup.runtime.block_on(data.into_future()).unwrap().0, Some(ready.into()));
+}
+
+#[test]
+fn should_send_dispatch_outcome_after_in_block_status() {
+	struct Resolver;
+
+	impl ExtrinsicOutcomeResolver<Block> for Resolver {
+		fn extrinsic_outcome(&self, block: &H256, _: &Extrinsic) -> Option<serde_json::Value> {
+			Some(serde_json::json!({ "success": format!("{:?}", block) }))
+		}
+	}
+
+	let extrinsic = uxt(AccountKeyring::Alice, 0);
+	let block = H256::repeat_byte(1);
+	let statuses = |status| extrinsic_statuses::<Block, H256>(Some(&Resolver), &extrinsic, status);
+
+	assert_eq!(
+		statuses(TransactionStatus::Ready),
+		vec![ExtrinsicStatus::Status(TransactionStatus::Ready)],
+	);
+	let in_block = statuses(TransactionStatus::InBlock(block));
+	assert_eq!(
+		serde_json::to_string(&in_block).unwrap(),
+		format!(
+			r#"[{{"inBlock":"{:?}"}},{{"dispatched":"{:?}","outcome":{{"success":"{:?}"}}}}]"#,
+			block, block, block,
+		),
+	);
+}
+
 #[test]
 fn should_return_watch_validation_error() {
 	//given
@@ -26,10 +26,11 @@ use std::sync::Arc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use rpc::{Result as RpcResult, futures::{Future, future::result}};
 
-use sc_rpc_api::{Subscriptions, state::ReadProof};
+use sc_rpc_api::{DenyUnsafe, Subscriptions, state::ReadProof};
 use sc_client_api::light::{RemoteBlockchain, Fetcher};
 use sp_core::{Bytes, storage::{StorageKey, PrefixedStorageKey, StorageData, StorageChangeSet}};
 use sp_version::RuntimeVersion;
+use sp_rpc::tracing::BlockTrace;
 use sp_runtime::traits::Block as BlockT;
 
 use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
@@ -38,7 +39,7 @@ use self::error::{Error, FutureResult};
 
 pub use sc_rpc_api::state::*;
 pub use sc_rpc_api::child_state::*;
-use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend, ProofProvider};
+use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend, ProofProvider, BlockBackend};
 use sp_blockchain::{HeaderMetadata, HeaderBackend};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
@@ -107,6 +108,32 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 	/// Returns the runtime metadata as an opaque blob.
 	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes>;
 
+	/// Returns the runtime metadata at the given metadata version as an opaque blob.
+	fn metadata_at_version(
+		&self,
+		block: Option<Block::Hash>,
+		version: u32,
+	) -> FutureResult<Option<Bytes>>;
+
+	/// Returns the metadata versions supported by the runtime.
+	fn metadata_versions(&self, block: Option<Block::Hash>) -> FutureResult<Vec<u32>>;
+
+	/// Returns the metadata of a pallet constant as an opaque blob.
+	fn constant_metadata(
+		&self,
+		block: Option<Block::Hash>,
+		pallet: String,
+		name: String,
+	) -> FutureResult<Option<Bytes>>;
+
+	/// Returns the metadata of a pallet storage entry as an opaque blob.
+	fn storage_entry_metadata(
+		&self,
+		block: Option<Block::Hash>,
+		pallet: String,
+		name: String,
+	) -> FutureResult<Option<Bytes>>;
+
 	/// Get the runtime version.
 	fn runtime_version(&self, block: Option<Block::Hash>) -> FutureResult<RuntimeVersion>;
 
@@ -135,6 +162,13 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		keys: Vec<StorageKey>,
 	) -> FutureResult<ReadProof<Block::Hash>>;
 
+	/// Re-execute the given block, tracing the given comma separated `targets`.
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: String,
+	) -> FutureResult<BlockTrace>;
+
 	/// New runtime version subscription
 	fn subscribe_runtime_version(
 		&self,
@@ -169,6 +203,7 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 pub fn new_full<BE, Block: BlockT, Client>(
 	client: Arc<Client>,
 	subscriptions: Subscriptions,
+	deny_unsafe: DenyUnsafe,
 ) -> (State<Block, Client>, ChildState<Block, Client>)
 	where
 		Block: BlockT + 'static,
@@ -176,14 +211,14 @@ pub fn new_full<BE, Block: BlockT, Client>(
 		Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 			+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
 			+ CallApiAt<Block, Error = sp_blockchain::Error>
-			+ ProvideRuntimeApi<Block> + Send + Sync + 'static,
+			+ ProvideRuntimeApi<Block> + BlockBackend<Block> + Send + Sync + 'static,
 		Client::Api: Metadata<Block, Error = sp_blockchain::Error>,
 {
 	let child_backend = Box::new(
 		self::state_full::FullState::new(client.clone(), subscriptions.clone())
 	);
 	let backend = Box::new(self::state_full::FullState::new(client, subscriptions));
-	(State { backend }, ChildState { backend: child_backend })
+	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
 }
 
 /// Create new state API that works on light node.
@@ -192,6 +227,7 @@ pub fn new_light<BE, Block: BlockT, Client, F: Fetcher<Block>>(
 	subscriptions: Subscriptions,
 	remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
 	fetcher: Arc<F>,
+	deny_unsafe: DenyUnsafe,
 ) -> (State<Block, Client>, ChildState<Block, Client>)
 	where
 		Block: BlockT + 'static,
@@ -215,12 +251,14 @@ pub fn new_light<BE, Block: BlockT, Client, F: Fetcher<Block>>(
 			remote_blockchain,
 			fetcher,
 	));
-	(State { backend }, ChildState { backend: child_backend })
+	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
 }
 
 /// State API with subscriptions support.
 pub struct State<Block, Client> {
 	backend: Box<dyn StateBackend<Block, Client>>,
+	/// Whether to deny unsafe calls
+	deny_unsafe: DenyUnsafe,
 }
 
 impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
@@ -284,6 +322,36 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 		self.backend.metadata(block)
 	}
 
+	fn metadata_at_version(
+		&self,
+		version: u32,
+		block: Option<Block::Hash>,
+	) -> FutureResult<Option<Bytes>> {
+		self.backend.metadata_at_version(block, version)
+	}
+
+	fn metadata_versions(&self, block: Option<Block::Hash>) -> FutureResult<Vec<u32>> {
+		self.backend.metadata_versions(block)
+	}
+
+	fn constant_metadata(
+		&self,
+		pallet: String,
+		name: String,
+		block: Option<Block::Hash>,
+	) -> FutureResult<Option<Bytes>> {
+		self.backend.constant_metadata(block, pallet, name)
+	}
+
+	fn storage_entry_metadata(
+		&self,
+		pallet: String,
+		name: String,
+		block: Option<Block::Hash>,
+	) -> FutureResult<Option<Bytes>> {
+		self.backend.storage_entry_metadata(block, pallet, name)
+	}
+
 	fn query_storage(
 		&self,
 		keys: Vec<StorageKey>,
@@ -305,6 +373,15 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 		self.backend.read_proof(block, keys)
 	}
 
+	fn trace_block(&self, block: Block::Hash, targets: Option<String>) -> FutureResult<BlockTrace> {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return Box::new(result(Err(err.into())));
+		}
+
+		let targets = targets.unwrap_or_else(|| DEFAULT_TRACE_TARGETS.to_string());
+		self.backend.trace_block(block, targets)
+	}
+
 	fn subscribe_storage(
 		&self,
 		meta: Self::Metadata,
@@ -34,14 +34,17 @@ use sp_core::{
 };
 use sp_version::RuntimeVersion;
 use sp_runtime::{
-	generic::BlockId, traits::{Block as BlockT, NumberFor, SaturatedConversion, CheckedSub},
+	generic::BlockId, traits::{Block as BlockT, Header as HeaderT, NumberFor, SaturatedConversion, CheckedSub},
 };
+use sp_rpc::tracing::BlockTrace;
+use sp_state_machine::ExecutionStrategy;
+use codec::{Decode, Encode};
 
-use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
+use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt, ApiExt};
 
 use super::{StateBackend, ChildStateBackend, error::{FutureResult, Error, Result}, client_err};
 use std::marker::PhantomData;
-use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider};
+use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider, BlockBackend};
 
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
@@ -222,7 +225,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
 		+ CallApiAt<Block, Error = sp_blockchain::Error> + ProvideRuntimeApi<Block>
-		+ Send + Sync + 'static,
+		+ BlockBackend<Block> + Send + Sync + 'static,
 	Client::Api: Metadata<Block, Error = sp_blockchain::Error>,
 {
 	fn call(
@@ -318,6 +321,74 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 				.map_err(client_err)))
 	}
 
+	fn metadata_at_version(
+		&self,
+		block: Option<Block::Hash>,
+		version: u32,
+	) -> FutureResult<Option<Bytes>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.map_err(client_err)
+				.and_then(|block| {
+					let block = BlockId::Hash(block);
+					let api = self.client.runtime_api();
+					ensure_metadata_api_v2(&*api, &block)?;
+					api.metadata_at_version(&block, version)
+						.map(|metadata| metadata.map(Into::into))
+						.map_err(client_err)
+				})))
+	}
+
+	fn metadata_versions(&self, block: Option<Block::Hash>) -> FutureResult<Vec<u32>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.map_err(client_err)
+				.and_then(|block| {
+					let block = BlockId::Hash(block);
+					let api = self.client.runtime_api();
+					ensure_metadata_api_v2(&*api, &block)?;
+					api.metadata_versions(&block).map_err(client_err)
+				})))
+	}
+
+	fn constant_metadata(
+		&self,
+		block: Option<Block::Hash>,
+		pallet: String,
+		name: String,
+	) -> FutureResult<Option<Bytes>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.map_err(client_err)
+				.and_then(|block| {
+					let block = BlockId::Hash(block);
+					let api = self.client.runtime_api();
+					ensure_metadata_api_v2(&*api, &block)?;
+					api.constant(&block, pallet.into_bytes(), name.into_bytes())
+						.map(|metadata| metadata.map(Into::into))
+						.map_err(client_err)
+				})))
+	}
+
+	fn storage_entry_metadata(
+		&self,
+		block: Option<Block::Hash>,
+		pallet: String,
+		name: String,
+	) -> FutureResult<Option<Bytes>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.map_err(client_err)
+				.and_then(|block| {
+					let block = BlockId::Hash(block);
+					let api = self.client.runtime_api();
+					ensure_metadata_api_v2(&*api, &block)?;
+					api.storage_entry(&block, pallet.into_bytes(), name.into_bytes())
+						.map(|metadata| metadata.map(Into::into))
+						.map_err(client_err)
+				})))
+	}
+
 	fn runtime_version(&self, block: Option<Block::Hash>) -> FutureResult<RuntimeVersion> {
 		Box::new(result(
 			self.block_or_best(block)
@@ -371,6 +442,47 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		))
 	}
 
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: String,
+	) -> FutureResult<BlockTrace> {
+		let call_fn = move || {
+			let block = self.client.block(&BlockId::Hash(block))?
+				.ok_or_else(|| ClientError::UnknownBlock(format!("{}", block)))?
+				.block;
+			let block_hash = block.header().hash();
+			let parent_hash = *block.header().parent_hash();
+			let block = strip_seal(block);
+
+			// The block is executed natively where possible, as the events of the wasm runtime
+			// are not recorded.
+			let (execution, data) = sc_tracing::block::trace_execution(&targets, || {
+				self.client.executor().call(
+					&BlockId::Hash(parent_hash),
+					"Core_execute_block",
+					&block.encode(),
+					ExecutionStrategy::NativeWhenPossible,
+					None,
+				)
+			});
+			<()>::decode(&mut &execution?[..]).map_err(|e| ClientError::CallResultDecode(
+				"failed to decode the result of Core_execute_block",
+				e,
+			))?;
+
+			Ok(BlockTrace {
+				block_hash: format!("{:?}", block_hash),
+				parent_hash: format!("{:?}", parent_hash),
+				tracing_targets: targets,
+				spans: data.spans,
+				events: data.events,
+				storage: data.storage,
+			})
+		};
+		Box::new(result(call_fn().map_err(client_err)))
+	}
+
 	fn subscribe_runtime_version(
 		&self,
 		_meta: crate::metadata::Metadata,
@@ -567,6 +679,18 @@ impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block
 	}
 }
 
+/// Remove the seal from the given imported `block`.
+///
+/// The seal is added by the consensus engine after the block was built, so the runtime does not
+/// expect it in the digest when the block is executed again.
+pub(crate) fn strip_seal<Block: BlockT>(block: Block) -> Block {
+	let (mut header, extrinsics) = block.deconstruct();
+	if header.digest().logs().last().map_or(false, |item| item.as_seal().is_some()) {
+		header.digest_mut().pop();
+	}
+	Block::new(header, extrinsics)
+}
+
 /// Splits passed range into two subranges where:
 /// - first range has at least one element in it;
 /// - second range (optionally) starts at given `middle` element.
@@ -613,3 +737,20 @@ fn invalid_block<B: BlockT>(
 		details,
 	}
 }
+
+/// Checks that the runtime supports the metadata lookups added in version 2 of the `Metadata`
+/// api.
+fn ensure_metadata_api_v2<B, Api>(api: &Api, block: &BlockId<B>) -> Result<()> where
+	B: BlockT,
+	Api: Metadata<B, Error = sp_blockchain::Error>,
+{
+	let supported = api
+		.has_api_with::<dyn Metadata<B, Error = sp_blockchain::Error>, _>(block, |v| v >= 2)
+		.map_err(client_err)?;
+
+	if supported {
+		Ok(())
+	} else {
+		Err(Error::MetadataApiUnsupported)
+	}
+}
@@ -20,7 +20,7 @@ use std::{
 	sync::Arc,
 	collections::{HashSet, HashMap, hash_map::Entry},
 };
-use codec::Decode;
+use codec::{Encode, Decode};
 use futures::{
 	future::{ready, Either},
 	channel::oneshot::{channel, Sender},
@@ -43,7 +43,7 @@ use sp_blockchain::{Error as ClientError, HeaderBackend};
 use sc_client_api::{
 	BlockchainEvents,
 	light::{
-		RemoteCallRequest, RemoteReadRequest, RemoteReadChildRequest,
+		RemoteCallRequest, RemoteReadRequest, RemoteReadChildRequest, RemoteReadRangeRequest,
 		RemoteBlockchain, Fetcher, future_header,
 	},
 };
@@ -51,7 +51,9 @@ use sp_core::{
 	Bytes, OpaqueMetadata,
 	storage::{StorageKey, PrefixedStorageKey, StorageData, StorageChangeSet},
 };
+use sp_api::{Metadata, RuntimeApiInfo};
 use sp_version::RuntimeVersion;
+use sp_rpc::tracing::BlockTrace;
 use sp_runtime::{generic::BlockId, traits::{Block as BlockT, HashFor}};
 
 use super::{StateBackend, ChildStateBackend, error::{FutureResult, Error}, client_err};
@@ -165,6 +167,31 @@ impl<Block: BlockT, F: Fetcher<Block> + 'static, Client> LightState<Block, F, Cl
 	fn block_or_best(&self, hash: Option<Block::Hash>) -> Block::Hash {
 		hash.unwrap_or_else(|| self.client.info().best_hash)
 	}
+
+	/// Calls a function added in version 2 of the `Metadata` runtime api, failing with
+	/// `MetadataApiUnsupported` if the runtime at the given block doesn't implement it.
+	fn call_metadata_api_v2(
+		&self,
+		block: Option<Block::Hash>,
+		method: &str,
+		call_data: Bytes,
+	) -> FutureResult<Bytes> {
+		let block = self.block_or_best(block);
+		let remote_blockchain = self.remote_blockchain.clone();
+		let fetcher = self.fetcher.clone();
+		let method = method.to_string();
+		let metadata_api = <dyn Metadata<Block, Error = ()> as RuntimeApiInfo>::ID;
+
+		let response = runtime_version(&*self.remote_blockchain, self.fetcher.clone(), block)
+			.then(move |version| match version {
+				Ok(version) if version.has_api_with(&metadata_api, |v| v >= 2) =>
+					Either::Left(call(&*remote_blockchain, fetcher, block, method, call_data)),
+				Ok(_) => Either::Right(ready(Err(Error::MetadataApiUnsupported))),
+				Err(error) => Either::Right(ready(Err(error))),
+			});
+
+		Box::new(response.boxed().compat())
+	}
 }
 
 impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Client>
@@ -206,12 +233,42 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 
 	fn storage_keys_paged(
 		&self,
-		_block: Option<Block::Hash>,
-		_prefix: Option<StorageKey>,
-		_count: u32,
-		_start_key: Option<StorageKey>,
+		block: Option<Block::Hash>,
+		prefix: Option<StorageKey>,
+		count: u32,
+		start_key: Option<StorageKey>,
 	) -> FutureResult<Vec<StorageKey>> {
-		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+		if count == 0 {
+			return Box::new(result(Ok(Vec::new())));
+		}
+
+		let block = self.block_or_best(block);
+		let fetcher = self.fetcher.clone();
+		let prefix = prefix.map(|prefix| prefix.0).unwrap_or_default();
+		// Like on full nodes, the keys strictly after `start_key`, or after `prefix`, are returned.
+		let start_at = Some(start_key.map(|key| key.0).unwrap_or_else(|| prefix.clone()));
+		let keys = resolve_header(&*self.remote_blockchain, &*self.fetcher, block)
+			.then(move |result| match result {
+				Ok(header) => Either::Left(fetcher.remote_read_range(RemoteReadRangeRequest {
+					block,
+					header,
+					storage_key: None,
+					prefix,
+					start_at,
+					count,
+					retry_count: Default::default(),
+				}).then(|result| ready(result
+					.map(|range| range.entries
+						.into_iter()
+						.map(|(key, _)| StorageKey(key))
+						.collect()
+					)
+					.map_err(client_err)
+				))),
+				Err(error) => Either::Right(ready(Err(error))),
+			});
+
+		Box::new(keys.boxed().compat())
 	}
 
 	fn storage(
@@ -254,6 +311,54 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		Box::new(metadata)
 	}
 
+	fn metadata_at_version(
+		&self,
+		block: Option<Block::Hash>,
+		version: u32,
+	) -> FutureResult<Option<Bytes>> {
+		let call_data = Bytes(version.encode());
+		let metadata = self.call_metadata_api_v2(block, "Metadata_metadata_at_version", call_data);
+
+		Box::new(decode_call_result::<Option<OpaqueMetadata>>(metadata)
+			.map(|metadata| metadata.map(Into::into)))
+	}
+
+	fn metadata_versions(&self, block: Option<Block::Hash>) -> FutureResult<Vec<u32>> {
+		let versions = self.call_metadata_api_v2(
+			block,
+			"Metadata_metadata_versions",
+			Bytes(Vec::new()),
+		);
+
+		decode_call_result(versions)
+	}
+
+	fn constant_metadata(
+		&self,
+		block: Option<Block::Hash>,
+		pallet: String,
+		name: String,
+	) -> FutureResult<Option<Bytes>> {
+		let call_data = Bytes((pallet.into_bytes(), name.into_bytes()).encode());
+		let metadata = self.call_metadata_api_v2(block, "Metadata_constant", call_data);
+
+		Box::new(decode_call_result::<Option<OpaqueMetadata>>(metadata)
+			.map(|metadata| metadata.map(Into::into)))
+	}
+
+	fn storage_entry_metadata(
+		&self,
+		block: Option<Block::Hash>,
+		pallet: String,
+		name: String,
+	) -> FutureResult<Option<Bytes>> {
+		let call_data = Bytes((pallet.into_bytes(), name.into_bytes()).encode());
+		let metadata = self.call_metadata_api_v2(block, "Metadata_storage_entry", call_data);
+
+		Box::new(decode_call_result::<Option<OpaqueMetadata>>(metadata)
+			.map(|metadata| metadata.map(Into::into)))
+	}
+
 	fn runtime_version(&self, block: Option<Block::Hash>) -> FutureResult<RuntimeVersion> {
 		Box::new(runtime_version(
 			&*self.remote_blockchain,
@@ -287,6 +392,14 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
 	}
 
+	fn trace_block(
+		&self,
+		_block: Block::Hash,
+		_targets: String,
+	) -> FutureResult<BlockTrace> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
 	fn subscribe_storage(
 		&self,
 		_meta: crate::metadata::Metadata,
@@ -669,6 +782,15 @@ fn subscription_stream<
 		.map_err(|_| ())
 }
 
+/// Decodes the result of a runtime api call.
+fn decode_call_result<T: Decode + Send + 'static>(result: FutureResult<Bytes>) -> FutureResult<T> {
+	Box::new(result.and_then(|result| T::decode(&mut &result.0[..])
+		.map_err(|decode_err| client_err(ClientError::CallResultDecode(
+			"Unable to decode runtime api result",
+			decode_err,
+		)))))
+}
+
 /// Request some data from remote node, probably reusing response from already
 /// (in-progress) existing request.
 fn maybe_share_remote_request<Block: BlockT, Requests, V, IssueRequest, IssueRequestFuture>(
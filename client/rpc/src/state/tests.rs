@@ -15,7 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
-use super::state_full::split_range;
+use super::state_full::{split_range, strip_seal};
 use self::error::Error;
 
 use std::sync::Arc;
@@ -30,7 +30,7 @@ use substrate_test_runtime_client::{
 	sp_consensus::BlockOrigin,
 	runtime,
 };
-use sp_runtime::generic::BlockId;
+use sp_runtime::generic::{BlockId, DigestItem};
 
 const STORAGE_KEY: &[u8] = b"child";
 
@@ -52,7 +52,7 @@ fn should_return_storage() {
 		.add_extra_child_storage(&child_info, KEY.to_vec(), CHILD_VALUE.to_vec())
 		.build();
 	let genesis_hash = client.genesis_hash();
-	let (client, child) = new_full(Arc::new(client), Subscriptions::new(Arc::new(core.executor())));
+	let (client, child) = new_full(Arc::new(client), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 	let key = StorageKey(KEY.to_vec());
 
 	assert_eq!(
@@ -87,7 +87,7 @@ fn should_return_child_storage() {
 		.add_child_storage(&child_info, "key", vec![42_u8])
 		.build());
 	let genesis_hash = client.genesis_hash();
-	let (_client, child) = new_full(client, Subscriptions::new(Arc::new(core.executor())));
+	let (_client, child) = new_full(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 	let child_key = prefixed_storage_key();
 	let key = StorageKey(b"key".to_vec());
 
@@ -123,7 +123,7 @@ fn should_call_contract() {
 	let core = tokio::runtime::Runtime::new().unwrap();
 	let client = Arc::new(substrate_test_runtime_client::new());
 	let genesis_hash = client.genesis_hash();
-	let (client, _child) = new_full(client, Subscriptions::new(Arc::new(core.executor())));
+	let (client, _child) = new_full(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 	assert_matches!(
 		client.call("balanceOf".into(), Bytes(vec![1,2,3]), Some(genesis_hash).into()).wait(),
@@ -139,7 +139,7 @@ fn should_notify_about_storage_changes() {
 
 	{
 		let mut client = Arc::new(substrate_test_runtime_client::new());
-		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(remote)));
+		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(remote)), DenyUnsafe::No);
 
 		api.subscribe_storage(Default::default(), subscriber, None.into());
 
@@ -172,7 +172,7 @@ fn should_send_initial_storage_changes_and_notifications() {
 
 	{
 		let mut client = Arc::new(substrate_test_runtime_client::new());
-		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(remote)));
+		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(remote)), DenyUnsafe::No);
 
 		let alice_balance_key = blake2_256(&runtime::system::balance_of_key(AccountKeyring::Alice.into()));
 
@@ -208,7 +208,7 @@ fn should_send_initial_storage_changes_and_notifications() {
 fn should_query_storage() {
 	fn run_tests(mut client: Arc<TestClient>, has_changes_trie_config: bool) {
 		let core = tokio::runtime::Runtime::new().unwrap();
-		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 		let mut add_block = |nonce| {
 			let mut builder = client.new_block(Default::default()).unwrap();
@@ -427,7 +427,7 @@ fn should_return_runtime_version() {
 	let core = tokio::runtime::Runtime::new().unwrap();
 
 	let client = Arc::new(substrate_test_runtime_client::new());
-	let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+	let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 	let result = "{\"specName\":\"test\",\"implName\":\"parity-test\",\"authoringVersion\":1,\
 		\"specVersion\":2,\"implVersion\":2,\"apis\":[[\"0xdf6acb689907609b\",3],\
@@ -451,7 +451,7 @@ fn should_notify_on_runtime_version_initially() {
 
 	{
 		let client = Arc::new(substrate_test_runtime_client::new());
-		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+		let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
 
 		api.subscribe_runtime_version(Default::default(), subscriber);
 
@@ -473,3 +473,60 @@ fn should_deserialize_storage_key() {
 
 	assert_eq!(k.0.len(), 32);
 }
+
+#[test]
+fn should_trace_block() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(vec![1], Some(vec![2])).unwrap();
+	let block = builder.build().unwrap().block;
+	let hash = block.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	let (api, _child) = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::No);
+	let trace = api.trace_block(hash, None).wait().unwrap();
+
+	assert_eq!(trace.block_hash, format!("{:?}", hash));
+	assert_eq!(trace.tracing_targets, DEFAULT_TRACE_TARGETS);
+	let expected_hash = format!("{}", sp_core::hexdisplay::HexDisplay::from(&blake2_256(&[2])));
+	assert!(trace.storage.iter().any(|access|
+		access.kind == sp_rpc::tracing::StorageAccessKind::Write
+			&& access.key == "01"
+			&& access.value_hash.as_ref() == Some(&expected_hash)
+	));
+
+	let (api, _child) = new_full(client, Subscriptions::new(Arc::new(core.executor())), DenyUnsafe::Yes);
+	assert_matches!(api.trace_block(hash, None).wait(), Err(Error::UnsafeRpcCalled(_)));
+}
+
+#[test]
+fn should_trace_sealed_block() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(vec![1], Some(vec![2])).unwrap();
+	let mut block = builder.build().unwrap().block;
+	block.header.digest.push(DigestItem::Seal(*b"test", vec![1, 2, 3]));
+	let hash = block.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	let subscriptions = Subscriptions::new(Arc::new(core.executor()));
+	let (api, _child) = new_full(client, subscriptions, DenyUnsafe::No);
+	let trace = api.trace_block(hash, None).wait().unwrap();
+	assert_eq!(trace.block_hash, format!("{:?}", hash));
+}
+
+#[test]
+fn strip_seal_only_removes_the_seal() {
+	let client = substrate_test_runtime_client::new();
+	let mut block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	block.header.digest.push(DigestItem::Other(vec![1]));
+	let unsealed = block.clone();
+	block.header.digest.push(DigestItem::Seal(*b"test", vec![1, 2, 3]));
+
+	assert_eq!(strip_seal(block), unsealed);
+	assert_eq!(strip_seal(unsealed.clone()), unsealed);
+}
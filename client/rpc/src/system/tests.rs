@@ -86,6 +86,7 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						not_connected_peers: Default::default(),
 						average_download_per_sec: 0,
 						average_upload_per_sec: 0,
+						protocols_bandwidth: Default::default(),
 						peerset: serde_json::Value::Null,
 					}).unwrap());
 				},
@@ -281,6 +282,7 @@ fn system_network_state() {
 			not_connected_peers: Default::default(),
 			average_download_per_sec: 0,
 			average_upload_per_sec: 0,
+			protocols_bandwidth: Default::default(),
 			peerset: serde_json::Value::Null,
 		}
 	);
@@ -38,6 +38,7 @@ use sc_keystore::Store as Keystore;
 use log::{info, warn, error};
 use sc_network::config::{Role, FinalityProofProvider, OnDemand, BoxFinalityProofRequestBuilder};
 use sc_network::{NetworkService, NetworkStateInfo};
+use sc_rpc::author::outcome::ExtrinsicOutcomeResolver;
 use parking_lot::{Mutex, RwLock};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{
@@ -56,10 +57,12 @@ use sp_transaction_pool::{MaintainedTransactionPool, ChainEvent};
 use sp_blockchain;
 use prometheus_endpoint::Registry;
 use sc_client_db::{Backend, DatabaseSettings};
+use sp_blockchain::Backend as _;
+use codec::Encode;
 use sp_core::traits::CodeExecutor;
 use sp_runtime::BuildStorage;
 use sc_client_api::execution_extensions::ExecutionExtensions;
-use sp_core::storage::Storage;
+use sp_core::storage::{Storage, StorageKey, well_known_keys};
 
 pub type BackgroundTask = Pin<Box<dyn Future<Output=()> + Send>>;
 
@@ -85,7 +88,7 @@ pub struct ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
 {
 	config: Configuration,
 	pub (crate) client: Arc<TCl>,
-	backend: Arc<Backend>,
+	pub (crate) backend: Arc<Backend>,
 	task_manager: TaskManager,
 	keystore: Arc<RwLock<Keystore>>,
 	fetcher: Option<TFchr>,
@@ -98,6 +101,7 @@ pub struct ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
 	remote_backend: Option<Arc<dyn RemoteBlockchain<TBl>>>,
 	marker: PhantomData<(TBl, TRtApi)>,
 	block_announce_validator_builder: Option<Box<dyn FnOnce(Arc<TCl>) -> Box<dyn BlockAnnounceValidator<TBl> + Send> + Send>>,
+	extrinsic_outcome_resolver: Option<Arc<dyn ExtrinsicOutcomeResolver<TBl>>>,
 }
 
 /// Full client type.
@@ -182,10 +186,12 @@ fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		TaskManager::new(config.task_executor.clone(), registry)?
 	};
 
-	let executor = NativeExecutor::<TExecDisp>::new(
+	let executor = NativeExecutor::<TExecDisp>::new_with_wasm_cache_path(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
+		config.wasm_runtime_cache_path.clone(),
+		config.wasm_runtime_stack_limit,
 	);
 
 	let chain_spec = &config.chain_spec;
@@ -213,7 +219,7 @@ fn new_full_parts<TBl, TRtApi, TExecDisp>(
 
 		new_client(
 			db_config,
-			executor,
+			executor.clone(),
 			chain_spec.as_storage_builder(),
 			fork_blocks,
 			bad_blocks,
@@ -227,9 +233,110 @@ fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		)?
 	};
 
+	if config.wasm_runtime_cache_path.is_some() {
+		let code_changes = client.storage_changes_notification_stream(
+			Some(&[StorageKey(well_known_keys::CODE.to_vec())]),
+			None,
+		)?;
+		spawn_runtime_precompilation(executor.clone(), code_changes, &task_manager.spawn_handle());
+		spawn_embedded_runtime_precompilation(
+			executor,
+			client.import_notification_stream(),
+			backend.clone(),
+			&task_manager.spawn_handle(),
+		);
+	}
+
 	Ok((client, backend, keystore, task_manager))
 }
 
+/// Compile every new runtime code that is written by an imported block, so that a runtime upgrade
+/// is found in the on-disk cache once it is enacted.
+fn spawn_runtime_precompilation<H, TExecDisp>(
+	executor: NativeExecutor<TExecDisp>,
+	code_changes: sc_client_api::StorageEventStream<H>,
+	spawn_handle: &SpawnTaskHandle,
+) where
+	H: std::fmt::Debug + Send + 'static,
+	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	let compile_spawn_handle = spawn_handle.clone();
+	let task = code_changes.for_each(move |(hash, changes)| {
+		let code = changes.iter()
+			.filter_map(|(_, _, value)| value.map(|value| value.0.clone()))
+			.last();
+		if let Some(code) = code {
+			let executor = executor.clone();
+			compile_spawn_handle.spawn_blocking("runtime-precompile", async move {
+				match executor.precompile_runtime(&code) {
+					Ok(()) => info!("📦 Precompiled the runtime code set at block {:?}", hash),
+					Err(e) => warn!(
+						"Failed to precompile the runtime code set at block {:?}: {}",
+						hash,
+						e,
+					),
+				}
+			});
+		}
+		ready(())
+	});
+
+	spawn_handle.spawn("runtime-precompile-on-code-change", task);
+}
+
+/// Compile the runtime codes that are embedded in the extrinsics of every imported block, so that
+/// a runtime upgrade that is enacted later (e.g. a scheduled `set_code` or the preimage of a
+/// proposal) is compiled well before the `:code` is changed.
+fn spawn_embedded_runtime_precompilation<TBl, TExecDisp>(
+	executor: NativeExecutor<TExecDisp>,
+	imports: sc_client_api::ImportNotifications<TBl>,
+	backend: Arc<TFullBackend<TBl>>,
+	spawn_handle: &SpawnTaskHandle,
+) where
+	TBl: BlockT,
+	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	let compile_spawn_handle = spawn_handle.clone();
+	let task = imports.for_each(move |notification| {
+		let hash = notification.hash;
+		let body = sc_client_api::backend::Backend::blockchain(&*backend)
+			.body(BlockId::Hash(hash));
+		let extrinsics = match body {
+			Ok(Some(extrinsics)) => extrinsics.iter()
+				.map(Encode::encode)
+				.filter(|xt| !sc_executor::embedded_runtime_codes(xt).is_empty())
+				.collect::<Vec<_>>(),
+			Ok(None) => Vec::new(),
+			Err(e) => {
+				warn!("Failed to read the body of imported block {:?}: {}", hash, e);
+				Vec::new()
+			},
+		};
+
+		if !extrinsics.is_empty() {
+			let executor = executor.clone();
+			compile_spawn_handle.spawn_blocking("runtime-precompile", async move {
+				for xt in extrinsics {
+					match executor.precompile_embedded_runtimes(&xt) {
+						Ok(_) => info!(
+							"📦 Precompiled the runtime code included in block {:?}",
+							hash,
+						),
+						Err(e) => warn!(
+							"Failed to precompile the runtime code included in block {:?}: {}",
+							hash,
+							e,
+						),
+					}
+				}
+			});
+		}
+		ready(())
+	});
+
+	spawn_handle.spawn("runtime-precompile-on-import", task);
+}
+
 
 /// Create an instance of db-backed client.
 pub fn new_client<E, Block, RA>(
@@ -312,6 +419,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			rpc_extensions: Default::default(),
 			remote_backend: None,
 			block_announce_validator_builder: None,
+			extrinsic_outcome_resolver: None,
 			marker: PhantomData,
 		})
 	}
@@ -345,10 +453,12 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			KeystoreConfig::InMemory => Keystore::new_in_memory(),
 		};
 
-		let executor = NativeExecutor::<TExecDisp>::new(
+		let executor = NativeExecutor::<TExecDisp>::new_with_wasm_cache_path(
 			config.wasm_method,
 			config.default_heap_pages,
 			config.max_runtime_instances,
+			config.wasm_runtime_cache_path.clone(),
+			config.wasm_runtime_stack_limit,
 		);
 
 		let db_storage = {
@@ -395,6 +505,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			rpc_extensions: Default::default(),
 			remote_backend: Some(remote_blockchain),
 			block_announce_validator_builder: None,
+			extrinsic_outcome_resolver: None,
 			marker: PhantomData,
 		})
 	}
@@ -468,6 +579,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: self.block_announce_validator_builder,
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
@@ -512,6 +624,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: self.block_announce_validator_builder,
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
@@ -550,6 +663,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: self.block_announce_validator_builder,
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
@@ -614,6 +728,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: self.block_announce_validator_builder,
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
@@ -677,6 +792,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: self.block_announce_validator_builder,
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
@@ -705,6 +821,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: self.block_announce_validator_builder,
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
@@ -733,9 +850,25 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
 			block_announce_validator_builder: Some(Box::new(block_announce_validator_builder)),
+			extrinsic_outcome_resolver: self.extrinsic_outcome_resolver,
 			marker: self.marker,
 		})
 	}
+
+	/// Defines the `ExtrinsicOutcomeResolver` used to send the dispatch outcome of the extrinsics
+	/// watched through `author_submitAndWatchExtrinsicWithOutcome`. No outcome is sent by default.
+	pub fn with_extrinsic_outcome_resolver(
+		self,
+		extrinsic_outcome_resolver_builder:
+			impl FnOnce(&Self) -> Result<Arc<dyn ExtrinsicOutcomeResolver<TBl>>, Error>,
+	) -> Result<Self, Error> {
+		let extrinsic_outcome_resolver = extrinsic_outcome_resolver_builder(&self)?;
+
+		Ok(ServiceBuilder {
+			extrinsic_outcome_resolver: Some(extrinsic_outcome_resolver),
+			..self
+		})
+	}
 }
 
 /// Implemented on `ServiceBuilder`. Allows running block commands, such as import/export/validate
@@ -779,6 +912,9 @@ pub trait ServiceBuilderCommand {
 		&self,
 		block: Option<BlockId<Self::Block>>,
 	) -> Result<Storage, Error>;
+
+	/// Returns the database where the network saves the known peers.
+	fn peer_store(&self) -> Arc<dyn sc_network::config::PeerStoreBackend>;
 }
 
 impl<TBl, TRtApi, TBackend, TExec, TSc, TImpQu, TExPool, TRpc>
@@ -851,6 +987,7 @@ ServiceBuilder<
 			rpc_extensions,
 			remote_backend,
 			block_announce_validator_builder,
+			extrinsic_outcome_resolver,
 		} = self;
 
 		sp_session::generate_initial_session_keys(
@@ -921,7 +1058,8 @@ ServiceBuilder<
 			import_queue,
 			protocol_id,
 			block_announce_validator,
-			metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone())
+			metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone()),
+			peer_store: Some(backend.clone()),
 		};
 
 		let has_bootnodes = !network_params.network_config.boot_nodes.is_empty();
@@ -1118,14 +1256,19 @@ ServiceBuilder<
 					client.clone(),
 					subscriptions.clone(),
 					remote_backend.clone(),
-					on_demand.clone()
+					on_demand.clone(),
+					deny_unsafe,
 				);
 				(chain, state, child_state)
 
 			} else {
 				// Full nodes
 				let chain = sc_rpc::chain::new_full(client.clone(), subscriptions.clone());
-				let (state, child_state) = sc_rpc::state::new_full(client.clone(), subscriptions.clone());
+				let (state, child_state) = sc_rpc::state::new_full(
+					client.clone(),
+					subscriptions.clone(),
+					deny_unsafe,
+				);
 				(chain, state, child_state)
 			};
 
@@ -1136,6 +1279,10 @@ ServiceBuilder<
 				keystore.clone(),
 				deny_unsafe,
 			);
+			let author = match extrinsic_outcome_resolver.clone() {
+				Some(outcome_resolver) => author.with_outcome_resolver(outcome_resolver),
+				None => author,
+			};
 			let system = system::System::new(system_info, system_rpc_tx.clone(), deny_unsafe);
 
 			let maybe_offchain_rpc = offchain_storage.clone()
@@ -1230,15 +1377,22 @@ ServiceBuilder<
 		});
 
 		// Instrumentation
-		if let Some(tracing_targets) = config.tracing_targets.as_ref() {
+		let chrome_trace = if let Some(tracing_targets) = config.tracing_targets.as_ref() {
 			let subscriber = sc_tracing::ProfilingSubscriber::new(
 				config.tracing_receiver, tracing_targets
 			);
+			// The subscriber is never dropped, so the service finishes its Chrome trace.
+			let chrome_trace = subscriber.chrome_trace_handle();
 			match tracing::subscriber::set_global_default(subscriber) {
-				Ok(_) => (),
-				Err(e) => error!(target: "tracing", "Unable to set global default subscriber {}", e),
+				Ok(_) => chrome_trace,
+				Err(e) => {
+					error!(target: "tracing", "Unable to set global default subscriber {}", e);
+					None
+				},
 			}
-		}
+		} else {
+			None
+		};
 
 		Ok(Service {
 			client,
@@ -1254,6 +1408,7 @@ ServiceBuilder<
 			_telemetry: telemetry,
 			_offchain_workers: offchain_workers,
 			_telemetry_on_connect_sinks: telemetry_connection_sinks.clone(),
+			_chrome_trace: chrome_trace,
 			keystore,
 			marker: PhantomData::<TBl>,
 			prometheus_registry: config.prometheus_config.map(|config| config.registry)
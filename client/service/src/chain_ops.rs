@@ -36,7 +36,7 @@ use sc_executor::{NativeExecutor, NativeExecutionDispatch};
 use sp_core::storage::{StorageKey, well_known_keys, ChildInfo, Storage, StorageChild, StorageMap};
 use sc_client_api::{StorageProvider, BlockBackend, UsageProvider};
 
-use std::{io::{Read, Write, Seek}, pin::Pin, collections::HashMap};
+use std::{io::{Read, Write, Seek}, pin::Pin, collections::HashMap, sync::Arc};
 
 /// Build a chain spec json
 pub fn build_spec(spec: &dyn ChainSpec, raw: bool) -> error::Result<String> {
@@ -57,6 +57,7 @@ impl<
 	TExecDisp: 'static + NativeExecutionDispatch,
 	TImpQu: 'static + ImportQueue<TBl>,
 	TRtApi: 'static + Send + Sync,
+	Backend: 'static + sc_client_api::backend::AuxStore + Send + Sync,
 	Self: Send + 'static,
 {
 	type Block = TBl;
@@ -340,4 +341,8 @@ impl<
 		let top = top_storage.into_iter().map(|(k, v)| (k.0, v.0)).collect();
 		Ok(Storage { top, children_default })
 	}
+
+	fn peer_store(&self) -> Arc<dyn sc_network::config::PeerStoreBackend> {
+		self.backend.clone()
+	}
 }
@@ -40,7 +40,7 @@ use sp_runtime::{
 };
 use sp_state_machine::{
 	DBValue, Backend as StateBackend, ChangesTrieAnchorBlockId,
-	prove_read, prove_child_read, ChangesTrieRootsStorage, ChangesTrieStorage,
+	prove_read, prove_child_read, prove_range_read, ChangesTrieRootsStorage, ChangesTrieStorage,
 	ChangesTrieConfigurationRange, key_changes, key_changes_proof,
 };
 use sc_executor::RuntimeVersion;
@@ -1198,6 +1198,19 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 				.map_err(Into::into))
 	}
 
+	fn read_range_proof(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		count: u32,
+	) -> sp_blockchain::Result<StorageProof> {
+		self.state_at(id)
+			.and_then(|state| prove_range_read(state, child_info, prefix, start_at, count)
+				.map_err(Into::into))
+	}
+
 	fn execution_proof(
 		&self,
 		id: &BlockId<Block>,
@@ -31,7 +31,7 @@ use sp_runtime::traits::{
 use sp_state_machine::{
 	ChangesTrieRootsStorage, ChangesTrieAnchorBlockId, ChangesTrieConfigurationRange,
 	InMemoryChangesTrieStorage, TrieBackend, read_proof_check, key_changes_proof_check_with_db,
-	read_child_proof_check, CloneableSpawn,
+	read_child_proof_check, read_range_proof_check, CloneableSpawn,
 };
 pub use sp_state_machine::StorageProof;
 use sp_blockchain::{Error as ClientError, Result as ClientResult};
@@ -39,7 +39,8 @@ use sp_blockchain::{Error as ClientError, Result as ClientResult};
 pub use sc_client_api::{
 	light::{
 		RemoteCallRequest, RemoteHeaderRequest, RemoteReadRequest, RemoteReadChildRequest,
-		RemoteChangesRequest, ChangesProof, RemoteBodyRequest, Fetcher, FetchChecker,
+		RemoteReadRangeRequest, StorageRange, RemoteChangesRequest, ChangesProof,
+		RemoteBodyRequest, Fetcher, FetchChecker,
 		Storage as BlockchainStorage,
 	},
 	cht,
@@ -255,6 +256,28 @@ impl<E, Block, H, S> FetchChecker<Block> for LightDataChecker<E, H, Block, S>
 		).map_err(Into::into)
 	}
 
+	fn check_read_range_proof(
+		&self,
+		request: &RemoteReadRangeRequest<Block::Header>,
+		remote_proof: StorageProof,
+	) -> ClientResult<StorageRange> {
+		let child_info = match request.storage_key.as_ref().map(ChildType::from_prefixed_key) {
+			Some(Some((ChildType::ParentKeyId, storage_key))) => Some(ChildInfo::new_default(storage_key)),
+			Some(None) => return Err("Invalid child type".into()),
+			None => None,
+		};
+		read_range_proof_check::<H>(
+			convert_hash(request.header.state_root()),
+			remote_proof,
+			child_info.as_ref(),
+			&request.prefix,
+			request.start_at.as_ref().map(AsRef::as_ref),
+			request.count,
+		)
+			.map(|(entries, complete)| StorageRange { entries, complete })
+			.map_err(Into::into)
+	}
+
 	fn check_execution_proof(
 		&self,
 		request: &RemoteCallRequest<Block::Header>,
@@ -20,6 +20,7 @@ pub use sc_client_db::{Database, PruningMode, DatabaseSettingsSrc as DatabaseCon
 pub use sc_network::Multiaddr;
 pub use sc_network::config::{ExtTransport, MultiaddrWithPeerId, NetworkConfiguration, Role, NodeKeyConfig};
 pub use sc_executor::WasmExecutionMethod;
+pub use sc_executor::stack_limit::DeterministicStackLimit;
 use sc_client_api::execution_extensions::ExecutionStrategies;
 
 use std::{future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
@@ -98,6 +99,14 @@ pub struct Configuration {
 	///
 	/// The default value is 8.
 	pub max_runtime_instances: usize,
+	/// Directory where compiled Wasm runtimes are cached across restarts.
+	///
+	/// If `None`, compiled runtimes are only cached in memory.
+	pub wasm_runtime_cache_path: Option<PathBuf>,
+	/// Deterministic limit of the stack depth of the Wasm runtime.
+	///
+	/// If `None`, the stack depth only depends on the executor and the machine.
+	pub wasm_runtime_stack_limit: Option<DeterministicStackLimit>,
 	/// Announce block automatically after they have been imported
 	pub announce_block: bool,
 }
@@ -121,6 +121,8 @@ pub struct Service<TBl, TCl, TSc, TNetStatus, TNet, TTxPool, TOc> {
 	_rpc: Box<dyn std::any::Any + Send + Sync>,
 	_telemetry: Option<sc_telemetry::Telemetry>,
 	_telemetry_on_connect_sinks: Arc<Mutex<Vec<TracingUnboundedSender<()>>>>,
+	/// Finishes the Chrome trace of the tracing subscriber when the service shuts down.
+	_chrome_trace: Option<sc_tracing::ChromeTraceHandle>,
 	_offchain_workers: Option<Arc<TOc>>,
 	keystore: sc_keystore::KeyStorePtr,
 	marker: PhantomData<TBl>,
@@ -648,7 +650,8 @@ where
 	C: sc_network::config::Client<B> + Send + Sync,
 	Pool: 'static + TransactionPool<Block=B, Hash=H, Error=E>,
 	B: BlockT,
-	H: std::hash::Hash + Eq + sp_runtime::traits::Member + sp_runtime::traits::MaybeSerialize,
+	H: std::hash::Hash + Eq + sp_runtime::traits::Member + sp_runtime::traits::MaybeSerialize
+		+ Encode + Decode,
 	E: 'static + IntoPoolError + From<sp_transaction_pool::error::Error>,
 {
 	fn transactions(&self) -> Vec<(H, B::Extrinsic)> {
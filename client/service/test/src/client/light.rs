@@ -37,7 +37,7 @@ use sp_api::{InitializeBlock, StorageTransactionCache, ProofRecorder, OffchainOv
 use sp_consensus::{BlockOrigin};
 use sc_executor::{NativeExecutor, WasmExecutionMethod, RuntimeVersion, NativeVersion};
 use sp_core::{H256, tasks::executor as tasks_executor, NativeOrEncoded};
-use sc_client_api::{blockchain::Info, backend::NewBlockState, Backend as ClientBackend, ProofProvider, in_mem::{Backend as InMemBackend, Blockchain as InMemoryBlockchain}, AuxStore, Storage, CallExecutor, cht, ExecutionStrategy, StorageProof, BlockImportOperation, RemoteCallRequest, StorageProvider, ChangesProof, RemoteBodyRequest, RemoteReadRequest, RemoteChangesRequest, FetchChecker, RemoteReadChildRequest, RemoteHeaderRequest};
+use sc_client_api::{blockchain::Info, backend::NewBlockState, Backend as ClientBackend, ProofProvider, in_mem::{Backend as InMemBackend, Blockchain as InMemoryBlockchain}, AuxStore, Storage, CallExecutor, cht, ExecutionStrategy, StorageProof, BlockImportOperation, RemoteCallRequest, StorageProvider, ChangesProof, RemoteBodyRequest, RemoteReadRequest, RemoteChangesRequest, FetchChecker, RemoteReadChildRequest, RemoteReadRangeRequest, RemoteHeaderRequest, StorageRange};
 use sp_externalities::Extensions;
 use sc_block_builder::BlockBuilderProvider;
 use sp_blockchain::{
@@ -595,6 +595,70 @@ fn storage_child_read_proof_is_generated_and_checked() {
 	).unwrap().remove(b"key1".as_ref()).unwrap().unwrap(), result);
 }
 
+#[test]
+fn storage_range_read_proof_is_generated_and_checked() {
+	use substrate_test_runtime_client::DefaultTestClientBuilderExt;
+	use substrate_test_runtime_client::TestClientBuilderExt;
+	let child_info = ChildInfo::new_default(&b"child1"[..]);
+	// prepare remote client
+	let remote_client = substrate_test_runtime_client::TestClientBuilder::new()
+		.add_extra_child_storage(&child_info, b"key1".to_vec(), b"value1".to_vec())
+		.add_extra_child_storage(&child_info, b"key2".to_vec(), b"value2".to_vec())
+		.add_extra_child_storage(&child_info, b"key3".to_vec(), b"value3".to_vec())
+		.build();
+	let remote_block_id = BlockId::Number(0);
+	let mut remote_block_header = remote_client.header(&remote_block_id).unwrap().unwrap();
+	remote_block_header.state_root = remote_client.state_at(&remote_block_id).unwrap()
+		.storage_root(::std::iter::empty()).0.into();
+	let local_checker = LightDataChecker::new(
+		Arc::new(DummyBlockchain::new(DummyStorage::new())),
+		local_executor(),
+		tasks_executor(),
+	);
+	let request = |start_at: Option<&[u8]>, count| RemoteReadRangeRequest::<Header> {
+		block: remote_block_header.hash(),
+		header: remote_block_header.clone(),
+		storage_key: Some(child_info.prefixed_storage_key()),
+		prefix: b"key".to_vec(),
+		start_at: start_at.map(|key| key.to_vec()),
+		count,
+		retry_count: None,
+	};
+
+	// first page
+	let remote_proof = remote_client.read_range_proof(
+		&remote_block_id,
+		Some(&child_info),
+		b"key",
+		None,
+		2,
+	).unwrap();
+	assert_eq!(
+		(&local_checker as &dyn FetchChecker<Block>)
+			.check_read_range_proof(&request(None, 2), remote_proof)
+			.unwrap(),
+		StorageRange {
+			entries: vec![(b"key1".to_vec(), b"value1".to_vec()), (b"key2".to_vec(), b"value2".to_vec())],
+			complete: false,
+		},
+	);
+
+	// last page
+	let remote_proof = remote_client.read_range_proof(
+		&remote_block_id,
+		Some(&child_info),
+		b"key",
+		Some(b"key2"),
+		10,
+	).unwrap();
+	assert_eq!(
+		(&local_checker as &dyn FetchChecker<Block>)
+			.check_read_range_proof(&request(Some(b"key2"), 10), remote_proof)
+			.unwrap(),
+		StorageRange { entries: vec![(b"key3".to_vec(), b"value3".to_vec())], complete: true },
+	);
+}
+
 #[test]
 fn header_proof_is_generated_and_checked() {
 	let (local_checker, local_cht_root, remote_block_header, remote_header_proof) = prepare_for_header_proof_check(true);
@@ -207,6 +207,8 @@ fn node_config<G: RuntimeGenesis + 'static, E: ChainSpecExtension + Clone + 'sta
 		tracing_targets: None,
 		tracing_receiver: Default::default(),
 		max_runtime_instances: 8,
+		wasm_runtime_cache_path: None,
+		wasm_runtime_stack_limit: Some(Default::default()),
 		announce_block: true,
 	}
 }
@@ -0,0 +1,326 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collection of the spans, events and storage accesses of a single execution, e.g. of the
+//! re-execution of a block.
+//!
+//! Unlike the [`ProfilingSubscriber`](../struct.ProfilingSubscriber.html), the
+//! [`BlockSubscriber`] is only installed for the current thread while the execution runs, and
+//! returns all that it collected afterwards.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing_core::{
+	dispatcher::{self, Dispatch},
+	event::Event,
+	field::{Visit, Field},
+	Level,
+	metadata::Metadata,
+	span::{Attributes, Id, Record},
+	subscriber::Subscriber,
+};
+
+use sp_rpc::tracing::{Span, Event as TraceEvent, StorageAccess, StorageAccessKind};
+use sp_tracing::proxy::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+
+use crate::parse_target;
+
+/// The target of the events of storage accesses, see `sp_state_machine::Ext`.
+const STORAGE_ACCESS_TARGET: &str = "state";
+
+/// Everything that was collected during an execution.
+#[derive(Default, Debug)]
+pub struct TraceData {
+	/// The spans, in the order they were closed.
+	pub spans: Vec<Span>,
+	/// The events, in execution order, without storage accesses.
+	pub events: Vec<TraceEvent>,
+	/// The storage accesses, in execution order.
+	pub storage: Vec<StorageAccess>,
+}
+
+/// Execute `f` while collecting the spans and events of the given comma separated `targets`, as
+/// well as all storage accesses.
+///
+/// The targets have the same format as the ones of the `ProfilingSubscriber`, and the spans of the
+/// wasm runtime are collected if the `wasm_tracing` target is given.
+pub fn trace_execution<R>(targets: &str, f: impl FnOnce() -> R) -> (R, TraceData) {
+	let subscriber = BlockSubscriber::new(targets);
+	let collected = subscriber.collected.clone();
+
+	let dispatch = Dispatch::new(subscriber);
+	let result = dispatcher::with_default(&dispatch, f);
+	drop(dispatch);
+
+	let mut collected = collected.lock();
+	let mut data = std::mem::take(&mut collected.data);
+	// Spans that are still open were not closed by the execution, e.g. because it panicked.
+	data.spans.extend(collected.open_spans.drain().map(|(_, span)| span.into_span()));
+	(result, data)
+}
+
+/// A span that has not been closed yet.
+struct OpenSpan {
+	id: u64,
+	parent_id: Option<u64>,
+	name: String,
+	target: String,
+	line: u32,
+	first_entered: Option<Duration>,
+	last_entered: Option<Instant>,
+	overall_time: Duration,
+	values: BTreeMap<String, String>,
+}
+
+impl OpenSpan {
+	fn into_span(self) -> Span {
+		Span {
+			id: self.id,
+			parent_id: self.parent_id,
+			name: self.name,
+			target: self.target,
+			line: self.line,
+			start_ns: self.first_entered.unwrap_or_default().as_nanos() as u64,
+			duration_ns: self.overall_time.as_nanos() as u64,
+			values: self.values,
+		}
+	}
+}
+
+#[derive(Default)]
+struct Collected {
+	open_spans: HashMap<u64, OpenSpan>,
+	/// The ids of the currently entered spans, innermost last.
+	entered: Vec<u64>,
+	data: TraceData,
+}
+
+/// A `Subscriber` collecting spans, events and storage accesses.
+///
+/// It is meant to be installed for a single thread only, as the spans entered on all threads are
+/// treated as entered one within the other.
+pub struct BlockSubscriber {
+	next_id: AtomicU64,
+	targets: Vec<(String, Level)>,
+	start_time: Instant,
+	collected: Arc<Mutex<Collected>>,
+}
+
+impl BlockSubscriber {
+	/// Create a subscriber for the given comma separated `targets`.
+	pub fn new(targets: &str) -> Self {
+		let mut targets: Vec<_> = targets.split(',').filter(|s| !s.is_empty()).map(parse_target).collect();
+		// Storage accesses are always collected.
+		targets.push((STORAGE_ACCESS_TARGET.to_string(), Level::TRACE));
+
+		BlockSubscriber {
+			next_id: AtomicU64::new(1),
+			targets,
+			start_time: Instant::now(),
+			collected: Default::default(),
+		}
+	}
+}
+
+#[derive(Default)]
+struct ValuesVisitor(BTreeMap<String, String>);
+
+impl Visit for ValuesVisitor {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.0.insert(field.name().to_string(), value.to_string());
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+		self.0.insert(field.name().to_string(), format!("{:?}", value));
+	}
+}
+
+/// Convert the values of a storage access event into a `StorageAccess`.
+///
+/// Returns `None` if the event is not a storage access.
+fn storage_access(parent_id: Option<u64>, mut values: BTreeMap<String, String>) -> Option<StorageAccess> {
+	let value_hash = values.remove("value_hash").filter(|h| !h.is_empty());
+	let kind = match values.get("method")?.as_str() {
+		"Get" | "ChildGet" => StorageAccessKind::Read,
+		"Exists" | "ChildExists" => StorageAccessKind::Exists,
+		"Put" | "ChildPut" if value_hash.is_some() => StorageAccessKind::Write,
+		"Put" | "ChildPut" => StorageAccessKind::Remove,
+		"Append" => StorageAccessKind::Append,
+		"ClearPrefix" | "ChildClearPrefix" => StorageAccessKind::ClearPrefix,
+		"ChildKill" => StorageAccessKind::KillChild,
+		_ => return None,
+	};
+
+	Some(StorageAccess {
+		parent_id,
+		kind,
+		child_trie: values.remove("child_info"),
+		key: values.remove("key").unwrap_or_default(),
+		value_hash,
+	})
+}
+
+impl Subscriber for BlockSubscriber {
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		self.targets.iter().any(|(target, level)|
+			metadata.target().starts_with(target.as_str()) && metadata.level() <= level
+		)
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let mut values = ValuesVisitor::default();
+		attrs.record(&mut values);
+		let mut name = attrs.metadata().name().to_string();
+		let mut target = attrs.metadata().target().to_string();
+		// Spans of the wasm runtime carry their actual name and target as values.
+		if target == WASM_TRACE_IDENTIFIER {
+			name = values.0.remove(WASM_NAME_KEY).unwrap_or(name);
+			target = values.0.remove(WASM_TARGET_KEY).unwrap_or(target);
+		}
+
+		let mut collected = self.collected.lock();
+		let parent_id = collected.entered.last().cloned();
+		collected.open_spans.insert(id, OpenSpan {
+			id,
+			parent_id,
+			name,
+			target,
+			line: attrs.metadata().line().unwrap_or(0),
+			first_entered: None,
+			last_entered: None,
+			overall_time: Duration::from_nanos(0),
+			values: values.0,
+		});
+		Id::from_u64(id)
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		let mut visitor = ValuesVisitor::default();
+		values.record(&mut visitor);
+		if let Some(span) = self.collected.lock().open_spans.get_mut(&span.into_u64()) {
+			span.values.extend(visitor.0);
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+	fn event(&self, event: &Event<'_>) {
+		let mut values = ValuesVisitor::default();
+		event.record(&mut values);
+
+		let mut collected = self.collected.lock();
+		let parent_id = collected.entered.last().cloned();
+		let target = event.metadata().target();
+		if target == STORAGE_ACCESS_TARGET {
+			if let Some(access) = storage_access(parent_id, values.0) {
+				collected.data.storage.push(access);
+			}
+		} else {
+			collected.data.events.push(TraceEvent {
+				parent_id,
+				target: target.to_string(),
+				values: values.0,
+			});
+		}
+	}
+
+	fn enter(&self, span: &Id) {
+		let now = Instant::now();
+		let since_start = now - self.start_time;
+
+		let mut collected = self.collected.lock();
+		collected.entered.push(span.into_u64());
+		if let Some(span) = collected.open_spans.get_mut(&span.into_u64()) {
+			span.first_entered.get_or_insert(since_start);
+			span.last_entered = Some(now);
+		}
+	}
+
+	fn exit(&self, span: &Id) {
+		let now = Instant::now();
+
+		let mut collected = self.collected.lock();
+		if let Some(pos) = collected.entered.iter().rposition(|id| *id == span.into_u64()) {
+			collected.entered.remove(pos);
+		}
+		if let Some(span) = collected.open_spans.get_mut(&span.into_u64()) {
+			if let Some(entered) = span.last_entered.take() {
+				span.overall_time += now - entered;
+			}
+		}
+	}
+
+	fn try_close(&self, span: Id) -> bool {
+		let mut collected = self.collected.lock();
+		if let Some(span) = collected.open_spans.remove(&span.into_u64()) {
+			collected.data.spans.push(span.into_span());
+		}
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn collects_spans_events_and_storage_accesses() {
+		let ((), data) = trace_execution("test_target", || {
+			let outer = tracing::span!(target: "test_target", tracing::Level::INFO, "outer");
+			let _outer = outer.enter();
+			tracing::info!(target: "test_target", answer = 42);
+			tracing::trace!(target: "state", method = "Get", key = "0102", value_hash = "abcd");
+			{
+				let inner = tracing::span!(target: "test_target", tracing::Level::INFO, "inner");
+				let _inner = inner.enter();
+				tracing::trace!(target: "state", method = "Put", key = "0102", value_hash = "");
+			}
+			// Not one of the traced targets.
+			tracing::info!(target: "other_target", answer = 43);
+		});
+
+		assert_eq!(data.spans.len(), 2);
+		let (inner, outer) = (&data.spans[0], &data.spans[1]);
+		assert_eq!(inner.name, "inner");
+		assert_eq!(inner.parent_id, Some(outer.id));
+		assert_eq!(outer.parent_id, None);
+
+		assert_eq!(data.events.len(), 1);
+		assert_eq!(data.events[0].parent_id, Some(outer.id));
+		assert_eq!(data.events[0].values.get("answer"), Some(&"42".to_string()));
+
+		assert_eq!(data.storage, vec![
+			StorageAccess {
+				parent_id: Some(outer.id),
+				kind: StorageAccessKind::Read,
+				child_trie: None,
+				key: "0102".into(),
+				value_hash: Some("abcd".into()),
+			},
+			StorageAccess {
+				parent_id: Some(inner.id),
+				kind: StorageAccessKind::Remove,
+				child_trie: None,
+				key: "0102".into(),
+				value_hash: None,
+			},
+		]);
+	}
+}
@@ -0,0 +1,362 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Output of spans in the Chrome trace event format.
+//!
+//! Every time a span is exited, a complete event (`"ph": "X"`) is written for the time it was
+//! entered, on the thread it was entered on. Trace viewers like Perfetto or `chrome://tracing`
+//! nest the events of a thread by their timing, and the ids of the span and its parent are part
+//! of the arguments of every event.
+//!
+//! The events are written as a JSON array to files in the configured directory. Once a file
+//! exceeds the configured size, it is closed and a new one is started. Every event is flushed
+//! right away, and the JSON array of the last file is terminated once the writer is finished,
+//! e.g. by dropping the [`ChromeTraceHandle`] when the service shuts down.
+
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// The default maximum size of a trace file, in bytes.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Configuration of the Chrome trace output.
+#[derive(Debug, Clone)]
+pub struct ChromeTraceConfig {
+	/// The directory to write the trace files to.
+	pub path: PathBuf,
+	/// The size in bytes after which a new trace file is started.
+	pub max_file_size: u64,
+}
+
+impl ChromeTraceConfig {
+	/// Create a configuration writing to the given directory, with the default maximum file size.
+	pub fn new(path: PathBuf) -> Self {
+		ChromeTraceConfig { path, max_file_size: DEFAULT_MAX_FILE_SIZE }
+	}
+}
+
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+	/// The id of the current thread in the trace, `0` if not assigned yet.
+	static THREAD_ID: Cell<u64> = Cell::new(0);
+}
+
+/// Returns the id of the current thread in the trace.
+fn thread_id() -> u64 {
+	THREAD_ID.with(|id| {
+		if id.get() == 0 {
+			id.set(NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed));
+		}
+		id.get()
+	})
+}
+
+/// An event of the Chrome trace event format.
+#[derive(Serialize)]
+struct TraceEvent<'a> {
+	name: &'a str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	cat: Option<&'a str>,
+	ph: &'static str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	ts: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	dur: Option<f64>,
+	pid: u32,
+	tid: u64,
+	args: Map<String, Value>,
+}
+
+/// A span that was entered and exited, to be written as a complete event.
+pub struct CompleteSpan<'a> {
+	/// Id of the span.
+	pub id: u64,
+	/// Id of the parent of the span, if any.
+	pub parent_id: Option<u64>,
+	/// Name of the span.
+	pub name: &'a str,
+	/// Target of the span.
+	pub target: &'a str,
+	/// Line of the span.
+	pub line: u32,
+	/// When the span was entered.
+	pub entered: Instant,
+	/// When the span was exited.
+	pub exited: Instant,
+	/// The values recorded for the span.
+	pub values: &'a [(String, String)],
+}
+
+/// Writes spans in the Chrome trace event format to rotated files.
+pub struct ChromeTraceWriter {
+	config: ChromeTraceConfig,
+	/// The instant all timestamps are relative to.
+	start: Instant,
+	/// Part of the names of all files of this writer, to not overwrite the files of earlier runs.
+	file_prefix: String,
+	file_index: u64,
+	file: BufWriter<File>,
+	file_size: u64,
+	/// The threads whose name was already written to the current file.
+	named_threads: HashSet<u64>,
+	/// Whether the writer was finished, after which no more spans are written.
+	finished: bool,
+}
+
+impl ChromeTraceWriter {
+	/// Create a writer with the given configuration, creating the first trace file.
+	pub fn new(config: ChromeTraceConfig) -> io::Result<Self> {
+		fs::create_dir_all(&config.path)?;
+		let file_prefix = format!(
+			"trace-{}-{}",
+			SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+			std::process::id(),
+		);
+		let file = Self::create_file(&config, &file_prefix, 0)?;
+
+		Ok(ChromeTraceWriter {
+			config,
+			start: Instant::now(),
+			file_prefix,
+			file_index: 0,
+			file,
+			file_size: 1,
+			named_threads: HashSet::new(),
+			finished: false,
+		})
+	}
+
+	fn create_file(config: &ChromeTraceConfig, prefix: &str, index: u64) -> io::Result<BufWriter<File>> {
+		let path = config.path.join(format!("{}-{}.json", prefix, index));
+		let mut file = BufWriter::new(File::create(path)?);
+		file.write_all(b"[")?;
+		Ok(file)
+	}
+
+	/// Write the given span as complete event of the current thread.
+	pub fn write_span(&mut self, span: CompleteSpan) -> io::Result<()> {
+		if self.finished {
+			return Ok(())
+		}
+		if self.file_size >= self.config.max_file_size {
+			self.rotate()?;
+		}
+
+		let tid = thread_id();
+		if self.named_threads.insert(tid) {
+			let mut args = Map::new();
+			let thread = std::thread::current();
+			args.insert(
+				"name".into(),
+				thread.name().map(|name| name.to_string()).unwrap_or_else(|| format!("thread-{}", tid)).into(),
+			);
+			self.write_event(&TraceEvent {
+				name: "thread_name",
+				cat: None,
+				ph: "M",
+				ts: None,
+				dur: None,
+				pid: std::process::id(),
+				tid,
+				args,
+			})?;
+		}
+
+		let mut args = Map::new();
+		args.insert("id".into(), span.id.into());
+		if let Some(parent_id) = span.parent_id {
+			args.insert("parent_id".into(), parent_id.into());
+		}
+		args.insert("line".into(), span.line.into());
+		for (key, value) in span.values {
+			args.insert(key.clone(), value.clone().into());
+		}
+
+		let ts = span.entered.saturating_duration_since(self.start).as_nanos() as f64 / 1000.0;
+		let dur = span.exited.saturating_duration_since(span.entered).as_nanos() as f64 / 1000.0;
+		self.write_event(&TraceEvent {
+			name: span.name,
+			cat: Some(span.target),
+			ph: "X",
+			ts: Some(ts),
+			dur: Some(dur),
+			pid: std::process::id(),
+			tid,
+			args,
+		})
+	}
+
+	fn write_event(&mut self, event: &TraceEvent) -> io::Result<()> {
+		let event = serde_json::to_vec(event)?;
+		if self.file_size > 1 {
+			self.file.write_all(b",\n")?;
+			self.file_size += 2;
+		}
+		self.file.write_all(&event)?;
+		self.file_size += event.len() as u64;
+		// The writer may never be dropped, e.g. when it is part of the global default subscriber.
+		self.file.flush()
+	}
+
+	/// Close the current file and start a new one.
+	fn rotate(&mut self) -> io::Result<()> {
+		self.close_file()?;
+		self.file_index += 1;
+		self.file = Self::create_file(&self.config, &self.file_prefix, self.file_index)?;
+		self.file_size = 1;
+		self.named_threads.clear();
+		Ok(())
+	}
+
+	/// Terminate the JSON array of the current file and stop writing spans.
+	///
+	/// Does nothing if the writer is already finished.
+	pub fn finish(&mut self) -> io::Result<()> {
+		if self.finished {
+			return Ok(())
+		}
+		self.finished = true;
+		self.close_file()
+	}
+
+	/// Terminate the JSON array of the current file and flush it.
+	fn close_file(&mut self) -> io::Result<()> {
+		self.file.write_all(b"]\n")?;
+		self.file.flush()
+	}
+}
+
+impl Drop for ChromeTraceWriter {
+	fn drop(&mut self) {
+		if let Err(e) = self.finish() {
+			log::warn!(target: "tracing", "Failed to finish the Chrome trace file: {}", e);
+		}
+	}
+}
+
+/// Finishes the Chrome trace of a subscriber once dropped.
+///
+/// The subscriber is usually installed as the global default and thus never dropped, so this
+/// handle is kept by the service instead.
+pub struct ChromeTraceHandle(pub(crate) Arc<Mutex<ChromeTraceWriter>>);
+
+impl ChromeTraceHandle {
+	/// Terminate the current trace file. Spans exited afterwards are not written anymore.
+	pub fn finish(&self) -> io::Result<()> {
+		self.0.lock().finish()
+	}
+}
+
+impl Drop for ChromeTraceHandle {
+	fn drop(&mut self) {
+		if let Err(e) = self.finish() {
+			log::warn!(target: "tracing", "Failed to finish the Chrome trace file: {}", e);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn span(id: u64, parent_id: Option<u64>, entered: Instant) -> CompleteSpan<'static> {
+		CompleteSpan {
+			id,
+			parent_id,
+			name: "span",
+			target: "test",
+			line: 1,
+			entered,
+			exited: Instant::now(),
+			values: &[],
+		}
+	}
+
+	#[test]
+	fn writes_valid_json_and_rotates_files() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut writer = ChromeTraceWriter::new(ChromeTraceConfig {
+			path: dir.path().to_path_buf(),
+			max_file_size: 256,
+		}).unwrap();
+
+		let now = Instant::now();
+		for id in 1..=10 {
+			writer.write_span(span(id, id.checked_sub(1).filter(|p| *p > 0), now)).unwrap();
+		}
+		drop(writer);
+
+		let mut files = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().path()).collect::<Vec<_>>();
+		assert!(files.len() > 1);
+		files.sort();
+
+		let mut spans = 0;
+		for file in files {
+			let events: Vec<Value> = serde_json::from_slice(&fs::read(file).unwrap()).unwrap();
+			// Every file names the thread, so it can be loaded on its own.
+			assert_eq!(events[0]["ph"], "M");
+			for event in events.iter().filter(|e| e["ph"] == "X") {
+				assert_eq!(event["args"]["id"].as_u64().unwrap() > 1, event["args"]["parent_id"].is_u64());
+				spans += 1;
+			}
+		}
+		assert_eq!(spans, 10);
+	}
+
+	#[test]
+	fn flushes_every_span_and_stops_writing_once_finished() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ChromeTraceConfig::new(dir.path().to_path_buf());
+		let writer = ChromeTraceWriter::new(config).unwrap();
+		let handle = ChromeTraceHandle(Arc::new(Mutex::new(writer)));
+		let file = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap().path();
+
+		handle.0.lock().write_span(span(1, None, Instant::now())).unwrap();
+		// The span is written while the JSON array is still open.
+		let mut content = fs::read(&file).unwrap();
+		content.extend_from_slice(b"]");
+		let events: Vec<Value> = serde_json::from_slice(&content).unwrap();
+		assert_eq!(events.len(), 2);
+
+		drop(handle);
+		let events: Vec<Value> = serde_json::from_slice(&fs::read(&file).unwrap()).unwrap();
+		assert_eq!(events.len(), 2);
+	}
+
+	#[test]
+	fn finished_writer_ignores_spans() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = ChromeTraceConfig::new(dir.path().to_path_buf());
+		let mut writer = ChromeTraceWriter::new(config).unwrap();
+		writer.finish().unwrap();
+		writer.write_span(span(1, None, Instant::now())).unwrap();
+		drop(writer);
+
+		let file = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap().path();
+		let events: Vec<Value> = serde_json::from_slice(&fs::read(&file).unwrap()).unwrap();
+		assert!(events.is_empty());
+	}
+}
@@ -22,11 +22,20 @@
 //!
 //! See `sp-tracing` for examples on how to use tracing.
 //!
-//! Currently we provide `Log` (default), `Telemetry` variants for `Receiver`
+//! Currently we provide `Log` (default), `Telemetry` and `ChromeTrace` variants for `Receiver`
+//!
+//! Spans of the wasm runtime are entered on the host through `sp_tracing::proxy`. They are
+//! recorded like native spans once the `wasm_tracing` target is enabled.
+//!
+//! The [`block`] module collects the spans, events and storage accesses of a single execution.
+
+pub mod block;
+mod chrome_trace;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
 use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
@@ -42,6 +51,10 @@ use tracing_core::{
 };
 
 use sc_telemetry::{telemetry, SUBSTRATE_INFO};
+use sp_tracing::proxy::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+
+pub use chrome_trace::{ChromeTraceConfig, ChromeTraceHandle, DEFAULT_MAX_FILE_SIZE};
+use chrome_trace::{ChromeTraceWriter, CompleteSpan};
 
 /// Used to configure how to receive the metrics
 #[derive(Debug, Clone)]
@@ -50,6 +63,8 @@ pub enum TracingReceiver {
 	Log,
 	/// Output to telemetry
 	Telemetry,
+	/// Output to files in the Chrome trace event format
+	ChromeTrace(ChromeTraceConfig),
 }
 
 impl Default for TracingReceiver {
@@ -58,11 +73,12 @@ impl Default for TracingReceiver {
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct SpanDatum {
 	id: u64,
-	name: &'static str,
-	target: &'static str,
+	parent_id: Option<u64>,
+	name: String,
+	target: String,
 	level: Level,
 	line: u32,
 	start_time: Instant,
@@ -86,6 +102,10 @@ impl Visit for Visitor {
 		self.record_debug(field, &value)
 	}
 
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.0.push((field.name().to_string(), value.to_string()));
+	}
+
 	fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
 		self.0.push((field.name().to_string(), format!("{:?}",value)));
 	}
@@ -137,6 +157,12 @@ pub struct ProfilingSubscriber {
 	targets: Vec<(String, Level)>,
 	receiver: TracingReceiver,
 	span_data: Mutex<HashMap<u64, SpanDatum>>,
+	chrome_trace: Option<Arc<Mutex<ChromeTraceWriter>>>,
+}
+
+thread_local! {
+	/// The ids of the spans entered on the current thread, innermost last.
+	static CURRENT_SPANS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
 }
 
 impl ProfilingSubscriber {
@@ -145,13 +171,35 @@ impl ProfilingSubscriber {
 	/// or without: "pallet".
 	pub fn new(receiver: TracingReceiver, targets: &str) -> Self {
 		let targets: Vec<_> = targets.split(',').map(|s| parse_target(s)).collect();
+		let chrome_trace = match &receiver {
+			TracingReceiver::ChromeTrace(config) => match ChromeTraceWriter::new(config.clone()) {
+				Ok(writer) => Some(Arc::new(Mutex::new(writer))),
+				Err(e) => {
+					log::error!(
+						target: "tracing",
+						"Unable to create Chrome trace files in {:?}: {}",
+						config.path,
+						e,
+					);
+					None
+				}
+			},
+			_ => None,
+		};
 		ProfilingSubscriber {
 			next_id: AtomicU64::new(1),
 			targets,
 			receiver,
 			span_data: Mutex::new(HashMap::new()),
+			chrome_trace,
 		}
 	}
+
+	/// Returns a handle that finishes the Chrome trace once dropped, if the `ChromeTrace`
+	/// receiver is used.
+	pub fn chrome_trace_handle(&self) -> Option<ChromeTraceHandle> {
+		self.chrome_trace.clone().map(ChromeTraceHandle)
+	}
 }
 
 // Default to TRACE if no level given or unable to parse Level
@@ -188,10 +236,28 @@ impl Subscriber for ProfilingSubscriber {
 		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
 		let mut values = Visitor(Vec::new());
 		attrs.record(&mut values);
+		let mut name = attrs.metadata().name().to_string();
+		let mut target = attrs.metadata().target().to_string();
+		// Spans of the wasm runtime carry their actual name and target as values.
+		if target == WASM_TRACE_IDENTIFIER {
+			values.0.retain(|(key, value)| match key.as_str() {
+				WASM_NAME_KEY => { name = value.clone(); false },
+				WASM_TARGET_KEY => { target = value.clone(); false },
+				_ => true,
+			});
+		}
+		let parent_id = if let Some(parent) = attrs.parent() {
+			Some(parent.into_u64())
+		} else if attrs.is_contextual() {
+			CURRENT_SPANS.with(|spans| spans.borrow().last().cloned())
+		} else {
+			None
+		};
 		let span_datum = SpanDatum {
 			id,
-			name: attrs.metadata().name(),
-			target: attrs.metadata().target(),
+			parent_id,
+			name,
+			target,
 			level: attrs.metadata().level().clone(),
 			line: attrs.metadata().line().unwrap_or(0),
 			start_time: Instant::now(),
@@ -209,6 +275,7 @@ impl Subscriber for ProfilingSubscriber {
 	fn event(&self, _event: &Event<'_>) {}
 
 	fn enter(&self, span: &Id) {
+		CURRENT_SPANS.with(|spans| spans.borrow_mut().push(span.into_u64()));
 		let mut span_data = self.span_data.lock();
 		let start_time = Instant::now();
 		if let Some(mut s) = span_data.get_mut(&span.into_u64()) {
@@ -219,10 +286,38 @@ impl Subscriber for ProfilingSubscriber {
 	}
 
 	fn exit(&self, span: &Id) {
-		let mut span_data = self.span_data.lock();
+		CURRENT_SPANS.with(|spans| {
+			let mut spans = spans.borrow_mut();
+			if let Some(pos) = spans.iter().rposition(|id| *id == span.into_u64()) {
+				spans.remove(pos);
+			}
+		});
 		let end_time = Instant::now();
-		if let Some(mut s) = span_data.get_mut(&span.into_u64()) {
-			s.overall_time = end_time - s.start_time + s.overall_time;
+		let exited = {
+			let mut span_data = self.span_data.lock();
+			span_data.get_mut(&span.into_u64()).and_then(|s| {
+				s.overall_time = end_time - s.start_time + s.overall_time;
+				// Only the Chrome trace needs a copy of the span.
+				self.chrome_trace.as_ref().map(|_| s.clone())
+			})
+		};
+
+		// The span is written without holding the lock of `span_data`, to not block the other
+		// threads on the file I/O.
+		if let (Some(chrome_trace), Some(s)) = (&self.chrome_trace, exited) {
+			let complete = CompleteSpan {
+				id: s.id,
+				parent_id: s.parent_id,
+				name: &s.name,
+				target: &s.target,
+				line: s.line,
+				entered: s.start_time,
+				exited: end_time,
+				values: &s.values.0,
+			};
+			if let Err(e) = chrome_trace.lock().write_span(complete) {
+				log::warn!(target: "tracing", "Unable to write span to Chrome trace: {}", e);
+			}
 		}
 	}
 
@@ -240,6 +335,8 @@ impl ProfilingSubscriber {
 		match self.receiver {
 			TracingReceiver::Log => print_log(span_datum),
 			TracingReceiver::Telemetry => send_telemetry(span_datum),
+			// Spans are written every time they are exited.
+			TracingReceiver::ChromeTrace(_) => (),
 		}
 	}
 }
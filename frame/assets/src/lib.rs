@@ -21,43 +21,82 @@
 //! ## Overview
 //!
 //! The Assets module provides functionality for asset management of fungible asset classes
-//! with a fixed supply, including:
+//! with a managed supply, including:
 //!
+//! * Asset Creation
 //! * Asset Issuance
-//! * Asset Transfer
+//! * Asset Transfer, directly or through an approved delegate
+//! * Asset Freezing, of single accounts or of the whole asset class
 //! * Asset Destruction
 //!
 //! To use it in your runtime, you need to implement the assets [`Trait`](./trait.Trait.html).
 //!
 //! The supported dispatchable functions are documented in the [`Call`](./enum.Call.html) enum.
 //!
+//! The module implements the [`fungibles`](../frame_support/traits/fungibles/index.html) traits,
+//! so that other modules, e.g. for fee payment or exchanges, can be generic over the assets.
+//!
 //! ### Terminology
 //!
-//! * **Asset issuance:** The creation of a new asset, whose total supply will belong to the
-//!   account that issues the asset.
-//! * **Asset transfer:** The action of transferring assets from one account to another.
-//! * **Asset destruction:** The process of an account removing its entire holding of an asset.
-//! * **Fungible asset:** An asset whose units are interchangeable.
-//! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
+//! * **Admin**: An account ID uniquely privileged to be able to unfreeze (thaw) an account and its
+//!   assets, as well as forcibly transfer a particular class of assets between arbitrary accounts
+//!   and reduce the balance of a particular class of assets of arbitrary accounts.
+//! * **Asset issuance/minting**: The creation of a new asset, whose total supply will belong to the
+//!   account that issues the asset. This is a privileged operation.
+//! * **Asset transfer**: The reduction of the balance of an asset of one account with the
+//!   corresponding increase in the balance of another.
+//! * **Asset burning**: The destruction of an asset balance. This is a privileged operation.
+//! * **Fungible asset**: An asset whose units are interchangeable.
+//! * **Issuer**: An account ID uniquely privileged to be able to mint a particular class of assets.
+//! * **Freezer**: An account ID uniquely privileged to be able to freeze an account from
+//!   transferring a particular class of assets.
+//! * **Freezing**: Removing the possibility of an unpermissioned transfer of an asset from a
+//!   particular account.
+//! * **Minimum balance**: The minimum balance required for an account to hold an asset. Like the
+//!   existential deposit of a currency, a holding below it is removed, and its dust is moved along
+//!   with the transfer or burnt.
+//! * **Owner**: An account ID uniquely privileged to be able to destroy a particular asset class,
+//!   or to set the Issuer, Freezer or Admin of that asset class. The owner places the deposits of
+//!   the asset class.
+//! * **Approval**: The permission given by an account to a delegate to transfer up to a given
+//!   amount of its assets.
+//! * **Metadata**: The name, symbol and number of decimals of an asset class, kept on-chain for a
+//!   deposit.
 //!
 //! ### Goals
 //!
 //! The assets system in Substrate is designed to make the following possible:
 //!
-//! * Issue a unique asset to its creator's account.
-//! * Move assets between accounts.
-//! * Remove an account's balance of an asset when requested by that account's owner and update
-//!   the asset's total supply.
+//! * Create a new asset class, with a dedicated team managing its supply.
+//! * Issue new assets in a permissioned or permissionless way, depending on the team.
+//! * Move assets between accounts, directly or by a delegate.
+//! * Freeze assets of an account, or of the whole asset class.
+//! * Burn assets of an account, and destroy the whole asset class.
 //!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
 //!
-//! * `issue` - Issues the total supply of a new fungible asset to the account of the caller of the function.
+//! * `create` - Creates a new asset class, taking the required deposit.
+//! * `force_create` - Creates a new asset class without a deposit; called by the `ForceOrigin`.
+//! * `destroy` - Destroys an entire asset class; called by the owner or the `ForceOrigin`.
+//! * `mint` - Increases the asset balance of an account; called by the asset class's Issuer.
+//! * `burn` - Decreases the asset balance of an account; called by the asset class's Admin.
 //! * `transfer` - Transfers an `amount` of units of fungible asset `id` from the balance of
 //! the function caller's account (`origin`) to a `target` account.
-//! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
-//! that called the function.
+//! * `force_transfer` - Transfers between arbitrary accounts; called by the asset class's Admin.
+//! * `freeze` - Disallows further `transfer`s from an account; called by the asset class's Freezer.
+//! * `thaw` - Allows further `transfer`s from an account; called by the asset class's Admin.
+//! * `freeze_asset` - Disallows further `transfer`s of the asset class; called by the Freezer.
+//! * `thaw_asset` - Allows further `transfer`s of the asset class; called by the Admin.
+//! * `transfer_ownership` - Changes an asset class's Owner; called by the asset class's Owner.
+//! * `set_team` - Changes an asset class's Admin, Freezer and Issuer; called by the asset class's
+//!   Owner.
+//! * `set_metadata` - Sets the name, symbol and decimals of an asset class; called by the asset
+//!   class's Owner.
+//! * `approve_transfer` - Allows a delegate to transfer up to an amount of the caller's assets.
+//! * `cancel_approval` - Revokes an approval of the caller.
+//! * `transfer_approved` - Transfers assets of an account that approved the caller as delegate.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
@@ -69,61 +108,6 @@
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
-//! ## Usage
-//!
-//! The following example shows how to use the Assets module in your runtime by exposing public functions to:
-//!
-//! * Issue a new fungible asset for a token distribution event (airdrop).
-//! * Query the fungible asset holding balance of an account.
-//! * Query the total supply of a fungible asset that has been issued.
-//!
-//! ### Prerequisites
-//!
-//! Import the Assets module and types and derive your runtime's configuration traits from the Assets module trait.
-//!
-//! ### Simple Code Snippet
-//!
-//! ```rust,ignore
-//! use pallet_assets as assets;
-//! use frame_support::{decl_module, dispatch, ensure};
-//! use frame_system::{self as system, ensure_signed};
-//!
-//! pub trait Trait: assets::Trait { }
-//!
-//! decl_module! {
-//! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-//! 		pub fn issue_token_airdrop(origin) -> dispatch::DispatchResult {
-//! 			let sender = ensure_signed(origin).map_err(|e| e.as_str())?;
-//!
-//! 			const ACCOUNT_ALICE: u64 = 1;
-//! 			const ACCOUNT_BOB: u64 = 2;
-//! 			const COUNT_AIRDROP_RECIPIENTS: u64 = 2;
-//! 			const TOKENS_FIXED_SUPPLY: u64 = 100;
-//!
-//! 			ensure!(!COUNT_AIRDROP_RECIPIENTS.is_zero(), "Divide by zero error.");
-//!
-//! 			let asset_id = Self::next_asset_id();
-//!
-//! 			<NextAssetId<T>>::mutate(|asset_id| *asset_id += 1);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_ALICE), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_BOB), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<TotalSupply<T>>::insert(asset_id, TOKENS_FIXED_SUPPLY);
-//!
-//! 			Self::deposit_event(RawEvent::Issued(asset_id, sender, TOKENS_FIXED_SUPPLY));
-//! 			Ok(())
-//! 		}
-//! 	}
-//! }
-//! ```
-//!
-//! ## Assumptions
-//!
-//! Below are assumptions that must be held when using this module.  If any of
-//! them are violated, the behavior of this module is undefined.
-//!
-//! * The total count of assets should be less than
-//!   `Trait::AssetId::max_value()`.
-//!
 //! ## Related Modules
 //!
 //! * [`System`](../frame_system/index.html)
@@ -132,10 +116,25 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure};
-use sp_runtime::traits::{Member, AtLeast32Bit, Zero, StaticLookup};
+#[cfg(test)]
+mod tests;
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode, HasCompact};
+use frame_support::type_info::TypeInfo;
+use frame_support::{
+	Parameter, IterableStorageDoubleMap, decl_module, decl_event, decl_storage, decl_error, ensure,
+};
+use frame_support::traits::{
+	Currency, ReservableCurrency, EnsureOrigin, Get, BalanceStatus::Reserved,
+	fungibles::{self, WithdrawConsequence, DepositConsequence},
+};
+use frame_support::weights::Weight;
+use sp_runtime::{RuntimeDebug, DispatchResult, DispatchError};
+use sp_runtime::traits::{Member, AtLeast32Bit, Zero, StaticLookup, CheckedAdd, CheckedSub, Saturating};
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::traits::One;
+
+type DepositBalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
 /// The module configuration trait.
 pub trait Trait: frame_system::Trait {
@@ -146,117 +145,765 @@ pub trait Trait: frame_system::Trait {
 	type Balance: Member + Parameter + AtLeast32Bit + Default + Copy;
 
 	/// The arithmetic type of asset identifier.
-	type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+	type AssetId: Member + Parameter + Default + Copy + HasCompact;
+
+	/// The currency mechanism, used for the deposits of the asset classes.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The origin which may forcibly create or destroy an asset.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The basic amount of funds that must be reserved when creating a new asset class.
+	type AssetDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The basic amount of funds that must be reserved when adding metadata to an asset class.
+	type MetadataDepositBase: Get<DepositBalanceOf<Self>>;
+
+	/// The additional funds that must be reserved for every byte of the name and symbol of an
+	/// asset class.
+	type MetadataDepositPerByte: Get<DepositBalanceOf<Self>>;
+
+	/// The amount of funds that must be reserved when creating a new approval.
+	type ApprovalDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The maximum length of a name or symbol stored on-chain.
+	type StringLimit: Get<u32>;
+}
+
+/// The details of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct AssetDetails<Balance, AccountId, DepositBalance> {
+	/// Can change `owner`, `issuer`, `freezer` and `admin` accounts.
+	owner: AccountId,
+	/// Can mint tokens.
+	issuer: AccountId,
+	/// Can thaw tokens, force transfers and burn tokens from any account.
+	admin: AccountId,
+	/// Can freeze tokens.
+	freezer: AccountId,
+	/// The total supply across all accounts.
+	supply: Balance,
+	/// The balance deposited for this asset class. This pays for the data stored here.
+	deposit: DepositBalance,
+	/// The minimum balance an account must hold to keep holding the asset.
+	min_balance: Balance,
+	/// The total number of accounts holding the asset.
+	accounts: u32,
+	/// Whether the asset is frozen for unprivileged transfers.
+	is_frozen: bool,
+}
+
+/// The balance of an account for an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, TypeInfo)]
+pub struct AssetBalance<Balance> {
+	/// The balance.
+	balance: Balance,
+	/// Whether the account is frozen for unprivileged transfers.
+	is_frozen: bool,
+}
+
+/// An approval for a delegate to transfer assets of an account.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, TypeInfo)]
+pub struct Approval<Balance, DepositBalance> {
+	/// The amount of funds approved for the balance transfer from the owner to some delegated
+	/// target.
+	amount: Balance,
+	/// The amount reserved on the owner's account to hold this item in storage.
+	deposit: DepositBalance,
+}
+
+/// The metadata of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, TypeInfo)]
+pub struct AssetMetadata<DepositBalance> {
+	/// The balance deposited for this metadata.
+	///
+	/// This pays for the data stored in this struct.
+	deposit: DepositBalance,
+	/// The user friendly name of this asset. Limited in length by `StringLimit`.
+	name: Vec<u8>,
+	/// The ticker symbol for this asset. Limited in length by `StringLimit`.
+	symbol: Vec<u8>,
+	/// The number of decimals this asset uses to represent one unit.
+	decimals: u8,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Assets {
+		/// Details of an asset class.
+		Asset: map hasher(blake2_128_concat) T::AssetId
+			=> Option<AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T>>>;
+
+		/// The number of units of assets held by any given account.
+		Account: double_map hasher(blake2_128_concat) T::AssetId, hasher(blake2_128_concat) T::AccountId
+			=> AssetBalance<T::Balance>;
+
+		/// Approved balance transfers. First balance is the amount approved for transfer. Second
+		/// is the amount of `T::Currency` reserved for storing this.
+		Approvals: double_map hasher(blake2_128_concat) T::AssetId,
+			hasher(blake2_128_concat) (T::AccountId, T::AccountId)
+			=> Option<Approval<T::Balance, DepositBalanceOf<T>>>;
+
+		/// Metadata of an asset class.
+		Metadata: map hasher(blake2_128_concat) T::AssetId => Option<AssetMetadata<DepositBalanceOf<T>>>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+		<T as Trait>::Balance,
+		<T as Trait>::AssetId,
+	{
+		/// Some asset class was created. \[asset_id, creator, owner\]
+		Created(AssetId, AccountId, AccountId),
+		/// Some asset class was force-created. \[asset_id, owner\]
+		ForceCreated(AssetId, AccountId),
+		/// An asset class was destroyed. \[asset_id\]
+		Destroyed(AssetId),
+		/// Some assets were issued. \[asset_id, owner, amount\]
+		Issued(AssetId, AccountId, Balance),
+		/// Some assets were transferred. \[asset_id, from, to, amount\]
+		Transferred(AssetId, AccountId, AccountId, Balance),
+		/// Some assets were destroyed. \[asset_id, owner, balance\]
+		Burned(AssetId, AccountId, Balance),
+		/// The management team changed. \[asset_id, issuer, admin, freezer\]
+		TeamChanged(AssetId, AccountId, AccountId, AccountId),
+		/// The owner changed. \[asset_id, owner\]
+		OwnerChanged(AssetId, AccountId),
+		/// Some account `who` was frozen. \[asset_id, who\]
+		Frozen(AssetId, AccountId),
+		/// Some account `who` was thawed. \[asset_id, who\]
+		Thawed(AssetId, AccountId),
+		/// Some asset class was frozen. \[asset_id\]
+		AssetFrozen(AssetId),
+		/// Some asset class was thawed. \[asset_id\]
+		AssetThawed(AssetId),
+		/// New metadata has been set for an asset class. \[asset_id, name, symbol, decimals\]
+		MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+		/// Metadata has been cleared for an asset class. \[asset_id\]
+		MetadataCleared(AssetId),
+		/// Funds of an account have been approved for transfer by a delegate.
+		/// \[asset_id, owner, delegate, amount\]
+		ApprovedTransfer(AssetId, AccountId, AccountId, Balance),
+		/// An approval for a delegate has been cancelled. \[asset_id, owner, delegate\]
+		ApprovalCancelled(AssetId, AccountId, AccountId),
+		/// A delegate transferred funds of an account. \[asset_id, owner, delegate, destination,
+		/// amount\]
+		TransferredApproved(AssetId, AccountId, AccountId, AccountId, Balance),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Transfer amount should be non-zero
+		AmountZero,
+		/// Account balance must be greater than or equal to the transfer amount
+		BalanceLow,
+		/// Balance should be non-zero
+		BalanceZero,
+		/// The signing account has no permission to do the operation.
+		NoPermission,
+		/// The given asset ID is unknown.
+		Unknown,
+		/// The origin account is frozen.
+		Frozen,
+		/// The asset ID is already taken.
+		InUse,
+		/// The number of accounts given as witness is too low.
+		BadWitness,
+		/// Minimum balance should be non-zero.
+		MinBalanceZero,
+		/// The asset supply or a balance would overflow.
+		Overflow,
+		/// Invalid metadata given.
+		BadMetadata,
+		/// No approval exists that would allow the transfer.
+		Unapproved,
+	}
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The basic amount of funds that must be reserved when creating a new asset class.
+		const AssetDeposit: DepositBalanceOf<T> = T::AssetDeposit::get();
+
+		/// The basic amount of funds that must be reserved when adding metadata to an asset class.
+		const MetadataDepositBase: DepositBalanceOf<T> = T::MetadataDepositBase::get();
+
+		/// The additional funds that must be reserved for every byte of metadata.
+		const MetadataDepositPerByte: DepositBalanceOf<T> = T::MetadataDepositPerByte::get();
+
+		/// The amount of funds that must be reserved when creating a new approval.
+		const ApprovalDeposit: DepositBalanceOf<T> = T::ApprovalDeposit::get();
+
+		/// The maximum length of a name or symbol stored on-chain.
+		const StringLimit: u32 = T::StringLimit::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
-		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
-		/// such assets and they'll all belong to the `origin` initially. It will have an
-		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
+
+		/// Issue a new class of fungible assets from a public origin.
+		///
+		/// This new asset class has no assets initially. `AssetDeposit` is reserved from the
+		/// origin, which becomes the owner of the asset class.
+		///
+		/// - `id`: The identifier of the new asset. This must not be currently in use to identify
+		///   an existing asset.
+		/// - `admin`: The admin of this class of assets, who is also its initial issuer and
+		///   freezer.
+		/// - `min_balance`: The minimum balance of this new asset that any single account must
+		///   have. If an account's balance is reduced below this, then it collapses to zero.
+		///
+		/// Emits `Created` event when successful.
 		///
 		/// # <weight>
 		/// - `O(1)`
-		/// - 1 storage mutation (codec `O(1)`).
-		/// - 2 storage writes (condec `O(1)`).
-		/// - 1 event.
+		/// - 1 storage read and write, 1 reserve.
 		/// # </weight>
-		#[weight = 0]
-		fn issue(origin, #[compact] total: T::Balance) {
-			let origin = ensure_signed(origin)?;
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn create(origin,
+			#[compact] id: T::AssetId,
+			admin: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+		) {
+			let owner = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
+
+			ensure!(!Asset::<T>::contains_key(id), Error::<T>::InUse);
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			let deposit = T::AssetDeposit::get();
+			T::Currency::reserve(&owner, deposit)?;
+
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: admin.clone(),
+				admin: admin.clone(),
+				freezer: admin.clone(),
+				supply: Zero::zero(),
+				deposit,
+				min_balance,
+				accounts: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::Created(id, owner, admin));
+		}
+
+		/// Issue a new class of fungible assets from a privileged origin, without a deposit.
+		///
+		/// The origin must conform to `ForceOrigin`.
+		///
+		/// - `id`: The identifier of the new asset. This must not be currently in use.
+		/// - `owner`: The owner of this class of assets, who is also its initial issuer, admin and
+		///   freezer.
+		/// - `min_balance`: The minimum balance of this new asset that any single account must
+		///   have.
+		///
+		/// Emits `ForceCreated` event when successful.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read and write.
+		/// # </weight>
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn force_create(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+		) {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			ensure!(!Asset::<T>::contains_key(id), Error::<T>::InUse);
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: owner.clone(),
+				admin: owner.clone(),
+				freezer: owner.clone(),
+				supply: Zero::zero(),
+				deposit: Zero::zero(),
+				min_balance,
+				accounts: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::ForceCreated(id, owner));
+		}
+
+		/// Destroy a class of fungible assets, together with all of its balances and approvals.
+		///
+		/// The origin must be the owner of the asset class, or conform to `ForceOrigin`. All
+		/// deposits are returned.
+		///
+		/// - `id`: The identifier of the asset to be destroyed.
+		/// - `accounts_witness`: An upper bound of the number of accounts holding the asset.
+		///
+		/// Emits `Destroyed` event when successful.
+		///
+		/// # <weight>
+		/// - `O(A + P)` where `A` is `accounts_witness` and `P` the number of approvals.
+		/// # </weight>
+		#[weight = 60_000_000
+			+ T::DbWeight::get().reads_writes(2, 3)
+			+ (10_000_000 + T::DbWeight::get().writes(1)) * *accounts_witness as Weight]
+		fn destroy(origin, #[compact] id: T::AssetId, #[compact] accounts_witness: u32) {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
 
-			let id = Self::next_asset_id();
-			<NextAssetId<T>>::mutate(|id| *id += One::one());
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = maybe_check_owner {
+				ensure!(details.owner == check_owner, Error::<T>::NoPermission);
+			}
+			ensure!(details.accounts <= accounts_witness, Error::<T>::BadWitness);
 
-			<Balances<T>>::insert((id, &origin), total);
-			<TotalSupply<T>>::insert(id, total);
+			for ((owner, _), approval) in Approvals::<T>::drain_prefix(id) {
+				T::Currency::unreserve(&owner, approval.deposit);
+			}
+			Account::<T>::remove_prefix(id);
+			if let Some(metadata) = Metadata::<T>::take(id) {
+				T::Currency::unreserve(&details.owner, metadata.deposit);
+			}
+			T::Currency::unreserve(&details.owner, details.deposit);
+			Asset::<T>::remove(id);
 
-			Self::deposit_event(RawEvent::Issued(id, origin, total));
+			Self::deposit_event(RawEvent::Destroyed(id));
 		}
 
-		/// Move some assets from one holder to another.
+		/// Mint assets of a particular class.
+		///
+		/// The origin must be the issuer of the asset class.
+		///
+		/// - `id`: The identifier of the asset to have some amount minted.
+		/// - `beneficiary`: The account to be credited with the minted assets.
+		/// - `amount`: The amount of the asset to be minted. Must bring the balance of the
+		///   beneficiary to at least the minimum balance.
+		///
+		/// Emits `Issued` event when successful.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads and writes.
+		/// # </weight>
+		#[weight = 40_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn mint(origin,
+			#[compact] id: T::AssetId,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let origin = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			Self::do_mint(id, &beneficiary, amount, Some(origin))?;
+		}
+
+		/// Reduce the balance of `who` by as much as possible up to `amount` assets of `id`.
+		///
+		/// The origin must be the admin of the asset class.
+		///
+		/// Bails with `BalanceZero` if `who` does not hold the asset. If the remaining balance
+		/// would be below the minimum balance, it is burnt as well.
+		///
+		/// Emits `Burned` with the actual amount burned.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads and writes.
+		/// # </weight>
+		#[weight = 35_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn burn(origin,
+			#[compact] id: T::AssetId,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			Self::do_burn(id, &who, amount, Some(origin))?;
+		}
+
+		/// Move some assets from the sender account to another.
+		///
+		/// - `id`: The identifier of the asset to have some amount transferred.
+		/// - `target`: The account to be credited.
+		/// - `amount`: The amount by which the sender's balance of assets should be reduced and
+		///   `target`'s balance increased. The amount actually transferred may be slightly greater
+		///   in the case that the transfer would otherwise take the sender balance above zero but
+		///   below the minimum balance. Must be greater than zero.
+		///
+		/// Emits `Transferred` with the actual amount transferred.
 		///
 		/// # <weight>
 		/// - `O(1)`
 		/// - 1 static lookup
-		/// - 2 storage mutations (codec `O(1)`).
-		/// - 1 event.
+		/// - 3 storage reads and writes.
 		/// # </weight>
-		#[weight = 0]
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(3, 3)]
 		fn transfer(origin,
 			#[compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
-			#[compact] amount: T::Balance
+			#[compact] amount: T::Balance,
 		) {
 			let origin = ensure_signed(origin)?;
-			let origin_account = (id, origin.clone());
-			let origin_balance = <Balances<T>>::get(&origin_account);
 			let target = T::Lookup::lookup(target)?;
-			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
+			Self::do_transfer(id, &origin, &target, amount, None)?;
+		}
 
-			Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
-			<Balances<T>>::insert(origin_account, origin_balance - amount);
-			<Balances<T>>::mutate((id, target), |balance| *balance += amount);
+		/// Move some assets from one account to another, ignoring any freeze.
+		///
+		/// The origin must be the admin of the asset class.
+		///
+		/// Emits `Transferred` with the actual amount transferred.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 static lookups
+		/// - 3 storage reads and writes.
+		/// # </weight>
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		fn force_transfer(origin,
+			#[compact] id: T::AssetId,
+			source: <T::Lookup as StaticLookup>::Source,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let origin = ensure_signed(origin)?;
+			let source = T::Lookup::lookup(source)?;
+			let dest = T::Lookup::lookup(dest)?;
+			Self::do_transfer(id, &source, &dest, amount, Some(origin))?;
 		}
 
-		/// Destroy any assets of `id` owned by `origin`.
+		/// Disallow further unprivileged transfers from an account.
+		///
+		/// The origin must be the freezer of the asset class.
+		///
+		/// Emits `Frozen`.
 		///
 		/// # <weight>
 		/// - `O(1)`
-		/// - 1 storage mutation (codec `O(1)`).
-		/// - 1 storage deletion (codec `O(1)`).
-		/// - 1 event.
+		/// - 2 storage reads, 1 storage write.
 		/// # </weight>
-		#[weight = 0]
-		fn destroy(origin, #[compact] id: T::AssetId) {
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn freeze(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
 			let origin = ensure_signed(origin)?;
-			let balance = <Balances<T>>::take((id, &origin));
-			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
+			let who = T::Lookup::lookup(who)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.freezer, Error::<T>::NoPermission);
+			ensure!(Account::<T>::contains_key(id, &who), Error::<T>::BalanceZero);
 
-			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
-			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
+			Account::<T>::mutate(id, &who, |a| a.is_frozen = true);
+			Self::deposit_event(RawEvent::Frozen(id, who));
 		}
-	}
-}
 
-decl_event! {
-	pub enum Event<T> where
-		<T as frame_system::Trait>::AccountId,
-		<T as Trait>::Balance,
-		<T as Trait>::AssetId,
-	{
-		/// Some assets were issued.
-		Issued(AssetId, AccountId, Balance),
-		/// Some assets were transferred.
-		Transferred(AssetId, AccountId, AccountId, Balance),
-		/// Some assets were destroyed.
-		Destroyed(AssetId, AccountId, Balance),
-	}
-}
+		/// Allow unprivileged transfers from an account again.
+		///
+		/// The origin must be the admin of the asset class.
+		///
+		/// Emits `Thawed`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 1 storage write.
+		/// # </weight>
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn thaw(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
 
-decl_error! {
-	pub enum Error for Module<T: Trait> {
-		/// Transfer amount should be non-zero
-		AmountZero,
-		/// Account balance must be greater than or equal to the transfer amount
-		BalanceLow,
-		/// Balance should be non-zero
-		BalanceZero,
-	}
-}
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.admin, Error::<T>::NoPermission);
+			ensure!(Account::<T>::contains_key(id, &who), Error::<T>::BalanceZero);
 
-decl_storage! {
-	trait Store for Module<T: Trait> as Assets {
-		/// The number of units of assets held by any given account.
-		Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
-		/// The next asset identifier up for grabs.
-		NextAssetId get(fn next_asset_id): T::AssetId;
-		/// The total unit supply of an asset.
-		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
+			Account::<T>::mutate(id, &who, |a| a.is_frozen = false);
+			Self::deposit_event(RawEvent::Thawed(id, who));
+		}
+
+		/// Disallow further unprivileged transfers for the asset class.
+		///
+		/// The origin must be the freezer of the asset class.
+		///
+		/// Emits `AssetFrozen`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read and write.
+		/// # </weight>
+		#[weight = 20_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn freeze_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.freezer, Error::<T>::NoPermission);
+				details.is_frozen = true;
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::AssetFrozen(id));
+		}
+
+		/// Allow unprivileged transfers for the asset class again.
+		///
+		/// The origin must be the admin of the asset class.
+		///
+		/// Emits `AssetThawed`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read and write.
+		/// # </weight>
+		#[weight = 20_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn thaw_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.admin, Error::<T>::NoPermission);
+				details.is_frozen = false;
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::AssetThawed(id));
+		}
+
+		/// Change the owner of an asset class.
+		///
+		/// The origin must be the owner of the asset class. The deposits of the asset class and
+		/// of its metadata are moved to the new owner.
+		///
+		/// Emits `OwnerChanged`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 1 storage write, 1 repatriation of reserved funds.
+		/// # </weight>
+		#[weight = 30_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn transfer_ownership(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.owner, Error::<T>::NoPermission);
+				if details.owner == owner {
+					return Ok(());
+				}
+
+				let metadata_deposit = Metadata::<T>::get(id).map_or(Zero::zero(), |m| m.deposit);
+				let deposit = details.deposit.saturating_add(metadata_deposit);
+				// Move the deposits to the new owner.
+				T::Currency::repatriate_reserved(&details.owner, &owner, deposit, Reserved)?;
+
+				details.owner = owner.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::OwnerChanged(id, owner));
+		}
+
+		/// Change the issuer, admin and freezer of an asset class.
+		///
+		/// The origin must be the owner of the asset class.
+		///
+		/// Emits `TeamChanged`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read and write.
+		/// # </weight>
+		#[weight = 25_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn set_team(origin,
+			#[compact] id: T::AssetId,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+		) {
+			let origin = ensure_signed(origin)?;
+			let issuer = T::Lookup::lookup(issuer)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.owner, Error::<T>::NoPermission);
+
+				details.issuer = issuer.clone();
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::TeamChanged(id, issuer, admin, freezer));
+		}
+
+		/// Set the metadata of an asset class.
+		///
+		/// The origin must be the owner of the asset class. Funds of the owner are reserved or
+		/// unreserved so that `MetadataDepositBase + MetadataDepositPerByte * (name.len +
+		/// symbol.len)` is held on deposit. Setting an empty name and symbol, and zero decimals,
+		/// clears the metadata and returns the deposit.
+		///
+		/// - `name`: The user friendly name of this asset. Limited in length by `StringLimit`.
+		/// - `symbol`: The ticker symbol for this asset. Limited in length by `StringLimit`.
+		/// - `decimals`: The number of decimals this asset uses to represent one unit.
+		///
+		/// Emits `MetadataSet` or `MetadataCleared`.
+		///
+		/// # <weight>
+		/// - `O(N + S)` where `N` and `S` are the length of the name and the symbol.
+		/// - 2 storage reads, 1 storage write.
+		/// # </weight>
+		#[weight = 30_000_000
+			+ 1_000 * (name.len() + symbol.len()) as Weight
+			+ T::DbWeight::get().reads_writes(2, 1)]
+		fn set_metadata(origin,
+			#[compact] id: T::AssetId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) {
+			let origin = ensure_signed(origin)?;
+
+			let limit = T::StringLimit::get() as usize;
+			ensure!(name.len() <= limit, Error::<T>::BadMetadata);
+			ensure!(symbol.len() <= limit, Error::<T>::BadMetadata);
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.owner, Error::<T>::NoPermission);
+
+			let old_deposit = Metadata::<T>::get(id).map_or(Zero::zero(), |m| m.deposit);
+			if name.is_empty() && symbol.is_empty() && decimals.is_zero() {
+				T::Currency::unreserve(&origin, old_deposit);
+				Metadata::<T>::remove(id);
+				Self::deposit_event(RawEvent::MetadataCleared(id));
+				return Ok(());
+			}
+
+			let bytes_used = (name.len() + symbol.len()) as u32;
+			let new_deposit = T::MetadataDepositBase::get()
+				.saturating_add(T::MetadataDepositPerByte::get().saturating_mul(bytes_used.into()));
+			if new_deposit > old_deposit {
+				T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+			} else {
+				T::Currency::unreserve(&origin, old_deposit - new_deposit);
+			}
+
+			Metadata::<T>::insert(id, AssetMetadata {
+				deposit: new_deposit,
+				name: name.clone(),
+				symbol: symbol.clone(),
+				decimals,
+			});
+			Self::deposit_event(RawEvent::MetadataSet(id, name, symbol, decimals));
+		}
+
+		/// Approve an amount of assets of the sender to be transferred by a delegate.
+		///
+		/// `ApprovalDeposit` is reserved from the sender if no approval for the delegate exists
+		/// yet. If it exists, `amount` is added to the already approved amount.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `delegate`: The account to delegate permission to transfer the assets.
+		/// - `amount`: The amount of assets that the delegate may additionally transfer.
+		///
+		/// Emits `ApprovedTransfer`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 1 storage write, 1 reserve.
+		/// # </weight>
+		#[weight = 40_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn approve_transfer(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			ensure!(Asset::<T>::contains_key(id), Error::<T>::Unknown);
+
+			Approvals::<T>::try_mutate(id, (owner.clone(), delegate.clone()), |maybe_approved| -> DispatchResult {
+				let mut approved = maybe_approved.take().unwrap_or_default();
+				let deposit_required = T::ApprovalDeposit::get();
+				if approved.deposit < deposit_required {
+					T::Currency::reserve(&owner, deposit_required - approved.deposit)?;
+					approved.deposit = deposit_required;
+				}
+				approved.amount = approved.amount.saturating_add(amount);
+				*maybe_approved = Some(approved);
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::ApprovedTransfer(id, owner, delegate, amount));
+		}
+
+		/// Cancel all of an approval of the sender for a delegate, and return its deposit.
+		///
+		/// Emits `ApprovalCancelled`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read and write, 1 unreserve.
+		/// # </weight>
+		#[weight = 35_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn cancel_approval(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let approval = Approvals::<T>::take(id, (owner.clone(), delegate.clone()))
+				.ok_or(Error::<T>::Unknown)?;
+			T::Currency::unreserve(&owner, approval.deposit);
+
+			Self::deposit_event(RawEvent::ApprovalCancelled(id, owner, delegate));
+		}
+
+		/// Transfer some assets of `owner`, which approved the sender as delegate, to
+		/// `destination`.
+		///
+		/// The approved amount is reduced by `amount`. If it reaches zero, the approval is removed
+		/// and its deposit returned to `owner`.
+		///
+		/// - `id`: The identifier of the asset.
+		/// - `owner`: The account which previously approved for a transfer of at least `amount`.
+		/// - `destination`: The account to which the assets will be transferred.
+		/// - `amount`: The amount of assets to transfer.
+		///
+		/// Emits `TransferredApproved`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 4 storage reads and writes, 1 unreserve.
+		/// # </weight>
+		#[weight = 60_000_000 + T::DbWeight::get().reads_writes(5, 5)]
+		fn transfer_approved(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			destination: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let delegate = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let destination = T::Lookup::lookup(destination)?;
+
+			Approvals::<T>::try_mutate(id, (owner.clone(), delegate.clone()), |maybe_approved| -> DispatchResult {
+				let mut approved = maybe_approved.take().ok_or(Error::<T>::Unapproved)?;
+				let remaining = approved.amount.checked_sub(&amount).ok_or(Error::<T>::Unapproved)?;
+
+				Self::do_transfer(id, &owner, &destination, amount, None)?;
+
+				if remaining.is_zero() {
+					T::Currency::unreserve(&owner, approved.deposit);
+				} else {
+					approved.amount = remaining;
+					*maybe_approved = Some(approved);
+				}
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::TransferredApproved(id, owner, delegate, destination, amount));
+		}
 	}
 }
 
@@ -266,160 +913,242 @@ impl<T: Trait> Module<T> {
 
 	/// Get the asset `id` balance of `who`.
 	pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
-		<Balances<T>>::get((id, who))
+		Account::<T>::get(id, who).balance
 	}
 
 	/// Get the total supply of an asset `id`.
 	pub fn total_supply(id: T::AssetId) -> T::Balance {
-		<TotalSupply<T>>::get(id)
+		Asset::<T>::get(id).map(|x| x.supply).unwrap_or_else(Zero::zero)
 	}
-}
-
-#[cfg(test)]
-mod tests {
-	use super::*;
-
-	use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight};
-	use sp_core::H256;
-	// The testing primitives are very useful for avoiding having to work with signatures
-	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
-	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
 
-	impl_outer_origin! {
-		pub enum Origin for Test  where system = frame_system {}
+	/// Whether `amount` of asset `id` can be deposited into the account of `who`.
+	fn can_increase(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> DepositConsequence {
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return DepositConsequence::UnknownAsset,
+		};
+		if details.supply.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow;
+		}
+		let account = Account::<T>::get(id, who);
+		if account.balance.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow;
+		}
+		if account.balance.is_zero() && amount < details.min_balance {
+			return DepositConsequence::BelowMinimum;
+		}
+		DepositConsequence::Success
 	}
 
-	// For testing the pallet, we construct most of a mock runtime. This means
-	// first constructing a configuration type (`Test`) which `impl`s each of the
-	// configuration traits of pallets we want to use.
-	#[derive(Clone, Eq, PartialEq)]
-	pub struct Test;
-	parameter_types! {
-		pub const BlockHashCount: u64 = 250;
-		pub const MaximumBlockWeight: Weight = 1024;
-		pub const MaximumBlockLength: u32 = 2 * 1024;
-		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	/// Whether `amount` of asset `id` can be withdrawn from the account of `who`.
+	fn can_decrease(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> WithdrawConsequence<T::Balance> {
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return WithdrawConsequence::UnknownAsset,
+		};
+		if details.supply.checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow;
+		}
+		let account = Account::<T>::get(id, who);
+		match account.balance.checked_sub(&amount) {
+			None => WithdrawConsequence::NoFunds,
+			Some(rest) if rest < details.min_balance && !rest.is_zero() =>
+				WithdrawConsequence::ReducedToZero(rest),
+			Some(_) => WithdrawConsequence::Success,
+		}
 	}
-	impl frame_system::Trait for Test {
-		type Origin = Origin;
-		type Index = u64;
-		type Call = ();
-		type BlockNumber = u64;
-		type Hash = H256;
-		type Hashing = BlakeTwo256;
-		type AccountId = u64;
-		type Lookup = IdentityLookup<Self::AccountId>;
-		type Header = Header;
-		type Event = ();
-		type BlockHashCount = BlockHashCount;
-		type MaximumBlockWeight = MaximumBlockWeight;
-		type DbWeight = ();
-		type BlockExecutionWeight = ();
-		type ExtrinsicBaseWeight = ();
-		type AvailableBlockRatio = AvailableBlockRatio;
-		type MaximumBlockLength = MaximumBlockLength;
-		type Version = ();
-		type ModuleToIndex = ();
-		type AccountData = ();
-		type OnNewAccount = ();
-		type OnKilledAccount = ();
+
+	/// Increase the balance of `beneficiary` by `amount` of asset `id`.
+	///
+	/// If `maybe_check_issuer` is `Some`, it must be the issuer of the asset.
+	fn do_mint(
+		id: T::AssetId,
+		beneficiary: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_issuer: Option<T::AccountId>,
+	) -> DispatchResult {
+		Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			if let Some(check_issuer) = maybe_check_issuer {
+				ensure!(check_issuer == details.issuer, Error::<T>::NoPermission);
+			}
+
+			details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+
+			let mut account = Account::<T>::get(id, beneficiary);
+			let new_balance = account.balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+			if account.balance.is_zero() {
+				details.accounts = details.accounts.saturating_add(1);
+			}
+			account.balance = new_balance;
+			Account::<T>::insert(id, beneficiary, account);
+			Ok(())
+		})?;
+		Self::deposit_event(RawEvent::Issued(id, beneficiary.clone(), amount));
+		Ok(())
 	}
-	impl Trait for Test {
-		type Event = ();
-		type Balance = u64;
-		type AssetId = u32;
+
+	/// Reduce the balance of `who` by as much as possible up to `amount` of asset `id`, and
+	/// return the amount actually burned.
+	///
+	/// If the remaining balance would be below the minimum balance, it is burned as well. If
+	/// `maybe_check_admin` is `Some`, it must be the admin of the asset.
+	fn do_burn(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_admin: Option<T::AccountId>,
+	) -> Result<T::Balance, DispatchError> {
+		let burned = Asset::<T>::try_mutate(id, |maybe_details| -> Result<T::Balance, DispatchError> {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			if let Some(check_admin) = maybe_check_admin {
+				ensure!(check_admin == details.admin, Error::<T>::NoPermission);
+			}
+			ensure!(Account::<T>::contains_key(id, who), Error::<T>::BalanceZero);
+
+			let mut account = Account::<T>::get(id, who);
+			let mut burned = amount.min(account.balance);
+			account.balance -= burned;
+			if account.balance < details.min_balance {
+				burned += account.balance;
+				details.accounts = details.accounts.saturating_sub(1);
+				Account::<T>::remove(id, who);
+			} else {
+				Account::<T>::insert(id, who, account);
+			}
+
+			details.supply = details.supply.saturating_sub(burned);
+			Ok(burned)
+		})?;
+		Self::deposit_event(RawEvent::Burned(id, who.clone(), burned));
+		Ok(burned)
 	}
-	type Assets = Module<Test>;
 
-	// This function basically just builds a genesis storage key/value store according to
-	// our desired mockup.
-	fn new_test_ext() -> sp_io::TestExternalities {
-		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	/// Move `amount` of asset `id` from `source` to `dest`, and return the amount actually
+	/// transferred.
+	///
+	/// If the remaining balance of `source` would be below the minimum balance, it is
+	/// transferred as well. If `maybe_need_admin` is `Some`, it must be the admin of the asset,
+	/// and any freeze is ignored.
+	fn do_transfer(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		maybe_need_admin: Option<T::AccountId>,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+
+		let mut source_account = Account::<T>::get(id, source);
+		ensure!(source_account.balance >= amount, Error::<T>::BalanceLow);
+
+		let transferred = Asset::<T>::try_mutate(id, |maybe_details| -> Result<T::Balance, DispatchError> {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			if let Some(need_admin) = maybe_need_admin {
+				ensure!(need_admin == details.admin, Error::<T>::NoPermission);
+			} else {
+				ensure!(!details.is_frozen && !source_account.is_frozen, Error::<T>::Frozen);
+			}
+
+			if source == dest {
+				return Ok(amount);
+			}
+
+			// Take the dust along, if the source would be left below the minimum balance.
+			let mut amount = amount;
+			if source_account.balance - amount < details.min_balance {
+				amount = source_account.balance;
+			}
+
+			let mut dest_account = Account::<T>::get(id, dest);
+			let new_dest_balance = dest_account.balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			ensure!(new_dest_balance >= details.min_balance, Error::<T>::BalanceLow);
+			if dest_account.balance.is_zero() {
+				details.accounts = details.accounts.saturating_add(1);
+			}
+			dest_account.balance = new_dest_balance;
+
+			source_account.balance -= amount;
+			if source_account.balance.is_zero() {
+				details.accounts = details.accounts.saturating_sub(1);
+				Account::<T>::remove(id, source);
+			} else {
+				Account::<T>::insert(id, source, &source_account);
+			}
+			Account::<T>::insert(id, dest, dest_account);
+			Ok(amount)
+		})?;
+
+		Self::deposit_event(RawEvent::Transferred(id, source.clone(), dest.clone(), transferred));
+		Ok(transferred)
 	}
+}
+
+impl<T: Trait> fungibles::Inspect<<T as frame_system::Trait>::AccountId> for Module<T> {
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
 
-	#[test]
-	fn issuing_asset_units_to_issuer_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-		});
+	fn total_issuance(asset: Self::AssetId) -> Self::Balance {
+		Self::total_supply(asset)
 	}
 
-	#[test]
-	fn querying_total_supply_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 31));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 19);
-			assert_eq!(Assets::balance(0, 3), 31);
-			assert_ok!(Assets::destroy(Origin::signed(3), 0));
-			assert_eq!(Assets::total_supply(0), 69);
-		});
+	fn minimum_balance(asset: Self::AssetId) -> Self::Balance {
+		Asset::<T>::get(asset).map(|x| x.min_balance).unwrap_or_else(Zero::zero)
 	}
 
-	#[test]
-	fn transferring_amount_above_available_balance_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-		});
+	fn balance(asset: Self::AssetId, who: &<T as frame_system::Trait>::AccountId) -> Self::Balance {
+		Account::<T>::get(asset, who).balance
 	}
 
-	#[test]
-	fn transferring_amount_more_than_available_balance_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
-			assert_eq!(Assets::balance(0, 1), 50);
-			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
-			assert_eq!(Assets::balance(0, 1), 0);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 1, 50), Error::<Test>::BalanceLow);
-		});
+	fn can_deposit(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Trait>::AccountId,
+		amount: Self::Balance,
+	) -> DepositConsequence {
+		Self::can_increase(asset, who, amount)
 	}
 
-	#[test]
-	fn transferring_less_than_one_unit_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
-		});
+	fn can_withdraw(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Trait>::AccountId,
+		amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		Self::can_decrease(asset, who, amount)
 	}
+}
 
-	#[test]
-	fn transferring_more_units_than_total_supply_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 101), Error::<Test>::BalanceLow);
-		});
+impl<T: Trait> fungibles::Mutate<<T as frame_system::Trait>::AccountId> for Module<T> {
+	fn deposit(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Trait>::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Self::can_increase(asset, who, amount).into_result()?;
+		Self::do_mint(asset, who, amount, None)
 	}
 
-	#[test]
-	fn destroying_asset_balance_with_positive_balance_should_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
-		});
+	fn withdraw(
+		asset: Self::AssetId,
+		who: &<T as frame_system::Trait>::AccountId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		Self::can_decrease(asset, who, amount).into_result()?;
+		Self::do_burn(asset, who, amount, None)
 	}
+}
 
-	#[test]
-	fn destroying_asset_balance_with_zero_balance_should_not_work() {
-		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 2), 0);
-			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::BalanceZero);
-		});
+impl<T: Trait> fungibles::Transfer<<T as frame_system::Trait>::AccountId> for Module<T> {
+	fn transfer(
+		asset: Self::AssetId,
+		source: &<T as frame_system::Trait>::AccountId,
+		dest: &<T as frame_system::Trait>::AccountId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		Self::do_transfer(asset, source, dest, amount, None)
 	}
 }
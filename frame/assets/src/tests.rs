@@ -0,0 +1,389 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Assets pallet tests.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight};
+use frame_support::traits::fungibles::{Inspect, Mutate, Transfer};
+use sp_core::H256;
+use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+impl Trait for Test {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+}
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Assets = Module<Test>;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
+
+#[test]
+fn basic_minting_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert_eq!(Assets::total_supply(0), 200);
+		assert_noop!(Assets::mint(Origin::signed(2), 0, 2, 100), Error::<Test>::NoPermission);
+	});
+}
+
+#[test]
+fn create_should_reserve_deposit_and_destroy_should_return_it() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_noop!(Assets::create(Origin::signed(2), 0, 2, 1), Error::<Test>::InUse);
+		assert_noop!(Assets::create(Origin::signed(2), 1, 2, 0), Error::<Test>::MinBalanceZero);
+
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 10], 12));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Balances::reserved_balance(&1), 1 + 21 + 1);
+
+		assert_noop!(Assets::destroy(Origin::signed(2), 0, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::destroy(Origin::signed(1), 0, 0), Error::<Test>::BadWitness);
+		assert_ok!(Assets::destroy(Origin::signed(1), 0, 1));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert!(!Asset::<Test>::contains_key(0));
+		assert!(!Metadata::<Test>::contains_key(0));
+		assert!(!Approvals::<Test>::contains_key(0, (1, 2)));
+	});
+}
+
+#[test]
+fn force_destroy_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+		assert_ok!(Assets::destroy(Origin::root(), 0, 1));
+		assert_eq!(Assets::balance(0, 2), 0);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+	});
+}
+
+#[test]
+fn min_balance_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 1);
+
+		// Cannot create a new account with a balance that is below minimum...
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 2, 9), Error::<Test>::BalanceLow);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 9), Error::<Test>::BalanceLow);
+
+		// When deducting from an account to below minimum, it should be reaped.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 91));
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 1);
+
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 2, 91));
+		assert_eq!(Assets::balance(0, 2), 0);
+		assert_eq!(Assets::total_supply(0), 0);
+		assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 0);
+	});
+}
+
+#[test]
+fn transferring_frozen_user_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::freeze(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+		// The admin may still move frozen funds.
+		assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 1, 2, 10));
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 2), 60);
+	});
+}
+
+#[test]
+fn transferring_frozen_asset_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+		assert_noop!(Assets::thaw_asset(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+	});
+}
+
+#[test]
+fn origin_guards_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_noop!(Assets::transfer_ownership(Origin::signed(2), 0, 2), Error::<Test>::NoPermission);
+		assert_noop!(Assets::set_team(Origin::signed(2), 0, 2, 2, 2), Error::<Test>::NoPermission);
+		assert_noop!(Assets::freeze(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+		assert_noop!(Assets::thaw(Origin::signed(2), 0, 2), Error::<Test>::NoPermission);
+		assert_noop!(Assets::mint(Origin::signed(2), 0, 2, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::burn(Origin::signed(2), 0, 1, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::force_transfer(Origin::signed(2), 0, 1, 2, 100), Error::<Test>::NoPermission);
+		assert_noop!(Assets::destroy(Origin::signed(2), 0, 100), Error::<Test>::NoPermission);
+	});
+}
+
+#[test]
+fn set_team_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::set_team(Origin::signed(1), 0, 2, 3, 4));
+
+		assert_ok!(Assets::mint(Origin::signed(2), 0, 2, 100));
+		assert_ok!(Assets::freeze(Origin::signed(4), 0, 2));
+		assert_ok!(Assets::thaw(Origin::signed(3), 0, 2));
+		assert_ok!(Assets::force_transfer(Origin::signed(3), 0, 2, 3, 100));
+		assert_ok!(Assets::burn(Origin::signed(3), 0, 3, 100));
+	});
+}
+
+#[test]
+fn transfer_ownership_should_move_deposits() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create(Origin::signed(1), 0, 1, 1));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 5], vec![0u8; 5], 12));
+		assert_eq!(Balances::reserved_balance(&1), 12);
+
+		assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 12);
+
+		assert_noop!(Assets::transfer_ownership(Origin::signed(1), 0, 1), Error::<Test>::NoPermission);
+		assert_ok!(Assets::transfer_ownership(Origin::signed(2), 0, 1));
+		assert_eq!(Balances::reserved_balance(&1), 12);
+	});
+}
+
+#[test]
+fn transferring_amount_more_than_available_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(Assets::balance(0, 2), 50);
+		assert_ok!(Assets::burn(Origin::signed(1), 0, 1, u64::max_value()));
+		assert_eq!(Assets::balance(0, 1), 0);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 1, 50), Error::<Test>::BalanceLow);
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 1, 51), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn transferring_less_than_one_unit_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
+	});
+}
+
+#[test]
+fn burning_asset_balance_with_zero_balance_should_not_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_noop!(Assets::burn(Origin::signed(1), 0, 2, u64::max_value()), Error::<Test>::BalanceZero);
+	});
+}
+
+#[test]
+fn set_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		// Cannot add metadata to unknown asset
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 10], 12),
+			Error::<Test>::Unknown,
+		);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		// Cannot add metadata to unowned asset
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(2), 0, vec![0u8; 10], vec![0u8; 10], 12),
+			Error::<Test>::NoPermission,
+		);
+		// Cannot add oversized metadata
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 100], vec![0u8; 10], 12),
+			Error::<Test>::BadMetadata,
+		);
+
+		// Successfully add metadata and take deposit
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 10], 12));
+		assert_eq!(Balances::free_balance(&1), 79);
+
+		// Update deposit
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 5], 12));
+		assert_eq!(Balances::free_balance(&1), 84);
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 10], vec![0u8; 15], 12));
+		assert_eq!(Balances::free_balance(&1), 74);
+
+		// Clear metadata
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![], vec![], 0));
+		assert_eq!(Balances::free_balance(&1), 100);
+		assert!(!Metadata::<Test>::contains_key(0));
+	});
+}
+
+#[test]
+fn approval_lifecycle_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 51),
+			Error::<Test>::Unapproved,
+		);
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+		assert_eq!(Assets::balance(0, 1), 60);
+		assert_eq!(Assets::balance(0, 3), 40);
+		assert_eq!(Balances::reserved_balance(&1), 1);
+
+		// Exhausting the approval removes it and returns the deposit.
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 10));
+		assert_eq!(Assets::balance(0, 3), 50);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 1),
+			Error::<Test>::Unapproved,
+		);
+	});
+}
+
+#[test]
+fn cancel_approval_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+
+		assert_noop!(Assets::cancel_approval(Origin::signed(1), 0, 3), Error::<Test>::Unknown);
+		assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 1),
+			Error::<Test>::Unapproved,
+		);
+	});
+}
+
+#[test]
+fn fungibles_traits_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10));
+		assert_eq!(<Assets as Inspect<u64>>::minimum_balance(0), 10);
+		assert_eq!(<Assets as Inspect<u64>>::can_deposit(0, &1, 5), DepositConsequence::BelowMinimum);
+		assert_eq!(<Assets as Inspect<u64>>::can_deposit(1, &1, 5), DepositConsequence::UnknownAsset);
+
+		assert_ok!(<Assets as Mutate<u64>>::deposit(0, &1, 100));
+		assert_eq!(<Assets as Inspect<u64>>::balance(0, &1), 100);
+		assert_eq!(<Assets as Inspect<u64>>::total_issuance(0), 100);
+
+		assert_eq!(<Assets as Inspect<u64>>::can_withdraw(0, &1, 101), WithdrawConsequence::NoFunds);
+		assert_eq!(<Assets as Inspect<u64>>::can_withdraw(0, &1, 95), WithdrawConsequence::ReducedToZero(5));
+		assert_eq!(<Assets as Inspect<u64>>::can_withdraw(0, &1, 90), WithdrawConsequence::Success);
+
+		assert_eq!(<Assets as Transfer<u64>>::transfer(0, &1, &2, 50), Ok(50));
+		assert_eq!(<Assets as Mutate<u64>>::withdraw(0, &2, 45), Ok(50));
+		assert_eq!(<Assets as Inspect<u64>>::balance(0, &2), 0);
+		assert_eq!(<Assets as Inspect<u64>>::total_issuance(0), 50);
+	});
+}
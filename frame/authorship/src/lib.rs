@@ -25,6 +25,7 @@ use sp_std::collections::btree_set::BTreeSet;
 use frame_support::{decl_module, decl_storage, decl_error, dispatch, ensure};
 use frame_support::traits::{FindAuthor, VerifySeal, Get};
 use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
 use frame_system::ensure_none;
 use sp_runtime::traits::{Header as HeaderT, One, Zero};
 use frame_support::weights::{Weight, DispatchClass};
@@ -143,7 +144,7 @@ where
 	}
 }
 
-#[derive(Encode, Decode, sp_runtime::RuntimeDebug)]
+#[derive(Encode, Decode, sp_runtime::RuntimeDebug, TypeInfo)]
 #[cfg_attr(any(feature = "std", test), derive(PartialEq))]
 enum UncleEntryItem<BlockNumber, Hash, Author> {
 	InclusionHeight(BlockNumber),
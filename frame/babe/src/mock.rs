@@ -17,27 +17,53 @@
 //! Test utilities
 
 use codec::Encode;
-use super::{Trait, Module, GenesisConfig, CurrentSlot};
+use super::{Trait, Module, CurrentSlot};
 use sp_runtime::{
 	Perbill, impl_opaque_keys,
-	testing::{Header, UintAuthorityId, Digest, DigestItem},
-	traits::IdentityLookup,
+	curve::PiecewiseLinear,
+	testing::{Digest, DigestItem, Header, TestXt},
+	traits::{Convert, Header as _, IdentityLookup, OpaqueKeys, SaturatedConversion},
 };
 use frame_system::InitKind;
 use frame_support::{
-	impl_outer_origin, parameter_types, StorageValue,
-	traits::OnInitialize,
+	impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types, StorageValue,
+	traits::{KeyOwnerProofSystem, OnInitialize},
 	weights::Weight,
 };
 use sp_io;
-use sp_core::{H256, U256, crypto::Pair};
-use sp_consensus_babe::AuthorityPair;
+use sp_core::{H256, U256, crypto::{KeyTypeId, Pair}};
+use sp_consensus_babe::{AuthorityId, AuthorityPair, SlotNumber};
 use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
+use sp_staking::SessionIndex;
+use pallet_staking::EraIndex;
+
+use frame_system as system;
+use pallet_balances as balances;
+use pallet_offences as offences;
+use pallet_session as session;
+use pallet_staking as staking;
 
 impl_outer_origin!{
 	pub enum Origin for Test where system = frame_system {}
 }
 
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		babe::Babe,
+		staking::Staking,
+	}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		balances<T>,
+		offences,
+		session,
+		staking<T>,
+	}
+}
+
 type DummyValidatorId = u64;
 
 // Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
@@ -59,14 +85,14 @@ impl frame_system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
 	type BlockNumber = u64;
-	type Call = ();
+	type Call = Call;
 	type Hash = H256;
 	type Version = ();
 	type Hashing = sp_runtime::traits::BlakeTwo256;
 	type AccountId = DummyValidatorId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type DbWeight = ();
@@ -75,39 +101,155 @@ impl frame_system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type MaximumBlockLength = MaximumBlockLength;
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = balances::AccountData<u128>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 }
 
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = TestXt<Call, ()>;
+}
+
 impl_opaque_keys! {
 	pub struct MockSessionKeys {
-		pub dummy: UintAuthorityId,
+		pub babe_authority: super::Module<Test>,
 	}
 }
 
-impl pallet_session::Trait for Test {
-	type Event = ();
+impl session::Trait for Test {
+	type Event = TestEvent;
 	type ValidatorId = <Self as frame_system::Trait>::AccountId;
 	type ShouldEndSession = Babe;
-	type SessionHandler = (Babe,);
-	type SessionManager = ();
-	type ValidatorIdOf = ();
+	type SessionHandler = <MockSessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type SessionManager = session::historical::NoteHistoricalRoot<Self, Staking>;
+	type ValidatorIdOf = staking::StashOf<Self>;
 	type Keys = MockSessionKeys;
 	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
 	type NextSessionRotation = Babe;
 }
 
+impl session::historical::Trait for Test {
+	type FullIdentification = staking::Exposure<u64, u128>;
+	type FullIdentificationOf = staking::ExposureOf<Self>;
+}
+
+parameter_types! {
+	pub const UncleGenerations: u64 = 0;
+}
+
+impl pallet_authorship::Trait for Test {
+	type FindAuthor = ();
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
 impl pallet_timestamp::Trait for Test {
 	type Moment = u64;
 	type OnTimestampSet = Babe;
 	type MinimumPeriod = MinimumPeriod;
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u128 = 1;
+}
+
+impl balances::Trait for Test {
+	type Balance = u128;
+	type DustRemoval = ();
+	type Event = TestEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+pallet_staking_reward_curve::build! {
+	const REWARD_CURVE: PiecewiseLinear<'static> = curve!(
+		min_inflation: 0_025_000u64,
+		max_inflation: 0_100_000,
+		ideal_stake: 0_500_000,
+		falloff: 0_050_000,
+		max_piece_count: 40,
+		test_precision: 0_005_000,
+	);
+}
+
+parameter_types! {
+	pub const SessionsPerEra: SessionIndex = 3;
+	pub const BondingDuration: EraIndex = 3;
+	pub const SlashDeferDuration: EraIndex = 0;
+	pub const AttestationPeriod: u64 = 100;
+	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
+	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominatorsInElection: u32 = 1_000;
+	pub const ElectionLookahead: u64 = 0;
+	pub const StakingUnsignedPriority: u64 = u64::max_value() / 2;
+}
+
+pub struct CurrencyToVoteHandler;
+
+impl Convert<u128, u128> for CurrencyToVoteHandler {
+	fn convert(x: u128) -> u128 {
+		x
+	}
+}
+
+impl Convert<u128, u64> for CurrencyToVoteHandler {
+	fn convert(x: u128) -> u64 {
+		x.saturated_into()
+	}
+}
+
+impl staking::Trait for Test {
+	type RewardRemainder = ();
+	type CurrencyToVote = CurrencyToVoteHandler;
+	type Event = TestEvent;
+	type Currency = Balances;
+	type Slash = ();
+	type Reward = ();
+	type SessionsPerEra = SessionsPerEra;
+	type BondingDuration = BondingDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	type SlashCancelOrigin = system::EnsureRoot<Self::AccountId>;
+	type SessionInterface = Self;
+	type UnixTime = pallet_timestamp::Module<Test>;
+	type RewardCurve = RewardCurve;
+	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type NextNewSession = Session;
+	type ElectionLookahead = ElectionLookahead;
+	type Call = Call;
+	type UnsignedPriority = StakingUnsignedPriority;
+	type OnStakerSlash = ();
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
+	type MaxIterations = ();
+}
+
+impl offences::Trait for Test {
+	type Event = TestEvent;
+	type IdentificationTuple = session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = Staking;
+}
+
 impl Trait for Test {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
 	type EpochChangeTrigger = crate::ExternalTrigger;
+
+	type KeyOwnerProofSystem = Historical;
+
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, AuthorityId)>>::Proof;
+
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		AuthorityId,
+	)>>::IdentificationTuple;
+
+	type HandleEquivocation = super::EquivocationHandler<Self::KeyOwnerIdentification, Offences>;
 }
 
 pub fn new_test_ext(authorities_len: usize) -> (Vec<AuthorityPair>, sp_io::TestExternalities) {
@@ -116,9 +258,42 @@ pub fn new_test_ext(authorities_len: usize) -> (Vec<AuthorityPair>, sp_io::TestE
 	}).collect::<Vec<_>>();
 
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
-	GenesisConfig {
-		authorities: pairs.iter().map(|a| (a.public(), 1)).collect(),
-	}.assimilate_storage::<Test>(&mut t).unwrap();
+
+	// stashes are the index.
+	let session_keys: Vec<_> = pairs
+		.iter()
+		.enumerate()
+		.map(|(i, p)| (i as u64, i as u64, MockSessionKeys { babe_authority: p.public() }))
+		.collect();
+
+	// controllers are the index + 1000
+	let stakers: Vec<_> = (0..authorities_len)
+		.map(|i| (i as u64, i as u64 + 1000, 10_000, staking::StakerStatus::<u64>::Validator))
+		.collect();
+
+	let balances: Vec<_> = (0..authorities_len)
+		.map(|i| (i as u64, 10_000_000))
+		.collect();
+
+	// NOTE: this will initialize the babe authorities
+	// through OneSessionHandler::on_genesis_session
+	session::GenesisConfig::<Test> { keys: session_keys }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	balances::GenesisConfig::<Test> { balances }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	staking::GenesisConfig::<Test> {
+		stakers,
+		validator_count: 8,
+		force_era: staking::Forcing::ForceNew,
+		minimum_validator_count: 0,
+		invulnerables: vec![],
+		..Default::default()
+	}.assimilate_storage(&mut t).unwrap();
+
 	(pairs, t.into())
 }
 
@@ -174,6 +349,62 @@ pub fn make_secondary_plain_pre_digest(
 	Digest { logs: vec![log] }
 }
 
+/// Progress block by block until the given session starts. Sessions are
+/// coupled to BABE epochs, so this will go through at least `EpochDuration`
+/// slots per session.
+pub fn start_session(session_index: SessionIndex) {
+	while Session::current_index() < session_index {
+		progress_to_block(System::block_number() + 1);
+	}
+
+	assert_eq!(Session::current_index(), session_index);
+}
+
+pub fn start_era(era_index: EraIndex) {
+	start_session((era_index * 3).into());
+	assert_eq!(Staking::current_era(), Some(era_index));
+}
+
+pub fn generate_equivocation_proof(
+	offender_authority_index: u32,
+	offender_authority_pair: &AuthorityPair,
+	slot_number: SlotNumber,
+) -> sp_consensus_babe::EquivocationProof<Header> {
+	use sp_consensus_babe::digests::CompatibleDigestItem;
+
+	let current_block = System::block_number();
+	let pre_digest = make_secondary_plain_pre_digest(offender_authority_index, slot_number);
+
+	// generate two headers at the current block, with the same pre-digest
+	// but different extrinsics roots, and seal them with the offender's key
+	let make_header = || {
+		let mut header = Header::new(
+			current_block,
+			H256::random(),
+			Default::default(),
+			System::parent_hash(),
+			pre_digest.clone(),
+		);
+
+		let seal = <DigestItem as CompatibleDigestItem>::babe_seal(
+			offender_authority_pair.sign(header.hash().as_ref()),
+		);
+		header.digest_mut().push(seal);
+		header
+	};
+
+	sp_consensus_babe::EquivocationProof {
+		slot_number,
+		offender: offender_authority_pair.public(),
+		first_header: make_header(),
+		second_header: make_header(),
+	}
+}
+
+pub type Balances = pallet_balances::Module<Test>;
+pub type Historical = pallet_session::historical::Module<Test>;
+pub type Offences = pallet_offences::Module<Test>;
+pub type Session = pallet_session::Module<Test>;
+pub type Staking = pallet_staking::Module<Test>;
 pub type System = frame_system::Module<Test>;
 pub type Babe = Module<Test>;
-pub type Session = pallet_session::Module<Test>;
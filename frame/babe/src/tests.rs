@@ -18,10 +18,18 @@
 
 use super::*;
 use mock::*;
-use frame_support::traits::OnFinalize;
+use frame_support::{
+	assert_err, assert_ok,
+	traits::{Currency, OnFinalize},
+	unsigned::ValidateUnsigned,
+};
 use pallet_session::ShouldEndSession;
-use sp_core::crypto::IsWrappedBy;
+use sp_core::crypto::{IsWrappedBy, Pair};
 use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
+use sp_runtime::transaction_validity::{
+	InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource,
+	TransactionValidity, ValidTransaction,
+};
 
 const EMPTY_RANDOMNESS: [u8; 32] = [
 	74, 25, 49, 128, 53, 97, 244, 49,
@@ -131,7 +139,7 @@ fn authority_index() {
 
 #[test]
 fn can_predict_next_epoch_change() {
-	new_test_ext(0).1.execute_with(|| {
+	new_test_ext(1).1.execute_with(|| {
 		assert_eq!(<Test as Trait>::EpochDuration::get(), 3);
 		// this sets the genesis slot to 6;
 		go_to_block(1, 6);
@@ -149,3 +157,386 @@ fn can_predict_next_epoch_change() {
 		assert_eq!(Babe::next_expected_epoch_change(System::block_number()), Some(5 + 2));
 	})
 }
+
+#[test]
+fn report_equivocation_current_session_works() {
+	let (pairs, mut ext) = new_test_ext(3);
+
+	ext.execute_with(|| {
+		start_era(1);
+
+		let authorities = Babe::authorities();
+		let validators = Session::validators();
+
+		// make sure that all authorities have the same balance
+		for validator in &validators {
+			assert_eq!(Balances::total_balance(validator), 10_000_000);
+			assert_eq!(Staking::slashable_balance_of(validator), 10_000);
+
+			assert_eq!(
+				Staking::eras_stakers(1, validator),
+				pallet_staking::Exposure {
+					total: 10_000,
+					own: 10_000,
+					others: vec![],
+				},
+			);
+		}
+
+		// we will use the validator at index 0 as the offending authority
+		let offending_validator_index = 0;
+		let offending_validator_id = Session::validators()[offending_validator_index];
+		let offending_authority_pair = pairs
+			.into_iter()
+			.find(|p| p.public() == authorities[offending_validator_index].0)
+			.unwrap();
+
+		// generate an equivocation proof. it creates two headers at the given
+		// slot with different block hashes and signed by the given key
+		let equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+
+		// create the key ownership proof
+		let key_owner_proof = Historical::prove((
+			sp_consensus_babe::KEY_TYPE,
+			&offending_authority_pair.public(),
+		)).unwrap();
+
+		// report the equivocation
+		assert_ok!(Babe::report_equivocation_unsigned(
+			Origin::NONE,
+			equivocation_proof,
+			key_owner_proof,
+		));
+
+		start_era(2);
+
+		// check that the balance of offending validator is slashed 100%.
+		assert_eq!(Balances::total_balance(&offending_validator_id), 10_000_000 - 10_000);
+		assert_eq!(Staking::slashable_balance_of(&offending_validator_id), 0);
+		assert_eq!(
+			Staking::eras_stakers(2, offending_validator_id),
+			pallet_staking::Exposure {
+				total: 0,
+				own: 0,
+				others: vec![],
+			},
+		);
+
+		// check that the balances of all other validators are left intact.
+		for validator in &validators {
+			if *validator == offending_validator_id {
+				continue;
+			}
+
+			assert_eq!(Balances::total_balance(validator), 10_000_000);
+			assert_eq!(Staking::slashable_balance_of(validator), 10_000);
+			assert_eq!(
+				Staking::eras_stakers(2, validator),
+				pallet_staking::Exposure {
+					total: 10_000,
+					own: 10_000,
+					others: vec![],
+				},
+			);
+		}
+	})
+}
+
+#[test]
+fn report_equivocation_old_session_works() {
+	let (pairs, mut ext) = new_test_ext(3);
+
+	ext.execute_with(|| {
+		start_era(1);
+
+		let authorities = Babe::authorities();
+
+		// we will use the validator at index 0 as the offending authority
+		let offending_validator_index = 0;
+		let offending_validator_id = Session::validators()[offending_validator_index];
+		let offending_authority_pair = pairs
+			.into_iter()
+			.find(|p| p.public() == authorities[offending_validator_index].0)
+			.unwrap();
+
+		// generate an equivocation proof at the current slot
+		let equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+
+		// create the key ownership proof
+		let key_owner_proof = Historical::prove((
+			sp_consensus_babe::KEY_TYPE,
+			&offending_authority_pair.public(),
+		)).unwrap();
+
+		// start a new era and report the equivocation
+		// from the previous era
+		start_era(2);
+
+		// check the balance of the offending validator
+		assert_eq!(Balances::total_balance(&offending_validator_id), 10_000_000);
+		assert_eq!(Staking::slashable_balance_of(&offending_validator_id), 10_000);
+
+		// report the equivocation
+		assert_ok!(Babe::report_equivocation_unsigned(
+			Origin::NONE,
+			equivocation_proof,
+			key_owner_proof,
+		));
+
+		// start a new era so that the slashes are applied
+		start_era(3);
+
+		// check that the balance of offending validator is slashed 100%.
+		assert_eq!(Balances::total_balance(&offending_validator_id), 10_000_000 - 10_000);
+		assert_eq!(Staking::slashable_balance_of(&offending_validator_id), 0);
+		assert_eq!(
+			Staking::eras_stakers(3, offending_validator_id),
+			pallet_staking::Exposure {
+				total: 0,
+				own: 0,
+				others: vec![],
+			},
+		);
+	})
+}
+
+#[test]
+fn report_equivocation_invalid_key_owner_proof() {
+	let (pairs, mut ext) = new_test_ext(3);
+
+	ext.execute_with(|| {
+		start_era(1);
+
+		let authorities = Babe::authorities();
+
+		// we will use the validator at index 0 as the offending authority
+		let offending_validator_index = 0;
+		let offending_authority_pair = pairs
+			.into_iter()
+			.find(|p| p.public() == authorities[offending_validator_index].0)
+			.unwrap();
+
+		// generate an equivocation proof at the current slot
+		let equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+
+		// create the key ownership proof
+		let mut key_owner_proof = Historical::prove((
+			sp_consensus_babe::KEY_TYPE,
+			&offending_authority_pair.public(),
+		)).unwrap();
+
+		// we change the session index in the key ownership proof
+		// which should make it invalid
+		key_owner_proof.session = 0;
+		assert_err!(
+			Babe::report_equivocation_unsigned(
+				Origin::NONE,
+				equivocation_proof.clone(),
+				key_owner_proof,
+			),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		// it should fail as well if we create a key owner proof
+		// for a different authority than the offender
+		let key_owner_proof = Historical::prove((
+			sp_consensus_babe::KEY_TYPE,
+			&authorities[1].0,
+		)).unwrap();
+
+		// we need to progress to a new era to make sure that the key
+		// ownership proof is properly checked, otherwise since the state
+		// is still available the historical module will just check
+		// against current session data.
+		start_era(2);
+
+		assert_err!(
+			Babe::report_equivocation_unsigned(Origin::NONE, equivocation_proof, key_owner_proof),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+	})
+}
+
+#[test]
+fn report_equivocation_invalid_equivocation_proof() {
+	use sp_runtime::traits::Header;
+
+	let (pairs, mut ext) = new_test_ext(3);
+
+	ext.execute_with(|| {
+		start_era(1);
+
+		let authorities = Babe::authorities();
+
+		// we will use the validator at index 0 as the offending authority
+		let offending_validator_index = 0;
+		let offending_authority_pair = pairs
+			.iter()
+			.find(|p| p.public() == authorities[offending_validator_index].0)
+			.unwrap();
+
+		// create the key ownership proof
+		let key_owner_proof = Historical::prove((
+			sp_consensus_babe::KEY_TYPE,
+			&offending_authority_pair.public(),
+		)).unwrap();
+
+		let assert_invalid_equivocation = |equivocation_proof| {
+			assert_err!(
+				Babe::report_equivocation_unsigned(
+					Origin::NONE,
+					equivocation_proof,
+					key_owner_proof.clone(),
+				),
+				Error::<Test>::InvalidEquivocationProof,
+			)
+		};
+
+		// both headers are the same, no equivocation
+		let mut equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+		equivocation_proof.second_header = equivocation_proof.first_header.clone();
+		assert_invalid_equivocation(equivocation_proof);
+
+		// missing preruntime digest from one header
+		let mut equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+		equivocation_proof.first_header.digest_mut().logs.remove(0);
+		assert_invalid_equivocation(equivocation_proof);
+
+		// missing seal from one header
+		let mut equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+		equivocation_proof.first_header.digest_mut().logs.remove(1);
+		assert_invalid_equivocation(equivocation_proof);
+
+		// invalid slot number in proof header
+		let equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get() + 1,
+		);
+
+		// use the header from the previous equivocation generated
+		// at the previous slot
+		let equivocation_proof = sp_consensus_babe::EquivocationProof {
+			slot_number: CurrentSlot::get(),
+			..equivocation_proof
+		};
+		assert_invalid_equivocation(equivocation_proof.clone());
+
+		// different slot numbers in headers
+		let h1 = equivocation_proof.first_header;
+		let mut equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get() + 1,
+		);
+
+		// use the header from the previous equivocation generated
+		// at the previous slot
+		equivocation_proof.first_header = h1.clone();
+		assert_invalid_equivocation(equivocation_proof);
+
+		// invalid seal signature
+		let mut equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get() + 1,
+		);
+
+		// replace the seal digest with the digest from the
+		// previous header at the previous slot
+		equivocation_proof.first_header.digest_mut().pop();
+		equivocation_proof.first_header.digest_mut().push(h1.digest().logs().last().unwrap().clone());
+		assert_invalid_equivocation(equivocation_proof);
+	})
+}
+
+#[test]
+fn report_equivocation_validate_unsigned_works() {
+	let (pairs, mut ext) = new_test_ext(3);
+
+	ext.execute_with(|| {
+		start_era(1);
+
+		let authorities = Babe::authorities();
+
+		// generate and report an equivocation for the validator at index 0
+		let offending_validator_index = 0;
+		let offending_authority_pair = pairs
+			.into_iter()
+			.find(|p| p.public() == authorities[offending_validator_index].0)
+			.unwrap();
+
+		let equivocation_proof = generate_equivocation_proof(
+			offending_validator_index as u32,
+			&offending_authority_pair,
+			CurrentSlot::get(),
+		);
+
+		let key = (sp_consensus_babe::KEY_TYPE, &offending_authority_pair.public());
+		let key_owner_proof = Historical::prove(key).unwrap();
+
+		let inner = crate::Call::<Test>::report_equivocation_unsigned(
+			equivocation_proof.clone(),
+			key_owner_proof.clone(),
+		);
+
+		// only local/inblock reports are allowed
+		assert_eq!(
+			<Babe as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &inner),
+			TransactionValidity::Err(InvalidTransaction::Call.into()),
+		);
+
+		// the transaction is valid when passed as local
+		let tx_tag = (offending_authority_pair.public(), CurrentSlot::get());
+		assert_eq!(
+			<Babe as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &inner),
+			TransactionValidity::Ok(ValidTransaction {
+				priority: TransactionPriority::max_value(),
+				requires: vec![],
+				provides: vec![("BabeEquivocation", tx_tag).encode()],
+				longevity: TransactionLongevity::max_value(),
+				propagate: false,
+			})
+		);
+
+		// the pre dispatch checks should also pass
+		assert_ok!(<Babe as ValidateUnsigned>::pre_dispatch(&inner));
+
+		// we submit the report
+		assert_ok!(Babe::report_equivocation_unsigned(
+			Origin::NONE,
+			equivocation_proof.clone(),
+			key_owner_proof.clone(),
+		));
+
+		// reporting the same equivocation again is rejected when dispatching
+		assert_err!(
+			Babe::report_equivocation_unsigned(Origin::NONE, equivocation_proof, key_owner_proof),
+			Error::<Test>::DuplicateOffenceReport,
+		);
+	});
+}
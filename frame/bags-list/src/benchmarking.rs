@@ -0,0 +1,70 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bags list pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account};
+use list::notional_bag_for;
+
+use crate::Module as BagsList;
+
+const SEED: u32 = 0;
+
+benchmarks! {
+	_ { }
+
+	// The worst case: the voter is taken out of the middle of its bag, and appended to a bag that
+	// is not empty.
+	rebag {
+		let dislocated: T::AccountId = account("dislocated", 0, SEED);
+		let weight = T::VoteWeightProvider::vote_weight(&dislocated);
+		let dest_bag = notional_bag_for::<T>(weight);
+		let origin_bag = T::BagThresholds::get().iter().cloned()
+			.chain(Some(VoteWeight::max_value()))
+			.find(|bag_upper| *bag_upper != dest_bag)
+			.ok_or("there is a single bag, voters can't be moved")?;
+
+		List::<T>::insert(account("origin", 0, SEED), origin_bag)?;
+		List::<T>::insert(dislocated.clone(), origin_bag)?;
+		List::<T>::insert(account("origin", 1, SEED), origin_bag)?;
+		List::<T>::insert(account("dest", 0, SEED), weight)?;
+
+		let caller = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), dislocated.clone())
+	verify {
+		let node = BagsList::<T>::list_nodes(&dislocated).ok_or("the voter was removed")?;
+		assert_eq!(node.bag_upper, dest_bag);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_rebag::<Test>());
+		});
+	}
+}
@@ -0,0 +1,196 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Bags List Module
+//!
+//! A semi-sorted list of voters, where voters are kept in bags by their vote weight.
+//!
+//! - [`bags_list::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The election needs the heaviest voters, but keeping all voters fully sorted on-chain is too
+//! expensive. Instead, this module groups voters into bags, each bag covering a range of vote
+//! weights given by `T::BagThresholds`. Inserting, removing and moving a voter to another bag
+//! are all O(1) operations. Iterating the bags from the heaviest to the lightest yields the voters
+//! sorted by bag, which is enough to take the top N voters without reading all of them.
+//!
+//! The module does not track vote weights itself. It implements
+//! [`SortedListProvider`](sp_staking::SortedListProvider), and expects the owner of the voters,
+//! e.g. the staking module, to report insertions, updates and removals. Updates only move a
+//! voter to another bag if their owner reports them; anyone can also call `rebag` to move a voter
+//! whose weight changed without a report, e.g. through a slash.
+//!
+//! ### Choosing the thresholds
+//!
+//! The thresholds should be sorted in ascending order, and grow by a constant factor, so that the
+//! relative error of the order within a bag is bounded. A voter heavier than the largest
+//! threshold is kept in an implicit bag with the threshold `VoteWeight::max_value()`. Changing the
+//! thresholds requires a migration that calls `regenerate`.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `rebag` - Move a voter into the bag matching its current vote weight.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod benchmarking;
+pub mod list;
+pub mod weights;
+
+use sp_std::prelude::*;
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, traits::Get};
+use frame_system::ensure_signed;
+use sp_staking::{SortedListProvider, VoteWeight, VoteWeightProvider};
+use list::{List, Node, Bag};
+pub use weights::WeightInfo;
+
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// Something that provides the current vote weight of the voters.
+	type VoteWeightProvider: VoteWeightProvider<Self::AccountId>;
+
+	/// The upper thresholds of the bags, sorted in ascending order.
+	///
+	/// Each voter is kept in the bag with the smallest threshold that is not below its weight.
+	type BagThresholds: Get<&'static [VoteWeight]>;
+
+	/// The weights of the calls of this module.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as BagsList {
+		/// The number of voters in the list.
+		pub CounterForListNodes get(fn counter_for_list_nodes): u32;
+
+		/// The voters in the list, keyed by their account.
+		pub ListNodes get(fn list_nodes): map hasher(twox_64_concat) T::AccountId => Option<Node<T::AccountId>>;
+
+		/// The non-empty bags, keyed by their upper threshold.
+		pub ListBags get(fn list_bags): map hasher(twox_64_concat) VoteWeight => Option<Bag<T::AccountId>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where <T as frame_system::Trait>::AccountId {
+		/// A voter was moved from the bag with the first threshold to the bag with the second.
+		Rebagged(AccountId, VoteWeight, VoteWeight),
+	}
+);
+
+decl_error! {
+	/// Error for the bags list module.
+	pub enum Error for Module<T: Trait> {
+		/// The voter is already in the list.
+		Duplicate,
+		/// The voter is not in the list.
+		NotInList,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The upper thresholds of the bags.
+		const BagThresholds: &'static [VoteWeight] = T::BagThresholds::get();
+
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Move `dislocated` into the bag matching its current vote weight.
+		///
+		/// Anyone can call this. It does nothing if the voter is already in the right bag.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Reads the weight of the voter, and up to 4 nodes and 2 bags.
+		/// # </weight>
+		#[weight = T::WeightInfo::rebag()]
+		fn rebag(origin, dislocated: T::AccountId) {
+			let _ = ensure_signed(origin)?;
+			ensure!(List::<T>::contains(&dislocated), Error::<T>::NotInList);
+
+			let weight = T::VoteWeightProvider::vote_weight(&dislocated);
+			Self::do_rebag(&dislocated, weight);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Move `id` into the bag matching `new_weight`, emitting an event if it has been moved.
+	///
+	/// Returns the upper thresholds of the old and the new bag if `id` has been moved.
+	pub fn do_rebag(id: &T::AccountId, new_weight: VoteWeight) -> Option<(VoteWeight, VoteWeight)> {
+		let maybe_movement = List::<T>::update_position_for(id, new_weight);
+		if let Some((from, to)) = maybe_movement {
+			Self::deposit_event(RawEvent::Rebagged(id.clone(), from, to));
+		}
+		maybe_movement
+	}
+}
+
+impl<T: Trait> SortedListProvider<T::AccountId> for Module<T> {
+	type Error = Error<T>;
+
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(List::<T>::iter().map(|node| node.id))
+	}
+
+	fn count() -> u32 {
+		CounterForListNodes::get()
+	}
+
+	fn contains(id: &T::AccountId) -> bool {
+		List::<T>::contains(id)
+	}
+
+	fn on_insert(id: T::AccountId, weight: VoteWeight) -> Result<(), Self::Error> {
+		List::<T>::insert(id, weight)
+	}
+
+	fn on_update(id: &T::AccountId, weight: VoteWeight) {
+		Self::do_rebag(id, weight);
+	}
+
+	fn on_remove(id: &T::AccountId) {
+		List::<T>::remove(id)
+	}
+
+	fn regenerate(
+		all: impl IntoIterator<Item = T::AccountId>,
+		weight_of: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		List::<T>::regenerate(all, weight_of)
+	}
+
+	fn clear() {
+		List::<T>::clear()
+	}
+
+	fn sanity_check() -> Result<(), &'static str> {
+		List::<T>::sanity_check()
+	}
+}
@@ -0,0 +1,245 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The semi-sorted list of voters.
+//!
+//! Every voter is a [`Node`] of a doubly linked list. The nodes are grouped into [`Bag`]s, where
+//! each bag holds the voters whose weight is at most its upper threshold, and more than the
+//! threshold of the next lighter bag. Within a bag, the voters are not sorted.
+//!
+//! Iterating the bags from the heaviest to the lightest yields the voters roughly sorted by
+//! weight, which is good enough to take the heaviest voters for an election.
+
+use sp_std::{prelude::*, iter, marker::PhantomData, collections::btree_set::BTreeSet};
+use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
+use frame_support::{ensure, traits::Get, storage::StoragePrefixedMap};
+use sp_runtime::RuntimeDebug;
+use sp_staking::VoteWeight;
+use crate::{Trait, ListNodes, ListBags, CounterForListNodes};
+
+/// A voter in the list.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Node<AccountId> {
+	/// The voter.
+	pub id: AccountId,
+	/// The previous voter in the same bag.
+	pub prev: Option<AccountId>,
+	/// The next voter in the same bag.
+	pub next: Option<AccountId>,
+	/// The upper threshold of the bag this voter is in.
+	pub bag_upper: VoteWeight,
+}
+
+/// The head and tail of the voters in a bag.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Bag<AccountId> {
+	/// The first voter in the bag.
+	pub head: Option<AccountId>,
+	/// The last voter in the bag. New voters are appended here.
+	pub tail: Option<AccountId>,
+}
+
+impl<AccountId> Default for Bag<AccountId> {
+	fn default() -> Self {
+		Bag { head: None, tail: None }
+	}
+}
+
+/// The upper threshold of the bag that a voter with the given `weight` belongs to.
+///
+/// This is the smallest threshold that is not below `weight`. Weights above the largest
+/// threshold go into a bag with the threshold `VoteWeight::max_value()`.
+pub fn notional_bag_for<T: Trait>(weight: VoteWeight) -> VoteWeight {
+	let thresholds = T::BagThresholds::get();
+	let index = match thresholds.binary_search(&weight) {
+		Ok(index) => index,
+		Err(index) => index,
+	};
+	thresholds.get(index).cloned().unwrap_or(VoteWeight::max_value())
+}
+
+/// The upper thresholds of all bags, from the heaviest to the lightest.
+fn bag_uppers<T: Trait>() -> impl Iterator<Item = VoteWeight> {
+	let thresholds = T::BagThresholds::get();
+	let has_max_bag = thresholds.last() == Some(&VoteWeight::max_value());
+	iter::once(VoteWeight::max_value())
+		.filter(move |_| !has_max_bag)
+		.chain(thresholds.iter().rev().cloned())
+}
+
+/// Interface to the list of voters, kept in the storage of the module.
+pub struct List<T>(PhantomData<T>);
+
+impl<T: Trait> List<T> {
+	/// Iterate over the voters, from the heaviest bag to the lightest one.
+	pub fn iter() -> impl Iterator<Item = Node<T::AccountId>> {
+		bag_uppers::<T>()
+			.filter_map(|bag_upper| ListBags::<T>::get(bag_upper))
+			.flat_map(|bag| Self::iter_bag(bag))
+	}
+
+	/// Iterate over the voters of `bag`, from its head to its tail.
+	fn iter_bag(bag: Bag<T::AccountId>) -> impl Iterator<Item = Node<T::AccountId>> {
+		iter::successors(
+			bag.head.and_then(|head| ListNodes::<T>::get(head)),
+			|node| node.next.as_ref().and_then(|next| ListNodes::<T>::get(next)),
+		)
+	}
+
+	/// Whether `id` is in the list.
+	pub fn contains(id: &T::AccountId) -> bool {
+		ListNodes::<T>::contains_key(id)
+	}
+
+	/// Insert `id` with the given `weight` at the tail of its bag.
+	///
+	/// Returns an error if `id` is already in the list.
+	pub fn insert(id: T::AccountId, weight: VoteWeight) -> Result<(), crate::Error<T>> {
+		ensure!(!Self::contains(&id), crate::Error::<T>::Duplicate);
+		Self::insert_into_bag(id, notional_bag_for::<T>(weight));
+		CounterForListNodes::mutate(|c| *c = c.saturating_add(1));
+		Ok(())
+	}
+
+	/// Remove `id` from the list. Does nothing if `id` is not in the list.
+	pub fn remove(id: &T::AccountId) {
+		if let Some(node) = ListNodes::<T>::take(id) {
+			Self::remove_from_bag(&node);
+			CounterForListNodes::mutate(|c| *c = c.saturating_sub(1));
+		}
+	}
+
+	/// Move `id` into the bag matching its `new_weight`, if it is not in that bag already.
+	///
+	/// Returns the upper thresholds of the old and the new bag if `id` has been moved.
+	pub fn update_position_for(id: &T::AccountId, new_weight: VoteWeight) -> Option<(VoteWeight, VoteWeight)> {
+		let node = ListNodes::<T>::get(id)?;
+		let new_bag_upper = notional_bag_for::<T>(new_weight);
+		if node.bag_upper == new_bag_upper {
+			return None;
+		}
+
+		Self::remove_from_bag(&node);
+		Self::insert_into_bag(node.id, new_bag_upper);
+		Some((node.bag_upper, new_bag_upper))
+	}
+
+	/// Remove all voters and rebuild the list from `all`, with the weights given by `weight_of`.
+	///
+	/// Returns the number of voters inserted.
+	pub fn regenerate(
+		all: impl IntoIterator<Item = T::AccountId>,
+		weight_of: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		Self::clear();
+		let mut count = 0;
+		for id in all {
+			let weight = weight_of(&id);
+			if Self::insert(id, weight).is_ok() {
+				count += 1;
+			}
+		}
+		count
+	}
+
+	/// Remove all voters from the list.
+	pub fn clear() {
+		ListNodes::<T>::remove_all();
+		ListBags::<T>::remove_all();
+		CounterForListNodes::kill();
+	}
+
+	/// Check that the bags and nodes are consistent with each other and with the counter.
+	pub fn sanity_check() -> Result<(), &'static str> {
+		let mut seen = BTreeSet::new();
+		for bag_upper in bag_uppers::<T>() {
+			let bag = match ListBags::<T>::get(bag_upper) {
+				Some(bag) => bag,
+				None => continue,
+			};
+			ensure!(bag.head.is_some() && bag.tail.is_some(), "stored bag is empty");
+
+			let mut prev: Option<T::AccountId> = None;
+			for node in Self::iter_bag(bag.clone()) {
+				ensure!(node.bag_upper == bag_upper, "node is in the wrong bag");
+				ensure!(node.prev == prev, "node does not link back to its predecessor");
+				ensure!(seen.insert(node.id.encode()), "duplicate node in the list");
+				prev = Some(node.id);
+			}
+			ensure!(prev == bag.tail, "bag tail does not match its last node");
+		}
+
+		ensure!(seen.len() as u32 == CounterForListNodes::get(), "counter does not match the list");
+		ensure!(
+			ListNodes::<T>::iter_values().count() == seen.len(),
+			"not all nodes are reachable from a bag",
+		);
+		Ok(())
+	}
+
+	/// Append `id` to the tail of the bag with the upper threshold `bag_upper`.
+	fn insert_into_bag(id: T::AccountId, bag_upper: VoteWeight) {
+		let mut bag = ListBags::<T>::get(bag_upper).unwrap_or_default();
+
+		if let Some(tail) = &bag.tail {
+			ListNodes::<T>::mutate(tail, |maybe_tail| {
+				if let Some(tail) = maybe_tail {
+					tail.next = Some(id.clone());
+				}
+			});
+		}
+		if bag.head.is_none() {
+			bag.head = Some(id.clone());
+		}
+
+		ListNodes::<T>::insert(&id, Node { id: id.clone(), prev: bag.tail.take(), next: None, bag_upper });
+		bag.tail = Some(id);
+		ListBags::<T>::insert(bag_upper, bag);
+	}
+
+	/// Unlink `node` from its neighbours and its bag. The node itself is not removed.
+	fn remove_from_bag(node: &Node<T::AccountId>) {
+		if let Some(prev) = &node.prev {
+			ListNodes::<T>::mutate(prev, |maybe_prev| {
+				if let Some(prev) = maybe_prev {
+					prev.next = node.next.clone();
+				}
+			});
+		}
+		if let Some(next) = &node.next {
+			ListNodes::<T>::mutate(next, |maybe_next| {
+				if let Some(next) = maybe_next {
+					next.prev = node.prev.clone();
+				}
+			});
+		}
+
+		let mut bag = ListBags::<T>::get(node.bag_upper).unwrap_or_default();
+		if bag.head.as_ref() == Some(&node.id) {
+			bag.head = node.next.clone();
+		}
+		if bag.tail.as_ref() == Some(&node.id) {
+			bag.tail = node.prev.clone();
+		}
+
+		if bag.head.is_none() {
+			ListBags::<T>::remove(node.bag_upper);
+		} else {
+			ListBags::<T>::insert(node.bag_upper, bag);
+		}
+	}
+}
@@ -0,0 +1,139 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+use std::{cell::RefCell, collections::HashMap};
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+};
+use crate as bags_list;
+
+pub type AccountId = u64;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		bags_list<T>,
+	}
+}
+
+thread_local! {
+	static VOTE_WEIGHTS: RefCell<HashMap<AccountId, VoteWeight>> = RefCell::new(HashMap::new());
+}
+
+/// Provides the vote weights set through `set_vote_weight`, defaulting to zero.
+pub struct StakingMock;
+
+impl StakingMock {
+	pub fn set_vote_weight(who: AccountId, weight: VoteWeight) {
+		VOTE_WEIGHTS.with(|w| w.borrow_mut().insert(who, weight));
+	}
+}
+
+impl VoteWeightProvider<AccountId> for StakingMock {
+	fn vote_weight(who: &AccountId) -> VoteWeight {
+		VOTE_WEIGHTS.with(|w| w.borrow().get(who).cloned().unwrap_or(0))
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const BagThresholds: &'static [VoteWeight] = &[10, 20, 30, 40];
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type VoteWeightProvider = StakingMock;
+	type BagThresholds = BagThresholds;
+	type WeightInfo = ();
+}
+
+pub type BagsList = Module<Test>;
+pub type System = frame_system::Module<Test>;
+
+/// Build the externalities with voters 1 to 4 in the bag of 10, 5 in the bag of 20, and 6 in the
+/// bag above the largest threshold.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	VOTE_WEIGHTS.with(|w| w.borrow_mut().clear());
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		for (id, weight) in vec![(1, 10), (2, 5), (3, 10), (4, 1), (5, 15), (6, 1_000)] {
+			StakingMock::set_vote_weight(id, weight);
+			assert!(BagsList::on_insert(id, weight).is_ok());
+		}
+	});
+	ext
+}
+
+/// The voters of every non-empty bag, from the lightest to the heaviest bag.
+pub fn bags() -> Vec<(VoteWeight, Vec<AccountId>)> {
+	let mut uppers = BagThresholds::get().to_vec();
+	uppers.push(VoteWeight::max_value());
+	uppers.into_iter().filter_map(|upper| {
+		let ids = List::<Test>::iter()
+			.filter(|node| node.bag_upper == upper)
+			.map(|node| node.id)
+			.collect::<Vec<_>>();
+		if ids.is_empty() { None } else { Some((upper, ids)) }
+	}).collect()
+}
@@ -0,0 +1,160 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+use super::*;
+use mock::{BagsList, StakingMock, Test, Origin, System, TestEvent, new_test_ext, bags};
+use frame_support::{assert_noop, assert_ok};
+use list::notional_bag_for;
+
+const MAX: VoteWeight = VoteWeight::max_value();
+
+#[test]
+fn notional_bag_for_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(notional_bag_for::<Test>(0), 10);
+		assert_eq!(notional_bag_for::<Test>(10), 10);
+		assert_eq!(notional_bag_for::<Test>(11), 20);
+		assert_eq!(notional_bag_for::<Test>(40), 40);
+		assert_eq!(notional_bag_for::<Test>(41), MAX);
+		assert_eq!(notional_bag_for::<Test>(MAX), MAX);
+	});
+}
+
+#[test]
+fn setup_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(bags(), vec![(10, vec![1, 2, 3, 4]), (20, vec![5]), (MAX, vec![6])]);
+		assert_eq!(BagsList::count(), 6);
+		assert_eq!(BagsList::list_bags(10), Some(list::Bag { head: Some(1), tail: Some(4) }));
+		assert_eq!(BagsList::list_bags(30), None);
+		assert_ok!(BagsList::sanity_check());
+	});
+}
+
+#[test]
+fn iter_goes_from_heaviest_bag_to_lightest() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![6, 5, 1, 2, 3, 4]);
+		assert_eq!(BagsList::iter().take(2).collect::<Vec<_>>(), vec![6, 5]);
+	});
+}
+
+#[test]
+fn insert_rejects_duplicates() {
+	new_test_ext().execute_with(|| {
+		assert!(BagsList::on_insert(3, 30).is_err());
+
+		assert_ok!(BagsList::on_insert(7, 30));
+		assert_eq!(bags(), vec![(10, vec![1, 2, 3, 4]), (20, vec![5]), (30, vec![7]), (MAX, vec![6])]);
+		assert_eq!(BagsList::count(), 7);
+		assert!(BagsList::contains(&7));
+		assert_ok!(BagsList::sanity_check());
+	});
+}
+
+#[test]
+fn remove_works_for_head_middle_and_tail() {
+	new_test_ext().execute_with(|| {
+		// middle
+		BagsList::on_remove(&2);
+		assert_eq!(bags(), vec![(10, vec![1, 3, 4]), (20, vec![5]), (MAX, vec![6])]);
+		assert_ok!(BagsList::sanity_check());
+
+		// head
+		BagsList::on_remove(&1);
+		assert_eq!(BagsList::list_bags(10), Some(list::Bag { head: Some(3), tail: Some(4) }));
+
+		// tail
+		BagsList::on_remove(&4);
+		assert_eq!(BagsList::list_bags(10), Some(list::Bag { head: Some(3), tail: Some(3) }));
+
+		// the last voter of a bag removes the bag.
+		BagsList::on_remove(&5);
+		assert_eq!(BagsList::list_bags(20), None);
+		assert_eq!(bags(), vec![(10, vec![3]), (MAX, vec![6])]);
+
+		// removing an unknown voter does nothing.
+		BagsList::on_remove(&42);
+		assert_eq!(BagsList::count(), 2);
+		assert!(!BagsList::contains(&5));
+		assert_ok!(BagsList::sanity_check());
+	});
+}
+
+#[test]
+fn on_update_moves_voter_between_bags() {
+	new_test_ext().execute_with(|| {
+		// staying within the same bag does nothing.
+		BagsList::on_update(&2, 9);
+		assert_eq!(bags(), vec![(10, vec![1, 2, 3, 4]), (20, vec![5]), (MAX, vec![6])]);
+		assert!(System::events().is_empty());
+
+		BagsList::on_update(&2, 35);
+		assert_eq!(bags(), vec![(10, vec![1, 3, 4]), (20, vec![5]), (40, vec![2]), (MAX, vec![6])]);
+
+		// moved voters are appended to the tail of the new bag.
+		BagsList::on_update(&6, 20);
+		assert_eq!(bags(), vec![(10, vec![1, 3, 4]), (20, vec![5, 6]), (40, vec![2])]);
+		assert_ok!(BagsList::sanity_check());
+
+		assert_eq!(
+			System::events().into_iter().map(|r| r.event).collect::<Vec<_>>(),
+			vec![
+				TestEvent::bags_list(RawEvent::Rebagged(2, 10, 40)),
+				TestEvent::bags_list(RawEvent::Rebagged(6, MAX, 20)),
+			],
+		);
+	});
+}
+
+#[test]
+fn rebag_uses_current_vote_weight() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(BagsList::rebag(Origin::signed(1), 42), Error::<Test>::NotInList);
+
+		// the weight changed without the list being told.
+		StakingMock::set_vote_weight(4, 25);
+		assert_ok!(BagsList::rebag(Origin::signed(1), 4));
+		assert_eq!(bags(), vec![(10, vec![1, 2, 3]), (20, vec![5]), (30, vec![4]), (MAX, vec![6])]);
+
+		// rebagging a voter in the right bag does nothing.
+		assert_ok!(BagsList::rebag(Origin::signed(1), 4));
+		assert_eq!(System::events().len(), 1);
+		assert_ok!(BagsList::sanity_check());
+	});
+}
+
+#[test]
+fn regenerate_and_clear_work() {
+	new_test_ext().execute_with(|| {
+		let migrated = BagsList::regenerate(
+			vec![10, 11, 12],
+			Box::new(|id: &u64| *id * 2),
+		);
+		assert_eq!(migrated, 3);
+		assert_eq!(bags(), vec![(20, vec![10]), (30, vec![11, 12])]);
+		assert!(!BagsList::contains(&1));
+		assert_eq!(BagsList::count(), 3);
+		assert_ok!(BagsList::sanity_check());
+
+		BagsList::clear();
+		assert_eq!(BagsList::count(), 0);
+		assert_eq!(BagsList::iter().count(), 0);
+		assert_eq!(bags(), vec![]);
+	});
+}
@@ -0,0 +1,46 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for the bags list module.
+//!
+//! The base weights are upper estimates of the execution time of the calls, to be replaced by
+//! the results of the benchmarks in `benchmarking.rs` on the reference hardware.
+
+use frame_support::weights::{Weight, constants::RocksDbWeight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions of the calls of the bags list module.
+pub trait WeightInfo {
+	fn rebag() -> Weight;
+}
+
+/// The weights of the calls, using the database weights of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	// Reads the vote weight from the ledger of the voter, the voter and its two neighbours, the
+	// old and the new bag and the tail of the new bag. Writes all of them but the ledger.
+	fn rebag() -> Weight {
+		(50_000_000 as Weight).saturating_add(T::DbWeight::get().reads_writes(8, 6))
+	}
+}
+
+// For tests.
+impl WeightInfo for () {
+	fn rebag() -> Weight {
+		(50_000_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(8, 6))
+	}
+}
@@ -0,0 +1,220 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bounties pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::{EventRecord, RawOrigin};
+use frame_benchmarking::{benchmarks, account};
+use pallet_treasury::SpendFunds;
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Bounties;
+
+const SEED: u32 = 0;
+const MAX_BYTES: u32 = 16384;
+
+// Create the pre-requisite information needed to create a bounty with a description of `d` bytes.
+fn setup_bounty<T: Trait>(u: u32, d: u32) -> (
+	T::AccountId,
+	T::AccountId,
+	BalanceOf<T>,
+	BalanceOf<T>,
+	Vec<u8>,
+) {
+	let caller = account("caller", u, SEED);
+	let value: BalanceOf<T> = T::BountyValueMinimum::get().saturating_mul(100.into());
+	let fee = value / 2.into();
+	let deposit = T::BountyDepositBase::get() + T::DataDepositPerByte::get() * MAX_BYTES.into();
+	let _ = T::Currency::make_free_balance_be(&caller, deposit + T::Currency::minimum_balance());
+	let curator = account("curator", u, SEED);
+	let _ = T::Currency::make_free_balance_be(&curator, fee + T::Currency::minimum_balance());
+	let reason = vec![0; d as usize];
+	(caller, curator, fee, value, reason)
+}
+
+// Propose and approve a bounty, returning its index.
+fn create_approved_bounty<T: Trait>(u: u32) -> Result<BountyIndex, &'static str> {
+	let (caller, _, _, value, reason) = setup_bounty::<T>(u, MAX_BYTES);
+	Bounties::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+	let bounty_id = BountyCount::get() - 1;
+	Bounties::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+	Ok(bounty_id)
+}
+
+// Fund all approved bounties, as the treasury does on its spend period.
+fn fund_approved_bounties<T: Trait>() {
+	let mut budget_remaining = BalanceOf::<T>::max_value();
+	let mut imbalance = PositiveImbalanceOf::<T>::zero();
+	let mut total_weight = Weight::zero();
+	let mut missed_any = false;
+	<Bounties<T> as SpendFunds<T>>::spend_funds(
+		&mut budget_remaining,
+		&mut imbalance,
+		&mut total_weight,
+		&mut missed_any,
+	);
+}
+
+// Create a funded bounty whose curator accepted the role, returning the curator and the index.
+fn create_active_bounty<T: Trait>(u: u32) -> Result<(T::AccountId, BountyIndex), &'static str> {
+	let (_, curator, fee, _, _) = setup_bounty::<T>(u, 0);
+	let curator_lookup = T::Lookup::unlookup(curator.clone());
+	let bounty_id = create_approved_bounty::<T>(u)?;
+	fund_approved_bounties::<T>();
+	Bounties::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	Bounties::<T>::accept_curator(RawOrigin::Signed(curator.clone()).into(), bounty_id)?;
+	Ok((curator, bounty_id))
+}
+
+fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
+	let events = frame_system::Module::<T>::events();
+	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+benchmarks! {
+	_ { }
+
+	propose_bounty {
+		let d in 0 .. MAX_BYTES;
+
+		let (caller, _, _, value, description) = setup_bounty::<T>(0, d);
+	}: _(RawOrigin::Signed(caller), value, description)
+
+	approve_bounty {
+		let (caller, _, _, value, reason) = setup_bounty::<T>(0, MAX_BYTES);
+		Bounties::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+	}: _(RawOrigin::Root, bounty_id)
+
+	propose_curator {
+		let (_, curator, fee, _, _) = setup_bounty::<T>(0, 0);
+		let curator_lookup = T::Lookup::unlookup(curator);
+		let bounty_id = create_approved_bounty::<T>(0)?;
+		fund_approved_bounties::<T>();
+	}: _(RawOrigin::Root, bounty_id, curator_lookup, fee)
+
+	// Worst case: a signed account unassigns an inactive curator, whose deposit is slashed.
+	unassign_curator {
+		let (_, bounty_id) = create_active_bounty::<T>(0)?;
+		let update_period = T::BountyUpdatePeriod::get();
+		frame_system::Module::<T>::set_block_number(update_period + 2.into());
+		let caller = account("caller", 1, SEED);
+	}: _(RawOrigin::Signed(caller), bounty_id)
+
+	accept_curator {
+		let (_, curator, fee, _, _) = setup_bounty::<T>(0, 0);
+		let curator_lookup = T::Lookup::unlookup(curator.clone());
+		let bounty_id = create_approved_bounty::<T>(0)?;
+		fund_approved_bounties::<T>();
+		Bounties::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	}: _(RawOrigin::Signed(curator), bounty_id)
+
+	award_bounty {
+		let (curator, bounty_id) = create_active_bounty::<T>(0)?;
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+	}: _(RawOrigin::Signed(curator), bounty_id, beneficiary)
+
+	claim_bounty {
+		let (curator, bounty_id) = create_active_bounty::<T>(0)?;
+		let beneficiary_account: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary = T::Lookup::unlookup(beneficiary_account.clone());
+		Bounties::<T>::award_bounty(
+			RawOrigin::Signed(curator.clone()).into(),
+			bounty_id,
+			beneficiary,
+		)?;
+		let unlock_at = frame_system::Module::<T>::block_number()
+			+ T::BountyDepositPayoutDelay::get();
+		frame_system::Module::<T>::set_block_number(unlock_at);
+	}: _(RawOrigin::Signed(curator), bounty_id)
+	verify {
+		let beneficiary_balance = T::Currency::free_balance(&beneficiary_account);
+		ensure!(beneficiary_balance > Zero::zero(), "Beneficiary not paid");
+	}
+
+	close_bounty_proposed {
+		let (caller, _, _, value, reason) = setup_bounty::<T>(0, 0);
+		Bounties::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+	}: close_bounty(RawOrigin::Root, bounty_id)
+
+	close_bounty_active {
+		let (_, bounty_id) = create_active_bounty::<T>(0)?;
+	}: close_bounty(RawOrigin::Root, bounty_id)
+	verify {
+		assert_last_event::<T>(RawEvent::BountyCanceled(bounty_id).into())
+	}
+
+	extend_bounty_expiry {
+		let (curator, bounty_id) = create_active_bounty::<T>(0)?;
+	}: _(RawOrigin::Signed(curator), bounty_id, Vec::new())
+	verify {
+		assert_last_event::<T>(RawEvent::BountyExtended(bounty_id).into())
+	}
+
+	spend_funds {
+		let b in 1 .. 100;
+
+		for i in 0 .. b {
+			create_approved_bounty::<T>(i)?;
+		}
+		let mut budget_remaining = BalanceOf::<T>::max_value();
+		let mut imbalance = PositiveImbalanceOf::<T>::zero();
+		let mut total_weight = Weight::zero();
+		let mut missed_any = false;
+	}: {
+		<Bounties<T> as SpendFunds<T>>::spend_funds(
+			&mut budget_remaining,
+			&mut imbalance,
+			&mut total_weight,
+			&mut missed_any,
+		);
+	}
+	verify {
+		ensure!(!missed_any, "Missed some");
+		ensure!(BountyApprovals::get().is_empty(), "Some bounties were not funded");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_propose_bounty::<Test>());
+			assert_ok!(test_benchmark_approve_bounty::<Test>());
+			assert_ok!(test_benchmark_propose_curator::<Test>());
+			assert_ok!(test_benchmark_unassign_curator::<Test>());
+			assert_ok!(test_benchmark_accept_curator::<Test>());
+			assert_ok!(test_benchmark_award_bounty::<Test>());
+			assert_ok!(test_benchmark_claim_bounty::<Test>());
+			assert_ok!(test_benchmark_close_bounty_proposed::<Test>());
+			assert_ok!(test_benchmark_close_bounty_active::<Test>());
+			assert_ok!(test_benchmark_extend_bounty_expiry::<Test>());
+			assert_ok!(test_benchmark_spend_funds::<Test>());
+		});
+	}
+}
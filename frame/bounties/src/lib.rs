@@ -0,0 +1,738 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Bounties Module
+//!
+//! The Bounties module allows to reserve funds of the treasury pot for a specific piece of work,
+//! and to delegate the assessment of that work to a curator.
+//!
+//! - [`bounties::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A bounty is a spending proposal with a specific goal that is too broad or too long-running to
+//! be paid out as a single proposal. Anyone can propose a bounty by placing a deposit. Once it is
+//! approved, the bounty is funded from the treasury pot on the next spend period, through the
+//! treasury's [`SpendFunds`](../pallet_treasury/trait.SpendFunds.html) hook.
+//!
+//! The `ApproveOrigin` then proposes a curator, who has to accept the role and place a deposit
+//! proportional to their fee. The curator manages the bounty, and eventually awards it to a
+//! beneficiary. After a delay in which the award can still be contested, the beneficiary can claim
+//! the funds of the bounty, minus the fee of the curator.
+//!
+//! A curator that misbehaves can be unassigned by the `RejectOrigin`, in which case their deposit
+//! is slashed. A curator that does not extend the expiry of the bounty in time is considered
+//! inactive, and can be unassigned and slashed by anyone.
+//!
+//! ### Terminology
+//!
+//! - **Bounty:** A reward for a predefined body of work, paid out of the treasury pot once the
+//!   curator awards it.
+//! - **Curator:** An account managing a bounty, and assigning its payout.
+//! - **Curator Deposit:** The amount a curator places on deposit to accept a bounty. It is
+//!   returned when the bounty is paid out or cancelled, and slashed if the curator is unassigned
+//!   for misbehaviour.
+//! - **Bounty Value:** The total amount that will be paid to the beneficiary and the curator.
+//! - **Payout Delay:** The delay between the award of a bounty and the moment it can be claimed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `propose_bounty` - Propose a specific treasury amount to be earmarked for a predefined set of
+//!   tasks and stake the required deposit.
+//! - `approve_bounty` - Accept a specific treasury amount to be earmarked for a predefined body of
+//!   work.
+//! - `propose_curator` - Assign an account to a bounty as candidate curator.
+//! - `accept_curator` - Accept a bounty assignment from the council, setting a curator deposit.
+//! - `extend_bounty_expiry` - Extend the expiry block number of the bounty and stay active.
+//! - `award_bounty` - Close and pay out the specified amount for the completed work.
+//! - `claim_bounty` - Claim a specific bounty amount from the payout address.
+//! - `unassign_curator` - Unassign an accepted curator from a specific earmark.
+//! - `close_bounty` - Cancel the earmark for a specific treasury amount and close the bounty.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use frame_support::{decl_module, decl_storage, decl_event, ensure, decl_error};
+use frame_support::traits::{
+	Currency, Get, Imbalance, OnUnbalanced, ExistenceRequirement::AllowDeath,
+	ReservableCurrency, EnsureOrigin,
+};
+use sp_runtime::{Permill, RuntimeDebug, DispatchResult, traits::{
+	Zero, StaticLookup, AccountIdConversion, Saturating, BadOrigin
+}};
+use frame_support::weights::{Weight, DispatchClass};
+use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
+use frame_system::{self as system, ensure_signed, ensure_root};
+
+mod tests;
+mod benchmarking;
+
+type BalanceOf<T> = pallet_treasury::BalanceOf<T>;
+type PositiveImbalanceOf<T> = pallet_treasury::PositiveImbalanceOf<T>;
+
+pub trait Trait: frame_system::Trait + pallet_treasury::Trait {
+	/// The amount held on deposit for placing a bounty proposal.
+	type BountyDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount held on deposit per byte within the bounty description.
+	type DataDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The delay period for which a bounty beneficiary need to wait before claim the payout.
+	type BountyDepositPayoutDelay: Get<Self::BlockNumber>;
+
+	/// The period after which an active curator has to extend the expiry of their bounty, or be
+	/// considered inactive.
+	type BountyUpdatePeriod: Get<Self::BlockNumber>;
+
+	/// Percentage of the curator fee that will be reserved upfront as deposit for bounty curator.
+	type BountyCuratorDeposit: Get<Permill>;
+
+	/// Minimum value for a bounty.
+	type BountyValueMinimum: Get<BalanceOf<Self>>;
+
+	/// Maximum acceptable length of a bounty description, in bytes.
+	type MaximumReasonLength: Get<u32>;
+
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+}
+
+/// An index of a bounty. Just a `u32`.
+pub type BountyIndex = u32;
+
+/// A bounty proposal.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Bounty<AccountId, Balance, BlockNumber> {
+	/// The account proposing it.
+	proposer: AccountId,
+	/// The (total) amount that should be paid if the bounty is rewarded.
+	value: Balance,
+	/// The curator fee. Included in value.
+	fee: Balance,
+	/// The deposit of curator.
+	curator_deposit: Balance,
+	/// The amount held on deposit (reserved) for making this proposal.
+	bond: Balance,
+	/// The status of this bounty.
+	status: BountyStatus<AccountId, BlockNumber>,
+}
+
+/// The status of a bounty proposal.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum BountyStatus<AccountId, BlockNumber> {
+	/// The bounty is proposed and waiting for approval.
+	Proposed,
+	/// The bounty is approved and waiting to be funded on the next spend period.
+	Approved,
+	/// The bounty is funded and waiting for a curator assignment.
+	Funded,
+	/// A curator has been proposed by the `ApproveOrigin`. Waiting for acceptance from the
+	/// curator.
+	CuratorProposed {
+		/// The assigned curator of this bounty.
+		curator: AccountId,
+	},
+	/// The bounty is active and waiting to be awarded.
+	Active {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// An update from the curator is due by this block, else they are considered inactive.
+		update_due: BlockNumber,
+	},
+	/// The bounty is awarded and waiting to be released after a delay.
+	PendingPayout {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// The beneficiary of the bounty.
+		beneficiary: AccountId,
+		/// When the bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Treasury {
+		/// Number of bounty proposals that have been made.
+		pub BountyCount get(fn bounty_count): BountyIndex;
+
+		/// Bounties that have been made.
+		pub Bounties get(fn bounties):
+			map hasher(twox_64_concat) BountyIndex
+			=> Option<Bounty<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+
+		/// The description of each bounty.
+		pub BountyDescriptions get(fn bounty_descriptions): map hasher(twox_64_concat) BountyIndex => Option<Vec<u8>>;
+
+		/// Bounty indices that have been approved but not yet funded.
+		pub BountyApprovals get(fn bounty_approvals): Vec<BountyIndex>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Balance = BalanceOf<T>,
+		<T as frame_system::Trait>::AccountId,
+	{
+		/// New bounty proposal.
+		BountyProposed(BountyIndex),
+		/// A bounty proposal was rejected; funds were slashed.
+		BountyRejected(BountyIndex, Balance),
+		/// A bounty proposal is funded and became active.
+		BountyBecameActive(BountyIndex),
+		/// A bounty is awarded to a beneficiary.
+		BountyAwarded(BountyIndex, AccountId),
+		/// A bounty is claimed by beneficiary.
+		BountyClaimed(BountyIndex, Balance, AccountId),
+		/// A bounty is cancelled.
+		BountyCanceled(BountyIndex),
+		/// A bounty expiry is extended.
+		BountyExtended(BountyIndex),
+	}
+);
+
+decl_error! {
+	/// Error for the bounties module.
+	pub enum Error for Module<T: Trait> {
+		/// Proposer's balance is too low.
+		InsufficientProposersBalance,
+		/// No bounty at that index.
+		InvalidIndex,
+		/// The reason given is just too big.
+		ReasonTooBig,
+		/// The bounty status is unexpected.
+		UnexpectedStatus,
+		/// Require bounty curator.
+		RequireCurator,
+		/// Invalid bounty value.
+		InvalidValue,
+		/// Invalid bounty fee.
+		InvalidFee,
+		/// A bounty payout is pending.
+		/// To cancel the bounty, you must unassign and slash the curator.
+		PendingPayout,
+		/// The bounties cannot be claimed/closed because it's still in the countdown period.
+		Premature,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The amount held on deposit for placing a bounty proposal.
+		const BountyDepositBase: BalanceOf<T> = T::BountyDepositBase::get();
+
+		/// The amount held on deposit per byte within the bounty description.
+		const DataDepositPerByte: BalanceOf<T> = T::DataDepositPerByte::get();
+
+		/// The delay period for which a bounty beneficiary need to wait before claim the payout.
+		const BountyDepositPayoutDelay: T::BlockNumber = T::BountyDepositPayoutDelay::get();
+
+		/// The period after which an active curator has to extend the expiry of their bounty.
+		const BountyUpdatePeriod: T::BlockNumber = T::BountyUpdatePeriod::get();
+
+		/// Percentage of the curator fee that will be reserved upfront as deposit for bounty
+		/// curator.
+		const BountyCuratorDeposit: Permill = T::BountyCuratorDeposit::get();
+
+		/// Minimum value for a bounty.
+		const BountyValueMinimum: BalanceOf<T> = T::BountyValueMinimum::get();
+
+		/// Maximum acceptable length of a bounty description, in bytes.
+		const MaximumReasonLength: u32 = T::MaximumReasonLength::get();
+
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Propose a new bounty.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Payment: `BountyDepositBase` will be reserved from the origin account, as well as
+		/// `DataDepositPerByte` for each byte in `description`. It will be unreserved upon
+		/// approval, or slashed when rejected.
+		///
+		/// - `value`: The total payment amount of this bounty, curator fee included.
+		/// - `description`: The description of this bounty.
+		///
+		/// # <weight>
+		/// - Complexity: `O(D)` where `D` is the length of `description`.
+		/// - DbReads: `BountyCount`, `origin account`
+		/// - DbWrites: `BountyCount`, `Bounties`, `BountyDescriptions`, `origin account`
+		/// # </weight>
+		#[weight = 130_000_000 + 1_000 * description.len() as Weight
+			+ T::DbWeight::get().reads_writes(2, 4)]
+		fn propose_bounty(
+			origin,
+			#[compact] value: BalanceOf<T>,
+			description: Vec<u8>,
+		) {
+			let proposer = ensure_signed(origin)?;
+			Self::create_bounty(proposer, description, value)?;
+		}
+
+		/// Approve a bounty proposal. At a later time, the bounty will be funded and become
+		/// active, and the original deposit will be returned.
+		///
+		/// The dispatch origin for this call must be the `ApproveOrigin` or _Root_.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`, `BountyApprovals`
+		/// - DbWrites: `Bounties`, `BountyApprovals`
+		/// # </weight>
+		#[weight = (40_000_000 + T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational)]
+		fn approve_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+				ensure!(bounty.status == BountyStatus::Proposed, Error::<T>::UnexpectedStatus);
+
+				bounty.status = BountyStatus::Approved;
+				BountyApprovals::mutate(|v| v.push(bounty_id));
+				Ok(())
+			})?;
+		}
+
+		/// Assign a curator to a funded bounty.
+		///
+		/// The dispatch origin for this call must be the `ApproveOrigin` or _Root_.
+		///
+		/// - `curator`: The proposed curator, who still has to accept the role.
+		/// - `fee`: The part of the bounty value that is paid to the curator. Must be below the
+		///   value of the bounty.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`
+		/// - DbWrites: `Bounties`
+		/// # </weight>
+		#[weight = (35_000_000 + T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational)]
+		fn propose_curator(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			curator: <T::Lookup as StaticLookup>::Source,
+			#[compact] fee: BalanceOf<T>,
+		) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			let curator = T::Lookup::lookup(curator)?;
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+				match bounty.status {
+					BountyStatus::Funded | BountyStatus::CuratorProposed { .. } => {},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				};
+
+				ensure!(fee < bounty.value, Error::<T>::InvalidFee);
+
+				bounty.status = BountyStatus::CuratorProposed { curator };
+				bounty.fee = fee;
+				Ok(())
+			})?;
+		}
+
+		/// Unassign the curator of a bounty.
+		///
+		/// The dispatch origin for this call can be:
+		/// - the `RejectOrigin` or _Root_, which can always unassign the curator, and slashes the
+		///   deposit of an accepted curator, as this implies that the curator misbehaved;
+		/// - the curator themselves, in which case their deposit is returned, unless the bounty has
+		///   already been awarded;
+		/// - any signed account, if the curator has not extended the expiry of the bounty in time,
+		///   in which case the curator is considered inactive and their deposit is slashed.
+		///
+		/// The bounty goes back to the funded state, waiting for a new curator.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`, `curator account`
+		/// - DbWrites: `Bounties`, `curator account`
+		/// # </weight>
+		#[weight = 90_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn unassign_curator(origin, #[compact] bounty_id: BountyIndex) {
+			let maybe_sender = match ensure_signed(origin.clone()) {
+				Ok(sender) => Some(sender),
+				Err(_) => {
+					T::RejectOrigin::try_origin(origin)
+						.map(|_| ())
+						.or_else(ensure_root)?;
+					None
+				}
+			};
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+
+				let slash_curator = |curator: &T::AccountId, curator_deposit: &mut BalanceOf<T>| {
+					let imbalance = T::Currency::slash_reserved(curator, *curator_deposit).0;
+					T::ProposalRejection::on_unbalanced(imbalance);
+					*curator_deposit = Zero::zero();
+				};
+
+				match bounty.status {
+					BountyStatus::Proposed | BountyStatus::Approved | BountyStatus::Funded => {
+						// No curator to unassign at this point.
+						return Err(Error::<T>::UnexpectedStatus.into());
+					},
+					BountyStatus::CuratorProposed { ref curator } => {
+						// The curator has not accepted yet, so there is no deposit to slash.
+						ensure!(maybe_sender.map_or(true, |sender| sender == *curator), BadOrigin);
+					},
+					BountyStatus::Active { ref curator, ref update_due } => {
+						match maybe_sender {
+							// The `RejectOrigin` considers the curator to be misbehaving.
+							None => slash_curator(curator, &mut bounty.curator_deposit),
+							// The curator is giving up their role.
+							Some(ref sender) if sender == curator => {
+								let _ = T::Currency::unreserve(curator, bounty.curator_deposit);
+								bounty.curator_deposit = Zero::zero();
+							},
+							// Anyone else can only unassign an inactive curator.
+							Some(_) => {
+								let block_number = system::Module::<T>::block_number();
+								ensure!(*update_due < block_number, Error::<T>::Premature);
+								slash_curator(curator, &mut bounty.curator_deposit);
+							},
+						}
+					},
+					BountyStatus::PendingPayout { ref curator, .. } => {
+						// Only the `RejectOrigin` can contest an award, which implies that the
+						// curator misbehaved.
+						ensure!(maybe_sender.is_none(), BadOrigin);
+						slash_curator(curator, &mut bounty.curator_deposit);
+					},
+				};
+
+				bounty.status = BountyStatus::Funded;
+				Ok(())
+			})?;
+		}
+
+		/// Accept the curator role for a bounty.
+		///
+		/// The dispatch origin for this call must be the proposed curator of the bounty. A
+		/// deposit of `BountyCuratorDeposit` of their fee will be reserved.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`, `curator account`
+		/// - DbWrites: `Bounties`, `curator account`
+		/// # </weight>
+		#[weight = 100_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		fn accept_curator(origin, #[compact] bounty_id: BountyIndex) {
+			let signer = ensure_signed(origin)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+
+				match &bounty.status {
+					BountyStatus::CuratorProposed { curator } => {
+						ensure!(signer == *curator, Error::<T>::RequireCurator);
+					},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				}
+
+				let deposit = T::BountyCuratorDeposit::get() * bounty.fee;
+				T::Currency::reserve(&signer, deposit)?;
+				bounty.curator_deposit = deposit;
+
+				let update_due = system::Module::<T>::block_number() + T::BountyUpdatePeriod::get();
+				bounty.status = BountyStatus::Active { curator: signer, update_due };
+
+				Ok(())
+			})?;
+		}
+
+		/// Award a bounty to a beneficiary account. The beneficiary will be able to claim the
+		/// funds after a delay of `BountyDepositPayoutDelay`.
+		///
+		/// The dispatch origin for this call must be the curator of this bounty.
+		///
+		/// - `beneficiary`: The beneficiary account whom will receive the payout.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`
+		/// - DbWrites: `Bounties`
+		/// # </weight>
+		#[weight = 75_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn award_bounty(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+				match &bounty.status {
+					BountyStatus::Active { curator, .. } => {
+						ensure!(signer == *curator, Error::<T>::RequireCurator);
+					},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				}
+				bounty.status = BountyStatus::PendingPayout {
+					curator: signer,
+					beneficiary: beneficiary.clone(),
+					unlock_at: system::Module::<T>::block_number() + T::BountyDepositPayoutDelay::get(),
+				};
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::BountyAwarded(bounty_id, beneficiary));
+		}
+
+		/// Claim the payout of an awarded bounty after the payout delay.
+		///
+		/// The dispatch origin for this call must be _Signed_. The curator receives their fee and
+		/// deposit back, and the beneficiary receives the rest of the bounty account.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`, `bounty account`, `curator account`
+		/// - DbWrites: `Bounties`, `BountyDescriptions`, `bounty account`, `curator account`,
+		///   `beneficiary account`
+		/// # </weight>
+		#[weight = 220_000_000 + T::DbWeight::get().reads_writes(3, 5)]
+		fn claim_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			let _ = ensure_signed(origin)?;
+
+			let bounty = Self::bounties(bounty_id).ok_or(Error::<T>::InvalidIndex)?;
+			let (curator, beneficiary, unlock_at) = match bounty.status {
+				BountyStatus::PendingPayout { curator, beneficiary, unlock_at } =>
+					(curator, beneficiary, unlock_at),
+				_ => Err(Error::<T>::UnexpectedStatus)?,
+			};
+			ensure!(system::Module::<T>::block_number() >= unlock_at, Error::<T>::Premature);
+
+			let bounty_account = Self::bounty_account_id(bounty_id);
+			let balance = T::Currency::free_balance(&bounty_account);
+			let fee = bounty.fee.min(balance);
+			let payout = balance.saturating_sub(fee);
+
+			let _ = T::Currency::unreserve(&curator, bounty.curator_deposit);
+			// should not fail, the fee and the payout are within the free balance.
+			let _ = T::Currency::transfer(&bounty_account, &curator, fee, AllowDeath);
+			let _ = T::Currency::transfer(&bounty_account, &beneficiary, payout, AllowDeath);
+
+			Bounties::<T>::remove(bounty_id);
+			BountyDescriptions::remove(bounty_id);
+
+			Self::deposit_event(Event::<T>::BountyClaimed(bounty_id, payout, beneficiary));
+		}
+
+		/// Cancel a proposed or active bounty. A proposed bounty has its deposit slashed; the funds
+		/// of an active bounty are returned to the treasury, and its curator gets their deposit
+		/// back.
+		///
+		/// The dispatch origin for this call must be the `RejectOrigin` or _Root_.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`, `bounty account`, `curator account`
+		/// - DbWrites: `Bounties`, `BountyDescriptions`, `bounty account`, `curator account`,
+		///   `treasury account`
+		/// # </weight>
+		#[weight = (160_000_000 + T::DbWeight::get().reads_writes(3, 5), DispatchClass::Operational)]
+		fn close_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::RejectOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			let bounty = Self::bounties(bounty_id).ok_or(Error::<T>::InvalidIndex)?;
+			match &bounty.status {
+				BountyStatus::Proposed => {
+					// The reject origin would like to cancel a proposed bounty.
+					Bounties::<T>::remove(bounty_id);
+					BountyDescriptions::remove(bounty_id);
+					let value = bounty.bond;
+					let imbalance = T::Currency::slash_reserved(&bounty.proposer, value).0;
+					T::ProposalRejection::on_unbalanced(imbalance);
+
+					Self::deposit_event(Event::<T>::BountyRejected(bounty_id, value));
+					return Ok(());
+				},
+				BountyStatus::Approved => {
+					// For weight reasons, we don't allow a council to cancel in this phase.
+					// We ask for them to wait until it is funded before they can cancel.
+					Err(Error::<T>::UnexpectedStatus)?
+				},
+				BountyStatus::Funded | BountyStatus::CuratorProposed { .. } => {
+					// Nothing extra to do besides the removal of the bounty below.
+				},
+				BountyStatus::Active { curator, .. } => {
+					// Cancelled by the council, refund the deposit of the curator.
+					let _ = T::Currency::unreserve(curator, bounty.curator_deposit);
+				},
+				BountyStatus::PendingPayout { .. } => {
+					// The bounty is already awarded; the curator has to be unassigned and
+					// slashed first, so that the beneficiary cannot be robbed by the council.
+					Err(Error::<T>::PendingPayout)?
+				},
+			}
+
+			let bounty_account = Self::bounty_account_id(bounty_id);
+			let balance = T::Currency::free_balance(&bounty_account);
+			// should not fail, the funds are returned to the treasury in full.
+			let _ = T::Currency::transfer(
+				&bounty_account,
+				&pallet_treasury::Module::<T>::account_id(),
+				balance,
+				AllowDeath,
+			);
+
+			Bounties::<T>::remove(bounty_id);
+			BountyDescriptions::remove(bounty_id);
+
+			Self::deposit_event(Event::<T>::BountyCanceled(bounty_id));
+		}
+
+		/// Extend the expiry time of an active bounty.
+		///
+		/// The dispatch origin for this call must be the curator of this bounty.
+		///
+		/// - `_remark`: additional information, e.g. a progress report.
+		///
+		/// # <weight>
+		/// - Complexity: O(1).
+		/// - DbReads: `Bounties`
+		/// - DbWrites: `Bounties`
+		/// # </weight>
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn extend_bounty_expiry(origin, #[compact] bounty_id: BountyIndex, _remark: Vec<u8>) {
+			let signer = ensure_signed(origin)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+
+				match bounty.status {
+					BountyStatus::Active { ref curator, ref mut update_due } => {
+						ensure!(*curator == signer, Error::<T>::RequireCurator);
+						*update_due = (system::Module::<T>::block_number() + T::BountyUpdatePeriod::get())
+							.max(*update_due);
+					},
+					_ => Err(Error::<T>::UnexpectedStatus)?,
+				}
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::<T>::BountyExtended(bounty_id));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account ID of a bounty account.
+	pub fn bounty_account_id(id: BountyIndex) -> T::AccountId {
+		// only use two byte prefix to support 16 byte account id (used by test)
+		// "modl" ++ "py/trsry" ++ "bt" is 14 bytes, and two bytes remaining for bounty index
+		T::ModuleId::get().into_sub_account(("bt", id))
+	}
+
+	fn create_bounty(
+		proposer: T::AccountId,
+		description: Vec<u8>,
+		value: BalanceOf<T>,
+	) -> DispatchResult {
+		ensure!(description.len() <= T::MaximumReasonLength::get() as usize, Error::<T>::ReasonTooBig);
+		ensure!(value >= T::BountyValueMinimum::get(), Error::<T>::InvalidValue);
+
+		let index = Self::bounty_count();
+
+		// reserve deposit for new bounty
+		let bond = T::BountyDepositBase::get()
+			+ T::DataDepositPerByte::get() * (description.len() as u32).into();
+		T::Currency::reserve(&proposer, bond)
+			.map_err(|_| Error::<T>::InsufficientProposersBalance)?;
+
+		BountyCount::put(index + 1);
+
+		let bounty = Bounty {
+			proposer,
+			value,
+			fee: Zero::zero(),
+			curator_deposit: Zero::zero(),
+			bond,
+			status: BountyStatus::Proposed,
+		};
+
+		Bounties::<T>::insert(index, &bounty);
+		BountyDescriptions::insert(index, description);
+
+		Self::deposit_event(RawEvent::BountyProposed(index));
+
+		Ok(())
+	}
+}
+
+impl<T: Trait> pallet_treasury::SpendFunds<T> for Module<T> {
+	fn spend_funds(
+		budget_remaining: &mut BalanceOf<T>,
+		imbalance: &mut PositiveImbalanceOf<T>,
+		total_weight: &mut Weight,
+		missed_any: &mut bool,
+	) {
+		let bounties_len = BountyApprovals::mutate(|v| {
+			let bounties_approval_len = v.len() as u64;
+			v.retain(|&index| {
+				Bounties::<T>::mutate(index, |bounty| {
+					// Should always be true, but shouldn't panic if false or we're screwed.
+					if let Some(bounty) = bounty {
+						if bounty.value <= *budget_remaining {
+							*budget_remaining -= bounty.value;
+
+							bounty.status = BountyStatus::Funded;
+
+							// return their deposit.
+							let _ = T::Currency::unreserve(&bounty.proposer, bounty.bond);
+
+							// fund the bounty account
+							imbalance.subsume(T::Currency::deposit_creating(
+								&Self::bounty_account_id(index),
+								bounty.value,
+							));
+
+							Self::deposit_event(RawEvent::BountyBecameActive(index));
+							false
+						} else {
+							*missed_any = true;
+							true
+						}
+					} else {
+						false
+					}
+				})
+			});
+			bounties_approval_len
+		});
+
+		*total_weight += 200_000_000 * bounties_len
+			+ T::DbWeight::get().reads_writes(1 + bounties_len * 2, 1 + bounties_len * 3);
+	}
+}
@@ -0,0 +1,443 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bounties pallet tests.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_origin, impl_outer_event, parameter_types, weights::Weight,
+	traits::OnInitialize
+};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill, Permill, ModuleId,
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup, BadOrigin},
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test  where system = frame_system {}
+}
+
+
+mod bounties {
+	// Re-export needed for `impl_outer_event!`.
+	pub use super::super::*;
+}
+
+impl_outer_event! {
+	pub enum Event for Test {
+		system<T>,
+		pallet_balances<T>,
+		pallet_treasury<T>,
+		bounties<T>,
+	}
+}
+
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	// `u128` so that the bounty accounts do not collide with the treasury account.
+	type AccountId = u128;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: u64 = 1;
+	pub const SpendPeriod: u64 = 2;
+	pub const Burn: Permill = Permill::from_percent(50);
+	pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+}
+impl pallet_treasury::Trait for Test {
+	type ModuleId = TreasuryModuleId;
+	type Currency = pallet_balances::Module<Test>;
+	type ApproveOrigin = frame_system::EnsureRoot<u128>;
+	type RejectOrigin = frame_system::EnsureRoot<u128>;
+	type Event = Event;
+	type ProposalRejection = ();
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type SpendFunds = Bounties;
+}
+parameter_types! {
+	pub const BountyDepositBase: u64 = 80;
+	pub const DataDepositPerByte: u64 = 1;
+	pub const BountyDepositPayoutDelay: u64 = 3;
+	pub const BountyUpdatePeriod: u64 = 20;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: u64 = 1;
+	pub const MaximumReasonLength: u32 = 16384;
+}
+impl Trait for Test {
+	type BountyDepositBase = BountyDepositBase;
+	type DataDepositPerByte = DataDepositPerByte;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
+	type MaximumReasonLength = MaximumReasonLength;
+	type Event = Event;
+}
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Treasury = pallet_treasury::Module<Test>;
+type Bounties = Module<Test>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test>{
+		// Total issuance will be 200 with treasury account initialized at ED.
+		balances: vec![(0, 100), (1, 98), (2, 1)],
+	}.assimilate_storage(&mut t).unwrap();
+	pallet_treasury::GenesisConfig::default().assimilate_storage::<Test>(&mut t).unwrap();
+	t.into()
+}
+
+fn last_event() -> RawEvent<u64, u128> {
+	System::events().into_iter().map(|r| r.event)
+		.filter_map(|e| {
+			if let Event::bounties(inner) = e { Some(inner) } else { None }
+		})
+		.last()
+		.unwrap()
+}
+
+/// Propose a bounty of `value` from `0` and fund it on the next spend period.
+fn fund_bounty(value: u64) {
+	assert_ok!(Bounties::propose_bounty(Origin::signed(0), value, b"12345".to_vec()));
+	assert_ok!(Bounties::approve_bounty(Origin::ROOT, 0));
+	<Treasury as OnInitialize<u64>>::on_initialize(2);
+}
+
+/// Fund a bounty of 50 and make `4` its curator, with a fee of 4.
+fn fund_bounty_with_curator() {
+	Balances::make_free_balance_be(&Treasury::account_id(), 101);
+	Balances::make_free_balance_be(&4, 10);
+	fund_bounty(50);
+	assert_ok!(Bounties::propose_curator(Origin::ROOT, 0, 4, 4));
+	assert_ok!(Bounties::accept_curator(Origin::signed(4), 0));
+}
+
+#[test]
+fn propose_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+
+		assert_ok!(Bounties::propose_bounty(Origin::signed(0), 10, b"1234567890".to_vec()));
+		assert_eq!(last_event(), RawEvent::BountyProposed(0));
+
+		let deposit: u64 = 80 + 10;
+		assert_eq!(Balances::reserved_balance(0), deposit);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		assert_eq!(Bounties::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			curator_deposit: 0,
+			value: 10,
+			bond: deposit,
+			status: BountyStatus::Proposed,
+		});
+		assert_eq!(Bounties::bounty_descriptions(0).unwrap(), b"1234567890".to_vec());
+		assert_eq!(Bounties::bounty_count(), 1);
+	});
+}
+
+#[test]
+fn propose_bounty_validation_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Bounties::propose_bounty(Origin::signed(1), 0, [0; 16385].to_vec()),
+			Error::<Test>::ReasonTooBig
+		);
+		assert_noop!(
+			Bounties::propose_bounty(Origin::signed(1), 10, b"12345678901234567890".to_vec()),
+			Error::<Test>::InsufficientProposersBalance
+		);
+		assert_noop!(
+			Bounties::propose_bounty(Origin::signed(1), 0, b"12345".to_vec()),
+			Error::<Test>::InvalidValue
+		);
+	});
+}
+
+#[test]
+fn close_proposed_bounty_slashes_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Bounties::propose_bounty(Origin::signed(0), 10, b"12345".to_vec()));
+
+		assert_noop!(Bounties::close_bounty(Origin::signed(0), 0), BadOrigin);
+		assert_ok!(Bounties::close_bounty(Origin::ROOT, 0));
+		assert_eq!(last_event(), RawEvent::BountyRejected(0, 85));
+
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert_eq!(Balances::free_balance(0), 15);
+		assert_eq!(Bounties::bounties(0), None);
+		assert_eq!(Bounties::bounty_descriptions(0), None);
+
+		assert_noop!(Bounties::close_bounty(Origin::ROOT, 0), Error::<Test>::InvalidIndex);
+	});
+}
+
+#[test]
+fn approve_bounty_funds_it_on_spend_period() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_eq!(Treasury::pot(), 100);
+
+		assert_ok!(Bounties::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+		assert_ok!(Bounties::approve_bounty(Origin::ROOT, 0));
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Approved);
+		assert_eq!(Bounties::bounty_approvals(), vec![0]);
+
+		assert_noop!(Bounties::approve_bounty(Origin::ROOT, 0), Error::<Test>::UnexpectedStatus);
+		assert_noop!(Bounties::close_bounty(Origin::ROOT, 0), Error::<Test>::UnexpectedStatus);
+
+		// the bounty is funded, and half of the remaining budget is burnt.
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(last_event(), RawEvent::BountyBecameActive(0));
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert!(Bounties::bounty_approvals().is_empty());
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 50);
+		assert_eq!(Treasury::pot(), 25);
+
+		// the deposit is returned.
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert_eq!(Balances::free_balance(0), 100);
+	});
+}
+
+#[test]
+fn approved_bounty_waits_for_budget() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&Treasury::account_id(), 51);
+
+		assert_ok!(Bounties::propose_bounty(Origin::signed(0), 60, b"12345".to_vec()));
+		assert_ok!(Bounties::approve_bounty(Origin::ROOT, 0));
+
+		// nothing is burnt while the bounty is waiting.
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Approved);
+		assert_eq!(Treasury::pot(), 50);
+
+		let _ = Balances::deposit_into_existing(&Treasury::account_id(), 10).unwrap();
+		<Treasury as OnInitialize<u64>>::on_initialize(4);
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 60);
+		assert_eq!(Treasury::pot(), 0);
+	});
+}
+
+#[test]
+fn assign_curator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+
+		assert_ok!(Bounties::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+		assert_noop!(Bounties::propose_curator(Origin::ROOT, 0, 4, 4), Error::<Test>::UnexpectedStatus);
+		assert_ok!(Bounties::approve_bounty(Origin::ROOT, 0));
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+
+		assert_noop!(Bounties::propose_curator(Origin::signed(0), 0, 4, 4), BadOrigin);
+		assert_noop!(Bounties::propose_curator(Origin::ROOT, 0, 4, 50), Error::<Test>::InvalidFee);
+		assert_ok!(Bounties::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::CuratorProposed { curator: 4 });
+
+		assert_noop!(Bounties::accept_curator(Origin::signed(1), 0), Error::<Test>::RequireCurator);
+		assert!(Bounties::accept_curator(Origin::signed(4), 0).is_err());
+
+		Balances::make_free_balance_be(&4, 10);
+		assert_ok!(Bounties::accept_curator(Origin::signed(4), 0));
+
+		let bounty = Bounties::bounties(0).unwrap();
+		assert_eq!(bounty.status, BountyStatus::Active { curator: 4, update_due: 21 });
+		assert_eq!(bounty.curator_deposit, 2);
+		assert_eq!(Balances::free_balance(4), 8);
+		assert_eq!(Balances::reserved_balance(4), 2);
+	});
+}
+
+#[test]
+fn unassign_curator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		Balances::make_free_balance_be(&4, 10);
+		fund_bounty(50);
+		assert_noop!(Bounties::unassign_curator(Origin::ROOT, 0), Error::<Test>::UnexpectedStatus);
+
+		// a proposed curator can decline.
+		assert_ok!(Bounties::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_noop!(Bounties::unassign_curator(Origin::signed(1), 0), BadOrigin);
+		assert_ok!(Bounties::unassign_curator(Origin::signed(4), 0));
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Funded);
+
+		// an active curator can step down and get their deposit back.
+		assert_ok!(Bounties::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Bounties::accept_curator(Origin::signed(4), 0));
+		assert_ok!(Bounties::unassign_curator(Origin::signed(4), 0));
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert_eq!(Balances::free_balance(4), 10);
+		assert_eq!(Balances::reserved_balance(4), 0);
+
+		// the reject origin slashes an active curator.
+		assert_ok!(Bounties::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Bounties::accept_curator(Origin::signed(4), 0));
+		assert_ok!(Bounties::unassign_curator(Origin::ROOT, 0));
+		let bounty = Bounties::bounties(0).unwrap();
+		assert_eq!(bounty.status, BountyStatus::Funded);
+		assert_eq!(bounty.curator_deposit, 0);
+		assert_eq!(Balances::free_balance(4), 8);
+		assert_eq!(Balances::reserved_balance(4), 0);
+	});
+}
+
+#[test]
+fn inactive_curator_can_be_unassigned_by_anyone() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		fund_bounty_with_curator();
+
+		assert_noop!(Bounties::unassign_curator(Origin::signed(0), 0), Error::<Test>::Premature);
+
+		System::set_block_number(22);
+		assert_ok!(Bounties::unassign_curator(Origin::signed(0), 0));
+		assert_eq!(Bounties::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert_eq!(Balances::free_balance(4), 8);
+		assert_eq!(Balances::reserved_balance(4), 0);
+	});
+}
+
+#[test]
+fn extend_bounty_expiry_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		fund_bounty_with_curator();
+
+		assert_noop!(
+			Bounties::extend_bounty_expiry(Origin::signed(1), 0, Vec::new()),
+			Error::<Test>::RequireCurator
+		);
+
+		System::set_block_number(10);
+		assert_ok!(Bounties::extend_bounty_expiry(Origin::signed(4), 0, Vec::new()));
+		assert_eq!(last_event(), RawEvent::BountyExtended(0));
+		assert_eq!(
+			Bounties::bounties(0).unwrap().status,
+			BountyStatus::Active { curator: 4, update_due: 30 },
+		);
+
+		System::set_block_number(25);
+		assert_noop!(Bounties::unassign_curator(Origin::signed(0), 0), Error::<Test>::Premature);
+	});
+}
+
+#[test]
+fn award_and_claim_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		fund_bounty_with_curator();
+
+		assert_noop!(Bounties::award_bounty(Origin::signed(1), 0, 3), Error::<Test>::RequireCurator);
+		assert_ok!(Bounties::award_bounty(Origin::signed(4), 0, 3));
+		assert_eq!(last_event(), RawEvent::BountyAwarded(0, 3));
+		assert_eq!(
+			Bounties::bounties(0).unwrap().status,
+			BountyStatus::PendingPayout { curator: 4, beneficiary: 3, unlock_at: 4 },
+		);
+
+		// an awarded bounty can only be contested by unassigning the curator.
+		assert_noop!(Bounties::close_bounty(Origin::ROOT, 0), Error::<Test>::PendingPayout);
+		assert_noop!(Bounties::unassign_curator(Origin::signed(4), 0), BadOrigin);
+
+		assert_noop!(Bounties::claim_bounty(Origin::signed(1), 0), Error::<Test>::Premature);
+
+		System::set_block_number(4);
+		assert_ok!(Bounties::claim_bounty(Origin::signed(1), 0));
+		assert_eq!(last_event(), RawEvent::BountyClaimed(0, 46, 3));
+
+		assert_eq!(Balances::free_balance(4), 14);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(3), 46);
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 0);
+		assert_eq!(Bounties::bounties(0), None);
+		assert_eq!(Bounties::bounty_descriptions(0), None);
+	});
+}
+
+#[test]
+fn close_active_bounty_refunds_treasury() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		fund_bounty_with_curator();
+		assert_eq!(Treasury::pot(), 25);
+
+		assert_noop!(Bounties::close_bounty(Origin::signed(0), 0), BadOrigin);
+		assert_ok!(Bounties::close_bounty(Origin::ROOT, 0));
+		assert_eq!(last_event(), RawEvent::BountyCanceled(0));
+
+		assert_eq!(Treasury::pot(), 75);
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 0);
+		assert_eq!(Balances::free_balance(4), 10);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Bounties::bounties(0), None);
+		assert_eq!(Bounties::bounty_descriptions(0), None);
+	});
+}
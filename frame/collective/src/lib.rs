@@ -44,7 +44,7 @@ use frame_support::{
 	dispatch::{Dispatchable, Parameter}, codec::{Encode, Decode},
 	traits::{Get, ChangeMembers, InitializeMembers, EnsureOrigin}, decl_module, decl_event,
 	decl_storage, decl_error, ensure,
-	weights::DispatchClass,
+	weights::DispatchClass, type_info::TypeInfo,
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
 
@@ -88,7 +88,7 @@ pub enum RawOrigin<AccountId, I> {
 /// Origin for the collective module.
 pub type Origin<T, I=DefaultInstance> = RawOrigin<<T as frame_system::Trait>::AccountId, I>;
 
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 /// Info for keeping track of a motion being voted on.
 pub struct Votes<AccountId, BlockNumber> {
 	/// The proposal's unique index.
@@ -169,6 +169,7 @@ use sp_runtime::{
 	traits::{Zero, Hash, Dispatchable, Saturating},
 };
 use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
 	weights::{Weight, DispatchClass},
@@ -279,7 +280,7 @@ pub trait Trait: frame_system::Trait + Sized {
 	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Proposal>;
 }
 
-#[derive(Clone, Encode, Decode, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum PreimageStatus<AccountId, Balance, BlockNumber> {
 	/// The preimage is imminently needed at the argument.
 	Missing(BlockNumber),
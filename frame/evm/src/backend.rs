@@ -3,6 +3,7 @@ use sp_std::vec::Vec;
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
 use sp_core::{U256, H256, H160};
 use sp_runtime::traits::UniqueSaturatedInto;
 use frame_support::storage::{StorageMap, StorageDoubleMap};
@@ -10,7 +11,7 @@ use sha3::{Keccak256, Digest};
 use evm::backend::{Backend as BackendT, ApplyBackend, Apply};
 use crate::{Trait, Accounts, AccountStorages, AccountCodes, Module, Event};
 
-#[derive(Clone, Eq, PartialEq, Encode, Decode, Default)]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 /// Ethereum account nonce, balance and code. Used by storage.
 pub struct Account {
@@ -20,7 +21,7 @@ pub struct Account {
 	pub balance: U256,
 }
 
-#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 /// Ethereum log. Used for `deposit_event`.
 pub struct Log {
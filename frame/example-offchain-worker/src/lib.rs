@@ -65,6 +65,7 @@ use sp_runtime::{
 	},
 };
 use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
 use sp_std::vec::Vec;
 use lite_json::json::JsonValue;
 
@@ -133,7 +134,7 @@ pub trait Trait: CreateSignedTransaction<Call<Self>> {
 
 /// Payload used by this example crate to hold price
 /// data required to submit a transaction.
-#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct PricePayload<Public, BlockNumber> {
 	block_number: BlockNumber,
 	price: u32,
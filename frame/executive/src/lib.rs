@@ -230,11 +230,15 @@ where
 		extrinsics_root: &System::Hash,
 		digest: &Digest<System::Hash>,
 	) {
-		if Self::runtime_upgraded() {
-			// System is not part of `AllModules`, so we need to call this manually.
-			let mut weight = <frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade();
-			weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
-			weight = weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade());
+		let runtime_upgraded = Self::runtime_upgraded();
+		if runtime_upgraded {
+			let weight = Self::execute_on_runtime_upgrade()
+				.saturating_add(<frame_system::Module<System>>::put_pallet_versions());
+			frame_support::debug::info!(
+				target: "runtime::executive",
+				"Runtime upgrade executed with a weight of {}",
+				weight,
+			);
 			<frame_system::Module<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
 		}
 		<frame_system::Module<System>>::initialize(
@@ -244,6 +248,11 @@ where
 			digest,
 			frame_system::InitKind::Full,
 		);
+		if runtime_upgraded {
+			// Checked once the block is initialized, so a changed layout is reported in its events.
+			let weight = <frame_system::Module<System>>::check_modules_layout();
+			<frame_system::Module<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
+		}
 		<frame_system::Module<System> as OnInitialize<System::BlockNumber>>::on_initialize(*block_number);
 		let weight = <AllModules as OnInitialize<System::BlockNumber>>::on_initialize(*block_number)
 			.saturating_add(<System::BlockExecutionWeight as frame_support::traits::Get<_>>::get());
@@ -252,6 +261,32 @@ where
 		frame_system::Module::<System>::note_finished_initialize();
 	}
 
+	/// Execute all `OnRuntimeUpgrade` of this runtime, custom logic before the modules.
+	///
+	/// System is not part of `AllModules`, so it is called manually, first.
+	fn execute_on_runtime_upgrade() -> frame_support::weights::Weight {
+		<(frame_system::Module<System>, COnRuntimeUpgrade, AllModules) as OnRuntimeUpgrade>
+			::on_runtime_upgrade()
+	}
+
+	/// Execute all `OnRuntimeUpgrade` of this runtime, along with their `pre_upgrade` and
+	/// `post_upgrade` checks, and write the new pallet versions.
+	///
+	/// Meant to try a runtime upgrade against a copy of the live state, before enacting it.
+	///
+	/// The pallet versions are written after the `post_upgrade` checks, so the checks of the
+	/// versioned migrations that ran aren't skipped.
+	#[cfg(feature = "std")]
+	pub fn test_runtime_upgrade() -> Result<frame_support::weights::Weight, &'static str> {
+		type Upgrades<System, C, A> = (frame_system::Module<System>, C, A);
+
+		<Upgrades<System, COnRuntimeUpgrade, AllModules> as OnRuntimeUpgrade>::pre_upgrade()?;
+		let weight = Self::execute_on_runtime_upgrade();
+		<Upgrades<System, COnRuntimeUpgrade, AllModules> as OnRuntimeUpgrade>::post_upgrade()?;
+
+		Ok(weight.saturating_add(<frame_system::Module<System>>::put_pallet_versions()))
+	}
+
 	/// Returns if the runtime was upgraded since the last time this function was called.
 	fn runtime_upgraded() -> bool {
 		let last = frame_system::LastRuntimeUpgrade::get();
@@ -458,7 +493,10 @@ mod tests {
 	use frame_support::{
 		impl_outer_event, impl_outer_origin, parameter_types, impl_outer_dispatch,
 		weights::{Weight, RuntimeDbWeight},
-		traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReasons, WithdrawReason},
+		traits::{
+			Currency, LockIdentifier, LockableCurrency, WithdrawReasons, WithdrawReason,
+			GetPalletVersion, PalletVersion, VersionedMigration,
+		},
 	};
 	use frame_system::{self as system, Call as SystemCall, ChainContext, LastRuntimeUpgradeInfo};
 	use pallet_balances::Call as BalancesCall;
@@ -561,7 +599,7 @@ mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type MaximumBlockLength = MaximumBlockLength;
 		type Version = RuntimeVersion;
-		type ModuleToIndex = ();
+		type ModuleToIndex = TestModuleToIndex;
 		type AccountData = pallet_balances::AccountData<Balance>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
@@ -619,6 +657,17 @@ mod tests {
 	thread_local! {
 		pub static RUNTIME_VERSION: std::cell::RefCell<sp_version::RuntimeVersion> =
 			Default::default();
+		pub static PALLET_VERSIONS: std::cell::RefCell<Vec<(&'static str, PalletVersion)>> =
+			Default::default();
+	}
+
+	pub struct TestModuleToIndex;
+	impl frame_support::traits::ModuleToIndex for TestModuleToIndex {
+		fn module_to_index<M: 'static>() -> Option<usize> { Some(0) }
+
+		fn pallet_versions() -> Vec<(&'static str, PalletVersion)> {
+			PALLET_VERSIONS.with(|v| v.borrow().clone())
+		}
 	}
 
 	type SignedExtra = (
@@ -640,6 +689,20 @@ mod tests {
 			sp_io::storage::set(CUSTOM_ON_RUNTIME_KEY, &true.encode());
 			0
 		}
+
+		fn pre_upgrade() -> Result<(), &'static str> {
+			match sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY) {
+				None => Ok(()),
+				Some(_) => Err("the custom upgrade already ran"),
+			}
+		}
+
+		fn post_upgrade() -> Result<(), &'static str> {
+			match sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY) {
+				Some(_) => Ok(()),
+				None => Err("the custom upgrade didn't run"),
+			}
+		}
 	}
 
 	type Executive = super::Executive<
@@ -1000,4 +1063,59 @@ mod tests {
 			assert_eq!(sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY).unwrap(), true.encode());
 		});
 	}
+
+	#[test]
+	fn test_runtime_upgrade_runs_checks_around_upgrades() {
+		new_test_ext(1).execute_with(|| {
+			assert!(Executive::test_runtime_upgrade().is_ok());
+			assert_eq!(&sp_io::storage::get(TEST_KEY).unwrap()[..], *b"module");
+
+			// The custom upgrade refuses to run twice.
+			assert_eq!(
+				Executive::test_runtime_upgrade(),
+				Err("the custom upgrade already ran"),
+			);
+		});
+	}
+
+	struct CustomPallet;
+	impl GetPalletVersion for CustomPallet {
+		fn current_version() -> PalletVersion { PalletVersion::new(2, 0, 0) }
+		fn storage_version() -> Option<PalletVersion> { PalletVersion::get_from_storage(b"Custom") }
+	}
+
+	struct FailingMigration;
+	impl OnRuntimeUpgrade for FailingMigration {
+		fn post_upgrade() -> Result<(), &'static str> {
+			Err("the migration failed")
+		}
+	}
+
+	parameter_types! {
+		pub const CustomV2: PalletVersion = PalletVersion::new(2, 0, 0);
+	}
+
+	#[test]
+	fn test_runtime_upgrade_checks_versioned_migrations_before_writing_versions() {
+		type Executive = super::Executive<
+			Runtime,
+			Block<TestXt>,
+			ChainContext<Runtime>,
+			Runtime,
+			AllModules,
+			VersionedMigration<CustomPallet, CustomV2, FailingMigration>,
+		>;
+
+		new_test_ext(1).execute_with(|| {
+			PALLET_VERSIONS.with(|v| *v.borrow_mut() = vec![("Custom", CustomV2::get())]);
+			PalletVersion::new(1, 0, 0).put_into_storage(b"Custom");
+
+			assert_eq!(Executive::test_runtime_upgrade(), Err("the migration failed"));
+			assert_eq!(CustomPallet::storage_version(), Some(PalletVersion::new(1, 0, 0)));
+
+			// Once the storage is at the new version, the migration and its checks are skipped.
+			CustomV2::get().put_into_storage(b"Custom");
+			assert!(Executive::test_runtime_upgrade().is_ok());
+		});
+	}
 }
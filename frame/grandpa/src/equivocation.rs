@@ -39,6 +39,7 @@
 use sp_std::prelude::*;
 
 use codec::{self as codec, Decode, Encode};
+use frame_support::type_info::TypeInfo;
 use frame_support::{debug, dispatch::IsSubType, traits::KeyOwnerProofSystem};
 use frame_system::offchain::{AppCrypto, CreateSignedTransaction, Signer};
 use sp_finality_grandpa::{EquivocationProof, RoundNumber, SetId};
@@ -55,7 +56,7 @@ use sp_staking::{
 };
 
 /// Ensure that equivocation reports are only processed if valid.
-#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub struct ValidateEquivocationReport<T>(sp_std::marker::PhantomData<T>);
 
 impl<T> Default for ValidateEquivocationReport<T> {
@@ -375,38 +376,3 @@ impl<FullIdentification: Clone> Offence<FullIdentification>
 		x.square()
 	}
 }
-
-/// A trait to get a session number the `MembershipProof` belongs to.
-pub trait GetSessionNumber {
-	fn session(&self) -> SessionIndex;
-}
-
-/// A trait to get the validator count at the session the `MembershipProof`
-/// belongs to.
-pub trait GetValidatorCount {
-	fn validator_count(&self) -> sp_session::ValidatorCount;
-}
-
-impl GetSessionNumber for frame_support::Void {
-	fn session(&self) -> SessionIndex {
-		Default::default()
-	}
-}
-
-impl GetValidatorCount for frame_support::Void {
-	fn validator_count(&self) -> sp_session::ValidatorCount {
-		Default::default()
-	}
-}
-
-impl GetSessionNumber for sp_session::MembershipProof {
-	fn session(&self) -> SessionIndex {
-		self.session
-	}
-}
-
-impl GetValidatorCount for sp_session::MembershipProof {
-	fn validator_count(&self) -> sp_session::ValidatorCount {
-		self.validator_count
-	}
-}
@@ -33,6 +33,7 @@ pub use sp_finality_grandpa as fg_primitives;
 use sp_std::prelude::*;
 
 use codec::{self as codec, Decode, Encode};
+use frame_support::type_info::TypeInfo;
 pub use fg_primitives::{AuthorityId, AuthorityList, AuthorityWeight, VersionedAuthorityList};
 use fg_primitives::{
 	ConsensusLog, EquivocationProof, ScheduledChange, SetId, GRANDPA_AUTHORITIES_KEY,
@@ -55,9 +56,10 @@ mod mock;
 mod tests;
 
 pub use equivocation::{
-	EquivocationHandler, GetSessionNumber, GetValidatorCount, GrandpaEquivocationOffence,
-	GrandpaOffence, GrandpaTimeSlot, HandleEquivocation, ValidateEquivocationReport,
+	EquivocationHandler, GrandpaEquivocationOffence, GrandpaOffence, GrandpaTimeSlot,
+	HandleEquivocation, ValidateEquivocationReport,
 };
+pub use sp_session::{GetSessionNumber, GetValidatorCount};
 
 pub trait Trait: frame_system::Trait {
 	/// The event type of this module.
@@ -95,7 +97,7 @@ pub trait Trait: frame_system::Trait {
 /// A stored pending change, old format.
 // TODO: remove shim
 // https://github.com/paritytech/substrate/issues/1614
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, TypeInfo)]
 pub struct OldStoredPendingChange<N> {
 	/// The block number this was scheduled at.
 	pub scheduled_at: N,
@@ -106,7 +108,7 @@ pub struct OldStoredPendingChange<N> {
 }
 
 /// A stored pending change.
-#[derive(Encode)]
+#[derive(Encode, TypeInfo)]
 pub struct StoredPendingChange<N> {
 	/// The block number this was scheduled at.
 	pub scheduled_at: N,
@@ -136,7 +138,7 @@ impl<N: Decode> Decode for StoredPendingChange<N> {
 /// Current state of the GRANDPA authority set. State transitions must happen in
 /// the same order of states defined below, e.g. `Paused` implies a prior
 /// `PendingPause`.
-#[derive(Decode, Encode)]
+#[derive(Decode, Encode, TypeInfo)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub enum StoredState<N> {
 	/// The current authority set is live, and GRANDPA is enabled.
@@ -189,6 +189,7 @@ parameter_types! {
 	pub const AttestationPeriod: u64 = 100;
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominatorsInElection: u32 = 1_000;
 	pub const ElectionLookahead: u64 = 0;
 	pub const StakingUnsignedPriority: u64 = u64::max_value() / 2;
 }
@@ -226,6 +227,9 @@ impl staking::Trait for Test {
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
 	type UnsignedPriority = StakingUnsignedPriority;
+	type OnStakerSlash = ();
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
 	type MaxIterations = ();
 }
 
@@ -69,6 +69,7 @@ use sp_std::prelude::*;
 use sp_std::{fmt::Debug, ops::Add, iter::once};
 use enumflags2::BitFlags;
 use codec::{Encode, Decode};
+use frame_support::type_info::{TypeInfo, Type, TypeDef, Path, Variant, Field};
 use sp_runtime::{DispatchError, RuntimeDebug};
 use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput};
 use frame_support::{
@@ -185,6 +186,44 @@ impl Encode for Data {
 }
 impl codec::EncodeLike for Data {}
 
+impl TypeInfo for Data {
+	type Identity = Self;
+
+	/// Raw data is encoded without a length prefix, its length being given by the leading byte:
+	/// it is described as a variant per length.
+	fn type_info() -> Type {
+		macro_rules! data_variants {
+			( $( $n:literal )* ) => {
+				sp_std::vec![
+					Variant::new("None", 0, sp_std::vec![], &[]),
+					$( Variant::new(
+						concat!("Raw", $n),
+						$n + 1,
+						sp_std::vec![Field::unnamed::<[u8; $n]>(concat!("[u8; ", $n, "]"), &[])],
+						&[],
+					), )*
+					Variant::new("BlakeTwo256", 34, sp_std::vec![hash_field()], &[]),
+					Variant::new("Sha256", 35, sp_std::vec![hash_field()], &[]),
+					Variant::new("Keccak256", 36, sp_std::vec![hash_field()], &[]),
+					Variant::new("ShaThree256", 37, sp_std::vec![hash_field()], &[]),
+				]
+			}
+		}
+
+		fn hash_field() -> Field {
+			Field::unnamed::<[u8; 32]>("[u8; 32]", &[])
+		}
+
+		Type::new(
+			Path::new("Data", module_path!()),
+			TypeDef::Variant(data_variants!(
+				0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30
+				31 32
+			)),
+		)
+	}
+}
+
 impl Default for Data {
 	fn default() -> Self {
 		Self::None
@@ -198,7 +237,7 @@ pub type RegistrarIndex = u32;
 ///
 /// NOTE: Registrars may pay little attention to some fields. Registrars may want to make clear
 /// which fields their attestation is relevant for by off-chain means.
-#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub enum Judgement<
 	Balance: Encode + Decode + Copy + Clone + Debug + Eq + PartialEq
 > {
@@ -249,7 +288,7 @@ impl<
 /// The fields that we use to identify the owner of an account with. Each corresponds to a field
 /// in the `IdentityInfo` struct.
 #[repr(u64)]
-#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, BitFlags, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, BitFlags, RuntimeDebug, TypeInfo)]
 pub enum IdentityField {
 	Display        = 0b0000000000000000000000000000000000000000000000000000000000000001,
 	Legal          = 0b0000000000000000000000000000000000000000000000000000000000000010,
@@ -278,11 +317,22 @@ impl Decode for IdentityFields {
 	}
 }
 
+impl TypeInfo for IdentityFields {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		Type::new(
+			Path::new("IdentityFields", module_path!()),
+			TypeDef::Composite(sp_std::vec![Field::unnamed::<u64>("u64", &[])]),
+		)
+	}
+}
+
 /// Information concerning the identity of the controller of an account.
 ///
 /// NOTE: This should be stored at the end of the storage item to facilitate the addition of extra
 /// fields in a backwards compatible way through a specialized `Decode` impl.
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 #[cfg_attr(test, derive(Default))]
 pub struct IdentityInfo {
 	/// Additional fields of the identity that are not catered for with the struct's explicit
@@ -334,7 +384,7 @@ pub struct IdentityInfo {
 ///
 /// NOTE: This is stored separately primarily to facilitate the addition of extra fields in a
 /// backwards compatible way through a specialized `Decode` impl.
-#[derive(Clone, Encode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct Registration<
 	Balance: Encode + Decode + Copy + Clone + Debug + Eq + PartialEq
 > {
@@ -369,7 +419,7 @@ impl<
 }
 
 /// Information concerning a registrar.
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct RegistrarInfo<
 	Balance: Encode + Decode + Clone + Debug + Eq + PartialEq,
 	AccountId: Encode + Decode + Clone + Debug + Eq + PartialEq
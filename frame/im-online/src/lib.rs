@@ -74,12 +74,16 @@ mod benchmarking;
 
 use sp_application_crypto::RuntimeAppPublic;
 use codec::{Encode, Decode};
-use sp_core::offchain::OpaqueNetworkState;
+use frame_support::type_info::TypeInfo;
+use sp_core::offchain::{OpaqueNetworkState, Duration};
 use sp_std::prelude::*;
 use sp_std::convert::TryInto;
 use pallet_session::historical::IdentificationTuple;
 use sp_runtime::{
-	offchain::storage::StorageValueRef,
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{StorageLock, BlockAndTime},
+	},
 	RuntimeDebug,
 	traits::{Convert, Member, Saturating, AtLeast32Bit}, Perbill,
 	transaction_validity::{
@@ -139,9 +143,13 @@ pub mod ed25519 {
 }
 
 const DB_PREFIX: &[u8] = b"parity/im-online-heartbeat/";
+const DB_LOCK_PREFIX: &[u8] = b"parity/im-online-heartbeat-lock/";
 /// How many blocks do we wait for heartbeat transaction to be included
 /// before sending another one.
 const INCLUDE_THRESHOLD: u32 = 3;
+/// How long (in milliseconds) a worker may hold the heartbeat lock of an authority
+/// before it is considered stale, in addition to `INCLUDE_THRESHOLD` blocks passing.
+const LOCK_EXPIRATION_MS: u64 = 20_000;
 
 /// Status of the offchain worker code.
 ///
@@ -209,7 +217,7 @@ impl<BlockNumber: sp_std::fmt::Debug> sp_std::fmt::Debug for OffchainErr<BlockNu
 pub type AuthIndex = u32;
 
 /// Heartbeat which is sent/received.
-#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct Heartbeat<BlockNumber>
 	where BlockNumber: PartialEq + Eq + Decode + Encode,
 {
@@ -562,34 +570,42 @@ impl<T: Trait> Module<T> {
 			key.extend(authority_index.encode());
 			key
 		};
-		let storage = StorageValueRef::persistent(&key);
-		let res = storage.mutate(|status: Option<Option<HeartbeatStatus<T::BlockNumber>>>| {
-			// Check if there is already a lock for that particular block.
-			// This means that the heartbeat has already been sent, and we are just waiting
-			// for it to be included. However if it doesn't get included for INCLUDE_THRESHOLD
-			// we will re-send it.
-			match status {
-				// we are still waiting for inclusion.
-				Some(Some(status)) if status.is_recent(session_index, now) => {
-					Err(OffchainErr::WaitingForInclusion(status.sent_at))
-				},
-				// attempt to set new status
-				_ => Ok(HeartbeatStatus {
-					session_index,
-					sent_at: now,
-				}),
-			}
-		})?;
+		let lock_key = {
+			let mut key = DB_LOCK_PREFIX.to_vec();
+			key.extend(authority_index.encode());
+			key
+		};
 
-		let mut new_status = res.map_err(|_| OffchainErr::FailedToAcquireLock)?;
+		// Hold the lock for that authority while we check the status and send the heartbeat,
+		// so that workers running concurrently for overlapping blocks cannot both see a stale
+		// status and submit the same heartbeat twice.
+		let mut lock = StorageLock::<BlockAndTime<frame_system::Module<T>>>::with_block_and_time_deadline(
+			&lock_key,
+			INCLUDE_THRESHOLD,
+			Duration::from_millis(LOCK_EXPIRATION_MS),
+		);
+		let _guard = lock.try_lock().map_err(|_| OffchainErr::FailedToAcquireLock)?;
+
+		// Check if the heartbeat has already been sent, and we are just waiting
+		// for it to be included. However if it doesn't get included for INCLUDE_THRESHOLD
+		// we will re-send it.
+		let storage = StorageValueRef::persistent(&key);
+		match storage.get::<HeartbeatStatus<T::BlockNumber>>() {
+			Some(Some(status)) if status.is_recent(session_index, now) => {
+				return Err(OffchainErr::WaitingForInclusion(status.sent_at));
+			},
+			_ => {},
+		}
 
-		// we got the lock, let's try to send the heartbeat.
 		let res = f();
 
-		// clear the lock in case we have failed to send transaction.
-		if res.is_err() {
-			new_status.sent_at = 0.into();
-			storage.set(&new_status);
+		// only record the heartbeat as sent if submitting the transaction succeeded,
+		// otherwise the next worker should retry as soon as possible.
+		if res.is_ok() {
+			storage.set(&HeartbeatStatus {
+				session_index,
+				sent_at: now,
+			});
 		}
 
 		res
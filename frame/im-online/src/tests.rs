@@ -353,3 +353,54 @@ fn should_not_send_a_report_if_already_online() {
 		});
 	});
 }
+
+#[test]
+fn should_not_send_heartbeat_while_locked_or_waiting_for_inclusion() {
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		advance_session();
+		// given
+		VALIDATORS.with(|l| *l.borrow_mut() = Some(vec![1, 2, 3, 4, 5, 6]));
+		// enact the change and buffer another one
+		advance_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+		UintAuthorityId::set_all_keys(vec![1, 2, 3]);
+
+		// another worker is currently sending the heartbeat of authority 0.
+		let lock_key = {
+			let mut key = DB_LOCK_PREFIX.to_vec();
+			key.extend(0u32.encode());
+			key
+		};
+		let mut lock = StorageLock::<BlockAndTime<System>>::new(&lock_key);
+		let guard = lock.try_lock().unwrap();
+
+		// when
+		let res = ImOnline::send_heartbeats(4).unwrap().collect::<Vec<_>>();
+
+		// then
+		assert_eq!(res, vec![
+			Err(OffchainErr::FailedToAcquireLock),
+			Ok(()),
+			Ok(()),
+		]);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		// when the other worker is done
+		drop(guard);
+		let res = ImOnline::send_heartbeats(5).unwrap().collect::<Vec<_>>();
+
+		// then only the heartbeat that could not be sent before is sent.
+		assert_eq!(res, vec![
+			Ok(()),
+			Err(OffchainErr::WaitingForInclusion(4)),
+			Err(OffchainErr::WaitingForInclusion(4)),
+		]);
+		assert_eq!(pool_state.read().transactions.len(), 3);
+	});
+}
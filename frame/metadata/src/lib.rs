@@ -29,6 +29,7 @@ use codec::{Decode, Input, Error};
 use codec::{Encode, Output};
 use sp_std::vec::Vec;
 use sp_core::RuntimeDebug;
+pub use sp_type_info::PortableRegistry;
 
 #[cfg(feature = "std")]
 type StringBuf = String;
@@ -62,6 +63,12 @@ impl<B, O> Encode for DecodeDifferent<B, O> where B: Encode + 'static, O: Encode
 
 impl<B, O> codec::EncodeLike for DecodeDifferent<B, O> where B: Encode + 'static, O: Encode + 'static {}
 
+impl<B, O> Default for DecodeDifferent<B, O> where B: 'static, O: Default + 'static {
+	fn default() -> Self {
+		DecodeDifferent::Decoded(Default::default())
+	}
+}
+
 #[cfg(feature = "std")]
 impl<B, O> Decode for DecodeDifferent<B, O> where B: 'static, O: Decode + 'static {
 	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
@@ -193,6 +200,10 @@ pub struct StorageEntryMetadata {
 	pub ty: StorageEntryType,
 	pub default: ByteGetter,
 	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+	/// Only part of `RuntimeMetadataV12`, it is not encoded in the V11 metadata.
+	#[codec(skip)]
+	#[cfg_attr(feature = "std", serde(skip))]
+	pub max_size: MaxSizeGetter,
 }
 
 /// All the metadata about one module constant.
@@ -236,6 +247,10 @@ pub struct DefaultByteGetter(pub &'static dyn DefaultByte);
 /// Decode different for static lazy initiated byte value.
 pub type ByteGetter = DecodeDifferent<DefaultByteGetter, Vec<u8>>;
 
+/// Decode different for the lazily computed maximum size of a storage entry, `None` if the
+/// storage item is not bounded.
+pub type MaxSizeGetter = DecodeDifferent<FnEncode<Option<u32>>, Option<u32>>;
+
 impl Encode for DefaultByteGetter {
 	fn encode_to<W: Output>(&self, dest: &mut W) {
 		self.0.default_byte().encode_to(dest)
@@ -363,6 +378,8 @@ pub enum RuntimeMetadata {
 	V10(RuntimeMetadataDeprecated),
 	/// Version 11 for runtime metadata.
 	V11(RuntimeMetadataV11),
+	/// Version 12 for runtime metadata, describing the types with a portable type registry.
+	V12(RuntimeMetadataV12),
 }
 
 /// Enum that should fail.
@@ -394,7 +411,7 @@ pub struct RuntimeMetadataV11 {
 }
 
 /// The latest version of the metadata.
-pub type RuntimeMetadataLastVersion = RuntimeMetadataV11;
+pub type RuntimeMetadataLastVersion = RuntimeMetadataV12;
 
 /// All metadata about an runtime module.
 #[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
@@ -417,8 +434,240 @@ impl Into<sp_core::OpaqueMetadata> for RuntimeMetadataPrefixed {
 	}
 }
 
-impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataLastVersion {
+impl Into<sp_core::OpaqueMetadata> for PalletConstantMetadataV12 {
+	fn into(self) -> sp_core::OpaqueMetadata {
+		sp_core::OpaqueMetadata::new(self.encode())
+	}
+}
+
+impl Into<sp_core::OpaqueMetadata> for PalletStorageEntryMetadataV12 {
+	fn into(self) -> sp_core::OpaqueMetadata {
+		sp_core::OpaqueMetadata::new(self.encode())
+	}
+}
+
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV11 {
 	fn into(self) -> RuntimeMetadataPrefixed {
 		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V11(self))
 	}
 }
+
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV12 {
+	fn into(self) -> RuntimeMetadataPrefixed {
+		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V12(self))
+	}
+}
+
+/// The metadata of a runtime, referring to the types of its registry by their id.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct RuntimeMetadataV12 {
+	/// The registry of all the types referred to by the metadata.
+	pub types: PortableRegistry,
+	/// Metadata of all the modules.
+	pub modules: Vec<ModuleMetadataV12>,
+	/// Metadata of the extrinsic.
+	pub extrinsic: ExtrinsicMetadataV12,
+}
+
+impl RuntimeMetadataV12 {
+	/// Returns the module and the error that a `DispatchError::Module` refers to by its module
+	/// and error indices.
+	pub fn module_error(
+		&self,
+		index: u8,
+		error: u8,
+	) -> Option<(&ModuleMetadataV12, &ErrorMetadataV12)> {
+		let module = self.modules.iter().find(|module| module.index == index)?;
+		module.errors.get(error as usize).map(|error| (module, error))
+	}
+}
+
+/// Metadata of the extrinsic used by the runtime.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ExtrinsicMetadataV12 {
+	/// Extrinsic version.
+	pub version: u8,
+	/// The type of the outer call dispatched by the extrinsic.
+	pub call: u32,
+	/// The signed extensions in the order they appear in the extrinsic.
+	pub signed_extensions: Vec<SignedExtensionMetadataV12>,
+}
+
+/// Metadata of a signed extension.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct SignedExtensionMetadataV12 {
+	/// The unique identifier of the signed extension.
+	pub identifier: StringBuf,
+	/// The type of the signed extension, as included in the extrinsic.
+	pub ty: u32,
+	/// The type of the additional data signed with the extrinsic.
+	pub additional_signed: u32,
+}
+
+/// All metadata about a runtime module.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleMetadataV12 {
+	pub name: StringBuf,
+	/// The index of the module in the runtime, as used by `ModuleToIndex`.
+	pub index: u8,
+	pub storage: Option<StorageMetadataV12>,
+	pub calls: Option<Vec<FunctionMetadataV12>>,
+	pub event: Option<Vec<EventMetadataV12>>,
+	pub constants: Vec<ModuleConstantMetadataV12>,
+	pub errors: Vec<ErrorMetadataV12>,
+}
+
+/// All the metadata about a function.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct FunctionMetadataV12 {
+	pub name: StringBuf,
+	pub arguments: Vec<FunctionArgumentMetadataV12>,
+	pub documentation: Vec<StringBuf>,
+}
+
+/// All the metadata about a function argument.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct FunctionArgumentMetadataV12 {
+	pub name: StringBuf,
+	/// The type of the argument, `Compact<_>` for compact arguments.
+	pub ty: u32,
+	/// The type of the argument as written in the module.
+	pub type_name: StringBuf,
+}
+
+/// All the metadata about an event.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct EventMetadataV12 {
+	pub name: StringBuf,
+	pub arguments: Vec<EventArgumentMetadataV12>,
+	pub documentation: Vec<StringBuf>,
+}
+
+/// All the metadata about an event argument.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct EventArgumentMetadataV12 {
+	pub ty: u32,
+	/// The type of the argument as written in the event declaration.
+	pub type_name: StringBuf,
+}
+
+/// All metadata of the storage.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageMetadataV12 {
+	/// The common prefix used by all storage entries.
+	pub prefix: StringBuf,
+	pub entries: Vec<StorageEntryMetadataV12>,
+}
+
+/// All the metadata about one storage entry.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageEntryMetadataV12 {
+	pub name: StringBuf,
+	pub modifier: StorageEntryModifier,
+	pub ty: StorageEntryTypeV12,
+	pub default: Vec<u8>,
+	pub documentation: Vec<StringBuf>,
+	/// The maximum encoded size of the value, `None` if the storage item is not bounded.
+	pub max_size: Option<u32>,
+}
+
+/// A storage entry type, referring to the key and value types by their id.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum StorageEntryTypeV12 {
+	Plain(u32),
+	Map {
+		hasher: StorageHasher,
+		key: u32,
+		value: u32,
+	},
+	DoubleMap {
+		hasher: StorageHasher,
+		key1: u32,
+		key2: u32,
+		value: u32,
+		key2_hasher: StorageHasher,
+	},
+	/// A storage with any number of keys, e.g. a `StorageNMap`.
+	///
+	/// The V11 metadata can't express such a storage, so these entries are only described by
+	/// the V12 metadata and are missing from the V11 metadata of their module.
+	NMap {
+		keys: Vec<u32>,
+		hashers: Vec<StorageHasher>,
+		value: u32,
+	},
+}
+
+/// All the metadata about a module constant.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleConstantMetadataV12 {
+	pub name: StringBuf,
+	pub ty: u32,
+	pub value: Vec<u8>,
+	pub documentation: Vec<StringBuf>,
+}
+
+/// All the metadata about a module error.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ErrorMetadataV12 {
+	pub name: StringBuf,
+	pub documentation: Vec<StringBuf>,
+}
+
+impl From<&ErrorMetadata> for ErrorMetadataV12 {
+	fn from(error: &ErrorMetadata) -> Self {
+		Self {
+			name: decode_different_str(&error.name),
+			documentation: match &error.documentation {
+				DecodeDifferent::Encode(docs) => docs.iter().map(|d| (*d).into()).collect(),
+				DecodeDifferent::Decoded(docs) => docs.clone(),
+			},
+		}
+	}
+}
+
+/// The metadata of a single module constant, along with the types it refers to.
+///
+/// Returned by the `Metadata` runtime api to look up one constant without the full metadata.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct PalletConstantMetadataV12 {
+	/// The registry of the types referred to by the constants of the module.
+	pub types: PortableRegistry,
+	pub constant: ModuleConstantMetadataV12,
+}
+
+/// The metadata of a single storage entry, along with the types it refers to.
+///
+/// Returned by the `Metadata` runtime api to look up one storage entry without the full
+/// metadata.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct PalletStorageEntryMetadataV12 {
+	/// The registry of the types referred to by the storage entries of the module.
+	pub types: PortableRegistry,
+	/// The common prefix used by all storage entries of the module.
+	pub prefix: StringBuf,
+	pub entry: StorageEntryMetadataV12,
+}
+
+/// Returns the string of a `DecodeDifferentStr`, as built by the runtime or decoded.
+fn decode_different_str(s: &DecodeDifferentStr) -> StringBuf {
+	match s {
+		DecodeDifferent::Encode(s) => (*s).into(),
+		DecodeDifferent::Decoded(s) => s.clone(),
+	}
+}
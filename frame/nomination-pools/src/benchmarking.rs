@@ -0,0 +1,229 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Nomination pools pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account};
+
+use crate::Module as Pools;
+
+const SEED: u32 = 0;
+const MAX_NOMINATIONS: u32 = 16;
+
+// The smallest amount that can be used to create a pool.
+fn create_bond<T: Trait>() -> BalanceOf<T> {
+	Pools::<T>::min_create_bond()
+		.max(Pools::<T>::min_join_bond())
+		.max(T::StakingInterface::minimum_bond())
+		.max(T::Currency::minimum_balance())
+}
+
+// Create an account with enough funds to bond `amount` a few times over.
+fn funded_account<T: Trait>(name: &'static str, index: u32, amount: BalanceOf<T>) -> T::AccountId {
+	let who = account(name, index, SEED);
+	let balance = amount.saturating_mul(10.into()).saturating_add(T::Currency::minimum_balance());
+	let _ = T::Currency::make_free_balance_be(&who, balance);
+	who
+}
+
+// Create a pool with its depositor as root and nominator, returning the depositor and the pool.
+fn create_pool<T: Trait>() -> Result<(T::AccountId, PoolId), &'static str> {
+	let amount = create_bond::<T>();
+	let depositor = funded_account::<T>("depositor", 0, amount);
+	Pools::<T>::create(
+		RawOrigin::Signed(depositor.clone()).into(),
+		amount,
+		depositor.clone(),
+		depositor.clone(),
+	)?;
+	Ok((depositor, Pools::<T>::last_pool_id()))
+}
+
+// Have a new member join `pool_id` with the smallest possible bond.
+fn join_pool<T: Trait>(pool_id: PoolId) -> Result<(T::AccountId, BalanceOf<T>), &'static str> {
+	let amount = create_bond::<T>();
+	let member = funded_account::<T>("member", 0, amount);
+	Pools::<T>::join(RawOrigin::Signed(member.clone()).into(), amount, pool_id)?;
+	Ok((member, amount))
+}
+
+// Move the unbonding funds of `member` to the current era, so that the pool considers them
+// withdrawable without waiting for the bonding duration.
+fn make_withdrawable<T: Trait>(member_account: &T::AccountId) -> Result<(), &'static str> {
+	let current_era = T::StakingInterface::current_era();
+	let mut member = Pools::<T>::pool_members(member_account).ok_or("not a member")?;
+	let mut sub_pools = Pools::<T>::sub_pools(member.pool_id).ok_or("no sub pools")?;
+
+	let points = member.unbonding_eras.values()
+		.fold(Zero::zero(), |acc: BalanceOf<T>, points| acc.saturating_add(*points));
+	let pool = sub_pools.with_era.values()
+		.fold(UnbondPool::default(), |acc: UnbondPool<BalanceOf<T>>, pool| UnbondPool {
+			points: acc.points.saturating_add(pool.points),
+			balance: acc.balance.saturating_add(pool.balance),
+		});
+	member.unbonding_eras = vec![(current_era, points)].into_iter().collect();
+	sub_pools.with_era = vec![(current_era, pool)].into_iter().collect();
+
+	SubPoolsStorage::<T>::insert(member.pool_id, sub_pools);
+	PoolMembers::<T>::insert(member_account, member);
+	Ok(())
+}
+
+benchmarks! {
+	_ { }
+
+	create {
+		let amount = create_bond::<T>();
+		let depositor = funded_account::<T>("depositor", 0, amount);
+		let root: T::AccountId = account("root", 0, SEED);
+		let nominator: T::AccountId = account("nominator", 0, SEED);
+		let last_pool_id = Pools::<T>::last_pool_id();
+	}: _(RawOrigin::Signed(depositor), amount, root, nominator)
+	verify {
+		assert_eq!(Pools::<T>::last_pool_id(), last_pool_id + 1);
+	}
+
+	join {
+		let (_, pool_id) = create_pool::<T>()?;
+		let amount = create_bond::<T>();
+		let member = funded_account::<T>("member", 0, amount);
+	}: _(RawOrigin::Signed(member.clone()), amount, pool_id)
+	verify {
+		assert!(Pools::<T>::pool_members(&member).is_some());
+	}
+
+	bond_extra {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		// pending rewards are paid out first.
+		let amount = create_bond::<T>();
+		let _ = T::Currency::deposit_creating(&Pools::<T>::reward_account(pool_id), amount);
+	}: _(RawOrigin::Signed(depositor), amount)
+
+	claim_payout {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let amount = create_bond::<T>();
+		let _ = T::Currency::deposit_creating(&Pools::<T>::reward_account(pool_id), amount);
+	}: _(RawOrigin::Signed(depositor.clone()))
+	verify {
+		assert_eq!(Pools::<T>::pending_rewards(&depositor), Some(Zero::zero()));
+	}
+
+	unbond {
+		let (_, pool_id) = create_pool::<T>()?;
+		let (member, points) = join_pool::<T>(pool_id)?;
+		let _ = T::Currency::deposit_creating(&Pools::<T>::reward_account(pool_id), points);
+	}: _(RawOrigin::Signed(member.clone()), member.clone(), points)
+	verify {
+		assert!(Pools::<T>::pool_members(&member).unwrap().points.is_zero());
+	}
+
+	pool_withdraw_unbonded {
+		let (depositor, pool_id) = create_pool::<T>()?;
+	}: _(RawOrigin::Signed(depositor), pool_id)
+
+	withdraw_unbonded_update {
+		let (_, pool_id) = create_pool::<T>()?;
+		let (member, points) = join_pool::<T>(pool_id)?;
+		let half = points / 2.into();
+		Pools::<T>::unbond(RawOrigin::Signed(member.clone()).into(), member.clone(), half)?;
+		make_withdrawable::<T>(&member)?;
+	}: withdraw_unbonded(RawOrigin::Signed(member.clone()), member.clone())
+	verify {
+		assert!(Pools::<T>::pool_members(&member).unwrap().unbonding_eras.is_empty());
+	}
+
+	withdraw_unbonded_kill {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let points = Pools::<T>::pool_members(&depositor).ok_or("not a member")?.points;
+		Pools::<T>::set_state(
+			RawOrigin::Signed(depositor.clone()).into(),
+			pool_id,
+			PoolState::Destroying,
+		)?;
+		Pools::<T>::unbond(RawOrigin::Signed(depositor.clone()).into(), depositor.clone(), points)?;
+		make_withdrawable::<T>(&depositor)?;
+	}: withdraw_unbonded(RawOrigin::Signed(depositor.clone()), depositor.clone())
+	verify {
+		assert!(Pools::<T>::bonded_pools(pool_id).is_none());
+	}
+
+	nominate {
+		let n in 1 .. MAX_NOMINATIONS;
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let validators = (0 .. n).map(|i| account("validator", i, SEED)).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(depositor), pool_id, validators)
+
+	chill {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let validators = vec![account("validator", 0, SEED)];
+		Pools::<T>::nominate(RawOrigin::Signed(depositor.clone()).into(), pool_id, validators)?;
+	}: _(RawOrigin::Signed(depositor), pool_id)
+
+	set_state {
+		let (depositor, pool_id) = create_pool::<T>()?;
+	}: _(RawOrigin::Signed(depositor), pool_id, PoolState::Blocked)
+	verify {
+		assert_eq!(Pools::<T>::bonded_pools(pool_id).unwrap().state, PoolState::Blocked);
+	}
+
+	update_roles {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let root: T::AccountId = account("root", 0, SEED);
+		let nominator: T::AccountId = account("nominator", 0, SEED);
+	}: _(RawOrigin::Signed(depositor), pool_id, root.clone(), nominator)
+	verify {
+		assert_eq!(Pools::<T>::bonded_pools(pool_id).unwrap().roles.root, root);
+	}
+
+	set_configs {
+		let min_join_bond = create_bond::<T>();
+		let min_create_bond = min_join_bond.saturating_mul(2.into());
+	}: _(RawOrigin::Root, min_join_bond, min_create_bond)
+	verify {
+		assert_eq!(Pools::<T>::min_create_bond(), min_create_bond);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create::<Test>());
+			assert_ok!(test_benchmark_join::<Test>());
+			assert_ok!(test_benchmark_bond_extra::<Test>());
+			assert_ok!(test_benchmark_claim_payout::<Test>());
+			assert_ok!(test_benchmark_unbond::<Test>());
+			assert_ok!(test_benchmark_pool_withdraw_unbonded::<Test>());
+			assert_ok!(test_benchmark_withdraw_unbonded_update::<Test>());
+			assert_ok!(test_benchmark_withdraw_unbonded_kill::<Test>());
+			assert_ok!(test_benchmark_nominate::<Test>());
+			assert_ok!(test_benchmark_chill::<Test>());
+			assert_ok!(test_benchmark_set_state::<Test>());
+			assert_ok!(test_benchmark_update_roles::<Test>());
+			assert_ok!(test_benchmark_set_configs::<Test>());
+		});
+	}
+}
@@ -0,0 +1,1056 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Nomination Pools Module
+//!
+//! A module that allows members to delegate their stake to nominating pools. A nomination pool
+//! acts as a single nominator that nominates validators on behalf of its members.
+//!
+//! - [`nomination_pools::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! Accounts that do not hold enough funds to bond and nominate on their own can join a pool
+//! instead. Each pool owns two accounts derived from the module id: a _bonded_ account, which is
+//! the stash that holds the bonded funds of all members, and a _reward_ account, which acts as the
+//! controller of that stash and receives all of its staking rewards. All interaction with the
+//! staking system happens through the [`StakingInterface`](sp_staking::StakingInterface).
+//!
+//! ### Terminology
+//!
+//! - **Points:** Members own a share of the bonded pool, expressed in points. When the pool is
+//!   created, one point is worth one unit of balance. Slashes change this ratio, so that every
+//!   member loses a share of their stake proportional to their points.
+//! - **Reward counter:** The amount of rewards a single point has accumulated since the pool was
+//!   created. Members store the counter at which they last claimed, which allows rewards to be
+//!   accounted for lazily, in O(1) for each member.
+//! - **Unbonding pools:** Funds that are being unbonded are moved into a sub-pool specific to the
+//!   era in which they can be withdrawn. Slashes that hit unlocking funds of the pool are applied
+//!   to the sub-pool of the matching era only. Sub-pools that are older than
+//!   `PostUnbondingPoolsWindow` eras are merged into a single pool that is not tied to an era.
+//! - **Roles:** Every pool has a _depositor_, who created the pool and must be the last member to
+//!   leave, a _root_, who can change the state and roles of the pool, and a _nominator_, who
+//!   picks the validators that the pool nominates.
+//!
+//! ### Pool lifecycle
+//!
+//! A pool is created `Open`, which allows anyone to join. The root can move it to `Blocked`, after
+//! which nobody can join and the root can unbond members, or to `Destroying`, after which anyone
+//! can unbond and withdraw members. Once the depositor withdraws as the last member, all storage
+//! of the pool is removed.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create` - Create a new pool and become its depositor.
+//! - `join` - Bond funds into a pool.
+//! - `bond_extra` - Bond more funds into the pool the origin is a member of.
+//! - `claim_payout` - Claim the pending rewards of the origin.
+//! - `unbond` - Unbond points of a member.
+//! - `pool_withdraw_unbonded` - Withdraw the unlocked funds of a pool from the staking system.
+//! - `withdraw_unbonded` - Withdraw the unbonded funds of a member.
+//! - `nominate` - Nominate validators on behalf of a pool.
+//! - `chill` - Stop nominating on behalf of a pool.
+//! - `set_state` - Change the state of a pool.
+//! - `update_roles` - Change the root or the nominator of a pool.
+//! - `set_configs` - Set the minimum bonds to join or create a pool.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod benchmarking;
+
+use sp_std::{prelude::*, collections::btree_map::BTreeMap};
+use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure,
+	traits::{Currency, ExistenceRequirement, Get},
+	weights::Weight,
+};
+use frame_system::{ensure_signed, ensure_root};
+use sp_runtime::{
+	ModuleId, RuntimeDebug, DispatchError,
+	helpers_128bit::multiply_by_rational,
+	traits::{AccountIdConversion, AtLeast32Bit, Bounded, Saturating, SaturatedConversion, Zero},
+};
+use sp_staking::{EraIndex, OnStakerSlash, StakingInterface};
+
+/// The balance type of this module.
+pub type BalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// Identifier of a pool.
+pub type PoolId = u32;
+
+/// The accumulated rewards of a single point, scaled by [`REWARD_COUNTER_PRECISION`].
+pub type RewardCounter = u128;
+
+/// The precision with which reward counters are tracked.
+pub const REWARD_COUNTER_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// Compute `a * b / c` with 128 bit precision, saturating if the result does not fit into `B`.
+fn mul_div<B: AtLeast32Bit + Copy>(a: B, b: B, c: B) -> B {
+	multiply_by_rational(a.saturated_into(), b.saturated_into(), c.saturated_into())
+		.map(|r| r.saturated_into())
+		.unwrap_or_else(|_| Bounded::max_value())
+}
+
+/// The kind of account that is derived for each pool.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum AccountType {
+	/// The stash that holds the bonded funds of the pool.
+	Bonded,
+	/// The controller of the stash, which also collects all rewards of the pool.
+	Reward,
+}
+
+/// The state of a pool.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum PoolState {
+	/// The pool is open to be joined, and is working normally.
+	Open,
+	/// The pool cannot be joined, and its root can unbond its members.
+	Blocked,
+	/// The pool is being destroyed. Nobody can join, and anyone can unbond and withdraw its
+	/// members. This state cannot be left.
+	Destroying,
+}
+
+/// The accounts that hold privileged roles in a pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PoolRoles<AccountId> {
+	/// Creates the pool and is the initial member. It can only leave the pool once all other
+	/// members have left.
+	pub depositor: AccountId,
+	/// Can change the nominator, the state and the root of the pool.
+	pub root: AccountId,
+	/// Can select which validators the pool nominates.
+	pub nominator: AccountId,
+}
+
+/// The information about a bonded pool which is kept in storage.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BondedPoolInner<AccountId, Balance> {
+	/// Total points of all members of the pool that are still bonded.
+	pub points: Balance,
+	/// The state of the pool.
+	pub state: PoolState,
+	/// Number of members of the pool, including those that are only unbonding.
+	pub member_counter: u32,
+	/// The privileged roles of the pool.
+	pub roles: PoolRoles<AccountId>,
+}
+
+/// Conversion between the points and the balance of a pool.
+///
+/// If the pool has no points, points are issued 1:1. If the pool has points but no balance, the
+/// pool has been fully slashed and its points are worthless.
+fn balance_to_point<B: AtLeast32Bit + Copy>(current_balance: B, current_points: B, new_funds: B) -> B {
+	match (current_balance.is_zero(), current_points.is_zero()) {
+		(_, true) => new_funds,
+		(true, false) => new_funds.saturating_mul(current_points),
+		(false, false) => mul_div(new_funds, current_points, current_balance),
+	}
+}
+
+/// The inverse of [`balance_to_point`].
+fn point_to_balance<B: AtLeast32Bit + Copy>(current_balance: B, current_points: B, points: B) -> B {
+	if current_balance.is_zero() || current_points.is_zero() || points.is_zero() {
+		return Zero::zero();
+	}
+	mul_div(current_balance, points, current_points)
+}
+
+/// The lazily accounted rewards of a pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, Default, TypeInfo)]
+pub struct RewardPool<Balance> {
+	/// The reward counter at the time of the last change to the points of the bonded pool.
+	pub last_recorded_reward_counter: RewardCounter,
+	/// The total amount ever paid out to the reward account at the time of the last change to
+	/// the points of the bonded pool.
+	pub last_recorded_total_payouts: Balance,
+	/// The total amount of rewards claimed by members so far.
+	pub total_rewards_claimed: Balance,
+}
+
+impl<Balance: AtLeast32Bit + Copy> RewardPool<Balance> {
+	/// The reward counter given the current points of the bonded pool and the total amount ever
+	/// paid out to the reward account.
+	fn current_reward_counter(&self, bonded_points: Balance, current_total_payouts: Balance) -> RewardCounter {
+		if bonded_points.is_zero() {
+			return self.last_recorded_reward_counter;
+		}
+		let new_payouts = current_total_payouts.saturating_sub(self.last_recorded_total_payouts);
+		let increase = multiply_by_rational(
+			new_payouts.saturated_into(),
+			REWARD_COUNTER_PRECISION,
+			bonded_points.saturated_into(),
+		).unwrap_or(RewardCounter::max_value());
+		self.last_recorded_reward_counter.saturating_add(increase)
+	}
+
+	/// Record the current reward counter. This must be called before the points of the bonded
+	/// pool change, so that past rewards are accounted for with the points they were earned with.
+	fn update_records(&mut self, bonded_points: Balance, current_total_payouts: Balance) {
+		self.last_recorded_reward_counter = self.current_reward_counter(bonded_points, current_total_payouts);
+		self.last_recorded_total_payouts = current_total_payouts;
+	}
+}
+
+/// A pool of funds that are unbonding and become withdrawable in the same era.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, Default, TypeInfo)]
+pub struct UnbondPool<Balance> {
+	/// The points of all members in this pool.
+	pub points: Balance,
+	/// The funds in the pool.
+	pub balance: Balance,
+}
+
+impl<Balance: AtLeast32Bit + Copy> UnbondPool<Balance> {
+	/// Add `new_funds` to the pool, returning the points issued for them.
+	fn issue(&mut self, new_funds: Balance) -> Balance {
+		let new_points = balance_to_point(self.balance, self.points, new_funds);
+		self.points = self.points.saturating_add(new_points);
+		self.balance = self.balance.saturating_add(new_funds);
+		new_points
+	}
+
+	/// Remove `points` from the pool, returning the funds that they were worth.
+	fn dissolve(&mut self, points: Balance) -> Balance {
+		let balance = point_to_balance(self.balance, self.points, points);
+		self.points = self.points.saturating_sub(points);
+		self.balance = self.balance.saturating_sub(balance);
+		balance
+	}
+}
+
+/// All unbonding pools of a single bonded pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, Default, TypeInfo)]
+pub struct SubPools<Balance> {
+	/// Funds of eras that are older than the `PostUnbondingPoolsWindow`, merged together.
+	pub no_era: UnbondPool<Balance>,
+	/// Unbonding pools, keyed by the era in which their funds become withdrawable.
+	pub with_era: BTreeMap<EraIndex, UnbondPool<Balance>>,
+}
+
+impl<Balance: AtLeast32Bit + Copy> SubPools<Balance> {
+	/// Merge all pools of eras that are more than `window` eras older than `current_era` into
+	/// the `no_era` pool.
+	fn maybe_merge_pools(mut self, current_era: EraIndex, window: u32) -> Self {
+		if let Some(newest_era_to_remove) = current_era.checked_sub(window) {
+			let eras_to_remove = self.with_era.keys()
+				.filter(|era| **era <= newest_era_to_remove)
+				.cloned()
+				.collect::<Vec<_>>();
+			for era in eras_to_remove {
+				if let Some(pool) = self.with_era.remove(&era) {
+					self.no_era.points = self.no_era.points.saturating_add(pool.points);
+					self.no_era.balance = self.no_era.balance.saturating_add(pool.balance);
+				}
+			}
+		}
+		self
+	}
+}
+
+/// A member of a pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PoolMember<Balance> {
+	/// The pool the member belongs to.
+	pub pool_id: PoolId,
+	/// The points of the member in the bonded pool.
+	pub points: Balance,
+	/// The reward counter of the pool at the time of the last payout to this member.
+	pub last_recorded_reward_counter: RewardCounter,
+	/// The points of the member in the unbonding pools, keyed by the era in which they become
+	/// withdrawable.
+	pub unbonding_eras: BTreeMap<EraIndex, Balance>,
+}
+
+impl<Balance: AtLeast32Bit + Copy> PoolMember<Balance> {
+	/// The rewards this member can claim given the `current_reward_counter` of its pool.
+	fn pending_rewards(&self, current_reward_counter: RewardCounter) -> Balance {
+		let counter_diff = current_reward_counter.saturating_sub(self.last_recorded_reward_counter);
+		multiply_by_rational(self.points.saturated_into(), counter_diff, REWARD_COUNTER_PRECISION)
+			.map(|r| r.saturated_into())
+			.unwrap_or_else(|_| Bounded::max_value())
+	}
+
+	/// Whether this member has no funds left in the pool, neither bonded nor unbonding.
+	fn is_empty(&self) -> bool {
+		self.points.is_zero() && self.unbonding_eras.is_empty()
+	}
+}
+
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The currency of the staking system.
+	type Currency: Currency<Self::AccountId>;
+
+	/// The staking system the pools bond and nominate with.
+	type StakingInterface: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
+
+	/// The module id, used for deriving the bonded and reward accounts of every pool.
+	type ModuleId: Get<ModuleId>;
+
+	/// The number of eras an unbonding pool is kept after its funds become withdrawable, before
+	/// it is merged into the pool that is not tied to an era.
+	type PostUnbondingPoolsWindow: Get<u32>;
+
+	/// The maximum number of eras in which a member can have funds unbonding at the same time.
+	type MaxUnbonding: Get<u32>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as NominationPools {
+		/// Minimum amount to bond to join a pool.
+		pub MinJoinBond get(fn min_join_bond) config(): BalanceOf<T>;
+
+		/// Minimum bond required to create a pool.
+		///
+		/// This is the amount that the depositor must put as their initial stake in the pool, as
+		/// an indication of "skin in the game".
+		pub MinCreateBond get(fn min_create_bond) config(): BalanceOf<T>;
+
+		/// Members of all pools, keyed by their account.
+		pub PoolMembers get(fn pool_members):
+			map hasher(twox_64_concat) T::AccountId => Option<PoolMember<BalanceOf<T>>>;
+
+		/// The bonded pools, keyed by pool id.
+		pub BondedPools get(fn bonded_pools):
+			map hasher(twox_64_concat) PoolId => Option<BondedPoolInner<T::AccountId, BalanceOf<T>>>;
+
+		/// The number of existing bonded pools.
+		pub CounterForBondedPools get(fn counter_for_bonded_pools): u32;
+
+		/// The reward pools, keyed by pool id. There is one for every bonded pool.
+		pub RewardPools get(fn reward_pools):
+			map hasher(twox_64_concat) PoolId => Option<RewardPool<BalanceOf<T>>>;
+
+		/// The unbonding pools of every bonded pool, keyed by pool id.
+		pub SubPoolsStorage get(fn sub_pools):
+			map hasher(twox_64_concat) PoolId => Option<SubPools<BalanceOf<T>>>;
+
+		/// Lookup from the bonded account of a pool to its id. Used when the pool is slashed.
+		pub ReversePoolIdLookup get(fn reverse_pool_id_lookup):
+			map hasher(twox_64_concat) T::AccountId => Option<PoolId>;
+
+		/// The id of the last pool that was created.
+		pub LastPoolId get(fn last_pool_id): PoolId;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// A pool has been created by the given depositor.
+		Created(AccountId, PoolId),
+		/// A member has bonded this amount into the pool. The flag is true if the member joined
+		/// the pool with this bond.
+		Bonded(AccountId, PoolId, Balance, bool),
+		/// A payout has been made to a member.
+		PaidOut(AccountId, PoolId, Balance),
+		/// A member has unbonded this amount from the pool.
+		Unbonded(AccountId, PoolId, Balance),
+		/// A member has withdrawn this amount from the pool.
+		Withdrawn(AccountId, PoolId, Balance),
+		/// A member has left the pool.
+		MemberRemoved(PoolId, AccountId),
+		/// A pool has been destroyed.
+		Destroyed(PoolId),
+		/// The state of a pool has changed.
+		StateChanged(PoolId, PoolState),
+		/// The roles of a pool have been updated to the given root and nominator.
+		RolesUpdated(PoolId, AccountId, AccountId),
+		/// The active funds of a pool have been slashed to the given balance.
+		PoolSlashed(PoolId, Balance),
+		/// The unbonding pool of the given era has been slashed to the given balance.
+		UnbondingPoolSlashed(PoolId, EraIndex, Balance),
+	}
+);
+
+decl_error! {
+	/// Error for the nomination pools module.
+	pub enum Error for Module<T: Trait> {
+		/// A (bonded) pool id does not exist.
+		PoolNotFound,
+		/// An account is not a member.
+		PoolMemberNotFound,
+		/// A reward pool does not exist. In all cases this is a system logic error.
+		RewardPoolNotFound,
+		/// A sub pool does not exist.
+		SubPoolsNotFound,
+		/// An account is already delegating in another pool. An account may only belong to one
+		/// pool at a time.
+		AccountBelongsToOtherPool,
+		/// The amount does not meet the minimum bond to either join or create a pool.
+		MinimumBondNotMet,
+		/// The pool is not open to join.
+		NotOpen,
+		/// The member has too many eras with funds unbonding. Withdraw some of them first.
+		MaxUnbondingLimit,
+		/// None of the funds can be withdrawn yet because the bonding duration has not passed.
+		CannotWithdrawAny,
+		/// The amount to unbond exceeds the points of the member.
+		NotEnoughPointsToUnbond,
+		/// The caller does not have enough permission to perform this operation.
+		DoesNotHavePermission,
+		/// The depositor can only unbond below the minimum create bond once the pool is being
+		/// destroyed and they are the last member.
+		MinimumCreateBondNotMaintained,
+		/// The pool is being destroyed, its state cannot be changed any more.
+		CanNotChangeState,
+		/// The pool has been fully slashed, joining it is not possible any more.
+		OverflowRisk,
+		/// The reward account cannot be funded with the existential deposit.
+		RewardAccountNotFunded,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The module id, used for deriving the bonded and reward accounts of every pool.
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// The number of eras an unbonding pool is kept after its funds become withdrawable.
+		const PostUnbondingPoolsWindow: u32 = T::PostUnbondingPoolsWindow::get();
+
+		/// The maximum number of eras in which a member can have funds unbonding.
+		const MaxUnbonding: u32 = T::MaxUnbonding::get();
+
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Create a new nomination pool, bonding `amount` from the origin, who becomes the
+		/// depositor and first member of the pool.
+		///
+		/// The existential deposit is transferred from the origin to the reward account of the
+		/// pool, in addition to `amount`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Bonds through the staking system.
+		/// # </weight>
+		#[weight = 400_000_000 + T::DbWeight::get().reads_writes(6, 8)]
+		fn create(origin,
+			#[compact] amount: BalanceOf<T>,
+			root: T::AccountId,
+			nominator: T::AccountId,
+		) {
+			let depositor = ensure_signed(origin)?;
+
+			ensure!(
+				amount >= Self::min_create_bond()
+					&& amount >= Self::min_join_bond()
+					&& amount >= T::StakingInterface::minimum_bond(),
+				Error::<T>::MinimumBondNotMet,
+			);
+			ensure!(!PoolMembers::<T>::contains_key(&depositor), Error::<T>::AccountBelongsToOtherPool);
+
+			let pool_id = Self::last_pool_id().checked_add(1).ok_or(Error::<T>::OverflowRisk)?;
+			let bonded_account = Self::bonded_account(pool_id);
+			let reward_account = Self::reward_account(pool_id);
+
+			// The reward account is the controller of the pool, and rewards can only be paid into
+			// an existing account.
+			T::Currency::transfer(
+				&depositor,
+				&reward_account,
+				T::Currency::minimum_balance(),
+				ExistenceRequirement::KeepAlive,
+			).map_err(|_| Error::<T>::RewardAccountNotFunded)?;
+			T::Currency::transfer(&depositor, &bonded_account, amount, ExistenceRequirement::KeepAlive)?;
+			T::StakingInterface::bond(bonded_account.clone(), reward_account, amount)?;
+
+			LastPoolId::put(pool_id);
+			BondedPools::<T>::insert(pool_id, BondedPoolInner {
+				points: amount,
+				state: PoolState::Open,
+				member_counter: 1,
+				roles: PoolRoles { depositor: depositor.clone(), root, nominator },
+			});
+			CounterForBondedPools::mutate(|c| *c = c.saturating_add(1));
+			RewardPools::<T>::insert(pool_id, RewardPool::default());
+			ReversePoolIdLookup::<T>::insert(&bonded_account, pool_id);
+			PoolMembers::<T>::insert(&depositor, PoolMember {
+				pool_id,
+				points: amount,
+				last_recorded_reward_counter: Zero::zero(),
+				unbonding_eras: BTreeMap::new(),
+			});
+
+			Self::deposit_event(RawEvent::Created(depositor.clone(), pool_id));
+			Self::deposit_event(RawEvent::Bonded(depositor, pool_id, amount, true));
+		}
+
+		/// Stake `amount` of the free balance of the origin into the pool `pool_id`.
+		///
+		/// The origin must not be a member of any pool yet, and the pool must be `Open`.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Bonds through the staking system.
+		/// # </weight>
+		#[weight = 400_000_000 + T::DbWeight::get().reads_writes(6, 5)]
+		fn join(origin, #[compact] amount: BalanceOf<T>, pool_id: PoolId) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(amount >= Self::min_join_bond(), Error::<T>::MinimumBondNotMet);
+			ensure!(!PoolMembers::<T>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+
+			let mut bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.state == PoolState::Open, Error::<T>::NotOpen);
+			let mut reward_pool = Self::reward_pools(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			let points_issued = Self::do_bond(&who, pool_id, &mut bonded_pool, &mut reward_pool, amount)?;
+			bonded_pool.member_counter = bonded_pool.member_counter.saturating_add(1);
+
+			PoolMembers::<T>::insert(&who, PoolMember {
+				pool_id,
+				points: points_issued,
+				// rewards that were earned before joining belong to the existing members.
+				last_recorded_reward_counter: reward_pool.last_recorded_reward_counter,
+				unbonding_eras: BTreeMap::new(),
+			});
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+
+			Self::deposit_event(RawEvent::Bonded(who, pool_id, amount, true));
+		}
+
+		/// Bond `extra` more of the free balance of the origin into the pool it is a member of.
+		///
+		/// Any pending rewards are paid out to the origin first.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Bonds through the staking system.
+		/// # </weight>
+		#[weight = 400_000_000 + T::DbWeight::get().reads_writes(6, 4)]
+		fn bond_extra(origin, #[compact] extra: BalanceOf<T>) {
+			let who = ensure_signed(origin)?;
+
+			let mut member = Self::pool_members(&who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.state == PoolState::Open, Error::<T>::NotOpen);
+			let mut reward_pool = Self::reward_pools(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			Self::do_reward_payout(&who, &mut member, &bonded_pool, &mut reward_pool)?;
+
+			let points_issued = Self::do_bond(&who, pool_id, &mut bonded_pool, &mut reward_pool, extra)?;
+			member.points = member.points.saturating_add(points_issued);
+
+			PoolMembers::<T>::insert(&who, member);
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+
+			Self::deposit_event(RawEvent::Bonded(who, pool_id, extra, false));
+		}
+
+		/// Pay out the pending rewards of the origin to its free balance.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = 200_000_000 + T::DbWeight::get().reads_writes(4, 3)]
+		fn claim_payout(origin) {
+			let who = ensure_signed(origin)?;
+
+			let mut member = Self::pool_members(&who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let bonded_pool = Self::bonded_pools(member.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut reward_pool = Self::reward_pools(member.pool_id)
+				.ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			Self::do_reward_payout(&who, &mut member, &bonded_pool, &mut reward_pool)?;
+
+			RewardPools::<T>::insert(member.pool_id, reward_pool);
+			PoolMembers::<T>::insert(&who, member);
+		}
+
+		/// Unbond `unbonding_points` of `member_account` from its pool.
+		///
+		/// The funds are moved into the unbonding pool of the era in which they can be withdrawn,
+		/// and any pending rewards of the member are paid out.
+		///
+		/// The origin can be:
+		/// - the member itself, in which case the depositor must keep at least the minimum
+		///   create bond, unless the pool is being destroyed and they are the last member.
+		/// - the root of the pool, if the pool is `Blocked`.
+		/// - any account, if the pool is `Destroying`.
+		///
+		/// # <weight>
+		/// - O(U), where U is the number of unbonding eras of the member, bounded by
+		///   `MaxUnbonding`.
+		/// - Unbonds through the staking system.
+		/// # </weight>
+		#[weight = 400_000_000 + T::DbWeight::get().reads_writes(8, 5)]
+		fn unbond(origin, member_account: T::AccountId, #[compact] unbonding_points: BalanceOf<T>) {
+			let caller = ensure_signed(origin)?;
+
+			let mut member = Self::pool_members(&member_account)
+				.ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut reward_pool = Self::reward_pools(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+
+			ensure!(unbonding_points <= member.points, Error::<T>::NotEnoughPointsToUnbond);
+			ensure!(
+				Self::can_unbond(&caller, &member_account, &bonded_pool),
+				Error::<T>::DoesNotHavePermission,
+			);
+
+			let reward_account = Self::reward_account(pool_id);
+			let bonded_balance = T::StakingInterface::active_stake(&reward_account)
+				.unwrap_or_else(Zero::zero);
+
+			if member_account == bonded_pool.roles.depositor {
+				let remaining = point_to_balance(
+					bonded_balance,
+					bonded_pool.points,
+					member.points.saturating_sub(unbonding_points),
+				);
+				let is_last_member_leaving = bonded_pool.state == PoolState::Destroying
+					&& bonded_pool.member_counter == 1
+					&& unbonding_points == member.points;
+				ensure!(
+					remaining >= Self::min_create_bond() || is_last_member_leaving,
+					Error::<T>::MinimumCreateBondNotMaintained,
+				);
+			}
+
+			let current_era = T::StakingInterface::current_era();
+			let unbond_era = current_era.saturating_add(T::StakingInterface::bonding_duration());
+			ensure!(
+				member.unbonding_eras.contains_key(&unbond_era)
+					|| (member.unbonding_eras.len() as u32) < T::MaxUnbonding::get(),
+				Error::<T>::MaxUnbondingLimit,
+			);
+
+			let unbonding_balance = point_to_balance(bonded_balance, bonded_pool.points, unbonding_points);
+			T::StakingInterface::unbond(reward_account.clone(), unbonding_balance)?;
+
+			// the reward account always keeps the existential deposit, so the payout cannot fail
+			// once the funds have been unbonded.
+			Self::do_reward_payout(&member_account, &mut member, &bonded_pool, &mut reward_pool)?;
+
+			// the points of the bonded pool are about to change.
+			let current_total_payouts = Self::current_total_payouts(&reward_account, &reward_pool);
+			reward_pool.update_records(bonded_pool.points, current_total_payouts);
+
+			let mut sub_pools = Self::sub_pools(pool_id)
+				.unwrap_or_default()
+				.maybe_merge_pools(current_era, T::PostUnbondingPoolsWindow::get());
+			let unbonding_pool_points = sub_pools.with_era
+				.entry(unbond_era)
+				.or_insert_with(UnbondPool::default)
+				.issue(unbonding_balance);
+			let points = member.unbonding_eras.entry(unbond_era).or_insert_with(Zero::zero);
+			*points = points.saturating_add(unbonding_pool_points);
+
+			member.points = member.points.saturating_sub(unbonding_points);
+			bonded_pool.points = bonded_pool.points.saturating_sub(unbonding_points);
+
+			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+			PoolMembers::<T>::insert(&member_account, member);
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+			RewardPools::<T>::insert(pool_id, reward_pool);
+
+			Self::deposit_event(RawEvent::Unbonded(member_account, pool_id, unbonding_balance));
+		}
+
+		/// Withdraw all funds of the pool `pool_id` whose unbonding period has passed from the
+		/// staking system, so that they can be withdrawn by the members.
+		///
+		/// This is called by `withdraw_unbonded` as well, but can be called separately by anyone.
+		///
+		/// # <weight>
+		/// - O(1), plus the cost of withdrawing from the staking system.
+		/// # </weight>
+		#[weight = 300_000_000 + T::DbWeight::get().reads_writes(3, 2)]
+		fn pool_withdraw_unbonded(origin, pool_id: PoolId) {
+			let _ = ensure_signed(origin)?;
+			ensure!(BondedPools::<T>::contains_key(pool_id), Error::<T>::PoolNotFound);
+			T::StakingInterface::withdraw_unbonded(Self::reward_account(pool_id))?;
+		}
+
+		/// Withdraw the funds of `member_account` whose unbonding period has passed.
+		///
+		/// The origin can be the member itself, or any account if the pool is `Destroying`. If the
+		/// member has no funds left in the pool afterwards, it is removed. Once the depositor
+		/// leaves as the last member, the pool is destroyed and the remaining balance of its
+		/// accounts is transferred to the depositor.
+		///
+		/// # <weight>
+		/// - O(U), where U is the number of unbonding eras of the member, bounded by
+		///   `MaxUnbonding`.
+		/// - Withdraws through the staking system.
+		/// # </weight>
+		#[weight = 500_000_000 + T::DbWeight::get().reads_writes(8, 10)]
+		fn withdraw_unbonded(origin, member_account: T::AccountId) {
+			let caller = ensure_signed(origin)?;
+
+			let mut member = Self::pool_members(&member_account)
+				.ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut sub_pools = Self::sub_pools(pool_id).ok_or(Error::<T>::SubPoolsNotFound)?;
+
+			ensure!(
+				caller == member_account || bonded_pool.state == PoolState::Destroying,
+				Error::<T>::DoesNotHavePermission,
+			);
+
+			let current_era = T::StakingInterface::current_era();
+			let withdrawable_eras = member.unbonding_eras.iter()
+				.filter(|(era, _)| **era <= current_era)
+				.map(|(era, points)| (*era, *points))
+				.collect::<Vec<_>>();
+			ensure!(!withdrawable_eras.is_empty(), Error::<T>::CannotWithdrawAny);
+
+			let reward_account = Self::reward_account(pool_id);
+			let bonded_account = Self::bonded_account(pool_id);
+			T::StakingInterface::withdraw_unbonded(reward_account.clone())?;
+
+			sub_pools = sub_pools.maybe_merge_pools(current_era, T::PostUnbondingPoolsWindow::get());
+			let mut balance_to_withdraw: BalanceOf<T> = Zero::zero();
+			for (era, points) in withdrawable_eras {
+				member.unbonding_eras.remove(&era);
+				let balance = match sub_pools.with_era.get_mut(&era) {
+					Some(pool) => pool.dissolve(points),
+					// the pool of this era has been merged.
+					None => sub_pools.no_era.dissolve(points),
+				};
+				balance_to_withdraw = balance_to_withdraw.saturating_add(balance);
+			}
+			// remove any era pools that have been emptied.
+			let empty_eras = sub_pools.with_era.iter()
+				.filter(|(_, pool)| pool.points.is_zero())
+				.map(|(era, _)| *era)
+				.collect::<Vec<_>>();
+			for era in empty_eras {
+				sub_pools.with_era.remove(&era);
+			}
+
+			// slashes may have left less funds in the bonded account than accounted for. Only the
+			// funds that the staking system no longer holds can be transferred; the rest is still
+			// locked for the other members.
+			let locked = T::StakingInterface::total_stake(&reward_account)
+				.unwrap_or_else(Zero::zero);
+			let transferable = T::Currency::free_balance(&bonded_account).saturating_sub(locked);
+			let balance_to_withdraw = balance_to_withdraw.min(transferable);
+			T::Currency::transfer(
+				&bonded_account,
+				&member_account,
+				balance_to_withdraw,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(RawEvent::Withdrawn(member_account.clone(), pool_id, balance_to_withdraw));
+
+			if member.is_empty() {
+				PoolMembers::<T>::remove(&member_account);
+				bonded_pool.member_counter = bonded_pool.member_counter.saturating_sub(1);
+				Self::deposit_event(RawEvent::MemberRemoved(pool_id, member_account.clone()));
+
+				if member_account == bonded_pool.roles.depositor && bonded_pool.member_counter == 0 {
+					Self::dissolve_pool(pool_id, &bonded_pool.roles.depositor);
+					return Ok(());
+				}
+			} else {
+				PoolMembers::<T>::insert(&member_account, member);
+			}
+
+			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+		}
+
+		/// Nominate `validators` on behalf of the pool `pool_id`.
+		///
+		/// The origin must be the root or the nominator of the pool.
+		///
+		/// # <weight>
+		/// - O(V), where V is the number of validators, plus the cost of nominating.
+		/// # </weight>
+		#[weight = 300_000_000
+			+ 1_000_000 * validators.len() as Weight
+			+ T::DbWeight::get().reads_writes(2, 1)]
+		fn nominate(origin, pool_id: PoolId, validators: Vec<T::AccountId>) {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == bonded_pool.roles.root || who == bonded_pool.roles.nominator,
+				Error::<T>::DoesNotHavePermission,
+			);
+			T::StakingInterface::nominate(Self::reward_account(pool_id), validators)?;
+		}
+
+		/// Stop nominating on behalf of the pool `pool_id`.
+		///
+		/// The origin must be the root or the nominator of the pool.
+		///
+		/// # <weight>
+		/// - O(1), plus the cost of chilling.
+		/// # </weight>
+		#[weight = 200_000_000 + T::DbWeight::get().reads_writes(2, 1)]
+		fn chill(origin, pool_id: PoolId) {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == bonded_pool.roles.root || who == bonded_pool.roles.nominator,
+				Error::<T>::DoesNotHavePermission,
+			);
+			T::StakingInterface::chill(Self::reward_account(pool_id))?;
+		}
+
+		/// Set the state of the pool `pool_id`.
+		///
+		/// The origin must be the root of the pool. A pool that is `Destroying` cannot change its
+		/// state any more.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn set_state(origin, pool_id: PoolId, state: PoolState) {
+			let who = ensure_signed(origin)?;
+			let mut bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(who == bonded_pool.roles.root, Error::<T>::DoesNotHavePermission);
+			ensure!(bonded_pool.state != PoolState::Destroying, Error::<T>::CanNotChangeState);
+
+			bonded_pool.state = state;
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+
+			Self::deposit_event(RawEvent::StateChanged(pool_id, state));
+		}
+
+		/// Update the root and the nominator of the pool `pool_id`.
+		///
+		/// The origin must be the current root of the pool, or _Root_.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(1, 1)]
+		fn update_roles(origin, pool_id: PoolId, new_root: T::AccountId, new_nominator: T::AccountId) {
+			let mut bonded_pool = Self::bonded_pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			if ensure_root(origin.clone()).is_err() {
+				let who = ensure_signed(origin)?;
+				ensure!(who == bonded_pool.roles.root, Error::<T>::DoesNotHavePermission);
+			}
+
+			bonded_pool.roles.root = new_root.clone();
+			bonded_pool.roles.nominator = new_nominator.clone();
+			BondedPools::<T>::insert(pool_id, bonded_pool);
+
+			Self::deposit_event(RawEvent::RolesUpdated(pool_id, new_root, new_nominator));
+		}
+
+		/// Set the minimum bonds to join and to create a pool.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = 10_000_000 + T::DbWeight::get().writes(2)]
+		fn set_configs(origin, #[compact] min_join_bond: BalanceOf<T>, #[compact] min_create_bond: BalanceOf<T>) {
+			ensure_root(origin)?;
+			MinJoinBond::<T>::put(min_join_bond);
+			MinCreateBond::<T>::put(min_create_bond);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account that holds the bonded funds of the pool `id`.
+	pub fn bonded_account(id: PoolId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((AccountType::Bonded, id))
+	}
+
+	/// The account that controls the bonded funds and collects the rewards of the pool `id`.
+	pub fn reward_account(id: PoolId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((AccountType::Reward, id))
+	}
+
+	/// The pending rewards of `who`, if it is a member of a pool.
+	pub fn pending_rewards(who: &T::AccountId) -> Option<BalanceOf<T>> {
+		let member = Self::pool_members(who)?;
+		let bonded_pool = Self::bonded_pools(member.pool_id)?;
+		let reward_pool = Self::reward_pools(member.pool_id)?;
+		let current_total_payouts = Self::current_total_payouts(
+			&Self::reward_account(member.pool_id),
+			&reward_pool,
+		);
+		let current_reward_counter = reward_pool.current_reward_counter(
+			bonded_pool.points,
+			current_total_payouts,
+		);
+		Some(member.pending_rewards(current_reward_counter))
+	}
+
+	/// The total amount ever paid out to the reward account of a pool.
+	fn current_total_payouts(
+		reward_account: &T::AccountId,
+		reward_pool: &RewardPool<BalanceOf<T>>,
+	) -> BalanceOf<T> {
+		// the existential deposit of the reward account is not a reward.
+		T::Currency::free_balance(reward_account)
+			.saturating_sub(T::Currency::minimum_balance())
+			.saturating_add(reward_pool.total_rewards_claimed)
+	}
+
+	/// Whether `caller` may unbond the funds of `member_account` from `bonded_pool`.
+	fn can_unbond(
+		caller: &T::AccountId,
+		member_account: &T::AccountId,
+		bonded_pool: &BondedPoolInner<T::AccountId, BalanceOf<T>>,
+	) -> bool {
+		caller == member_account
+			|| bonded_pool.state == PoolState::Destroying
+			|| (bonded_pool.state == PoolState::Blocked && *caller == bonded_pool.roles.root)
+	}
+
+	/// Transfer `amount` from `who` to the pool and bond it, returning the points issued.
+	///
+	/// The reward records are updated before the points of the pool change.
+	fn do_bond(
+		who: &T::AccountId,
+		pool_id: PoolId,
+		bonded_pool: &mut BondedPoolInner<T::AccountId, BalanceOf<T>>,
+		reward_pool: &mut RewardPool<BalanceOf<T>>,
+		amount: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let reward_account = Self::reward_account(pool_id);
+		let bonded_account = Self::bonded_account(pool_id);
+
+		let bonded_balance = T::StakingInterface::active_stake(&reward_account)
+			.unwrap_or_else(Zero::zero);
+		ensure!(
+			!bonded_balance.is_zero() || bonded_pool.points.is_zero(),
+			Error::<T>::OverflowRisk,
+		);
+		let points_issued = balance_to_point(bonded_balance, bonded_pool.points, amount);
+
+		let current_total_payouts = Self::current_total_payouts(&reward_account, reward_pool);
+		reward_pool.update_records(bonded_pool.points, current_total_payouts);
+
+		T::Currency::transfer(who, &bonded_account, amount, ExistenceRequirement::KeepAlive)?;
+		T::StakingInterface::bond_extra(bonded_account, amount)?;
+
+		bonded_pool.points = bonded_pool.points.saturating_add(points_issued);
+		Ok(points_issued)
+	}
+
+	/// Pay out the pending rewards of `member`.
+	fn do_reward_payout(
+		member_account: &T::AccountId,
+		member: &mut PoolMember<BalanceOf<T>>,
+		bonded_pool: &BondedPoolInner<T::AccountId, BalanceOf<T>>,
+		reward_pool: &mut RewardPool<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let reward_account = Self::reward_account(member.pool_id);
+		let current_total_payouts = Self::current_total_payouts(&reward_account, reward_pool);
+		let current_reward_counter = reward_pool.current_reward_counter(
+			bonded_pool.points,
+			current_total_payouts,
+		);
+		let pending_rewards = member.pending_rewards(current_reward_counter);
+		member.last_recorded_reward_counter = current_reward_counter;
+
+		if pending_rewards.is_zero() {
+			return Ok(pending_rewards);
+		}
+
+		reward_pool.total_rewards_claimed =
+			reward_pool.total_rewards_claimed.saturating_add(pending_rewards);
+		T::Currency::transfer(
+			&reward_account,
+			member_account,
+			pending_rewards,
+			ExistenceRequirement::KeepAlive,
+		)?;
+
+		Self::deposit_event(RawEvent::PaidOut(member_account.clone(), member.pool_id, pending_rewards));
+		Ok(pending_rewards)
+	}
+
+	/// Remove all storage of the pool `pool_id`, and transfer anything left in its accounts to
+	/// the `depositor`.
+	fn dissolve_pool(pool_id: PoolId, depositor: &T::AccountId) {
+		let bonded_account = Self::bonded_account(pool_id);
+		let reward_account = Self::reward_account(pool_id);
+
+		BondedPools::<T>::remove(pool_id);
+		RewardPools::<T>::remove(pool_id);
+		SubPoolsStorage::<T>::remove(pool_id);
+		ReversePoolIdLookup::<T>::remove(&bonded_account);
+		CounterForBondedPools::mutate(|c| *c = c.saturating_sub(1));
+
+		// anything left is dust from rounding or rewards that could not be claimed.
+		for account in &[reward_account, bonded_account] {
+			let remaining = T::Currency::free_balance(account);
+			if !remaining.is_zero() {
+				let _ = T::Currency::transfer(
+					account,
+					depositor,
+					remaining,
+					ExistenceRequirement::AllowDeath,
+				);
+			}
+		}
+
+		Self::deposit_event(RawEvent::Destroyed(pool_id));
+	}
+}
+
+impl<T: Trait> OnStakerSlash<T::AccountId, BalanceOf<T>> for Module<T> {
+	fn on_slash(
+		pool_account: &T::AccountId,
+		slashed_active: BalanceOf<T>,
+		slashed_unlocking: &BTreeMap<EraIndex, BalanceOf<T>>,
+	) {
+		let pool_id = match Self::reverse_pool_id_lookup(pool_account) {
+			Some(pool_id) => pool_id,
+			// not a pool.
+			None => return,
+		};
+
+		// the active funds are read from the staking system, so the value of all points of the
+		// bonded pool is already reduced proportionally.
+		Self::deposit_event(RawEvent::PoolSlashed(pool_id, slashed_active));
+
+		if let Some(mut sub_pools) = Self::sub_pools(pool_id) {
+			for (era, slashed_balance) in slashed_unlocking.iter() {
+				if let Some(pool) = sub_pools.with_era.get_mut(era) {
+					pool.balance = *slashed_balance;
+					Self::deposit_event(RawEvent::UnbondingPoolSlashed(pool_id, *era, *slashed_balance));
+				}
+			}
+			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+		}
+	}
+}
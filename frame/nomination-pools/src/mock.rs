@@ -0,0 +1,314 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+use std::{cell::RefCell, collections::HashMap};
+use frame_support::{
+	impl_outer_origin, impl_outer_event, parameter_types,
+	weights::Weight,
+};
+use sp_core::H256;
+use sp_runtime::{
+	Perbill, DispatchResult,
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+};
+use crate as pools;
+
+pub type AccountId = u128;
+pub type Balance = u128;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		pools<T>,
+	}
+}
+
+/// An unlocking chunk of the mock staking system.
+#[derive(Clone, Debug)]
+pub struct MockUnlockChunk {
+	pub value: Balance,
+	pub era: EraIndex,
+}
+
+/// The ledger of a stash in the mock staking system.
+#[derive(Clone, Debug, Default)]
+pub struct MockLedger {
+	pub stash: AccountId,
+	pub active: Balance,
+	pub unlocking: Vec<MockUnlockChunk>,
+}
+
+thread_local! {
+	pub static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(0);
+	pub static LEDGERS: RefCell<HashMap<AccountId, MockLedger>> = RefCell::new(HashMap::new());
+	pub static NOMINATIONS: RefCell<HashMap<AccountId, Vec<AccountId>>> = RefCell::new(HashMap::new());
+}
+
+/// A minimal staking system that keeps its ledgers in thread local storage.
+///
+/// Bonded funds are not locked; they are left in the stash, which is where the pool expects to
+/// find them once they are withdrawn.
+pub struct StakingMock;
+
+impl StakingMock {
+	pub fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+
+	pub fn ledger(controller: &AccountId) -> Option<MockLedger> {
+		LEDGERS.with(|l| l.borrow().get(controller).cloned())
+	}
+
+	pub fn nominations(controller: &AccountId) -> Option<Vec<AccountId>> {
+		NOMINATIONS.with(|n| n.borrow().get(controller).cloned())
+	}
+
+	/// Slash `value` from the ledger of `controller`, active funds first, and report it to the
+	/// pools the same way the staking module does.
+	pub fn slash(controller: &AccountId, mut value: Balance) {
+		let mut ledger = Self::ledger(controller).expect("controller is bonded");
+		let from_active = value.min(ledger.active);
+		ledger.active -= from_active;
+		value -= from_active;
+
+		let mut slashed_unlocking = BTreeMap::new();
+		for chunk in ledger.unlocking.iter_mut() {
+			if value.is_zero() {
+				break;
+			}
+			let from_chunk = value.min(chunk.value);
+			chunk.value -= from_chunk;
+			value -= from_chunk;
+			slashed_unlocking.insert(chunk.era, chunk.value);
+		}
+
+		let slashed = Self::ledger(controller).unwrap().total() - ledger.total();
+		let _ = Balances::slash(&ledger.stash, slashed);
+		let stash = ledger.stash;
+		let active = ledger.active;
+		LEDGERS.with(|l| l.borrow_mut().insert(*controller, ledger));
+		<Pools as OnStakerSlash<AccountId, Balance>>::on_slash(&stash, active, &slashed_unlocking);
+	}
+}
+
+impl MockLedger {
+	pub fn total(&self) -> Balance {
+		self.active + self.unlocking.iter().map(|c| c.value).sum::<Balance>()
+	}
+}
+
+/// The controller of `stash`, if it is bonded.
+fn controller_of(stash: &AccountId) -> Option<AccountId> {
+	LEDGERS.with(|l| l.borrow().iter().find(|(_, ledger)| ledger.stash == *stash).map(|(c, _)| *c))
+}
+
+impl sp_staking::StakingInterface for StakingMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn minimum_bond() -> Self::Balance {
+		10
+	}
+
+	fn bonding_duration() -> EraIndex {
+		3
+	}
+
+	fn current_era() -> EraIndex {
+		CURRENT_ERA.with(|e| *e.borrow())
+	}
+
+	fn active_stake(controller: &Self::AccountId) -> Option<Self::Balance> {
+		Self::ledger(controller).map(|l| l.active)
+	}
+
+	fn total_stake(controller: &Self::AccountId) -> Option<Self::Balance> {
+		Self::ledger(controller).map(|l| l.total())
+	}
+
+	fn bond(stash: Self::AccountId, controller: Self::AccountId, value: Self::Balance) -> DispatchResult {
+		LEDGERS.with(|l| {
+			l.borrow_mut().insert(controller, MockLedger { stash, active: value, unlocking: vec![] })
+		});
+		Ok(())
+	}
+
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult {
+		let controller = controller_of(&stash).ok_or("not a stash")?;
+		LEDGERS.with(|l| {
+			l.borrow_mut().get_mut(&controller).map(|ledger| ledger.active += extra)
+		});
+		Ok(())
+	}
+
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult {
+		let era = Self::current_era() + Self::bonding_duration();
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or("not a controller")?;
+			if value > ledger.active {
+				return Err("insufficient active stake".into());
+			}
+			ledger.active -= value;
+			ledger.unlocking.push(MockUnlockChunk { value, era });
+			Ok(())
+		})
+	}
+
+	fn withdraw_unbonded(controller: Self::AccountId) -> Result<bool, DispatchError> {
+		let current_era = Self::current_era();
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or("not a controller")?;
+			ledger.unlocking.retain(|chunk| chunk.era > current_era);
+			if ledger.active.is_zero() && ledger.unlocking.is_empty() {
+				ledgers.remove(&controller);
+				Ok(true)
+			} else {
+				Ok(false)
+			}
+		})
+	}
+
+	fn nominate(controller: Self::AccountId, validators: Vec<Self::AccountId>) -> DispatchResult {
+		Self::ledger(&controller).ok_or("not a controller")?;
+		NOMINATIONS.with(|n| n.borrow_mut().insert(controller, validators));
+		Ok(())
+	}
+
+	fn chill(controller: Self::AccountId) -> DispatchResult {
+		NOMINATIONS.with(|n| n.borrow_mut().remove(&controller));
+		Ok(())
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	// `u128` so that the sub-accounts of the pools do not collide with each other.
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 5;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = TestEvent;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub const PoolsModuleId: ModuleId = ModuleId(*b"py/nopls");
+	pub const PostUnbondingPoolsWindow: u32 = 2;
+	pub const MaxUnbonding: u32 = 4;
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type Currency = Balances;
+	type StakingInterface = StakingMock;
+	type ModuleId = PoolsModuleId;
+	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
+	type MaxUnbonding = MaxUnbonding;
+}
+
+pub type Pools = Module<Test>;
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+
+/// The depositor of the pool created in `new_test_ext`.
+pub const DEPOSITOR: AccountId = 10;
+/// The root and nominator of the pool created in `new_test_ext`.
+pub const ROOT: AccountId = 900;
+
+/// Build the externalities with a single pool, created by `DEPOSITOR` with a bond of 10.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(DEPOSITOR, 100),
+			(20, 100),
+			(30, 100),
+			(40, 100),
+			(ROOT, 100),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+	GenesisConfig::<Test> {
+		min_join_bond: 2,
+		min_create_bond: 10,
+	}.assimilate_storage(&mut t).unwrap();
+
+	CURRENT_ERA.with(|e| *e.borrow_mut() = 0);
+	LEDGERS.with(|l| l.borrow_mut().clear());
+	NOMINATIONS.with(|n| n.borrow_mut().clear());
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert!(Pools::create(Origin::signed(DEPOSITOR), 10, ROOT, ROOT).is_ok());
+	});
+	ext
+}
+
+/// The events of this module that have been deposited so far.
+pub fn pool_events() -> Vec<RawEvent<AccountId, Balance>> {
+	System::events().into_iter().filter_map(|r| match r.event {
+		TestEvent::pools(inner) => Some(inner),
+		_ => None,
+	}).collect()
+}
@@ -0,0 +1,452 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module.
+
+use super::*;
+use mock::{
+	Pools, Balances, Test, Origin, StakingMock, AccountId, DEPOSITOR, ROOT,
+	new_test_ext, pool_events,
+};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::BadOrigin;
+
+const POOL: PoolId = 1;
+
+fn bonded() -> AccountId {
+	Pools::bonded_account(POOL)
+}
+
+fn reward() -> AccountId {
+	Pools::reward_account(POOL)
+}
+
+fn active_stake() -> u128 {
+	<StakingMock as StakingInterface>::active_stake(&reward()).unwrap_or(0)
+}
+
+#[test]
+fn create_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Pools::last_pool_id(), POOL);
+		assert_eq!(Pools::counter_for_bonded_pools(), 1);
+		assert_eq!(Pools::bonded_pools(POOL), Some(BondedPoolInner {
+			points: 10,
+			state: PoolState::Open,
+			member_counter: 1,
+			roles: PoolRoles { depositor: DEPOSITOR, root: ROOT, nominator: ROOT },
+		}));
+		assert_eq!(Pools::pool_members(DEPOSITOR).unwrap().points, 10);
+		assert_eq!(Pools::reverse_pool_id_lookup(bonded()), Some(POOL));
+
+		// the depositor paid the bond and the existential deposit of the reward account.
+		assert_eq!(Balances::free_balance(DEPOSITOR), 85);
+		assert_eq!(Balances::free_balance(reward()), 5);
+		assert_eq!(Balances::free_balance(bonded()), 10);
+		assert_eq!(active_stake(), 10);
+		assert_eq!(StakingMock::ledger(&reward()).unwrap().stash, bonded());
+
+		assert_eq!(pool_events(), vec![
+			RawEvent::Created(DEPOSITOR, POOL),
+			RawEvent::Bonded(DEPOSITOR, POOL, 10, true),
+		]);
+	});
+}
+
+#[test]
+fn create_checks_minimum_bond_and_membership() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pools::create(Origin::signed(20), 9, 20, 20),
+			Error::<Test>::MinimumBondNotMet,
+		);
+		assert_noop!(
+			Pools::create(Origin::signed(DEPOSITOR), 10, DEPOSITOR, DEPOSITOR),
+			Error::<Test>::AccountBelongsToOtherPool,
+		);
+
+		assert_ok!(Pools::create(Origin::signed(20), 20, 20, 20));
+		assert_eq!(Pools::last_pool_id(), 2);
+		assert_eq!(Pools::counter_for_bonded_pools(), 2);
+		assert_ne!(Pools::bonded_account(2), bonded());
+		assert_ne!(Pools::reward_account(2), reward());
+	});
+}
+
+#[test]
+fn join_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Pools::join(Origin::signed(20), 1, POOL), Error::<Test>::MinimumBondNotMet);
+		assert_noop!(Pools::join(Origin::signed(20), 10, 2), Error::<Test>::PoolNotFound);
+		assert_noop!(
+			Pools::join(Origin::signed(DEPOSITOR), 10, POOL),
+			Error::<Test>::AccountBelongsToOtherPool,
+		);
+
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+
+		assert_eq!(Pools::pool_members(20).unwrap().points, 20);
+		let bonded_pool = Pools::bonded_pools(POOL).unwrap();
+		assert_eq!(bonded_pool.points, 30);
+		assert_eq!(bonded_pool.member_counter, 2);
+		assert_eq!(active_stake(), 30);
+		assert_eq!(Balances::free_balance(20), 80);
+	});
+}
+
+#[test]
+fn join_issues_points_according_to_the_slashed_ratio() {
+	new_test_ext().execute_with(|| {
+		// half of the pool is slashed, every point is now worth half a unit.
+		StakingMock::slash(&reward(), 5);
+		assert_eq!(active_stake(), 5);
+
+		assert_ok!(Pools::join(Origin::signed(20), 10, POOL));
+		assert_eq!(Pools::pool_members(20).unwrap().points, 20);
+		assert_eq!(Pools::bonded_pools(POOL).unwrap().points, 30);
+
+		// a fully slashed pool cannot be joined.
+		StakingMock::slash(&reward(), 15);
+		assert_noop!(Pools::join(Origin::signed(30), 10, POOL), Error::<Test>::OverflowRisk);
+	});
+}
+
+#[test]
+fn join_requires_open_pool() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pools::set_state(Origin::signed(DEPOSITOR), POOL, PoolState::Blocked),
+			Error::<Test>::DoesNotHavePermission,
+		);
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Blocked));
+		assert_noop!(Pools::join(Origin::signed(20), 10, POOL), Error::<Test>::NotOpen);
+
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Destroying));
+		assert_noop!(Pools::join(Origin::signed(20), 10, POOL), Error::<Test>::NotOpen);
+		assert_noop!(
+			Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Open),
+			Error::<Test>::CanNotChangeState,
+		);
+	});
+}
+
+#[test]
+fn rewards_are_shared_by_points() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 30, POOL));
+
+		// the pool earns 40, split 10:30.
+		let _ = Balances::deposit_creating(&reward(), 40);
+		assert_eq!(Pools::pending_rewards(&DEPOSITOR), Some(10));
+		assert_eq!(Pools::pending_rewards(&20), Some(30));
+
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 85 + 10);
+		assert_eq!(Pools::pending_rewards(&DEPOSITOR), Some(0));
+
+		// rewards earned before joining are not shared with the new member.
+		assert_ok!(Pools::join(Origin::signed(30), 40, POOL));
+		assert_eq!(Pools::pending_rewards(&30), Some(0));
+
+		// the pool earns 80, split 10:30:40.
+		let _ = Balances::deposit_creating(&reward(), 80);
+		assert_eq!(Pools::pending_rewards(&DEPOSITOR), Some(10));
+		assert_eq!(Pools::pending_rewards(&20), Some(30 + 30));
+		assert_eq!(Pools::pending_rewards(&30), Some(40));
+
+		assert_ok!(Pools::claim_payout(Origin::signed(20)));
+		assert_ok!(Pools::claim_payout(Origin::signed(30)));
+		assert_eq!(Balances::free_balance(20), 70 + 60);
+		assert_eq!(Balances::free_balance(30), 60 + 40);
+
+		// only the existential deposit and the unclaimed rewards are left.
+		assert_eq!(Balances::free_balance(reward()), 5 + 10);
+		assert_eq!(Pools::reward_pools(POOL).unwrap().total_rewards_claimed, 10 + 60 + 40);
+		assert!(pool_events().contains(&RawEvent::PaidOut(20, POOL, 60)));
+	});
+}
+
+#[test]
+fn bond_extra_pays_out_pending_rewards() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&reward(), 10);
+
+		assert_ok!(Pools::bond_extra(Origin::signed(DEPOSITOR), 10));
+
+		assert_eq!(Balances::free_balance(DEPOSITOR), 85 + 10 - 10);
+		assert_eq!(Pools::pool_members(DEPOSITOR).unwrap().points, 20);
+		assert_eq!(Pools::bonded_pools(POOL).unwrap().points, 20);
+		assert_eq!(Pools::pending_rewards(&DEPOSITOR), Some(0));
+		assert_eq!(active_stake(), 20);
+
+		assert_noop!(Pools::bond_extra(Origin::signed(20), 10), Error::<Test>::PoolMemberNotFound);
+	});
+}
+
+#[test]
+fn unbond_moves_funds_into_era_pool() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+
+		assert_noop!(
+			Pools::unbond(Origin::signed(20), 20, 21),
+			Error::<Test>::NotEnoughPointsToUnbond,
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 10),
+			Error::<Test>::DoesNotHavePermission,
+		);
+
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 10));
+
+		let member = Pools::pool_members(20).unwrap();
+		assert_eq!(member.points, 10);
+		assert_eq!(member.unbonding_eras.get(&3), Some(&10));
+		assert_eq!(Pools::bonded_pools(POOL).unwrap().points, 20);
+		assert_eq!(
+			Pools::sub_pools(POOL).unwrap().with_era.get(&3),
+			Some(&UnbondPool { points: 10, balance: 10 }),
+		);
+		assert_eq!(active_stake(), 20);
+		assert!(pool_events().contains(&RawEvent::Unbonded(20, POOL, 10)));
+	});
+}
+
+#[test]
+fn unbond_respects_max_unbonding() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+
+		for era in 0..4 {
+			StakingMock::set_current_era(era);
+			assert_ok!(Pools::unbond(Origin::signed(20), 20, 1));
+		}
+		// unbonding again in the same era is fine.
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 1));
+
+		StakingMock::set_current_era(4);
+		assert_noop!(Pools::unbond(Origin::signed(20), 20, 1), Error::<Test>::MaxUnbondingLimit);
+	});
+}
+
+#[test]
+fn depositor_must_keep_min_create_bond() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::bond_extra(Origin::signed(DEPOSITOR), 5));
+
+		assert_ok!(Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 5));
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 1),
+			Error::<Test>::MinimumCreateBondNotMaintained,
+		);
+
+		// once the pool is being destroyed, the depositor can leave as the last member.
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Destroying));
+		assert_ok!(Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 10));
+	});
+}
+
+#[test]
+fn permissionless_unbond_depends_on_state() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+		assert_noop!(
+			Pools::unbond(Origin::signed(ROOT), 20, 10),
+			Error::<Test>::DoesNotHavePermission,
+		);
+
+		// the root can kick members out of a blocked pool.
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Blocked));
+		assert_ok!(Pools::unbond(Origin::signed(ROOT), 20, 10));
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 10),
+			Error::<Test>::DoesNotHavePermission,
+		);
+
+		// anyone can unbond members of a pool that is being destroyed.
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Destroying));
+		assert_ok!(Pools::unbond(Origin::signed(30), 20, 10));
+		assert_eq!(Pools::pool_members(20).unwrap().points, 0);
+	});
+}
+
+#[test]
+fn withdraw_unbonded_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+
+		assert_noop!(
+			Pools::withdraw_unbonded(Origin::signed(20), 20),
+			Error::<Test>::CannotWithdrawAny,
+		);
+
+		StakingMock::set_current_era(3);
+		assert_noop!(
+			Pools::withdraw_unbonded(Origin::signed(30), 20),
+			Error::<Test>::DoesNotHavePermission,
+		);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+
+		assert_eq!(Balances::free_balance(20), 100);
+		assert_eq!(Pools::pool_members(20), None);
+		assert_eq!(Pools::bonded_pools(POOL).unwrap().member_counter, 1);
+		assert!(Pools::sub_pools(POOL).unwrap().with_era.is_empty());
+		assert!(StakingMock::ledger(&reward()).unwrap().unlocking.is_empty());
+		assert!(pool_events().contains(&RawEvent::Withdrawn(20, POOL, 20)));
+		assert!(pool_events().contains(&RawEvent::MemberRemoved(POOL, 20)));
+	});
+}
+
+#[test]
+fn withdraw_unbonded_only_transfers_unlocked_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+
+		// a slash of the unlocking chunk that the pool was not told about.
+		mock::LEDGERS.with(|l| l.borrow_mut().get_mut(&reward()).unwrap().unlocking[0].value = 15);
+		let _ = Balances::slash(&bonded(), 5);
+
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+
+		// the bonded stake of the depositor is left untouched.
+		assert_eq!(Balances::free_balance(20), 80 + 15);
+		assert_eq!(Balances::free_balance(bonded()), 10);
+		assert_eq!(active_stake(), 10);
+		assert!(pool_events().contains(&RawEvent::Withdrawn(20, POOL, 15)));
+	});
+}
+
+#[test]
+fn old_unbonding_pools_are_merged() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+		assert_ok!(Pools::join(Origin::signed(30), 20, POOL));
+
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 10));
+		StakingMock::set_current_era(1);
+		assert_ok!(Pools::unbond(Origin::signed(30), 30, 10));
+
+		// era 3 is more than `PostUnbondingPoolsWindow` eras old in era 5.
+		StakingMock::set_current_era(5);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(30), 30));
+
+		let sub_pools = Pools::sub_pools(POOL).unwrap();
+		assert_eq!(sub_pools.no_era, UnbondPool { points: 10, balance: 10 });
+		assert!(sub_pools.with_era.is_empty());
+
+		// the points of era 3 are now dissolved from the merged pool.
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(Balances::free_balance(20), 90);
+		assert_eq!(Pools::sub_pools(POOL).unwrap().no_era, UnbondPool::default());
+	});
+}
+
+#[test]
+fn slashes_are_applied_to_unbonding_pools() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+
+		// 10 of the active stake and 10 of the unlocking funds are slashed.
+		StakingMock::slash(&reward(), 20);
+
+		assert_eq!(active_stake(), 0);
+		assert_eq!(
+			Pools::sub_pools(POOL).unwrap().with_era.get(&3),
+			Some(&UnbondPool { points: 20, balance: 10 }),
+		);
+		assert!(pool_events().contains(&RawEvent::PoolSlashed(POOL, 0)));
+		assert!(pool_events().contains(&RawEvent::UnbondingPoolSlashed(POOL, 3, 10)));
+
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(Balances::free_balance(20), 80 + 10);
+	});
+}
+
+#[test]
+fn pool_is_dissolved_when_depositor_leaves() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, POOL));
+		let _ = Balances::deposit_creating(&reward(), 3);
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), POOL, PoolState::Destroying));
+
+		assert_ok!(Pools::unbond(Origin::signed(30), 20, 20));
+		// the depositor cannot leave while other members are still in the pool.
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), DEPOSITOR, 10),
+			Error::<Test>::MinimumCreateBondNotMaintained,
+		);
+
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(30), 20));
+		assert_ok!(Pools::unbond(Origin::signed(30), DEPOSITOR, 10));
+
+		StakingMock::set_current_era(6);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(DEPOSITOR), DEPOSITOR));
+
+		assert_eq!(Pools::bonded_pools(POOL), None);
+		assert_eq!(Pools::reward_pools(POOL), None);
+		assert_eq!(Pools::sub_pools(POOL), None);
+		assert_eq!(Pools::reverse_pool_id_lookup(bonded()), None);
+		assert_eq!(Pools::counter_for_bonded_pools(), 0);
+		assert_eq!(StakingMock::ledger(&reward()).map(|l| l.total()), None);
+
+		// everything, including the existential deposit of the reward account, is returned.
+		assert_eq!(Balances::free_balance(reward()), 0);
+		assert_eq!(Balances::free_balance(bonded()), 0);
+		assert_eq!(Balances::free_balance(DEPOSITOR) + Balances::free_balance(20), 200 + 3);
+		assert!(pool_events().contains(&RawEvent::Destroyed(POOL)));
+	});
+}
+
+#[test]
+fn nominate_and_chill_require_roles() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pools::nominate(Origin::signed(DEPOSITOR), POOL, vec![1, 2]),
+			Error::<Test>::DoesNotHavePermission,
+		);
+		assert_noop!(Pools::nominate(Origin::signed(ROOT), 2, vec![1]), Error::<Test>::PoolNotFound);
+
+		assert_ok!(Pools::nominate(Origin::signed(ROOT), POOL, vec![1, 2]));
+		assert_eq!(StakingMock::nominations(&reward()), Some(vec![1, 2]));
+
+		assert_ok!(Pools::update_roles(Origin::signed(ROOT), POOL, ROOT, 40));
+		assert_ok!(Pools::chill(Origin::signed(40), POOL));
+		assert_eq!(StakingMock::nominations(&reward()), None);
+	});
+}
+
+#[test]
+fn update_roles_and_set_configs_check_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pools::update_roles(Origin::signed(40), POOL, 40, 40),
+			Error::<Test>::DoesNotHavePermission,
+		);
+		assert_ok!(Pools::update_roles(Origin::root(), POOL, 40, 40));
+		assert_eq!(Pools::bonded_pools(POOL).unwrap().roles.root, 40);
+
+		assert_noop!(Pools::set_configs(Origin::signed(40), 1, 1), BadOrigin);
+		assert_ok!(Pools::set_configs(Origin::root(), 1, 20));
+		assert_eq!(Pools::min_join_bond(), 1);
+		assert_eq!(Pools::min_create_bond(), 20);
+	});
+}
@@ -131,6 +131,7 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominatorsInElection: u32 = 1_000;
 }
 
 pub type Extrinsic = sp_runtime::testing::TestXt<Call, ()>;
@@ -166,6 +167,9 @@ impl pallet_staking::Trait for Test {
 	type Call = Call;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = ();
+	type OnStakerSlash = ();
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
 	type MaxIterations = ();
 }
 
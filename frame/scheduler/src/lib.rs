@@ -48,6 +48,7 @@ mod benchmarking;
 
 use sp_std::prelude::*;
 use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
 use sp_runtime::{RuntimeDebug, traits::{Zero, One}};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error,
@@ -83,7 +84,7 @@ pub type PeriodicIndex = u32;
 pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
 
 /// Information regarding an item to be executed in the future.
-#[derive(Clone, RuntimeDebug, Encode, Decode)]
+#[derive(Clone, RuntimeDebug, Encode, Decode, TypeInfo)]
 pub struct Scheduled<Call, BlockNumber> {
 	/// The unique identity for this task, if there is one.
 	maybe_id: Option<Vec<u8>>,
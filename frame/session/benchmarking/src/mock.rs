@@ -149,6 +149,7 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominatorsInElection: u32 = 1_000;
 	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
@@ -180,6 +181,9 @@ impl pallet_staking::Trait for Test {
 	type Call = Call;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
+	type OnStakerSlash = ();
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
 	type MaxIterations = ();
 }
 
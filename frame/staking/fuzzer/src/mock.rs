@@ -153,6 +153,7 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxNominatorsInElection: u32 = 1_000;
 	pub const MaxIterations: u32 = 20;
 }
 
@@ -185,4 +186,7 @@ impl pallet_staking::Trait for Test {
 	type MaxIterations = MaxIterations;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = ();
+	type OnStakerSlash = ();
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
 }
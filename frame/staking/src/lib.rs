@@ -288,6 +288,7 @@ use sp_std::{
 	mem::size_of,
 };
 use codec::{HasCompact, Encode, Decode};
+use frame_support::type_info::TypeInfo;
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error, debug,
 	weights::{Weight, DispatchClass},
@@ -300,7 +301,7 @@ use frame_support::{
 };
 use pallet_session::historical;
 use sp_runtime::{
-	Perbill, PerU16, PerThing, RuntimeDebug,
+	Perbill, PerU16, PerThing, RuntimeDebug, DispatchError,
 	curve::PiecewiseLinear,
 	traits::{
 		Convert, Zero, StaticLookup, CheckedSub, Saturating, SaturatedConversion, AtLeast32Bit,
@@ -312,7 +313,7 @@ use sp_runtime::{
 	},
 };
 use sp_staking::{
-	SessionIndex,
+	SessionIndex, OnStakerSlash, StakingInterface, SortedListProvider, VoteWeightProvider,
 	offence::{OnOffenceHandler, OffenceDetails, Offence, ReportOffence, OffenceError},
 };
 #[cfg(feature = "std")]
@@ -358,7 +359,7 @@ pub(crate) const MAX_VALIDATORS: usize = ValidatorIndex::max_value() as usize;
 pub(crate) const MAX_NOMINATORS: usize = NominatorIndex::max_value() as usize;
 
 /// Counter for the number of eras that have passed.
-pub type EraIndex = u32;
+pub use sp_staking::EraIndex;
 
 /// Counter for the number of "reward" points earned by a given validator.
 pub type RewardPoint = u32;
@@ -367,7 +368,7 @@ pub type RewardPoint = u32;
 generate_compact_solution_type!(pub GenericCompactAssignments, 16);
 
 /// Information regarding the active era (era in used in session).
-#[derive(Encode, Decode, RuntimeDebug)]
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct ActiveEraInfo {
 	/// Index of era.
 	pub index: EraIndex,
@@ -400,7 +401,7 @@ type NegativeImbalanceOf<T> =
 /// Reward points of an era. Used to split era total payout between validators.
 ///
 /// This points will be used to reward validators and their respective nominators.
-#[derive(PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[derive(PartialEq, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
 pub struct EraRewardPoints<AccountId: Ord> {
 	/// Total number of points. Equals the sum of reward points for each validator.
 	total: RewardPoint,
@@ -421,7 +422,7 @@ pub enum StakerStatus<AccountId> {
 }
 
 /// A destination account for payment.
-#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum RewardDestination {
 	/// Pay into the stash account, increasing the amount at stake accordingly.
 	Staked,
@@ -438,7 +439,7 @@ impl Default for RewardDestination {
 }
 
 /// Preference of what happens regarding validation.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct ValidatorPrefs {
 	/// Reward that validator takes up-front; only the rest is split between themselves and
 	/// nominators.
@@ -455,7 +456,7 @@ impl Default for ValidatorPrefs {
 }
 
 /// Just a Balance/BlockNumber tuple to encode when a chunk of funds will be unlocked.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct UnlockChunk<Balance: HasCompact> {
 	/// Amount of funds to be unlocked.
 	#[codec(compact)]
@@ -466,7 +467,7 @@ pub struct UnlockChunk<Balance: HasCompact> {
 }
 
 /// The ledger of a (bonded) stash.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct StakingLedger<AccountId, Balance: HasCompact> {
 	/// The stash account whose balance is actually locked and at stake.
 	pub stash: AccountId,
@@ -546,11 +547,13 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 	/// active funds. Returns the amount of funds actually slashed.
 	///
 	/// Slashes from `active` funds first, and then `unlocking`, starting with the
-	/// chunks that are closest to unlocking.
+	/// chunks that are closest to unlocking. The new balance of every unlocking chunk that
+	/// has been slashed is recorded in `slashed_unlocking`, keyed by the era of the chunk.
 	fn slash(
 		&mut self,
 		mut value: Balance,
 		minimum_balance: Balance,
+		slashed_unlocking: &mut BTreeMap<EraIndex, Balance>,
 	) -> Balance {
 		let pre_total = self.total;
 		let total = &mut self.total;
@@ -579,9 +582,14 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 
 		slash_out_of(total, active, &mut value);
 
+		let mut slashed_eras = Vec::new();
 		let i = self.unlocking.iter_mut()
 			.map(|chunk| {
+				let pre_value = chunk.value;
 				slash_out_of(total, &mut chunk.value, &mut value);
+				if chunk.value != pre_value {
+					slashed_eras.push(chunk.era);
+				}
 				chunk.value
 			})
 			.take_while(|value| value.is_zero()) // take all fully-consumed chunks out.
@@ -590,12 +598,20 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 		// kill all drained chunks.
 		let _ = self.unlocking.drain(..i);
 
+		// several chunks may unlock in the same era.
+		for era in slashed_eras {
+			let remaining = self.unlocking.iter()
+				.filter(|chunk| chunk.era == era)
+				.fold(Zero::zero(), |acc: Balance, chunk| acc.saturating_add(chunk.value));
+			slashed_unlocking.insert(era, remaining);
+		}
+
 		pre_total.saturating_sub(*total)
 	}
 }
 
 /// A record of the nominations made by a specific account.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct Nominations<AccountId> {
 	/// The targets of nomination.
 	pub targets: Vec<AccountId>,
@@ -608,7 +624,7 @@ pub struct Nominations<AccountId> {
 }
 
 /// The amount of exposure (to slashing) than an individual nominator has.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct IndividualExposure<AccountId, Balance: HasCompact> {
 	/// The stash account of the nominator in question.
 	pub who: AccountId,
@@ -618,7 +634,7 @@ pub struct IndividualExposure<AccountId, Balance: HasCompact> {
 }
 
 /// A snapshot of the stake backing a single validator in the system.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, RuntimeDebug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
 pub struct Exposure<AccountId, Balance: HasCompact> {
 	/// The total balance backing this validator.
 	#[codec(compact)]
@@ -632,7 +648,7 @@ pub struct Exposure<AccountId, Balance: HasCompact> {
 
 /// A pending slash record. The value of the slash has been computed but not applied yet,
 /// rather deferred for several eras.
-#[derive(Encode, Decode, Default, RuntimeDebug)]
+#[derive(Encode, Decode, Default, RuntimeDebug, TypeInfo)]
 pub struct UnappliedSlash<AccountId, Balance: HasCompact> {
 	/// The stash ID of the offending validator.
 	validator: AccountId,
@@ -647,7 +663,7 @@ pub struct UnappliedSlash<AccountId, Balance: HasCompact> {
 }
 
 /// Indicate how an election round was computed.
-#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum ElectionCompute {
 	/// Result was forcefully computed on chain at the end of the session.
 	OnChain,
@@ -659,7 +675,7 @@ pub enum ElectionCompute {
 }
 
 /// The result of an election round.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct ElectionResult<AccountId, Balance: HasCompact> {
 	/// Flat list of validators who have been elected.
 	elected_stashes: Vec<AccountId>,
@@ -671,7 +687,7 @@ pub struct ElectionResult<AccountId, Balance: HasCompact> {
 }
 
 /// The status of the upcoming (offchain) election.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum ElectionStatus<BlockNumber> {
 	/// Nothing has and will happen for now. submission window is not open.
 	Closed,
@@ -818,10 +834,28 @@ pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
 	/// This is exposed so that it can be tuned for particular runtime, when
 	/// multiple pallets send unsigned transactions.
 	type UnsignedPriority: Get<TransactionPriority>;
+
+	/// Something that listens to staking slash events.
+	///
+	/// Used by pallets that bond funds on behalf of others, e.g. nomination pools, to keep
+	/// their own accounting of bonded and unlocking funds in line with the staking ledger.
+	type OnStakerSlash: OnStakerSlash<Self::AccountId, BalanceOf<Self>>;
+
+	/// Something that keeps the nominators sorted by their vote weight.
+	///
+	/// The election only reads the heaviest nominators from this list. Use [`UseNominatorsMap`]
+	/// to read all of them from [`Nominators`] instead.
+	type SortedListProvider: SortedListProvider<Self::AccountId>;
+
+	/// The maximum number of nominators read from `SortedListProvider` for an election.
+	///
+	/// The snapshot can hold at most `NominatorIndex::max_value()` voters, validators included,
+	/// so fewer nominators may be read if there are many validators.
+	type MaxNominatorsInElection: Get<u32>;
 }
 
 /// Mode of era-forcing.
-#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum Forcing {
 	/// Not forcing anything - just let whatever happen.
@@ -841,16 +875,17 @@ impl Default for Forcing {
 // A value placed in storage that represents the current version of the Staking storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
 // storage migration logic. This should match directly with the semantic versions of the Rust crate.
-#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 enum Releases {
 	V1_0_0Ancient,
 	V2_0_0,
 	V3_0_0,
+	V4_0_0, // Nominators are kept in `T::SortedListProvider`.
 }
 
 impl Default for Releases {
 	fn default() -> Self {
-		Releases::V3_0_0
+		Releases::V4_0_0
 	}
 }
 
@@ -1035,8 +1070,8 @@ decl_storage! {
 		/// True if network has been upgraded to this version.
 		/// Storage version of the pallet.
 		///
-		/// This is set to v3.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V3_0_0): Releases;
+		/// This is set to v4.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V4_0_0): Releases;
 
 		/// The era where we migrated from Lazy Payouts to Simple Payouts
 		MigrateEra: Option<EraIndex>;
@@ -1175,10 +1210,21 @@ decl_module! {
 		/// Number of eras that staked funds must remain bonded for.
 		const BondingDuration: EraIndex = T::BondingDuration::get();
 
+		/// The maximum number of nominators read for an election.
+		const MaxNominatorsInElection: u32 = T::MaxNominatorsInElection::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V3_0_0 {
+				Self::migrate_nominators_to_sorted_list()
+			} else {
+				0
+			}
+		}
+
 		/// sets `ElectionStatus` to `Open(now)` where `now` is the block number at which the
 		/// election window has opened, if we are at the last session and less blocks than
 		/// `T::ElectionLookahead` is remaining until the next new session schedule. The offchain
@@ -1470,7 +1516,7 @@ decl_module! {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
 			let stash = &ledger.stash;
-			<Nominators<T>>::remove(stash);
+			Self::do_remove_nominator(stash);
 			<Validators<T>>::insert(stash, prefs);
 		}
 
@@ -1507,7 +1553,7 @@ decl_module! {
 			};
 
 			<Validators<T>>::remove(stash);
-			<Nominators<T>>::insert(stash, &nominations);
+			Self::do_add_nominator(stash, nominations);
 		}
 
 		/// Declare no desire to either validate or nominate.
@@ -1941,9 +1987,13 @@ impl<T: Trait> Module<T> {
 	/// is successful.
 	fn create_stakers_snapshot() -> bool {
 		let validators = <Validators<T>>::iter().map(|(v, _)| v).collect::<Vec<_>>();
-		let mut nominators = <Nominators<T>>::iter().map(|(n, _)| n).collect::<Vec<_>>();
-
 		let num_validators = validators.len();
+		let max_nominators = Self::max_electing_nominators(num_validators);
+		let mut nominators = Self::electing_nominators(max_nominators)
+			.into_iter()
+			.map(|(n, _)| n)
+			.collect::<Vec<_>>();
+
 		let num_nominators = nominators.len();
 		if
 			num_validators > MAX_VALIDATORS ||
@@ -2226,12 +2276,76 @@ impl<T: Trait> Module<T> {
 			WithdrawReasons::all(),
 		);
 		<Ledger<T>>::insert(controller, ledger);
+
+		if <Nominators<T>>::contains_key(&ledger.stash) {
+			let weight = Self::slashable_balance_of_vote_weight(&ledger.stash);
+			T::SortedListProvider::on_update(&ledger.stash, weight);
+		}
 	}
 
 	/// Chill a stash account.
 	fn chill_stash(stash: &T::AccountId) {
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
+	}
+
+	/// Add `stash` to the nominators, or update its nominations, keeping the
+	/// `T::SortedListProvider` in sync.
+	fn do_add_nominator(stash: &T::AccountId, nominations: Nominations<T::AccountId>) {
+		if !<Nominators<T>>::contains_key(stash) {
+			// `UseNominatorsMap` cannot fail, and the list of any other provider can only fail
+			// for duplicates, which are excluded above.
+			let _ = T::SortedListProvider::on_insert(
+				stash.clone(),
+				Self::slashable_balance_of_vote_weight(stash),
+			);
+		}
+		<Nominators<T>>::insert(stash, nominations);
+	}
+
+	/// Remove `stash` from the nominators, keeping the `T::SortedListProvider` in sync.
+	///
+	/// Returns `true` if `stash` was a nominator.
+	fn do_remove_nominator(stash: &T::AccountId) -> bool {
+		if <Nominators<T>>::contains_key(stash) {
+			T::SortedListProvider::on_remove(stash);
+			<Nominators<T>>::remove(stash);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// The nominators that take part in the election, together with their nominations.
+	///
+	/// At most `limit` nominators are read, heaviest first as far as `T::SortedListProvider`
+	/// can tell.
+	fn electing_nominators(limit: usize) -> Vec<(T::AccountId, Nominations<T::AccountId>)> {
+		T::SortedListProvider::iter()
+			.filter_map(|nominator| <Nominators<T>>::get(&nominator).map(|n| (nominator, n)))
+			.take(limit)
+			.collect()
+	}
+
+	/// The number of nominators to read for an election along with `num_validators` validators.
+	///
+	/// This is `T::MaxNominatorsInElection`, capped so that the voters fit in the snapshot.
+	fn max_electing_nominators(num_validators: usize) -> usize {
+		(T::MaxNominatorsInElection::get() as usize)
+			.min(MAX_NOMINATORS.saturating_sub(num_validators))
+	}
+
+	/// Move all existing nominators into `T::SortedListProvider` and bump the storage version.
+	fn migrate_nominators_to_sorted_list() -> Weight {
+		let migrated = T::SortedListProvider::regenerate(
+			<Nominators<T>>::iter().map(|(n, _)| n),
+			Box::new(|n: &T::AccountId| Self::slashable_balance_of_vote_weight(n)),
+		);
+		StorageVersion::put(Releases::V4_0_0);
+		log!(info, "💸 Migrated {} nominators into the sorted list.", migrated);
+
+		// every nominator is read, along with its ledger, and written into the list.
+		T::DbWeight::get().reads_writes(3 * migrated as Weight + 1, 2 * migrated as Weight + 1)
 	}
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
@@ -2738,7 +2852,8 @@ impl<T: Trait> Module<T> {
 			all_validators.push(validator);
 		}
 
-		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
+		let max_nominators = Self::max_electing_nominators(all_validators.len());
+		let nominator_votes = Self::electing_nominators(max_nominators).into_iter().map(|(nominator, nominations)| {
 			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
 
 			// Filter out nomination targets which were nominated before the most recent
@@ -2810,7 +2925,7 @@ impl<T: Trait> Module<T> {
 
 		<Payee<T>>::remove(stash);
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
 
 		slashing::clear_stash_metadata::<T>(stash);
 
@@ -2903,6 +3018,123 @@ impl<T: Trait> Module<T> {
 
 }
 
+impl<T: Trait> VoteWeightProvider<T::AccountId> for Module<T> {
+	fn vote_weight(who: &T::AccountId) -> VoteWeight {
+		Self::slashable_balance_of_vote_weight(who)
+	}
+}
+
+/// A [`SortedListProvider`] that reads all nominators from the [`Nominators`] map, unsorted.
+///
+/// This keeps the behaviour of iterating all nominators in the election, and is only suitable
+/// for runtimes with a small number of nominators, and for tests.
+pub struct UseNominatorsMap<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Trait> SortedListProvider<T::AccountId> for UseNominatorsMap<T> {
+	type Error = ();
+
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(<Nominators<T>>::iter().map(|(n, _)| n))
+	}
+
+	fn count() -> u32 {
+		<Nominators<T>>::iter().count() as u32
+	}
+
+	fn contains(id: &T::AccountId) -> bool {
+		<Nominators<T>>::contains_key(id)
+	}
+
+	fn on_insert(_: T::AccountId, _: VoteWeight) -> Result<(), Self::Error> {
+		// nothing to do on insert.
+		Ok(())
+	}
+
+	fn on_update(_: &T::AccountId, _: VoteWeight) {
+		// nothing to do on update.
+	}
+
+	fn on_remove(_: &T::AccountId) {
+		// nothing to do on remove.
+	}
+
+	fn regenerate(
+		_: impl IntoIterator<Item = T::AccountId>,
+		_: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		// nothing to do upon regenerate.
+		0
+	}
+
+	fn clear() {
+		// the nominators are owned by staking, and are not cleared here.
+	}
+
+	fn sanity_check() -> Result<(), &'static str> {
+		Ok(())
+	}
+}
+
+impl<T: Trait> StakingInterface for Module<T> {
+	type Balance = BalanceOf<T>;
+	type AccountId = T::AccountId;
+
+	fn minimum_bond() -> Self::Balance {
+		T::Currency::minimum_balance()
+	}
+
+	fn bonding_duration() -> EraIndex {
+		T::BondingDuration::get()
+	}
+
+	fn current_era() -> EraIndex {
+		Self::current_era().unwrap_or(Zero::zero())
+	}
+
+	fn active_stake(controller: &Self::AccountId) -> Option<Self::Balance> {
+		Self::ledger(controller).map(|l| l.active)
+	}
+
+	fn total_stake(controller: &Self::AccountId) -> Option<Self::Balance> {
+		Self::ledger(controller).map(|l| l.total)
+	}
+
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+	) -> DispatchResult {
+		Self::bond(
+			system::RawOrigin::Signed(stash).into(),
+			T::Lookup::unlookup(controller),
+			value,
+			RewardDestination::Controller,
+		)
+	}
+
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult {
+		Self::bond_extra(system::RawOrigin::Signed(stash).into(), extra)
+	}
+
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult {
+		Self::unbond(system::RawOrigin::Signed(controller).into(), value)
+	}
+
+	fn withdraw_unbonded(controller: Self::AccountId) -> Result<bool, DispatchError> {
+		Self::withdraw_unbonded(system::RawOrigin::Signed(controller.clone()).into())?;
+		Ok(!<Ledger<T>>::contains_key(&controller))
+	}
+
+	fn nominate(controller: Self::AccountId, targets: Vec<Self::AccountId>) -> DispatchResult {
+		let targets = targets.into_iter().map(T::Lookup::unlookup).collect::<Vec<_>>();
+		Self::nominate(system::RawOrigin::Signed(controller).into(), targets)
+	}
+
+	fn chill(controller: Self::AccountId) -> DispatchResult {
+		Self::chill(system::RawOrigin::Signed(controller).into())
+	}
+}
+
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
 /// i.e. the new session must be planned before the ending of the previous session.
 ///
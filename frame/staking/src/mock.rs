@@ -65,6 +65,8 @@ thread_local! {
 	static ELECTION_LOOKAHEAD: RefCell<BlockNumber> = RefCell::new(0);
 	static PERIOD: RefCell<BlockNumber> = RefCell::new(1);
 	static MAX_ITERATIONS: RefCell<u32> = RefCell::new(0);
+	static MAX_NOMINATORS_IN_ELECTION: RefCell<u32> = RefCell::new(10_000);
+	static USE_BAGS_LIST: RefCell<bool> = RefCell::new(false);
 }
 
 /// Another session handler struct to test on_disabled.
@@ -151,6 +153,13 @@ impl Get<u32> for MaxIterations {
 	}
 }
 
+pub struct MaxNominatorsInElection;
+impl Get<u32> for MaxNominatorsInElection {
+	fn get() -> u32 {
+		MAX_NOMINATORS_IN_ELECTION.with(|v| *v.borrow())
+	}
+}
+
 impl_outer_origin! {
 	pub enum Origin for Test  where system = frame_system {}
 }
@@ -168,6 +177,7 @@ mod staking {
 use frame_system as system;
 use pallet_balances as balances;
 use pallet_session as session;
+use pallet_bags_list as bags_list;
 
 impl_outer_event! {
 	pub enum MetaEvent for Test {
@@ -175,6 +185,7 @@ impl_outer_event! {
 		balances<T>,
 		session,
 		staking<T>,
+		bags_list<T>,
 	}
 }
 
@@ -321,6 +332,97 @@ impl Trait for Test {
 	type MaxIterations = MaxIterations;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
+	type OnStakerSlash = ();
+	type SortedListProvider = SortedListMock;
+	type MaxNominatorsInElection = MaxNominatorsInElection;
+}
+
+parameter_types! {
+	pub const BagThresholds: &'static [VoteWeight] = &[10, 100, 1_000, 10_000];
+}
+
+impl pallet_bags_list::Trait for Test {
+	type Event = MetaEvent;
+	type VoteWeightProvider = Staking;
+	type BagThresholds = BagThresholds;
+	type WeightInfo = ();
+}
+
+/// Keeps the nominators in the bags list if the externalities were built with `use_bags_list`,
+/// and reads them from the `Nominators` map otherwise.
+pub struct SortedListMock;
+
+impl SortedListMock {
+	fn use_bags_list() -> bool {
+		USE_BAGS_LIST.with(|v| *v.borrow())
+	}
+}
+
+impl SortedListProvider<AccountId> for SortedListMock {
+	type Error = ();
+
+	fn iter() -> Box<dyn Iterator<Item = AccountId>> {
+		if Self::use_bags_list() { BagsList::iter() } else { UseNominatorsMap::<Test>::iter() }
+	}
+
+	fn count() -> u32 {
+		if Self::use_bags_list() { BagsList::count() } else { UseNominatorsMap::<Test>::count() }
+	}
+
+	fn contains(id: &AccountId) -> bool {
+		if Self::use_bags_list() {
+			BagsList::contains(id)
+		} else {
+			UseNominatorsMap::<Test>::contains(id)
+		}
+	}
+
+	fn on_insert(id: AccountId, weight: VoteWeight) -> Result<(), Self::Error> {
+		if Self::use_bags_list() {
+			BagsList::on_insert(id, weight).map_err(|_| ())
+		} else {
+			UseNominatorsMap::<Test>::on_insert(id, weight)
+		}
+	}
+
+	fn on_update(id: &AccountId, weight: VoteWeight) {
+		if Self::use_bags_list() {
+			BagsList::on_update(id, weight)
+		} else {
+			UseNominatorsMap::<Test>::on_update(id, weight)
+		}
+	}
+
+	fn on_remove(id: &AccountId) {
+		if Self::use_bags_list() {
+			BagsList::on_remove(id)
+		} else {
+			UseNominatorsMap::<Test>::on_remove(id)
+		}
+	}
+
+	fn regenerate(
+		all: impl IntoIterator<Item = AccountId>,
+		weight_of: Box<dyn Fn(&AccountId) -> VoteWeight>,
+	) -> u32 {
+		if Self::use_bags_list() {
+			BagsList::regenerate(all, weight_of)
+		} else {
+			UseNominatorsMap::<Test>::regenerate(all, weight_of)
+		}
+	}
+
+	fn clear() {
+		if Self::use_bags_list() { BagsList::clear() } else { UseNominatorsMap::<Test>::clear() }
+	}
+
+	fn sanity_check() -> Result<(), &'static str> {
+		if Self::use_bags_list() {
+			BagsList::sanity_check()
+		} else {
+			UseNominatorsMap::<Test>::sanity_check()
+		}
+	}
 }
 
 impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
@@ -347,6 +449,8 @@ pub struct ExtBuilder {
 	invulnerables: Vec<AccountId>,
 	has_stakers: bool,
 	max_offchain_iterations: u32,
+	max_nominators_in_election: u32,
+	use_bags_list: bool,
 }
 
 impl Default for ExtBuilder {
@@ -366,6 +470,8 @@ impl Default for ExtBuilder {
 			invulnerables: vec![],
 			has_stakers: true,
 			max_offchain_iterations: 0,
+			max_nominators_in_election: 10_000,
+			use_bags_list: false,
 		}
 	}
 }
@@ -427,6 +533,14 @@ impl ExtBuilder {
 		self.max_offchain_iterations = iterations;
 		self
 	}
+	pub fn max_nominators_in_election(mut self, max: u32) -> Self {
+		self.max_nominators_in_election = max;
+		self
+	}
+	pub fn use_bags_list(mut self, use_bags_list: bool) -> Self {
+		self.use_bags_list = use_bags_list;
+		self
+	}
 	pub fn offchain_phragmen_ext(self) -> Self {
 		self.session_per_era(4)
 			.session_length(5)
@@ -439,6 +553,8 @@ impl ExtBuilder {
 		ELECTION_LOOKAHEAD.with(|v| *v.borrow_mut() = self.election_lookahead);
 		PERIOD.with(|v| *v.borrow_mut() = self.session_length);
 		MAX_ITERATIONS.with(|v| *v.borrow_mut() = self.max_offchain_iterations);
+		MAX_NOMINATORS_IN_ELECTION.with(|v| *v.borrow_mut() = self.max_nominators_in_election);
+		USE_BAGS_LIST.with(|v| *v.borrow_mut() = self.use_bags_list);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		let _ = env_logger::try_init();
@@ -544,6 +660,7 @@ pub type Balances = pallet_balances::Module<Test>;
 pub type Session = pallet_session::Module<Test>;
 pub type Timestamp = pallet_timestamp::Module<Test>;
 pub type Staking = Module<Test>;
+pub type BagsList = pallet_bags_list::Module<Test>;
 
 pub(crate) fn current_era() -> EraIndex {
 	Staking::current_era().unwrap()
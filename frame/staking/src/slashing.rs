@@ -52,13 +52,15 @@ use super::{
 	EraIndex, Trait, Module, Store, BalanceOf, Exposure, Perbill, SessionInterface,
 	NegativeImbalanceOf, UnappliedSlash,
 };
+use sp_staking::OnStakerSlash;
 use sp_runtime::{traits::{Zero, Saturating}, RuntimeDebug};
 use frame_support::{
 	StorageMap, StorageDoubleMap,
 	traits::{Currency, OnUnbalanced, Imbalance},
 };
-use sp_std::vec::Vec;
+use sp_std::{vec::Vec, collections::btree_map::BTreeMap};
 use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
 
 /// The proportion of the slashing reward to be paid out on the first slashing detection.
 /// This is f_1 in the paper.
@@ -83,7 +85,7 @@ impl SlashingSpan {
 }
 
 /// An encoding of all of a nominator's slashing spans.
-#[derive(Encode, Decode, RuntimeDebug)]
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct SlashingSpans {
 	// the index of the current slashing span of the nominator. different for
 	// every stash, resets when the account hits free balance 0.
@@ -173,7 +175,7 @@ impl SlashingSpans {
 }
 
 /// A slashing-span record for a particular stash.
-#[derive(Encode, Decode, Default)]
+#[derive(Encode, Decode, Default, TypeInfo)]
 pub(crate) struct SpanRecord<Balance> {
 	slashed: Balance,
 	paid_out: Balance,
@@ -582,7 +584,8 @@ pub fn do_slash<T: Trait>(
 		None => return, // nothing to do.
 	};
 
-	let value = ledger.slash(value, T::Currency::minimum_balance());
+	let mut slashed_unlocking = BTreeMap::new();
+	let value = ledger.slash(value, T::Currency::minimum_balance(), &mut slashed_unlocking);
 
 	if !value.is_zero() {
 		let (imbalance, missing) = T::Currency::slash(stash, value);
@@ -594,6 +597,7 @@ pub fn do_slash<T: Trait>(
 		}
 
 		<Module<T>>::update_ledger(&controller, &ledger);
+		T::OnStakerSlash::on_slash(stash, ledger.active, &slashed_unlocking);
 
 		// trigger the event
 		<Module<T>>::deposit_event(
@@ -333,6 +333,7 @@ pub fn clean<T: Trait>(era: EraIndex)
 		<ErasStakers<T>>::remove(k, era);
 	});
 	<Nominators<T>>::iter().for_each(|(k, _)| <Nominators<T>>::remove(k));
+	T::SortedListProvider::clear();
 	<Ledger<T>>::remove_all();
 	<Bonded<T>>::remove_all();
 	<QueuedElected<T>>::kill();
@@ -23,8 +23,8 @@ use sp_runtime::{
 };
 use sp_staking::offence::OffenceDetails;
 use frame_support::{
-	assert_ok, assert_noop, StorageMap,
-	traits::{Currency, ReservableCurrency, OnInitialize},
+	assert_ok, assert_noop, StorageMap, StorageValue,
+	traits::{Currency, ReservableCurrency, OnInitialize, OnRuntimeUpgrade},
 };
 use pallet_balances::Error as BalancesError;
 use substrate_test_utils::assert_eq_uvec;
@@ -4598,3 +4598,57 @@ fn migrate_era_should_handle_errors_2() {
 		assert_eq_error_rate!(Balances::total_balance(&101), init_balance_101, 2);
 	});
 }
+
+#[test]
+fn sorted_list_migration_moves_nominators_into_the_bags_list() {
+	ExtBuilder::default().use_bags_list(true).build_and_execute(|| {
+		assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+		bond_nominator(1, 2, 50, vec![11]);
+		assert_eq!(BagsList::count(), 2);
+
+		// Before the migration, the nominators are only in the `Nominators` map.
+		BagsList::clear();
+		StorageVersion::put(Releases::V3_0_0);
+		<Staking as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+		assert_eq!(BagsList::count(), 2);
+		// 101 has 500 bonded, 1 has 50, so 101 is in a heavier bag.
+		assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![101, 1]);
+		assert_ok!(BagsList::sanity_check());
+	});
+}
+
+#[test]
+fn elections_read_at_most_max_nominators_in_election() {
+	ExtBuilder::default()
+		.use_bags_list(true)
+		.max_nominators_in_election(1)
+		.build_and_execute(|| {
+			bond_nominator(1, 2, 50, vec![11]);
+			assert_eq!(<Test as Trait>::SortedListProvider::count(), 2);
+
+			// Only the heaviest nominator makes it into the snapshot, along with the validators.
+			assert!(Staking::create_stakers_snapshot());
+			let nominators = Staking::snapshot_nominators().unwrap();
+			assert!(nominators.contains(&101));
+			assert!(!nominators.contains(&1));
+			assert_eq!(nominators.len(), 1 + Staking::snapshot_validators().unwrap().len());
+			Staking::kill_stakers_snapshot();
+		});
+}
+
+#[test]
+fn nominators_are_read_through_sorted_list_provider() {
+	ExtBuilder::default().nominate(true).build_and_execute(|| {
+		assert_eq!(
+			<Test as Trait>::SortedListProvider::iter().collect::<Vec<_>>(),
+			vec![101],
+		);
+		assert_eq!(<Test as Trait>::SortedListProvider::count(), 1);
+
+		assert_ok!(Staking::chill(Origin::signed(100)));
+		assert_eq!(<Test as Trait>::SortedListProvider::count(), 0);
+		assert!(Staking::electing_nominators(10).is_empty());
+	});
+}
@@ -20,8 +20,9 @@ use frame_support_procedural_tools::syn_ext as ext;
 use frame_support_procedural_tools::{generate_crate_access, generate_hidden_includes};
 use parse::{ModuleDeclaration, RuntimeDefinition, WhereSection};
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
+use std::collections::HashMap;
 use syn::{Ident, Result, TypePath};
 
 /// The fixed name of the system module.
@@ -66,6 +67,10 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 	let scrate = generate_crate_access(&hidden_crate_name, "frame-support");
 	let scrate_decl = generate_hidden_includes(&hidden_crate_name, "frame-support");
 
+	let module_indices = resolve_module_indices(modules.iter())?;
+	check_variant_indices("Call", modules.iter())?;
+	check_variant_indices("Event", modules.iter())?;
+
 	let all_but_system_modules = modules.iter().filter(|module| module.name != SYSTEM_MODULE_NAME);
 
 	let outer_event = decl_outer_event(
@@ -81,10 +86,15 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 		&scrate,
 	)?;
 	let all_modules = decl_all_modules(&name, modules.iter());
-	let module_to_index = decl_module_to_index(modules.iter(), modules.len(), &scrate);
+	let module_to_index = decl_module_to_index(modules.iter().zip(&module_indices), &scrate);
 
 	let dispatch = decl_outer_dispatch(&name, modules.iter(), &scrate);
-	let metadata = decl_runtime_metadata(&name, modules.iter(), &scrate, &unchecked_extrinsic);
+	let metadata = decl_runtime_metadata(
+		&name,
+		modules.iter().zip(&module_indices),
+		&scrate,
+		&unchecked_extrinsic,
+	);
 	let outer_config = decl_outer_config(&name, modules.iter(), &scrate);
 	let inherent = decl_outer_inherent(&block, &unchecked_extrinsic, modules.iter(), &scrate);
 	let validate_unsigned = decl_validate_unsigned(&name, modules.iter(), &scrate);
@@ -208,12 +218,12 @@ fn decl_outer_config<'a>(
 
 fn decl_runtime_metadata<'a>(
 	runtime: &'a Ident,
-	module_declarations: impl Iterator<Item = &'a ModuleDeclaration>,
+	module_declarations: impl Iterator<Item = (&'a ModuleDeclaration, &'a u8)>,
 	scrate: &'a TokenStream2,
 	extrinsic: &TypePath,
 ) -> TokenStream2 {
 	let modules_tokens = module_declarations
-		.filter_map(|module_declaration| {
+		.filter_map(|(module_declaration, index)| {
 			module_declaration.find_part("Module").map(|_| {
 				let filtered_names: Vec<_> = module_declaration
 					.module_parts()
@@ -221,10 +231,10 @@ fn decl_runtime_metadata<'a>(
 					.filter(|part| part.name() != "Module")
 					.map(|part| part.ident())
 					.collect();
-				(module_declaration, filtered_names)
+				(module_declaration, index, filtered_names)
 			})
 		})
-		.map(|(module_declaration, filtered_names)| {
+		.map(|(module_declaration, index, filtered_names)| {
 			let module = &module_declaration.module;
 			let name = &module_declaration.name;
 			let instance = module_declaration
@@ -232,7 +242,7 @@ fn decl_runtime_metadata<'a>(
 				.as_ref()
 				.map(|name| quote!(<#name>))
 				.into_iter();
-			quote!(#module::Module #(#instance)* as #name with #(#filtered_names)* ,)
+			quote!(#module::Module #(#instance)* as #name = #index with #(#filtered_names)* ,)
 		});
 	quote!(
 		#scrate::impl_runtime_metadata!{
@@ -252,7 +262,8 @@ fn decl_outer_dispatch<'a>(
 		.map(|module_declaration| {
 			let module = &module_declaration.module;
 			let name = &module_declaration.name;
-			quote!(#module::#name)
+			let index = module_declaration.index.map(codec_index);
+			quote!(#index #module::#name)
 		});
 	quote!(
 		#scrate::impl_outer_dispatch! {
@@ -320,7 +331,8 @@ fn decl_outer_event<'a>(
 					);
 					return Err(syn::Error::new(module_declaration.name.span(), msg));
 				}
-				let tokens = quote!(#module #instance #generics ,);
+				let index = module_declaration.index.map(codec_index);
+				let tokens = quote!(#index #module #instance #generics ,);
 				modules_tokens.extend(tokens);
 			}
 			None => {}
@@ -371,12 +383,23 @@ fn decl_all_modules<'a>(
 }
 
 fn decl_module_to_index<'a>(
-	module_declarations: impl Iterator<Item = &'a ModuleDeclaration>,
-	num_modules: usize,
+	module_declarations: impl Iterator<Item = (&'a ModuleDeclaration, &'a u8)>,
 	scrate: &TokenStream2,
 ) -> TokenStream2 {
-	let names = module_declarations.map(|d| &d.name);
-	let indices = 0..num_modules;
+	let (module_declarations, indices): (Vec<_>, Vec<_>) = module_declarations.unzip();
+	let names: Vec<_> = module_declarations.iter().map(|d| &d.name).collect();
+	let name_strings = names.iter().map(|name| name.to_string());
+	let storage_prefixes = module_declarations.iter().map(|d| if d.exists_part("Storage") {
+		let name = &d.name;
+		quote!(Some(#name::module_storage_prefix().as_bytes().to_vec()))
+	} else {
+		quote!(None)
+	});
+	let usize_indices = indices.iter().map(|index| **index as usize);
+	let versioned_modules: Vec<_> = module_declarations.iter()
+		.filter(|d| d.exists_part("Storage"))
+		.map(|d| &d.name)
+		.collect();
 
 	quote!(
 		/// Provides an implementation of `ModuleToIndex` to map a module
@@ -388,16 +411,112 @@ fn decl_module_to_index<'a>(
 				let type_id = #scrate::sp_std::any::TypeId::of::<M>();
 				#(
 					if type_id == #scrate::sp_std::any::TypeId::of::<#names>() {
-						return Some(#indices)
+						return Some(#usize_indices)
 					}
 				)*
 
 				None
 			}
+
+			fn modules_layout() -> #scrate::sp_std::vec::Vec<#scrate::traits::ModuleLayout> {
+				#scrate::sp_std::vec![
+					#(
+						#scrate::traits::ModuleLayout {
+							name: #name_strings.as_bytes().to_vec(),
+							index: #indices,
+							storage_prefix: #storage_prefixes,
+						},
+					)*
+				]
+			}
+
+			fn pallet_versions() -> #scrate::sp_std::vec::Vec<
+				(&'static str, #scrate::traits::PalletVersion)
+			> {
+				#scrate::sp_std::vec![
+					#(
+						(
+							#versioned_modules::module_storage_prefix(),
+							<#versioned_modules as #scrate::traits::GetPalletVersion>
+								::current_version(),
+						),
+					)*
+				]
+			}
 		}
 	)
 }
 
+/// Returns the index of every module, in the order of their declaration.
+///
+/// A module without an explicit index gets the index following the one of the module declared
+/// before it, starting at `0`. Two modules must not share an index.
+fn resolve_module_indices<'a>(
+	module_declarations: impl Iterator<Item = &'a ModuleDeclaration>,
+) -> Result<Vec<u8>> {
+	let mut used = HashMap::new();
+	let mut next_index = Some(0u8);
+	let mut indices = Vec::new();
+	for module_declaration in module_declarations {
+		let name = &module_declaration.name;
+		let index = match module_declaration.index.or(next_index) {
+			Some(index) => index,
+			None => {
+				let msg = format!(
+					"The index of module `{}` would not fit into a `u8`. \
+					 Please give the module an explicit index",
+					name,
+				);
+				return Err(syn::Error::new(name.span(), msg));
+			}
+		};
+		if let Some(other) = used.insert(index, name) {
+			let msg = format!(
+				"Module index {} of `{}` is already used by `{}`",
+				index, name, other,
+			);
+			return Err(syn::Error::new(name.span(), msg));
+		}
+		next_index = index.checked_add(1);
+		indices.push(index);
+	}
+
+	Ok(indices)
+}
+
+/// Checks that no two variants of the outer enum generated for the module part `part` are
+/// encoded with the same index.
+///
+/// The variant of a module with an explicit index is encoded with that index, the variant of any
+/// other module with its position in the enum.
+fn check_variant_indices<'a>(
+	part: &str,
+	module_declarations: impl Iterator<Item = &'a ModuleDeclaration>,
+) -> Result<()> {
+	let mut used = HashMap::new();
+	let module_declarations = module_declarations.filter(|d| d.exists_part(part));
+	for (position, module_declaration) in module_declarations.enumerate() {
+		let name = &module_declaration.name;
+		let index = module_declaration.index.map(usize::from).unwrap_or(position);
+		if let Some(other) = used.insert(index, name) {
+			let msg = format!(
+				"The `{}` of `{}` would be encoded with index {}, which is already used by `{}`. \
+				 Please give both modules an explicit index",
+				part, name, index, other,
+			);
+			return Err(syn::Error::new(name.span(), msg));
+		}
+	}
+
+	Ok(())
+}
+
+/// The attribute encoding an outer enum variant with the given module index.
+fn codec_index(index: u8) -> TokenStream2 {
+	let index = syn::LitStr::new(&index.to_string(), Span::call_site());
+	quote!(#[codec(index = #index)])
+}
+
 fn find_system_module<'a>(
 	mut module_declarations: impl Iterator<Item = &'a ModuleDeclaration>,
 ) -> Option<&'a Ident> {
@@ -154,6 +154,8 @@ pub struct ModuleDeclaration {
 	pub module: Ident,
 	pub instance: Option<Ident>,
 	pub module_parts: Vec<ModulePart>,
+	/// The index given explicitly with `= <index>`.
+	pub index: Option<u8>,
 }
 
 impl Parse for ModuleDeclaration {
@@ -174,11 +176,20 @@ impl Parse for ModuleDeclaration {
 		let _: Token![::] = input.parse()?;
 		let module_parts = parse_module_parts(input)?;
 
+		let index = if input.peek(Token![=]) {
+			let _: Token![=] = input.parse()?;
+			let index: syn::LitInt = input.parse()?;
+			Some(index.base10_parse()?)
+		} else {
+			None
+		};
+
 		let parsed = Self {
 			name,
 			module,
 			instance,
 			module_parts,
+			index,
 		};
 
 		Ok(parsed)
@@ -22,6 +22,11 @@
 
 mod storage;
 mod construct_runtime;
+mod transactional;
+mod max_encoded_len;
+mod pallet_version;
+mod pallet;
+mod no_bound;
 
 use proc_macro::TokenStream;
 
@@ -123,6 +128,26 @@ use proc_macro::TokenStream;
 ///   Twox128(module_prefix) ++ Twox128(storage_prefix) ++ Hasher1(encode(key1)) ++ Hasher2(encode(key2))
 ///   ```
 ///
+/// * N map: `Foo: nmap hasher($hash1) u32, hasher($hash2) u32, hasher($hash3) u32 => u32`:
+///   Implements the [`StorageNMap`](../frame_support/storage/trait.StorageNMap.html) trait using
+///   the [`StorageNMap generator`](../frame_support/storage/generator/trait.StorageNMap.html).
+///   And [`StoragePrefixedMap`](../frame_support/storage/trait.StoragePrefixedMap.html).
+///
+///   Each key has its own hasher, chosen as for double maps. Keys are passed as a tuple, e.g.
+///   `Foo::get((k1, k2, k3))`, and values sharing their first keys can be iterated over or
+///   removed with a partial key, e.g. `Foo::iter_prefix((k1,))`. Up to 10 keys are supported.
+///
+///   The generator is implemented with:
+///   * `module_prefix`: $module_prefix
+///   * `storage_prefix`: storage_name
+///   * `Key`: `(Key<$hash1, u32>, Key<$hash2, u32>, Key<$hash3, u32>)`
+///
+///   Thus keys are stored at:
+///   ```nocompile
+///   Twox128(module_prefix) ++ Twox128(storage_prefix)
+///       ++ Hasher1(encode(key1)) ++ Hasher2(encode(key2)) ++ Hasher3(encode(key3))
+///   ```
+///
 /// Supported hashers (ordered from least to best security):
 ///
 /// * `identity` - Just the unrefined key material. Use only when it is known to be a secure hash
@@ -140,7 +165,7 @@ use proc_macro::TokenStream;
 ///
 /// Basic storage can be extended as such:
 ///
-/// `#vis #name get(fn #getter) config(#field_name) build(#closure): #type = #default;`
+/// `#vis #name get(fn #getter) config(#field_name) build(#closure) bounded: #type = #default;`
 ///
 /// * `#vis`: Set the visibility of the structure. `pub` or nothing.
 /// * `#name`: Name of the storage item, used as a prefix in storage.
@@ -148,6 +173,11 @@ use proc_macro::TokenStream;
 /// * \[optional\] `config(#field_name)`: `field_name` is optional if get is set.
 /// Will include the item in `GenesisConfig`.
 /// * \[optional\] `build(#closure)`: Closure called with storage overlays.
+/// * \[optional\] `bounded`: Records the maximum size of an entry of the storage item in its
+/// metadata. The keys and the value must implement
+/// [`MaxEncodedLen`](../frame_support/traits/trait.MaxEncodedLen.html), see
+/// [`max_size`](../frame_support/storage/max_size/index.html). The `max_size` of other items is
+/// `None`.
 /// * `#type`: Storage type.
 /// * \[optional\] `#default`: Value returned when none.
 ///
@@ -253,7 +283,7 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 ///         UncheckedExtrinsic = UncheckedExtrinsic
 ///     {
 ///         System: system::{Module, Call, Event<T>, Config<T>},
-///         Test: test::{Module, Call},
+///         Test: test::{Module, Call} = 5,
 ///         Test2: test_with_long_module::{Module},
 ///
 ///         // Module with instances
@@ -281,6 +311,22 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 ///                             inherent.
 /// - `ValidateUnsigned`      - If the module validates unsigned extrinsics.
 ///
+/// # Module index
+///
+/// Every module has an index, returned by the generated `ModuleToIndex` and used in
+/// `DispatchError::Module`. It can be given explicitly with `= <index>` after the module parts, as
+/// for `Test` above. Otherwise it is the index following the one of the module declared before,
+/// so `Test2` gets `6`. Two modules can't have the same index.
+///
+/// The `Call` and `Event` of a module with an explicit index are encoded with that index. Those of
+/// the other modules are encoded with their position among the modules having a `Call`, resp. an
+/// `Event`. Pinning the index keeps the encoding stable when modules are reordered.
+///
+/// `frame_system` records the index and storage prefix of every module and, on the first block
+/// after a runtime upgrade, emits `ModulesLayoutChanged` for modules that moved, unless a
+/// migration called `frame_system::Module::note_modules_layout_migrated` for them. Chains started
+/// before the layout was recorded seed it with `frame_system::SeedModulesLayout`.
+///
 /// # Note
 ///
 /// The population of the genesis storage depends on the order of modules. So, if one of your
@@ -290,3 +336,203 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 pub fn construct_runtime(input: TokenStream) -> TokenStream {
 	construct_runtime::construct_runtime(input)
 }
+
+/// Execute the annotated function in a new storage transaction.
+///
+/// The return type of the annotated function must be `Result`. All changes to storage performed
+/// by the annotated function are discarded if it returns `Err`, or committed if `Ok`.
+///
+/// #Example
+///
+/// ```nocompile
+/// #[transactional]
+/// fn value_commits(v: u32) -> result::Result<u32, &'static str> {
+/// 	Value::set(v);
+/// 	Ok(v)
+/// }
+///
+/// #[transactional]
+/// fn value_rollbacks(v: u32) -> result::Result<u32, &'static str> {
+/// 	Value::set(v);
+/// 	Err("nah")
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn transactional(attr: TokenStream, input: TokenStream) -> TokenStream {
+	transactional::transactional(attr, input).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+/// Returns the version of the crate invoking the macro as a `PalletVersion`.
+///
+/// The version is taken from the `Cargo.toml` of the crate, the major version must fit into a
+/// `u16` and the minor and patch versions into a `u8`. `decl_storage!` uses it to implement
+/// `GetPalletVersion::current_version`.
+///
+/// A pallet must bump its crate version with every migration of its storage, as this version is
+/// what `VersionedMigration` compares the storage version with.
+///
+/// # Example
+///
+/// ```nocompile
+/// let version = frame_support::crate_to_pallet_version!();
+/// ```
+#[proc_macro]
+pub fn crate_to_pallet_version(input: TokenStream) -> TokenStream {
+	pallet_version::crate_to_pallet_version(input)
+		.unwrap_or_else(|e| e.to_compile_error())
+		.into()
+}
+
+/// Derive `MaxEncodedLen` for a type whose fields all implement it.
+///
+/// The maximum encoded length of a struct is the sum of the maximum encoded length of its fields,
+/// and the one of an enum is one byte for the variant index plus the largest of its variants.
+/// Fields with `#[codec(compact)]` use the maximum encoded length of their `Compact` encoding, and
+/// fields with `#[codec(skip)]` are ignored. All type parameters are required to implement
+/// `MaxEncodedLen`.
+///
+/// #Example
+///
+/// ```nocompile
+/// #[derive(Encode, Decode, MaxEncodedLen)]
+/// struct Ledger<Balance> {
+/// 	total: Balance,
+/// 	#[codec(compact)]
+/// 	unlocking: u32,
+/// }
+/// ```
+#[proc_macro_derive(MaxEncodedLen, attributes(codec))]
+pub fn derive_max_encoded_len(input: TokenStream) -> TokenStream {
+	max_encoded_len::derive_max_encoded_len(input)
+}
+
+/// Declare a pallet as a module annotated with `#[frame_support::pallet]`.
+///
+/// The pallet items are marked with `#[pallet::*]` attributes, and the module is expanded into the
+/// equivalent `decl_module!`, `decl_storage!` and `decl_error!` declarations: the calls, the
+/// storages, the errors, the events and the constants have the same metadata, and the storages the
+/// same keys, as if the pallet was written with the decl_* macros. The module must contain:
+///
+/// * `#[pallet::config]`: the trait of the pallet, named as wanted. Associated types declared with
+///   `#[pallet::constant] type Name: Get<Type>;` are exposed as constants in the metadata.
+/// * `#[pallet::pallet]`: the `pub struct Pallet<T>(PhantomData<T>);` declaration, with
+///   `#[pallet::storage_prefix = "Example"]` giving the prefix of the storages and the optional
+///   `#[pallet::generate_store(pub(super) trait Store)]` generating the `Store` trait.
+///
+/// And optionally:
+///
+/// * `#[pallet::hooks]`: an implementation of `Hooks<BlockNumberFor<T>>` for the pallet.
+/// * `#[pallet::call]`: an inherent implementation of the pallet whose functions are the calls.
+///   Each function requires a `#[pallet::weight(..)]` attribute, takes the origin as first
+///   argument and returns a `DispatchResult` or `DispatchResultWithPostInfo`. Arguments marked
+///   with `#[pallet::compact]` are compact encoded.
+/// * `#[pallet::event]`: the `Event` enum, generic over `T` or not.
+///   `#[pallet::metadata(T::AccountId = "AccountId")]` gives the name of the argument types in the
+///   metadata and `#[pallet::generate_deposit(pub(super) fn deposit_event)]` generates a function
+///   depositing the event.
+/// * `#[pallet::error]`: the `pub enum Error<T>` of unit variants.
+/// * `#[pallet::storage]`: type aliases to `StorageValue`, `StorageMap`, `StorageDoubleMap` or
+///   `StorageNMap`, with `_` as prefix. `#[pallet::getter(fn name)]` generates a getter on the
+///   pallet.
+/// * `#[pallet::genesis_config]` and `#[pallet::genesis_build]`: the `GenesisConfig` struct and its
+///   `GenesisBuild<T>` implementation, declared together.
+///
+/// The pallet generic must be named `T`, and instantiable pallets are not supported.
+///
+/// #Example
+///
+/// ```nocompile
+/// #[frame_support::pallet]
+/// pub mod pallet {
+/// 	use frame_support::pallet_prelude::*;
+/// 	use frame_system::pallet_prelude::*;
+///
+/// 	#[pallet::config]
+/// 	pub trait Trait: frame_system::Trait {
+/// 		#[pallet::constant]
+/// 		type MaxValue: Get<u32>;
+/// 		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+/// 	}
+///
+/// 	#[pallet::pallet]
+/// 	#[pallet::storage_prefix = "Example"]
+/// 	#[pallet::generate_store(pub(super) trait Store)]
+/// 	pub struct Pallet<T>(PhantomData<T>);
+///
+/// 	#[pallet::hooks]
+/// 	impl<T: Trait> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+///
+/// 	#[pallet::call]
+/// 	impl<T: Trait> Pallet<T> {
+/// 		#[pallet::weight(10_000)]
+/// 		fn set_value(origin: OriginFor<T>, #[pallet::compact] value: u32) -> DispatchResult {
+/// 			let who = ensure_signed(origin)?;
+/// 			ensure!(value <= T::MaxValue::get(), Error::<T>::ValueTooLarge);
+/// 			<Value<T>>::insert(&who, value);
+/// 			Self::deposit_event(Event::ValueSet(who, value));
+/// 			Ok(())
+/// 		}
+/// 	}
+///
+/// 	#[pallet::event]
+/// 	#[pallet::metadata(T::AccountId = "AccountId")]
+/// 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+/// 	pub enum Event<T: Trait> {
+/// 		/// A value was set. [who, value]
+/// 		ValueSet(T::AccountId, u32),
+/// 	}
+///
+/// 	#[pallet::error]
+/// 	pub enum Error<T> {
+/// 		/// The value is above `MaxValue`.
+/// 		ValueTooLarge,
+/// 	}
+///
+/// 	#[pallet::storage]
+/// 	#[pallet::getter(fn value)]
+/// 	pub(super) type Value<T: Trait> =
+/// 		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+///
+/// 	#[pallet::genesis_config]
+/// 	#[derive(Default)]
+/// 	pub struct GenesisConfig {
+/// 		pub values: Vec<(u64, u32)>,
+/// 	}
+///
+/// 	#[pallet::genesis_build]
+/// 	impl<T: Trait> GenesisBuild<T> for GenesisConfig {
+/// 		fn build(&self) {
+/// 			// ...
+/// 		}
+/// 	}
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn pallet(attr: TokenStream, item: TokenStream) -> TokenStream {
+	pallet::pallet(attr, item).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+/// Derive `Clone` without bounding the type parameters: only the fields need to implement `Clone`.
+#[proc_macro_derive(CloneNoBound)]
+pub fn derive_clone_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_clone_no_bound(input)
+}
+
+/// Derive `PartialEq` without bounding the type parameters: only the fields need to implement
+/// `PartialEq`.
+#[proc_macro_derive(PartialEqNoBound)]
+pub fn derive_partial_eq_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_partial_eq_no_bound(input)
+}
+
+/// Derive `Eq` without bounding the type parameters: only the fields need to implement `Eq`.
+#[proc_macro_derive(EqNoBound)]
+pub fn derive_eq_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_eq_no_bound(input)
+}
+
+/// Derive `Debug` without bounding the type parameters: only the fields need to implement `Debug`.
+#[proc_macro_derive(DebugNoBound)]
+pub fn derive_debug_no_bound(input: TokenStream) -> TokenStream {
+	no_bound::derive_debug_no_bound(input)
+}
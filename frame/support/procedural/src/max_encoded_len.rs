@@ -0,0 +1,121 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `MaxEncodedLen` derive.
+
+use frame_support_procedural_tools::generate_crate_access_2018;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+	Data, DeriveInput, Error, Fields, GenericParam, Meta, NestedMeta, Result, parse_quote,
+	spanned::Spanned,
+};
+
+pub fn derive_max_encoded_len(input: TokenStream) -> TokenStream {
+	derive(input).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn derive(input: TokenStream) -> Result<TokenStream> {
+	let input: DeriveInput = syn::parse(input)?;
+	let crate_ = generate_crate_access_2018()?;
+
+	let mut generics = input.generics;
+	for param in generics.params.iter_mut() {
+		if let GenericParam::Type(param) = param {
+			param.bounds.push(parse_quote!( #crate_::traits::MaxEncodedLen ));
+		}
+	}
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	let max_encoded_len = match input.data {
+		Data::Struct(data) => fields_max_encoded_len(&crate_, &data.fields)?,
+		Data::Enum(data) => {
+			let variants = data.variants.iter()
+				.map(|variant| fields_max_encoded_len(&crate_, &variant.fields))
+				.collect::<Result<Vec<_>>>()?;
+
+			// The variant index is encoded on one byte, followed by the largest variant.
+			quote! {
+				1usize.saturating_add(0usize #( .max(#variants) )*)
+			}
+		},
+		Data::Union(data) => {
+			return Err(Error::new(data.union_token.span(), "Unions are not supported"))
+		},
+	};
+
+	let name = &input.ident;
+	Ok(quote! {
+		impl #impl_generics #crate_::traits::MaxEncodedLen for #name #ty_generics #where_clause {
+			fn max_encoded_len() -> usize {
+				#max_encoded_len
+			}
+		}
+	}.into())
+}
+
+/// The sum of the maximum encoded length of all the non-skipped fields.
+fn fields_max_encoded_len(crate_: &TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+	let mut field_lens = Vec::new();
+	for field in fields.iter() {
+		let ty = &field.ty;
+		match codec_attribute(field)? {
+			CodecAttribute::None => field_lens.push(quote_spanned! { ty.span() =>
+				<#ty as #crate_::traits::MaxEncodedLen>::max_encoded_len()
+			}),
+			CodecAttribute::Compact => field_lens.push(quote_spanned! { ty.span() =>
+				<#crate_::codec::Compact<#ty> as #crate_::traits::MaxEncodedLen>::max_encoded_len()
+			}),
+			CodecAttribute::Skip => (),
+		}
+	}
+
+	Ok(quote! {
+		0usize #( .saturating_add(#field_lens) )*
+	})
+}
+
+enum CodecAttribute {
+	None,
+	Compact,
+	Skip,
+}
+
+/// Find how the field is encoded, from its `#[codec(..)]` attributes.
+fn codec_attribute(field: &syn::Field) -> Result<CodecAttribute> {
+	let mut result = CodecAttribute::None;
+	for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+		if let Meta::List(list) = attr.parse_meta()? {
+			for nested in list.nested.iter() {
+				match nested {
+					NestedMeta::Meta(Meta::Path(path)) if path.is_ident("compact") => {
+						result = CodecAttribute::Compact;
+					},
+					NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+						result = CodecAttribute::Skip;
+					},
+					other => return Err(Error::new(
+						other.span(),
+						"`MaxEncodedLen` only supports the `compact` and `skip` codec attributes",
+					)),
+				}
+			}
+		}
+	}
+
+	Ok(result)
+}
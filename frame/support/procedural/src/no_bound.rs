@@ -0,0 +1,192 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `CloneNoBound`, `PartialEqNoBound`, `EqNoBound` and `DebugNoBound`
+//! derives.
+//!
+//! Unlike the std derives, these don't add any bound on the type parameters: the generated
+//! implementation only requires the fields themselves to implement the trait.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Result, spanned::Spanned};
+
+pub fn derive_clone_no_bound(input: TokenStream) -> TokenStream {
+	derive(input, clone).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+pub fn derive_partial_eq_no_bound(input: TokenStream) -> TokenStream {
+	derive(input, partial_eq).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+pub fn derive_eq_no_bound(input: TokenStream) -> TokenStream {
+	derive(input, |input| {
+		let name = &input.ident;
+		let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+		Ok(quote! {
+			impl #impl_generics core::cmp::Eq for #name #ty_generics #where_clause {}
+		})
+	}).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+pub fn derive_debug_no_bound(input: TokenStream) -> TokenStream {
+	derive(input, debug).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn derive(
+	input: TokenStream,
+	expand: impl FnOnce(&DeriveInput) -> Result<TokenStream2>,
+) -> Result<TokenStream> {
+	let input: DeriveInput = syn::parse(input)?;
+	if let Data::Union(data) = &input.data {
+		return Err(Error::new(data.union_token.span(), "Unions are not supported"));
+	}
+
+	expand(&input).map(Into::into)
+}
+
+/// The identifiers binding the fields in a pattern, prefixed with `prefix`.
+fn bindings(fields: &Fields, prefix: &str) -> Vec<Ident> {
+	(0..fields.len()).map(|i| Ident::new(&format!("{}{}", prefix, i), Span::call_site())).collect()
+}
+
+/// A pattern destructuring `fields` of `path` into `bindings`.
+fn pattern(path: &TokenStream2, fields: &Fields, bindings: &[Ident]) -> TokenStream2 {
+	match fields {
+		Fields::Named(named) => {
+			let names = named.named.iter().map(|f| &f.ident);
+			quote!( #path { #( #names: #bindings ),* } )
+		},
+		Fields::Unnamed(_) => quote!( #path ( #( #bindings ),* ) ),
+		Fields::Unit => quote!( #path ),
+	}
+}
+
+/// The variants of the input as `(path, name, fields)`, a struct being a single variant.
+fn variants(input: &DeriveInput) -> Vec<(TokenStream2, Ident, &Fields)> {
+	match &input.data {
+		Data::Struct(data) => vec![(quote!(Self), input.ident.clone(), &data.fields)],
+		Data::Enum(data) => data.variants.iter()
+			.map(|v| {
+				let ident = &v.ident;
+				(quote!( Self::#ident ), ident.clone(), &v.fields)
+			})
+			.collect(),
+		Data::Union(_) => unreachable!("unions are rejected in `derive`; qed"),
+	}
+}
+
+/// The expression to match on: an enum without variants can only be matched by value.
+fn scrutinee(input: &DeriveInput) -> TokenStream2 {
+	match &input.data {
+		Data::Enum(data) if data.variants.is_empty() => quote!( *self ),
+		_ => quote!( self ),
+	}
+}
+
+fn clone(input: &DeriveInput) -> Result<TokenStream2> {
+	let arms = variants(input).into_iter().map(|(path, _, fields)| {
+		let bindings = bindings(fields, "_");
+		let matched = pattern(&path, fields, &bindings);
+		let cloned = bindings.iter().map(|b| quote!( core::clone::Clone::clone(#b) ));
+		let built = match fields {
+			Fields::Named(named) => {
+				let names = named.named.iter().map(|f| &f.ident);
+				quote!( #path { #( #names: #cloned ),* } )
+			},
+			Fields::Unnamed(_) => quote!( #path ( #( #cloned ),* ) ),
+			Fields::Unit => quote!( #path ),
+		};
+		quote!( #matched => #built, )
+	});
+
+	let scrutinee = scrutinee(input);
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics core::clone::Clone for #name #ty_generics #where_clause {
+			fn clone(&self) -> Self {
+				match #scrutinee {
+					#( #arms )*
+				}
+			}
+		}
+	})
+}
+
+fn partial_eq(input: &DeriveInput) -> Result<TokenStream2> {
+	let is_enum = matches!(input.data, Data::Enum(_));
+	let arms = variants(input).into_iter().map(|(path, _, fields)| {
+		let left = bindings(fields, "_l");
+		let right = bindings(fields, "_r");
+		let left_pattern = pattern(&path, fields, &left);
+		let right_pattern = pattern(&path, fields, &right);
+		quote! {
+			(#left_pattern, #right_pattern) => true #( && #left == #right )*,
+		}
+	});
+	let fallback = if is_enum {
+		quote!( #[allow(unreachable_patterns)] _ => false, )
+	} else {
+		quote!()
+	};
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics core::cmp::PartialEq for #name #ty_generics #where_clause {
+			fn eq(&self, other: &Self) -> bool {
+				match (self, other) {
+					#( #arms )*
+					#fallback
+				}
+			}
+		}
+	})
+}
+
+fn debug(input: &DeriveInput) -> Result<TokenStream2> {
+	let arms = variants(input).into_iter().map(|(path, ident, fields)| {
+		let bindings = bindings(fields, "_");
+		let matched = pattern(&path, fields, &bindings);
+		let name = syn::LitStr::new(&ident.to_string(), Span::call_site());
+		let formatted = match fields {
+			Fields::Named(named) => {
+				let names = named.named.iter().map(|f| {
+					f.ident.as_ref().expect("named fields have an ident; qed").to_string()
+				});
+				quote!( f.debug_struct(#name) #( .field(#names, #bindings) )* .finish() )
+			},
+			Fields::Unnamed(_) => quote!( f.debug_tuple(#name) #( .field(#bindings) )* .finish() ),
+			Fields::Unit => quote!( f.write_str(#name) ),
+		};
+		quote!( #matched => #formatted, )
+	});
+
+	let scrutinee = scrutinee(input);
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics core::fmt::Debug for #name #ty_generics #where_clause {
+			fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				match #scrutinee {
+					#( #arms )*
+				}
+			}
+		}
+	})
+}
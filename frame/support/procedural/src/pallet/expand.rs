@@ -0,0 +1,417 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Expansion of the pallet definition.
+//!
+//! Calls, hooks and constants are declared through `decl_module!`, storages through
+//! `decl_storage!` and errors through `decl_error!`, so the metadata and the storage layout are
+//! the ones of the equivalent decl_* pallet. The event and the genesis config are kept as written
+//! and get their implementations generated here.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Ident, Item, parse_quote};
+use super::parse::{Def, StorageDef, StorageKind, RUNTIME_GENERIC};
+
+/// The name of the store trait when none is asked with `#[pallet::generate_store(..)]`.
+const HIDDEN_STORE_NAME: &str = "__InherentHiddenStore";
+
+/// The instance generated by `decl_storage` for non-instantiable pallets.
+const INHERENT_INSTANCE_NAME: &str = "__InherentHiddenInstance";
+
+pub fn expand(mut def: Def, scrate: &TokenStream) -> TokenStream {
+	let decl_storage = expand_storage(&def, scrate);
+	let decl_module = expand_module(&def, scrate);
+	let decl_error = expand_error(&def, scrate);
+	let event = expand_event(&mut def, scrate);
+	let genesis = expand_genesis(&mut def, scrate);
+	let hooks = expand_default_hooks(&def, scrate);
+
+	// The pallet struct, the calls, the storages and the error are declared by the decl_* macros.
+	let mut removed = vec![def.pallet_struct.index];
+	removed.extend(def.call.as_ref().map(|call| call.index));
+	removed.extend(def.error.as_ref().map(|error| error.index));
+	removed.extend(def.storages.iter().map(|storage| storage.index));
+
+	let pallet = &def.pallet_struct.ident;
+	let module_alias = if pallet != "Module" {
+		Some(quote! {
+			/// Alias to the pallet, as expected by `construct_runtime`.
+			pub type Module<T> = #pallet<T>;
+		})
+	} else {
+		None
+	};
+
+	let syn::ItemMod { attrs, vis, mod_token, ident, content, .. } = def.item;
+	let items = content.expect("checked by the parsing; qed").1.into_iter()
+		.enumerate()
+		.filter(|(index, _)| !removed.contains(index))
+		.map(|(_, item)| item);
+
+	quote! {
+		#( #attrs )*
+		#vis #mod_token #ident {
+			#( #items )*
+
+			#decl_storage
+			#decl_module
+			#decl_error
+			#event
+			#genesis
+			#hooks
+			#module_alias
+		}
+	}
+}
+
+fn runtime_generic() -> Ident {
+	Ident::new(RUNTIME_GENERIC, Span::call_site())
+}
+
+/// The block number type of the runtime, as seen from the pallet.
+fn block_number() -> TokenStream {
+	let t = runtime_generic();
+	quote!( <#t as frame_system::Trait>::BlockNumber )
+}
+
+fn expand_storage(def: &Def, scrate: &TokenStream) -> TokenStream {
+	let t = runtime_generic();
+	let config = &def.config.ident;
+	let pallet = &def.pallet_struct.ident;
+	let prefix = &def.pallet_struct.storage_prefix;
+	let (store_vis, store) = match &def.pallet_struct.store {
+		Some((vis, ident)) => (vis.to_token_stream(), ident.clone()),
+		None => (quote!(), Ident::new(HIDDEN_STORE_NAME, Span::call_site())),
+	};
+	let lines = def.storages.iter().map(|storage| storage_line(storage, scrate));
+
+	quote! {
+		#scrate::decl_storage! {
+			#store_vis trait #store for #pallet<#t: #config> as #prefix {
+				#( #lines )*
+			}
+		}
+	}
+}
+
+/// The `decl_storage` line declaring the storage.
+fn storage_line(storage: &StorageDef, scrate: &TokenStream) -> TokenStream {
+	let StorageDef { attrs, vis, ident, getter, kind, value, is_option, on_empty, .. } = storage;
+
+	let getter = getter.as_ref().map(|getter| quote!( get(fn #getter) ));
+	let value_type = if *is_option {
+		quote!( Option<#value> )
+	} else {
+		quote!( #value )
+	};
+	let storage_type = match kind {
+		StorageKind::Value => value_type,
+		StorageKind::Map { hasher, key } => quote!( map hasher(#hasher) #key => #value_type ),
+		StorageKind::DoubleMap { hasher1, key1, hasher2, key2 } => quote! {
+			double_map hasher(#hasher1) #key1, hasher(#hasher2) #key2 => #value_type
+		},
+		StorageKind::NMap { keys } => {
+			let keys = keys.iter().map(|(hasher, key)| quote!( hasher(#hasher) #key ));
+			quote!( nmap #( #keys ),* => #value_type )
+		},
+	};
+	let default = on_empty.as_ref().map(|on_empty| quote! {
+		= <#on_empty as #scrate::traits::Get<#value>>::get()
+	});
+
+	quote! {
+		#( #attrs )*
+		#vis #ident #getter: #storage_type #default;
+	}
+}
+
+fn expand_module(def: &Def, scrate: &TokenStream) -> TokenStream {
+	let t = runtime_generic();
+	let config = &def.config.ident;
+	let pallet = &def.pallet_struct.ident;
+	let pallet_attrs = &def.pallet_struct.attrs;
+	let block_number = block_number();
+
+	let consts = def.config.consts.iter().map(|constant| {
+		let (docs, ident, ty) = (&constant.docs, &constant.ident, &constant.ty);
+		quote! {
+			#( #docs )*
+			const #ident: #ty = <#t as #config>::#ident::get();
+		}
+	});
+
+	let error = def.error.as_ref().map(|_| quote!( type Error = Error<#t>; ));
+
+	let calls = def.call.iter().flat_map(|call| call.fns.iter()).map(|call| {
+		let (docs, attrs, weight) = (&call.docs, &call.attrs, &call.weight);
+		let (vis, ident, output) = (&call.vis, &call.ident, &call.output);
+		let args = call.args.iter().map(|(is_compact, name, ty)| {
+			let compact = if *is_compact {
+				Some(quote!( #[compact] ))
+			} else {
+				None
+			};
+			quote!( #compact #name: #ty )
+		});
+
+		// `decl_module` requires the origin to be named `origin`.
+		let origin = &call.origin;
+		let rename_origin = if origin != "origin" {
+			Some(quote!( let #origin = origin; ))
+		} else {
+			None
+		};
+		let stmts = &call.block.stmts;
+
+		quote! {
+			#( #docs )*
+			#[weight = #weight]
+			#( #attrs )*
+			#vis fn #ident(origin #( , #args )*) #output {
+				#rename_origin
+				#( #stmts )*
+			}
+		}
+	});
+
+	quote! {
+		#scrate::decl_module! {
+			#( #pallet_attrs )*
+			pub struct #pallet<#t: #config> for enum Call where
+				origin: <#t as frame_system::Trait>::Origin,
+				system = frame_system
+			{
+				#( #consts )*
+
+				#error
+
+				fn on_initialize(n: #block_number) -> #scrate::weights::Weight {
+					<Self as #scrate::traits::Hooks<#block_number>>::on_initialize(n)
+				}
+
+				fn on_finalize(n: #block_number) {
+					<Self as #scrate::traits::Hooks<#block_number>>::on_finalize(n)
+				}
+
+				fn on_runtime_upgrade() -> #scrate::weights::Weight {
+					<Self as #scrate::traits::Hooks<#block_number>>::on_runtime_upgrade()
+				}
+
+				fn offchain_worker(n: #block_number) {
+					<Self as #scrate::traits::Hooks<#block_number>>::offchain_worker(n)
+				}
+
+				#( #calls )*
+			}
+		}
+	}
+}
+
+fn expand_error(def: &Def, scrate: &TokenStream) -> TokenStream {
+	let error = match &def.error {
+		Some(error) => error,
+		None => return quote!(),
+	};
+	let t = runtime_generic();
+	let config = &def.config.ident;
+	let pallet = &def.pallet_struct.ident;
+	let attrs = &error.attrs;
+	let variants = error.variants.iter().map(|(ident, docs)| quote!( #( #docs )* #ident ));
+
+	quote! {
+		#scrate::decl_error! {
+			#( #attrs )*
+			pub enum Error for #pallet<#t: #config> {
+				#( #variants, )*
+			}
+		}
+	}
+}
+
+/// Complete the event enum and implement its metadata and deposit function.
+fn expand_event(def: &mut Def, scrate: &TokenStream) -> TokenStream {
+	let event = match &def.event {
+		Some(event) => event,
+		None => return quote!(),
+	};
+	let t = runtime_generic();
+	let config = &def.config.ident;
+	let pallet = &def.pallet_struct.ident;
+	let items = &mut def.item.content.as_mut().expect("checked by the parsing; qed").1;
+	let item = match &mut items[event.index] {
+		Item::Enum(item) => item,
+		_ => unreachable!("checked by the parsing; qed"),
+	};
+
+	let metadata = item.variants.iter().map(|variant| {
+		let name = variant.ident.to_string();
+		let docs = variant.attrs.iter()
+			.filter_map(|attr| match attr.parse_meta() {
+				Ok(syn::Meta::NameValue(meta)) if meta.path.is_ident("doc") => Some(meta.lit),
+				_ => None,
+			});
+		let arguments = variant.fields.iter().map(|field| {
+			let ty = field.ty.to_token_stream().to_string();
+			event.metadata.iter()
+				.find(|(named, _)| named.to_token_stream().to_string() == ty)
+				.map(|(_, name)| name.value())
+				// Formatted like the `stringify!` of `decl_event`.
+				.unwrap_or_else(|| ty.replace(' ', ""))
+		});
+
+		quote! {
+			#scrate::event::EventMetadata {
+				name: #scrate::event::DecodeDifferent::Encode(#name),
+				arguments: #scrate::event::DecodeDifferent::Encode(&[ #( #arguments ),* ]),
+				documentation: #scrate::event::DecodeDifferent::Encode(&[ #( #docs ),* ]),
+			}
+		}
+	}).collect::<Vec<_>>();
+
+	item.attrs.push(parse_quote! {
+		#[derive(
+			#scrate::CloneNoBound,
+			#scrate::EqNoBound,
+			#scrate::PartialEqNoBound,
+			#scrate::DebugNoBound,
+			#scrate::codec::Encode,
+			#scrate::codec::Decode,
+			#scrate::type_info::TypeInfo,
+		)]
+	});
+	if event.is_generic {
+		// Makes sure the generic is used even if no variant refers to it.
+		item.variants.push(parse_quote! {
+			#[doc(hidden)]
+			#[codec(skip)]
+			__Ignore(#scrate::sp_std::marker::PhantomData<#t>, #scrate::Never)
+		});
+	}
+
+	let event_type = if event.is_generic {
+		quote!( Event<#t> )
+	} else {
+		quote!( Event )
+	};
+	let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+	let deposit = event.deposit.as_ref().map(|(vis, ident)| quote! {
+		impl<#t: #config> #pallet<#t> {
+			#vis fn #ident(event: #event_type) {
+				let event = <<#t as #config>::Event as From<#event_type>>::from(event);
+				<frame_system::Module<#t>>::deposit_event(event)
+			}
+		}
+	});
+
+	quote! {
+		impl #impl_generics #event_type #where_clause {
+			#[allow(dead_code)]
+			#[doc(hidden)]
+			pub fn metadata() -> &'static [#scrate::event::EventMetadata] {
+				&[ #( #metadata ),* ]
+			}
+		}
+
+		impl #impl_generics From<#event_type> for () #where_clause {
+			fn from(_: #event_type) -> () { () }
+		}
+
+		#deposit
+	}
+}
+
+/// Make the genesis config serializable and implement the genesis storage build for
+/// `construct_runtime`.
+fn expand_genesis(def: &mut Def, scrate: &TokenStream) -> TokenStream {
+	let (genesis_config, genesis_build) = match (&def.genesis_config, def.genesis_build) {
+		(Some(genesis_config), Some(genesis_build)) => (genesis_config, genesis_build),
+		_ => return quote!(),
+	};
+	let t = runtime_generic();
+	let config = &def.config.ident;
+	let items = &mut def.item.content.as_mut().expect("checked by the parsing; qed").1;
+
+	if let Item::Impl(item) = &mut items[genesis_build] {
+		item.attrs.push(parse_quote!( #[cfg(feature = "std")] ));
+	}
+
+	let item = match &mut items[genesis_config.index] {
+		Item::Struct(item) => item,
+		_ => unreachable!("checked by the parsing; qed"),
+	};
+
+	// Same bounds as the genesis config of `decl_storage`.
+	let serde_bounds = if !genesis_config.field_types.is_empty() {
+		let mut b_ser = String::new();
+		let mut b_dser = String::new();
+		for ty in genesis_config.field_types.iter() {
+			let ty = quote!( #ty );
+			b_ser.push_str(&format!("{} : {}::serde::Serialize, ", ty, scrate));
+			b_dser.push_str(&format!("{} : {}::serde::de::DeserializeOwned, ", ty, scrate));
+		}
+		vec![
+			parse_quote!( #[serde(bound(serialize = #b_ser))] ),
+			parse_quote!( #[serde(bound(deserialize = #b_dser))] ),
+		]
+	} else {
+		vec![]
+	};
+	item.attrs.extend(vec![
+		parse_quote!( #[cfg(feature = "std")] ),
+		parse_quote!( #[derive(#scrate::Serialize, #scrate::Deserialize)] ),
+		parse_quote!( #[serde(rename_all = "camelCase")] ),
+		parse_quote!( #[serde(deny_unknown_fields)] ),
+	]);
+	item.attrs.extend(serde_bounds);
+
+	let genesis_type = if genesis_config.is_generic {
+		quote!( GenesisConfig<#t> )
+	} else {
+		quote!( GenesisConfig )
+	};
+	let instance = Ident::new(INHERENT_INSTANCE_NAME, Span::call_site());
+
+	quote! {
+		#[cfg(feature = "std")]
+		impl<#t: #config> #scrate::sp_runtime::BuildModuleGenesisStorage<#t, #instance>
+			for #genesis_type
+		{
+			fn build_module_genesis_storage(
+				&self,
+				storage: &mut #scrate::sp_runtime::Storage,
+			) -> std::result::Result<(), String> {
+				<Self as #scrate::traits::GenesisBuild<#t>>::assimilate_storage(self, storage)
+			}
+		}
+	}
+}
+
+/// Implement `Hooks` with its default functions if the pallet doesn't.
+fn expand_default_hooks(def: &Def, scrate: &TokenStream) -> TokenStream {
+	if def.hooks.is_some() {
+		return quote!();
+	}
+	let t = runtime_generic();
+	let config = &def.config.ident;
+	let pallet = &def.pallet_struct.ident;
+	let block_number = block_number();
+
+	quote! {
+		impl<#t: #config> #scrate::traits::Hooks<#block_number> for #pallet<#t> {}
+	}
+}
@@ -0,0 +1,40 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `#[frame_support::pallet]` attribute.
+//!
+//! The pallet module is first parsed into a [`parse::Def`], checking every item marked with a
+//! `#[pallet::..]` attribute, then expanded into the decl_* macros.
+
+mod parse;
+mod expand;
+
+use frame_support_procedural_tools::generate_crate_access_2018;
+use proc_macro::TokenStream;
+use syn::{Error, Result, spanned::Spanned};
+
+pub fn pallet(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+	if !attr.is_empty() {
+		let attr: proc_macro2::TokenStream = attr.into();
+		return Err(Error::new(attr.span(), "Unexpected argument, expected `#[pallet]`"));
+	}
+
+	let item = syn::parse::<syn::ItemMod>(item)?;
+	let def = parse::Def::try_from(item)?;
+	let scrate = generate_crate_access_2018()?;
+
+	Ok(expand::expand(def, &scrate).into())
+}
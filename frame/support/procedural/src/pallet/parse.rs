@@ -0,0 +1,851 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of the `#[frame_support::pallet]` module into its definition.
+
+use proc_macro2::Span;
+use syn::{
+	Attribute, Error, Ident, Item, Result, Token, Type, Visibility,
+	parse::{Parse, ParseStream, Parser},
+	spanned::Spanned,
+};
+
+/// The name of the generic parameter of the pallet, as required by the decl_* macros.
+pub const RUNTIME_GENERIC: &str = "T";
+
+/// The attributes declaring the kind of a pallet item.
+const ITEM_KINDS: &[&str] = &[
+	"config", "pallet", "hooks", "call", "event", "error", "storage", "genesis_config",
+	"genesis_build",
+];
+
+/// The definition of the pallet, with the indices of its items in the module content.
+pub struct Def {
+	/// The pallet module, its content stripped of all `#[pallet::*]` attributes.
+	pub item: syn::ItemMod,
+	pub config: ConfigDef,
+	pub pallet_struct: PalletStructDef,
+	pub hooks: Option<usize>,
+	pub call: Option<CallDef>,
+	pub event: Option<EventDef>,
+	pub error: Option<ErrorDef>,
+	pub storages: Vec<StorageDef>,
+	pub genesis_config: Option<GenesisConfigDef>,
+	pub genesis_build: Option<usize>,
+}
+
+/// The `#[pallet::config]` trait.
+pub struct ConfigDef {
+	pub ident: Ident,
+	/// The associated types declared `#[pallet::constant]`.
+	pub consts: Vec<ConstDef>,
+}
+
+/// A constant of the configuration trait, declared as `type Name: Get<Type>;`.
+pub struct ConstDef {
+	pub ident: Ident,
+	pub ty: Type,
+	pub docs: Vec<Attribute>,
+}
+
+/// The `#[pallet::pallet]` struct.
+pub struct PalletStructDef {
+	pub index: usize,
+	pub ident: Ident,
+	/// The attributes of the struct other than the pallet ones.
+	pub attrs: Vec<Attribute>,
+	/// The prefix of all storage keys of the pallet, given by `#[pallet::storage_prefix = ".."]`.
+	pub storage_prefix: Ident,
+	/// The store trait asked by `#[pallet::generate_store($vis trait $ident)]`.
+	pub store: Option<(Visibility, Ident)>,
+}
+
+/// The `#[pallet::call]` implementation block.
+pub struct CallDef {
+	pub index: usize,
+	pub fns: Vec<CallFnDef>,
+}
+
+/// A dispatchable function.
+pub struct CallFnDef {
+	pub ident: Ident,
+	pub vis: Visibility,
+	pub docs: Vec<Attribute>,
+	/// The attributes other than the documentation and the weight.
+	pub attrs: Vec<Attribute>,
+	pub weight: syn::Expr,
+	pub origin: Ident,
+	/// The arguments after the origin, as `(is_compact, name, type)`.
+	pub args: Vec<(bool, Ident, Type)>,
+	pub output: syn::ReturnType,
+	pub block: syn::Block,
+}
+
+/// The `#[pallet::event]` enum.
+pub struct EventDef {
+	pub index: usize,
+	pub is_generic: bool,
+	/// The names of the argument types in the metadata, given by `#[pallet::metadata(..)]`.
+	pub metadata: Vec<(Type, syn::LitStr)>,
+	/// The visibility and name of the function asked by `#[pallet::generate_deposit(..)]`.
+	pub deposit: Option<(Visibility, Ident)>,
+}
+
+/// The `#[pallet::error]` enum.
+pub struct ErrorDef {
+	pub index: usize,
+	pub attrs: Vec<Attribute>,
+	/// The variants with their documentation.
+	pub variants: Vec<(Ident, Vec<Attribute>)>,
+}
+
+/// A `#[pallet::storage]` type alias.
+pub struct StorageDef {
+	pub index: usize,
+	pub attrs: Vec<Attribute>,
+	pub vis: Visibility,
+	pub ident: Ident,
+	pub getter: Option<Ident>,
+	pub kind: StorageKind,
+	pub value: Type,
+	pub is_option: bool,
+	/// The `Get` implementation providing the value of an empty `ValueQuery` storage.
+	pub on_empty: Option<Type>,
+}
+
+/// The storage type, with its hashers given as `decl_storage` keywords.
+pub enum StorageKind {
+	Value,
+	Map { hasher: Ident, key: Type },
+	DoubleMap { hasher1: Ident, key1: Type, hasher2: Ident, key2: Type },
+	NMap { keys: Vec<(Ident, Type)> },
+}
+
+/// The `#[pallet::genesis_config]` struct.
+pub struct GenesisConfigDef {
+	pub index: usize,
+	pub is_generic: bool,
+	pub field_types: Vec<Type>,
+}
+
+impl Def {
+	pub fn try_from(mut item: syn::ItemMod) -> Result<Self> {
+		let item_span = item.span();
+		let items = &mut item.content.as_mut()
+			.ok_or_else(|| Error::new(item_span, "Invalid pallet definition, expected inline mod"))?
+			.1;
+
+		let mut config = None;
+		let mut pallet_struct = None;
+		let mut hooks = None;
+		let mut call = None;
+		let mut event = None;
+		let mut error = None;
+		let mut storages = vec![];
+		let mut genesis_config = None;
+		let mut genesis_build = None;
+
+		for (index, item) in items.iter_mut().enumerate() {
+			let attrs = match item_attrs(item) {
+				Some(attrs) => attrs,
+				None => continue,
+			};
+			let kind = match take_item_kind_attr(attrs)? {
+				Some(attr) => attr,
+				None => continue,
+			};
+
+			match pallet_attr_name(&kind).to_string().as_str() {
+				"config" => set_once(&mut config, ConfigDef::try_from(&kind, item)?, &kind)?,
+				"pallet" => set_once(
+					&mut pallet_struct,
+					PalletStructDef::try_from(&kind, index, item)?,
+					&kind,
+				)?,
+				"hooks" => {
+					check_impl(&kind, item)?;
+					set_once(&mut hooks, index, &kind)?
+				},
+				"call" => set_once(&mut call, CallDef::try_from(&kind, index, item)?, &kind)?,
+				"event" => set_once(&mut event, EventDef::try_from(&kind, index, item)?, &kind)?,
+				"error" => set_once(&mut error, ErrorDef::try_from(&kind, index, item)?, &kind)?,
+				"storage" => storages.push(StorageDef::try_from(&kind, index, item)?),
+				"genesis_config" => set_once(
+					&mut genesis_config,
+					GenesisConfigDef::try_from(&kind, index, item)?,
+					&kind,
+				)?,
+				"genesis_build" => {
+					check_impl(&kind, item)?;
+					set_once(&mut genesis_build, index, &kind)?
+				},
+				_ => unreachable!("`take_item_kind_attr` only returns item kinds; qed"),
+			}
+
+			// All pallet attributes specific to the item have been taken by now.
+			let leftover = item_attrs(item)
+				.and_then(|attrs| attrs.iter().find(|a| is_pallet_attr(a)).map(|a| a.span()));
+			if let Some(span) = leftover {
+				return Err(Error::new(span, "Unexpected pallet attribute for this item"));
+			}
+		}
+
+		match (&genesis_config, &genesis_build) {
+			(Some(_), None) => return Err(Error::new(
+				item_span,
+				"Missing `#[pallet::genesis_build]` for the `#[pallet::genesis_config]`",
+			)),
+			(None, Some(_)) => return Err(Error::new(
+				item_span,
+				"Missing `#[pallet::genesis_config]` for the `#[pallet::genesis_build]`",
+			)),
+			_ => (),
+		}
+
+		Ok(Def {
+			config: config
+				.ok_or_else(|| Error::new(item_span, "Missing `#[pallet::config]`"))?,
+			pallet_struct: pallet_struct
+				.ok_or_else(|| Error::new(item_span, "Missing `#[pallet::pallet]`"))?,
+			hooks,
+			call,
+			event,
+			error,
+			storages,
+			genesis_config,
+			genesis_build,
+			item,
+		})
+	}
+}
+
+impl ConfigDef {
+	fn try_from(attr: &Attribute, item: &mut Item) -> Result<Self> {
+		let item = match item {
+			Item::Trait(item) => item,
+			_ => return Err(Error::new(attr.span(), "Invalid pallet::config, expected trait")),
+		};
+		if !item.generics.params.is_empty() {
+			return Err(Error::new(
+				item.generics.span(),
+				"Invalid pallet::config, generics are not supported",
+			));
+		}
+
+		let mut consts = vec![];
+		for trait_item in item.items.iter_mut() {
+			let ty = match trait_item {
+				syn::TraitItem::Type(ty) => ty,
+				_ => continue,
+			};
+			let constant = match take_pallet_attr(&mut ty.attrs, "constant") {
+				Some(constant) => constant,
+				None => continue,
+			};
+			let get_type = ty.bounds.iter()
+				.find_map(get_bound_type)
+				.ok_or_else(|| Error::new(
+					constant.span(),
+					"Invalid pallet::constant, expected `type Name: Get<Type>;`",
+				))?;
+
+			consts.push(ConstDef {
+				ident: ty.ident.clone(),
+				ty: get_type,
+				docs: docs(&ty.attrs),
+			});
+		}
+
+		Ok(ConfigDef { ident: item.ident.clone(), consts })
+	}
+}
+
+impl PalletStructDef {
+	fn try_from(attr: &Attribute, index: usize, item: &mut Item) -> Result<Self> {
+		let item = match item {
+			Item::Struct(item) => item,
+			_ => return Err(Error::new(attr.span(), "Invalid pallet::pallet, expected struct")),
+		};
+		if !matches!(item.vis, Visibility::Public(_)) {
+			return Err(Error::new(item.span(), "Invalid pallet::pallet, expected `pub struct`"));
+		}
+		check_runtime_generic(&item.generics, &item.ident)?;
+
+		let storage_prefix = take_pallet_attr(&mut item.attrs, "storage_prefix")
+			.ok_or_else(|| Error::new(
+				attr.span(),
+				"Missing `#[pallet::storage_prefix = \"..\"]` on the pallet struct",
+			))
+			.and_then(|attr| {
+				let prefix = parse_name_value::<syn::LitStr>(&attr)?;
+				syn::parse_str::<Ident>(&prefix.value())
+					.map(|ident| Ident::new(&ident.to_string(), prefix.span()))
+					.map_err(|_| Error::new(prefix.span(), "Storage prefix must be an identifier"))
+			})?;
+
+		let store = take_pallet_attr(&mut item.attrs, "generate_store")
+			.map(|attr| parse_args(&attr, |input| {
+				let vis: Visibility = input.parse()?;
+				input.parse::<Token![trait]>()?;
+				Ok((vis, input.parse::<Ident>()?))
+			}))
+			.transpose()?;
+
+		Ok(PalletStructDef {
+			index,
+			ident: item.ident.clone(),
+			attrs: item.attrs.clone(),
+			storage_prefix,
+			store,
+		})
+	}
+}
+
+impl CallDef {
+	fn try_from(attr: &Attribute, index: usize, item: &mut Item) -> Result<Self> {
+		check_impl(attr, item)?;
+		let item = match item {
+			Item::Impl(item) => item,
+			_ => unreachable!("checked by `check_impl`; qed"),
+		};
+		if let Some((_, path, _)) = &item.trait_ {
+			return Err(Error::new(path.span(), "Invalid pallet::call, expected inherent impl"));
+		}
+
+		let mut fns = vec![];
+		for impl_item in item.items.iter_mut() {
+			let method = match impl_item {
+				syn::ImplItem::Method(method) => method,
+				other => return Err(Error::new(
+					other.span(),
+					"Invalid pallet::call, only functions are allowed",
+				)),
+			};
+			fns.push(CallFnDef::try_from(method)?);
+		}
+
+		Ok(CallDef { index, fns })
+	}
+}
+
+impl CallFnDef {
+	fn try_from(method: &mut syn::ImplItemMethod) -> Result<Self> {
+		let weight = take_pallet_attr(&mut method.attrs, "weight")
+			.ok_or_else(|| Error::new(
+				method.sig.ident.span(),
+				"Invalid pallet::call, every dispatchable requires a `#[pallet::weight(..)]`",
+			))
+			.and_then(|attr| parse_args(&attr, |input| input.parse::<syn::Expr>()))?;
+
+		if let syn::ReturnType::Default = method.sig.output {
+			return Err(Error::new(
+				method.sig.span(),
+				"Invalid pallet::call, expected a return type: `DispatchResult` or \
+				`DispatchResultWithPostInfo`",
+			));
+		}
+		if !method.sig.generics.params.is_empty() {
+			return Err(Error::new(
+				method.sig.generics.span(),
+				"Invalid pallet::call, dispatchables can't be generic",
+			));
+		}
+
+		let mut inputs = method.sig.inputs.iter_mut();
+		let origin = match inputs.next() {
+			Some(syn::FnArg::Typed(arg)) => pat_ident(&arg.pat)?,
+			_ => return Err(Error::new(
+				method.sig.span(),
+				"Invalid pallet::call, the first argument must be `origin: OriginFor<T>`",
+			)),
+		};
+
+		let mut args = vec![];
+		for input in inputs {
+			let arg = match input {
+				syn::FnArg::Typed(arg) => arg,
+				syn::FnArg::Receiver(receiver) => return Err(Error::new(
+					receiver.span(),
+					"Invalid pallet::call, dispatchables don't take `self`",
+				)),
+			};
+			let is_compact = take_pallet_attr(&mut arg.attrs, "compact").is_some();
+			if let Some(attr) = arg.attrs.first() {
+				return Err(Error::new(attr.span(), "Invalid pallet::call, unexpected attribute"));
+			}
+			args.push((is_compact, pat_ident(&arg.pat)?, (*arg.ty).clone()));
+		}
+
+		let (docs, attrs): (Vec<_>, Vec<_>) = method.attrs.iter().cloned()
+			.partition(|a| a.path.is_ident("doc"));
+		Ok(CallFnDef {
+			ident: method.sig.ident.clone(),
+			vis: method.vis.clone(),
+			docs,
+			attrs,
+			weight,
+			origin,
+			args,
+			output: method.sig.output.clone(),
+			block: method.block.clone(),
+		})
+	}
+}
+
+impl EventDef {
+	fn try_from(attr: &Attribute, index: usize, item: &mut Item) -> Result<Self> {
+		let item = match item {
+			Item::Enum(item) => item,
+			_ => return Err(Error::new(attr.span(), "Invalid pallet::event, expected enum")),
+		};
+		if item.ident != "Event" {
+			return Err(Error::new(item.ident.span(), "Invalid pallet::event, expected `Event`"));
+		}
+		let is_generic = !item.generics.params.is_empty();
+		if is_generic {
+			check_runtime_generic(&item.generics, &item.ident)?;
+		}
+		for variant in item.variants.iter() {
+			if let syn::Fields::Named(fields) = &variant.fields {
+				return Err(Error::new(
+					fields.span(),
+					"Invalid pallet::event, named fields are not supported",
+				));
+			}
+		}
+
+		let metadata = take_pallet_attr(&mut item.attrs, "metadata")
+			.map(|attr| parse_args(&attr, |input| {
+				let entries = input.parse_terminated::<_, Token![,]>(|input| {
+					let ty: Type = input.parse()?;
+					input.parse::<Token![=]>()?;
+					Ok((ty, input.parse::<syn::LitStr>()?))
+				})?;
+				Ok(entries.into_iter().collect())
+			}))
+			.transpose()?
+			.unwrap_or_default();
+
+		let deposit = take_pallet_attr(&mut item.attrs, "generate_deposit")
+			.map(|attr| parse_args(&attr, |input| {
+				let vis: Visibility = input.parse()?;
+				input.parse::<Token![fn]>()?;
+				Ok((vis, input.parse::<Ident>()?))
+			}))
+			.transpose()?;
+
+		Ok(EventDef { index, is_generic, metadata, deposit })
+	}
+}
+
+impl ErrorDef {
+	fn try_from(attr: &Attribute, index: usize, item: &mut Item) -> Result<Self> {
+		let item = match item {
+			Item::Enum(item) => item,
+			_ => return Err(Error::new(attr.span(), "Invalid pallet::error, expected enum")),
+		};
+		if item.ident != "Error" {
+			return Err(Error::new(item.ident.span(), "Invalid pallet::error, expected `Error`"));
+		}
+		if !matches!(item.vis, Visibility::Public(_)) {
+			return Err(Error::new(item.span(), "Invalid pallet::error, expected `pub enum`"));
+		}
+		check_runtime_generic(&item.generics, &item.ident)?;
+
+		let variants = item.variants.iter()
+			.map(|variant| {
+				if !matches!(variant.fields, syn::Fields::Unit) {
+					return Err(Error::new(
+						variant.fields.span(),
+						"Invalid pallet::error, only unit variants are supported",
+					));
+				}
+				if let Some(discriminant) = &variant.discriminant {
+					return Err(Error::new(
+						discriminant.1.span(),
+						"Invalid pallet::error, explicit discriminants are not supported",
+					));
+				}
+				Ok((variant.ident.clone(), docs(&variant.attrs)))
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(ErrorDef { index, attrs: item.attrs.clone(), variants })
+	}
+}
+
+impl StorageDef {
+	fn try_from(attr: &Attribute, index: usize, item: &mut Item) -> Result<Self> {
+		let item = match item {
+			Item::Type(item) => item,
+			_ => return Err(Error::new(attr.span(), "Invalid pallet::storage, expected type")),
+		};
+
+		let getter = take_pallet_attr(&mut item.attrs, "getter")
+			.map(|attr| parse_args(&attr, |input| {
+				input.parse::<Token![fn]>()?;
+				input.parse::<Ident>()
+			}))
+			.transpose()?;
+
+		let invalid_type = || Error::new(
+			item.ty.span(),
+			"Invalid pallet::storage, expected `StorageValue<_, ..>`, `StorageMap<_, ..>`, \
+			`StorageDoubleMap<_, ..>` or `StorageNMap<_, ..>`",
+		);
+		let segment = match &*item.ty {
+			Type::Path(path) => path.path.segments.last().ok_or_else(invalid_type)?,
+			_ => return Err(invalid_type()),
+		};
+		let mut args = match &segment.arguments {
+			syn::PathArguments::AngleBracketed(args) => args.args.iter()
+				.map(|arg| match arg {
+					syn::GenericArgument::Type(ty) => Ok(ty.clone()),
+					other => Err(Error::new(other.span(), "Expected a type")),
+				})
+				.collect::<Result<Vec<_>>>()?,
+			_ => return Err(invalid_type()),
+		};
+
+		match args.first() {
+			Some(Type::Infer(_)) => { args.remove(0); },
+			_ => return Err(Error::new(
+				segment.span(),
+				"Invalid pallet::storage, the first generic argument must be `_`, it is replaced \
+				by the storage prefix",
+			)),
+		}
+
+		let (key_count, kind_name) = match segment.ident.to_string().as_str() {
+			"StorageValue" => (0, "StorageValue<_, Value, Query?, OnEmpty?>"),
+			"StorageMap" => (2, "StorageMap<_, Hasher, Key, Value, Query?, OnEmpty?>"),
+			"StorageDoubleMap" => (
+				4,
+				"StorageDoubleMap<_, Hasher1, Key1, Hasher2, Key2, Value, Query?, OnEmpty?>",
+			),
+			"StorageNMap" => (
+				1,
+				"StorageNMap<_, (NMapKey<Hasher, Key>, ..), Value, Query?, OnEmpty?>",
+			),
+			_ => return Err(invalid_type()),
+		};
+		if args.len() < key_count + 1 || args.len() > key_count + 3 {
+			return Err(Error::new(
+				segment.span(),
+				format!("Invalid pallet::storage, expected `{}`", kind_name),
+			));
+		}
+
+		let mut rest = args.split_off(key_count);
+		let value = rest.remove(0);
+		let mut rest = rest.into_iter();
+		let is_option = match rest.next() {
+			None => true,
+			Some(query) => match type_last_ident(&query).map(|i| i.to_string()).as_deref() {
+				Some("OptionQuery") => true,
+				Some("ValueQuery") => false,
+				_ => return Err(Error::new(
+					query.span(),
+					"Invalid pallet::storage, expected `OptionQuery` or `ValueQuery`",
+				)),
+			},
+		};
+		let on_empty = rest.next();
+		if let (true, Some(on_empty)) = (is_option, &on_empty) {
+			return Err(Error::new(
+				on_empty.span(),
+				"Invalid pallet::storage, a default value can only be given to a `ValueQuery` \
+				storage",
+			));
+		}
+
+		let mut args = args.into_iter();
+		let mut next_arg = || args.next().expect("number of arguments is checked above; qed");
+		let kind = match key_count {
+			0 => StorageKind::Value,
+			2 => StorageKind::Map { hasher: hasher_keyword(&next_arg())?, key: next_arg() },
+			4 => StorageKind::DoubleMap {
+				hasher1: hasher_keyword(&next_arg())?,
+				key1: next_arg(),
+				hasher2: hasher_keyword(&next_arg())?,
+				key2: next_arg(),
+			},
+			_ => StorageKind::NMap { keys: n_map_keys(&next_arg())? },
+		};
+
+		let def = StorageDef {
+			index,
+			attrs: item.attrs.clone(),
+			vis: item.vis.clone(),
+			ident: item.ident.clone(),
+			getter,
+			kind,
+			value,
+			is_option,
+			on_empty,
+		};
+
+		// `decl_storage` makes the storage generic if and only if its types use the runtime
+		// generic, so the alias must declare it in the same case.
+		let is_generic = !item.generics.params.is_empty();
+		if is_generic {
+			check_runtime_generic(&item.generics, &item.ident)?;
+		}
+		match (is_generic, def.uses_runtime_generic()) {
+			(true, false) => Err(Error::new(
+				item.generics.span(),
+				"Invalid pallet::storage, the storage doesn't use `T`, remove the generic",
+			)),
+			(false, true) => Err(Error::new(
+				item.ident.span(),
+				"Invalid pallet::storage, the storage uses `T`, declare it as `Name<T>`",
+			)),
+			_ => Ok(def),
+		}
+	}
+
+	/// The types whose use of the runtime generic makes the storage generic in `decl_storage`.
+	fn uses_runtime_generic(&self) -> bool {
+		let generic = Ident::new(RUNTIME_GENERIC, Span::call_site());
+		let keys = match &self.kind {
+			StorageKind::Value => vec![],
+			StorageKind::Map { key, .. } => vec![key],
+			StorageKind::DoubleMap { key1, key2, .. } => vec![key1, key2],
+			StorageKind::NMap { keys } => keys.iter().map(|(_, key)| key).collect(),
+		};
+		keys.into_iter()
+			.chain(Some(&self.value))
+			.any(|ty| frame_support_procedural_tools::syn_ext::type_contains_ident(ty, &generic))
+	}
+}
+
+impl GenesisConfigDef {
+	fn try_from(attr: &Attribute, index: usize, item: &mut Item) -> Result<Self> {
+		let item = match item {
+			Item::Struct(item) => item,
+			_ => return Err(Error::new(
+				attr.span(),
+				"Invalid pallet::genesis_config, expected struct",
+			)),
+		};
+		if item.ident != "GenesisConfig" {
+			return Err(Error::new(
+				item.ident.span(),
+				"Invalid pallet::genesis_config, expected `GenesisConfig`",
+			));
+		}
+		let is_generic = !item.generics.params.is_empty();
+		if is_generic {
+			check_runtime_generic(&item.generics, &item.ident)?;
+		}
+
+		Ok(GenesisConfigDef {
+			index,
+			is_generic,
+			field_types: item.fields.iter().map(|field| field.ty.clone()).collect(),
+		})
+	}
+}
+
+/// The attributes of the items which can be pallet items.
+fn item_attrs(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+	match item {
+		Item::Trait(item) => Some(&mut item.attrs),
+		Item::Struct(item) => Some(&mut item.attrs),
+		Item::Enum(item) => Some(&mut item.attrs),
+		Item::Impl(item) => Some(&mut item.attrs),
+		Item::Type(item) => Some(&mut item.attrs),
+		_ => None,
+	}
+}
+
+/// The documentation attributes among `attrs`.
+fn docs(attrs: &[Attribute]) -> Vec<Attribute> {
+	attrs.iter().filter(|attr| attr.path.is_ident("doc")).cloned().collect()
+}
+
+fn is_pallet_attr(attr: &Attribute) -> bool {
+	attr.path.segments.len() == 2 && attr.path.segments[0].ident == "pallet"
+}
+
+/// The name of the pallet attribute, e.g. `storage` for `#[pallet::storage]`.
+fn pallet_attr_name(attr: &Attribute) -> &Ident {
+	&attr.path.segments[1].ident
+}
+
+/// Remove the attribute declaring the kind of the item from `attrs` and return it.
+///
+/// Fails if the item has pallet attributes but none of them is a kind.
+fn take_item_kind_attr(attrs: &mut Vec<Attribute>) -> Result<Option<Attribute>> {
+	let index = attrs.iter().position(|attr| {
+		is_pallet_attr(attr) && ITEM_KINDS.iter().any(|kind| pallet_attr_name(attr) == kind)
+	});
+	match (index, attrs.iter().find(|attr| is_pallet_attr(attr))) {
+		(Some(index), _) => Ok(Some(attrs.remove(index))),
+		(None, Some(attr)) => Err(Error::new(
+			attr.span(),
+			"Invalid pallet item, expected one of `config`, `pallet`, `hooks`, `call`, `event`, \
+			`error`, `storage`, `genesis_config` or `genesis_build`",
+		)),
+		(None, None) => Ok(None),
+	}
+}
+
+/// Remove the pallet attribute called `name` from `attrs` and return it.
+fn take_pallet_attr(attrs: &mut Vec<Attribute>, name: &str) -> Option<Attribute> {
+	let index = attrs.iter()
+		.position(|attr| is_pallet_attr(attr) && pallet_attr_name(attr) == name)?;
+	Some(attrs.remove(index))
+}
+
+/// Parse the parenthesized arguments of an attribute, e.g. `(fn foo)` of
+/// `#[pallet::getter(fn foo)]`.
+fn parse_args<T>(attr: &Attribute, parser: impl FnOnce(ParseStream) -> Result<T>) -> Result<T> {
+	let parser = |input: ParseStream| {
+		let content;
+		syn::parenthesized!(content in input);
+		parser(&content)
+	};
+	parser.parse2(attr.tokens.clone())
+}
+
+/// Parse the value of a name-value attribute, e.g. `"Example"` of
+/// `#[pallet::storage_prefix = "Example"]`.
+fn parse_name_value<T: Parse>(attr: &Attribute) -> Result<T> {
+	let parser = |input: ParseStream| {
+		input.parse::<Token![=]>()?;
+		input.parse()
+	};
+	parser.parse2(attr.tokens.clone())
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, attr: &Attribute) -> Result<()> {
+	if slot.is_some() {
+		return Err(Error::new(
+			attr.span(),
+			format!("Duplicate `#[pallet::{}]` item", pallet_attr_name(attr)),
+		));
+	}
+	*slot = Some(value);
+	Ok(())
+}
+
+fn check_impl(attr: &Attribute, item: &Item) -> Result<()> {
+	match item {
+		Item::Impl(_) => Ok(()),
+		_ => Err(Error::new(
+			attr.span(),
+			format!("Invalid pallet::{}, expected impl block", pallet_attr_name(attr)),
+		)),
+	}
+}
+
+/// Check that the only generic parameter is the runtime generic `T`.
+fn check_runtime_generic(generics: &syn::Generics, item: &Ident) -> Result<()> {
+	let mut params = generics.params.iter();
+	match (params.next(), params.next()) {
+		(Some(syn::GenericParam::Type(param)), None) if param.ident == RUNTIME_GENERIC => Ok(()),
+		_ => Err(Error::new(
+			generics.span(),
+			format!("Invalid generics for `{}`, expected a single type parameter `T`", item),
+		)),
+	}
+}
+
+fn pat_ident(pat: &syn::Pat) -> Result<Ident> {
+	match pat {
+		syn::Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+			Ok(pat.ident.clone())
+		},
+		other => Err(Error::new(other.span(), "Invalid pallet::call, expected an identifier")),
+	}
+}
+
+fn type_last_ident(ty: &Type) -> Option<&Ident> {
+	match ty {
+		Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+		_ => None,
+	}
+}
+
+/// The type argument of a `Get<Type>` bound.
+fn get_bound_type(bound: &syn::TypeParamBound) -> Option<Type> {
+	let segment = match bound {
+		syn::TypeParamBound::Trait(bound) => bound.path.segments.last()?,
+		_ => return None,
+	};
+	if segment.ident != "Get" {
+		return None;
+	}
+	match &segment.arguments {
+		syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+			syn::GenericArgument::Type(ty) => Some(ty.clone()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// The `decl_storage` keyword of the hasher type, e.g. `blake2_128_concat` for
+/// `Blake2_128Concat`.
+fn hasher_keyword(hasher: &Type) -> Result<Ident> {
+	let keyword = match type_last_ident(hasher).map(|i| i.to_string()).as_deref() {
+		Some("Blake2_128") => "opaque_blake2_128",
+		Some("Blake2_256") => "opaque_blake2_256",
+		Some("Blake2_128Concat") => "blake2_128_concat",
+		Some("Twox128") => "opaque_twox_128",
+		Some("Twox256") => "opaque_twox_256",
+		Some("Twox64Concat") => "twox_64_concat",
+		Some("Identity") => "identity",
+		_ => return Err(Error::new(
+			hasher.span(),
+			"Invalid pallet::storage, expected a hasher: `Blake2_128`, `Blake2_256`, \
+			`Blake2_128Concat`, `Twox128`, `Twox256`, `Twox64Concat` or `Identity`",
+		)),
+	};
+	Ok(Ident::new(keyword, hasher.span()))
+}
+
+/// The hashers and keys of a `NMapKey<Hasher, Key>` or a tuple of them.
+fn n_map_keys(keys: &Type) -> Result<Vec<(Ident, Type)>> {
+	let n_map_key = |ty: &Type| {
+		let invalid = || Error::new(
+			ty.span(),
+			"Invalid pallet::storage, expected `NMapKey<Hasher, Key>`",
+		);
+		let segment = match ty {
+			Type::Path(path) => path.path.segments.last().ok_or_else(invalid)?,
+			_ => return Err(invalid()),
+		};
+		let args = match &segment.arguments {
+			syn::PathArguments::AngleBracketed(args)
+				if segment.ident == "NMapKey" && args.args.len() == 2 => &args.args,
+			_ => return Err(invalid()),
+		};
+		match (&args[0], &args[1]) {
+			(syn::GenericArgument::Type(hasher), syn::GenericArgument::Type(key)) => {
+				Ok((hasher_keyword(hasher)?, key.clone()))
+			},
+			_ => Err(invalid()),
+		}
+	};
+
+	match keys {
+		Type::Tuple(tuple) => tuple.elems.iter().map(n_map_key).collect(),
+		Type::Paren(paren) => Ok(vec![n_map_key(&paren.elem)?]),
+		other => Ok(vec![n_map_key(other)?]),
+	}
+}
@@ -0,0 +1,51 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `crate_to_pallet_version!` macro.
+
+use frame_support_procedural_tools::generate_crate_access_2018;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::{env, str::FromStr};
+use syn::{Error, Result};
+
+/// Reads the part `name` of the version of the crate being compiled.
+fn version_part<T: FromStr>(name: &str) -> Result<T> {
+	let value = env::var(name)
+		.map_err(|_| Error::new(Span::call_site(), format!("`{}` is not set", name)))?;
+	value.parse().map_err(|_| Error::new(
+		Span::call_site(),
+		format!("`{}` is `{}`, which doesn't fit into the `PalletVersion`", name, value),
+	))
+}
+
+/// Returns the `PalletVersion` of the crate being compiled, with `scrate` the path to
+/// `frame-support`.
+pub fn pallet_version(scrate: &TokenStream) -> Result<TokenStream> {
+	let major: u16 = version_part("CARGO_PKG_VERSION_MAJOR")?;
+	let minor: u8 = version_part("CARGO_PKG_VERSION_MINOR")?;
+	let patch: u8 = version_part("CARGO_PKG_VERSION_PATCH")?;
+
+	Ok(quote!(#scrate::traits::PalletVersion::new(#major, #minor, #patch)))
+}
+
+pub fn crate_to_pallet_version(input: proc_macro::TokenStream) -> Result<TokenStream> {
+	if !input.is_empty() {
+		return Err(Error::new(Span::call_site(), "No arguments expected"));
+	}
+
+	pallet_version(&generate_crate_access_2018()?)
+}
@@ -17,8 +17,8 @@
 //! Builder logic definition used to build genesis storage.
 
 use frame_support_procedural_tools::syn_ext as ext;
-use proc_macro2::TokenStream;
-use syn::spanned::Spanned;
+use proc_macro2::{TokenStream, Span};
+use syn::{Ident, spanned::Spanned};
 use quote::{quote, quote_spanned};
 use super::super::{DeclStorageDefExt, StorageLineTypeDef};
 
@@ -113,6 +113,22 @@ impl BuilderDef {
 							});
 						}}
 					},
+					StorageLineTypeDef::NMap(map) => {
+						let keys = &map.keys;
+						let key_tuple = map.to_key_tuple();
+						let key_args = (0..keys.len())
+							.map(|i| Ident::new(&format!("k{}", i), Span::call_site()))
+							.collect::<Vec<_>>();
+						quote!{{
+							#data
+							let data: &#scrate::sp_std::vec::Vec<(#( #keys, )* #value_type)> = data;
+							data.iter().for_each(|(#( #key_args, )* v)| {
+								<#storage_struct as #scrate::#storage_trait>::insert::<
+									(#( &#keys, )*), &#value_type
+								>((#( #key_args, )*), v);
+							});
+						}}
+					},
 				});
 			}
 		}
@@ -0,0 +1,46 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of `GetPalletVersion` on module structure.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use super::DeclStorageDefExt;
+
+pub fn impl_get_pallet_version(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStream {
+	let current_version = match crate::pallet_version::pallet_version(scrate) {
+		Ok(version) => version,
+		Err(err) => return err.to_compile_error(),
+	};
+
+	let module_struct = &def.module_struct;
+	let module_impl = &def.module_impl;
+	let where_clause = &def.where_clause;
+
+	quote!(
+		impl#module_impl #scrate::traits::GetPalletVersion for #module_struct #where_clause {
+			fn current_version() -> #scrate::traits::PalletVersion {
+				#current_version
+			}
+
+			fn storage_version() -> Option<#scrate::traits::PalletVersion> {
+				#scrate::traits::PalletVersion::get_from_storage(
+					Self::module_storage_prefix().as_bytes(),
+				)
+			}
+		}
+	)
+}
@@ -64,6 +64,20 @@ pub fn impl_getters(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStrea
 					}
 				}
 			},
+			StorageLineTypeDef::NMap(map) => {
+				let keys = map.to_key_tuple();
+				let value = &map.value;
+				quote!{
+					#( #[ #attrs ] )*
+					pub fn #get_fn<KArg>(key: KArg) -> #value
+					where
+						KArg: #scrate::storage::EncodeLikeTuple<#keys>
+							+ #scrate::storage::TupleToEncodedIter,
+					{
+						<#storage_struct as #scrate::#storage_trait>::get(key)
+					}
+				}
+			},
 		};
 		getters.extend(getter);
 	}
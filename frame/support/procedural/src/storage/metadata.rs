@@ -16,15 +16,19 @@
 
 //! Implementation of `storage_metadata` on module structure, used by construct_runtime.
 
-use frame_support_procedural_tools::clean_type_string;
+use frame_support_procedural_tools::{clean_type_string, syn_ext as ext};
 use proc_macro2::TokenStream;
 use quote::quote;
 use super::{DeclStorageDefExt, StorageLineDefExt, StorageLineTypeDef};
 
-fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) -> TokenStream {
+/// The type of the storage entry in the V11 metadata, `None` if the V11 metadata can't express it.
+fn storage_line_metadata_type(
+	scrate: &TokenStream,
+	line: &StorageLineDefExt,
+) -> Option<TokenStream> {
 	let value_type = &line.value_type;
 	let value_type = clean_type_string(&quote!( #value_type ).to_string());
-	match &line.storage_type {
+	let ty = match &line.storage_type {
 		StorageLineTypeDef::Simple(_) => {
 			quote!{
 				#scrate::metadata::StorageEntryType::Plain(
@@ -62,6 +66,107 @@ fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) ->
 				}
 			}
 		},
+		// Only described by the V12 metadata.
+		StorageLineTypeDef::NMap(_) => return None,
+	};
+	Some(ty)
+}
+
+/// The type of the storage entry, registering the key and value types, and the key and value
+/// types themselves.
+fn storage_line_metadata_type_v12(
+	scrate: &TokenStream,
+	line: &StorageLineDefExt,
+) -> (TokenStream, Vec<syn::Type>) {
+	let register = |ty: &syn::Type| quote!(
+		registry.register_type(&#scrate::type_info::meta_type::<#ty>())
+	);
+	let value_type = &line.value_type;
+	let value = register(value_type);
+	match &line.storage_type {
+		StorageLineTypeDef::Simple(_) => {
+			let ty = quote!( #scrate::metadata::StorageEntryTypeV12::Plain(#value) );
+			(ty, vec![value_type.clone()])
+		},
+		StorageLineTypeDef::Map(map) => {
+			let hasher = map.hasher.into_metadata();
+			let key = register(&map.key);
+			let ty = quote!{
+				#scrate::metadata::StorageEntryTypeV12::Map {
+					hasher: #scrate::metadata::#hasher,
+					key: #key,
+					value: #value,
+				}
+			};
+			(ty, vec![map.key.clone(), value_type.clone()])
+		},
+		StorageLineTypeDef::DoubleMap(map) => {
+			let hasher1 = map.hasher1.into_metadata();
+			let hasher2 = map.hasher2.into_metadata();
+			let key1 = register(&map.key1);
+			let key2 = register(&map.key2);
+			let ty = quote!{
+				#scrate::metadata::StorageEntryTypeV12::DoubleMap {
+					hasher: #scrate::metadata::#hasher1,
+					key1: #key1,
+					key2: #key2,
+					value: #value,
+					key2_hasher: #scrate::metadata::#hasher2,
+				}
+			};
+			(ty, vec![map.key1.clone(), map.key2.clone(), value_type.clone()])
+		},
+		StorageLineTypeDef::NMap(map) => {
+			let hashers = map.hashers.iter().map(|hasher| {
+				let hasher = hasher.into_metadata();
+				quote!( #scrate::metadata::#hasher )
+			});
+			let keys = map.keys.iter().map(|key| register(key));
+			let ty = quote!{
+				#scrate::metadata::StorageEntryTypeV12::NMap {
+					keys: #scrate::sp_std::vec![ #( #keys, )* ],
+					hashers: #scrate::sp_std::vec![ #( #hashers, )* ],
+					value: #value,
+				}
+			};
+			let mut types = map.keys.clone();
+			types.push(value_type.clone());
+			(ty, types)
+		},
+	}
+}
+
+/// The function computing the maximum size of an entry of the storage, as recorded in metadata.
+fn max_size_getter(scrate: &TokenStream, line: &StorageLineDefExt) -> TokenStream {
+	if !line.bounded {
+		return quote!( #scrate::storage::max_size::unbounded )
+	}
+
+	let value_type = &line.value_type;
+	match &line.storage_type {
+		StorageLineTypeDef::Simple(_) => {
+			quote!( #scrate::storage::max_size::value::<#value_type> )
+		},
+		StorageLineTypeDef::Map(map) => {
+			let hasher = map.hasher.to_storage_hasher_struct();
+			let key = &map.key;
+			quote!( #scrate::storage::max_size::map::<#scrate::#hasher, #key, #value_type> )
+		},
+		StorageLineTypeDef::DoubleMap(map) => {
+			let hasher1 = map.hasher1.to_storage_hasher_struct();
+			let hasher2 = map.hasher2.to_storage_hasher_struct();
+			let key1 = &map.key1;
+			let key2 = &map.key2;
+			quote!(
+				#scrate::storage::max_size::double_map::<
+					#scrate::#hasher1, #key1, #scrate::#hasher2, #key2, #value_type
+				>
+			)
+		},
+		StorageLineTypeDef::NMap(map) => {
+			let key_generator = map.to_key_generator(scrate);
+			quote!( #scrate::storage::max_size::n_map::<#key_generator, #value_type> )
+		},
 	}
 }
 
@@ -141,6 +246,8 @@ fn default_byte_getter(
 
 pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStream {
 	let mut entries = TokenStream::new();
+	let mut entries_v12 = TokenStream::new();
+	let mut types_v12 = Vec::new();
 	let mut default_byte_getter_struct_defs = TokenStream::new();
 
 	for line in def.storage_lines.iter() {
@@ -159,6 +266,8 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			default_byte_getter_struct_instance,
 		) = default_byte_getter(scrate, line, def);
 
+		let max_size_getter = max_size_getter(scrate, line);
+
 		let mut docs = TokenStream::new();
 		for attr in line.attrs.iter().filter_map(|v| v.parse_meta().ok()) {
 			if let syn::Meta::NameValue(meta) = attr {
@@ -169,7 +278,7 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			}
 		}
 
-		let entry = quote! {
+		let entry = ty.map(|ty| quote! {
 			#scrate::metadata::StorageEntryMetadata {
 				name: #scrate::metadata::DecodeDifferent::Encode(#str_name),
 				modifier: #modifier,
@@ -178,11 +287,33 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 					#scrate::metadata::DefaultByteGetter(&#default_byte_getter_struct_instance)
 				),
 				documentation: #scrate::metadata::DecodeDifferent::Encode(&[ #docs ]),
+				max_size: #scrate::metadata::DecodeDifferent::Encode(
+					#scrate::metadata::FnEncode(#max_size_getter)
+				),
+			},
+		});
+
+		let (ty_v12, types) = storage_line_metadata_type_v12(scrate, line);
+		let entry_v12 = quote! {
+			#scrate::metadata::StorageEntryMetadataV12 {
+				name: #str_name.into(),
+				modifier: #modifier,
+				ty: #ty_v12,
+				default: #scrate::metadata::DefaultByte::default_byte(
+					&#default_byte_getter_struct_instance
+				),
+				documentation: registry.map_strings(&[ #docs ]),
+				max_size: #max_size_getter(),
 			},
 		};
 
 		default_byte_getter_struct_defs.extend(default_byte_getter_struct_def);
 		entries.extend(entry);
+		entries_v12.extend(entry_v12);
+		// Only the generic types are bounded, a concrete type must implement `TypeInfo`.
+		types_v12.extend(
+			types.into_iter().filter(|ty| ext::type_contains_ident(ty, &def.module_runtime_generic))
+		);
 	}
 
 	let prefix = if let Some(instance) = &def.module_instance {
@@ -208,10 +339,27 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 		#default_byte_getter_struct_defs
 
 		impl#module_impl #module_struct #where_clause {
+			#[doc(hidden)]
+			pub fn module_storage_prefix() -> &'static str {
+				#prefix
+			}
+
 			#[doc(hidden)]
 			pub fn storage_metadata() -> #scrate::metadata::StorageMetadata {
 				#store_metadata
 			}
+
+			#[doc(hidden)]
+			pub fn storage_metadata_v12(
+				registry: &mut #scrate::type_info::Registry,
+			) -> #scrate::metadata::StorageMetadataV12
+				where #( #types_v12: #scrate::type_info::TypeInfo + 'static, )*
+			{
+				#scrate::metadata::StorageMetadataV12 {
+					prefix: #prefix.into(),
+					entries: #scrate::sp_std::vec![ #entries_v12 ],
+				}
+			}
 		}
 	)
 }
@@ -23,6 +23,7 @@ mod getters;
 mod metadata;
 mod instance_trait;
 mod genesis_config;
+mod get_pallet_version;
 
 use quote::quote;
 use frame_support_procedural_tools::{
@@ -104,9 +105,10 @@ pub struct DeclStorageDefExt {
 
 impl From<DeclStorageDef> for DeclStorageDefExt {
 	fn from(mut def: DeclStorageDef) -> Self {
+		let scrate = generate_crate_access(&hidden_crate_name(&def.hidden_crate), "frame-support");
 		let storage_lines = def.storage_lines.drain(..).collect::<Vec<_>>();
 		let storage_lines = storage_lines.into_iter()
-			.map(|line| StorageLineDefExt::from_def(line, &def))
+			.map(|line| StorageLineDefExt::from_def(line, &def, &scrate))
 			.collect();
 
 		let (
@@ -182,6 +184,8 @@ pub struct StorageLineDef {
 	config: Option<syn::Ident>,
 	/// The build function of the storage if any.
 	build: Option<syn::Expr>,
+	/// Whether the maximum size of the storage entries is recorded in metadata.
+	bounded: bool,
 	/// Default value of genesis config field and also for storage when no value available.
 	default_value: Option<syn::Expr>,
 	storage_type: StorageLineTypeDef,
@@ -199,6 +203,8 @@ pub struct StorageLineDefExt {
 	config: Option<syn::Ident>,
 	/// The build function of the storage if any.
 	build: Option<syn::Expr>,
+	/// Whether the maximum size of the storage entries is recorded in metadata.
+	bounded: bool,
 	/// Default value of genesis config field and also for storage when no value available.
 	default_value: Option<syn::Expr>,
 	storage_type: StorageLineTypeDef,
@@ -226,7 +232,11 @@ pub struct StorageLineDefExt {
 }
 
 impl StorageLineDefExt {
-	fn from_def(storage_def: StorageLineDef, def: &DeclStorageDef) -> Self {
+	fn from_def(
+		storage_def: StorageLineDef,
+		def: &DeclStorageDef,
+		scrate: &proc_macro2::TokenStream,
+	) -> Self {
 		let is_generic = match &storage_def.storage_type {
 			StorageLineTypeDef::Simple(value) => {
 				ext::type_contains_ident(&value, &def.module_runtime_generic)
@@ -240,12 +250,17 @@ impl StorageLineDefExt {
 					|| ext::type_contains_ident(&map.key2, &def.module_runtime_generic)
 					|| ext::type_contains_ident(&map.value, &def.module_runtime_generic)
 			}
+			StorageLineTypeDef::NMap(map) => {
+				map.keys.iter().any(|key| ext::type_contains_ident(key, &def.module_runtime_generic))
+					|| ext::type_contains_ident(&map.value, &def.module_runtime_generic)
+			}
 		};
 
 		let query_type = match &storage_def.storage_type {
 			StorageLineTypeDef::Simple(value) => value.clone(),
 			StorageLineTypeDef::Map(map) => map.value.clone(),
 			StorageLineTypeDef::DoubleMap(map) => map.value.clone(),
+			StorageLineTypeDef::NMap(map) => map.value.clone(),
 		};
 		let is_option = ext::extract_type_option(&query_type).is_some();
 		let value_type = ext::extract_type_option(&query_type).unwrap_or(query_type.clone());
@@ -291,6 +306,10 @@ impl StorageLineDefExt {
 				let key2 = &map.key2;
 				quote!( StorageDoubleMap<#key1, #key2, #value_type> )
 			},
+			StorageLineTypeDef::NMap(map) => {
+				let key_generator = map.to_key_generator(scrate);
+				quote!( StorageNMap<#key_generator, #value_type> )
+			},
 		};
 
 		let storage_trait = quote!( storage::#storage_trait_truncated );
@@ -308,6 +327,7 @@ impl StorageLineDefExt {
 			getter: storage_def.getter,
 			config: storage_def.config,
 			build: storage_def.build,
+			bounded: storage_def.bounded,
 			default_value: storage_def.default_value,
 			storage_type: storage_def.storage_type,
 			doc_attrs,
@@ -328,6 +348,7 @@ impl StorageLineDefExt {
 pub enum StorageLineTypeDef {
 	Map(MapDef),
 	DoubleMap(DoubleMapDef),
+	NMap(NMapDef),
 	Simple(syn::Type),
 }
 
@@ -347,6 +368,41 @@ pub struct DoubleMapDef {
 	pub value: syn::Type,
 }
 
+/// The maximum number of keys of an N map, bounded by the key generators implemented in
+/// `frame_support::storage::key`.
+const NMAP_MAX_KEYS: usize = 10;
+
+pub struct NMapDef {
+	pub hashers: Vec<HasherKind>,
+	pub keys: Vec<syn::Type>,
+	/// This is the query value not the inner value used in storage trait implementation.
+	pub value: syn::Type,
+}
+
+impl NMapDef {
+	/// The key generator of the map, for example `(Key<Twox64Concat, u32>, Key<Identity, u64>)`.
+	///
+	/// A map with a single key uses the `Key` type directly.
+	fn to_key_generator(&self, scrate: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		let keys = self.hashers.iter().zip(&self.keys).map(|(hasher, key)| {
+			let hasher = hasher.to_storage_hasher_struct();
+			quote!( #scrate::storage::key::Key<#scrate::#hasher, #key> )
+		}).collect::<Vec<_>>();
+
+		if keys.len() == 1 {
+			quote!( #( #keys )* )
+		} else {
+			quote!( ( #( #keys, )* ) )
+		}
+	}
+
+	/// The tuple of keys of the map, for example `(u32, u64)`.
+	fn to_key_tuple(&self) -> proc_macro2::TokenStream {
+		let keys = &self.keys;
+		quote!( ( #( #keys, )* ) )
+	}
+}
+
 pub struct ExtraGenesisLineDef {
 	attrs: Vec<syn::Attribute>,
 	name: syn::Ident,
@@ -391,13 +447,17 @@ impl HasherKind {
 	}
 }
 
+/// The name used for the hidden includes, `decl_storage` unless overridden with `hiddencrate`.
+fn hidden_crate_name(hidden_crate: &Option<syn::Ident>) -> String {
+	hidden_crate.as_ref().map(|i| i.to_string()).unwrap_or_else(|| "decl_storage".to_string())
+}
+
 /// Full implementation of decl_storage.
 pub fn decl_storage_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let def = syn::parse_macro_input!(input as DeclStorageDef);
 	let def_ext = DeclStorageDefExt::from(def);
 
-	let hidden_crate_name = def_ext.hidden_crate.as_ref().map(|i| i.to_string())
-		.unwrap_or_else(|| "decl_storage".to_string());
+	let hidden_crate_name = hidden_crate_name(&def_ext.hidden_crate);
 
 	let scrate = generate_crate_access(&hidden_crate_name, "frame-support");
 	let scrate_decl = generate_hidden_includes(&hidden_crate_name, "frame-support");
@@ -408,12 +468,14 @@ pub fn decl_storage_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 	let instance_trait = instance_trait::decl_and_impl(&scrate, &def_ext);
 	let genesis_config = genesis_config::genesis_config_and_build_storage(&scrate, &def_ext);
 	let storage_struct = storage_struct::decl_and_impl(&scrate, &def_ext);
+	let get_pallet_version = get_pallet_version::impl_get_pallet_version(&scrate, &def_ext);
 
 	quote!(
 		use #scrate::{
 			StorageValue as _,
 			StorageMap as _,
 			StorageDoubleMap as _,
+			StorageNMap as _,
 			StoragePrefixedMap as _,
 		};
 
@@ -424,5 +486,6 @@ pub fn decl_storage_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 		#instance_trait
 		#genesis_config
 		#storage_struct
+		#get_pallet_version
 	).into()
 }
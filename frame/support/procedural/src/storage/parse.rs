@@ -25,9 +25,11 @@ mod keyword {
 	syn::custom_keyword!(extra_genesis_skip_phantom_data_field);
 	syn::custom_keyword!(config);
 	syn::custom_keyword!(build);
+	syn::custom_keyword!(bounded);
 	syn::custom_keyword!(get);
 	syn::custom_keyword!(map);
 	syn::custom_keyword!(double_map);
+	syn::custom_keyword!(nmap);
 	syn::custom_keyword!(opaque_blake2_256);
 	syn::custom_keyword!(opaque_blake2_128);
 	syn::custom_keyword!(blake2_128_concat);
@@ -159,6 +161,7 @@ struct DeclStorageLine {
 	pub getter: Opt<DeclStorageGetter>,
 	pub config: Opt<DeclStorageConfig>,
 	pub build: Opt<DeclStorageBuild>,
+	pub bounded: Opt<DeclStorageBounded>,
 	pub coldot_token: Token![:],
 	pub storage_type: DeclStorageType,
 	pub default_value: Opt<DeclStorageDefault>,
@@ -194,10 +197,18 @@ struct DeclStorageBuild {
 
 impl_parse_for_opt!(DeclStorageBuild => keyword::build);
 
+#[derive(Parse, ToTokens, Debug)]
+struct DeclStorageBounded {
+	pub bounded_keyword: keyword::bounded,
+}
+
+impl_parse_for_opt!(DeclStorageBounded => keyword::bounded);
+
 #[derive(ToTokens, Debug)]
 enum DeclStorageType {
 	Map(DeclStorageMap),
 	DoubleMap(DeclStorageDoubleMap),
+	NMap(DeclStorageNMap),
 	Simple(syn::Type),
 }
 
@@ -207,6 +218,8 @@ impl syn::parse::Parse for DeclStorageType {
 			Ok(Self::Map(input.parse()?))
 		} else if input.peek(keyword::double_map) {
 			Ok(Self::DoubleMap(input.parse()?))
+		} else if input.peek(keyword::nmap) {
+			Ok(Self::NMap(input.parse()?))
 		} else {
 			Ok(Self::Simple(input.parse()?))
 		}
@@ -234,6 +247,42 @@ struct DeclStorageDoubleMap {
 	pub value: syn::Type,
 }
 
+#[derive(Parse, ToTokens, Debug)]
+struct DeclStorageNMapKey {
+	pub hasher: Opt<SetHasher>,
+	pub key: syn::Type,
+}
+
+#[derive(ToTokens, Debug)]
+struct DeclStorageNMap {
+	pub map_keyword: keyword::nmap,
+	pub storage_keys: syn::punctuated::Punctuated<DeclStorageNMapKey, Token![,]>,
+	pub ass_keyword: Token![=>],
+	pub value: syn::Type,
+}
+
+impl syn::parse::Parse for DeclStorageNMap {
+	fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+		let map_keyword = input.parse()?;
+
+		let mut storage_keys = syn::punctuated::Punctuated::new();
+		loop {
+			storage_keys.push_value(input.parse()?);
+			if input.peek(Token![=>]) {
+				break;
+			}
+			storage_keys.push_punct(input.parse()?);
+		}
+
+		Ok(Self {
+			map_keyword,
+			storage_keys,
+			ass_keyword: input.parse()?,
+			value: input.parse()?,
+		})
+	}
+}
+
 #[derive(ToTokens, Debug)]
 enum Hasher {
 	Blake2_256(keyword::opaque_blake2_256),
@@ -485,6 +534,29 @@ fn parse_storage_line_defs(
 					value: map.value,
 				}
 			),
+			DeclStorageType::NMap(map) => {
+				if map.storage_keys.len() > super::NMAP_MAX_KEYS {
+					return Err(syn::Error::new(
+						span,
+						format!("`nmap` supports at most {} keys.", super::NMAP_MAX_KEYS),
+					))
+				}
+
+				let mut hashers = Vec::new();
+				let mut keys = Vec::new();
+				for storage_key in map.storage_keys {
+					hashers.push(storage_key.hasher.inner.ok_or_else(no_hasher_error)?.into());
+					keys.push(storage_key.key);
+				}
+
+				super::StorageLineTypeDef::NMap(
+					super::NMapDef {
+						hashers,
+						keys,
+						value: map.value,
+					}
+				)
+			},
 			DeclStorageType::Simple(expr) => super::StorageLineTypeDef::Simple(expr),
 		};
 
@@ -495,6 +567,7 @@ fn parse_storage_line_defs(
 			getter,
 			config,
 			build: line.build.inner.map(|o| o.expr.content),
+			bounded: line.bounded.inner.is_some(),
 			default_value: line.default_value.inner.map(|o| o.expr),
 			storage_type,
 		})
@@ -29,6 +29,7 @@ pub use crate::weights::{
 };
 pub use sp_runtime::{traits::Dispatchable, DispatchError};
 pub use crate::traits::{CallMetadata, GetCallMetadata, GetCallName};
+use crate::type_info::TypeInfo;
 
 /// The return typ of a `Dispatchable` in frame. When returned explicitly from
 /// a dispatchable function it allows overriding the default `PostDispatchInfo`
@@ -59,8 +60,8 @@ pub type CallableCallFor<A, T> = <A as Callable<T>>::Call;
 /// A type that can be used as a parameter in a dispatchable function.
 ///
 /// When using `decl_module` all arguments for call functions must implement this trait.
-pub trait Parameter: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
-impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
+pub trait Parameter: Codec + EncodeLike + Clone + Eq + fmt::Debug + TypeInfo {}
+impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug + TypeInfo {}
 
 /// Declares a `Module` struct and a `Call` enum, which implements the dispatch logic.
 ///
@@ -167,6 +168,28 @@ impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
 /// # fn main() {}
 /// ```
 ///
+/// ### Transactional Function Example
+///
+/// Transactional function discards all changes to storage if it returns `Err`, or commits if
+/// `Ok`, via the #\[transactional\] attribute. Note the attribute must be after #\[weight\].
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate frame_support;
+/// # use frame_support::transactional;
+/// # use frame_system::{self as system, Trait};
+/// decl_module! {
+/// 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+/// 		#[weight = 0]
+/// 		#[transactional]
+/// 		fn my_short_function(origin) {
+///				// Your implementation
+/// 		}
+/// 	}
+/// }
+/// # fn main() {}
+/// ```
+///
 /// ### Privileged Function Example
 ///
 /// A privileged function checks that the origin of the call is `ROOT`.
@@ -807,6 +830,7 @@ macro_rules! decl_module {
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		#[weight = $weight:expr]
+		$(#[$fn_attr:meta])*
 		$fn_vis:vis fn $fn_name:ident(
 			$origin:ident $( , $(#[$codec_attr:ident])* $param_name:ident : $param:ty )* $(,)?
 		) $( -> $result:ty )* { $( $impl:tt )* }
@@ -830,6 +854,7 @@ macro_rules! decl_module {
 				$( $dispatchables )*
 				$(#[doc = $doc_attr])*
 				#[weight = $weight]
+				$(#[$fn_attr])*
 				$fn_vis fn $fn_name(
 					$origin $( , $(#[$codec_attr])* $param_name : $param )*
 				) $( -> $result )* { $( $impl )* }
@@ -1022,7 +1047,7 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn on_initialize(_block_number_not_used: $trait_instance::BlockNumber) -> $return {
-				$crate::sp_tracing::enter_span!("on_initialize");
+				$crate::enter_span!("on_initialize");
 				{ $( $impl )* }
 			}
 		}
@@ -1038,7 +1063,7 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn on_initialize($param: $param_ty) -> $return {
-				$crate::sp_tracing::enter_span!("on_initialize");
+				$crate::enter_span!("on_initialize");
 				{ $( $impl )* }
 			}
 		}
@@ -1064,7 +1089,7 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn on_runtime_upgrade() -> $return {
-				$crate::sp_tracing::enter_span!("on_runtime_upgrade");
+				$crate::enter_span!("on_runtime_upgrade");
 				{ $( $impl )* }
 			}
 		}
@@ -1091,7 +1116,7 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn on_finalize(_block_number_not_used: $trait_instance::BlockNumber) {
-				$crate::sp_tracing::enter_span!("on_finalize");
+				$crate::enter_span!("on_finalize");
 				{ $( $impl )* }
 			}
 		}
@@ -1107,7 +1132,7 @@ macro_rules! decl_module {
 			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
 		{
 			fn on_finalize($param: $param_ty) {
-				$crate::sp_tracing::enter_span!("on_finalize");
+				$crate::enter_span!("on_finalize");
 				{ $( $impl )* }
 			}
 		}
@@ -1167,16 +1192,18 @@ macro_rules! decl_module {
 		$error_type:ty;
 		$ignore:ident;
 		$(#[doc = $doc_attr:tt])*
+		[ $(#[$fn_attr:meta])* ]
 		$vis:vis fn $name:ident (
 			$origin:ident $(, $param:ident : $param_ty:ty )*
 		) { $( $impl:tt )* }
 	) => {
 		$(#[doc = $doc_attr])*
 		#[allow(unreachable_code)]
+		$(#[$fn_attr])*
 		$vis fn $name(
 			$origin: $origin_ty $(, $param: $param_ty )*
 		) -> $crate::dispatch::DispatchResult {
-			$crate::sp_tracing::enter_span!(stringify!($name));
+			$crate::enter_span!(stringify!($name));
 			{ $( $impl )* }
 			Ok(())
 		}
@@ -1189,13 +1216,15 @@ macro_rules! decl_module {
 		$error_type:ty;
 		$ignore:ident;
 		$(#[doc = $doc_attr:tt])*
+		[ $(#[$fn_attr:meta])* ]
 		$vis:vis fn $name:ident (
 			$origin:ident $(, $param:ident : $param_ty:ty )*
 		) -> $result:ty { $( $impl:tt )* }
 	) => {
 		$(#[doc = $doc_attr])*
+		$(#[$fn_attr])*
 		$vis fn $name($origin: $origin_ty $(, $param: $param_ty )* ) -> $result {
-			$crate::sp_tracing::enter_span!(stringify!($name));
+			$crate::enter_span!(stringify!($name));
 			$( $impl )*
 		}
 	};
@@ -1302,7 +1331,7 @@ macro_rules! decl_module {
 		/// Dispatchable calls.
 		///
 		/// Each variant of this enum maps to a dispatchable function from the associated module.
-		#[derive($crate::codec::Encode, $crate::codec::Decode)]
+		#[derive($crate::codec::Encode, $crate::codec::Decode, $crate::type_info::TypeInfo)]
 		pub enum $call_type<$trait_instance: $trait_name$(<I>, $instance: $instantiable $( = $module_default_instance)?)?>
 			where $( $other_where_bounds )*
 		{
@@ -1325,6 +1354,7 @@ macro_rules! decl_module {
 			$(
 				$(#[doc = $doc_attr:tt])*
 				#[weight = $weight:expr]
+				$(#[$fn_attr:meta])*
 				$fn_vis:vis fn $fn_name:ident(
 					$from:ident $( , $(#[$codec_attr:ident])* $param_name:ident : $param:ty)*
 				) $( -> $result:ty )* { $( $impl:tt )* }
@@ -1401,6 +1431,7 @@ macro_rules! decl_module {
 					$error_type;
 					$from;
 					$(#[doc = $doc_attr])*
+					[ $(#[$fn_attr])* ]
 					$fn_vis fn $fn_name (
 						$from $(, $param_name : $param )*
 					) $( -> $result )* { $( $impl )* }
@@ -1616,12 +1647,16 @@ pub trait IsSubType<T: Callable<R>, R> {
 }
 
 /// Implement a meta-dispatch module to dispatch to other dispatchers.
+///
+/// A module can be prefixed with `#[codec(index = "N")]` to pin the index its calls are encoded
+/// with, instead of its position in the list.
 #[macro_export]
 macro_rules! impl_outer_dispatch {
 	(
 		$(#[$attr:meta])*
 		pub enum $call_type:ident for $runtime:ident where origin: $origin:ty {
 			$(
+				$( #[codec(index = $index:tt)] )?
 				$module:ident::$camelcase:ident,
 			)*
 		}
@@ -1632,9 +1667,11 @@ macro_rules! impl_outer_dispatch {
 			$crate::codec::Encode,
 			$crate::codec::Decode,
 			$crate::RuntimeDebug,
+			$crate::type_info::TypeInfo,
 		)]
 		pub enum $call_type {
 			$(
+				$( #[codec(index = $index)] )?
 				$camelcase ( $crate::dispatch::CallableCallFor<$camelcase, $runtime> )
 			,)*
 		}
@@ -1756,14 +1793,27 @@ macro_rules! __dispatch_impl_metadata {
 	(
 		$mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>
 		{ $( $other_where_bounds:tt )* }
-		$($rest:tt)*
+		$call_type:ident $($rest:tt)*
 	) => {
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $mod_type<$trait_instance $(, $instance)?>
 			where $( $other_where_bounds )*
 		{
 			#[doc(hidden)]
 			pub fn call_functions() -> &'static [$crate::dispatch::FunctionMetadata] {
-				$crate::__call_to_functions!($($rest)*)
+				$crate::__call_to_functions!($call_type $($rest)*)
+			}
+
+			#[doc(hidden)]
+			pub fn call_functions_v12(
+				registry: &mut $crate::type_info::Registry,
+			) -> $crate::dispatch::Vec<$crate::metadata::FunctionMetadataV12>
+				where
+					$call_type<$trait_instance $(, $instance)?>:
+						$crate::type_info::TypeInfo + 'static,
+			{
+				$crate::__call_to_functions_v12!(
+					registry; $trait_instance $(, $instance)?; $call_type $($rest)*
+				)
 			}
 		}
 	}
@@ -1887,10 +1937,57 @@ macro_rules! __impl_module_constants_metadata {
 					),*
 				]
 			}
+
+			#[doc(hidden)]
+			pub fn module_constants_metadata_v12(
+				registry: &mut $crate::type_info::Registry,
+			) -> $crate::dispatch::Vec<$crate::metadata::ModuleConstantMetadataV12>
+				where $( $type: $crate::type_info::TypeInfo + 'static, )*
+			{
+				$crate::sp_std::vec![
+					$(
+						$crate::metadata::ModuleConstantMetadataV12 {
+							name: stringify!($name).into(),
+							ty: registry.register_type(&$crate::type_info::meta_type::<$type>()),
+							value: {
+								let value: $type = $value;
+								$crate::dispatch::Encode::encode(&value)
+							},
+							documentation: registry.map_strings(&[ $( $doc_attr ),* ]),
+						}
+					),*
+				]
+			}
 		}
 	}
 }
 
+/// Convert the list of calls into their metadata, described by the type of the `Call` enum.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __call_to_functions_v12 {
+	(
+		$registry:ident;
+		$trait_instance:ident $(, $instance:ident)?;
+		$call_type:ident $origin_type:ty
+		{
+			$(
+				$(#[doc = $doc_attr:tt])*
+				fn $fn_name:ident($from:ident
+					$(
+						, $(#[$codec_attr:ident])* $param_name:ident : $param:ty
+					)*
+				);
+			)*
+		}
+	) => {
+		$crate::metadata::functions_metadata_v12::<$call_type<$trait_instance $(, $instance)?>>(
+			$registry,
+			&[ $( &[ $( stringify!($param_name) ),* ] ),* ],
+		)
+	};
+}
+
 /// Convert the list of calls into their JSON representation, joined by ",".
 #[macro_export]
 #[doc(hidden)]
@@ -20,6 +20,30 @@
 pub use sp_runtime::traits::{LookupError, BadOrigin};
 #[doc(hidden)]
 pub use frame_metadata::{ModuleErrorMetadata, ErrorMetadata, DecodeDifferent};
+use sp_runtime::DispatchError;
+use crate::traits::ModuleToIndex;
+
+/// Returns the name of the error of the module `M` that the given `DispatchError` refers to.
+///
+/// The error is resolved from its module and error indices, so this also works for an error
+/// that lost its message, e.g. after being encoded in an event. `I` is the `ModuleToIndex` of
+/// the runtime. Returns `None` if the error is not an error of `M`.
+pub fn module_error_name<M, I>(error: &DispatchError) -> Option<&'static str> where
+	M: ModuleErrorMetadata + 'static,
+	I: ModuleToIndex,
+{
+	match error {
+		DispatchError::Module { index, error, .. }
+			if I::module_to_index::<M>() == Some(*index as usize) =>
+		{
+			match M::metadata().get(*error as usize)?.name {
+				DecodeDifferent::Encode(name) => Some(name),
+				DecodeDifferent::Decoded(_) => None,
+			}
+		},
+		_ => None,
+	}
+}
 
 /// Declare an error type for a runtime module.
 ///
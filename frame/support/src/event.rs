@@ -126,6 +126,7 @@ macro_rules! decl_event {
 			$crate::codec::Encode,
 			$crate::codec::Decode,
 			$crate::RuntimeDebug,
+			$crate::type_info::TypeInfo,
 		)]
 		/// Events for this module.
 		///
@@ -269,6 +270,7 @@ macro_rules! __decl_generic_event {
 			$crate::codec::Encode,
 			$crate::codec::Decode,
 			$crate::RuntimeDebug,
+			$crate::type_info::TypeInfo,
 		)]
 		/// Events for this module.
 		///
@@ -331,6 +333,9 @@ macro_rules! __events_to_metadata {
 
 /// Constructs an Event type for a runtime. This is usually called automatically by the
 /// construct_runtime macro.
+///
+/// A module can be prefixed with `#[codec(index = "N")]` to pin the index its events are encoded
+/// with, instead of its position in the list.
 #[macro_export]
 macro_rules! impl_outer_event {
 	// Macro transformations (to convert invocations with incomplete parameters to the canonical
@@ -355,17 +360,25 @@ macro_rules! impl_outer_event {
 		$name:ident;
 		$runtime:ident;
 		Modules {
+			$( #[codec(index = $index:tt)] )?
 			$module:ident $instance:ident<T>,
 			$( $rest_event_generic_instance:tt )*
 		};
-		$( $module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?, )*;
+		$(
+			$( #[codec(index = $prev_index:tt)] )?
+			$module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?,
+		)*;
 	) => {
 		$crate::impl_outer_event!(
 			$( #[$attr] )*;
 			$name;
 			$runtime;
 			Modules { $( $rest_event_generic_instance )* };
-			$( $module_name::Event $( <$generic_param> )? $( { $generic_instance } )?, )* $module::Event<$runtime>{ $instance },;
+			$(
+				$( #[codec(index = $prev_index)] )?
+				$module_name::Event $( <$generic_param> )? $( { $generic_instance } )?,
+			)*
+			$( #[codec(index = $index)] )? $module::Event<$runtime>{ $instance },;
 		);
 	};
 	// Instance
@@ -374,17 +387,25 @@ macro_rules! impl_outer_event {
 		$name:ident;
 		$runtime:ident;
 		Modules {
+			$( #[codec(index = $index:tt)] )?
 			$module:ident $instance:ident,
 			$( $rest_event_instance:tt )*
 		};
-		$( $module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?, )*;
+		$(
+			$( #[codec(index = $prev_index:tt)] )?
+			$module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?,
+		)*;
 	) => {
 		$crate::impl_outer_event!(
 			$( #[$attr] )*;
 			$name;
 			$runtime;
 			Modules { $( $rest_event_instance )* };
-			$( $module_name::Event $( <$generic_param> )* $( { $generic_instance } )?, )* $module::Event { $instance },;
+			$(
+				$( #[codec(index = $prev_index)] )?
+				$module_name::Event $( <$generic_param> )* $( { $generic_instance } )?,
+			)*
+			$( #[codec(index = $index)] )? $module::Event { $instance },;
 		);
 	};
 	// Generic
@@ -393,17 +414,25 @@ macro_rules! impl_outer_event {
 		$name:ident;
 		$runtime:ident;
 		Modules {
+			$( #[codec(index = $index:tt)] )?
 			$module:ident<T>,
 			$( $rest_event_generic:tt )*
 		};
-		$( $module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?, )*;
+		$(
+			$( #[codec(index = $prev_index:tt)] )?
+			$module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?,
+		)*;
 	) => {
 		$crate::impl_outer_event!(
 			$( #[$attr] )*;
 			$name;
 			$runtime;
 			Modules { $( $rest_event_generic )* };
-			$( $module_name::Event $( <$generic_param> )? $( { $generic_instance } )?, )* $module::Event<$runtime>,;
+			$(
+				$( #[codec(index = $prev_index)] )?
+				$module_name::Event $( <$generic_param> )? $( { $generic_instance } )?,
+			)*
+			$( #[codec(index = $index)] )? $module::Event<$runtime>,;
 		);
 	};
 	// No Generic and no Instance
@@ -412,17 +441,25 @@ macro_rules! impl_outer_event {
 		$name:ident;
 		$runtime:ident;
 		Modules {
+			$( #[codec(index = $index:tt)] )?
 			$module:ident,
 			$( $rest_event_no_generic_no_instance:tt )*
 		};
-		$( $module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?, )*;
+		$(
+			$( #[codec(index = $prev_index:tt)] )?
+			$module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?,
+		)*;
 	) => {
 		$crate::impl_outer_event!(
 			$( #[$attr] )*;
 			$name;
 			$runtime;
 			Modules { $( $rest_event_no_generic_no_instance )* };
-			$( $module_name::Event $( <$generic_param> )? $( { $generic_instance } )?, )* $module::Event,;
+			$(
+				$( #[codec(index = $prev_index)] )?
+				$module_name::Event $( <$generic_param> )? $( { $generic_instance } )?,
+			)*
+			$( #[codec(index = $index)] )? $module::Event,;
 		);
 	};
 
@@ -432,7 +469,10 @@ macro_rules! impl_outer_event {
 		$name:ident;
 		$runtime:ident;
 		Modules {};
-		$( $module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?, )*;
+		$(
+			$( #[codec(index = $prev_index:tt)] )?
+			$module_name:ident::Event $( <$generic_param:ident> )? $( { $generic_instance:ident } )?,
+		)*;
 	) => {
 		$crate::paste::item! {
 			#[derive(
@@ -440,11 +480,13 @@ macro_rules! impl_outer_event {
 				$crate::codec::Encode,
 				$crate::codec::Decode,
 				$crate::RuntimeDebug,
+				$crate::type_info::TypeInfo,
 			)]
 			$(#[$attr])*
 			#[allow(non_camel_case_types)]
 			pub enum $name {
 				$(
+					$( #[codec(index = $prev_index)] )?
 					[< $module_name $(_ $generic_instance )? >](
 						$module_name::Event < $( $generic_param )? $(, $module_name::$generic_instance )? >
 					),
@@ -530,6 +572,15 @@ macro_rules! __impl_outer_event_json_metadata {
 				{
 					$module_name::Event ::< $( $generic_params ),* > ::metadata()
 				}
+
+				#[allow(dead_code)]
+				pub fn [< __module_events_v12_ $module_name $( _ $instance )? >] (
+					registry: &mut $crate::type_info::Registry,
+				) -> $crate::sp_std::vec::Vec<$crate::metadata::EventMetadataV12> {
+					$crate::metadata::events_metadata_v12::<
+						$module_name::Event ::< $( $generic_params ),* >
+					>(registry)
+				}
 			)*
 		}
 	}
@@ -33,7 +33,6 @@ pub use serde;
 pub use sp_std;
 #[doc(hidden)]
 pub use codec;
-use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 #[doc(hidden)]
 pub use once_cell;
@@ -46,6 +45,7 @@ pub use sp_state_machine::BasicExternalities;
 pub use sp_io::storage::root as storage_root;
 #[doc(hidden)]
 pub use sp_runtime::RuntimeDebug;
+pub use sp_type_info as type_info;
 
 #[macro_use]
 pub mod debug;
@@ -73,14 +73,15 @@ pub use self::hash::{
 	StorageHasher, ReversibleStorageHasher
 };
 pub use self::storage::{
-	StorageValue, StorageMap, StorageDoubleMap, StoragePrefixedMap, IterableStorageMap,
-	IterableStorageDoubleMap, migration
+	StorageValue, StorageMap, StorageDoubleMap, StorageNMap, StoragePrefixedMap,
+	IterableStorageMap, IterableStorageDoubleMap, IterableStorageNMap, BoundedVec, BoundedBTreeMap,
+	migration
 };
 pub use self::dispatch::{Parameter, Callable, IsSubType};
 pub use sp_runtime::{self, ConsensusEngineId, print, traits::Printable};
 
 /// A type that cannot be instantiated.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Never {}
 
 /// Macro for easily creating a new implementation of the `Get` trait. Use similarly to
@@ -153,7 +154,36 @@ macro_rules! ord_parameter_types {
 }
 
 #[doc(inline)]
-pub use frame_support_procedural::{decl_storage, construct_runtime};
+pub use frame_support_procedural::{
+	decl_storage, construct_runtime, transactional, pallet, CloneNoBound, PartialEqNoBound,
+	EqNoBound, DebugNoBound, crate_to_pallet_version,
+};
+
+/// Prelude to be used alongside the `#[frame_support::pallet]` attribute.
+///
+/// `use frame_support::pallet_prelude::*;` brings in scope the types and traits usually needed to
+/// write the pallet items.
+pub mod pallet_prelude {
+	pub use sp_std::marker::PhantomData;
+	pub use crate::{
+		ensure, RuntimeDebug, CloneNoBound, PartialEqNoBound, EqNoBound, DebugNoBound,
+	};
+	pub use crate::codec::{Encode, Decode};
+	pub use crate::type_info::TypeInfo;
+	pub use crate::dispatch::{DispatchResult, DispatchResultWithPostInfo, Parameter, DispatchError};
+	pub use crate::weights::{Weight, DispatchClass, Pays};
+	pub use crate::traits::{Get, Hooks};
+	#[cfg(feature = "std")]
+	pub use crate::traits::GenesisBuild;
+	pub use crate::storage::{
+		StorageValue, StorageMap, StorageDoubleMap, StorageNMap, IterableStorageMap,
+		IterableStorageDoubleMap, IterableStorageNMap, NMapKey,
+	};
+	pub use crate::{
+		Twox256, Twox128, Blake2_256, Blake2_128, Identity, Twox64Concat, Blake2_128Concat,
+	};
+	pub use sp_runtime::traits::{MaybeSerializeDeserialize, Member};
+}
 
 /// Return Err of the expression: `return Err($expression);`.
 ///
@@ -236,10 +266,60 @@ macro_rules! assert_ok {
 	}
 }
 
-/// The void type - it cannot exist.
-// Oh rust, you crack me up...
-#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
-pub enum Void {}
+/// Enter a span, which is valid until the scope is left.
+///
+/// The native runtime uses `sp_tracing::enter_span!`, while the wasm runtime asks the host to enter
+/// the span on its behalf, with the module path of the caller as target.
+#[macro_export]
+#[cfg(feature = "std")]
+macro_rules! enter_span {
+	( $name:expr ) => {
+		$crate::sp_tracing::enter_span!($name);
+	}
+}
+
+/// Enter a span, which is valid until the scope is left.
+///
+/// The native runtime uses `sp_tracing::enter_span!`, while the wasm runtime asks the host to enter
+/// the span on its behalf, with the module path of the caller as target.
+#[macro_export]
+#[cfg(all(not(feature = "std"), feature = "with-tracing"))]
+macro_rules! enter_span {
+	( $name:expr ) => {
+		let __tracing_guard__ = $crate::WasmSpanGuard::enter(module_path!(), $name);
+	}
+}
+
+/// Enter a span, which is valid until the scope is left.
+///
+/// Without the `with-tracing` feature the wasm runtime does not report any spans to the host.
+#[macro_export]
+#[cfg(all(not(feature = "std"), not(feature = "with-tracing")))]
+macro_rules! enter_span {
+	( $name:expr ) => {}
+}
+
+/// Guard of a span entered on the host by the wasm runtime, exiting the span when dropped.
+#[cfg(all(not(feature = "std"), feature = "with-tracing"))]
+#[doc(hidden)]
+pub struct WasmSpanGuard(u64);
+
+#[cfg(all(not(feature = "std"), feature = "with-tracing"))]
+impl WasmSpanGuard {
+	/// Enter a span with the given `target` and `name` on the host.
+	pub fn enter(target: &str, name: &str) -> Self {
+		WasmSpanGuard(sp_io::wasm_tracing::enter_span(target, name))
+	}
+}
+
+#[cfg(all(not(feature = "std"), feature = "with-tracing"))]
+impl Drop for WasmSpanGuard {
+	fn drop(&mut self) {
+		sp_io::wasm_tracing::exit_span(self.0);
+	}
+}
+
+pub use sp_core::Void;
 
 #[cfg(feature = "std")]
 #[doc(hidden)]
@@ -251,7 +331,7 @@ mod tests {
 	use codec::{Codec, EncodeLike};
 	use frame_metadata::{
 		DecodeDifferent, StorageEntryMetadata, StorageMetadata, StorageEntryType,
-		StorageEntryModifier, DefaultByteGetter, StorageHasher,
+		StorageEntryModifier, DefaultByteGetter, StorageHasher, FnEncode, StorageEntryTypeV12,
 	};
 	use sp_std::marker::PhantomData;
 
@@ -290,6 +370,14 @@ mod tests {
 				=> Option<T::BlockNumber>;
 			pub AppendableDM:
 				double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) T::BlockNumber => Vec<u32>;
+			pub DataNM config(test_nmap_config) build(|_| vec![(15u32, 16u32, 17u32, 42u64)]):
+				nmap hasher(twox_64_concat) u32, hasher(blake2_128_concat) u32, hasher(identity) u32
+				=> u64;
+			pub GenericDataNM get(fn generic_data_nm):
+				nmap hasher(blake2_128_concat) T::BlockNumber, hasher(twox_64_concat) u32
+				=> Option<T::BlockNumber>;
+			pub BoundedDataDM bounded:
+				double_map hasher(twox_64_concat) u32, hasher(identity) u8 => Option<u64>;
 		}
 	}
 
@@ -505,6 +593,64 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn n_map_basic_insert_remove_remove_prefix_should_work() {
+		new_test_ext().execute_with(|| {
+			type NMap = DataNM;
+			// initialized during genesis
+			assert_eq!(NMap::get((15u32, 16u32, 17u32)), 42u64);
+
+			// get / insert / take
+			let key = (17u32, 18u32, 19u32);
+			assert_eq!(NMap::get(key), 0u64);
+			NMap::insert(&key, &4u64);
+			assert_eq!(NMap::get(key), 4u64);
+			assert_eq!(NMap::take(key), 4u64);
+			assert_eq!(NMap::get(key), 0u64);
+
+			// mutate
+			NMap::mutate(key, |val| {
+				*val = 15;
+			});
+			assert_eq!(NMap::get(key), 15u64);
+
+			// swap
+			NMap::swap(key, (1u32, 2u32, 3u32));
+			assert_eq!(NMap::get(key), 0u64);
+			assert_eq!(NMap::get((1u32, 2u32, 3u32)), 15u64);
+
+			// remove
+			NMap::remove((1u32, 2u32, 3u32));
+			assert!(!NMap::contains_key((1u32, 2u32, 3u32)));
+
+			// remove prefix
+			let (key1, key2, key3) = key;
+			NMap::insert((key1, key2, key3), 4u64);
+			NMap::insert((key1, key2, key3 + 1), 4u64);
+			NMap::insert((key1, key2 + 1, key3), 4u64);
+			NMap::insert((key1 + 1, key2, key3), 4u64);
+			NMap::remove_prefix((key1, key2));
+			assert_eq!(NMap::get((key1, key2, key3)), 0u64);
+			assert_eq!(NMap::get((key1, key2, key3 + 1)), 0u64);
+			assert_eq!(NMap::get((key1, key2 + 1, key3)), 4u64);
+			NMap::remove_prefix((key1,));
+			assert_eq!(NMap::get((key1, key2 + 1, key3)), 0u64);
+			assert_eq!(NMap::get((key1 + 1, key2, key3)), 4u64);
+		});
+	}
+
+	#[test]
+	fn n_map_generic_getter_should_work() {
+		new_test_ext().execute_with(|| {
+			type NMap = GenericDataNM<Test>;
+
+			assert_eq!(Module::<Test>::generic_data_nm((1u32, 2u32)), None);
+			NMap::insert((1u32, 2u32), 3u32);
+			assert_eq!(Module::<Test>::generic_data_nm((1u32, 2u32)), Some(3));
+			assert_eq!(NMap::iter_prefix((1u32,)).collect::<Vec<_>>(), vec![(2, 3)]);
+		});
+	}
+
 	const EXPECTED_METADATA: StorageMetadata = StorageMetadata {
 		prefix: DecodeDifferent::Encode("Test"),
 		entries: DecodeDifferent::Encode(
@@ -522,6 +668,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructData(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("OptionLinkedMap"),
@@ -536,6 +683,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructOptionLinkedMap(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GenericData"),
@@ -550,6 +698,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGenericData(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GenericData2"),
@@ -564,6 +713,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGenericData2(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("DataDM"),
@@ -579,6 +729,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructDataDM(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GenericDataDM"),
@@ -594,6 +745,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGenericDataDM(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GenericData2DM"),
@@ -609,6 +761,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGenericData2DM(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("AppendableDM"),
@@ -624,6 +777,24 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGenericData2DM(PhantomData::<Test>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Encode("BoundedDataDM"),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::DoubleMap {
+						hasher: StorageHasher::Twox64Concat,
+						key1: DecodeDifferent::Encode("u32"),
+						key2: DecodeDifferent::Encode("u8"),
+						value: DecodeDifferent::Encode("u64"),
+						key2_hasher: StorageHasher::Identity,
+					},
+					default: DecodeDifferent::Encode(
+						DefaultByteGetter(&__GetByteStructBoundedDataDM(PhantomData::<Test>))
+					),
+					documentation: DecodeDifferent::Encode(&[]),
+					// Twox64Concat(u32) ++ Identity(u8), then the u64 value.
+					max_size: DecodeDifferent::Encode(FnEncode(|| Some(8 + 4 + 1 + 8))),
 				},
 			]
 		),
@@ -634,4 +805,31 @@ mod tests {
 		let metadata = Module::<Test>::storage_metadata();
 		pretty_assertions::assert_eq!(EXPECTED_METADATA, metadata);
 	}
+
+	#[test]
+	fn nmap_is_only_described_by_the_v12_metadata() {
+		let mut registry = type_info::Registry::new();
+		let metadata = Module::<Test>::storage_metadata_v12(&mut registry);
+		let u32_id = registry.register_type(&type_info::meta_type::<u32>());
+		let u64_id = registry.register_type(&type_info::meta_type::<u64>());
+
+		let entry = metadata.entries.iter().find(|entry| entry.name == "DataNM").unwrap();
+		assert_eq!(entry.ty, StorageEntryTypeV12::NMap {
+			keys: vec![u32_id; 3],
+			hashers: vec![
+				StorageHasher::Twox64Concat,
+				StorageHasher::Blake2_128Concat,
+				StorageHasher::Identity,
+			],
+			value: u64_id,
+		});
+
+		// The V11 metadata can't express it.
+		let metadata = Module::<Test>::storage_metadata();
+		let entries = match metadata.entries {
+			DecodeDifferent::Encode(entries) => entries,
+			DecodeDifferent::Decoded(_) => unreachable!("the metadata is not decoded"),
+		};
+		assert!(entries.iter().all(|entry| entry.name != DecodeDifferent::Encode("DataNM")));
+	}
 }
@@ -18,11 +18,120 @@ pub use frame_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataLastVersion,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata, StorageMetadata,
 	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher, ModuleErrorMetadata,
-	ExtrinsicMetadata,
+	ExtrinsicMetadata, RuntimeMetadataV11, RuntimeMetadataV12, ModuleMetadataV12,
+	ExtrinsicMetadataV12, SignedExtensionMetadataV12, FunctionMetadataV12,
+	FunctionArgumentMetadataV12, EventMetadataV12, EventArgumentMetadataV12, StorageMetadataV12,
+	StorageEntryMetadataV12, StorageEntryTypeV12, ModuleConstantMetadataV12, ErrorMetadataV12,
+	PalletConstantMetadataV12, PalletStorageEntryMetadataV12,
 };
+use sp_std::prelude::*;
+use crate::type_info::{TypeInfo, TypeDef, Variant, Registry};
+
+/// Returns the variants of the type, empty if the type is not an enum.
+fn variants<T: TypeInfo + 'static>() -> Vec<Variant> {
+	match T::type_info().type_def {
+		TypeDef::Variant(variants) => variants,
+		_ => Vec::new(),
+	}
+}
+
+/// Returns the metadata of the functions of a module, described by the variants of its `Call`.
+///
+/// `arguments` holds the argument names of each function, in the order of the variants.
+#[doc(hidden)]
+pub fn functions_metadata_v12<Call: TypeInfo + 'static>(
+	registry: &mut Registry,
+	arguments: &[&[&'static str]],
+) -> Vec<FunctionMetadataV12> {
+	variants::<Call>().into_iter()
+		.zip(arguments)
+		.map(|(variant, names)| FunctionMetadataV12 {
+			name: variant.name.into(),
+			arguments: variant.fields.into_iter()
+				.zip(names.iter())
+				.map(|(field, name)| FunctionArgumentMetadataV12 {
+					name: (*name).into(),
+					ty: registry.register_type(&field.ty),
+					type_name: field.type_name.into(),
+				})
+				.collect(),
+			documentation: registry.map_strings(&variant.docs),
+		})
+		.collect()
+}
+
+/// Returns the metadata of the events of a module, described by the variants of its `Event`.
+#[doc(hidden)]
+pub fn events_metadata_v12<Event: TypeInfo + 'static>(
+	registry: &mut Registry,
+) -> Vec<EventMetadataV12> {
+	variants::<Event>().into_iter()
+		.map(|variant| EventMetadataV12 {
+			name: variant.name.into(),
+			arguments: variant.fields.into_iter()
+				.map(|field| EventArgumentMetadataV12 {
+					ty: registry.register_type(&field.ty),
+					type_name: field.type_name.into(),
+				})
+				.collect(),
+			documentation: registry.map_strings(&variant.docs),
+		})
+		.collect()
+}
+
+/// The functions building the metadata of the constants and the storage of a module.
+#[doc(hidden)]
+pub struct PalletMetadataFns {
+	pub name: &'static str,
+	pub constants: fn(&mut Registry) -> Vec<ModuleConstantMetadataV12>,
+	pub storage: Option<fn(&mut Registry) -> StorageMetadataV12>,
+}
+
+/// Returns the metadata of the constant `name` of the module `pallet`.
+///
+/// The type registry only holds the types of the constants of that module.
+#[doc(hidden)]
+pub fn pallet_constant_metadata_v12(
+	pallets: &[PalletMetadataFns],
+	pallet: &[u8],
+	name: &[u8],
+) -> Option<PalletConstantMetadataV12> {
+	let pallet = pallets.iter().find(|p| p.name.as_bytes() == pallet)?;
+	let mut registry = Registry::new();
+	let constant = (pallet.constants)(&mut registry)
+		.into_iter()
+		.find(|constant| constant.name.as_bytes() == name)?;
+
+	Some(PalletConstantMetadataV12 { types: registry.into(), constant })
+}
+
+/// Returns the metadata of the storage entry `name` of the module `pallet`.
+///
+/// The type registry only holds the types of the storage entries of that module.
+#[doc(hidden)]
+pub fn pallet_storage_entry_metadata_v12(
+	pallets: &[PalletMetadataFns],
+	pallet: &[u8],
+	name: &[u8],
+) -> Option<PalletStorageEntryMetadataV12> {
+	let storage = pallets.iter().find(|p| p.name.as_bytes() == pallet)?.storage?;
+	let mut registry = Registry::new();
+	let StorageMetadataV12 { prefix, entries } = storage(&mut registry);
+	let entry = entries.into_iter().find(|entry| entry.name.as_bytes() == name)?;
+
+	Some(PalletStorageEntryMetadataV12 { types: registry.into(), prefix, entry })
+}
 
 /// Implements the metadata support for the given runtime and all its modules.
 ///
+/// `metadata` returns the version 11 metadata, which refers to types by their name only, so that
+/// existing clients keep decoding it. `metadata_v12` returns the version 12 metadata, which
+/// describes every type it refers to in a portable type registry. Clients opt into it through
+/// `metadata_at_version`, which returns any of the versions listed by `metadata_versions`.
+///
+/// `constant_metadata` and `storage_entry_metadata` look up a single constant or storage entry
+/// of a module by their names, without building the metadata of the whole runtime.
+///
 /// Example:
 /// ```
 ///# mod module0 {
@@ -57,6 +166,9 @@ pub use frame_metadata::{
 /// ```
 ///
 /// In this example, just `MODULE3` implements the `Storage` trait.
+///
+/// A module can be given an explicit index with `as Module0 = 5`. A module without an explicit
+/// index gets the index following the one of the previous module, starting at `0`.
 #[macro_export]
 macro_rules! impl_runtime_metadata {
 	(
@@ -64,8 +176,44 @@ macro_rules! impl_runtime_metadata {
 			$( $rest:tt )*
 	) => {
 		impl $runtime {
+			#[allow(unused_mut, unused_assignments)]
+			pub fn metadata_v12() -> $crate::metadata::RuntimeMetadataPrefixed {
+				let mut registry = $crate::type_info::Registry::new();
+				let mut next_index = 0u8;
+				let modules = $crate::__runtime_modules_to_metadata_v12!(
+					registry, next_index; $runtime;; $( $rest )*
+				);
+				let call = registry.register_type(&$crate::type_info::meta_type::<
+					<$ext as $crate::sp_runtime::traits::Extrinsic>::Call
+				>());
+				let signed_extensions = <
+						<
+							$ext as $crate::sp_runtime::traits::ExtrinsicMetadata
+						>::SignedExtensions as $crate::sp_runtime::traits::SignedExtension
+					>::metadata()
+						.into_iter()
+						.map(|extension| $crate::metadata::SignedExtensionMetadataV12 {
+							identifier: extension.identifier.into(),
+							ty: registry.register_type(&extension.ty),
+							additional_signed: registry.register_type(
+								&extension.additional_signed,
+							),
+						})
+						.collect();
+
+				$crate::metadata::RuntimeMetadataV12 {
+					types: registry.into(),
+					modules,
+					extrinsic: $crate::metadata::ExtrinsicMetadataV12 {
+						version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
+						call,
+						signed_extensions,
+					},
+				}.into()
+			}
+
 			pub fn metadata() -> $crate::metadata::RuntimeMetadataPrefixed {
-				$crate::metadata::RuntimeMetadataLastVersion {
+				$crate::metadata::RuntimeMetadataV11 {
 						modules: $crate::__runtime_modules_to_metadata!($runtime;; $( $rest )*),
 						extrinsic: $crate::metadata::ExtrinsicMetadata {
 							version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
@@ -80,17 +228,117 @@ macro_rules! impl_runtime_metadata {
 						},
 				}.into()
 			}
+
+			pub fn metadata_at_version(
+				version: u32,
+			) -> Option<$crate::metadata::RuntimeMetadataPrefixed> {
+				match version {
+					11 => Some(Self::metadata()),
+					12 => Some(Self::metadata_v12()),
+					_ => None,
+				}
+			}
+
+			pub fn metadata_versions() -> $crate::sp_std::vec::Vec<u32> {
+				$crate::sp_std::vec![11, 12]
+			}
+
+			pub fn constant_metadata(
+				pallet: &[u8],
+				name: &[u8],
+			) -> Option<$crate::metadata::PalletConstantMetadataV12> {
+				$crate::metadata::pallet_constant_metadata_v12(
+					&$crate::__runtime_modules_to_metadata_fns!($runtime;; $( $rest )*),
+					pallet,
+					name,
+				)
+			}
+
+			pub fn storage_entry_metadata(
+				pallet: &[u8],
+				name: &[u8],
+			) -> Option<$crate::metadata::PalletStorageEntryMetadataV12> {
+				$crate::metadata::pallet_storage_entry_metadata_v12(
+					&$crate::__runtime_modules_to_metadata_fns!($runtime;; $( $rest )*),
+					pallet,
+					name,
+				)
+			}
 		}
 	}
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_fns {
+	(
+		$runtime: ident;
+		$( $fns:expr ),*;
+		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident $( = $_index:literal )?
+			$(with)+ $($kw:ident)*,
+		$( $rest:tt )*
+	) => {
+		$crate::__runtime_modules_to_metadata_fns!(
+			$runtime;
+			$( $fns, )* $crate::metadata::PalletMetadataFns {
+				name: stringify!($name),
+				constants: $mod::$module::<$runtime $(, $mod::$instance )?>
+					::module_constants_metadata_v12,
+				storage: $crate::__runtime_modules_to_metadata_fns_storage!(
+					$mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+			};
+			$( $rest )*
+		)
+	};
+	(
+		$runtime:ident;
+		$( $fns:expr ),*;
+	) => {
+		[ $( $fns ),* ]
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_fns_storage {
+	(
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Storage
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::storage_metadata_v12)
+	};
+	(
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_fns_storage! {
+			$mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		};
+	};
+	(
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __runtime_modules_to_metadata {
 	(
 		$runtime: ident;
 		$( $metadata:expr ),*;
-		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident $(with)+ $($kw:ident)*,
+		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident $( = $_index:literal )?
+			$(with)+ $($kw:ident)*,
 		$( $rest:tt )*
 	) => {
 		$crate::__runtime_modules_to_metadata!(
@@ -238,6 +486,173 @@ macro_rules! __runtime_modules_to_metadata_calls_storage {
 }
 
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v12 {
+	(
+		$registry:ident, $next_index:ident;
+		$runtime: ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident $( = $index:literal )?
+			$(with)+ $($kw:ident)*,
+		$( $rest:tt )*
+	) => {
+		$crate::__runtime_modules_to_metadata_v12!(
+			$registry, $next_index;
+			$runtime;
+			$( $metadata, )* $crate::metadata::ModuleMetadataV12 {
+				name: stringify!($name).into(),
+				index: $crate::__runtime_module_index!($next_index $(, $index)?),
+				storage: $crate::__runtime_modules_to_metadata_v12_calls_storage!(
+					$registry; $mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+				calls: $crate::__runtime_modules_to_metadata_v12_calls_call!(
+					$registry; $mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+				event: $crate::__runtime_modules_to_metadata_v12_calls_event!(
+					$registry; $mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+				constants: $mod::$module::<$runtime $(, $mod::$instance )?>
+					::module_constants_metadata_v12(&mut $registry),
+				errors: <
+					$mod::$module::<$runtime $(, $mod::$instance )?>
+						as $crate::metadata::ModuleErrorMetadata
+				>::metadata().iter().map(Into::into).collect(),
+			};
+			$( $rest )*
+		)
+	};
+	(
+		$registry:ident, $next_index:ident;
+		$runtime:ident;
+		$( $metadata:expr ),*;
+	) => {
+		$crate::sp_std::vec![ $( $metadata ),* ]
+	};
+}
+
+/// Returns the index of a module, either the given one or the one following the previous module.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_module_index {
+	($next_index:ident, $index:literal) => {{
+		$next_index = ($index as u8).wrapping_add(1);
+		$index
+	}};
+	($next_index:ident) => {{
+		let index = $next_index;
+		$next_index = $next_index.wrapping_add(1);
+		index
+	}};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v12_calls_call {
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Call
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::call_functions_v12(&mut $registry))
+	};
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v12_calls_call! {
+			$registry; $mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		};
+	};
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v12_calls_event {
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Event
+		$(with $kws:ident)*
+	) => {
+		Some($crate::paste::expr!{
+			$runtime:: [< __module_events_v12_ $mod $(_ $instance)?>](&mut $registry)
+		})
+	};
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v12_calls_event!(
+			$registry; $mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		);
+	};
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v12_calls_storage {
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Storage
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::storage_metadata_v12(&mut $registry))
+	};
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v12_calls_storage! {
+			$registry; $mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		};
+	};
+	(
+		$registry:ident;
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
 #[cfg(test)]
 // Do not complain about unused `dispatch` and `dispatch_aux`.
 #[allow(dead_code)]
@@ -250,9 +665,10 @@ mod tests {
 	};
 	use codec::{Encode, Decode};
 	use crate::traits::Get;
+	use crate::type_info::{TypeInfo, TypeDefPrimitive, PortableRegistry};
 	use sp_runtime::transaction_validity::TransactionValidityError;
 
-	#[derive(Clone, Eq, Debug, PartialEq, Encode, Decode)]
+	#[derive(Clone, Eq, Debug, PartialEq, Encode, Decode, TypeInfo)]
 	struct TestExtension;
 	impl sp_runtime::traits::SignedExtension for TestExtension {
 		type AccountId = u32;
@@ -265,7 +681,7 @@ mod tests {
 		}
 	}
 
-	#[derive(Clone, Eq, Debug, PartialEq, Encode, Decode)]
+	#[derive(Clone, Eq, Debug, PartialEq, Encode, Decode, TypeInfo)]
 	struct TestExtension2;
 	impl sp_runtime::traits::SignedExtension for TestExtension2 {
 		type AccountId = u32;
@@ -278,12 +694,12 @@ mod tests {
 		}
 	}
 
-	struct TestExtrinsic;
-
-	impl sp_runtime::traits::ExtrinsicMetadata for TestExtrinsic {
-		const VERSION: u8 = 1;
-		type SignedExtensions = (TestExtension, TestExtension2);
-	}
+	type TestExtrinsic = sp_runtime::generic::UncheckedExtrinsic<
+		u32,
+		Call,
+		(),
+		(TestExtension, TestExtension2),
+	>;
 
 	mod system {
 		use super::*;
@@ -445,7 +861,7 @@ mod tests {
 	impl_runtime_metadata!(
 		for TestRuntime with modules where Extrinsic = TestExtrinsic
 			system::Module as System with Event,
-			event_module::Module as Module with Event Call,
+			event_module::Module as Module = 3 with Event Call,
 			event_module2::Module as Module2 with Event Storage Call,
 	);
 
@@ -471,8 +887,8 @@ mod tests {
 	}
 
 	#[test]
-	fn runtime_metadata() {
-		let expected_metadata: RuntimeMetadataLastVersion = RuntimeMetadataLastVersion {
+	fn runtime_metadata_v11() {
+		let expected_metadata: RuntimeMetadataV11 = RuntimeMetadataV11 {
 			modules: DecodeDifferent::Encode(&[
 				ModuleMetadata {
 					name: DecodeDifferent::Encode("System"),
@@ -571,6 +987,7 @@ mod tests {
 											)
 										),
 										documentation: DecodeDifferent::Encode(&[]),
+										max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 									}
 								]
 							)
@@ -591,7 +1008,7 @@ mod tests {
 				},
 			]),
 			extrinsic: ExtrinsicMetadata {
-				version: 1,
+				version: 4,
 				signed_extensions: vec![
 					DecodeDifferent::Encode("testextension"),
 					DecodeDifferent::Encode("testextension2"),
@@ -605,4 +1022,100 @@ mod tests {
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
+
+	fn primitive(types: &PortableRegistry, id: u32) -> TypeDefPrimitive {
+		match types.resolve(id).map(|ty| &ty.type_def) {
+			Some(TypeDef::Primitive(primitive)) => primitive.clone(),
+			ty => panic!("{:?} is not a primitive", ty),
+		}
+	}
+
+	#[test]
+	fn runtime_metadata_v12() {
+		let metadata_encoded = TestRuntime::metadata_v12().encode();
+		let metadata_decoded = RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]);
+		let metadata = match metadata_decoded.unwrap().1 {
+			RuntimeMetadata::V12(metadata) => metadata,
+			_ => panic!("`metadata_v12` returns V12"),
+		};
+		let types = &metadata.types;
+
+		let names: Vec<_> = metadata.modules.iter().map(|module| module.name.as_str()).collect();
+		assert_eq!(names, vec!["System", "Module", "Module2"]);
+		let indices: Vec<_> = metadata.modules.iter().map(|module| module.index).collect();
+		assert_eq!(indices, vec![0, 3, 4]);
+
+		let system = &metadata.modules[0];
+		assert_eq!(system.constants.len(), 3);
+		assert_eq!(system.constants[0].name, "BlockNumber");
+		assert_eq!(primitive(types, system.constants[0].ty), TypeDefPrimitive::U32);
+		assert_eq!(system.constants[0].value, 100u32.encode());
+		assert_eq!(system.constants[0].documentation, vec![" Hi, I am a comment."]);
+		assert_eq!(primitive(types, system.constants[2].ty), TypeDefPrimitive::U64);
+		assert_eq!(system.event.as_ref().unwrap()[0].name, "SystemEvent");
+
+		let module = &metadata.modules[1];
+		let calls = module.calls.as_ref().unwrap();
+		assert_eq!(calls.len(), 1);
+		assert_eq!(calls[0].name, "aux_0");
+		assert!(calls[0].arguments.is_empty());
+		let event = &module.event.as_ref().unwrap()[0];
+		assert_eq!(event.name, "TestEvent");
+		assert_eq!(event.arguments[0].type_name, "Balance");
+		assert_eq!(primitive(types, event.arguments[0].ty), TypeDefPrimitive::U32);
+		assert_eq!(event.documentation, vec![" Hi, I am a comment."]);
+		let errors: Vec<_> = module.errors.iter().map(|error| error.name.as_str()).collect();
+		assert_eq!(errors, vec!["UserInputError", "BadThingHappened"]);
+
+		let storage = metadata.modules[2].storage.as_ref().unwrap();
+		assert_eq!(storage.prefix, "TestStorage");
+		assert_eq!(storage.entries[0].name, "StorageMethod");
+		assert_eq!(storage.entries[0].modifier, StorageEntryModifier::Optional);
+		assert_eq!(storage.entries[0].default, Option::<u32>::None.encode());
+		match storage.entries[0].ty {
+			StorageEntryTypeV12::Plain(ty) =>
+				assert_eq!(primitive(types, ty), TypeDefPrimitive::U32),
+			ref ty => panic!("unexpected storage type {:?}", ty),
+		}
+
+		assert_eq!(metadata.extrinsic.version, 4);
+		let call = types.resolve(metadata.extrinsic.call).unwrap();
+		assert_eq!(call.path.segments.last().unwrap(), "Call");
+		match &call.type_def {
+			TypeDef::Variant(variants) => {
+				let names: Vec<_> = variants.iter().map(|variant| variant.name.as_str()).collect();
+				assert_eq!(names, vec!["EventModule", "EventModule2"]);
+			},
+			ty => panic!("unexpected call type {:?}", ty),
+		}
+		let extensions = &metadata.extrinsic.signed_extensions;
+		assert_eq!(extensions[0].identifier, "testextension");
+		assert_eq!(extensions[1].identifier, "testextension2");
+		let extension = types.resolve(extensions[0].ty).unwrap();
+		assert_eq!(extension.path.segments.last().unwrap(), "TestExtension");
+		assert_eq!(primitive(types, extensions[0].additional_signed), TypeDefPrimitive::U32);
+	}
+	#[test]
+	fn metadata_versions_and_lookups() {
+		assert_eq!(TestRuntime::metadata_versions(), vec![11, 12]);
+		assert_eq!(TestRuntime::metadata_at_version(11), Some(TestRuntime::metadata()));
+		assert_eq!(TestRuntime::metadata_at_version(12), Some(TestRuntime::metadata_v12()));
+		assert!(TestRuntime::metadata_at_version(10).is_none());
+
+		let constant = TestRuntime::constant_metadata(b"System", b"ASSOCIATED_CONST").unwrap();
+		assert_eq!(constant.constant.value, 500u64.encode());
+		assert_eq!(primitive(&constant.types, constant.constant.ty), TypeDefPrimitive::U64);
+		assert!(TestRuntime::constant_metadata(b"System", b"Unknown").is_none());
+		assert!(TestRuntime::constant_metadata(b"Unknown", b"BlockNumber").is_none());
+
+		let storage = TestRuntime::storage_entry_metadata(b"Module2", b"StorageMethod").unwrap();
+		assert_eq!(storage.prefix, "TestStorage");
+		assert_eq!(storage.entry.modifier, StorageEntryModifier::Optional);
+		match storage.entry.ty {
+			StorageEntryTypeV12::Plain(ty) =>
+				assert_eq!(primitive(&storage.types, ty), TypeDefPrimitive::U32),
+			ref ty => panic!("unexpected storage type {:?}", ty),
+		}
+		assert!(TestRuntime::storage_entry_metadata(b"System", b"StorageMethod").is_none());
+	}
 }
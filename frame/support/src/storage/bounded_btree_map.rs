@@ -0,0 +1,229 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A map with a length bound, suitable to be kept in storage.
+
+use sp_std::{
+	borrow::Borrow, collections::btree_map::BTreeMap, convert::TryFrom, fmt, marker::PhantomData,
+	ops::Deref, vec::Vec,
+};
+use codec::{Encode, EncodeLike, Decode, Input, Output, Compact, CompactLen};
+use crate::{traits::{Get, MaxEncodedLen}, storage::{StorageDecodeLength, private}};
+
+/// A `BTreeMap` holding at most `S::get()` entries.
+///
+/// It encodes exactly like a `BTreeMap<K, V>`, but decoding fails if the encoded length is larger
+/// than the bound, before any entry is decoded. All mutating operations make sure the bound is
+/// respected.
+pub struct BoundedBTreeMap<K, V, S>(BTreeMap<K, V>, PhantomData<S>);
+
+impl<K: Ord, V, S: Get<u32>> BoundedBTreeMap<K, V, S> {
+	/// The maximum number of entries this map can hold.
+	pub fn bound() -> usize {
+		S::get() as usize
+	}
+
+	/// Consume self and return the inner `BTreeMap`.
+	pub fn into_inner(self) -> BTreeMap<K, V> {
+		self.0
+	}
+
+	/// Insert `value` at `key`, returning the previous value if there was one.
+	///
+	/// Replacing the value of an existing key always succeeds. Inserting a new key returns
+	/// `Err((key, value))` if the map is already full.
+	pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+		if self.0.len() < Self::bound() || self.0.contains_key(&key) {
+			Ok(self.0.insert(key, value))
+		} else {
+			Err((key, value))
+		}
+	}
+
+	/// Remove `key` from the map, returning its value if it was present.
+	pub fn remove<Q>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q>, Q: Ord + ?Sized {
+		self.0.remove(key)
+	}
+
+	/// Return a mutable reference to the value at `key`, if any.
+	pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+	where
+		K: Borrow<Q>,
+		Q: Ord + ?Sized,
+	{
+		self.0.get_mut(key)
+	}
+}
+
+impl<K: Ord, V, S> Default for BoundedBTreeMap<K, V, S> {
+	fn default() -> Self {
+		BoundedBTreeMap(BTreeMap::new(), PhantomData)
+	}
+}
+
+impl<K: Clone, V: Clone, S> Clone for BoundedBTreeMap<K, V, S> {
+	fn clone(&self) -> Self {
+		BoundedBTreeMap(self.0.clone(), PhantomData)
+	}
+}
+
+impl<K: PartialEq, V: PartialEq, S> PartialEq for BoundedBTreeMap<K, V, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<K: Eq, V: Eq, S> Eq for BoundedBTreeMap<K, V, S> {}
+
+impl<K: fmt::Debug, V: fmt::Debug, S: Get<u32>> fmt::Debug for BoundedBTreeMap<K, V, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("BoundedBTreeMap").field(&self.0).field(&S::get()).finish()
+	}
+}
+
+impl<K: Ord, V, S: Get<u32>> TryFrom<BTreeMap<K, V>> for BoundedBTreeMap<K, V, S> {
+	type Error = BTreeMap<K, V>;
+
+	fn try_from(map: BTreeMap<K, V>) -> Result<Self, Self::Error> {
+		if map.len() <= Self::bound() {
+			Ok(BoundedBTreeMap(map, PhantomData))
+		} else {
+			Err(map)
+		}
+	}
+}
+
+impl<K, V, S> From<BoundedBTreeMap<K, V, S>> for BTreeMap<K, V> {
+	fn from(map: BoundedBTreeMap<K, V, S>) -> Self {
+		map.0
+	}
+}
+
+impl<K, V, S> Deref for BoundedBTreeMap<K, V, S> {
+	type Target = BTreeMap<K, V>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<K, V, S> IntoIterator for BoundedBTreeMap<K, V, S> {
+	type Item = (K, V);
+	type IntoIter = sp_std::collections::btree_map::IntoIter<K, V>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+impl<K: Encode, V: Encode, S> Encode for BoundedBTreeMap<K, V, S> {
+	fn size_hint(&self) -> usize {
+		self.0.size_hint()
+	}
+
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.0.encode_to(dest)
+	}
+}
+
+impl<K: Encode, V: Encode, S> EncodeLike for BoundedBTreeMap<K, V, S> {}
+impl<K: Encode, V: Encode, S> EncodeLike<BTreeMap<K, V>> for BoundedBTreeMap<K, V, S> {}
+
+impl<K: Decode + Ord, V: Decode, S: Get<u32>> Decode for BoundedBTreeMap<K, V, S> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let len = <Compact<u32>>::decode(input)?.0;
+		if len > S::get() {
+			return Err("BoundedBTreeMap exceeds its limit".into());
+		}
+
+		let mut inner = BTreeMap::new();
+		for _ in 0..len {
+			let (key, value) = <(K, V)>::decode(input)?;
+			inner.insert(key, value);
+		}
+		Ok(BoundedBTreeMap(inner, PhantomData))
+	}
+}
+
+impl<K, V, S> MaxEncodedLen for BoundedBTreeMap<K, V, S>
+where
+	K: MaxEncodedLen + Ord,
+	V: MaxEncodedLen,
+	S: Get<u32>,
+{
+	fn max_encoded_len() -> usize {
+		// The length prefix is encoded as a `Compact<u32>`, followed by the entries.
+		let entry_len = K::max_encoded_len().saturating_add(V::max_encoded_len());
+		Compact::<u32>::compact_len(&S::get())
+			.saturating_add(Self::bound().saturating_mul(entry_len))
+	}
+}
+
+impl<K: Encode, V: Encode, S> codec::DecodeLength for BoundedBTreeMap<K, V, S> {
+	fn len(self_encoded: &[u8]) -> Result<usize, codec::Error> {
+		// `BTreeMap` is encoded like a `Vec` of its entries, so its length prefix is the same.
+		<Vec<(K, V)> as codec::DecodeLength>::len(self_encoded)
+	}
+}
+
+impl<K: Encode, V: Encode, S> private::Sealed for BoundedBTreeMap<K, V, S> {}
+impl<K: Encode, V: Encode, S> StorageDecodeLength for BoundedBTreeMap<K, V, S> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parameter_types;
+
+	parameter_types! {
+		pub const Three: u32 = 3;
+	}
+
+	fn map_from_keys(keys: &[u8]) -> BTreeMap<u8, ()> {
+		keys.iter().map(|k| (*k, ())).collect()
+	}
+
+	#[test]
+	fn try_insert_respects_the_bound() {
+		let mut map = BoundedBTreeMap::<u8, (), Three>::try_from(map_from_keys(&[1, 2])).unwrap();
+		assert_eq!(map.try_insert(3, ()), Ok(None));
+		assert_eq!(map.try_insert(4, ()), Err((4, ())));
+		assert_eq!(map.try_insert(3, ()), Ok(Some(())));
+		assert_eq!(map.remove(&1), Some(()));
+		assert_eq!(map.try_insert(4, ()), Ok(None));
+		assert_eq!(map.into_inner(), map_from_keys(&[2, 3, 4]));
+	}
+
+	#[test]
+	fn decode_fails_past_the_bound() {
+		let encoded = map_from_keys(&[1, 2, 3, 4]).encode();
+		assert!(BoundedBTreeMap::<u8, (), Three>::decode(&mut &encoded[..]).is_err());
+
+		let encoded = map_from_keys(&[1, 2, 3]).encode();
+		let decoded = BoundedBTreeMap::<u8, (), Three>::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded.into_inner(), map_from_keys(&[1, 2, 3]));
+	}
+
+	#[test]
+	fn max_encoded_len_is_the_worst_case() {
+		let full: BTreeMap<u32, u64> = (0..3).map(|k| (k, u64::max_value())).collect();
+		let full = BoundedBTreeMap::<u32, u64, Three>::try_from(full).unwrap();
+		assert_eq!(BoundedBTreeMap::<u32, u64, Three>::max_encoded_len(), full.encode().len());
+		assert_eq!(
+			<BoundedBTreeMap<u32, u64, Three> as codec::DecodeLength>::len(&full.encode()).ok(),
+			Some(3),
+		);
+	}
+}
@@ -0,0 +1,299 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A vector with a length bound, suitable to be kept in storage.
+
+use sp_std::{prelude::*, convert::TryFrom, fmt, marker::PhantomData, ops::Deref};
+use codec::{Encode, EncodeLike, Decode, Input, Output, Compact, CompactLen};
+use crate::{traits::{Get, MaxEncodedLen}, storage::{StorageDecodeLength, private}};
+
+/// A vector holding at most `S::get()` elements.
+///
+/// It encodes exactly like a `Vec<T>`, but decoding fails if the encoded length is larger than
+/// the bound, before any element is decoded. All mutating operations make sure the bound is
+/// respected, so a `BoundedVec` read from storage can never grow past it.
+pub struct BoundedVec<T, S>(Vec<T>, PhantomData<S>);
+
+impl<T, S: Get<u32>> BoundedVec<T, S> {
+	/// The maximum number of elements this vector can hold.
+	pub fn bound() -> usize {
+		S::get() as usize
+	}
+
+	/// Consume self and return the inner `Vec<T>`.
+	pub fn into_inner(self) -> Vec<T> {
+		self.0
+	}
+
+	/// Push `element` to the end of the vector.
+	///
+	/// Returns `Err(element)` if the vector is already full.
+	pub fn try_push(&mut self, element: T) -> Result<(), T> {
+		if self.0.len() < Self::bound() {
+			self.0.push(element);
+			Ok(())
+		} else {
+			Err(element)
+		}
+	}
+
+	/// Insert `element` at position `index`, shifting all elements after it to the right.
+	///
+	/// Returns `Err(element)` if the vector is already full.
+	///
+	/// # Panics
+	///
+	/// Panics if `index > len`.
+	pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), T> {
+		if self.0.len() < Self::bound() {
+			self.0.insert(index, element);
+			Ok(())
+		} else {
+			Err(element)
+		}
+	}
+
+	/// Remove and return the element at position `index`, shifting all elements after it to the
+	/// left.
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds.
+	pub fn remove(&mut self, index: usize) -> T {
+		self.0.remove(index)
+	}
+
+	/// Remove and return the element at position `index`, replacing it with the last element.
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds.
+	pub fn swap_remove(&mut self, index: usize) -> T {
+		self.0.swap_remove(index)
+	}
+
+	/// Retain only the elements specified by the predicate `f`.
+	pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+		self.0.retain(f)
+	}
+
+	/// Shorten the vector, keeping the first `len` elements.
+	pub fn truncate(&mut self, len: usize) {
+		self.0.truncate(len)
+	}
+
+	/// Return a mutable reference to the element at `index`, if any.
+	pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+		self.0.get_mut(index)
+	}
+}
+
+impl<T, S> Default for BoundedVec<T, S> {
+	fn default() -> Self {
+		BoundedVec(Vec::new(), PhantomData)
+	}
+}
+
+impl<T: Clone, S> Clone for BoundedVec<T, S> {
+	fn clone(&self) -> Self {
+		BoundedVec(self.0.clone(), PhantomData)
+	}
+}
+
+impl<T: PartialEq, S> PartialEq for BoundedVec<T, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<T: Eq, S> Eq for BoundedVec<T, S> {}
+
+impl<T: fmt::Debug, S: Get<u32>> fmt::Debug for BoundedVec<T, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("BoundedVec").field(&self.0).field(&S::get()).finish()
+	}
+}
+
+impl<T, S: Get<u32>> TryFrom<Vec<T>> for BoundedVec<T, S> {
+	type Error = Vec<T>;
+
+	fn try_from(v: Vec<T>) -> Result<Self, Self::Error> {
+		if v.len() <= Self::bound() {
+			Ok(BoundedVec(v, PhantomData))
+		} else {
+			Err(v)
+		}
+	}
+}
+
+impl<T, S> From<BoundedVec<T, S>> for Vec<T> {
+	fn from(v: BoundedVec<T, S>) -> Self {
+		v.0
+	}
+}
+
+impl<T, S> Deref for BoundedVec<T, S> {
+	type Target = Vec<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, S> AsRef<[T]> for BoundedVec<T, S> {
+	fn as_ref(&self) -> &[T] {
+		&self.0
+	}
+}
+
+impl<T, S> IntoIterator for BoundedVec<T, S> {
+	type Item = T;
+	type IntoIter = sp_std::vec::IntoIter<T>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+impl<T: Encode, S> Encode for BoundedVec<T, S> {
+	fn size_hint(&self) -> usize {
+		self.0.size_hint()
+	}
+
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.0.encode_to(dest)
+	}
+}
+
+impl<T: Encode, S> EncodeLike for BoundedVec<T, S> {}
+impl<T: Encode, S> EncodeLike<Vec<T>> for BoundedVec<T, S> {}
+
+impl<T: Decode, S: Get<u32>> Decode for BoundedVec<T, S> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let len = <Compact<u32>>::decode(input)?.0;
+		if len > S::get() {
+			return Err("BoundedVec exceeds its limit".into());
+		}
+
+		let mut inner = Vec::with_capacity(len as usize);
+		for _ in 0..len {
+			inner.push(T::decode(input)?);
+		}
+		Ok(BoundedVec(inner, PhantomData))
+	}
+}
+
+impl<T: MaxEncodedLen, S: Get<u32>> MaxEncodedLen for BoundedVec<T, S> {
+	fn max_encoded_len() -> usize {
+		// The length prefix is encoded as a `Compact<u32>`, followed by the elements.
+		Compact::<u32>::compact_len(&S::get())
+			.saturating_add(Self::bound().saturating_mul(T::max_encoded_len()))
+	}
+}
+
+impl<T: Encode, S> codec::DecodeLength for BoundedVec<T, S> {
+	fn len(self_encoded: &[u8]) -> Result<usize, codec::Error> {
+		<Vec<T> as codec::DecodeLength>::len(self_encoded)
+	}
+}
+
+impl<T: Encode, S> private::Sealed for BoundedVec<T, S> {}
+impl<T: Encode, S> StorageDecodeLength for BoundedVec<T, S> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_io::TestExternalities;
+	use crate::{parameter_types, storage::{StorageValue, StorageMap}};
+
+	parameter_types! {
+		pub const Seven: u32 = 7;
+		pub const Four: u32 = 4;
+	}
+
+	crate::decl_storage! {
+		trait Store for Module<T: Trait> as Test {
+			pub Foo: BoundedVec<u32, Seven>;
+			pub FooMap: map hasher(blake2_128_concat) u32 => BoundedVec<u32, Seven>;
+		}
+	}
+
+	struct Runtime;
+	pub trait Trait {
+		type Origin;
+		type BlockNumber;
+	}
+
+	impl Trait for Runtime {
+		type Origin = u32;
+		type BlockNumber = u32;
+	}
+
+	crate::decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	#[test]
+	fn try_push_respects_the_bound() {
+		let mut v: BoundedVec<u32, Four> = TryFrom::try_from(vec![1, 2, 3]).unwrap();
+		assert_eq!(v.try_push(4), Ok(()));
+		assert_eq!(v.try_push(5), Err(5));
+		assert_eq!(v.try_insert(0, 0), Err(0));
+		assert_eq!(v.remove(0), 1);
+		assert_eq!(v.try_insert(0, 0), Ok(()));
+		assert_eq!(*v, vec![0, 2, 3, 4]);
+	}
+
+	#[test]
+	fn try_from_rejects_too_long_vec() {
+		assert_eq!(
+			BoundedVec::<u32, Four>::try_from(vec![1, 2, 3, 4, 5]),
+			Err(vec![1, 2, 3, 4, 5]),
+		);
+	}
+
+	#[test]
+	fn decode_fails_past_the_bound() {
+		let encoded = vec![1u32, 2, 3, 4, 5].encode();
+		assert!(BoundedVec::<u32, Four>::decode(&mut &encoded[..]).is_err());
+
+		let encoded = vec![1u32, 2, 3, 4].encode();
+		let decoded = BoundedVec::<u32, Four>::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded.into_inner(), vec![1, 2, 3, 4]);
+	}
+
+	#[test]
+	fn max_encoded_len_is_the_worst_case() {
+		let full: BoundedVec<u64, Seven> = TryFrom::try_from(vec![u64::max_value(); 7]).unwrap();
+		assert_eq!(BoundedVec::<u64, Seven>::max_encoded_len(), full.encode().len());
+	}
+
+	#[test]
+	fn works_in_storage() {
+		TestExternalities::default().execute_with(|| {
+			let bounded: BoundedVec<u32, Seven> = TryFrom::try_from(vec![1, 2, 3]).unwrap();
+			Foo::put(bounded.clone());
+			assert_eq!(Foo::decode_len(), Some(3));
+
+			assert_eq!(Foo::try_mutate(|v| v.try_push(4)), Ok(()));
+			assert_eq!(*Foo::get(), vec![1, 2, 3, 4]);
+
+			FooMap::insert(1, bounded);
+			assert_eq!(FooMap::decode_len(1), Some(3));
+			assert_eq!(*FooMap::get(1), vec![1, 2, 3]);
+		});
+	}
+}
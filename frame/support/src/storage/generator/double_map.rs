@@ -17,7 +17,7 @@
 use sp_std::prelude::*;
 use sp_std::borrow::Borrow;
 use codec::{FullCodec, FullEncode, Decode, Encode, EncodeLike};
-use crate::{storage::{self, unhashed, StorageAppend}, Never};
+use crate::{storage::{self, unhashed, StorageAppend, KeyPrefixIterator, BoundedProgress}, Never};
 use crate::hash::{StorageHasher, Twox128, ReversibleStorageHasher};
 
 /// Generator for `StorageDoubleMap` used by `decl_storage`.
@@ -295,13 +295,22 @@ impl<K1, K2, V, G> storage::StorageDoubleMap<K1, K2, V> for G where
 
 /// Iterate over a prefix and decode raw_key and raw_value into `T`.
 pub struct MapIterator<T> {
-	prefix: Vec<u8>,
-	previous_key: Vec<u8>,
+	pub(super) prefix: Vec<u8>,
+	pub(super) previous_key: Vec<u8>,
 	/// If true then value are removed while iterating
-	drain: bool,
+	pub(super) drain: bool,
 	/// Function that take `(raw_key_without_prefix, raw_value)` and decode `T`.
 	/// `raw_key_without_prefix` is the raw storage key without the prefix iterated on.
-	closure: fn(&[u8], &[u8]) -> Result<T, codec::Error>,
+	pub(super) closure: fn(&[u8], &[u8]) -> Result<T, codec::Error>,
+}
+
+impl<T> MapIterator<T> {
+	/// The raw key of the last element returned, or the key the iteration started after.
+	///
+	/// Can be used as a cursor to resume the iteration later on.
+	pub fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
 }
 
 impl<T> Iterator for MapIterator<T> {
@@ -352,6 +361,7 @@ impl<
 {
 	type PrefixIterator = MapIterator<(K2, V)>;
 	type Iterator = MapIterator<(K1, K2, V)>;
+	type KeyIterator = KeyPrefixIterator<(K1, K2)>;
 
 	fn iter_prefix(k1: impl EncodeLike<K1>) -> Self::PrefixIterator {
 		let prefix = G::storage_double_map_final_key1(k1);
@@ -366,6 +376,15 @@ impl<
 		}
 	}
 
+	fn iter_prefix_from(
+		k1: impl EncodeLike<K1>,
+		starting_raw_key: Vec<u8>,
+	) -> Self::PrefixIterator {
+		let mut iterator = Self::iter_prefix(k1);
+		iterator.previous_key = starting_raw_key;
+		iterator
+	}
+
 	fn drain_prefix(k1: impl EncodeLike<K1>) -> Self::PrefixIterator {
 		let mut iterator = Self::iter_prefix(k1);
 		iterator.drain = true;
@@ -373,21 +392,35 @@ impl<
 	}
 
 	fn iter() -> Self::Iterator {
-		let prefix = G::prefix_hash();
+		Self::iter_from(G::prefix_hash())
+	}
+
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator {
 		Self::Iterator {
-			prefix: prefix.clone(),
-			previous_key: prefix,
+			prefix: G::prefix_hash(),
+			previous_key: starting_raw_key,
 			drain: false,
 			closure: |raw_key_without_prefix, mut raw_value| {
-				let mut k1_k2_material = G::Hasher1::reverse(raw_key_without_prefix);
-				let k1 = K1::decode(&mut k1_k2_material)?;
-				let mut k2_material = G::Hasher2::reverse(k1_k2_material);
-				let k2 = K2::decode(&mut k2_material)?;
+				let (k1, k2) = decode_keys::<K1, K2, G::Hasher1, G::Hasher2>(
+					raw_key_without_prefix,
+				)?;
 				Ok((k1, k2, V::decode(&mut raw_value)?))
 			},
 		}
 	}
 
+	fn iter_keys() -> Self::KeyIterator {
+		Self::iter_keys_from(G::prefix_hash())
+	}
+
+	fn iter_keys_from(starting_raw_key: Vec<u8>) -> Self::KeyIterator {
+		KeyPrefixIterator {
+			prefix: G::prefix_hash(),
+			previous_key: starting_raw_key,
+			closure: decode_keys::<K1, K2, G::Hasher1, G::Hasher2>,
+		}
+	}
+
 	fn drain() -> Self::Iterator {
 		let mut iterator = Self::iter();
 		iterator.drain = true;
@@ -414,6 +447,56 @@ impl<
 			}
 		}
 	}
+
+	fn translate_bounded<O: Decode, F: Fn(K1, K2, O) -> Option<V>>(
+		limit: u32,
+		cursor: Option<Vec<u8>>,
+		f: F,
+	) -> BoundedProgress {
+		let prefix = G::prefix_hash();
+		let mut previous_key = cursor.unwrap_or_else(|| prefix.clone());
+		let mut processed = 0;
+		while processed < limit {
+			match sp_io::storage::next_key(&previous_key).filter(|n| n.starts_with(&prefix)) {
+				Some(next) => {
+					previous_key = next;
+					processed += 1;
+					let maybe_value = unhashed::get::<O>(&previous_key);
+					match maybe_value {
+						Some(value) => match decode_keys::<K1, K2, G::Hasher1, G::Hasher2>(
+							&previous_key[prefix.len()..],
+						) {
+							Ok((k1, k2)) => match f(k1, k2, value) {
+								Some(new) => unhashed::put::<V>(&previous_key, &new),
+								None => unhashed::kill(&previous_key),
+							},
+							Err(_) => continue,
+						},
+						None => continue,
+					}
+				}
+				None => return BoundedProgress { processed, cursor: None },
+			}
+		}
+
+		BoundedProgress { processed, cursor: Some(previous_key) }
+	}
+}
+
+/// Decode both keys of a double map from a raw key stripped of the map prefix.
+fn decode_keys<K1, K2, Hasher1, Hasher2>(
+	raw_key_without_prefix: &[u8],
+) -> Result<(K1, K2), codec::Error> where
+	K1: Decode,
+	K2: Decode,
+	Hasher1: ReversibleStorageHasher,
+	Hasher2: ReversibleStorageHasher,
+{
+	let mut k1_k2_material = Hasher1::reverse(raw_key_without_prefix);
+	let k1 = K1::decode(&mut k1_k2_material)?;
+	let mut k2_material = Hasher2::reverse(k1_k2_material);
+	let k2 = K2::decode(&mut k2_material)?;
+	Ok((k1, k2))
 }
 
 /// Test iterators for StorageDoubleMap
@@ -421,6 +504,7 @@ impl<
 #[allow(dead_code)]
 mod test_iterators {
 	use codec::{Encode, Decode};
+	use crate::type_info::TypeInfo;
 	use crate::storage::{generator::StorageDoubleMap, IterableStorageDoubleMap, unhashed};
 
 	pub trait Trait {
@@ -432,7 +516,7 @@ mod test_iterators {
 		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
 	}
 
-	#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+	#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 	struct NoDef(u32);
 
 	crate::decl_storage! {
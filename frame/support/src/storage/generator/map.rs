@@ -19,7 +19,7 @@ use sp_std::prelude::*;
 use sp_std::borrow::Borrow;
 use codec::{FullCodec, FullEncode, Decode, Encode, EncodeLike};
 use crate::{
-	storage::{self, unhashed, StorageAppend},
+	storage::{self, unhashed, StorageAppend, KeyPrefixIterator, BoundedProgress},
 	Never, hash::{StorageHasher, Twox128, ReversibleStorageHasher},
 };
 
@@ -97,6 +97,15 @@ pub struct StorageMapIterator<K, V, Hasher> {
 	_phantom: ::sp_std::marker::PhantomData<(K, V, Hasher)>,
 }
 
+impl<K, V, Hasher> StorageMapIterator<K, V, Hasher> {
+	/// The raw key of the last element returned, or the key the iteration started after.
+	///
+	/// Can be used as a cursor to resume the iteration later on.
+	pub fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
+}
+
 impl<
 	K: Decode + Sized,
 	V: Decode + Sized,
@@ -139,18 +148,40 @@ impl<
 	G::Hasher: ReversibleStorageHasher
 {
 	type Iterator = StorageMapIterator<K, V, G::Hasher>;
+	type KeyIterator = KeyPrefixIterator<K>;
 
 	/// Enumerate all elements in the map.
 	fn iter() -> Self::Iterator {
-		let prefix = G::prefix_hash();
+		Self::iter_from(G::prefix_hash())
+	}
+
+	/// Enumerate all elements in the map after a given key.
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator {
 		Self::Iterator {
-			prefix: prefix.clone(),
-			previous_key: prefix,
+			prefix: G::prefix_hash(),
+			previous_key: starting_raw_key,
 			drain: false,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Enumerate all keys in the map.
+	fn iter_keys() -> Self::KeyIterator {
+		Self::iter_keys_from(G::prefix_hash())
+	}
+
+	/// Enumerate all keys in the map after a given key.
+	fn iter_keys_from(starting_raw_key: Vec<u8>) -> Self::KeyIterator {
+		KeyPrefixIterator {
+			prefix: G::prefix_hash(),
+			previous_key: starting_raw_key,
+			closure: |raw_key_without_prefix| {
+				let mut key_material = G::Hasher::reverse(raw_key_without_prefix);
+				K::decode(&mut key_material)
+			},
+		}
+	}
+
 	/// Enumerate all elements in the map.
 	fn drain() -> Self::Iterator {
 		let prefix = G::prefix_hash();
@@ -163,12 +194,22 @@ impl<
 	}
 
 	fn translate<O: Decode, F: Fn(K, O) -> Option<V>>(f: F) {
+		Self::translate_bounded(u32::max_value(), None, f);
+	}
+
+	fn translate_bounded<O: Decode, F: Fn(K, O) -> Option<V>>(
+		limit: u32,
+		cursor: Option<Vec<u8>>,
+		f: F,
+	) -> BoundedProgress {
 		let prefix = G::prefix_hash();
-		let mut previous_key = prefix.clone();
-		loop {
+		let mut previous_key = cursor.unwrap_or_else(|| prefix.clone());
+		let mut processed = 0;
+		while processed < limit {
 			match sp_io::storage::next_key(&previous_key).filter(|n| n.starts_with(&prefix)) {
 				Some(next) => {
 					previous_key = next;
+					processed += 1;
 					let maybe_value = unhashed::get::<O>(&previous_key);
 					match maybe_value {
 						Some(value) => {
@@ -184,9 +225,11 @@ impl<
 						None => continue,
 					}
 				}
-				None => return,
+				None => return BoundedProgress { processed, cursor: None },
 			}
 		}
+
+		BoundedProgress { processed, cursor: Some(previous_key) }
 	}
 }
 
@@ -25,10 +25,12 @@
 
 mod map;
 mod double_map;
+mod nmap;
 mod value;
 
 pub use map::StorageMap;
-pub use double_map::StorageDoubleMap;
+pub use double_map::{StorageDoubleMap, MapIterator};
+pub use nmap::StorageNMap;
 pub use value::StorageValue;
 
 #[cfg(test)]
@@ -36,7 +38,10 @@ pub use value::StorageValue;
 mod tests {
 	use sp_io::TestExternalities;
 	use codec::Encode;
-	use crate::storage::{unhashed, generator::StorageValue, IterableStorageMap};
+	use crate::storage::{
+		unhashed, generator::StorageValue, IterableStorageMap, IterableStorageDoubleMap,
+		BoundedProgress,
+	};
 	use crate::{assert_noop, assert_ok};
 
 	struct Runtime {}
@@ -105,6 +110,131 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn map_iteration_can_be_resumed() {
+		let t = GenesisConfig::default().build_storage().unwrap();
+		TestExternalities::new(t).execute_with(|| {
+			for i in 0u32..10u32 {
+				NumberMap::insert(i, i as u64);
+			}
+
+			let mut iter = NumberMap::iter();
+			assert_eq!(
+				iter.by_ref().take(4).collect::<Vec<_>>(),
+				vec![(0, 0), (1, 1), (2, 2), (3, 3)],
+			);
+			let cursor = iter.last_raw_key().to_vec();
+			assert_eq!(cursor, NumberMap::hashed_key_for(3u32));
+
+			assert_eq!(
+				NumberMap::iter_from(cursor.clone()).collect::<Vec<_>>(),
+				(4..10).map(|x| (x as u32, x as u64)).collect::<Vec<_>>(),
+			);
+			assert_eq!(NumberMap::iter_keys().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+			assert_eq!(
+				NumberMap::iter_keys_from(cursor.clone()).collect::<Vec<_>>(),
+				(4..10).collect::<Vec<_>>(),
+			);
+			assert_eq!(
+				NumberMap::iter_values_from(cursor).collect::<Vec<_>>(),
+				(4..10).collect::<Vec<_>>(),
+			);
+
+			let mut keys = NumberMap::iter_keys();
+			assert_eq!(keys.next(), Some(0));
+			assert_eq!(keys.last_raw_key(), &NumberMap::hashed_key_for(0u32)[..]);
+		})
+	}
+
+	#[test]
+	fn map_bounded_translate_and_remove_work() {
+		let t = GenesisConfig::default().build_storage().unwrap();
+		TestExternalities::new(t).execute_with(|| {
+			for i in 0u32..10u32 {
+				NumberMap::insert(i, i as u64);
+			}
+
+			let translate = |k: u32, v: u64| if k % 3 == 0 { None } else { Some(v * 10) };
+			let progress = NumberMap::translate_bounded(4, None, translate);
+			assert_eq!(
+				progress,
+				BoundedProgress { processed: 4, cursor: Some(NumberMap::hashed_key_for(3u32)) },
+			);
+			assert_eq!(
+				NumberMap::iter().collect::<Vec<_>>(),
+				vec![(1, 10), (2, 20), (4, 4), (5, 5), (6, 6), (7, 7), (8, 8), (9, 9)],
+			);
+
+			let progress = NumberMap::translate_bounded(10, progress.cursor, translate);
+			assert_eq!(progress, BoundedProgress { processed: 6, cursor: None });
+			assert_eq!(
+				NumberMap::iter().collect::<Vec<_>>(),
+				vec![(1, 10), (2, 20), (4, 40), (5, 50), (7, 70), (8, 80)],
+			);
+
+			let progress = NumberMap::remove_bounded(4, None);
+			assert_eq!(
+				progress,
+				BoundedProgress { processed: 4, cursor: Some(NumberMap::hashed_key_for(5u32)) },
+			);
+			assert_eq!(NumberMap::iter().collect::<Vec<_>>(), vec![(7, 70), (8, 80)]);
+
+			let progress = NumberMap::remove_bounded(4, progress.cursor);
+			assert_eq!(progress, BoundedProgress { processed: 2, cursor: None });
+			assert_eq!(NumberMap::iter().count(), 0);
+		})
+	}
+
+	#[test]
+	fn double_map_iteration_can_be_resumed() {
+		let t = GenesisConfig::default().build_storage().unwrap();
+		TestExternalities::new(t).execute_with(|| {
+			for i in 0u32..3u32 {
+				for j in 0u32..3u32 {
+					DoubleMap::insert(i, j, (i * 10 + j) as u64);
+				}
+			}
+
+			let mut iter = DoubleMap::iter();
+			assert_eq!(
+				iter.by_ref().take(4).collect::<Vec<_>>(),
+				vec![(0, 0, 0), (0, 1, 1), (0, 2, 2), (1, 0, 10)],
+			);
+			let cursor = iter.last_raw_key().to_vec();
+
+			assert_eq!(
+				DoubleMap::iter_from(cursor.clone()).collect::<Vec<_>>(),
+				vec![(1, 1, 11), (1, 2, 12), (2, 0, 20), (2, 1, 21), (2, 2, 22)],
+			);
+			assert_eq!(
+				DoubleMap::iter_keys_from(cursor).collect::<Vec<_>>(),
+				vec![(1, 1), (1, 2), (2, 0), (2, 1), (2, 2)],
+			);
+			assert_eq!(DoubleMap::iter_keys().count(), 9);
+
+			let mut prefix_iter = DoubleMap::iter_prefix(1u32);
+			assert_eq!(prefix_iter.next(), Some((0, 10)));
+			let cursor = prefix_iter.last_raw_key().to_vec();
+			assert_eq!(
+				DoubleMap::iter_prefix_from(1u32, cursor).collect::<Vec<_>>(),
+				vec![(1, 11), (2, 12)],
+			);
+
+			let translate = |k1: u32, k2: u32, v: u64| if k1 == k2 { None } else { Some(v + 100) };
+			let progress = DoubleMap::translate_bounded(5, None, translate);
+			assert_eq!(
+				progress,
+				BoundedProgress { processed: 5, cursor: Some(DoubleMap::hashed_key_for(1u32, 1u32)) },
+			);
+			let progress = DoubleMap::translate_bounded(5, progress.cursor, translate);
+			assert_eq!(progress, BoundedProgress { processed: 4, cursor: None });
+			assert_eq!(
+				DoubleMap::iter().collect::<Vec<_>>(),
+				vec![(0, 1, 101), (0, 2, 102), (1, 0, 110), (1, 2, 112), (2, 0, 120), (2, 1, 121)],
+			);
+		})
+	}
+
 	#[test]
 	fn try_mutate_works() {
 		let t = GenesisConfig::default().build_storage().unwrap();
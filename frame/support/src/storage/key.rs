@@ -0,0 +1,351 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Key types and key generators used by storage N-maps.
+//!
+//! A storage N-map is keyed by a tuple of [`Key`]s, each one holding the type of one key
+//! component together with the hasher used for it. Tuples of up to 10 keys are supported.
+
+use sp_std::{prelude::*, marker::PhantomData, vec::IntoIter};
+use codec::{FullCodec, Encode, EncodeLike};
+use crate::{hash::{StorageHasher, ReversibleStorageHasher}, traits::MaxEncodedLen};
+
+/// A single key component of a storage N-map, hashed with `Hasher`.
+///
+/// The final key of an N-map keyed by `(Key<Hasher1, Key1>, ..., Key<HasherN, KeyN>)` is:
+/// ```nocompile
+/// Hasher1(encode(key1)) ++ Hasher2(encode(key2)) ++ ... ++ HasherN(encode(keyN))
+/// ```
+pub struct Key<Hasher, KeyType>(PhantomData<(Hasher, KeyType)>);
+
+/// A type that generates the key of a storage N-map from its components.
+pub trait KeyGenerator {
+	/// The full key, a tuple of the key components if there is more than one.
+	type Key: FullCodec;
+	/// The tuple of key components accepted to build the key.
+	type KArg: Encode;
+
+	/// Hash each component of `key` and concatenate the results.
+	fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8>;
+}
+
+/// A key generator whose hashers can all be reversed, allowing to decode the key back.
+pub trait ReversibleKeyGenerator: KeyGenerator {
+	/// Decode the key from the start of `key_material`, returning it along with the remaining
+	/// bytes.
+	fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error>;
+}
+
+/// A key generator whose key components all have a maximum encoded length.
+pub trait KeyGeneratorMaxEncodedLen: KeyGenerator {
+	/// The maximum length of the final key, without the storage prefix.
+	fn key_max_encoded_len() -> usize;
+}
+
+impl<H: StorageHasher, K: FullCodec> Key<H, K> {
+	/// Hash an already encoded key component.
+	fn hash_encoded(encoded: &[u8]) -> H::Output {
+		H::hash(encoded)
+	}
+}
+
+impl<H: StorageHasher, K: FullCodec> KeyGenerator for Key<H, K> {
+	type Key = K;
+	type KArg = (K,);
+
+	fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8> {
+		let encoded = key.to_encoded_iter().next().expect("`KArg` holds exactly one key; qed");
+		Self::hash_encoded(&encoded).as_ref().to_vec()
+	}
+}
+
+impl<H: StorageHasher, K: FullCodec + MaxEncodedLen> KeyGeneratorMaxEncodedLen for Key<H, K> {
+	fn key_max_encoded_len() -> usize {
+		H::max_len::<K>()
+	}
+}
+
+impl<H: ReversibleStorageHasher, K: FullCodec> ReversibleKeyGenerator for Key<H, K> {
+	fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error> {
+		let mut current_key_material = H::reverse(key_material);
+		let key = K::decode(&mut current_key_material)?;
+		Ok((key, current_key_material))
+	}
+}
+
+/// Marker trait stating that a tuple can be encoded like the tuple `T`, element by element.
+pub trait EncodeLikeTuple<T> {}
+
+impl<T: EncodeLikeTuple<U>, U> EncodeLikeTuple<U> for &T {}
+
+/// A tuple whose elements can be encoded one by one.
+pub trait TupleToEncodedIter {
+	/// Encode each element of the tuple, in order.
+	fn to_encoded_iter(&self) -> IntoIter<Vec<u8>>;
+}
+
+impl<T: TupleToEncodedIter> TupleToEncodedIter for &T {
+	fn to_encoded_iter(&self) -> IntoIter<Vec<u8>> {
+		(*self).to_encoded_iter()
+	}
+}
+
+/// A key generator that can be given a prefix `P` of its key components, for example to iterate
+/// over or to remove all the entries sharing the prefix.
+pub trait HasKeyPrefix<P>: KeyGenerator {
+	/// The key components following the prefix, a tuple if there is more than one.
+	type Suffix;
+
+	/// Hash each component of the `prefix` and concatenate the results.
+	fn partial_key(prefix: P) -> Vec<u8>;
+}
+
+/// A key generator that can decode the key components following a prefix `P`.
+pub trait HasReversibleKeyPrefix<P>: ReversibleKeyGenerator + HasKeyPrefix<P> {
+	/// Decode the key components following the prefix from `key_material`.
+	fn decode_partial_key(key_material: &[u8]) -> Result<Self::Suffix, codec::Error>;
+}
+
+macro_rules! impl_encode_like_tuples {
+	() => {};
+	($first:ident $first_like:ident $(, $rest:ident $rest_like:ident)*) => {
+		impl<$first: EncodeLike<$first_like>, $first_like, $($rest: EncodeLike<$rest_like>, $rest_like),*>
+			EncodeLikeTuple<($first_like, $($rest_like,)*)> for ($first, $($rest,)*) {}
+
+		impl<$first: Encode, $($rest: Encode),*> TupleToEncodedIter for ($first, $($rest,)*) {
+			#[allow(non_snake_case)]
+			fn to_encoded_iter(&self) -> IntoIter<Vec<u8>> {
+				let ($first, $($rest,)*) = self;
+				vec![$first.encode(), $($rest.encode()),*].into_iter()
+			}
+		}
+
+		impl_encode_like_tuples!($($rest $rest_like),*);
+	};
+}
+
+impl_encode_like_tuples!(A LA, B LB, C LC, D LD, E LE, F LF, G LG, H LH, I LI, J LJ);
+
+macro_rules! impl_key_generator_for_tuple {
+	($($key:ident $hasher:ident),+) => {
+		impl<$($hasher: StorageHasher, $key: FullCodec),+> KeyGenerator
+			for ($(Key<$hasher, $key>,)+)
+		{
+			type Key = ($($key,)+);
+			type KArg = ($($key,)+);
+
+			fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(
+				key: KArg,
+			) -> Vec<u8> {
+				let mut final_key = Vec::new();
+				let mut iter = key.to_encoded_iter();
+				$(
+					let encoded = iter.next().expect("`KArg` holds as many keys as `Key`; qed");
+					final_key.extend_from_slice(
+						<Key<$hasher, $key>>::hash_encoded(&encoded).as_ref()
+					);
+				)+
+				final_key
+			}
+		}
+
+		impl<$($hasher: StorageHasher, $key: FullCodec + MaxEncodedLen),+> KeyGeneratorMaxEncodedLen
+			for ($(Key<$hasher, $key>,)+)
+		{
+			fn key_max_encoded_len() -> usize {
+				let mut len = 0usize;
+				$(
+					len = len.saturating_add($hasher::max_len::<$key>());
+				)+
+				len
+			}
+		}
+
+		impl<$($hasher: ReversibleStorageHasher, $key: FullCodec),+> ReversibleKeyGenerator
+			for ($(Key<$hasher, $key>,)+)
+		{
+			fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error> {
+				let mut current_key_material = key_material;
+				let key = ($({
+					let (key, remaining) =
+						<Key<$hasher, $key>>::decode_final_key(current_key_material)?;
+					current_key_material = remaining;
+					key
+				},)+);
+				Ok((key, current_key_material))
+			}
+		}
+	};
+}
+
+macro_rules! impl_key_prefix_for {
+	// Only one key component is left after the prefix, the suffix is not a tuple.
+	(
+		($($prefix:ident $prefix_hasher:ident),+),
+		($suffix:ident $suffix_hasher:ident)
+	) => {
+		impl<
+			$($prefix: FullCodec, $prefix_hasher: StorageHasher,)+
+			$suffix: FullCodec, $suffix_hasher: StorageHasher
+		> HasKeyPrefix<($($prefix,)+)>
+			for ($(Key<$prefix_hasher, $prefix>,)+ Key<$suffix_hasher, $suffix>,)
+		{
+			type Suffix = $suffix;
+
+			fn partial_key(prefix: ($($prefix,)+)) -> Vec<u8> {
+				impl_key_prefix_for!(@partial_key prefix, $($prefix $prefix_hasher),+)
+			}
+		}
+
+		impl<
+			$($prefix: FullCodec, $prefix_hasher: ReversibleStorageHasher,)+
+			$suffix: FullCodec, $suffix_hasher: ReversibleStorageHasher
+		> HasReversibleKeyPrefix<($($prefix,)+)>
+			for ($(Key<$prefix_hasher, $prefix>,)+ Key<$suffix_hasher, $suffix>,)
+		{
+			fn decode_partial_key(key_material: &[u8]) -> Result<Self::Suffix, codec::Error> {
+				<Key<$suffix_hasher, $suffix>>::decode_final_key(key_material).map(|(k, _)| k)
+			}
+		}
+	};
+	(
+		($($prefix:ident $prefix_hasher:ident),+),
+		($first:ident $first_hasher:ident, $($suffix:ident $suffix_hasher:ident),+)
+	) => {
+		impl<
+			$($prefix: FullCodec, $prefix_hasher: StorageHasher,)+
+			$first: FullCodec, $first_hasher: StorageHasher,
+			$($suffix: FullCodec, $suffix_hasher: StorageHasher),+
+		> HasKeyPrefix<($($prefix,)+)>
+			for (
+				$(Key<$prefix_hasher, $prefix>,)+
+				Key<$first_hasher, $first>,
+				$(Key<$suffix_hasher, $suffix>,)+
+			)
+		{
+			type Suffix = ($first, $($suffix,)+);
+
+			fn partial_key(prefix: ($($prefix,)+)) -> Vec<u8> {
+				impl_key_prefix_for!(@partial_key prefix, $($prefix $prefix_hasher),+)
+			}
+		}
+
+		impl<
+			$($prefix: FullCodec, $prefix_hasher: ReversibleStorageHasher,)+
+			$first: FullCodec, $first_hasher: ReversibleStorageHasher,
+			$($suffix: FullCodec, $suffix_hasher: ReversibleStorageHasher),+
+		> HasReversibleKeyPrefix<($($prefix,)+)>
+			for (
+				$(Key<$prefix_hasher, $prefix>,)+
+				Key<$first_hasher, $first>,
+				$(Key<$suffix_hasher, $suffix>,)+
+			)
+		{
+			fn decode_partial_key(key_material: &[u8]) -> Result<Self::Suffix, codec::Error> {
+				<(Key<$first_hasher, $first>, $(Key<$suffix_hasher, $suffix>,)+)>::decode_final_key(
+					key_material,
+				).map(|(k, _)| k)
+			}
+		}
+
+		impl_key_prefix_for!(
+			($($prefix $prefix_hasher),+, $first $first_hasher),
+			($($suffix $suffix_hasher),+)
+		);
+	};
+	(@partial_key $prefix_value:ident, $($prefix:ident $prefix_hasher:ident),+) => {{
+		let mut partial_key = Vec::new();
+		let mut iter = $prefix_value.to_encoded_iter();
+		$(
+			let encoded = iter.next().expect("the prefix holds as many keys as its type; qed");
+			partial_key.extend_from_slice(
+				<Key<$prefix_hasher, $prefix>>::hash_encoded(&encoded).as_ref()
+			);
+		)+
+		partial_key
+	}};
+}
+
+macro_rules! impl_key_generator_for_tuples {
+	($key:ident $hasher:ident) => {};
+	($key:ident $hasher:ident, $($rest_key:ident $rest_hasher:ident),+) => {
+		impl_key_generator_for_tuple!($key $hasher, $($rest_key $rest_hasher),+);
+		impl_key_prefix_for!(($key $hasher), ($($rest_key $rest_hasher),+));
+		impl_key_generator_for_tuples!($($rest_key $rest_hasher),+);
+	};
+}
+
+impl_key_generator_for_tuples!(
+	A HA, B HB, C HC, D HD, E HE, F HF, G HG, H HH, I HI, J HJ
+);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hash::{Identity, Twox64Concat};
+
+	type ThreeKeys = (Key<Identity, u8>, Key<Twox64Concat, u16>, Key<Identity, u32>);
+
+	#[test]
+	fn final_key_is_concatenation_of_hashed_keys() {
+		let final_key = ThreeKeys::final_key((1u8, 2u16, 3u32));
+		let mut expected = vec![1u8];
+		expected.extend(Twox64Concat::hash(&2u16.encode()));
+		expected.extend(3u32.encode());
+		assert_eq!(final_key, expected);
+
+		assert_eq!(ThreeKeys::final_key((&1u8, &2u16, &3u32)), final_key);
+		assert_eq!(ThreeKeys::final_key(&(1u8, 2u16, 3u32)), final_key);
+	}
+
+	#[test]
+	fn final_key_can_be_decoded() {
+		let final_key = ThreeKeys::final_key((1u8, 2u16, 3u32));
+		assert_eq!(ThreeKeys::decode_final_key(&final_key).ok(), Some(((1, 2, 3), &[][..])));
+	}
+
+	#[test]
+	fn key_max_encoded_len_accounts_for_hashers() {
+		assert_eq!(ThreeKeys::key_max_encoded_len(), 1 + (8 + 2) + 4);
+		assert_eq!(
+			ThreeKeys::key_max_encoded_len(),
+			ThreeKeys::final_key((u8::max_value(), u16::max_value(), u32::max_value())).len(),
+		);
+	}
+
+	#[test]
+	fn partial_keys_are_prefixes_of_final_key() {
+		let final_key = ThreeKeys::final_key((1u8, 2u16, 3u32));
+
+		let partial_key = <ThreeKeys as HasKeyPrefix<(u8,)>>::partial_key((1,));
+		assert!(final_key.starts_with(&partial_key));
+		assert_eq!(
+			<ThreeKeys as HasReversibleKeyPrefix<(u8,)>>::decode_partial_key(
+				&final_key[partial_key.len()..]
+			).ok(),
+			Some((2, 3)),
+		);
+
+		let partial_key = <ThreeKeys as HasKeyPrefix<(u8, u16)>>::partial_key((1, 2));
+		assert!(final_key.starts_with(&partial_key));
+		assert_eq!(
+			<ThreeKeys as HasReversibleKeyPrefix<(u8, u16)>>::decode_partial_key(
+				&final_key[partial_key.len()..]
+			).ok(),
+			Some(3),
+		);
+	}
+}
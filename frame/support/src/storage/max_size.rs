@@ -0,0 +1,100 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Maximum size of a single entry of a storage item.
+//!
+//! The size of an entry is the length of its hashed keys plus the length of its encoded value.
+//! The 32 bytes of module and storage prefix common to all entries are not included.
+//!
+//! These functions are used by `decl_storage` to fill the `max_size` field of the storage
+//! metadata of items declared `bounded`.
+
+use sp_runtime::SaturatedConversion;
+use crate::{hash::StorageHasher, traits::MaxEncodedLen, storage::KeyGeneratorMaxEncodedLen};
+
+/// The maximum size of an item whose size is not bounded, i.e. `None`.
+pub fn unbounded() -> Option<u32> {
+	None
+}
+
+/// The maximum size of a storage value holding a `Value`.
+pub fn value<Value: MaxEncodedLen>() -> Option<u32> {
+	Some(Value::max_encoded_len().saturated_into())
+}
+
+/// The maximum size of an entry of a storage map.
+pub fn map<Hasher, Key, Value>() -> Option<u32>
+where
+	Hasher: StorageHasher,
+	Key: MaxEncodedLen,
+	Value: MaxEncodedLen,
+{
+	Some(
+		Hasher::max_len::<Key>()
+			.saturating_add(Value::max_encoded_len())
+			.saturated_into()
+	)
+}
+
+/// The maximum size of an entry of a storage double map.
+pub fn double_map<Hasher1, Key1, Hasher2, Key2, Value>() -> Option<u32>
+where
+	Hasher1: StorageHasher,
+	Key1: MaxEncodedLen,
+	Hasher2: StorageHasher,
+	Key2: MaxEncodedLen,
+	Value: MaxEncodedLen,
+{
+	Some(
+		Hasher1::max_len::<Key1>()
+			.saturating_add(Hasher2::max_len::<Key2>())
+			.saturating_add(Value::max_encoded_len())
+			.saturated_into()
+	)
+}
+
+/// The maximum size of an entry of a storage N-map.
+pub fn n_map<Key: KeyGeneratorMaxEncodedLen, Value: MaxEncodedLen>() -> Option<u32> {
+	Some(Key::key_max_encoded_len().saturating_add(Value::max_encoded_len()).saturated_into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		hash::{Blake2_128Concat, Twox64Concat, Twox128},
+		storage::{BoundedVec, NMapKey},
+		parameter_types,
+	};
+
+	parameter_types! {
+		pub const Ten: u32 = 10;
+	}
+
+	#[test]
+	fn max_sizes_add_up() {
+		assert_eq!(unbounded(), None);
+		assert_eq!(value::<u64>(), Some(8));
+		assert_eq!(value::<BoundedVec<u32, Ten>>(), Some(1 + 40));
+		assert_eq!(map::<Twox64Concat, u32, u64>(), Some(8 + 4 + 8));
+		assert_eq!(map::<Twox128, u32, u64>(), Some(16 + 8));
+		assert_eq!(double_map::<Blake2_128Concat, u8, Twox64Concat, u16, ()>(), Some(17 + 10));
+		assert_eq!(
+			n_map::<(NMapKey<Blake2_128Concat, u8>, NMapKey<Twox64Concat, u16>), bool>(),
+			Some(17 + 10 + 1),
+		);
+	}
+}
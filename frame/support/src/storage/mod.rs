@@ -27,6 +27,44 @@ pub mod child;
 #[doc(hidden)]
 pub mod generator;
 pub mod migration;
+pub mod key;
+pub mod bounded_vec;
+pub mod bounded_btree_map;
+pub mod max_size;
+
+pub use key::{
+	Key as NMapKey, KeyGenerator, ReversibleKeyGenerator, KeyGeneratorMaxEncodedLen,
+	EncodeLikeTuple, TupleToEncodedIter, HasKeyPrefix, HasReversibleKeyPrefix,
+};
+pub use bounded_vec::BoundedVec;
+pub use bounded_btree_map::BoundedBTreeMap;
+
+/// Describes whether a storage transaction should be committed or rolled back.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TransactionOutcome<R> {
+	/// Commit the transaction.
+	Commit(R),
+	/// Rollback the transaction.
+	Rollback(R),
+}
+
+/// Execute the supplied function in a new storage transaction.
+///
+/// All changes to storage performed by the supplied function are discarded if the returned
+/// outcome is `TransactionOutcome::Rollback`.
+///
+/// Transactions can be nested to any depth. Commits happen to the parent transaction.
+pub fn with_transaction<R>(f: impl FnOnce() -> TransactionOutcome<R>) -> R {
+	use sp_io::storage::{start_transaction, commit_transaction, rollback_transaction};
+	use TransactionOutcome::*;
+
+	start_transaction();
+
+	match f() {
+		Commit(res) => { commit_transaction(); res },
+		Rollback(res) => { rollback_transaction(); res },
+	}
+}
 
 /// A trait for working with macro-generated storage values under the substrate storage API.
 ///
@@ -220,10 +258,28 @@ pub trait IterableStorageMap<K: FullEncode, V: FullCodec>: StorageMap<K, V> {
 	/// The type that iterates over all `(key, value)`.
 	type Iterator: Iterator<Item = (K, V)>;
 
+	/// The type that iterates over all `key`s.
+	type KeyIterator: Iterator<Item = K>;
+
 	/// Enumerate all elements in the map in no particular order. If you alter the map while doing
 	/// this, you'll get undefined results.
 	fn iter() -> Self::Iterator;
 
+	/// Enumerate all elements in the map after the given raw key, in the same order as `iter`.
+	///
+	/// `starting_raw_key` is usually the `last_raw_key` of a previous iterator, which allows to
+	/// resume an iteration, e.g. in a later block. If you alter the map while doing this, you'll
+	/// get undefined results.
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator;
+
+	/// Enumerate all keys in the map in no particular order, skipping over the values. If you
+	/// alter the map while doing this, you'll get undefined results.
+	fn iter_keys() -> Self::KeyIterator;
+
+	/// Enumerate all keys in the map after the given raw key, in the same order as `iter_keys`.
+	/// If you alter the map while doing this, you'll get undefined results.
+	fn iter_keys_from(starting_raw_key: Vec<u8>) -> Self::KeyIterator;
+
 	/// Remove all elements from the map and iterate through them in no particular order. If you
 	/// add elements to the map while doing this, you'll get undefined results.
 	fn drain() -> Self::Iterator;
@@ -231,6 +287,18 @@ pub trait IterableStorageMap<K: FullEncode, V: FullCodec>: StorageMap<K, V> {
 	/// Translate the values of all elements by a function `f`, in the map in no particular order.
 	/// By returning `None` from `f` for an element, you'll remove it from the map.
 	fn translate<O: Decode, F: Fn(K, O) -> Option<V>>(f: F);
+
+	/// Translate the values of at most `limit` elements by a function `f`, starting after the
+	/// raw key `cursor` or at the beginning of the map if `None`.
+	///
+	/// The returned progress holds the cursor to resume from, which allows to translate a large
+	/// map over several blocks. By returning `None` from `f` for an element, you'll remove it
+	/// from the map.
+	fn translate_bounded<O: Decode, F: Fn(K, O) -> Option<V>>(
+		limit: u32,
+		cursor: Option<Vec<u8>>,
+		f: F,
+	) -> BoundedProgress;
 }
 
 /// A strongly-typed double map in storage whose secondary keys and values can be iterated over.
@@ -245,11 +313,22 @@ pub trait IterableStorageDoubleMap<
 	/// The type that iterates over all `(key1, key2, value)`.
 	type Iterator: Iterator<Item = (K1, K2, V)>;
 
+	/// The type that iterates over all `(key1, key2)`.
+	type KeyIterator: Iterator<Item = (K1, K2)>;
+
 	/// Enumerate all elements in the map with first key `k1` in no particular order. If you add or
 	/// remove values whose first key is `k1` to the map while doing this, you'll get undefined
 	/// results.
 	fn iter_prefix(k1: impl EncodeLike<K1>) -> Self::PrefixIterator;
 
+	/// Enumerate all elements in the map with first key `k1` after the given raw key, in the same
+	/// order as `iter_prefix`. If you add or remove values whose first key is `k1` to the map
+	/// while doing this, you'll get undefined results.
+	fn iter_prefix_from(
+		k1: impl EncodeLike<K1>,
+		starting_raw_key: Vec<u8>,
+	) -> Self::PrefixIterator;
+
 	/// Remove all elements from the map with first key `k1` and iterate through them in no
 	/// particular order. If you add elements with first key `k1` to the map while doing this,
 	/// you'll get undefined results.
@@ -259,6 +338,21 @@ pub trait IterableStorageDoubleMap<
 	/// the map while doing this, you'll get undefined results.
 	fn iter() -> Self::Iterator;
 
+	/// Enumerate all elements in the map after the given raw key, in the same order as `iter`.
+	///
+	/// `starting_raw_key` is usually the `last_raw_key` of a previous iterator, which allows to
+	/// resume an iteration, e.g. in a later block. If you add or remove values to the map while
+	/// doing this, you'll get undefined results.
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator;
+
+	/// Enumerate all keys in the map in no particular order, skipping over the values. If you add
+	/// or remove values to the map while doing this, you'll get undefined results.
+	fn iter_keys() -> Self::KeyIterator;
+
+	/// Enumerate all keys in the map after the given raw key, in the same order as `iter_keys`.
+	/// If you add or remove values to the map while doing this, you'll get undefined results.
+	fn iter_keys_from(starting_raw_key: Vec<u8>) -> Self::KeyIterator;
+
 	/// Remove all elements from the map and iterate through them in no particular order. If you
 	/// add elements to the map while doing this, you'll get undefined results.
 	fn drain() -> Self::Iterator;
@@ -266,6 +360,18 @@ pub trait IterableStorageDoubleMap<
 	/// Translate the values of all elements by a function `f`, in the map in no particular order.
 	/// By returning `None` from `f` for an element, you'll remove it from the map.
 	fn translate<O: Decode, F: Fn(O) -> Option<V>>(f: F);
+
+	/// Translate the values of at most `limit` elements by a function `f`, starting after the
+	/// raw key `cursor` or at the beginning of the map if `None`.
+	///
+	/// The returned progress holds the cursor to resume from, which allows to translate a large
+	/// map over several blocks. By returning `None` from `f` for an element, you'll remove it
+	/// from the map.
+	fn translate_bounded<O: Decode, F: Fn(K1, K2, O) -> Option<V>>(
+		limit: u32,
+		cursor: Option<Vec<u8>>,
+		f: F,
+	) -> BoundedProgress;
 }
 
 /// An implementation of a map with a two keys.
@@ -398,6 +504,134 @@ pub trait StorageDoubleMap<K1: FullEncode, K2: FullEncode, V: FullCodec> {
 	>(key1: KeyArg1, key2: KeyArg2) -> Option<V>;
 }
 
+/// An implementation of a map with an arbitrary number of keys.
+///
+/// Each key component is hashed with its own hasher, which makes it possible to efficiently
+/// remove or iterate over all the entries sharing the first key components.
+///
+/// Details on implementation can be found at
+/// [`generator::StorageNMap`]
+pub trait StorageNMap<K: KeyGenerator, V: FullCodec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	fn hashed_key_for<KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8>;
+
+	/// Does the value (explicitly) exist in storage?
+	fn contains_key<KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter>(key: KArg) -> bool;
+
+	/// Load the value associated with the given key from the map.
+	fn get<KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter>(key: KArg) -> Self::Query;
+
+	/// Take a value from storage, removing it afterwards.
+	fn take<KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter>(key: KArg) -> Self::Query;
+
+	/// Swap the values of two keys.
+	fn swap<KArg1, KArg2>(key1: KArg1, key2: KArg2)
+	where
+		KArg1: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		KArg2: EncodeLikeTuple<K::KArg> + TupleToEncodedIter;
+
+	/// Store a value to be associated with the given key from the map.
+	fn insert<KArg, VArg>(key: KArg, val: VArg)
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		VArg: EncodeLike<V>;
+
+	/// Remove the value under the given key.
+	fn remove<KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter>(key: KArg);
+
+	/// Remove all values starting with the given partial key.
+	fn remove_prefix<KP>(partial_key: KP) where K: HasKeyPrefix<KP>;
+
+	/// Iterate over values that share the given partial key.
+	fn iter_prefix_values<KP>(partial_key: KP) -> PrefixIterator<V> where K: HasKeyPrefix<KP>;
+
+	/// Mutate the value under the given key.
+	fn mutate<KArg, R, F>(key: KArg, f: F) -> R
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		F: FnOnce(&mut Self::Query) -> R;
+
+	/// Mutate the value under the given key when the closure returns `Ok`.
+	fn try_mutate<KArg, R, E, F>(key: KArg, f: F) -> Result<R, E>
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		F: FnOnce(&mut Self::Query) -> Result<R, E>;
+
+	/// Append the given item to the value in the storage.
+	///
+	/// `V` is required to implement [`StorageAppend`].
+	///
+	/// # Warning
+	///
+	/// If the storage item is not encoded properly, the storage will be overwritten
+	/// and set to `[item]`. Any default value set for the storage item will be ignored
+	/// on overwrite.
+	fn append<Item, EncodeLikeItem, KArg>(key: KArg, item: EncodeLikeItem)
+	where
+		KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter,
+		Item: Encode,
+		EncodeLikeItem: EncodeLike<Item>,
+		V: StorageAppend<Item>;
+
+	/// Read the length of the storage value without decoding the entire value under the
+	/// given `key`.
+	///
+	/// `V` is required to implement [`StorageDecodeLength`].
+	///
+	/// If the value does not exists or it fails to decode the length, `None` is returned.
+	/// Otherwise `Some(len)` is returned.
+	///
+	/// # Warning
+	///
+	/// `None` does not mean that `get()` does not return a value. The default value is completly
+	/// ignored by this function.
+	fn decode_len<KArg: EncodeLikeTuple<K::KArg> + TupleToEncodedIter>(key: KArg) -> Option<usize>
+	where
+		V: StorageDecodeLength,
+	{
+		V::decode_len(&Self::hashed_key_for(key))
+	}
+}
+
+/// A strongly-typed N-map in storage whose keys and values can be iterated over.
+pub trait IterableStorageNMap<K: ReversibleKeyGenerator, V: FullCodec>: StorageNMap<K, V> {
+	/// The type that iterates over all `(key, value)`.
+	type Iterator: Iterator<Item = (K::Key, V)>;
+
+	/// Enumerate all elements in the map with the given partial key in no particular order. The
+	/// remaining key components are returned alongside the values. If you add or remove values
+	/// sharing the partial key to the map while doing this, you'll get undefined results.
+	fn iter_prefix<KP>(
+		partial_key: KP,
+	) -> generator::MapIterator<(<K as HasKeyPrefix<KP>>::Suffix, V)>
+	where
+		K: HasReversibleKeyPrefix<KP>;
+
+	/// Remove all elements from the map with the given partial key and iterate through them in
+	/// no particular order. If you add elements sharing the partial key to the map while doing
+	/// this, you'll get undefined results.
+	fn drain_prefix<KP>(
+		partial_key: KP,
+	) -> generator::MapIterator<(<K as HasKeyPrefix<KP>>::Suffix, V)>
+	where
+		K: HasReversibleKeyPrefix<KP>;
+
+	/// Enumerate all elements in the map in no particular order. If you add or remove values to
+	/// the map while doing this, you'll get undefined results.
+	fn iter() -> Self::Iterator;
+
+	/// Remove all elements from the map and iterate through them in no particular order. If you
+	/// add elements to the map while doing this, you'll get undefined results.
+	fn drain() -> Self::Iterator;
+
+	/// Translate the values of all elements by a function `f`, in the map in no particular order.
+	/// By returning `None` from `f` for an element, you'll remove it from the map.
+	fn translate<O: Decode, F: Fn(K::Key, O) -> Option<V>>(f: F);
+}
+
 /// Iterator for prefixed map.
 pub struct PrefixIterator<Value> {
 	prefix: Vec<u8>,
@@ -405,6 +639,15 @@ pub struct PrefixIterator<Value> {
 	phantom_data: PhantomData<Value>,
 }
 
+impl<Value> PrefixIterator<Value> {
+	/// The raw key of the last value returned, or the key the iteration started after.
+	///
+	/// Can be used as a cursor to resume the iteration later on.
+	pub fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
+}
+
 impl<Value: Decode> Iterator for PrefixIterator<Value> {
 	type Item = Value;
 
@@ -431,6 +674,56 @@ impl<Value: Decode> Iterator for PrefixIterator<Value> {
 	}
 }
 
+/// Iterate over the keys under a prefix and decode the raw keys into `T`.
+pub struct KeyPrefixIterator<T> {
+	prefix: Vec<u8>,
+	previous_key: Vec<u8>,
+	/// Function that takes the raw storage key without the prefix iterated on and decodes `T`.
+	closure: fn(&[u8]) -> Result<T, codec::Error>,
+}
+
+impl<T> KeyPrefixIterator<T> {
+	/// The raw key of the last key returned, or the key the iteration started after.
+	///
+	/// Can be used as a cursor to resume the iteration later on.
+	pub fn last_raw_key(&self) -> &[u8] {
+		&self.previous_key
+	}
+}
+
+impl<T> Iterator for KeyPrefixIterator<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let maybe_next = sp_io::storage::next_key(&self.previous_key)
+				.filter(|n| n.starts_with(&self.prefix));
+			break match maybe_next {
+				Some(next) => {
+					self.previous_key = next;
+					match (self.closure)(&self.previous_key[self.prefix.len()..]) {
+						Ok(item) => Some(item),
+						Err(_) => {
+							runtime_print!("ERROR: key failed to decode in KeyPrefixIterator");
+							continue
+						}
+					}
+				}
+				None => None,
+			}
+		}
+	}
+}
+
+/// The progress of an operation touching at most a given number of entries of a storage map.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BoundedProgress {
+	/// The number of entries touched by the operation.
+	pub processed: u32,
+	/// The raw key to resume the operation after, `None` once the end of the map was reached.
+	pub cursor: Option<Vec<u8>>,
+}
+
 /// Trait for maps that store all its value after a unique prefix.
 ///
 /// By default the final prefix is:
@@ -457,12 +750,40 @@ pub trait StoragePrefixedMap<Value: FullCodec> {
 		sp_io::storage::clear_prefix(&Self::final_prefix())
 	}
 
+	/// Remove at most `limit` values of the storage, starting after the raw key `cursor` or at
+	/// the beginning of the storage if `None`.
+	///
+	/// The returned progress holds the cursor to resume from, which allows to clear a large map
+	/// over several blocks.
+	fn remove_bounded(limit: u32, cursor: Option<Vec<u8>>) -> BoundedProgress {
+		let prefix = Self::final_prefix();
+		let mut previous_key = cursor.unwrap_or_else(|| prefix.to_vec());
+		let mut processed = 0;
+		while processed < limit {
+			match sp_io::storage::next_key(&previous_key).filter(|n| n.starts_with(&prefix[..])) {
+				Some(next_key) => {
+					unhashed::kill(&next_key);
+					previous_key = next_key;
+					processed += 1;
+				},
+				None => return BoundedProgress { processed, cursor: None },
+			}
+		}
+
+		BoundedProgress { processed, cursor: Some(previous_key) }
+	}
+
 	/// Iter over all value of the storage.
 	fn iter_values() -> PrefixIterator<Value> {
-		let prefix = Self::final_prefix();
+		Self::iter_values_from(Self::final_prefix().to_vec())
+	}
+
+	/// Iter over all value of the storage after the given raw key, in the same order as
+	/// `iter_values`.
+	fn iter_values_from(starting_raw_key: Vec<u8>) -> PrefixIterator<Value> {
 		PrefixIterator {
-			prefix: prefix.to_vec(),
-			previous_key: prefix.to_vec(),
+			prefix: Self::final_prefix().to_vec(),
+			previous_key: starting_raw_key,
 			phantom_data: Default::default(),
 		}
 	}
@@ -639,6 +960,50 @@ mod test {
 		});
 	}
 
+	#[test]
+	fn with_transaction_works() {
+		TestExternalities::default().execute_with(|| {
+			unhashed::put(b"key", &1u32);
+
+			// a rolled back transaction discards all its changes, including the ones
+			// committed by nested transactions.
+			let res = with_transaction(|| {
+				unhashed::put(b"key", &2u32);
+				unhashed::put(b"other", &2u32);
+
+				with_transaction(|| {
+					unhashed::put(b"key", &3u32);
+					unhashed::kill(b"other");
+					TransactionOutcome::Commit(())
+				});
+				assert_eq!(unhashed::get::<u32>(b"key"), Some(3));
+				assert_eq!(unhashed::get::<u32>(b"other"), None);
+
+				TransactionOutcome::Rollback(5)
+			});
+			assert_eq!(res, 5);
+			assert_eq!(unhashed::get::<u32>(b"key"), Some(1));
+			assert_eq!(unhashed::get::<u32>(b"other"), None);
+
+			// a nested transaction can be rolled back while the outer one is committed.
+			with_transaction(|| {
+				unhashed::put(b"key", &2u32);
+
+				with_transaction(|| {
+					unhashed::put(b"key", &3u32);
+					unhashed::put(b"other", &3u32);
+					TransactionOutcome::Rollback(())
+				});
+				assert_eq!(unhashed::get::<u32>(b"key"), Some(2));
+				assert_eq!(unhashed::get::<u32>(b"other"), None);
+
+				TransactionOutcome::Commit(())
+			});
+			assert_eq!(unhashed::get::<u32>(b"key"), Some(2));
+			assert_eq!(unhashed::get::<u32>(b"other"), None);
+		});
+	}
+
 	// This test ensures that the Digest encoding does not change without being noticied.
 	#[test]
 	fn digest_storage_append_works_as_expected() {
@@ -19,7 +19,7 @@
 //! NOTE: If you're looking for `parameter_types`, it has moved in to the top-level module.
 
 use sp_std::{prelude::*, result, marker::PhantomData, ops::Div, fmt::Debug};
-use codec::{FullCodec, Codec, Encode, Decode, EncodeLike};
+use codec::{FullCodec, Codec, Encode, Decode, EncodeLike, Compact, CompactLen};
 use sp_core::u32_trait::Value as U32;
 use sp_runtime::{
 	RuntimeDebug, ConsensusEngineId, DispatchResult, DispatchError, traits::{
@@ -28,9 +28,12 @@ use sp_runtime::{
 	},
 };
 use crate::dispatch::Parameter;
+use crate::type_info::TypeInfo;
 use crate::storage::StorageMap;
 use impl_trait_for_tuples::impl_for_tuples;
 
+pub use frame_support_procedural::MaxEncodedLen;
+
 /// An abstraction of a value stored within storage, but possibly as part of a larger composite
 /// item.
 pub trait StoredMap<K, T> {
@@ -190,6 +193,108 @@ impl<T: Default> Get<T> for () {
 	fn get() -> T { T::default() }
 }
 
+/// Items implementing `MaxEncodedLen` have a statically known maximum encoded size.
+///
+/// Containers with an enforced size limit, such as `BoundedVec`, can implement this trait
+/// accurately. It is used to compute the worst-case size of a storage item, and hence the
+/// worst-case size of a storage proof reading it.
+pub trait MaxEncodedLen: Encode {
+	/// Upper bound, in bytes, of the encoded size of this type.
+	fn max_encoded_len() -> usize;
+}
+
+macro_rules! impl_max_encoded_len_primitives {
+	( $( $t:ty ),+ ) => {
+		$(
+			impl MaxEncodedLen for $t {
+				fn max_encoded_len() -> usize {
+					sp_std::mem::size_of::<$t>()
+				}
+			}
+		)+
+	};
+}
+
+impl_max_encoded_len_primitives!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool);
+
+macro_rules! impl_max_encoded_len_compact {
+	( $( $t:ty ),+ ) => {
+		$(
+			impl MaxEncodedLen for Compact<$t> {
+				fn max_encoded_len() -> usize {
+					Compact::<$t>::compact_len(&<$t>::max_value())
+				}
+			}
+		)+
+	};
+}
+
+impl_max_encoded_len_compact!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_max_encoded_len_fixed_size {
+	( $( $t:ty ),+ ) => {
+		$(
+			impl MaxEncodedLen for $t {
+				fn max_encoded_len() -> usize {
+					<$t>::len_bytes()
+				}
+			}
+		)+
+	};
+}
+
+impl_max_encoded_len_fixed_size!(sp_core::H160, sp_core::H256, sp_core::H512);
+
+impl MaxEncodedLen for sp_core::crypto::AccountId32 {
+	fn max_encoded_len() -> usize {
+		32
+	}
+}
+
+macro_rules! impl_max_encoded_len_arrays {
+	( $( $n:expr ),+ ) => {
+		$(
+			impl<T: MaxEncodedLen> MaxEncodedLen for [T; $n] {
+				fn max_encoded_len() -> usize {
+					T::max_encoded_len().saturating_mul($n)
+				}
+			}
+		)+
+	};
+}
+
+impl_max_encoded_len_arrays!(
+	1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+	26, 27, 28, 29, 30, 31, 32, 64, 128, 256, 512, 1024
+);
+
+impl<T: MaxEncodedLen> MaxEncodedLen for Option<T> {
+	fn max_encoded_len() -> usize {
+		T::max_encoded_len().saturating_add(1)
+	}
+}
+
+impl<T: MaxEncodedLen, E: MaxEncodedLen> MaxEncodedLen for result::Result<T, E> {
+	fn max_encoded_len() -> usize {
+		T::max_encoded_len().max(E::max_encoded_len()).saturating_add(1)
+	}
+}
+
+impl<T> MaxEncodedLen for PhantomData<T> {
+	fn max_encoded_len() -> usize {
+		0
+	}
+}
+
+#[impl_for_tuples(18)]
+impl MaxEncodedLen for Tuple {
+	fn max_encoded_len() -> usize {
+		let mut len: usize = 0;
+		for_tuples!( #( len = len.saturating_add(Tuple::max_encoded_len()); )* );
+		len
+	}
+}
+
 /// A trait for querying whether a type can be said to "contain" a value.
 pub trait Contains<T: Ord> {
 	/// Return `true` if this "contains" the given value `t`.
@@ -568,7 +673,8 @@ impl<
 /// Abstraction over a fungible assets system.
 pub trait Currency<AccountId> {
 	/// The balance of an account.
-	type Balance: AtLeast32Bit + FullCodec + Copy + MaybeSerializeDeserialize + Debug + Default;
+	type Balance: AtLeast32Bit + FullCodec + Copy + MaybeSerializeDeserialize + Debug + Default
+		+ TypeInfo;
 
 	/// The opaque token type for an imbalance. This is returned by unbalanced operations
 	/// and must be dealt with. It may be dropped but cannot be cloned.
@@ -884,11 +990,11 @@ pub trait VestingSchedule<AccountId> {
 
 bitmask! {
 	/// Reasons for moving funds out of an account.
-	#[derive(Encode, Decode)]
+	#[derive(Encode, Decode, TypeInfo)]
 	pub mask WithdrawReasons: i8 where
 
 	/// Reason for moving funds out of an account.
-	#[derive(Encode, Decode)]
+	#[derive(Encode, Decode, TypeInfo)]
 	flags WithdrawReason {
 		/// In order to pay for (system) transaction costs.
 		TransactionPayment = 0b00000001,
@@ -1079,16 +1185,38 @@ pub trait ValidatorRegistration<ValidatorId> {
 
 /// Something that can convert a given module into the index of the module in the runtime.
 ///
-/// The index of a module is determined by the position it appears in `construct_runtime!`.
+/// The index of a module is either given explicitly in `construct_runtime!` with `= <index>`, or
+/// is the index following the one of the module declared before it.
 pub trait ModuleToIndex {
 	/// Convert the given module `M` into an index.
 	fn module_to_index<M: 'static>() -> Option<usize>;
+
+	/// The name, index and storage prefix of every module of the runtime.
+	///
+	/// Used to detect runtime upgrades that move a module to another index or storage prefix.
+	fn modules_layout() -> Vec<ModuleLayout> { Vec::new() }
+
+	/// The storage prefix and the current version of every module declaring storage.
+	///
+	/// Used to write the storage version of the modules at genesis and after runtime upgrades.
+	fn pallet_versions() -> Vec<(&'static str, PalletVersion)> { Vec::new() }
 }
 
 impl ModuleToIndex for () {
 	fn module_to_index<M: 'static>() -> Option<usize> { Some(0) }
 }
 
+/// The place of a module in the runtime: its index and the prefix of its storage.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ModuleLayout {
+	/// The name of the module in `construct_runtime!`.
+	pub name: Vec<u8>,
+	/// The index of the module.
+	pub index: u8,
+	/// The prefix of the storage of the module, if it declares any storage.
+	pub storage_prefix: Option<Vec<u8>>,
+}
+
 /// The function and pallet name of the Call.
 #[derive(Clone, Eq, PartialEq, Default, RuntimeDebug)]
 pub struct CallMetadata {
@@ -1149,6 +1277,16 @@ pub trait OnRuntimeUpgrade {
 	///
 	/// Return the non-negotiable weight consumed for runtime upgrade.
 	fn on_runtime_upgrade() -> crate::weights::Weight { 0 }
+
+	/// Check the state before the upgrade, e.g. that the data to migrate is there.
+	///
+	/// Only called by testing helpers such as `test_runtime_upgrade`, never on chain.
+	fn pre_upgrade() -> Result<(), &'static str> { Ok(()) }
+
+	/// Check the state after the upgrade, e.g. that the data was migrated.
+	///
+	/// Only called by testing helpers such as `test_runtime_upgrade`, never on chain.
+	fn post_upgrade() -> Result<(), &'static str> { Ok(()) }
 }
 
 #[impl_for_tuples(30)]
@@ -1158,6 +1296,165 @@ impl OnRuntimeUpgrade for Tuple {
 		for_tuples!( #( weight = weight.saturating_add(Tuple::on_runtime_upgrade()); )* );
 		weight
 	}
+
+	fn pre_upgrade() -> Result<(), &'static str> {
+		for_tuples!( #( Tuple::pre_upgrade()?; )* );
+		Ok(())
+	}
+
+	fn post_upgrade() -> Result<(), &'static str> {
+		for_tuples!( #( Tuple::post_upgrade()?; )* );
+		Ok(())
+	}
+}
+
+/// Runs the runtime upgrade `U` between its `pre_upgrade` and `post_upgrade` checks.
+///
+/// Returns the weight consumed by the upgrade, or the error of the first failing check.
+#[cfg(feature = "std")]
+pub fn test_runtime_upgrade<U: OnRuntimeUpgrade>() -> Result<crate::weights::Weight, &'static str> {
+	U::pre_upgrade()?;
+	let weight = U::on_runtime_upgrade();
+	U::post_upgrade()?;
+	Ok(weight)
+}
+
+/// The storage key postfix of the `PalletVersion` of a pallet.
+pub const PALLET_VERSION_STORAGE_KEY_POSTFIX: &[u8] = b":__PALLET_VERSION__:";
+
+/// The version of a pallet, taken from the version of its crate.
+///
+/// The version the storage of a pallet is at is stored under
+/// `twox_128(storage_prefix) ++ twox_128(PALLET_VERSION_STORAGE_KEY_POSTFIX)`. It is written at
+/// genesis and after every runtime upgrade, once the migrations ran.
+///
+/// As the version is the one of the crate, a pallet must bump the version in its `Cargo.toml`
+/// along with every migration of its storage, so that the migration can be given a version the
+/// storage is not at yet. Pallets sharing the version of the workspace, e.g. all pallets at
+/// `2.0.0-dev`, can't tell their storage versions apart.
+///
+/// Pallets tracking the version of their storage in a storage item of their own, e.g. the
+/// `StorageVersion` of `Releases` of staking, are not mapped to a `PalletVersion`. They keep
+/// running the migrations they check in their own `on_runtime_upgrade`, which must not be wrapped
+/// in a `VersionedMigration`.
+#[derive(
+	Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Encode, Decode, RuntimeDebug, TypeInfo,
+)]
+pub struct PalletVersion {
+	/// The major version of the crate.
+	pub major: u16,
+	/// The minor version of the crate.
+	pub minor: u8,
+	/// The patch version of the crate.
+	pub patch: u8,
+}
+
+impl PalletVersion {
+	/// Creates a new version.
+	pub const fn new(major: u16, minor: u8, patch: u8) -> Self {
+		Self { major, minor, patch }
+	}
+
+	/// The storage key of the version of the pallet with the given storage prefix.
+	pub fn storage_key(storage_prefix: &[u8]) -> [u8; 32] {
+		let mut key = [0; 32];
+		key[..16].copy_from_slice(&sp_io::hashing::twox_128(storage_prefix));
+		key[16..].copy_from_slice(&sp_io::hashing::twox_128(PALLET_VERSION_STORAGE_KEY_POSTFIX));
+		key
+	}
+
+	/// Reads the version the storage of the pallet with the given storage prefix is at.
+	pub fn get_from_storage(storage_prefix: &[u8]) -> Option<Self> {
+		crate::storage::unhashed::get(&Self::storage_key(storage_prefix))
+	}
+
+	/// Writes this version as the one the storage of the pallet with the given prefix is at.
+	pub fn put_into_storage(&self, storage_prefix: &[u8]) {
+		crate::storage::unhashed::put(&Self::storage_key(storage_prefix), self)
+	}
+}
+
+/// Provides the versions of a pallet. Implemented by `decl_storage!` on the module.
+pub trait GetPalletVersion {
+	/// The version of the crate of the pallet, see `crate_to_pallet_version!`.
+	fn current_version() -> PalletVersion;
+
+	/// The version the storage of the pallet is at, `None` if it was never written.
+	///
+	/// This is the `current_version` of the code that last wrote the storage, i.e. of the runtime
+	/// before the upgrade while the migrations run.
+	fn storage_version() -> Option<PalletVersion>;
+}
+
+/// A migration of the storage of `Pallet` to the version returned by `To`.
+///
+/// `To` is the crate version of the pallet that introduced the migration, see `PalletVersion`.
+/// `Migration` runs only if the storage of `Pallet` is at a version lower than `To`. The storage
+/// version is not updated until all migrations ran, so a tuple of migrations to increasing
+/// versions runs every migration the storage did not go through yet, in order:
+///
+/// ```ignore
+/// parameter_types! {
+/// 	pub const V2: PalletVersion = PalletVersion::new(2, 0, 0);
+/// 	pub const V3: PalletVersion = PalletVersion::new(3, 0, 0);
+/// }
+///
+/// pub type Migrations = (
+/// 	VersionedMigration<Staking, V2, staking::migrations::ToV2<Runtime>>,
+/// 	VersionedMigration<Staking, V3, staking::migrations::ToV3<Runtime>>,
+/// );
+/// ```
+///
+/// A pallet without a stored version, i.e. on a chain started before the versions were written,
+/// can't tell which migrations its storage went through, so none of them runs. Its version is
+/// seeded at the end of that runtime upgrade, and the migrations run from the next one.
+pub struct VersionedMigration<Pallet, To, Migration>(PhantomData<(Pallet, To, Migration)>);
+
+impl<Pallet, To, Migration> VersionedMigration<Pallet, To, Migration> where
+	Pallet: GetPalletVersion,
+	To: Get<PalletVersion>,
+	Migration: OnRuntimeUpgrade,
+{
+	/// Whether the storage of `Pallet` still needs to be migrated.
+	fn should_run() -> bool {
+		Pallet::storage_version().map_or(false, |version| version < To::get())
+	}
+}
+
+impl<Pallet, To, Migration> OnRuntimeUpgrade for VersionedMigration<Pallet, To, Migration> where
+	Pallet: GetPalletVersion,
+	To: Get<PalletVersion>,
+	Migration: OnRuntimeUpgrade,
+{
+	fn on_runtime_upgrade() -> crate::weights::Weight {
+		let pallet = sp_std::any::type_name::<Pallet>();
+		if Self::should_run() {
+			crate::debug::info!(
+				target: "runtime::migrations",
+				"Migrating the storage of {} to {:?}",
+				pallet,
+				To::get(),
+			);
+			Migration::on_runtime_upgrade()
+		} else {
+			crate::debug::info!(
+				target: "runtime::migrations",
+				"Skipping the migration of {} to {:?}, the storage is at {:?}",
+				pallet,
+				To::get(),
+				Pallet::storage_version(),
+			);
+			0
+		}
+	}
+
+	fn pre_upgrade() -> Result<(), &'static str> {
+		if Self::should_run() { Migration::pre_upgrade() } else { Ok(()) }
+	}
+
+	fn post_upgrade() -> Result<(), &'static str> {
+		if Self::should_run() { Migration::post_upgrade() } else { Ok(()) }
+	}
 }
 
 /// Off-chain computation trait.
@@ -1181,6 +1478,49 @@ pub trait OffchainWorker<BlockNumber> {
 	fn offchain_worker(_n: BlockNumber) {}
 }
 
+/// The hooks of a pallet declared with `#[frame_support::pallet]`.
+///
+/// Each function is called by the matching `OnInitialize`, `OnFinalize`, `OnRuntimeUpgrade` and
+/// `OffchainWorker` implementation of the pallet, and does nothing by default.
+pub trait Hooks<BlockNumber> {
+	/// The block is being initialized. Return the weight consumed.
+	fn on_initialize(_n: BlockNumber) -> crate::weights::Weight { 0 }
+
+	/// The block is being finalized.
+	fn on_finalize(_n: BlockNumber) {}
+
+	/// The runtime is being upgraded. Return the weight consumed.
+	fn on_runtime_upgrade() -> crate::weights::Weight { 0 }
+
+	/// Run off-chain computation after the block is imported, see `OffchainWorker`.
+	fn offchain_worker(_n: BlockNumber) {}
+}
+
+/// The genesis build of a pallet declared with `#[frame_support::pallet]`.
+///
+/// `T` is the configuration trait of the pallet. `build` is executed within externalities, so the
+/// genesis storage is written using the regular storage APIs of the pallet.
+#[cfg(feature = "std")]
+pub trait GenesisBuild<T>: Default + MaybeSerializeDeserialize {
+	/// Write the genesis storage of the pallet.
+	fn build(&self);
+
+	/// Build the storage of the pallet.
+	fn build_storage(&self) -> Result<sp_runtime::Storage, String> {
+		let mut storage = Default::default();
+		self.assimilate_storage(&mut storage)?;
+		Ok(storage)
+	}
+
+	/// Assimilate the storage of the pallet into pre-existing overlays.
+	fn assimilate_storage(&self, storage: &mut sp_runtime::Storage) -> Result<(), String> {
+		crate::BasicExternalities::execute_with_storage(storage, || {
+			self.build();
+			Ok(())
+		})
+	}
+}
+
 pub mod schedule {
 	use super::*;
 
@@ -1259,6 +1599,149 @@ pub mod schedule {
 	}
 }
 
+/// Traits for dealing with a set of fungible assets, each of them identified by an asset id.
+///
+/// Unlike `Currency`, these traits have no notion of imbalances: every operation is applied
+/// directly to the balance of an account and to the total issuance of the asset.
+pub mod fungibles {
+	use super::*;
+
+	/// One of a number of consequences of withdrawing a fungible from an account.
+	#[derive(Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+	pub enum WithdrawConsequence<Balance> {
+		/// Withdraw could not happen since the amount to be withdrawn is less than the total funds
+		/// in the account.
+		NoFunds,
+		/// The withdraw would mean the account dying when it needs to exist.
+		WouldDie,
+		/// The asset is unknown. Usually because an `AssetId` has been presented which doesn't
+		/// exist on the system.
+		UnknownAsset,
+		/// There has been an underflow in the system. This is indicative of a corrupt state and
+		/// likely unrecoverable.
+		Underflow,
+		/// Account balance would reduce to zero, potentially destroying it. The parameter is the
+		/// amount of balance which is destroyed.
+		ReducedToZero(Balance),
+		/// Account continued in existence.
+		Success,
+	}
+
+	impl<Balance: Zero> WithdrawConsequence<Balance> {
+		/// Convert the type into a `Result` with `DispatchError` as the error or the additional
+		/// `Balance` by which the account will be reduced.
+		pub fn into_result(self) -> Result<Balance, DispatchError> {
+			use WithdrawConsequence::*;
+			match self {
+				NoFunds => Err(DispatchError::Other("Insufficient funds")),
+				WouldDie => Err(DispatchError::Other("Account would die")),
+				UnknownAsset => Err(DispatchError::Other("Unknown asset")),
+				Underflow => Err(DispatchError::Other("Arithmetic underflow")),
+				ReducedToZero(result) => Ok(result),
+				Success => Ok(Zero::zero()),
+			}
+		}
+	}
+
+	/// One of a number of consequences of depositing a fungible into an account.
+	#[derive(Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+	pub enum DepositConsequence {
+		/// Deposit couldn't happen due to the amount being too low. This is usually because the
+		/// account doesn't yet exist and the deposit wouldn't bring it to at least the minimum
+		/// needed for existence.
+		BelowMinimum,
+		/// Deposit cannot happen since the account cannot be created.
+		CannotCreate,
+		/// The asset is unknown. Usually because an `AssetId` has been presented which doesn't
+		/// exist on the system.
+		UnknownAsset,
+		/// An overflow would occur. This is practically unexpected, but could happen in test
+		/// systems with extremely small balance types or balances that approach the max value of
+		/// the balance type.
+		Overflow,
+		/// Account continued in existence.
+		Success,
+	}
+
+	impl DepositConsequence {
+		/// Convert the type into a `Result` with `DispatchError` as the error.
+		pub fn into_result(self) -> DispatchResult {
+			use DepositConsequence::*;
+			match self {
+				BelowMinimum => Err(DispatchError::Other("Deposit below minimum balance")),
+				CannotCreate => Err(DispatchError::Other("Account cannot be created")),
+				UnknownAsset => Err(DispatchError::Other("Unknown asset")),
+				Overflow => Err(DispatchError::Other("Arithmetic overflow")),
+				Success => Ok(()),
+			}
+		}
+	}
+
+	/// Trait for providing balance-inspection access to a set of named fungible assets.
+	pub trait Inspect<AccountId> {
+		/// Means of identifying one asset class from another.
+		type AssetId: Parameter + Copy;
+
+		/// Scalar type for representing balance of an account.
+		type Balance: AtLeast32Bit + Parameter + Copy + Default;
+
+		/// The total amount of issuance in the system.
+		fn total_issuance(asset: Self::AssetId) -> Self::Balance;
+
+		/// The minimum balance any single account may have.
+		fn minimum_balance(asset: Self::AssetId) -> Self::Balance;
+
+		/// Get the `asset` balance of `who`.
+		fn balance(asset: Self::AssetId, who: &AccountId) -> Self::Balance;
+
+		/// Returns `Success` if the balance of `who` may be increased by `amount` of `asset`.
+		fn can_deposit(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DepositConsequence;
+
+		/// Returns `Success` if the balance of `who` may be decreased by `amount` of `asset`,
+		/// otherwise the consequence.
+		fn can_withdraw(
+			asset: Self::AssetId,
+			who: &AccountId,
+			amount: Self::Balance,
+		) -> WithdrawConsequence<Self::Balance>;
+	}
+
+	/// Trait for providing a set of named fungible assets which can be created and destroyed.
+	pub trait Mutate<AccountId>: Inspect<AccountId> {
+		/// Increase the `asset` balance of `who` by `amount`, and the total issuance with it.
+		///
+		/// If it cannot be done for some reason (e.g. because the account cannot be created or an
+		/// overflow) then an `Err` is returned.
+		fn deposit(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+		/// Decrease the `asset` balance of `who` by up to `amount`, and the total issuance with it.
+		///
+		/// If the remaining balance would be below the minimum balance, the account is removed,
+		/// and all of its balance withdrawn. Returns the amount that has actually been withdrawn,
+		/// which is never less than `amount`. If `who` does not have `amount`, nothing is
+		/// withdrawn and an `Err` is returned.
+		fn withdraw(
+			asset: Self::AssetId,
+			who: &AccountId,
+			amount: Self::Balance,
+		) -> result::Result<Self::Balance, DispatchError>;
+	}
+
+	/// Trait for providing a set of named fungible assets which can only be transferred.
+	pub trait Transfer<AccountId>: Inspect<AccountId> {
+		/// Transfer `amount` of `asset` from `source` to `dest`.
+		///
+		/// If the remaining balance of `source` would be below the minimum balance, all of its
+		/// balance is transferred. Returns the amount that has actually been transferred.
+		fn transfer(
+			asset: Self::AssetId,
+			source: &AccountId,
+			dest: &AccountId,
+			amount: Self::Balance,
+		) -> result::Result<Self::Balance, DispatchError>;
+	}
+}
+
 /// Some sort of check on the origin is performed by this object.
 pub trait EnsureOrigin<OuterOrigin> {
 	/// A return type.
@@ -1298,4 +1781,120 @@ mod tests {
 		assert_eq!(<(Test, Test)>::on_initialize(0), 20);
 		assert_eq!(<(Test, Test)>::on_runtime_upgrade(), 40);
 	}
+
+	#[test]
+	fn max_encoded_len_matches_worst_case_encoding() {
+		assert_eq!(u64::max_encoded_len(), u64::max_value().encode().len());
+		assert_eq!(Compact::<u32>::max_encoded_len(), Compact(u32::max_value()).encode().len());
+		assert_eq!(Compact::<u128>::max_encoded_len(), Compact(u128::max_value()).encode().len());
+		assert_eq!(<Option<u32>>::max_encoded_len(), Some(0u32).encode().len());
+		assert_eq!(<result::Result<u8, u64>>::max_encoded_len(), 9);
+		assert_eq!(<(u8, u16, [u32; 4])>::max_encoded_len(), 1 + 2 + 16);
+		assert_eq!(sp_core::H256::max_encoded_len(), sp_core::H256::default().encode().len());
+		assert_eq!(<()>::max_encoded_len(), 0);
+	}
+	thread_local! {
+		static MIGRATIONS: sp_std::cell::RefCell<Vec<&'static str>> = Default::default();
+	}
+
+	struct Pallet;
+	impl GetPalletVersion for Pallet {
+		fn current_version() -> PalletVersion { PalletVersion::new(3, 0, 0) }
+		fn storage_version() -> Option<PalletVersion> { PalletVersion::get_from_storage(b"Pallet") }
+	}
+
+	struct ToV2;
+	impl OnRuntimeUpgrade for ToV2 {
+		fn on_runtime_upgrade() -> crate::weights::Weight {
+			MIGRATIONS.with(|m| m.borrow_mut().push("v2"));
+			2
+		}
+
+		fn pre_upgrade() -> Result<(), &'static str> {
+			MIGRATIONS.with(|m| m.borrow_mut().push("pre v2"));
+			Ok(())
+		}
+
+		fn post_upgrade() -> Result<(), &'static str> {
+			MIGRATIONS.with(|m| m.borrow_mut().push("post v2"));
+			Ok(())
+		}
+	}
+
+	struct ToV3;
+	impl OnRuntimeUpgrade for ToV3 {
+		fn on_runtime_upgrade() -> crate::weights::Weight {
+			MIGRATIONS.with(|m| m.borrow_mut().push("v3"));
+			3
+		}
+
+		fn post_upgrade() -> Result<(), &'static str> {
+			Err("v3 failed")
+		}
+	}
+
+	crate::parameter_types! {
+		const V2: PalletVersion = PalletVersion::new(2, 0, 0);
+		const V3: PalletVersion = PalletVersion::new(3, 0, 0);
+	}
+
+	type Migrations = (
+		VersionedMigration<Pallet, V2, ToV2>,
+		VersionedMigration<Pallet, V3, ToV3>,
+	);
+
+	fn take_migrations() -> Vec<&'static str> {
+		MIGRATIONS.with(|m| m.borrow_mut().drain(..).collect())
+	}
+
+	#[test]
+	fn pallet_version_is_stored_under_the_storage_prefix() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			assert_eq!(Pallet::storage_version(), None);
+			PalletVersion::new(1, 2, 3).put_into_storage(b"Pallet");
+			assert_eq!(Pallet::storage_version(), Some(PalletVersion::new(1, 2, 3)));
+			assert_eq!(
+				sp_io::storage::get(&PalletVersion::storage_key(b"Pallet")),
+				Some(PalletVersion::new(1, 2, 3).encode()),
+			);
+			assert!(PalletVersion::new(1, 2, 3) < PalletVersion::new(2, 0, 0));
+		});
+	}
+
+	#[test]
+	fn versioned_migrations_only_run_from_older_versions() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			// Without a stored version, no migration runs.
+			assert_eq!(Migrations::on_runtime_upgrade(), 0);
+			assert!(take_migrations().is_empty());
+
+			// Otherwise every migration from a newer version runs, in order.
+			PalletVersion::new(1, 0, 0).put_into_storage(b"Pallet");
+			assert_eq!(Migrations::on_runtime_upgrade(), 5);
+			assert_eq!(take_migrations(), vec!["v2", "v3"]);
+
+			PalletVersion::new(2, 0, 0).put_into_storage(b"Pallet");
+			assert_eq!(Migrations::on_runtime_upgrade(), 3);
+			assert_eq!(take_migrations(), vec!["v3"]);
+
+			PalletVersion::new(3, 0, 0).put_into_storage(b"Pallet");
+			assert_eq!(Migrations::on_runtime_upgrade(), 0);
+			assert!(take_migrations().is_empty());
+		});
+	}
+
+	#[test]
+	fn test_runtime_upgrade_checks_the_migrations_that_run() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			PalletVersion::new(2, 0, 0).put_into_storage(b"Pallet");
+			assert_eq!(test_runtime_upgrade::<VersionedMigration<Pallet, V2, ToV2>>(), Ok(0));
+			assert!(take_migrations().is_empty());
+			assert_eq!(test_runtime_upgrade::<Migrations>(), Err("v3 failed"));
+			assert_eq!(take_migrations(), vec!["v3"]);
+
+			PalletVersion::new(1, 0, 0).put_into_storage(b"Pallet");
+			assert_eq!(test_runtime_upgrade::<VersionedMigration<Pallet, V2, ToV2>>(), Ok(2));
+			assert_eq!(take_migrations(), vec!["pre v2", "v2", "post v2"]);
+		});
+	}
 }
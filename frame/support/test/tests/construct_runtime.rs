@@ -0,0 +1,175 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the module indices given explicitly in `construct_runtime!`.
+
+#![recursion_limit="128"]
+
+use codec::Encode;
+use frame_support::traits::{ModuleToIndex as _, ModuleLayout, GetPalletVersion, PalletVersion};
+use frame_support::metadata::RuntimeMetadata;
+use sp_runtime::{generic, traits::{BlakeTwo256, Verify}, DispatchError};
+use sp_core::{H256, sr25519};
+
+mod system;
+
+mod module1 {
+	use super::*;
+
+	pub trait Trait: system::Trait {}
+
+	frame_support::decl_module! {
+		pub struct Module<T: Trait> for enum Call
+			where origin: <T as system::Trait>::Origin
+		{
+			#[weight = 0]
+			pub fn fail(_origin) -> frame_support::dispatch::DispatchResult {
+				Err(Error::<T>::Something.into())
+			}
+		}
+	}
+
+	frame_support::decl_error! {
+		pub enum Error for Module<T: Trait> {
+			Something
+		}
+	}
+
+	frame_support::decl_storage! {
+		trait Store for Module<T: Trait> as Module1 {}
+	}
+}
+
+mod module2 {
+	use super::*;
+
+	pub trait Trait: system::Trait {}
+
+	frame_support::decl_module! {
+		pub struct Module<T: Trait> for enum Call
+			where origin: <T as system::Trait>::Origin
+		{
+			#[weight = 0]
+			pub fn fail(_origin) -> frame_support::dispatch::DispatchResult {
+				Err(Error::<T>::Something.into())
+			}
+		}
+	}
+
+	frame_support::decl_error! {
+		pub enum Error for Module<T: Trait> {
+			Something
+		}
+	}
+}
+
+impl module1::Trait for Runtime {}
+impl module2::Trait for Runtime {}
+
+pub type Signature = sr25519::Signature;
+pub type AccountId = <Signature as Verify>::Signer;
+pub type BlockNumber = u64;
+
+impl system::Trait for Runtime {
+	type Hash = H256;
+	type Origin = Origin;
+	type BlockNumber = BlockNumber;
+	type AccountId = AccountId;
+	type Event = Event;
+	type ModuleToIndex = ModuleToIndex;
+}
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event<T>},
+		Module1: module1::{Module, Call, Storage} = 5,
+		Module2: module2::{Module, Call},
+	}
+);
+
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, Call, Signature, ()>;
+
+#[test]
+fn module_indices_follow_explicit_indices() {
+	assert_eq!(ModuleToIndex::module_to_index::<System>(), Some(0));
+	assert_eq!(ModuleToIndex::module_to_index::<Module1>(), Some(5));
+	assert_eq!(ModuleToIndex::module_to_index::<Module2>(), Some(6));
+
+	assert_eq!(
+		Module1::fail(system::Origin::<Runtime>::Root.into()),
+		Err(DispatchError::Module { index: 5, error: 0, message: Some("Something") }),
+	);
+	assert_eq!(
+		Module2::fail(system::Origin::<Runtime>::Root.into()),
+		Err(DispatchError::Module { index: 6, error: 0, message: Some("Something") }),
+	);
+}
+
+#[test]
+fn calls_are_encoded_with_explicit_indices() {
+	assert_eq!(Call::Module1(module1::Call::fail()).encode()[0], 5);
+	// Without an explicit index, the call keeps its position in the outer `Call` enum.
+	assert_eq!(Call::Module2(module2::Call::fail()).encode()[0], 2);
+}
+
+#[test]
+fn modules_layout_and_metadata_use_explicit_indices() {
+	assert_eq!(
+		ModuleToIndex::modules_layout(),
+		vec![
+			ModuleLayout { name: b"System".to_vec(), index: 0, storage_prefix: None },
+			ModuleLayout {
+				name: b"Module1".to_vec(),
+				index: 5,
+				storage_prefix: Some(b"Module1".to_vec()),
+			},
+			ModuleLayout { name: b"Module2".to_vec(), index: 6, storage_prefix: None },
+		],
+	);
+
+	let metadata = match Runtime::metadata_v12().1 {
+		RuntimeMetadata::V12(metadata) => metadata,
+		_ => panic!("`metadata_v12` returns V12"),
+	};
+	let indices: Vec<_> = metadata.modules.iter().map(|module| module.index).collect();
+	assert_eq!(indices, vec![0, 5, 6]);
+	let (module, error) = metadata.module_error(5, 0).unwrap();
+	assert_eq!((module.name.as_str(), error.name.as_str()), ("Module1", "Something"));
+}
+
+#[test]
+fn pallet_versions_follow_the_crate_version() {
+	let version = PalletVersion::new(
+		env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+		env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+		env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+	);
+	assert_eq!(frame_support::crate_to_pallet_version!(), version);
+	assert_eq!(Module1::current_version(), version);
+	assert_eq!(ModuleToIndex::pallet_versions(), vec![("Module1", version)]);
+
+	sp_io::TestExternalities::default().execute_with(|| {
+		assert_eq!(Module1::storage_version(), None);
+		version.put_into_storage(b"Module1");
+		assert_eq!(Module1::storage_version(), Some(version));
+	});
+}
@@ -0,0 +1,17 @@
+use frame_support::construct_runtime;
+
+construct_runtime! {
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call},
+		Balances: balances::{Module, Call} = 3,
+		Timestamp: timestamp::{Module},
+		Sudo: sudo::{Module, Call},
+		Utility: utility::{Module, Call},
+	}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use frame_support::construct_runtime;
+
+construct_runtime! {
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module} = 1,
+		Balances: balances::{Module, Call} = 1,
+	}
+}
+
+fn main() {}
@@ -136,3 +136,17 @@ fn check_module2_error_type() {
 		Err(DispatchError::Module { index: 2, error: 0, message: Some("Something") }),
 	);
 }
+
+#[test]
+fn module_error_name_resolves_encoded_errors() {
+	use codec::{Encode, Decode};
+	use frame_support::error::module_error_name;
+
+	let error = Module2::fail(system::Origin::<Runtime>::Root.into()).unwrap_err();
+	let decoded = DispatchError::decode(&mut &error.encode()[..]).unwrap();
+	assert_eq!(decoded, DispatchError::Module { index: 2, error: 0, message: None });
+
+	assert_eq!(module_error_name::<Module2, ModuleToIndex>(&decoded), Some("Something"));
+	assert_eq!(module_error_name::<Module1_1, ModuleToIndex>(&decoded), None);
+	assert_eq!(module_error_name::<Module2, ModuleToIndex>(&DispatchError::BadOrigin), None);
+}
@@ -101,6 +101,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[ " Hello, this is doc!" ]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBU32"),
@@ -110,6 +111,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("U32MYDEF"),
@@ -119,6 +121,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBU32MYDEF"),
@@ -128,6 +131,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GETU32"),
@@ -137,6 +141,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGETU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETU32"),
@@ -146,6 +151,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GETU32WITHCONFIG"),
@@ -155,6 +161,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGETU32WITHCONFIG(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETU32WITHCONFIG"),
@@ -164,6 +171,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETU32WITHCONFIG(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GETU32MYDEF"),
@@ -173,6 +181,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGETU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETU32MYDEF"),
@@ -182,6 +191,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GETU32WITHCONFIGMYDEF"),
@@ -191,6 +201,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGETU32WITHCONFIGMYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETU32WITHCONFIGMYDEF"),
@@ -200,6 +211,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETU32WITHCONFIGMYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETU32WITHCONFIGMYDEFOPT"),
@@ -209,6 +221,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETU32WITHCONFIGMYDEFOPT(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GetU32WithBuilder"),
@@ -218,6 +231,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGetU32WithBuilder(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GetOptU32WithBuilderSome"),
@@ -227,6 +241,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGetOptU32WithBuilderSome(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GetOptU32WithBuilderNone"),
@@ -236,6 +251,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGetOptU32WithBuilderNone(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("MAPU32"),
@@ -250,6 +266,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructMAPU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBMAPU32"),
@@ -264,6 +281,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBMAPU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("MAPU32MYDEF"),
@@ -278,6 +296,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructMAPU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBMAPU32MYDEF"),
@@ -292,6 +311,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBMAPU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GETMAPU32"),
@@ -306,6 +326,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGETMAPU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETMAPU32"),
@@ -320,6 +341,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETMAPU32(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("GETMAPU32MYDEF"),
@@ -334,6 +356,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructGETMAPU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("PUBGETMAPU32MYDEF"),
@@ -348,6 +371,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructPUBGETMAPU32MYDEF(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("COMPLEXTYPE1"),
@@ -357,6 +381,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructCOMPLEXTYPE1(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("COMPLEXTYPE2"),
@@ -366,6 +391,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructCOMPLEXTYPE2(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 				StorageEntryMetadata {
 					name: DecodeDifferent::Encode("COMPLEXTYPE3"),
@@ -375,6 +401,7 @@ mod tests {
 						DefaultByteGetter(&__GetByteStructCOMPLEXTYPE3(PhantomData::<TraitImpl>))
 					),
 					documentation: DecodeDifferent::Encode(&[]),
+					max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 				},
 			]
 		),
@@ -477,6 +504,7 @@ mod test3 {
 mod test_append_and_len {
 	use sp_io::TestExternalities;
 	use codec::{Encode, Decode};
+	use frame_support::type_info::TypeInfo;
 
 	pub trait Trait {
 		type Origin;
@@ -487,7 +515,7 @@ mod test_append_and_len {
 		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
 	}
 
-	#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+	#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 	struct NoDef(u32);
 
 	frame_support::decl_storage! {
@@ -21,7 +21,7 @@ use frame_support::{
 	Parameter, traits::Get, parameter_types,
 	metadata::{
 		DecodeDifferent, StorageMetadata, StorageEntryModifier, StorageEntryType, DefaultByteGetter,
-		StorageEntryMetadata, StorageHasher,
+		StorageEntryMetadata, StorageHasher, FnEncode,
 	},
 	StorageValue, StorageMap, StorageDoubleMap,
 };
@@ -383,6 +383,7 @@ const EXPECTED_METADATA: StorageMetadata = StorageMetadata {
 					)
 				),
 				documentation: DecodeDifferent::Encode(&[]),
+				max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 			},
 			StorageEntryMetadata {
 				name: DecodeDifferent::Encode("Map"),
@@ -401,6 +402,7 @@ const EXPECTED_METADATA: StorageMetadata = StorageMetadata {
 					)
 				),
 				documentation: DecodeDifferent::Encode(&[]),
+				max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 			},
 			StorageEntryMetadata {
 				name: DecodeDifferent::Encode("DoubleMap"),
@@ -420,6 +422,7 @@ const EXPECTED_METADATA: StorageMetadata = StorageMetadata {
 					)
 				),
 				documentation: DecodeDifferent::Encode(&[]),
+				max_size: DecodeDifferent::Encode(FnEncode(|| None)),
 			}
 		]
 	)
@@ -0,0 +1,85 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the `MaxEncodedLen` derive.
+
+use codec::{Compact, Encode};
+use frame_support::{parameter_types, storage::BoundedVec, traits::MaxEncodedLen};
+use std::convert::TryFrom;
+
+parameter_types! {
+	pub const Three: u32 = 3;
+}
+
+#[derive(Encode, MaxEncodedLen)]
+struct Unit;
+
+#[derive(Encode, MaxEncodedLen)]
+struct Tuple(u8, u16);
+
+#[derive(Encode, MaxEncodedLen)]
+struct Named {
+	a: u32,
+	#[codec(compact)]
+	b: u64,
+	#[codec(skip)]
+	#[allow(dead_code)]
+	c: u128,
+	d: BoundedVec<u16, Three>,
+}
+
+#[derive(Encode, MaxEncodedLen)]
+struct Generic<T> {
+	t: T,
+	u: Option<T>,
+}
+
+#[derive(Encode, MaxEncodedLen)]
+enum Enum {
+	A,
+	B(u32),
+	C { named: Named, tuple: Tuple },
+}
+
+#[test]
+fn unit_and_tuple_structs() {
+	assert_eq!(Unit::max_encoded_len(), 0);
+	assert_eq!(Tuple::max_encoded_len(), 3);
+	assert_eq!(Tuple(u8::max_value(), u16::max_value()).encode().len(), 3);
+}
+
+#[test]
+fn named_struct_respects_codec_attributes() {
+	let named = Named {
+		a: u32::max_value(),
+		b: u64::max_value(),
+		c: 0,
+		d: BoundedVec::try_from(vec![1, 2, 3]).unwrap(),
+	};
+	assert_eq!(Named::max_encoded_len(), named.encode().len());
+	assert_eq!(Named::max_encoded_len(), 4 + Compact::<u64>::max_encoded_len() + 1 + 3 * 2);
+}
+
+#[test]
+fn generic_struct() {
+	assert_eq!(Generic::<u64>::max_encoded_len(), 8 + 9);
+	assert_eq!(Generic::<Tuple>::max_encoded_len(), 3 + 4);
+}
+
+#[test]
+fn enum_is_index_plus_largest_variant() {
+	assert_eq!(Enum::max_encoded_len(), 1 + Named::max_encoded_len() + Tuple::max_encoded_len());
+}
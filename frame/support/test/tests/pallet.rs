@@ -0,0 +1,352 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+#![recursion_limit="128"]
+
+use sp_runtime::{
+	generic, BuildStorage, BuildModuleGenesisStorage, DispatchError,
+	traits::{BlakeTwo256, Block as _, Verify},
+};
+use frame_support::{
+	StorageValue, StorageMap, StorageDoubleMap, StorageNMap, parameter_types,
+	traits::OnInitialize, dispatch::ModuleErrorMetadata,
+};
+use sp_core::{H256, sr25519};
+
+mod system;
+
+// The pallets expect the system module to be named `frame_system`.
+use system as frame_system;
+
+parameter_types! {
+	pub const MyGetParam: u32 = 10;
+	pub const MyDefault: u32 = 3;
+}
+
+// The pallet declared with the attribute macro.
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Trait: frame_system::Trait {
+		/// Some constant.
+		#[pallet::constant]
+		type MyGetParam: Get<u32>;
+		type Balance: Parameter + Default;
+		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::storage_prefix = "Example"]
+	#[pallet::generate_store(pub(crate) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Trait> Hooks<<T as frame_system::Trait>::BlockNumber> for Pallet<T> {
+		fn on_initialize(_: <T as frame_system::Trait>::BlockNumber) -> Weight {
+			10
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Trait> Pallet<T> {
+		/// Doc comment put in metadata.
+		#[pallet::weight(10)]
+		pub fn foo(
+			origin: <T as frame_system::Trait>::Origin,
+			#[pallet::compact] foo: u32,
+			bar: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			frame_system::ensure_root(origin)?;
+			<Value>::put(foo);
+			Self::deposit_event(Event::Proposed(bar));
+			Ok(().into())
+		}
+
+		/// Doc comment put in metadata.
+		#[pallet::weight(1)]
+		pub fn fail(_origin: <T as frame_system::Trait>::Origin) -> DispatchResultWithPostInfo {
+			Err(Error::<T>::InsufficientProposersBalance.into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::Balance = "Balance")]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Trait> {
+		/// Doc comment put in metadata.
+		Proposed(T::Balance),
+		Spending(T::Balance),
+		Something(u32),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Doc comment put in metadata.
+		InsufficientProposersBalance,
+	}
+
+	/// Doc comment put in metadata.
+	#[pallet::storage]
+	#[pallet::getter(fn value)]
+	pub type Value = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type Map = StorageMap<_, Blake2_128Concat, u8, u16, ValueQuery>;
+
+	#[pallet::storage]
+	pub type Map2 = StorageMap<_, Twox64Concat, u16, u32>;
+
+	#[pallet::storage]
+	pub type DoubleMap = StorageDoubleMap<_, Blake2_128Concat, u8, Twox64Concat, u16, u32>;
+
+	#[pallet::storage]
+	pub type NMap = StorageNMap<
+		_,
+		(NMapKey<Blake2_128Concat, u8>, NMapKey<Twox64Concat, u16>),
+		u32,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn generic_value)]
+	pub type GenericValue<T> = StorageValue<_, <T as frame_system::Trait>::BlockNumber>;
+
+	#[pallet::storage]
+	pub type WithDefault = StorageValue<_, u32, ValueQuery, MyDefault>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {
+		pub value: u32,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Trait> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			<Value>::put(self.value);
+		}
+	}
+}
+
+// The same pallet declared with the decl_* macros.
+pub mod pallet_old {
+	use frame_support::{
+		decl_module, decl_storage, decl_event, decl_error, Parameter,
+		traits::Get, weights::Weight, dispatch::DispatchResultWithPostInfo,
+	};
+	use super::*;
+
+	pub trait Trait: frame_system::Trait {
+		/// Some constant.
+		type MyGetParam: Get<u32>;
+		type Balance: Parameter + Default;
+		type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Example {
+			/// Doc comment put in metadata.
+			pub Value get(fn value) config(): Option<u32>;
+			pub Map: map hasher(blake2_128_concat) u8 => u16;
+			pub Map2: map hasher(twox_64_concat) u16 => Option<u32>;
+			pub DoubleMap: double_map hasher(blake2_128_concat) u8, hasher(twox_64_concat) u16
+				=> Option<u32>;
+			pub NMap: nmap hasher(blake2_128_concat) u8, hasher(twox_64_concat) u16 => Option<u32>;
+			pub GenericValue get(fn generic_value):
+				Option<<T as frame_system::Trait>::BlockNumber>;
+			pub WithDefault: u32 = 3;
+		}
+	}
+
+	decl_event!(
+		pub enum Event<T> where Balance = <T as Trait>::Balance {
+			/// Doc comment put in metadata.
+			Proposed(Balance),
+			Spending(Balance),
+			Something(u32),
+		}
+	);
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where
+			origin: <T as frame_system::Trait>::Origin,
+			system = frame_system
+		{
+			/// Some constant.
+			const MyGetParam: u32 = T::MyGetParam::get();
+
+			type Error = Error<T>;
+
+			fn deposit_event() = default;
+
+			fn on_initialize(_n: <T as frame_system::Trait>::BlockNumber) -> Weight {
+				10
+			}
+
+			/// Doc comment put in metadata.
+			#[weight = 10]
+			pub fn foo(origin, #[compact] foo: u32, bar: T::Balance) -> DispatchResultWithPostInfo {
+				frame_system::ensure_root(origin)?;
+				<Value>::put(foo);
+				Self::deposit_event(RawEvent::Proposed(bar));
+				Ok(().into())
+			}
+
+			/// Doc comment put in metadata.
+			#[weight = 1]
+			pub fn fail(_origin) -> DispatchResultWithPostInfo {
+				Err(Error::<T>::InsufficientProposersBalance.into())
+			}
+		}
+	}
+
+	decl_error! {
+		pub enum Error for Module<T: Trait> {
+			/// Doc comment put in metadata.
+			InsufficientProposersBalance,
+		}
+	}
+}
+
+impl pallet::Trait for Runtime {
+	type MyGetParam = MyGetParam;
+	type Balance = u64;
+	type Event = Event;
+}
+
+impl pallet_old::Trait for Runtime {
+	type MyGetParam = MyGetParam;
+	type Balance = u64;
+	type Event = Event;
+}
+
+pub type Signature = sr25519::Signature;
+pub type AccountId = <Signature as Verify>::Signer;
+pub type BlockNumber = u64;
+pub type Index = u64;
+
+impl system::Trait for Runtime {
+	type Hash = H256;
+	type Origin = Origin;
+	type BlockNumber = BlockNumber;
+	type AccountId = AccountId;
+	type Event = Event;
+	type ModuleToIndex = ModuleToIndex;
+}
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event<T>},
+		Example: pallet::{Module, Call, Storage, Event<T>, Config},
+		ExampleOld: pallet_old::{Module, Call, Storage, Event<T>},
+	}
+);
+
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, Call, Signature, ()>;
+
+#[test]
+fn metadata_is_the_one_of_decl_macros() {
+	assert_eq!(Example::storage_metadata(), ExampleOld::storage_metadata());
+	assert_eq!(Example::call_functions(), ExampleOld::call_functions());
+	assert_eq!(Example::module_constants_metadata(), ExampleOld::module_constants_metadata());
+	assert_eq!(
+		<Example as ModuleErrorMetadata>::metadata(),
+		<ExampleOld as ModuleErrorMetadata>::metadata(),
+	);
+	assert_eq!(pallet::Event::<Runtime>::metadata(), pallet_old::Event::<Runtime>::metadata());
+}
+
+#[test]
+fn storage_keys_are_the_ones_of_decl_macros() {
+	assert_eq!(pallet::Value::hashed_key(), pallet_old::Value::hashed_key());
+	assert_eq!(pallet::Map::hashed_key_for(1u8), pallet_old::Map::hashed_key_for(1u8));
+	assert_eq!(pallet::Map2::hashed_key_for(1u16), pallet_old::Map2::hashed_key_for(1u16));
+	assert_eq!(
+		pallet::DoubleMap::hashed_key_for(1u8, 2u16),
+		pallet_old::DoubleMap::hashed_key_for(1u8, 2u16),
+	);
+	assert_eq!(
+		pallet::NMap::hashed_key_for((1u8, 2u16)),
+		pallet_old::NMap::hashed_key_for((1u8, 2u16)),
+	);
+	assert_eq!(
+		pallet::GenericValue::<Runtime>::hashed_key(),
+		pallet_old::GenericValue::<Runtime>::hashed_key(),
+	);
+	assert_eq!(pallet::WithDefault::hashed_key(), pallet_old::WithDefault::hashed_key());
+}
+
+#[test]
+fn genesis_config_is_the_one_of_decl_macros() {
+	use pallet::__InherentHiddenInstance as Instance;
+	use pallet_old::__InherentHiddenInstance as OldInstance;
+
+	let mut storage = Default::default();
+	<pallet::GenesisConfig as BuildModuleGenesisStorage<Runtime, Instance>>
+		::build_module_genesis_storage(&pallet::GenesisConfig { value: 3 }, &mut storage)
+		.unwrap();
+	let mut old_storage = Default::default();
+	<pallet_old::GenesisConfig as BuildModuleGenesisStorage<Runtime, OldInstance>>
+		::build_module_genesis_storage(&pallet_old::GenesisConfig { value: 3 }, &mut old_storage)
+		.unwrap();
+
+	assert_eq!(storage.top, old_storage.top);
+}
+
+#[test]
+fn pallet_works() {
+	let storage = GenesisConfig { pallet: Some(pallet::GenesisConfig { value: 3 }) }
+		.build_storage()
+		.unwrap();
+
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		assert_eq!(Example::value(), Some(3));
+		assert_eq!(pallet::WithDefault::get(), 3);
+		assert_eq!(Example::generic_value(), None);
+
+		assert_eq!(<Example as OnInitialize<BlockNumber>>::on_initialize(1), 10);
+
+		assert!(Example::foo(system::Origin::<Runtime>::Root.into(), 5, 2).is_ok());
+		assert_eq!(Example::value(), Some(5));
+
+		assert_eq!(
+			Example::fail(system::Origin::<Runtime>::Root.into()).map_err(|e| e.error),
+			Err(DispatchError::Module {
+				index: 1,
+				error: 0,
+				message: Some("InsufficientProposersBalance"),
+			}),
+		);
+	});
+}
+
+#[test]
+fn event_derives_no_bound() {
+	let event = pallet::Event::<Runtime>::Proposed(3);
+
+	assert_eq!(event.clone(), event);
+	assert!(event != pallet::Event::<Runtime>::Spending(3));
+	assert_eq!(format!("{:?}", event), "Proposed(3)");
+	assert_eq!(format!("{:?}", pallet::Event::<Runtime>::Something(2)), "Something(2)");
+}
@@ -0,0 +1,252 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use frame_support::{
+	StorageMap, StorageValue, transactional,
+	dispatch::{DispatchError, DispatchResult},
+	storage::{with_transaction, TransactionOutcome::*},
+};
+use sp_core::{H256, sr25519};
+use sp_io::TestExternalities;
+use sp_runtime::{generic, traits::{BlakeTwo256, Block as _, Verify}};
+
+mod system;
+
+mod module {
+	use super::*;
+
+	pub trait Trait: system::Trait {}
+
+	frame_support::decl_module! {
+		pub struct Module<T: Trait> for enum Call
+			where origin: <T as system::Trait>::Origin
+		{
+			#[weight = 0]
+			#[transactional]
+			pub fn value_commits(_origin, v: u32) {
+				Value::set(v);
+			}
+
+			#[weight = 0]
+			#[transactional]
+			pub fn value_rollbacks(_origin, v: u32) -> DispatchResult {
+				Value::set(v);
+				Err(DispatchError::Other("nah"))
+			}
+		}
+	}
+
+	frame_support::decl_storage! {
+		trait Store for Module<T: Trait> as StorageTransactions {
+			pub Value: u32;
+			pub Map: map hasher(twox_64_concat) String => u32;
+		}
+	}
+}
+
+use module::{Map, Value};
+
+impl module::Trait for Runtime {}
+
+pub type Signature = sr25519::Signature;
+pub type AccountId = <Signature as Verify>::Signer;
+pub type BlockNumber = u64;
+
+impl system::Trait for Runtime {
+	type Hash = H256;
+	type Origin = Origin;
+	type BlockNumber = BlockNumber;
+	type AccountId = AccountId;
+	type Event = Event;
+	type ModuleToIndex = ModuleToIndex;
+}
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event<T>},
+		Transactional: module::{Module, Call, Storage},
+	}
+);
+
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, Call, Signature, ()>;
+
+#[test]
+fn storage_transaction_basic_commit() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(Value::get(), 0);
+		assert!(!Map::contains_key("val0"));
+
+		with_transaction(|| {
+			Value::set(99);
+			Map::insert("val0", 99);
+			assert_eq!(Value::get(), 99);
+			assert_eq!(Map::get("val0"), 99);
+			Commit(())
+		});
+
+		assert_eq!(Value::get(), 99);
+		assert_eq!(Map::get("val0"), 99);
+	});
+}
+
+#[test]
+fn storage_transaction_basic_rollback() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(Value::get(), 0);
+		assert_eq!(Map::get("val0"), 0);
+
+		with_transaction(|| {
+			Value::set(99);
+			Map::insert("val0", 99);
+			assert_eq!(Value::get(), 99);
+			assert_eq!(Map::get("val0"), 99);
+			Rollback(())
+		});
+
+		assert_eq!(Value::get(), 0);
+		assert_eq!(Map::get("val0"), 0);
+	});
+}
+
+#[test]
+fn storage_transaction_rollback_then_commit() {
+	TestExternalities::default().execute_with(|| {
+		Value::set(1);
+		Map::insert("val1", 1);
+
+		with_transaction(|| {
+			Value::set(2);
+			Map::insert("val1", 2);
+			Map::insert("val2", 2);
+
+			with_transaction(|| {
+				Value::set(3);
+				Map::insert("val1", 3);
+				Map::insert("val2", 3);
+				Map::insert("val3", 3);
+
+				assert_eq!(Value::get(), 3);
+				assert_eq!(Map::get("val1"), 3);
+				assert_eq!(Map::get("val2"), 3);
+				assert_eq!(Map::get("val3"), 3);
+
+				Rollback(())
+			});
+
+			assert_eq!(Value::get(), 2);
+			assert_eq!(Map::get("val1"), 2);
+			assert_eq!(Map::get("val2"), 2);
+			assert_eq!(Map::get("val3"), 0);
+
+			Commit(())
+		});
+
+		assert_eq!(Value::get(), 2);
+		assert_eq!(Map::get("val1"), 2);
+		assert_eq!(Map::get("val2"), 2);
+		assert_eq!(Map::get("val3"), 0);
+	});
+}
+
+#[test]
+fn storage_transaction_commit_then_rollback() {
+	TestExternalities::default().execute_with(|| {
+		Value::set(1);
+		Map::insert("val1", 1);
+
+		with_transaction(|| {
+			Value::set(2);
+			Map::insert("val1", 2);
+			Map::insert("val2", 2);
+
+			with_transaction(|| {
+				Value::set(3);
+				Map::insert("val1", 3);
+				Map::insert("val2", 3);
+				Map::insert("val3", 3);
+
+				assert_eq!(Value::get(), 3);
+				assert_eq!(Map::get("val1"), 3);
+				assert_eq!(Map::get("val2"), 3);
+				assert_eq!(Map::get("val3"), 3);
+
+				Commit(())
+			});
+
+			assert_eq!(Value::get(), 3);
+			assert_eq!(Map::get("val1"), 3);
+			assert_eq!(Map::get("val2"), 3);
+			assert_eq!(Map::get("val3"), 3);
+
+			Rollback(())
+		});
+
+		assert_eq!(Value::get(), 1);
+		assert_eq!(Map::get("val1"), 1);
+		assert_eq!(Map::get("val2"), 0);
+		assert_eq!(Map::get("val3"), 0);
+	});
+}
+
+#[test]
+fn transactional_annotation() {
+	fn fails() -> Result<(), &'static str> {
+		Err("nah")
+	}
+
+	#[transactional]
+	fn value_commits(v: u32) -> Result<u32, &'static str> {
+		Value::set(v);
+		Ok(v)
+	}
+
+	#[transactional]
+	fn value_rollbacks(v: u32) -> Result<u32, &'static str> {
+		Value::set(v);
+		fails()?;
+		Ok(v)
+	}
+
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(value_commits(2), Ok(2));
+		assert_eq!(Value::get(), 2);
+
+		assert_eq!(value_rollbacks(3), Err("nah"));
+		assert_eq!(Value::get(), 2);
+	});
+}
+
+#[test]
+fn transactional_annotation_in_decl_module() {
+	TestExternalities::default().execute_with(|| {
+		let origin = || system::Origin::<Runtime>::Root.into();
+
+		assert_eq!(Transactional::value_commits(origin(), 2), Ok(()));
+		assert_eq!(Value::get(), 2);
+
+		assert_eq!(
+			Transactional::value_rollbacks(origin(), 3),
+			Err(DispatchError::Other("nah")),
+		);
+		assert_eq!(Value::get(), 2);
+	});
+}
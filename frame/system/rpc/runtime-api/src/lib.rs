@@ -22,6 +22,8 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use sp_runtime::DispatchResult;
+
 sp_api::decl_runtime_apis! {
 	/// The API to query account nonce (aka transaction index).
 	pub trait AccountNonceApi<AccountId, Index> where
@@ -31,4 +33,15 @@ sp_api::decl_runtime_apis! {
 		/// Get current account nonce of given `AccountId`.
 		fn account_nonce(account: AccountId) -> Index;
 	}
+
+	/// The API to query the outcome of the extrinsics applied in a block.
+	pub trait ExtrinsicOutcomeApi {
+		/// Get the outcome of the extrinsic at `index` in the block, `None` if no extrinsic was
+		/// applied at this index.
+		///
+		/// The outcome is read from the events in the state of the block, so no new block is
+		/// initialized on top of it.
+		#[skip_initialize_block]
+		fn extrinsic_outcome(index: u32) -> Option<DispatchResult>;
+	}
 }
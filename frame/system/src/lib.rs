@@ -109,7 +109,7 @@ use sp_runtime::{
 		self, CheckEqual, AtLeast32Bit, Zero, SignedExtension, Lookup, LookupError,
 		SimpleBitOps, Hash, Member, MaybeDisplay, BadOrigin, SaturatedConversion,
 		MaybeSerialize, MaybeSerializeDeserialize, MaybeMallocSizeOf, StaticLookup, One, Bounded,
-		Dispatchable, DispatchInfoOf, PostDispatchInfoOf,
+		Dispatchable, DispatchInfoOf, PostDispatchInfoOf, BlockNumberProvider,
 	},
 };
 
@@ -118,8 +118,8 @@ use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error, Parameter, ensure, debug,
 	storage,
 	traits::{
-		Contains, Get, ModuleToIndex, OnNewAccount, OnKilledAccount, IsDeadAccount, Happened,
-		StoredMap, EnsureOrigin,
+		Contains, Get, ModuleToIndex, ModuleLayout, OnNewAccount, OnKilledAccount, IsDeadAccount,
+		Happened, StoredMap, EnsureOrigin, OnRuntimeUpgrade,
 	},
 	weights::{
 		Weight, RuntimeDbWeight, DispatchInfo, PostDispatchInfo, DispatchClass,
@@ -127,12 +127,25 @@ use frame_support::{
 	}
 };
 use codec::{Encode, Decode, FullCodec, EncodeLike};
+use frame_support::type_info::TypeInfo;
 
 #[cfg(any(feature = "std", test))]
 use sp_io::TestExternalities;
 
 pub mod offchain;
 
+/// Prelude to be used alongside the `#[frame_support::pallet]` attribute, next to
+/// `frame_support::pallet_prelude`.
+pub mod pallet_prelude {
+	pub use crate::{ensure_signed, ensure_none, ensure_root};
+
+	/// Type alias for the `Origin` associated type of system trait.
+	pub type OriginFor<T> = <T as crate::Trait>::Origin;
+
+	/// Type alias for the `BlockNumber` associated type of system trait.
+	pub type BlockNumberFor<T> = <T as crate::Trait>::BlockNumber;
+}
+
 /// Compute the trie root of a list of extrinsics.
 pub fn extrinsics_root<H: Hash, E: codec::Encode>(extrinsics: &[E]) -> H::Output {
 	extrinsics_data_root::<H>(extrinsics.iter().map(codec::Encode::encode).collect())
@@ -245,7 +258,7 @@ pub type Key = Vec<u8>;
 pub type KeyValue = (Vec<u8>, Vec<u8>);
 
 /// A phase of a block's execution.
-#[derive(Encode, Decode, RuntimeDebug)]
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone))]
 pub enum Phase {
 	/// Applying an extrinsic.
@@ -263,7 +276,7 @@ impl Default for Phase {
 }
 
 /// Record of an event happening.
-#[derive(Encode, Decode, RuntimeDebug)]
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone))]
 pub struct EventRecord<E: Parameter + Member, T> {
 	/// The phase of the block it happened in.
@@ -318,7 +331,7 @@ type EventIndex = u32;
 pub type RefCount = u8;
 
 /// Information of an account.
-#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode)]
+#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
 pub struct AccountInfo<Index, AccountData> {
 	/// The number of transactions this account has sent.
 	pub nonce: Index,
@@ -332,7 +345,7 @@ pub struct AccountInfo<Index, AccountData> {
 
 /// Stores the `spec_version` and `spec_name` of when the last runtime upgrade
 /// happened.
-#[derive(sp_runtime::RuntimeDebug, Encode, Decode)]
+#[derive(sp_runtime::RuntimeDebug, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(PartialEq))]
 pub struct LastRuntimeUpgradeInfo {
 	pub spec_version: codec::Compact<u32>,
@@ -358,7 +371,7 @@ impl From<sp_version::RuntimeVersion> for LastRuntimeUpgradeInfo {
 }
 
 /// An object to track the currently used extrinsic weight in a block.
-#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode)]
+#[derive(Clone, Eq, PartialEq, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
 pub struct ExtrinsicsWeight {
 	normal: Weight,
 	operational: Weight,
@@ -473,6 +486,13 @@ decl_storage! {
 
 		/// The execution phase of the block.
 		ExecutionPhase: Option<Phase>;
+
+		/// The index and storage prefix of every module, as of the last runtime upgrade.
+		pub ModulesLayout build(|_| T::ModuleToIndex::modules_layout()): Vec<ModuleLayout>;
+
+		/// The names of the modules whose calls and storage were moved by a migration of the
+		/// current runtime upgrade.
+		ModulesLayoutMigrated: Vec<Vec<u8>>;
 	}
 	add_extra_genesis {
 		config(changes_trie_config): Option<ChangesTrieConfiguration>;
@@ -491,6 +511,10 @@ decl_storage! {
 					&changes_trie_config.encode(),
 				);
 			}
+
+			for (prefix, version) in T::ModuleToIndex::pallet_versions() {
+				version.put_into_storage(prefix.as_bytes());
+			}
 		});
 	}
 }
@@ -508,6 +532,9 @@ decl_event!(
 		NewAccount(AccountId),
 		/// An account was reaped.
 		KilledAccount(AccountId),
+		/// A runtime upgrade changed the index or the storage prefix of these modules without
+		/// migrating them.
+		ModulesLayoutChanged(Vec<ModuleLayout>),
 	}
 );
 
@@ -1164,6 +1191,25 @@ impl<T: Trait> Module<T> {
 		ExecutionPhase::put(Phase::ApplyExtrinsic(next_extrinsic_index));
 	}
 
+	/// Returns the outcome of the extrinsic at `index` in the current block, as noted by its
+	/// `ExtrinsicSuccess` or `ExtrinsicFailed` event.
+	///
+	/// Returns `None` if no extrinsic was applied at `index`.
+	pub fn extrinsic_outcome(index: u32) -> Option<DispatchResult> where
+		T::Event: sp_std::convert::TryInto<Event<T>>,
+	{
+		Self::events().into_iter()
+			.filter(|record| match record.phase {
+				Phase::ApplyExtrinsic(i) => i == index,
+				_ => false,
+			})
+			.find_map(|record| match sp_std::convert::TryInto::try_into(record.event) {
+				Ok(RawEvent::ExtrinsicSuccess(_)) => Some(Ok(())),
+				Ok(RawEvent::ExtrinsicFailed(err, _)) => Some(Err(err)),
+				_ => None,
+			})
+	}
+
 	/// To be called immediately after `note_applied_extrinsic` of the last extrinsic of the block
 	/// has been called.
 	pub fn note_finished_extrinsics() {
@@ -1179,6 +1225,59 @@ impl<T: Trait> Module<T> {
 		ExecutionPhase::put(Phase::ApplyExtrinsic(0))
 	}
 
+	/// Notes that a migration of the current runtime upgrade moved the calls and the storage of
+	/// the module with the given name in `construct_runtime!`.
+	///
+	/// To be called from `on_runtime_upgrade`, so `check_modules_layout` accepts the new index
+	/// and storage prefix of this module.
+	pub fn note_modules_layout_migrated(module: &[u8]) {
+		ModulesLayoutMigrated::append(module.to_vec());
+	}
+
+	/// Compares the layout of the modules with the one recorded at the last runtime upgrade and
+	/// records the new one.
+	///
+	/// A module is compared with the previous modules sharing its name, its index or its storage
+	/// prefix, so renaming a module in `construct_runtime!` doesn't hide that it moved. Modules
+	/// whose index or storage prefix changed are reported with `RawEvent::ModulesLayoutChanged`,
+	/// unless `note_modules_layout_migrated` was called for them. The new code is already in
+	/// place when this runs, so the upgrade can't be refused anymore. To be called by the
+	/// executive after a runtime upgrade, once the block is initialized.
+	pub fn check_modules_layout() -> Weight {
+		let layout = T::ModuleToIndex::modules_layout();
+		let migrated = ModulesLayoutMigrated::take();
+		let changed = moved_modules(&ModulesLayout::get(), &layout, &migrated);
+
+		if !changed.is_empty() {
+			for module in &changed {
+				debug::warn!(
+					target: "system",
+					"Module {} changed its index or storage prefix without a migration: {:?}",
+					sp_std::str::from_utf8(&module.name).unwrap_or_default(),
+					module,
+				);
+			}
+			Self::deposit_event(RawEvent::ModulesLayoutChanged(changed));
+		}
+		ModulesLayout::put(layout);
+
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+
+	/// Writes the current version of every module declaring storage, as returned by
+	/// `ModuleToIndex::pallet_versions`.
+	///
+	/// To be called by the executive after the migrations of a runtime upgrade ran, so the next
+	/// upgrade finds the versions its migrations start from.
+	pub fn put_pallet_versions() -> Weight {
+		let versions = T::ModuleToIndex::pallet_versions();
+		for (prefix, version) in &versions {
+			version.put_into_storage(prefix.as_bytes());
+		}
+
+		T::DbWeight::get().writes(versions.len() as Weight)
+	}
+
 	/// Remove all extrinsic data and save the extrinsics trie root.
 	pub fn derive_extrinsics() {
 		let extrinsics = (0..ExtrinsicCount::get().unwrap_or_default())
@@ -1244,6 +1343,45 @@ impl<T: Trait> Module<T> {
 	}
 }
 
+/// Returns the modules of `layout` which changed their index or storage prefix since `previous`,
+/// except the `migrated` ones.
+///
+/// A module is compared with the previous modules sharing its name, its index or its storage
+/// prefix.
+fn moved_modules(
+	previous: &[ModuleLayout],
+	layout: &[ModuleLayout],
+	migrated: &[Vec<u8>],
+) -> Vec<ModuleLayout> {
+	layout.iter()
+		.filter(|module| !migrated.contains(&module.name))
+		.filter(|module| previous.iter().any(|p| {
+			let same_module = p.name == module.name
+				|| p.index == module.index
+				|| (p.storage_prefix.is_some() && p.storage_prefix == module.storage_prefix);
+			same_module && (p.index, &p.storage_prefix) != (module.index, &module.storage_prefix)
+		}))
+		.cloned()
+		.collect()
+}
+
+/// Seeds `ModulesLayout` on a chain started before it was recorded, so the first runtime upgrade
+/// checked by `Module::check_modules_layout` is compared with the actual previous layout.
+///
+/// `Layout` is the layout of the modules in the runtime being upgraded from. To be part of the
+/// migrations of the runtime upgrade that starts recording `ModulesLayout`.
+pub struct SeedModulesLayout<T, Layout>(PhantomData<(T, Layout)>);
+impl<T: Trait, Layout: Get<Vec<ModuleLayout>>> OnRuntimeUpgrade for SeedModulesLayout<T, Layout> {
+	fn on_runtime_upgrade() -> Weight {
+		if ModulesLayout::exists() {
+			return T::DbWeight::get().reads(1)
+		}
+
+		ModulesLayout::put(Layout::get());
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+}
+
 /// Event handler which calls on_created_account when it happens.
 pub struct CallOnCreatedAccount<T>(PhantomData<T>);
 impl<T: Trait> Happened<T::AccountId> for CallOnCreatedAccount<T> {
@@ -1330,7 +1468,7 @@ pub fn split_inner<T, R, S>(option: Option<T>, splitter: impl FnOnce(T) -> (R, S
 }
 
 /// resource limit check.
-#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub struct CheckWeight<T: Trait + Send + Sync>(PhantomData<T>);
 
 impl<T: Trait + Send + Sync> CheckWeight<T> where
@@ -1553,7 +1691,7 @@ impl<T: Trait + Send + Sync> Debug for CheckWeight<T> {
 }
 
 /// Nonce check and increment to give replay protection for transactions.
-#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub struct CheckNonce<T: Trait>(#[codec(compact)] T::Index);
 
 impl<T: Trait> CheckNonce<T> {
@@ -1644,8 +1782,16 @@ impl<T: Trait> IsDeadAccount<T::AccountId> for Module<T> {
 	}
 }
 
+impl<T: Trait> BlockNumberProvider for Module<T> {
+	type BlockNumber = <T as Trait>::BlockNumber;
+
+	fn current_block_number() -> Self::BlockNumber {
+		Module::<T>::block_number()
+	}
+}
+
 /// Check for transaction mortality.
-#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub struct CheckEra<T: Trait + Send + Sync>(Era, sp_std::marker::PhantomData<T>);
 
 impl<T: Trait + Send + Sync> CheckEra<T> {
@@ -1701,7 +1847,7 @@ impl<T: Trait + Send + Sync> SignedExtension for CheckEra<T> {
 }
 
 /// Nonce check and increment to give replay protection for transactions.
-#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub struct CheckGenesis<T: Trait + Send + Sync>(sp_std::marker::PhantomData<T>);
 
 impl<T: Trait + Send + Sync> Debug for CheckGenesis<T> {
@@ -1736,7 +1882,7 @@ impl<T: Trait + Send + Sync> SignedExtension for CheckGenesis<T> {
 }
 
 /// Ensure the runtime version registered in the transaction is the same as at present.
-#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
 pub struct CheckVersion<T: Trait + Send + Sync>(sp_std::marker::PhantomData<T>);
 
 impl<T: Trait + Send + Sync> Debug for CheckVersion<T> {
@@ -1793,6 +1939,7 @@ pub(crate) mod tests {
 	use sp_core::H256;
 	use sp_runtime::{traits::{BlakeTwo256, IdentityLookup, SignedExtension}, testing::Header, DispatchError};
 	use frame_support::{impl_outer_origin, parameter_types, assert_ok, assert_noop};
+	use frame_support::traits::PalletVersion;
 
 	impl_outer_origin! {
 		pub enum Origin for Test where system = super {}
@@ -1825,6 +1972,31 @@ pub(crate) mod tests {
 
 	thread_local!{
 		pub static KILLED: RefCell<Vec<u64>> = RefCell::new(vec![]);
+		static MODULES_LAYOUT: RefCell<Vec<ModuleLayout>> = RefCell::new(vec![
+			module_layout("System", 0, Some("System")),
+			module_layout("Balances", 1, Some("Balances")),
+		]);
+	}
+
+	fn module_layout(name: &str, index: u8, storage_prefix: Option<&str>) -> ModuleLayout {
+		ModuleLayout {
+			name: name.as_bytes().to_vec(),
+			index,
+			storage_prefix: storage_prefix.map(|prefix| prefix.as_bytes().to_vec()),
+		}
+	}
+
+	pub struct TestModuleToIndex;
+	impl ModuleToIndex for TestModuleToIndex {
+		fn module_to_index<M: 'static>() -> Option<usize> { Some(0) }
+
+		fn modules_layout() -> Vec<ModuleLayout> {
+			MODULES_LAYOUT.with(|l| l.borrow().clone())
+		}
+
+		fn pallet_versions() -> Vec<(&'static str, PalletVersion)> {
+			vec![("System", PalletVersion::new(2, 0, 0)), ("Balances", PalletVersion::new(2, 1, 0))]
+		}
 	}
 
 	pub struct RecordKilled;
@@ -1865,7 +2037,7 @@ pub(crate) mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type MaximumBlockLength = MaximumBlockLength;
 		type Version = Version;
-		type ModuleToIndex = ();
+		type ModuleToIndex = TestModuleToIndex;
 		type AccountData = u32;
 		type OnNewAccount = ();
 		type OnKilledAccount = RecordKilled;
@@ -2481,4 +2653,136 @@ pub(crate) mod tests {
 			assert!(System::events().len() == 1);
 		});
 	}
+
+	#[test]
+	fn check_modules_layout_reports_moved_modules() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_eq!(ModulesLayout::get(), TestModuleToIndex::modules_layout());
+
+			// Adding a module doesn't move the others.
+			let layout = vec![
+				module_layout("System", 0, Some("System")),
+				module_layout("Balances", 1, Some("Balances")),
+				module_layout("Sudo", 2, None),
+			];
+			MODULES_LAYOUT.with(|l| *l.borrow_mut() = layout.clone());
+			System::check_modules_layout();
+			assert!(System::events().is_empty());
+			assert_eq!(ModulesLayout::get(), layout);
+
+			// Moving a module without a migration is reported.
+			let layout = vec![
+				module_layout("System", 0, Some("System")),
+				module_layout("Sudo", 1, None),
+				module_layout("Balances", 2, Some("Balances")),
+			];
+			MODULES_LAYOUT.with(|l| *l.borrow_mut() = layout.clone());
+			System::check_modules_layout();
+			assert_eq!(System::events().len(), 1);
+			assert_eq!(ModulesLayout::get(), layout);
+
+			// Moving the modules noted as migrated is accepted.
+			let layout = vec![
+				module_layout("System", 0, Some("System")),
+				module_layout("Sudo", 1, None),
+				module_layout("Balances", 2, Some("PalletBalances")),
+			];
+			MODULES_LAYOUT.with(|l| *l.borrow_mut() = layout.clone());
+			System::note_modules_layout_migrated(b"Balances");
+			System::check_modules_layout();
+			assert_eq!(System::events().len(), 1);
+			assert_eq!(ModulesLayout::get(), layout);
+			assert!(ModulesLayoutMigrated::get().is_empty());
+		});
+	}
+
+	#[test]
+	fn moved_modules_are_found_by_name_index_or_storage_prefix() {
+		let previous = vec![
+			module_layout("System", 0, Some("System")),
+			module_layout("Balances", 1, Some("Balances")),
+			module_layout("Sudo", 2, None),
+		];
+
+		// A module taking the index of another one is reported along with it.
+		let layout = vec![
+			module_layout("System", 0, Some("System")),
+			module_layout("Sudo", 1, None),
+			module_layout("Balances", 2, Some("Balances")),
+		];
+		assert_eq!(moved_modules(&previous, &layout, &[]), layout[1..].to_vec());
+
+		// Renaming a module in `construct_runtime!` doesn't hide that it moved.
+		let layout = vec![
+			module_layout("System", 0, Some("System")),
+			module_layout("Sudo", 2, None),
+			module_layout("Currency", 3, Some("Balances")),
+		];
+		assert_eq!(moved_modules(&previous, &layout, &[]), vec![layout[2].clone()]);
+
+		// Only renaming it moves nothing.
+		let layout = vec![
+			module_layout("System", 0, Some("System")),
+			module_layout("Currency", 1, Some("Balances")),
+			module_layout("Sudo", 2, None),
+		];
+		assert!(moved_modules(&previous, &layout, &[]).is_empty());
+
+		// Only the modules noted as migrated are accepted.
+		let layout = vec![
+			module_layout("System", 0, Some("System")),
+			module_layout("Sudo", 3, None),
+			module_layout("Balances", 4, Some("PalletBalances")),
+		];
+		assert_eq!(
+			moved_modules(&previous, &layout, &[b"Balances".to_vec()]),
+			vec![layout[1].clone()],
+		);
+	}
+
+	#[test]
+	fn seed_modules_layout_records_the_previous_layout_once() {
+		parameter_types! {
+			pub PreviousLayout: Vec<ModuleLayout> = vec![
+				module_layout("Balances", 0, Some("Balances")),
+				module_layout("System", 1, Some("System")),
+			];
+		}
+
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			// A chain started before `ModulesLayout` was recorded.
+			ModulesLayout::kill();
+
+			SeedModulesLayout::<Test, PreviousLayout>::on_runtime_upgrade();
+			assert_eq!(ModulesLayout::get(), PreviousLayout::get());
+			System::check_modules_layout();
+			assert_eq!(System::events().len(), 1);
+
+			// Once recorded, the layout is not seeded again.
+			SeedModulesLayout::<Test, PreviousLayout>::on_runtime_upgrade();
+			assert_eq!(ModulesLayout::get(), TestModuleToIndex::modules_layout());
+		});
+	}
+
+	#[test]
+	fn pallet_versions_are_written_at_genesis_and_after_upgrades() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				PalletVersion::get_from_storage(b"Balances"),
+				Some(PalletVersion::new(2, 1, 0)),
+			);
+
+			PalletVersion::new(1, 0, 0).put_into_storage(b"System");
+			assert_eq!(
+				System::put_pallet_versions(),
+				<Test as Trait>::DbWeight::get().writes(2),
+			);
+			assert_eq!(
+				PalletVersion::get_from_storage(b"System"),
+				Some(PalletVersion::new(2, 0, 0)),
+			);
+		});
+	}
 }
@@ -0,0 +1,447 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Tipping Module
+//!
+//! The Tipping module allows for an agile "tipping" process, whereby a reward may be given from the
+//! treasury pot without first having a pre-determined stakeholder group come to consensus on how
+//! much should be paid.
+//!
+//! - [`tips::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! A group of `Tippers` is determined through the config `Trait`. After half of these have declared
+//! some amount that they believe a particular reported reason deserves, then a countdown period is
+//! entered where any remaining members can declare their tip amounts also. After the close of the
+//! countdown period, the median of all declared tips is paid to the reported beneficiary, along
+//! with any finders fee, in case of a public (and bonded) original report.
+//!
+//! Tips are paid out of the pot of the treasury module, and their storage is kept under the
+//! treasury's prefix, so moving them out of the treasury module did not require a migration.
+//!
+//! ### Terminology
+//!
+//! - **Tipping:** The process of gathering declarations of amounts to tip and taking the median
+//!   amount to be transferred from the treasury to a beneficiary account.
+//! - **Tip Reason:** The reason for a tip; generally a URL which embodies or explains why a
+//!   particular individual (identified by an account ID) is worthy of a recognition by the
+//!   treasury.
+//! - **Finder:** The original public reporter of some reason for tipping.
+//! - **Finders Fee:** Some proportion of the tip amount that is paid to the reporter of the tip,
+//!   rather than the main beneficiary.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `report_awesome` - Report something worthy of a tip and register for a finders fee.
+//! - `retract_tip` - Retract a previous (finders fee registered) report.
+//! - `tip_new` - Report an item worthy of a tip and declare a specific amount to tip.
+//! - `tip` - Declare or redeclare an amount to tip for a particular reason.
+//! - `close_tip` - Close and pay out a tip.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use frame_support::{decl_module, decl_storage, decl_event, ensure, decl_error, Parameter};
+use frame_support::traits::{
+	Currency, Get, ExistenceRequirement::KeepAlive, ReservableCurrency,
+};
+use sp_runtime::{Percent, RuntimeDebug, traits::{Hash, BadOrigin}};
+use frame_support::weights::Weight;
+use frame_support::traits::{Contains, ContainsLengthBound};
+use codec::{Encode, Decode};
+use frame_support::type_info::TypeInfo;
+use frame_system::{self as system, ensure_signed};
+
+mod tests;
+mod benchmarking;
+
+type BalanceOf<T> = pallet_treasury::BalanceOf<T>;
+
+pub trait Trait: frame_system::Trait + pallet_treasury::Trait {
+	/// Origin from which tippers must come.
+	///
+	/// `ContainsLengthBound::max_len` must be cost free (i.e. no storage read or heavy operation).
+	type Tippers: Contains<Self::AccountId> + ContainsLengthBound;
+
+	/// The period for which a tip remains open after is has achieved threshold tippers.
+	type TipCountdown: Get<Self::BlockNumber>;
+
+	/// The percent of the final tip which goes to the original reporter of the tip.
+	type TipFindersFee: Get<Percent>;
+
+	/// The amount held on deposit for placing a tip report.
+	type TipReportDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount held on deposit per byte within the tip report reason.
+	type TipReportDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+}
+
+/// An open tipping "motion". Retains all details of a tip including information on the finder
+/// and the members who have voted.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct OpenTip<
+	AccountId: Parameter,
+	Balance: Parameter,
+	BlockNumber: Parameter,
+	Hash: Parameter,
+> {
+	/// The hash of the reason for the tip. The reason should be a human-readable UTF-8 encoded string. A URL would be
+	/// sensible.
+	reason: Hash,
+	/// The account to be tipped.
+	who: AccountId,
+	/// The account who began this tip and the amount held on deposit.
+	finder: Option<(AccountId, Balance)>,
+	/// The block number at which this tip will close if `Some`. If `None`, then no closing is
+	/// scheduled.
+	closes: Option<BlockNumber>,
+	/// The members who have voted for this tip. Sorted by AccountId.
+	tips: Vec<(AccountId, Balance)>,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Treasury {
+		/// Tips that are not yet completed. Keyed by the hash of `(reason, who)` from the value.
+		/// This has the insecure enumerable hash function since the key itself is already
+		/// guaranteed to be a secure hash.
+		pub Tips get(fn tips):
+			map hasher(twox_64_concat) T::Hash
+			=> Option<OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>>;
+
+		/// Simple preimage lookup from the reason's hash to the original data. Again, has an
+		/// insecure enumerable hash since the key is guaranteed to be the result of a secure hash.
+		pub Reasons get(fn reasons): map hasher(identity) T::Hash => Option<Vec<u8>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Balance = BalanceOf<T>,
+		<T as frame_system::Trait>::AccountId,
+		<T as frame_system::Trait>::Hash,
+	{
+		/// A new tip suggestion has been opened.
+		NewTip(Hash),
+		/// A tip suggestion has reached threshold and is closing.
+		TipClosing(Hash),
+		/// A tip suggestion has been closed.
+		TipClosed(Hash, AccountId, Balance),
+		/// A tip suggestion has been retracted.
+		TipRetracted(Hash),
+	}
+);
+
+decl_error! {
+	/// Error for the tips module.
+	pub enum Error for Module<T: Trait> {
+		/// The reason given is just too big.
+		ReasonTooBig,
+		/// The tip was already found/started.
+		AlreadyKnown,
+		/// The tip hash is unknown.
+		UnknownTip,
+		/// The account attempting to retract the tip is not the finder of the tip.
+		NotFinder,
+		/// The tip cannot be claimed/closed because there are not enough tippers yet.
+		StillOpen,
+		/// The tip cannot be claimed/closed because it's still in the countdown period.
+		Premature,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The period for which a tip remains open after is has achieved threshold tippers.
+		const TipCountdown: T::BlockNumber = T::TipCountdown::get();
+
+		/// The amount of the final tip which goes to the original reporter of the tip.
+		const TipFindersFee: Percent = T::TipFindersFee::get();
+
+		/// The amount held on deposit for placing a tip report.
+		const TipReportDepositBase: BalanceOf<T> = T::TipReportDepositBase::get();
+
+		/// The amount held on deposit per byte within the tip report reason.
+		const TipReportDepositPerByte: BalanceOf<T> = T::TipReportDepositPerByte::get();
+
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Report something `reason` that deserves a tip and claim any eventual the finder's fee.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Payment: `TipReportDepositBase` will be reserved from the origin account, as well as
+		/// `TipReportDepositPerByte` for each byte in `reason`.
+		///
+		/// - `reason`: The reason for, or the thing that deserves, the tip; generally this will be
+		///   a UTF-8-encoded URL.
+		/// - `who`: The account which should be credited for the tip.
+		///
+		/// Emits `NewTip` if successful.
+		///
+		/// # <weight>
+		/// - Complexity: `O(R)` where `R` length of `reason`.
+		///   - encoding and hashing of 'reason'
+		/// - DbReads: `Reasons`, `Tips`, `who account data`
+		/// - DbWrites: `Tips`, `who account data`
+		/// # </weight>
+		#[weight = 140_000_000 + 4_000 * reason.len() as Weight + T::DbWeight::get().reads_writes(3, 2)]
+		fn report_awesome(origin, reason: Vec<u8>, who: T::AccountId) {
+			let finder = ensure_signed(origin)?;
+
+			const MAX_SENSIBLE_REASON_LENGTH: usize = 16384;
+			ensure!(reason.len() <= MAX_SENSIBLE_REASON_LENGTH, Error::<T>::ReasonTooBig);
+
+			let reason_hash = T::Hashing::hash(&reason[..]);
+			ensure!(!Reasons::<T>::contains_key(&reason_hash), Error::<T>::AlreadyKnown);
+			let hash = T::Hashing::hash_of(&(&reason_hash, &who));
+			ensure!(!Tips::<T>::contains_key(&hash), Error::<T>::AlreadyKnown);
+
+			let deposit = T::TipReportDepositBase::get()
+				+ T::TipReportDepositPerByte::get() * (reason.len() as u32).into();
+			T::Currency::reserve(&finder, deposit)?;
+
+			Reasons::<T>::insert(&reason_hash, &reason);
+			let finder = Some((finder, deposit));
+			let tip = OpenTip { reason: reason_hash, who, finder, closes: None, tips: vec![] };
+			Tips::<T>::insert(&hash, tip);
+			Self::deposit_event(RawEvent::NewTip(hash));
+		}
+
+		/// Retract a prior tip-report from `report_awesome`, and cancel the process of tipping.
+		///
+		/// If successful, the original deposit will be unreserved.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the tip identified by `hash`
+		/// must have been reported by the signing account through `report_awesome` (and not
+		/// through `tip_new`).
+		///
+		/// - `hash`: The identity of the open tip for which a tip value is declared. This is formed
+		///   as the hash of the tuple of the original tip `reason` and the beneficiary account ID.
+		///
+		/// Emits `TipRetracted` if successful.
+		///
+		/// # <weight>
+		/// - Complexity: `O(1)`
+		///   - Depends on the length of `T::Hash` which is fixed.
+		/// - DbReads: `Tips`, `origin account`
+		/// - DbWrites: `Reasons`, `Tips`, `origin account`
+		/// # </weight>
+		#[weight = 120_000_000 + T::DbWeight::get().reads_writes(1, 2)]
+		fn retract_tip(origin, hash: T::Hash) {
+			let who = ensure_signed(origin)?;
+			let tip = Tips::<T>::get(&hash).ok_or(Error::<T>::UnknownTip)?;
+			let (finder, deposit) = tip.finder.ok_or(Error::<T>::NotFinder)?;
+			ensure!(finder == who, Error::<T>::NotFinder);
+
+			Reasons::<T>::remove(&tip.reason);
+			Tips::<T>::remove(&hash);
+			let _ = T::Currency::unreserve(&who, deposit);
+			Self::deposit_event(RawEvent::TipRetracted(hash));
+		}
+
+		/// Give a tip for something new; no finder's fee will be taken.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the signing account must be a
+		/// member of the `Tippers` set.
+		///
+		/// - `reason`: The reason for, or the thing that deserves, the tip; generally this will be
+		///   a UTF-8-encoded URL.
+		/// - `who`: The account which should be credited for the tip.
+		/// - `tip_value`: The amount of tip that the sender would like to give. The median tip
+		///   value of active tippers will be given to the `who`.
+		///
+		/// Emits `NewTip` if successful.
+		///
+		/// # <weight>
+		/// - Complexity: `O(R + T)` where `R` length of `reason`, `T` is the number of tippers.
+		///   - `O(T)`: decoding `Tipper` vec of length `T`
+		///     `T` is charged as upper bound given by `ContainsLengthBound`.
+		///     The actual cost depends on the implementation of `T::Tippers`.
+		///   - `O(R)`: hashing and encoding of reason of length `R`
+		/// - DbReads: `Tippers`, `Reasons`
+		/// - DbWrites: `Reasons`, `Tips`
+		/// # </weight>
+		#[weight = 110_000_000
+			+ 4_000 * reason.len() as Weight
+			+ 480_000 * T::Tippers::max_len() as Weight
+			+ T::DbWeight::get().reads_writes(2, 2)]
+		fn tip_new(origin, reason: Vec<u8>, who: T::AccountId, tip_value: BalanceOf<T>) {
+			let tipper = ensure_signed(origin)?;
+			ensure!(T::Tippers::contains(&tipper), BadOrigin);
+			let reason_hash = T::Hashing::hash(&reason[..]);
+			ensure!(!Reasons::<T>::contains_key(&reason_hash), Error::<T>::AlreadyKnown);
+			let hash = T::Hashing::hash_of(&(&reason_hash, &who));
+
+			Reasons::<T>::insert(&reason_hash, &reason);
+			Self::deposit_event(RawEvent::NewTip(hash.clone()));
+			let tips = vec![(tipper, tip_value)];
+			let tip = OpenTip { reason: reason_hash, who, finder: None, closes: None, tips };
+			Tips::<T>::insert(&hash, tip);
+		}
+
+		/// Declare a tip value for an already-open tip.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the signing account must be a
+		/// member of the `Tippers` set.
+		///
+		/// - `hash`: The identity of the open tip for which a tip value is declared. This is formed
+		///   as the hash of the tuple of the hash of the original tip `reason` and the beneficiary
+		///   account ID.
+		/// - `tip_value`: The amount of tip that the sender would like to give. The median tip
+		///   value of active tippers will be given to the `who`.
+		///
+		/// Emits `TipClosing` if the threshold of tippers has been reached and the countdown period
+		/// has started.
+		///
+		/// # <weight>
+		/// - Complexity: `O(T)` where `T` is the number of tippers.
+		///   decoding `Tipper` vec of length `T`, insert tip and check closing,
+		///   `T` is charged as upper bound given by `ContainsLengthBound`.
+		///   The actual cost depends on the implementation of `T::Tippers`.
+		///
+		///   Actually weight could be lower as it depends on how many tips are in `OpenTip` but it
+		///   is weighted as if almost full i.e of length `T-1`.
+		/// - DbReads: `Tippers`, `Tips`
+		/// - DbWrites: `Tips`
+		/// # </weight>
+		#[weight = 68_000_000 + 2_000_000 * T::Tippers::max_len() as Weight
+			+ T::DbWeight::get().reads_writes(2, 1)]
+		fn tip(origin, hash: T::Hash, tip_value: BalanceOf<T>) {
+			let tipper = ensure_signed(origin)?;
+			ensure!(T::Tippers::contains(&tipper), BadOrigin);
+
+			let mut tip = Tips::<T>::get(hash).ok_or(Error::<T>::UnknownTip)?;
+			if Self::insert_tip_and_check_closing(&mut tip, tipper, tip_value) {
+				Self::deposit_event(RawEvent::TipClosing(hash.clone()));
+			}
+			Tips::<T>::insert(&hash, tip);
+		}
+
+		/// Close and payout a tip.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// The tip identified by `hash` must have finished its countdown period.
+		///
+		/// - `hash`: The identity of the open tip for which a tip value is declared. This is formed
+		///   as the hash of the tuple of the original tip `reason` and the beneficiary account ID.
+		///
+		/// # <weight>
+		/// - Complexity: `O(T)` where `T` is the number of tippers.
+		///   decoding `Tipper` vec of length `T`.
+		///   `T` is charged as upper bound given by `ContainsLengthBound`.
+		///   The actual cost depends on the implementation of `T::Tippers`.
+		/// - DbReads: `Tips`, `Tippers`, `tip finder`
+		/// - DbWrites: `Reasons`, `Tips`, `Tippers`, `tip finder`
+		/// # </weight>
+		#[weight = 220_000_000 + 1_100_000 * T::Tippers::max_len() as Weight
+			+ T::DbWeight::get().reads_writes(3, 3)]
+		fn close_tip(origin, hash: T::Hash) {
+			ensure_signed(origin)?;
+
+			let tip = Tips::<T>::get(hash).ok_or(Error::<T>::UnknownTip)?;
+			let n = tip.closes.as_ref().ok_or(Error::<T>::StillOpen)?;
+			ensure!(system::Module::<T>::block_number() >= *n, Error::<T>::Premature);
+			// closed.
+			Reasons::<T>::remove(&tip.reason);
+			Tips::<T>::remove(hash);
+			Self::payout_tip(hash, tip);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Given a mutable reference to an `OpenTip`, insert the tip into it and check whether it
+	/// closes, if so, then deposit the relevant event and set closing accordingly.
+	///
+	/// `O(T)` and one storage access.
+	fn insert_tip_and_check_closing(
+		tip: &mut OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>,
+		tipper: T::AccountId,
+		tip_value: BalanceOf<T>,
+	) -> bool {
+		match tip.tips.binary_search_by_key(&&tipper, |x| &x.0) {
+			Ok(pos) => tip.tips[pos] = (tipper, tip_value),
+			Err(pos) => tip.tips.insert(pos, (tipper, tip_value)),
+		}
+		Self::retain_active_tips(&mut tip.tips);
+		let threshold = (T::Tippers::count() + 1) / 2;
+		if tip.tips.len() >= threshold && tip.closes.is_none() {
+			tip.closes = Some(system::Module::<T>::block_number() + T::TipCountdown::get());
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Remove any non-members of `Tippers` from a `tips` vector. `O(T)`.
+	fn retain_active_tips(tips: &mut Vec<(T::AccountId, BalanceOf<T>)>) {
+		let members = T::Tippers::sorted_members();
+		let mut members_iter = members.iter();
+		let mut member = members_iter.next();
+		tips.retain(|(ref a, _)| loop {
+			match member {
+				None => break false,
+				Some(m) if m > a => break false,
+				Some(m) => {
+					member = members_iter.next();
+					if m < a {
+						continue
+					} else {
+						break true;
+					}
+				}
+			}
+		});
+	}
+
+	/// Execute the payout of a tip.
+	///
+	/// Up to three balance operations.
+	/// Plus `O(T)` (`T` is Tippers length).
+	fn payout_tip(hash: T::Hash, tip: OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>) {
+		let mut tips = tip.tips;
+		Self::retain_active_tips(&mut tips);
+		tips.sort_by_key(|i| i.1);
+		let treasury = pallet_treasury::Module::<T>::account_id();
+		let max_payout = pallet_treasury::Module::<T>::pot();
+		let mut payout = tips[tips.len() / 2].1.min(max_payout);
+		if let Some((finder, deposit)) = tip.finder {
+			let _ = T::Currency::unreserve(&finder, deposit);
+			if finder != tip.who {
+				// pay out the finder's fee.
+				let finders_fee = T::TipFindersFee::get() * payout;
+				payout -= finders_fee;
+				// this should go through given we checked it's at most the free balance, but still
+				// we only make a best-effort.
+				let _ = T::Currency::transfer(&treasury, &finder, finders_fee, KeepAlive);
+			}
+		}
+		// same as above: best-effort only.
+		let _ = T::Currency::transfer(&treasury, &tip.who, payout, KeepAlive);
+		Self::deposit_event(RawEvent::TipClosed(hash, tip.who, payout));
+	}
+}
@@ -585,8 +585,29 @@ decl_runtime_apis! {
 	}
 
 	/// The `Metadata` api trait that returns metadata for the runtime.
+	#[api_version(2)]
 	pub trait Metadata {
 		/// Returns the metadata of a runtime.
 		fn metadata() -> OpaqueMetadata;
+		/// Returns the metadata of a runtime at the given metadata version.
+		///
+		/// Returns `None` if the runtime does not support this version.
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata>;
+		/// Returns the metadata versions supported by the runtime.
+		fn metadata_versions() -> sp_std::vec::Vec<u32>;
+		/// Returns the encoded metadata of the constant `name` of the pallet `pallet`.
+		///
+		/// Returns `None` if there is no such constant.
+		fn constant(
+			pallet: sp_std::vec::Vec<u8>,
+			name: sp_std::vec::Vec<u8>,
+		) -> Option<OpaqueMetadata>;
+		/// Returns the encoded metadata of the storage entry `name` of the pallet `pallet`.
+		///
+		/// Returns `None` if there is no such storage entry.
+		fn storage_entry(
+			pallet: sp_std::vec::Vec<u8>,
+			name: sp_std::vec::Vec<u8>,
+		) -> Option<OpaqueMetadata>;
 	}
 }
@@ -34,6 +34,8 @@ pub use codec;
 pub use serde;
 #[doc(hidden)]
 pub use sp_std::{ops::Deref, vec::Vec};
+#[doc(hidden)]
+pub use sp_type_info;
 
 pub mod ed25519;
 pub mod sr25519;
@@ -260,6 +262,8 @@ macro_rules! app_crypto_public_common {
 			fn as_mut(&mut self) -> &mut [u8] { self.0.as_mut() }
 		}
 
+		$crate::app_crypto_type_info!(Public, $public);
+
 		impl $crate::Public for Public {
 			fn from_slice(x: &[u8]) -> Self { Self(<$public>::from_slice(x)) }
 
@@ -456,6 +460,29 @@ macro_rules! app_crypto_signature_common {
 		impl $crate::AppSignature for Signature {
 			type Generic = $sig;
 		}
+
+		$crate::app_crypto_type_info!(Signature, $sig);
+	}
+}
+
+/// Implements `TypeInfo` for an application-specific wrapper, which is encoded as the wrapped
+/// type.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! app_crypto_type_info {
+	($outer:ident, $inner:ty) => {
+		impl $crate::sp_type_info::TypeInfo for $outer {
+			type Identity = Self;
+
+			fn type_info() -> $crate::sp_type_info::Type {
+				$crate::sp_type_info::Type::new(
+					$crate::sp_type_info::Path::new(stringify!($outer), module_path!()),
+					$crate::sp_type_info::TypeDef::Composite($crate::sp_type_info::sp_std::vec![
+						$crate::sp_type_info::Field::unnamed::<$inner>(stringify!($inner), &[]),
+					]),
+				)
+			}
+		}
 	}
 }
 
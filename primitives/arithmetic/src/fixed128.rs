@@ -15,6 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use codec::{Decode, Encode};
+use sp_type_info::TypeInfo;
 use primitive_types::U256;
 use crate::{
 	traits::{Bounded, Saturating, UniqueSaturatedInto, SaturatedConversion},
@@ -32,7 +33,7 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 /// A signed fixed-point number.
 /// Can hold any value in the range [-170_141_183_460_469_231_731, 170_141_183_460_469_231_731]
 /// with fixed-point accuracy of 10 ** 18.
-#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, TypeInfo)]
 pub struct Fixed128(i128);
 
 const DIV: i128 = 1_000_000_000_000_000_000;
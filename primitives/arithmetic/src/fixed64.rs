@@ -19,6 +19,7 @@ use sp_std::{
 	convert::{TryFrom, TryInto},
 };
 use codec::{Encode, Decode};
+use sp_type_info::TypeInfo;
 use crate::{
 	Perbill,
 	traits::{
@@ -28,7 +29,7 @@ use crate::{
 
 /// An unsigned fixed point number. Can hold any value in the range [-9_223_372_036, 9_223_372_036]
 /// with fixed point accuracy of one billion.
-#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, TypeInfo)]
 pub struct Fixed64(i64);
 
 /// The accuracy of the `Fixed64` type.
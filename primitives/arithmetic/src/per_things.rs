@@ -23,6 +23,7 @@ use crate::traits::{
 	SaturatedConversion, UniqueSaturatedInto, Saturating, BaseArithmetic, Bounded, Zero,
 };
 use sp_debug_derive::RuntimeDebug;
+use sp_type_info::TypeInfo;
 
 /// Something that implements a fixed point ration with an arbitrary granularity `X`, as _parts per
 /// `X`_.
@@ -312,7 +313,7 @@ macro_rules! implement_per_thing {
 		#[doc = $title]
 		#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 		#[derive(Encode, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord,
-				 RuntimeDebug, CompactAs)]
+				 RuntimeDebug, CompactAs, TypeInfo)]
 		pub struct $name($type);
 
 		impl PerThing for $name {
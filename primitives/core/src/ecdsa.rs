@@ -23,6 +23,7 @@ use sp_std::vec::Vec;
 
 use sp_std::cmp::Ordering;
 use codec::{Encode, Decode};
+use sp_type_info::TypeInfo;
 
 #[cfg(feature = "full_crypto")]
 use core::convert::{TryFrom, TryInto};
@@ -50,7 +51,7 @@ pub const CRYPTO_ID: CryptoTypeId = CryptoTypeId(*b"ecds");
 type Seed = [u8; 32];
 
 /// The ECDSA compressed public key.
-#[derive(Clone, Encode, Decode)]
+#[derive(Clone, Encode, Decode, TypeInfo)]
 pub struct Public([u8; 33]);
 
 impl PartialOrd for Public {
@@ -208,7 +209,7 @@ impl sp_std::hash::Hash for Public {
 }
 
 /// A signature (a 512-bit value, plus 8 bits for recovery ID).
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, TypeInfo)]
 pub struct Signature([u8; 65]);
 
 impl sp_std::convert::TryFrom<&[u8]> for Signature {
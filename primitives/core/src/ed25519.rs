@@ -23,6 +23,7 @@ use sp_std::vec::Vec;
 
 use crate::{hash::H256, hash::H512};
 use codec::{Encode, Decode};
+use sp_type_info::TypeInfo;
 
 #[cfg(feature = "full_crypto")]
 use blake2_rfc;
@@ -53,7 +54,9 @@ type Seed = [u8; 32];
 
 /// A public key.
 #[cfg_attr(feature = "full_crypto", derive(Hash))]
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Encode, Decode, Default, PassByInner)]
+#[derive(
+	PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Encode, Decode, Default, PassByInner, TypeInfo,
+)]
 pub struct Public(pub [u8; 32]);
 
 /// A key pair.
@@ -187,7 +190,7 @@ impl<'de> Deserialize<'de> for Public {
 }
 
 /// A signature (a 512-bit value).
-#[derive(Encode, Decode, PassByInner)]
+#[derive(Encode, Decode, PassByInner, TypeInfo)]
 pub struct Signature(pub [u8; 64]);
 
 impl sp_std::convert::TryFrom<&[u8]> for Signature {
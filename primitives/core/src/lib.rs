@@ -33,6 +33,7 @@ macro_rules! map {
 
 use sp_std::prelude::*;
 use sp_std::ops::Deref;
+use sp_type_info::TypeInfo;
 #[cfg(feature = "std")]
 use std::borrow::Cow;
 #[cfg(feature = "std")]
@@ -299,6 +300,11 @@ impl From<LogLevel> for log::Level {
 	}
 }
 
+/// The void type - it cannot exist.
+// Oh rust, you crack me up...
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum Void {}
+
 /// Encodes the given value into a buffer and returns the pointer and the length as a single `u64`.
 ///
 /// When Substrate calls into Wasm it expects a fixed signature for functions exported
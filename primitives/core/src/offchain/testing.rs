@@ -162,8 +162,8 @@ impl offchain::Externalities for TestOffchainExt {
 		Timestamp::from_unix_millis(self.0.read().timestamp)
 	}
 
-	fn sleep_until(&mut self, _deadline: Timestamp) {
-		unimplemented!("not needed in tests so far")
+	fn sleep_until(&mut self, deadline: Timestamp) {
+		self.0.write().timestamp = deadline.unix_millis();
 	}
 
 	fn random_seed(&mut self) -> [u8; 32] {
@@ -178,6 +178,14 @@ impl offchain::Externalities for TestOffchainExt {
 		}.set(b"", key, value);
 	}
 
+	fn local_storage_clear(&mut self, kind: StorageKind, key: &[u8]) {
+		let mut state = self.0.write();
+		match kind {
+			StorageKind::LOCAL => &mut state.local_storage,
+			StorageKind::PERSISTENT => &mut state.persistent_storage,
+		}.remove(b"", key);
+	}
+
 	fn local_storage_compare_and_set(
 		&mut self,
 		kind: StorageKind,
@@ -28,6 +28,7 @@ use codec::{Encode, Decode, Input, Codec};
 use sp_runtime::{ConsensusEngineId, RuntimeDebug, traits::NumberFor};
 use sp_std::borrow::Cow;
 use sp_std::vec::Vec;
+use sp_type_info::{TypeInfo, Type, TypeDef, Path, Variant, Field, meta_type};
 
 #[cfg(feature = "std")]
 use log::debug;
@@ -168,7 +169,7 @@ impl<N: Codec> ConsensusLog<N> {
 /// GRANDPA happens when a voter votes on the same round (either at prevote or
 /// precommit stage) for different blocks. Proving is achieved by collecting the
 /// signed messages of conflicting votes.
-#[derive(Clone, Debug, Decode, Encode, PartialEq)]
+#[derive(Clone, Debug, Decode, Encode, PartialEq, TypeInfo)]
 pub struct EquivocationProof<H, N> {
 	set_id: SetId,
 	equivocation: Equivocation<H, N>,
@@ -213,6 +214,72 @@ pub enum Equivocation<H, N> {
 	Precommit(grandpa::Equivocation<AuthorityId, grandpa::Precommit<H, N>, AuthoritySignature>),
 }
 
+/// Types encoded like the `finality-grandpa` types contained in an `Equivocation`, which are
+/// used to describe them.
+#[allow(dead_code)]
+mod grandpa_type_info {
+	use sp_type_info::TypeInfo;
+	use super::{AuthorityId, AuthoritySignature};
+
+	#[derive(TypeInfo)]
+	pub struct Prevote<H, N> {
+		pub target_hash: H,
+		pub target_number: N,
+	}
+
+	#[derive(TypeInfo)]
+	pub struct Precommit<H, N> {
+		pub target_hash: H,
+		pub target_number: N,
+	}
+
+	#[derive(TypeInfo)]
+	pub struct Equivocation<V> {
+		pub round_number: u64,
+		pub identity: AuthorityId,
+		pub first: (V, AuthoritySignature),
+		pub second: (V, AuthoritySignature),
+	}
+}
+
+impl<H: TypeInfo + 'static, N: TypeInfo + 'static> TypeInfo for Equivocation<H, N> {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		use grandpa_type_info::{Prevote, Precommit, Equivocation};
+
+		Type::new(
+			Path::new("Equivocation", module_path!()),
+			TypeDef::Variant(sp_std::vec![
+				Variant::new(
+					"Prevote",
+					0,
+					sp_std::vec![Field::unnamed::<Equivocation<Prevote<H, N>>>(
+						concat!(
+							"grandpa::Equivocation<AuthorityId, grandpa::Prevote<H, N>, ",
+							"AuthoritySignature>",
+						),
+						&[],
+					)],
+					&["Proof of equivocation at prevote stage."],
+				),
+				Variant::new(
+					"Precommit",
+					1,
+					sp_std::vec![Field::unnamed::<Equivocation<Precommit<H, N>>>(
+						concat!(
+							"grandpa::Equivocation<AuthorityId, grandpa::Precommit<H, N>, ",
+							"AuthoritySignature>",
+						),
+						&[],
+					)],
+					&["Proof of equivocation at precommit stage."],
+				),
+			]),
+		).type_params(sp_std::vec![meta_type::<H>(), meta_type::<N>()])
+	}
+}
+
 impl<H, N> From<grandpa::Equivocation<AuthorityId, grandpa::Prevote<H, N>, AuthoritySignature>>
 	for Equivocation<H, N>
 {
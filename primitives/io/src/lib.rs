@@ -154,6 +154,46 @@ pub trait Storage {
 	fn next_key(&mut self, key: &[u8]) -> Option<Vec<u8>> {
 		self.next_storage_key(&key)
 	}
+
+	/// Start a new nested transaction.
+	///
+	/// This allows to either commit or roll back all changes that are made after this call.
+	/// For every transaction there must be a matching call to either `rollback_transaction`
+	/// or `commit_transaction`. This is also effective for all values manipulated using the
+	/// `DefaultChildStorage` API.
+	///
+	/// # Warning
+	///
+	/// This is a low level API that is potentially dangerous as it can easily result
+	/// in unbalanced transactions. For example, FRAME users should use high level storage
+	/// abstractions.
+	fn start_transaction(&mut self) {
+		self.storage_start_transaction();
+	}
+
+	/// Rollback the last transaction started by `start_transaction`.
+	///
+	/// Any changes made during that transaction are discarded.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	fn rollback_transaction(&mut self) {
+		self.storage_rollback_transaction()
+			.expect("No open transaction that can be rolled back.");
+	}
+
+	/// Commit the last transaction started by `start_transaction`.
+	///
+	/// Any changes made during that transaction are committed.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	fn commit_transaction(&mut self) {
+		self.storage_commit_transaction()
+			.expect("No open transaction that can be committed.");
+	}
 }
 
 /// Interface for accessing the child storage for default child trie,
@@ -711,6 +751,16 @@ pub trait Offchain {
 			.local_storage_set(kind, key, value)
 	}
 
+	/// Remove a value from the local storage.
+	///
+	/// Note this storage is not part of the consensus, it's only accessible by
+	/// offchain worker tasks running on the same machine. It IS persisted between runs.
+	fn local_storage_clear(&mut self, kind: StorageKind, key: &[u8]) {
+		self.extension::<OffchainExt>()
+			.expect("local_storage_clear can be called only in the offchain worker context")
+			.local_storage_clear(kind, key)
+	}
+
 	/// Sets a value in the local storage if it matches current value.
 	///
 	/// Since multiple offchain workers may be running concurrently, to prevent
@@ -870,6 +920,27 @@ pub trait Logging {
 	}
 }
 
+/// Wasm-only interface that provides functions for tracing the execution of the runtime.
+///
+/// The spans are entered on the host on behalf of the runtime, see `sp_tracing::proxy`.
+///
+/// The functions are not traced themselves, as the spans they enter would otherwise become children
+/// of the span of the host function.
+#[runtime_interface(wasm_only, no_tracing)]
+pub trait WasmTracing {
+	/// Enter a span with the given `target` and `name`.
+	///
+	/// Returns the id of the span, which needs to be passed to `exit_span`.
+	fn enter_span(&mut self, target: &str, name: &str) -> u64 {
+		sp_tracing::proxy::create_registered_span(target, name)
+	}
+
+	/// Exit the span with the given `id`.
+	fn exit_span(&mut self, id: u64) {
+		sp_tracing::proxy::exit_span(id)
+	}
+}
+
 /// Wasm-only interface that provides functions for interacting with the sandbox.
 #[runtime_interface(wasm_only)]
 pub trait Sandbox {
@@ -1021,6 +1092,7 @@ pub type SubstrateHostFunctions = (
 	hashing::HostFunctions,
 	allocator::HostFunctions,
 	logging::HostFunctions,
+	wasm_tracing::HostFunctions,
 	sandbox::HostFunctions,
 	crate::trie::HostFunctions,
 	offchain_index::HostFunctions,
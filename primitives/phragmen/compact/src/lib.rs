@@ -167,6 +167,7 @@ fn struct_def(
 			Debug,
 			_phragmen::codec::Encode,
 			_phragmen::codec::Decode,
+			_phragmen::sp_type_info::TypeInfo,
 		)]
 		#vis struct #ident<#voter_type, #target_type, #weight_type> {
 			// _marker: sp_std::marker::PhantomData<A>,
@@ -64,6 +64,8 @@ pub use helpers::*;
 pub use codec;
 #[doc(hidden)]
 pub use sp_arithmetic;
+#[doc(hidden)]
+pub use sp_type_info;
 
 // re-export the compact solution type.
 pub use sp_phragmen_compact::generate_compact_solution_type;
@@ -0,0 +1,110 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types for working with tracing data.
+
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+/// The result of re-executing a block with tracing enabled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrace {
+	/// Hash of the block being traced.
+	pub block_hash: String,
+	/// Hash of the parent of the block being traced.
+	pub parent_hash: String,
+	/// The targets that were traced.
+	pub tracing_targets: String,
+	/// The spans that were recorded, in the order they were closed.
+	pub spans: Vec<Span>,
+	/// The events that were recorded, in execution order. Storage accesses are not included.
+	pub events: Vec<Event>,
+	/// The storage accesses, in execution order.
+	pub storage: Vec<StorageAccess>,
+}
+
+/// A span of the execution, with its timing.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+	/// Id of the span, unique within the trace.
+	pub id: u64,
+	/// Id of the span this span was entered in, if any.
+	pub parent_id: Option<u64>,
+	/// Name of the span.
+	pub name: String,
+	/// Target of the span, usually the module it is defined in.
+	pub target: String,
+	/// Line of the source code the span is defined at.
+	pub line: u32,
+	/// Nanoseconds from the start of the execution to the first entry of the span.
+	pub start_ns: u64,
+	/// Nanoseconds the span was entered in total.
+	pub duration_ns: u64,
+	/// The values recorded for the span.
+	pub values: BTreeMap<String, String>,
+}
+
+/// An event emitted during the execution.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// Id of the span the event was emitted in, if any.
+	pub parent_id: Option<u64>,
+	/// Target of the event, usually the module it is emitted from.
+	pub target: String,
+	/// The values recorded for the event.
+	pub values: BTreeMap<String, String>,
+}
+
+/// The kind of a storage access.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageAccessKind {
+	/// A value was read.
+	Read,
+	/// Whether a value exists was checked.
+	Exists,
+	/// A value was written.
+	Write,
+	/// A value was removed.
+	Remove,
+	/// Data was appended to a value.
+	Append,
+	/// All values with the given key as prefix were removed.
+	ClearPrefix,
+	/// The whole child trie was removed.
+	KillChild,
+}
+
+/// A read from or a write to the storage.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageAccess {
+	/// Id of the span the storage was accessed in, if any.
+	pub parent_id: Option<u64>,
+	/// The kind of access.
+	pub kind: StorageAccessKind,
+	/// The storage key of the child trie, in hex, if a child trie was accessed.
+	pub child_trie: Option<String>,
+	/// The accessed key, in hex. For `ClearPrefix` this is the prefix, and for `KillChild` it is
+	/// empty.
+	pub key: String,
+	/// The blake2-256 hash of the read or written value, in hex. For `Append` it is the hash of
+	/// the appended data.
+	pub value_hash: Option<String>,
+}
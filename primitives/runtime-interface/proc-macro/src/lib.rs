@@ -37,9 +37,9 @@ pub fn runtime_interface(
 	input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
 	let trait_def = parse_macro_input!(input as ItemTrait);
-	let wasm_only = parse_macro_input!(attrs as Option<runtime_interface::keywords::wasm_only>);
+	let options = parse_macro_input!(attrs as runtime_interface::Options);
 
-	runtime_interface::runtime_interface_impl(trait_def, wasm_only.is_some())
+	runtime_interface::runtime_interface_impl(trait_def, options)
 		.unwrap_or_else(|e| e.to_compile_error())
 		.into()
 }
@@ -18,7 +18,7 @@ use crate::utils::generate_runtime_interface_include;
 
 use proc_macro2::{Span, TokenStream};
 
-use syn::{Ident, ItemTrait, Result};
+use syn::{Ident, ItemTrait, Result, parse::{Parse, ParseStream}, Token};
 
 use inflector::Inflector;
 
@@ -32,18 +32,54 @@ mod trait_decl_impl;
 pub mod keywords {
 	// Custom keyword `wasm_only` that can be given as attribute to [`runtime_interface`].
 	syn::custom_keyword!(wasm_only);
+	// Custom keyword `no_tracing` that can be given as attribute to [`runtime_interface`].
+	syn::custom_keyword!(no_tracing);
+}
+
+/// The options given to the `runtime_interface` attribute.
+pub struct Options {
+	/// Is this a wasm only interface?
+	pub wasm_only: bool,
+	/// Should the functions of the interface be traced?
+	pub tracing: bool,
+}
+
+impl Parse for Options {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let mut options = Options { wasm_only: false, tracing: true };
+
+		while !input.is_empty() {
+			let lookahead = input.lookahead1();
+			if lookahead.peek(keywords::wasm_only) {
+				input.parse::<keywords::wasm_only>()?;
+				options.wasm_only = true;
+			} else if lookahead.peek(keywords::no_tracing) {
+				input.parse::<keywords::no_tracing>()?;
+				options.tracing = false;
+			} else {
+				return Err(lookahead.error())
+			}
+
+			if !input.is_empty() {
+				input.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(options)
+	}
 }
 
 /// Implementation of the `runtime_interface` attribute.
 ///
-/// It expects the trait definition the attribute was put above and if this should be an wasm only
-/// interface.
-pub fn runtime_interface_impl(trait_def: ItemTrait, is_wasm_only: bool) -> Result<TokenStream> {
-	let bare_functions = bare_function_interface::generate(&trait_def, is_wasm_only)?;
+/// It expects the trait definition the attribute was put above and the options given to the
+/// attribute.
+pub fn runtime_interface_impl(trait_def: ItemTrait, options: Options) -> Result<TokenStream> {
+	let is_wasm_only = options.wasm_only;
+	let bare_functions = bare_function_interface::generate(&trait_def, is_wasm_only, options.tracing)?;
 	let crate_include = generate_runtime_interface_include();
 	let mod_name = Ident::new(&trait_def.ident.to_string().to_snake_case(), Span::call_site());
 	let trait_decl_impl = trait_decl_impl::process(&trait_def, is_wasm_only)?;
-	let host_functions = host_function_interface::generate(&trait_def, is_wasm_only)?;
+	let host_functions = host_function_interface::generate(&trait_def, is_wasm_only, options.tracing)?;
 	let vis = trait_def.vis;
 	let attrs = &trait_def.attrs;
 
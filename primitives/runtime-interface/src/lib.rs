@@ -283,6 +283,13 @@ pub use sp_std;
 /// 1. The generated functions are not callable from the native side.
 /// 2. The trait as shown above is not implemented for `Externalities` and is instead implemented
 ///    for `FunctionExecutor` (from `sp-wasm-interface`).
+///
+/// # Disabling tracing
+///
+/// By default every function of the interface is executed within a tracing span named after the
+/// function. Interfaces that manage spans themselves can disable this by calling the macro like
+/// `#[runtime_interface(no_tracing)]`, which can be combined with `wasm_only` as
+/// `#[runtime_interface(wasm_only, no_tracing)]`.
 pub use sp_runtime_interface_proc_macro::runtime_interface;
 
 #[doc(hidden)]
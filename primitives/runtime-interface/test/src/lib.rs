@@ -45,6 +45,8 @@ fn call_wasm_method_with_result<HF: HostFunctionsT>(
 		Some(8),
 		host_functions,
 		8,
+		None,
+		None,
 	);
 	executor.call_in_wasm(
 		binary,
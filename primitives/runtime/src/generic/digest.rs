@@ -24,9 +24,10 @@ use sp_std::prelude::*;
 use crate::ConsensusEngineId;
 use crate::codec::{Decode, Encode, Input, Error};
 use sp_core::{ChangesTrieConfiguration, RuntimeDebug};
+use sp_type_info::{TypeInfo, Type, TypeDef, Path, Variant, Field};
 
 /// Generic header digest.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, parity_util_mem::MallocSizeOf))]
 pub struct Digest<Hash> {
 	/// A list of logs in the digest.
@@ -117,7 +118,7 @@ pub enum DigestItem<Hash> {
 }
 
 /// Available changes trie signals.
-#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug, parity_util_mem::MallocSizeOf))]
 pub enum ChangesTrieSignal {
 	/// New changes trie configuration is enacted, starting from **next block**.
@@ -276,6 +277,43 @@ impl<Hash: Encode> Encode for DigestItem<Hash> {
 
 impl<Hash: Encode> codec::EncodeLike for DigestItem<Hash> {}
 
+impl<Hash: TypeInfo + 'static> TypeInfo for DigestItem<Hash> {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		let raw = |type_name| sp_std::vec![
+			Field::unnamed::<ConsensusEngineId>("ConsensusEngineId", &[]),
+			Field::unnamed::<Vec<u8>>(type_name, &[]),
+		];
+		Type::new(
+			Path::new("DigestItem", module_path!()),
+			TypeDef::Variant(sp_std::vec![
+				Variant::new(
+					"Other",
+					DigestItemType::Other as u8,
+					sp_std::vec![Field::unnamed::<Vec<u8>>("Vec<u8>", &[])],
+					&[],
+				),
+				Variant::new(
+					"ChangesTrieRoot",
+					DigestItemType::ChangesTrieRoot as u8,
+					sp_std::vec![Field::unnamed::<Hash>("Hash", &[])],
+					&[],
+				),
+				Variant::new("Consensus", DigestItemType::Consensus as u8, raw("Vec<u8>"), &[]),
+				Variant::new("Seal", DigestItemType::Seal as u8, raw("Vec<u8>"), &[]),
+				Variant::new("PreRuntime", DigestItemType::PreRuntime as u8, raw("Vec<u8>"), &[]),
+				Variant::new(
+					"ChangesTrieSignal",
+					DigestItemType::ChangesTrieSignal as u8,
+					sp_std::vec![Field::unnamed::<ChangesTrieSignal>("ChangesTrieSignal", &[])],
+					&[],
+				),
+			]),
+		).type_params(sp_std::vec![sp_type_info::meta_type::<Hash>()])
+	}
+}
+
 impl<Hash: Decode> Decode for DigestItem<Hash> {
 	#[allow(deprecated)]
 	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
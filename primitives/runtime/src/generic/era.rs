@@ -20,6 +20,7 @@
 use serde::{Serialize, Deserialize};
 
 use crate::codec::{Decode, Encode, Input, Output, Error};
+use sp_type_info::{TypeInfo, Type, TypeDef, Path, Variant, Field};
 
 /// Era period
 pub type Period = u64;
@@ -120,6 +121,46 @@ impl Encode for Era {
 
 impl codec::EncodeLike for Era {}
 
+impl TypeInfo for Era {
+	type Identity = Self;
+
+	/// An immortal era is encoded as a zero byte, a mortal era as two bytes the first of which is
+	/// non-zero: the era is described as a variant per value of that first byte.
+	fn type_info() -> Type {
+		macro_rules! mortal_variants {
+			( $( $n:literal )* ) => {
+				sp_std::vec![
+					Variant::new("Immortal", 0, sp_std::vec![], &[]),
+					$( Variant::new(
+						concat!("Mortal", $n),
+						$n,
+						sp_std::vec![Field::unnamed::<u8>("u8", &[])],
+						&[],
+					), )*
+				]
+			}
+		}
+
+		Type::new(
+			Path::new("Era", module_path!()),
+			TypeDef::Variant(mortal_variants!(
+				1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29
+				30 31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55
+				56 57 58 59 60 61 62 63 64 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79 80 81
+				82 83 84 85 86 87 88 89 90 91 92 93 94 95 96 97 98 99 100 101 102 103 104 105
+				106 107 108 109 110 111 112 113 114 115 116 117 118 119 120 121 122 123 124 125
+				126 127 128 129 130 131 132 133 134 135 136 137 138 139 140 141 142 143 144 145
+				146 147 148 149 150 151 152 153 154 155 156 157 158 159 160 161 162 163 164 165
+				166 167 168 169 170 171 172 173 174 175 176 177 178 179 180 181 182 183 184 185
+				186 187 188 189 190 191 192 193 194 195 196 197 198 199 200 201 202 203 204 205
+				206 207 208 209 210 211 212 213 214 215 216 217 218 219 220 221 222 223 224 225
+				226 227 228 229 230 231 232 233 234 235 236 237 238 239 240 241 242 243 244 245
+				246 247 248 249 250 251 252 253 254 255
+			)),
+		)
+	}
+}
+
 impl Decode for Era {
 	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
 		let first = input.read_byte()?;
@@ -18,7 +18,9 @@
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use crate::codec::{Decode, Encode, Codec, Input, Output, HasCompact, EncodeAsRef, Error};
+use crate::codec::{
+	Decode, Encode, Codec, Input, Output, HasCompact, EncodeAsRef, Error, Compact,
+};
 use crate::traits::{
 	self, Member, AtLeast32Bit, SimpleBitOps, Hash as HashT,
 	MaybeSerializeDeserialize, MaybeSerialize, MaybeDisplay,
@@ -26,6 +28,7 @@ use crate::traits::{
 };
 use crate::generic::Digest;
 use sp_core::U256;
+use sp_type_info::{TypeInfo, Type, TypeDef, Path, Field, meta_type};
 use sp_std::{
 	convert::TryFrom,
 	fmt::Debug,
@@ -120,6 +123,27 @@ impl<Number, Hash> codec::EncodeLike for Header<Number, Hash> where
 	Hash::Output: Encode,
 {}
 
+impl<Number, Hash> TypeInfo for Header<Number, Hash> where
+	Number: TypeInfo + Copy + Into<U256> + TryFrom<U256> + 'static,
+	Hash: HashT + 'static,
+	Hash::Output: TypeInfo + 'static,
+{
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		Type::new(
+			Path::new("Header", module_path!()),
+			TypeDef::Composite(sp_std::vec![
+				Field::named::<Hash::Output>("parent_hash", "Hash::Output", &[]),
+				Field::named::<Compact<Number>>("number", "Number", &[]),
+				Field::named::<Hash::Output>("state_root", "Hash::Output", &[]),
+				Field::named::<Hash::Output>("extrinsics_root", "Hash::Output", &[]),
+				Field::named::<Digest<Hash::Output>>("digest", "Digest<Hash::Output>", &[]),
+			]),
+		).type_params(sp_std::vec![meta_type::<Number>()])
+	}
+}
+
 impl<Number, Hash> traits::Header for Header<Number, Hash> where
 	Number: Member + MaybeSerializeDeserialize + Debug + sp_std::hash::Hash + MaybeDisplay +
 		AtLeast32Bit + Codec + Copy + Into<U256> + TryFrom<U256> + sp_std::str::FromStr +
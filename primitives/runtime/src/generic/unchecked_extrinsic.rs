@@ -322,6 +322,7 @@ mod tests {
 	use crate::codec::{Encode, Decode};
 	use crate::traits::{SignedExtension, IdentityLookup};
 	use crate::testing::TestSignature as TestSig;
+	use sp_type_info::TypeInfo;
 
 	type TestContext = IdentityLookup<u64>;
 	type TestAccountId = u64;
@@ -330,7 +331,7 @@ mod tests {
 	const TEST_ACCOUNT: TestAccountId = 0;
 
 	// NOTE: this is demonstration. One can simply use `()` for testing.
-	#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Ord, PartialOrd)]
+	#[derive(Debug, Encode, Decode, Clone, Eq, PartialEq, Ord, PartialOrd, TypeInfo)]
 	struct TestExtra;
 	impl SignedExtension for TestExtra {
 		const IDENTIFIER: &'static str = "TestExtra";
@@ -18,5 +18,6 @@
 
 pub mod http;
 pub mod storage;
+pub mod storage_lock;
 
 pub use sp_core::offchain::*;
@@ -49,6 +49,11 @@ impl<'a> StorageValueRef<'a> {
 		})
 	}
 
+	/// Remove the associated value from the storage.
+	pub fn clear(&mut self) {
+		sp_io::offchain::local_storage_clear(self.kind, self.key)
+	}
+
 	/// Retrieve & decode the value from storage.
 	///
 	/// Note that if you want to do some checks based on the value
@@ -153,4 +158,23 @@ mod tests {
 			assert_eq!(res, Err(()));
 		})
 	}
+
+	#[test]
+	fn should_clear() {
+		let (offchain, state) = testing::TestOffchainExt::new();
+		let mut t = TestExternalities::default();
+		t.register_extension(OffchainExt::new(offchain));
+
+		t.execute_with(|| {
+			let mut val = StorageValue::persistent(b"testval");
+
+			val.set(&15_u32);
+			assert_eq!(val.get::<u32>(), Some(Some(15_u32)));
+
+			val.clear();
+
+			assert_eq!(val.get::<u32>(), None);
+			assert_eq!(state.read().persistent_storage.get(b"", b"testval"), None);
+		})
+	}
 }
@@ -38,6 +38,7 @@ pub use sp_arithmetic::traits::{
 	CheckedShl, CheckedShr, IntegerSquareRoot
 };
 use sp_application_crypto::AppKey;
+use sp_type_info::{TypeInfo, MetaType, meta_type};
 use impl_trait_for_tuples::impl_for_tuples;
 use crate::DispatchResult;
 
@@ -183,7 +184,7 @@ pub trait Lookup {
 /// context.
 pub trait StaticLookup {
 	/// Type to lookup from.
-	type Source: Codec + Clone + PartialEq + Debug;
+	type Source: Codec + Clone + PartialEq + Debug + TypeInfo;
 	/// Type to lookup into.
 	type Target;
 	/// Attempt a lookup.
@@ -195,7 +196,7 @@ pub trait StaticLookup {
 /// A lookup implementation returning the input value.
 #[derive(Default)]
 pub struct IdentityLookup<T>(PhantomData<T>);
-impl<T: Codec + Clone + PartialEq + Debug> StaticLookup for IdentityLookup<T> {
+impl<T: Codec + Clone + PartialEq + Debug + TypeInfo> StaticLookup for IdentityLookup<T> {
 	type Source = T;
 	type Target = T;
 	fn lookup(x: T) -> Result<T, LookupError> { Ok(x) }
@@ -655,9 +656,22 @@ impl Dispatchable for () {
 	}
 }
 
+/// The metadata of a [`SignedExtension`], exposed in the runtime metadata.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct SignedExtensionMetadata {
+	/// The unique identifier of the signed extension.
+	pub identifier: &'static str,
+	/// The type of the signed extension, as included in the extrinsic.
+	pub ty: MetaType,
+	/// The type of the additional data signed with the extrinsic.
+	pub additional_signed: MetaType,
+}
+
 /// Means by which a transaction may be extended. This type embodies both the data and the logic
 /// that should be additionally associated with the transaction. It should be plain old data.
-pub trait SignedExtension: Codec + Debug + Sync + Send + Clone + Eq + PartialEq {
+pub trait SignedExtension:
+	Codec + Debug + Sync + Send + Clone + Eq + PartialEq + TypeInfo + 'static
+{
 	/// Unique identifier of this signed extension.
 	///
 	/// This will be exposed in the metadata to identify the signed extension used
@@ -672,7 +686,7 @@ pub trait SignedExtension: Codec + Debug + Sync + Send + Clone + Eq + PartialEq
 
 	/// Any additional data that will go into the signed payload. This may be created dynamically
 	/// from the transaction using the `additional_signed` function.
-	type AdditionalSigned: Encode;
+	type AdditionalSigned: Encode + TypeInfo + 'static;
 
 	/// The type that encodes information that can be passed from pre_dispatch to post-dispatch.
 	type Pre: Default;
@@ -787,6 +801,18 @@ pub trait SignedExtension: Codec + Debug + Sync + Send + Clone + Eq + PartialEq
 	fn identifier() -> Vec<&'static str> {
 		sp_std::vec![Self::IDENTIFIER]
 	}
+
+	/// Returns the metadata of this signed extension.
+	///
+	/// Like [`identifier`](SignedExtension::identifier), this returns one entry for each
+	/// individual `SignedExtension`, in the order they appear in the extrinsic.
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		sp_std::vec![SignedExtensionMetadata {
+			identifier: Self::IDENTIFIER,
+			ty: meta_type::<Self>(),
+			additional_signed: meta_type::<Self::AdditionalSigned>(),
+		}]
+	}
 }
 
 #[impl_for_tuples(1, 12)]
@@ -854,6 +880,12 @@ impl<AccountId, Call: Dispatchable> SignedExtension for Tuple {
 		for_tuples!( #( ids.extend(Tuple::identifier()); )* );
 		ids
 	}
+
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		let mut metadata = Vec::new();
+		for_tuples!( #( metadata.extend(Tuple::metadata()); )* );
+		metadata
+	}
 }
 
 /// Only for bare bone testing when you don't care about signed extensions at all.
@@ -1139,6 +1171,7 @@ macro_rules! impl_opaque_keys {
 			$crate::codec::Encode,
 			$crate::codec::Decode,
 			$crate::RuntimeDebug,
+			$crate::sp_type_info::TypeInfo,
 		)]
 		#[cfg_attr(feature = "std", derive($crate::serde::Serialize, $crate::serde::Deserialize))]
 		pub struct $name {
@@ -1320,6 +1353,18 @@ pub trait BlockIdTo<Block: self::Block> {
 	) -> Result<Option<NumberFor<Block>>, Self::Error>;
 }
 
+/// Provide the current block number.
+///
+/// This is mostly useful for code that cannot access `frame_system` directly,
+/// like offchain worker primitives, but needs to know the current block number.
+pub trait BlockNumberProvider {
+	/// Type of `BlockNumber` to provide.
+	type BlockNumber: Codec + Clone + Ord + Eq + AtLeast32Bit;
+
+	/// Returns the current block number.
+	fn current_block_number() -> Self::BlockNumber;
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -17,7 +17,145 @@
 //! A crate which contains primitives that are useful for implementation that uses staking
 //! approaches in general. Definitions related to sessions, slashing, etc go here.
 
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::{boxed::Box, collections::btree_map::BTreeMap, vec::Vec};
+pub use sp_phragmen::VoteWeight;
+
 pub mod offence;
 
 /// Simple index type with which we can count sessions.
 pub type SessionIndex = u32;
+
+/// Counter for the number of eras that have passed.
+pub type EraIndex = u32;
+
+/// A hook for any operation to be triggered when a staker is slashed.
+pub trait OnStakerSlash<AccountId, Balance> {
+	/// A hook for any operation to be triggered when a staker is slashed.
+	///
+	/// # Arguments
+	///
+	/// * `stash` - The stash of the staker whom the slash was applied to.
+	/// * `slashed_active` - The new bonded balance of the staker after the slash was applied.
+	/// * `slashed_unlocking` - A map of slashed eras, and the balance of that unlocking chunk
+	///   after the slash is applied. Any era not present in the map is not affected at all.
+	fn on_slash(
+		stash: &AccountId,
+		slashed_active: Balance,
+		slashed_unlocking: &BTreeMap<EraIndex, Balance>,
+	);
+}
+
+impl<AccountId, Balance> OnStakerSlash<AccountId, Balance> for () {
+	fn on_slash(_: &AccountId, _: Balance, _: &BTreeMap<EraIndex, Balance>) {
+		// Nothing to do here
+	}
+}
+
+/// A generic representation of a staking implementation.
+///
+/// This interface uses the terminology of NPoS, but it aims to be usable by other implementations
+/// as well. Funds are bonded by a _stash_ account and managed through its _controller_ account.
+/// Rewards earned by a stash that has been bonded through this interface are paid out to its
+/// controller.
+pub trait StakingInterface {
+	/// Balance type used by the staking system.
+	type Balance;
+
+	/// AccountId type used by the staking system.
+	type AccountId;
+
+	/// The minimum amount required to bond in order to be a staker.
+	fn minimum_bond() -> Self::Balance;
+
+	/// Number of eras that staked funds must remain bonded for.
+	fn bonding_duration() -> EraIndex;
+
+	/// The current era index.
+	///
+	/// This should be the latest planned era that the staking system knows about.
+	fn current_era() -> EraIndex;
+
+	/// The amount of active stake that `controller` has in the staking system.
+	fn active_stake(controller: &Self::AccountId) -> Option<Self::Balance>;
+
+	/// The total stake that `controller` has in the staking system. This includes the
+	/// [`Self::active_stake`], and any funds currently in the process of unbonding.
+	fn total_stake(controller: &Self::AccountId) -> Option<Self::Balance>;
+
+	/// Bond `value` of the free balance of `stash`, to be managed by `controller`.
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+	) -> DispatchResult;
+
+	/// Bond up to `extra` of the free balance of `stash` that is not yet bonded.
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult;
+
+	/// Schedule a portion of the active stake of `controller` to be unlocked after the
+	/// [`Self::bonding_duration`] has passed.
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult;
+
+	/// Unlock any funds of `controller` whose unbonding period has passed.
+	///
+	/// Returns `true` if the stash was killed as a result, i.e. nothing is bonded any more.
+	fn withdraw_unbonded(controller: Self::AccountId) -> Result<bool, DispatchError>;
+
+	/// Have `controller` nominate `validators`.
+	fn nominate(controller: Self::AccountId, validators: Vec<Self::AccountId>) -> DispatchResult;
+
+	/// Have `controller` stop nominating or validating.
+	fn chill(controller: Self::AccountId) -> DispatchResult;
+}
+
+/// Something that can provide the vote weight of a voter, as used in the election.
+pub trait VoteWeightProvider<AccountId> {
+	/// Get the current vote weight of `who`.
+	fn vote_weight(who: &AccountId) -> VoteWeight;
+}
+
+/// A list of voters that is kept sorted, or semi-sorted, by their vote weight.
+///
+/// This allows the election to only read the heaviest voters, instead of iterating all of them.
+/// The implementor does not track the vote weights itself; the staking system is expected to
+/// report every change through the `on_*` hooks.
+pub trait SortedListProvider<AccountId> {
+	/// The error type returned when inserting fails.
+	type Error;
+
+	/// Iterate over the voters in the list, heaviest first as far as the implementation can
+	/// tell.
+	fn iter() -> Box<dyn Iterator<Item = AccountId>>;
+
+	/// The number of voters in the list.
+	fn count() -> u32;
+
+	/// Whether `id` is in the list.
+	fn contains(id: &AccountId) -> bool;
+
+	/// Hook for inserting a new voter with the given `weight`.
+	fn on_insert(id: AccountId, weight: VoteWeight) -> Result<(), Self::Error>;
+
+	/// Hook for the vote weight of an existing voter changing to `weight`.
+	fn on_update(id: &AccountId, weight: VoteWeight);
+
+	/// Hook for removing a voter from the list.
+	fn on_remove(id: &AccountId);
+
+	/// Clear the list and rebuild it from `all`, with the weights given by `weight_of`.
+	///
+	/// This is meant to be used in migrations only. Returns the number of voters inserted.
+	fn regenerate(
+		all: impl IntoIterator<Item = AccountId>,
+		weight_of: Box<dyn Fn(&AccountId) -> VoteWeight>,
+	) -> u32;
+
+	/// Remove all voters from the list.
+	fn clear();
+
+	/// Check the internal consistency of the list.
+	///
+	/// This is expensive and meant to be used in tests only.
+	fn sanity_check() -> Result<(), &'static str>;
+}
@@ -20,6 +20,7 @@
 use sp_std::vec::Vec;
 
 use codec::{Encode, Decode};
+use sp_type_info::TypeInfo;
 use sp_runtime::Perbill;
 
 use crate::SessionIndex;
@@ -167,7 +168,7 @@ impl<Reporter, Offender> OnOffenceHandler<Reporter, Offender> for () {
 }
 
 /// A details about an offending authority for a particular kind of offence.
-#[derive(Clone, PartialEq, Eq, Encode, Decode, sp_runtime::RuntimeDebug)]
+#[derive(Clone, PartialEq, Eq, Encode, Decode, sp_runtime::RuntimeDebug, TypeInfo)]
 pub struct OffenceDetails<Reporter, Offender> {
 	/// The offending authority id
 	pub offender: Offender,
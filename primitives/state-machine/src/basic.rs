@@ -38,13 +38,15 @@ use sp_externalities::Extensions;
 #[derive(Debug)]
 pub struct BasicExternalities {
 	inner: Storage,
+	/// Snapshots of `inner` taken when the open storage transactions were started.
+	transactions: Vec<Storage>,
 	extensions: Extensions,
 }
 
 impl BasicExternalities {
 	/// Create a new instance of `BasicExternalities`
 	pub fn new(inner: Storage) -> Self {
-		BasicExternalities { inner, extensions: Default::default() }
+		BasicExternalities { inner, transactions: Vec::new(), extensions: Default::default() }
 	}
 
 	/// New basic externalities with empty storage.
@@ -59,6 +61,7 @@ impl BasicExternalities {
 
 		Self {
 			inner: Storage::default(),
+			transactions: Vec::new(),
 			extensions,
 		}
 	}
@@ -85,6 +88,7 @@ impl BasicExternalities {
 				top: std::mem::take(&mut storage.top),
 				children_default: std::mem::take(&mut storage.children_default),
 			},
+			transactions: Vec::new(),
 			extensions: Default::default(),
 		};
 
@@ -134,6 +138,7 @@ impl From<BTreeMap<StorageKey, StorageValue>> for BasicExternalities {
 				top: hashmap,
 				children_default: Default::default(),
 			},
+			transactions: Vec::new(),
 			extensions: Default::default(),
 		}
 	}
@@ -306,6 +311,19 @@ impl Externalities for BasicExternalities {
 		Ok(None)
 	}
 
+	fn storage_start_transaction(&mut self) {
+		self.transactions.push(self.inner.clone());
+	}
+
+	fn storage_rollback_transaction(&mut self) -> Result<(), ()> {
+		self.inner = self.transactions.pop().ok_or(())?;
+		Ok(())
+	}
+
+	fn storage_commit_transaction(&mut self) -> Result<(), ()> {
+		self.transactions.pop().map(drop).ok_or(())
+	}
+
 	fn wipe(&mut self) {}
 
 	fn commit(&mut self) {}
@@ -387,6 +405,31 @@ mod tests {
 		assert_eq!(ext.child_storage(child_info, b"doe"), None);
 	}
 
+	#[test]
+	fn transactions_work() {
+		let mut ext = BasicExternalities::default();
+		ext.set_storage(b"doe".to_vec(), b"reindeer".to_vec());
+		assert_eq!(ext.storage_rollback_transaction(), Err(()));
+		assert_eq!(ext.storage_commit_transaction(), Err(()));
+
+		ext.storage_start_transaction();
+		ext.set_storage(b"dog".to_vec(), b"puppy".to_vec());
+		ext.storage_start_transaction();
+		ext.clear_storage(b"doe");
+		ext.set_storage(b"dog".to_vec(), b"cat".to_vec());
+		assert_eq!(ext.storage_rollback_transaction(), Ok(()));
+		assert_eq!(ext.storage(b"doe"), Some(b"reindeer".to_vec()));
+		assert_eq!(ext.storage(b"dog"), Some(b"puppy".to_vec()));
+
+		ext.storage_start_transaction();
+		ext.set_storage(b"dogglesworth".to_vec(), b"cat".to_vec());
+		assert_eq!(ext.storage_commit_transaction(), Ok(()));
+		assert_eq!(ext.storage_commit_transaction(), Ok(()));
+		assert_eq!(ext.storage_commit_transaction(), Err(()));
+		assert_eq!(ext.storage(b"dog"), Some(b"puppy".to_vec()));
+		assert_eq!(ext.storage(b"dogglesworth"), Some(b"cat".to_vec()));
+	}
+
 	#[test]
 	fn basic_externalities_is_empty() {
 		// Make sure no values are set by default in `BasicExternalities`.
@@ -26,7 +26,7 @@ use hash_db::Hasher;
 use sp_core::{
 	offchain::storage::OffchainOverlayedChanges,
 	storage::{well_known_keys::is_child_storage_key, ChildInfo},
-	traits::Externalities, hexdisplay::HexDisplay,
+	traits::Externalities, hexdisplay::HexDisplay, hashing::blake2_256,
 };
 use sp_trie::{trie_types::Layout, empty_child_trie_root};
 use sp_externalities::{Extensions, Extension};
@@ -37,6 +37,15 @@ use log::{warn, trace};
 
 const EXT_NOT_ALLOWED_TO_FAIL: &str = "Externalities not allowed to fail within runtime";
 
+/// The hash of a storage value, as recorded by the `tracing` events of storage accesses, or an
+/// empty string if there is no value.
+///
+/// Storage accesses are reported as `tracing` events with target `state`, so that they can be
+/// collected in execution order, e.g. when tracing the execution of a block.
+fn value_hash(value: Option<&[u8]>) -> String {
+	value.map(|v| HexDisplay::from(&blake2_256(v)).to_string()).unwrap_or_default()
+}
+
 /// Errors that can occur when interacting with the externalities.
 #[derive(Debug, Copy, Clone)]
 pub enum Error<B, E> {
@@ -180,6 +189,12 @@ where
 			HexDisplay::from(&key),
 			result.as_ref().map(HexDisplay::from)
 		);
+		tracing::trace!(target: "state",
+			method = "Get",
+			ext_id = self.id,
+			key = %HexDisplay::from(&key),
+			value_hash = %value_hash(result.as_deref()),
+		);
 		result
 	}
 
@@ -218,6 +233,13 @@ where
 			HexDisplay::from(&key),
 			result.as_ref().map(HexDisplay::from)
 		);
+		tracing::trace!(target: "state",
+			method = "ChildGet",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+			key = %HexDisplay::from(&key),
+			value_hash = %value_hash(result.as_deref()),
+		);
 
 		result
 	}
@@ -258,6 +280,12 @@ where
 			HexDisplay::from(&key),
 			result,
 		);
+		tracing::trace!(target: "state",
+			method = "Exists",
+			ext_id = self.id,
+			key = %HexDisplay::from(&key),
+			result,
+		);
 
 		result
 	}
@@ -282,6 +310,13 @@ where
 			HexDisplay::from(&key),
 			result,
 		);
+		tracing::trace!(target: "state",
+			method = "ChildExists",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+			key = %HexDisplay::from(&key),
+			result,
+		);
 		result
 	}
 
@@ -333,6 +368,12 @@ where
 			HexDisplay::from(&key),
 			value.as_ref().map(HexDisplay::from)
 		);
+		tracing::trace!(target: "state",
+			method = "Put",
+			ext_id = self.id,
+			key = %HexDisplay::from(&key),
+			value_hash = %value_hash(value.as_deref()),
+		);
 		let _guard = sp_panic_handler::AbortGuard::force_abort();
 		if is_child_storage_key(&key) {
 			warn!(target: "trie", "Refuse to directly set child storage key");
@@ -355,6 +396,13 @@ where
 			HexDisplay::from(&key),
 			value.as_ref().map(HexDisplay::from)
 		);
+		tracing::trace!(target: "state",
+			method = "ChildPut",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+			key = %HexDisplay::from(&key),
+			value_hash = %value_hash(value.as_deref()),
+		);
 		let _guard = sp_panic_handler::AbortGuard::force_abort();
 
 		self.mark_dirty();
@@ -369,6 +417,11 @@ where
 			self.id,
 			HexDisplay::from(&child_info.storage_key()),
 		);
+		tracing::trace!(target: "state",
+			method = "ChildKill",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+		);
 		let _guard = sp_panic_handler::AbortGuard::force_abort();
 
 		self.mark_dirty();
@@ -383,6 +436,11 @@ where
 			self.id,
 			HexDisplay::from(&prefix),
 		);
+		tracing::trace!(target: "state",
+			method = "ClearPrefix",
+			ext_id = self.id,
+			key = %HexDisplay::from(&prefix),
+		);
 		let _guard = sp_panic_handler::AbortGuard::force_abort();
 		if is_child_storage_key(prefix) {
 			warn!(target: "trie", "Refuse to directly clear prefix that is part of child storage key");
@@ -406,6 +464,12 @@ where
 			HexDisplay::from(&child_info.storage_key()),
 			HexDisplay::from(&prefix),
 		);
+		tracing::trace!(target: "state",
+			method = "ChildClearPrefix",
+			ext_id = self.id,
+			child_info = %HexDisplay::from(&child_info.storage_key()),
+			key = %HexDisplay::from(&prefix),
+		);
 		let _guard = sp_panic_handler::AbortGuard::force_abort();
 
 		self.mark_dirty();
@@ -425,6 +489,12 @@ where
 			HexDisplay::from(&key),
 			HexDisplay::from(&value),
 		);
+		tracing::trace!(target: "state",
+			method = "Append",
+			ext_id = self.id,
+			key = %HexDisplay::from(&key),
+			value_hash = %value_hash(Some(&value[..])),
+		);
 
 		let _guard = sp_panic_handler::AbortGuard::force_abort();
 		self.mark_dirty();
@@ -553,6 +623,31 @@ where
 		root.map(|r| r.map(|o| o.encode()))
 	}
 
+	fn storage_start_transaction(&mut self) {
+		self.overlay.start_transaction();
+		trace!(target: "state", "{:04x}: StartTransaction({})",
+			self.id,
+			self.overlay.transaction_depth(),
+		);
+	}
+
+	fn storage_rollback_transaction(&mut self) -> Result<(), ()> {
+		trace!(target: "state", "{:04x}: RollbackTransaction({})",
+			self.id,
+			self.overlay.transaction_depth(),
+		);
+		self.mark_dirty();
+		self.overlay.rollback_transaction().map_err(|_| ())
+	}
+
+	fn storage_commit_transaction(&mut self) -> Result<(), ()> {
+		trace!(target: "state", "{:04x}: CommitTransaction({})",
+			self.id,
+			self.overlay.transaction_depth(),
+		);
+		self.overlay.commit_transaction().map_err(|_| ())
+	}
+
 	fn wipe(&mut self) {
 		self.overlay.discard_prospective();
 		self.overlay.drain_storage_changes(
@@ -697,6 +792,7 @@ mod tests {
 			committed: Default::default(),
 			collect_extrinsics: true,
 			stats: Default::default(),
+			transactions: Default::default(),
 		}
 	}
 
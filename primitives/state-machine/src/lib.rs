@@ -65,7 +65,7 @@ pub use changes_trie::{
 };
 pub use overlayed_changes::{
 	OverlayedChanges, StorageChanges, StorageTransactionCache, StorageKey, StorageValue,
-	StorageCollection, ChildStorageCollection,
+	StorageCollection, ChildStorageCollection, NoOpenTransaction,
 };
 pub use proving_backend::{
 	create_proof_check_backend, ProofRecorder, ProvingBackend, ProvingBackendRecorder,
@@ -749,6 +749,117 @@ where
 		.map_err(|e| Box::new(e) as Box<dyn Error>)
 }
 
+/// Reads at most `count` entries of the storage, or of the child storage if `child_info` is
+/// `Some`, whose key starts with `prefix`, in lexicographic order of the keys.
+///
+/// Starts after `start_at`, or at `prefix` if `None`. Also returns `true` if there is no other
+/// entry with this prefix.
+fn read_range<B, H>(
+	backend: &B,
+	child_info: Option<&ChildInfo>,
+	prefix: &[u8],
+	start_at: Option<&[u8]>,
+	count: u32,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let next_key = |key: &[u8]| match child_info {
+		Some(child_info) => backend.next_child_storage_key(child_info, key),
+		None => backend.next_storage_key(key),
+	}.map_err(|e| Box::new(e) as Box<dyn Error>);
+	let value = |key: &[u8]| match child_info {
+		Some(child_info) => backend.child_storage(child_info, key),
+		None => backend.storage(key),
+	}.map_err(|e| Box::new(e) as Box<dyn Error>);
+
+	let mut key = match start_at {
+		Some(start_at) if start_at >= prefix => next_key(start_at)?,
+		_ => match value(prefix)? {
+			Some(_) => Some(prefix.to_vec()),
+			None => next_key(prefix)?,
+		},
+	};
+
+	let mut entries = Vec::new();
+	while let Some(current) = key {
+		if !current.starts_with(prefix) {
+			break;
+		}
+		if entries.len() as u32 >= count {
+			return Ok((entries, false));
+		}
+		key = next_key(&current)?;
+		if let Some(value) = value(&current)? {
+			entries.push((current, value));
+		}
+	}
+
+	Ok((entries, true))
+}
+
+/// Generate a proof of a range of storage entries, to be checked with `read_range_proof_check`.
+///
+/// The proof covers at most `count` entries of the storage, or of the child storage if
+/// `child_info` is `Some`, whose key starts with `prefix`, starting after `start_at`, and proves
+/// that there is no other entry in between.
+pub fn prove_range_read<B, H>(
+	mut backend: B,
+	child_info: Option<&ChildInfo>,
+	prefix: &[u8],
+	start_at: Option<&[u8]>,
+	count: u32,
+) -> Result<StorageProof, Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let trie_backend = backend.as_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+	prove_range_read_on_trie_backend(trie_backend, child_info, prefix, start_at, count)
+}
+
+/// Generate a proof of a range of storage entries on pre-created trie backend.
+pub fn prove_range_read_on_trie_backend<S, H>(
+	trie_backend: &TrieBackend<S, H>,
+	child_info: Option<&ChildInfo>,
+	prefix: &[u8],
+	start_at: Option<&[u8]>,
+	count: u32,
+) -> Result<StorageProof, Box<dyn Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	read_range(&proving_backend, child_info, prefix, start_at, count)?;
+	Ok(proving_backend.extract_proof())
+}
+
+/// Check a proof of a range of storage entries, generated by `prove_range_read` call.
+///
+/// Returns the entries, and `true` if there is no other entry with this prefix. Fails if the
+/// proof doesn't contain all the entries of the range.
+pub fn read_range_proof_check<H>(
+	root: H::Out,
+	proof: StorageProof,
+	child_info: Option<&ChildInfo>,
+	prefix: &[u8],
+	start_at: Option<&[u8]>,
+	count: u32,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+	read_range(&proving_backend, child_info, prefix, start_at, count)
+}
+
 #[cfg(test)]
 mod tests {
 	use std::collections::BTreeMap;
@@ -1277,6 +1388,64 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn prove_range_read_and_proof_check_works() {
+		let child_info = ChildInfo::new_default(b"sub1");
+		let child_info = &child_info;
+		// fetch range proof from 'remote' full node
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let remote_proof = prove_range_read(remote_backend, None, b"value", None, 1).unwrap();
+		// check proof locally
+		let local_result = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			remote_proof.clone(),
+			None,
+			b"value",
+			None,
+			1,
+		).unwrap();
+		assert_eq!(local_result, (vec![(b"value1".to_vec(), vec![42])], false));
+		// the proof doesn't cover the rest of the range
+		assert!(read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			remote_proof,
+			None,
+			b"value",
+			Some(b"value1"),
+			2,
+		).is_err());
+		// continue after the first entry, until the end of the range
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_proof = prove_range_read(remote_backend, None, b"value", Some(b"value1"), 10)
+			.unwrap();
+		let local_result = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			remote_proof,
+			None,
+			b"value",
+			Some(b"value1"),
+			10,
+		).unwrap();
+		assert_eq!(local_result, (vec![(b"value2".to_vec(), vec![24])], true));
+		// on child trie
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_proof = prove_range_read(remote_backend, Some(child_info), b"", None, 10)
+			.unwrap();
+		let local_result = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			remote_proof,
+			Some(child_info),
+			b"",
+			None,
+			10,
+		).unwrap();
+		assert_eq!(
+			local_result,
+			(vec![(b"value3".to_vec(), vec![142]), (b"value4".to_vec(), vec![124])], true),
+		);
+	}
+
 	#[test]
 	fn child_storage_uuid() {
 
@@ -47,16 +47,31 @@ pub type StorageCollection = Vec<(StorageKey, Option<StorageValue>)>;
 /// In memory arrays of storage values for multiple child tries.
 pub type ChildStorageCollection = Vec<(StorageKey, StorageCollection)>;
 
+/// Error returned when trying to commit or rollback while no transaction is open.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoOpenTransaction;
+
 /// The overlayed changes to state to be queried on top of the backend.
 ///
 /// A transaction shares all prospective changes within an inner overlay
 /// that can be cleared.
+///
+/// On top of the prospective changes an arbitrary number of nested storage
+/// transactions can be opened with [`start_transaction`]. Every change made while
+/// a transaction is open can be undone with [`rollback_transaction`] or kept with
+/// [`commit_transaction`].
+///
+/// [`start_transaction`]: #method.start_transaction
+/// [`rollback_transaction`]: #method.rollback_transaction
+/// [`commit_transaction`]: #method.commit_transaction
 #[derive(Debug, Default, Clone)]
 pub struct OverlayedChanges {
 	/// Changes that are not yet committed.
 	pub(crate) prospective: OverlayedChangeSet,
 	/// Committed changes.
 	pub(crate) committed: OverlayedChangeSet,
+	/// The currently open storage transactions, innermost last.
+	pub(crate) transactions: Vec<TransactionJournal>,
 	/// True if extrinsics stats must be collected.
 	pub(crate) collect_extrinsics: bool,
 	/// Collect statistic on this execution.
@@ -84,6 +99,19 @@ pub struct OverlayedChangeSet {
 	pub children_default: HashMap<StorageKey, (BTreeMap<StorageKey, OverlayedValue>, ChildInfo)>,
 }
 
+/// The prospective values of the keys changed within a single storage transaction,
+/// as they were before the first change made to them within this transaction.
+///
+/// A value of `None` means that the key was not part of the prospective change set.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TransactionJournal {
+	/// Original values of the top level storage keys.
+	top: BTreeMap<StorageKey, Option<OverlayedValue>>,
+	/// Original values of the child storage keys. The flag is `true` if the child change set
+	/// already existed in the prospective change set when the transaction changed it first.
+	children_default: HashMap<StorageKey, (BTreeMap<StorageKey, Option<OverlayedValue>>, bool)>,
+}
+
 /// A storage changes structure that can be generated by the data collected in [`OverlayedChanges`].
 ///
 /// This contains all the changes to the storage and transactions to apply theses changes to the
@@ -234,6 +262,7 @@ impl OverlayedChanges {
 		init: impl Fn() -> StorageValue,
 	) -> &mut StorageValue {
 		let extrinsic_index = self.extrinsic_index();
+		self.journal_storage(key);
 		let committed = &self.committed.top;
 
 		let mut entry = self.prospective.top.entry(key.to_vec())
@@ -286,6 +315,7 @@ impl OverlayedChanges {
 		let size_write = val.as_ref().map(|x| x.len() as u64).unwrap_or(0);
 		self.stats.tally_write_overlay(size_write);
 		let extrinsic_index = self.extrinsic_index();
+		self.journal_storage(&key);
 		let entry = self.prospective.top.entry(key).or_default();
 		entry.value = val;
 
@@ -307,6 +337,7 @@ impl OverlayedChanges {
 		let size_write = val.as_ref().map(|x| x.len() as u64).unwrap_or(0);
 		self.stats.tally_write_overlay(size_write);
 		let extrinsic_index = self.extrinsic_index();
+		self.journal_child_storage(child_info.storage_key(), Some(&key[..]));
 		let storage_key = child_info.storage_key().to_vec();
 		let map_entry = self.prospective.children_default.entry(storage_key)
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
@@ -334,6 +365,7 @@ impl OverlayedChanges {
 	) {
 		let extrinsic_index = self.extrinsic_index();
 		let storage_key = child_info.storage_key();
+		self.journal_child_prefix(storage_key, &[]);
 		let map_entry = self.prospective.children_default.entry(storage_key.to_vec())
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
@@ -373,6 +405,7 @@ impl OverlayedChanges {
 	/// [`discard_prospective`]: #method.discard_prospective
 	pub(crate) fn clear_prefix(&mut self, prefix: &[u8]) {
 		let extrinsic_index = self.extrinsic_index();
+		self.journal_prefix(prefix);
 
 		// Iterate over all prospective and mark all keys that share
 		// the given prefix as removed (None).
@@ -409,6 +442,7 @@ impl OverlayedChanges {
 	) {
 		let extrinsic_index = self.extrinsic_index();
 		let storage_key = child_info.storage_key();
+		self.journal_child_prefix(storage_key, prefix);
 		let map_entry = self.prospective.children_default.entry(storage_key.to_vec())
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
@@ -442,13 +476,166 @@ impl OverlayedChanges {
 		}
 	}
 
+	/// Returns the number of currently open storage transactions.
+	pub fn transaction_depth(&self) -> usize {
+		self.transactions.len()
+	}
+
+	/// Start a new nested storage transaction.
+	///
+	/// All changes made from now on can be discarded with [`rollback_transaction`] or kept
+	/// with [`commit_transaction`]. Transactions can be nested arbitrarily, but every call
+	/// to this function must be matched by one of those two.
+	///
+	/// [`rollback_transaction`]: #method.rollback_transaction
+	/// [`commit_transaction`]: #method.commit_transaction
+	pub fn start_transaction(&mut self) {
+		self.transactions.push(Default::default());
+	}
+
+	/// Rollback the innermost open storage transaction.
+	///
+	/// All changes made since the matching [`start_transaction`] are discarded.
+	///
+	/// [`start_transaction`]: #method.start_transaction
+	pub fn rollback_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+		let journal = self.transactions.pop().ok_or(NoOpenTransaction)?;
+
+		for (key, original) in journal.top.into_iter() {
+			match original {
+				Some(value) => { self.prospective.top.insert(key, value); },
+				None => { self.prospective.top.remove(&key); },
+			}
+		}
+
+		for (storage_key, (keys, existed)) in journal.children_default.into_iter() {
+			if !existed {
+				self.prospective.children_default.remove(&storage_key);
+				continue;
+			}
+
+			if let Some((map, _)) = self.prospective.children_default.get_mut(&storage_key) {
+				for (key, original) in keys.into_iter() {
+					match original {
+						Some(value) => { map.insert(key, value); },
+						None => { map.remove(&key); },
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Commit the innermost open storage transaction.
+	///
+	/// The changes made since the matching [`start_transaction`] become part of the
+	/// enclosing transaction, or of the prospective changes if there is none.
+	///
+	/// [`start_transaction`]: #method.start_transaction
+	pub fn commit_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+		let journal = self.transactions.pop().ok_or(NoOpenTransaction)?;
+
+		// The enclosing transaction needs to be able to restore the values as they were
+		// before any of the committed changes, unless it already knows an older value.
+		if let Some(parent) = self.transactions.last_mut() {
+			for (key, original) in journal.top.into_iter() {
+				parent.top.entry(key).or_insert(original);
+			}
+
+			for (storage_key, (keys, existed)) in journal.children_default.into_iter() {
+				let parent_keys = &mut parent.children_default.entry(storage_key)
+					.or_insert_with(|| (Default::default(), existed))
+					.0;
+				for (key, original) in keys.into_iter() {
+					parent_keys.entry(key).or_insert(original);
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Record the prospective value of `key` in the innermost open transaction, if any.
+	fn journal_storage(&mut self, key: &[u8]) {
+		if let Some(journal) = self.transactions.last_mut() {
+			if !journal.top.contains_key(key) {
+				journal.top.insert(key.to_vec(), self.prospective.top.get(key).cloned());
+			}
+		}
+	}
+
+	/// Record the prospective values of all the top level keys that share the given prefix
+	/// in the innermost open transaction, if any.
+	fn journal_prefix(&mut self, prefix: &[u8]) {
+		if self.transactions.is_empty() {
+			return;
+		}
+
+		let keys = self.prospective.top.keys()
+			.chain(self.committed.top.keys())
+			.filter(|key| key.starts_with(prefix))
+			.cloned()
+			.collect::<BTreeSet<_>>();
+		for key in keys {
+			self.journal_storage(&key);
+		}
+	}
+
+	/// Record the prospective value of `key` in the given child storage in the innermost
+	/// open transaction, if any.
+	///
+	/// With `key` set to `None` only the existence of the child change set is recorded.
+	fn journal_child_storage(&mut self, storage_key: &[u8], key: Option<&[u8]>) {
+		if let Some(journal) = self.transactions.last_mut() {
+			let prospective = self.prospective.children_default.get(storage_key);
+			let (keys, _) = journal.children_default.entry(storage_key.to_vec())
+				.or_insert_with(|| (Default::default(), prospective.is_some()));
+
+			if let Some(key) = key {
+				if !keys.contains_key(key) {
+					keys.insert(
+						key.to_vec(),
+						prospective.and_then(|(map, _)| map.get(key).cloned()),
+					);
+				}
+			}
+		}
+	}
+
+	/// Record the prospective values of all the keys in the given child storage that share
+	/// the given prefix in the innermost open transaction, if any.
+	fn journal_child_prefix(&mut self, storage_key: &[u8], prefix: &[u8]) {
+		if self.transactions.is_empty() {
+			return;
+		}
+
+		self.journal_child_storage(storage_key, None);
+		let keys = self.prospective.children_default.get(storage_key)
+			.into_iter()
+			.chain(self.committed.children_default.get(storage_key))
+			.flat_map(|(map, _)| map.keys())
+			.filter(|key| key.starts_with(prefix))
+			.cloned()
+			.collect::<BTreeSet<_>>();
+		for key in keys {
+			self.journal_child_storage(storage_key, Some(&key[..]));
+		}
+	}
+
 	/// Discard prospective changes to state.
+	///
+	/// Any storage transaction that is still open is discarded as well.
 	pub fn discard_prospective(&mut self) {
+		self.transactions.clear();
 		self.prospective.clear();
 	}
 
 	/// Commit prospective changes to state.
+	///
+	/// Any storage transaction that is still open is committed as well.
 	pub fn commit_prospective(&mut self) {
+		self.transactions.clear();
 		if self.committed.is_empty() {
 			mem::swap(&mut self.prospective, &mut self.committed);
 		} else {
@@ -765,6 +952,88 @@ mod tests {
 		assert!(overlayed.storage(&key).unwrap().is_none());
 	}
 
+	#[test]
+	fn overlayed_storage_transactions_work() {
+		let mut overlayed = OverlayedChanges::default();
+
+		overlayed.set_storage(vec![1], Some(vec![1]));
+		overlayed.commit_prospective();
+		overlayed.set_storage(vec![2], Some(vec![2]));
+
+		overlayed.start_transaction();
+		overlayed.set_storage(vec![1], None);
+		overlayed.set_storage(vec![2], Some(vec![20]));
+		overlayed.set_storage(vec![3], Some(vec![3]));
+
+		overlayed.start_transaction();
+		overlayed.set_storage(vec![3], Some(vec![30]));
+		overlayed.set_storage(vec![4], Some(vec![4]));
+		assert_eq!(overlayed.transaction_depth(), 2);
+
+		// the inner transaction is committed into the outer one.
+		overlayed.commit_transaction().unwrap();
+		assert_eq!(overlayed.storage(&[3]).unwrap(), Some(&[30][..]));
+		assert_eq!(overlayed.storage(&[4]).unwrap(), Some(&[4][..]));
+
+		overlayed.start_transaction();
+		overlayed.clear_prefix(&[]);
+		assert!(overlayed.storage(&[1]).unwrap().is_none());
+		assert!(overlayed.storage(&[4]).unwrap().is_none());
+
+		// the cleared values are restored.
+		overlayed.rollback_transaction().unwrap();
+		assert!(overlayed.storage(&[1]).unwrap().is_none());
+		assert_eq!(overlayed.storage(&[2]).unwrap(), Some(&[20][..]));
+		assert_eq!(overlayed.storage(&[4]).unwrap(), Some(&[4][..]));
+
+		// rolling back the outer transaction discards the committed inner one as well.
+		overlayed.rollback_transaction().unwrap();
+		assert_eq!(overlayed.transaction_depth(), 0);
+		assert_eq!(overlayed.storage(&[1]).unwrap(), Some(&[1][..]));
+		assert_eq!(overlayed.storage(&[2]).unwrap(), Some(&[2][..]));
+		assert!(overlayed.storage(&[3]).is_none());
+		assert!(overlayed.storage(&[4]).is_none());
+
+		assert_eq!(overlayed.rollback_transaction(), Err(NoOpenTransaction));
+		assert_eq!(overlayed.commit_transaction(), Err(NoOpenTransaction));
+	}
+
+	#[test]
+	fn overlayed_child_storage_transactions_work() {
+		let child_info = ChildInfo::new_default(b"Child1");
+		let child_info = &child_info;
+		let child = child_info.storage_key();
+		let other_info = ChildInfo::new_default(b"Child2");
+		let other_info = &other_info;
+		let mut overlayed = OverlayedChanges::default();
+
+		overlayed.set_child_storage(child_info, vec![1], Some(vec![1]));
+		overlayed.commit_prospective();
+		overlayed.set_child_storage(child_info, vec![2], Some(vec![2]));
+
+		overlayed.start_transaction();
+		overlayed.set_child_storage(child_info, vec![3], Some(vec![3]));
+		overlayed.set_child_storage(other_info, vec![1], Some(vec![1]));
+
+		overlayed.start_transaction();
+		overlayed.clear_child_storage(child_info);
+		assert!(overlayed.child_storage(child_info, &[1]).unwrap().is_none());
+		assert!(overlayed.child_storage(child_info, &[2]).unwrap().is_none());
+		assert!(overlayed.child_storage(child_info, &[3]).unwrap().is_none());
+		overlayed.commit_transaction().unwrap();
+
+		overlayed.rollback_transaction().unwrap();
+		assert_eq!(overlayed.child_storage(child_info, &[1]).unwrap(), Some(&[1][..]));
+		assert_eq!(overlayed.child_storage(child_info, &[2]).unwrap(), Some(&[2][..]));
+		assert!(overlayed.child_storage(child_info, &[3]).is_none());
+		assert!(overlayed.child_storage(other_info, &[1]).is_none());
+		assert!(overlayed.prospective.children_default.get(other_info.storage_key()).is_none());
+		assert_eq!(
+			overlayed.prospective.children_default.get(child).map(|(map, _)| map.len()),
+			Some(1),
+		);
+	}
+
 	#[test]
 	fn overlayed_storage_root_works() {
 		let initial: BTreeMap<_, _> = vec![
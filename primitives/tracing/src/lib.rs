@@ -25,6 +25,9 @@
 #[doc(hidden)]
 pub use tracing;
 
+#[cfg(feature = "std")]
+pub mod proxy;
+
 /// Runs given code within a tracing span, measuring it's execution time.
 ///
 /// If tracing is not enabled, the code is still executed.
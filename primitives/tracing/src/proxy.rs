@@ -0,0 +1,115 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Proxy of the spans entered by a wasm runtime.
+//!
+//! The wasm runtime cannot use `tracing` itself, so it asks the host to enter and exit spans on its
+//! behalf. As the metadata of a `tracing` span requires static strings, all proxied spans share the
+//! target [`WASM_TRACE_IDENTIFIER`] and carry the actual target and name of the span in the fields
+//! [`WASM_TARGET_KEY`] and [`WASM_NAME_KEY`]. Subscribers are expected to substitute them.
+
+use std::cell::RefCell;
+use tracing::{span, Level, Span, dispatcher};
+
+/// The target of all spans entered on behalf of a wasm runtime.
+pub const WASM_TRACE_IDENTIFIER: &str = "wasm_tracing";
+
+/// The name of the field holding the name of a span entered on behalf of a wasm runtime.
+pub const WASM_NAME_KEY: &str = "proxied_wasm_name";
+
+/// The name of the field holding the target of a span entered on behalf of a wasm runtime.
+pub const WASM_TARGET_KEY: &str = "proxied_wasm_target";
+
+/// The maximum number of nested spans a wasm runtime can enter.
+///
+/// Spans beyond are not recorded, so that a runtime cannot exhaust the memory of the host.
+const MAX_SPANS_LEN: usize = 1000;
+
+thread_local! {
+	static PROXY: RefCell<TracingProxy> = RefCell::new(TracingProxy::default());
+}
+
+/// Create and enter a span with the given `target` and `name` on behalf of the wasm runtime.
+///
+/// Returns the id of the span, which is `0` if the span is disabled.
+pub fn create_registered_span(target: &str, name: &str) -> u64 {
+	PROXY.with(|proxy| proxy.borrow_mut().create_span(target, name))
+}
+
+/// Exit and close the span with the given `id`, and all spans entered after it that were not
+/// exited yet.
+pub fn exit_span(id: u64) {
+	PROXY.with(|proxy| proxy.borrow_mut().exit_span(id))
+}
+
+/// The spans entered on behalf of the wasm runtime on the current thread, innermost last.
+#[derive(Default)]
+struct TracingProxy {
+	spans: Vec<(u64, Span)>,
+}
+
+impl TracingProxy {
+	fn create_span(&mut self, target: &str, name: &str) -> u64 {
+		if self.spans.len() >= MAX_SPANS_LEN {
+			log::warn!(
+				target: "tracing",
+				"Wasm runtime entered more than {} nested spans, ignoring span {}::{}",
+				MAX_SPANS_LEN,
+				target,
+				name,
+			);
+			return 0
+		}
+
+		let span = span!(
+			target: WASM_TRACE_IDENTIFIER,
+			Level::INFO,
+			"wasm_span",
+			proxied_wasm_target = target,
+			proxied_wasm_name = name,
+		);
+		let id = match span.id() {
+			Some(id) => id,
+			None => return 0,
+		};
+		dispatcher::get_default(|dispatch| dispatch.enter(&id));
+		self.spans.push((id.into_u64(), span));
+		id.into_u64()
+	}
+
+	fn exit_span(&mut self, id: u64) {
+		if id == 0 {
+			return
+		}
+
+		let pos = match self.spans.iter().rposition(|(span_id, _)| *span_id == id) {
+			Some(pos) => pos,
+			None => {
+				log::warn!(target: "tracing", "Wasm runtime tried to exit unknown span {}", id);
+				return
+			}
+		};
+
+		// Spans entered after the exited one were not exited by the runtime, e.g. because it
+		// trapped. They are exited and closed as well, innermost first.
+		for (_, span) in self.spans.drain(pos..).rev() {
+			if let Some(id) = span.id() {
+				dispatcher::get_default(|dispatch| dispatch.exit(&id));
+			}
+		}
+	}
+}
+
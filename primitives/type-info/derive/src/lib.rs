@@ -0,0 +1,305 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Derive of `sp_type_info::TypeInfo`.
+//!
+//! The description follows the encoding derived by `parity-scale-codec`: fields marked
+//! `#[codec(skip)]` are omitted, fields marked `#[codec(compact)]` or `#[codec(encoded_as = ..)]`
+//! are described by their encoded type, and the index of a variant is taken from
+//! `#[codec(index = ..)]`, then from its discriminant, then from its position.
+//!
+//! The derive is usable from crates depending on `sp-type-info` or on `frame-support`, which
+//! re-exports it as `frame_support::type_info`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use proc_macro_crate::crate_name;
+use quote::{quote, ToTokens};
+use syn::{
+	parse_quote, spanned::Spanned, visit::{self, Visit}, Attribute, Data, DeriveInput, Error,
+	Fields, Ident, Lit, Meta, NestedMeta, Result, Type,
+};
+
+#[proc_macro_derive(TypeInfo, attributes(codec))]
+pub fn type_info_derive(input: TokenStream) -> TokenStream {
+	derive(syn::parse_macro_input!(input))
+		.unwrap_or_else(|e| e.to_compile_error())
+		.into()
+}
+
+fn derive(input: DeriveInput) -> Result<TokenStream2> {
+	let scrate = crate_access()?;
+	let ident = &input.ident;
+	let name = ident.to_string();
+	let docs = docs(&input.attrs);
+
+	let type_def = match &input.data {
+		Data::Struct(data) => {
+			let fields = fields(&scrate, &data.fields)?;
+			quote!( #scrate::TypeDef::Composite(#fields) )
+		},
+		Data::Enum(data) => {
+			let mut variants = Vec::new();
+			for (i, variant) in data.variants.iter().enumerate() {
+				if codec_attrs(&variant.attrs)?.skip {
+					continue
+				}
+				let name = variant.ident.to_string();
+				let index = match (codec_attrs(&variant.attrs)?.index, &variant.discriminant) {
+					(Some(index), _) => quote!( #index ),
+					(None, Some((_, discriminant))) => quote!( (#discriminant) as u8 ),
+					(None, None) => Literal::u8_unsuffixed(i as u8).into_token_stream(),
+				};
+				let fields = fields(&scrate, &variant.fields)?;
+				let docs = docs(&variant.attrs);
+				variants.push(quote!(
+					#scrate::Variant::new(#name, #index, #fields, &[ #( #docs ),* ])
+				));
+			}
+			quote!( #scrate::TypeDef::Variant(#scrate::sp_std::vec![ #( #variants ),* ]) )
+		},
+		Data::Union(_) => return Err(Error::new(input.span(), "unions are not supported")),
+	};
+
+	let (type_params, where_predicates) = bounds(&scrate, &input)?;
+	let mut generics = input.generics.clone();
+	generics.make_where_clause().predicates.extend(where_predicates);
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	Ok(quote!(
+		const _: () = {
+			impl #impl_generics #scrate::TypeInfo for #ident #ty_generics #where_clause {
+				type Identity = Self;
+
+				fn type_info() -> #scrate::Type {
+					#scrate::Type::new(#scrate::Path::new(#name, module_path!()), #type_def)
+						.type_params(#scrate::sp_std::vec![
+							#( #scrate::meta_type::<#type_params>() ),*
+						])
+						.docs(&[ #( #docs ),* ])
+				}
+			}
+		};
+	))
+}
+
+/// Returns the path to the `sp-type-info` crate, possibly through `frame-support`.
+fn crate_access() -> Result<TokenStream2> {
+	if std::env::var("CARGO_PKG_NAME").map_or(false, |name| name == "sp-type-info") {
+		return Ok(quote!( ::sp_type_info ))
+	}
+
+	if let Ok(name) = crate_name("sp-type-info") {
+		let name = Ident::new(&name, Span::call_site());
+		Ok(quote!( ::#name ))
+	} else if let Ok(name) = crate_name("frame-support") {
+		let name = Ident::new(&name, Span::call_site());
+		Ok(quote!( ::#name::type_info ))
+	} else {
+		Err(Error::new(
+			Span::call_site(),
+			"`TypeInfo` requires `sp-type-info` or `frame-support` as a dependency",
+		))
+	}
+}
+
+/// The `#[codec(..)]` attributes relevant to the description.
+#[derive(Default)]
+struct CodecAttrs {
+	skip: bool,
+	compact: bool,
+	encoded_as: Option<Type>,
+	index: Option<u8>,
+}
+
+fn codec_attrs(attrs: &[Attribute]) -> Result<CodecAttrs> {
+	let mut res = CodecAttrs::default();
+	for attr in attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+		let list = match attr.parse_meta()? {
+			Meta::List(list) => list,
+			_ => continue,
+		};
+		for nested in list.nested {
+			match nested {
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => res.skip = true,
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("compact") =>
+					res.compact = true,
+				NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("encoded_as") => {
+					match nv.lit {
+						Lit::Str(s) => res.encoded_as = Some(s.parse()?),
+						lit => return Err(Error::new(lit.span(), "expected a string literal")),
+					}
+				},
+				NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("index") => {
+					res.index = Some(match nv.lit {
+						Lit::Str(s) => s.parse::<syn::LitInt>()?.base10_parse()?,
+						Lit::Int(i) => i.base10_parse()?,
+						lit => return Err(Error::new(lit.span(), "expected an integer")),
+					});
+				},
+				_ => (),
+			}
+		}
+	}
+	Ok(res)
+}
+
+fn docs(attrs: &[Attribute]) -> Vec<syn::LitStr> {
+	attrs.iter()
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::NameValue(nv)) if nv.path.is_ident("doc") => match nv.lit {
+				Lit::Str(s) => Some(s),
+				_ => None,
+			},
+			_ => None,
+		})
+		.collect()
+}
+
+/// Returns the type describing the encoding of the field, `None` if the field is not encoded.
+fn encoded_type(scrate: &TokenStream2, field: &syn::Field) -> Result<Option<Type>> {
+	let attrs = codec_attrs(&field.attrs)?;
+	let ty = &field.ty;
+	Ok(if attrs.skip {
+		None
+	} else if attrs.compact {
+		Some(parse_quote!( #scrate::codec::Compact<#ty> ))
+	} else if let Some(encoded_as) = attrs.encoded_as {
+		Some(encoded_as)
+	} else {
+		Some(ty.clone())
+	})
+}
+
+/// The type as written in the source, with the whitespace of the token stream removed.
+fn type_name(ty: &Type) -> String {
+	let mut name = ty.to_token_stream().to_string();
+	for (from, to) in &[
+		(" <", "<"), ("< ", "<"), (" >", ">"), (" ::", "::"), (":: ", "::"), (" ,", ","),
+		(" ;", ";"), ("& ", "&"), ("( ", "("), (" )", ")"), ("[ ", "["), (" ]", "]"),
+	] {
+		name = name.replace(from, to);
+	}
+	name
+}
+
+fn fields(scrate: &TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+	let mut res = Vec::new();
+	for field in fields.iter() {
+		let ty = match encoded_type(scrate, field)? {
+			Some(ty) => ty,
+			None => continue,
+		};
+		let type_name = type_name(&field.ty);
+		let docs = docs(&field.attrs);
+		res.push(match &field.ident {
+			Some(ident) => {
+				let name = ident.to_string();
+				quote!( #scrate::Field::named::<#ty>(#name, #type_name, &[ #( #docs ),* ]) )
+			},
+			None => quote!( #scrate::Field::unnamed::<#ty>(#type_name, &[ #( #docs ),* ]) ),
+		});
+	}
+	Ok(quote!( #scrate::sp_std::vec![ #( #res ),* ] ))
+}
+
+/// Returns the type parameters described by the type and the bounds of the implementation.
+///
+/// Each encoded field type which mentions a type parameter is bounded by `TypeInfo`. A type
+/// parameter is itself bounded by `TypeInfo`, and listed in the type parameters, only if it is
+/// used as a type in a field; type parameters used only through their associated types or in a
+/// `PhantomData`, like the `T: Trait` of a pallet, are only bounded by `'static`.
+fn bounds(
+	scrate: &TokenStream2,
+	input: &DeriveInput,
+) -> Result<(Vec<Ident>, Vec<syn::WherePredicate>)> {
+	let params: Vec<Ident> = input.generics.type_params().map(|p| p.ident.clone()).collect();
+
+	let mut field_types = Vec::new();
+	let fields: Vec<&syn::Field> = match &input.data {
+		Data::Struct(data) => data.fields.iter().collect(),
+		Data::Enum(data) => {
+			let mut fields = Vec::new();
+			for variant in &data.variants {
+				if !codec_attrs(&variant.attrs)?.skip {
+					fields.extend(variant.fields.iter());
+				}
+			}
+			fields
+		},
+		Data::Union(_) => Vec::new(),
+	};
+	for field in fields {
+		if let Some(ty) = encoded_type(scrate, field)? {
+			field_types.push(ty);
+		}
+	}
+
+	let mut visitor = ParamsVisitor { params: &params, mentioned: false, used: Vec::new() };
+	let mut predicates: Vec<syn::WherePredicate> = Vec::new();
+	for ty in &field_types {
+		visitor.mentioned = false;
+		visitor.visit_type(ty);
+		if visitor.mentioned {
+			predicates.push(parse_quote!( #ty: #scrate::TypeInfo + 'static ));
+		}
+	}
+
+	let used: Vec<Ident> = params.iter().filter(|p| visitor.used.contains(p)).cloned().collect();
+	for param in &params {
+		if used.contains(param) {
+			predicates.push(parse_quote!( #param: #scrate::TypeInfo + 'static ));
+		} else {
+			predicates.push(parse_quote!( #param: 'static ));
+		}
+	}
+
+	Ok((used, predicates))
+}
+
+/// Finds the type parameters mentioned by a type and those used as types.
+struct ParamsVisitor<'a> {
+	params: &'a [Ident],
+	mentioned: bool,
+	used: Vec<Ident>,
+}
+
+impl<'a, 'ast> Visit<'ast> for ParamsVisitor<'a> {
+	fn visit_type_path(&mut self, ty: &'ast syn::TypePath) {
+		if let Some(qself) = &ty.qself {
+			// `<T as Trait>::Type` only uses `T` through its associated type.
+			let mut inner = ParamsVisitor {
+				params: self.params,
+				mentioned: false,
+				used: Vec::new(),
+			};
+			inner.visit_type(&qself.ty);
+			self.mentioned |= inner.mentioned;
+			return
+		}
+		let first = &ty.path.segments[0].ident;
+		if let Some(param) = self.params.iter().find(|p| *p == first) {
+			self.mentioned = true;
+			if ty.path.segments.len() == 1 && !self.used.contains(param) {
+				self.used.push(param.clone());
+			}
+		}
+		if ty.path.segments.last().map_or(false, |s| s.ident == "PhantomData") {
+			return
+		}
+		visit::visit_type_path(self, ty);
+	}
+}
@@ -0,0 +1,47 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The forms a type description can take.
+
+use sp_std::fmt::Debug;
+use crate::{MetaType, StringBuf};
+
+/// The form of a type description: how it refers to other types and how it stores strings.
+pub trait Form {
+	/// The representation of a reference to another type.
+	type Type: PartialEq + Eq + PartialOrd + Ord + Clone + Debug;
+	/// The representation of a string.
+	type String: PartialEq + Eq + PartialOrd + Ord + Clone + Debug;
+}
+
+/// The form returned by `TypeInfo::type_info`: types are referred to by their `MetaType` and
+/// strings are static.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub enum MetaForm {}
+
+impl Form for MetaForm {
+	type Type = MetaType;
+	type String = &'static str;
+}
+
+/// The form stored in a `PortableRegistry`: types are referred to by their id in the registry.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub enum PortableForm {}
+
+impl Form for PortableForm {
+	type Type = u32;
+	type String = StringBuf;
+}
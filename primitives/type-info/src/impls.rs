@@ -0,0 +1,244 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementations of `TypeInfo` for primitive, standard library and `primitive-types` types.
+
+use codec::Compact;
+use primitive_types::{H160, H256, H512, U256};
+use sp_std::{
+	borrow::{Cow, ToOwned},
+	boxed::Box,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet, vec_deque::VecDeque},
+	marker::PhantomData,
+	prelude::*,
+};
+use crate::{Field, Path, Type, TypeDef, TypeDefPrimitive, TypeInfo, Variant, meta_type};
+
+macro_rules! impl_primitive {
+	( $( $ty:ty => $primitive:ident ),* $(,)? ) => {
+		$(
+			impl TypeInfo for $ty {
+				type Identity = Self;
+
+				fn type_info() -> Type {
+					TypeDefPrimitive::$primitive.into()
+				}
+			}
+		)*
+	}
+}
+
+impl_primitive! {
+	bool => Bool,
+	char => Char,
+	str => Str,
+	u8 => U8,
+	u16 => U16,
+	u32 => U32,
+	u64 => U64,
+	u128 => U128,
+	i8 => I8,
+	i16 => I16,
+	i32 => I32,
+	i64 => I64,
+	i128 => I128,
+	U256 => U256,
+}
+
+#[cfg(feature = "std")]
+impl TypeInfo for String {
+	type Identity = str;
+
+	fn type_info() -> Type {
+		str::type_info()
+	}
+}
+
+macro_rules! impl_array {
+	( $( $n:expr )* ) => {
+		$(
+			impl<T: TypeInfo + 'static> TypeInfo for [T; $n] {
+				type Identity = Self;
+
+				fn type_info() -> Type {
+					TypeDef::Array($n, meta_type::<T>()).into()
+				}
+			}
+		)*
+	}
+}
+
+impl_array!(
+	0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+	33 40 48 56 64 65 72 96 128 160 192 224 256 384 512 768 1024 2048 4096 8192 16384 32768
+);
+
+impl<T: TypeInfo + 'static> TypeInfo for [T] {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		TypeDef::Sequence(meta_type::<T>()).into()
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Vec<T> {
+	type Identity = [T];
+
+	fn type_info() -> Type {
+		<[T]>::type_info()
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for VecDeque<T> {
+	type Identity = [T];
+
+	fn type_info() -> Type {
+		<[T]>::type_info()
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for BTreeSet<T> {
+	type Identity = [T];
+
+	fn type_info() -> Type {
+		<[T]>::type_info()
+	}
+}
+
+impl<K: TypeInfo + 'static, V: TypeInfo + 'static> TypeInfo for BTreeMap<K, V> {
+	type Identity = [(K, V)];
+
+	fn type_info() -> Type {
+		<[(K, V)]>::type_info()
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Option<T> {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		Type::new(
+			Path::prelude("Option"),
+			TypeDef::Variant(vec![
+				Variant::new("None", 0, vec![], &[]),
+				Variant::new("Some", 1, vec![Field::unnamed::<T>("T", &[])], &[]),
+			]),
+		).type_params(vec![meta_type::<T>()])
+	}
+}
+
+impl<T: TypeInfo + 'static, E: TypeInfo + 'static> TypeInfo for Result<T, E> {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		Type::new(
+			Path::prelude("Result"),
+			TypeDef::Variant(vec![
+				Variant::new("Ok", 0, vec![Field::unnamed::<T>("T", &[])], &[]),
+				Variant::new("Err", 1, vec![Field::unnamed::<E>("E", &[])], &[]),
+			]),
+		).type_params(vec![meta_type::<T>(), meta_type::<E>()])
+	}
+}
+
+impl<T: TypeInfo + ?Sized + 'static> TypeInfo for Box<T> {
+	type Identity = T::Identity;
+
+	fn type_info() -> Type {
+		T::type_info()
+	}
+}
+
+impl<T: TypeInfo + ?Sized + 'static> TypeInfo for &'static T {
+	type Identity = T::Identity;
+
+	fn type_info() -> Type {
+		T::type_info()
+	}
+}
+
+impl<T: ToOwned + TypeInfo + ?Sized + 'static> TypeInfo for Cow<'static, T> {
+	type Identity = T::Identity;
+
+	fn type_info() -> Type {
+		T::type_info()
+	}
+}
+
+impl<T: ?Sized + 'static> TypeInfo for PhantomData<T> {
+	type Identity = ();
+
+	fn type_info() -> Type {
+		<()>::type_info()
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Compact<T> {
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		TypeDef::Compact(meta_type::<T>()).into()
+	}
+}
+
+macro_rules! impl_tuple {
+	() => {
+		impl TypeInfo for () {
+			type Identity = Self;
+
+			fn type_info() -> Type {
+				TypeDef::Tuple(vec![]).into()
+			}
+		}
+	};
+	( $first:ident $(, $rest:ident )* ) => {
+		impl<$first: TypeInfo + 'static $(, $rest: TypeInfo + 'static )*> TypeInfo
+			for ($first, $( $rest, )*)
+		{
+			type Identity = Self;
+
+			fn type_info() -> Type {
+				TypeDef::Tuple(vec![meta_type::<$first>() $(, meta_type::<$rest>() )*]).into()
+			}
+		}
+
+		impl_tuple!($( $rest ),*);
+	};
+}
+
+impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R);
+
+macro_rules! impl_fixed_hash {
+	( $( $ty:ident => $len:expr ),* ) => {
+		$(
+			impl TypeInfo for $ty {
+				type Identity = Self;
+
+				fn type_info() -> Type {
+					Type::new(
+						Path::new(stringify!($ty), "primitive_types"),
+						TypeDef::Composite(vec![Field::unnamed::<[u8; $len]>(
+							concat!("[u8; ", stringify!($len), "]"),
+							&[],
+						)]),
+					)
+				}
+			}
+		)*
+	}
+}
+
+impl_fixed_hash!(H160 => 20, H256 => 32, H512 => 64);
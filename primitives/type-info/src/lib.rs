@@ -0,0 +1,231 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Portable descriptions of runtime types.
+//!
+//! A type implementing [`TypeInfo`] describes the shape of its SCALE encoding: whether it is a
+//! composite of fields, a variant, a sequence, an array, a tuple, a compact or a primitive. The
+//! description refers to the types it is made of through [`MetaType`]s, which are resolved by a
+//! [`Registry`] into integer ids. The resulting [`PortableRegistry`] is encodable and is what the
+//! runtime metadata carries, so that clients can decode any type of the runtime without any
+//! out-of-band type definition.
+//!
+//! `TypeInfo` is implemented for the primitive and standard library types and can be derived for
+//! structs and enums:
+//!
+//! ```
+//! use sp_type_info::{TypeInfo, Registry, PortableRegistry, meta_type};
+//!
+//! #[derive(TypeInfo)]
+//! struct Foo<T> {
+//! 	bar: T,
+//! 	#[codec(compact)]
+//! 	baz: u64,
+//! }
+//!
+//! let mut registry = Registry::new();
+//! let id = registry.register_type(&meta_type::<Foo<bool>>());
+//! let registry = PortableRegistry::from(registry);
+//! assert_eq!(registry.resolve(id).unwrap().path.segments.last().unwrap(), "Foo");
+//! ```
+//!
+//! The derive honours the `#[codec(skip)]`, `#[codec(compact)]` and `#[codec(index = ..)]`
+//! attributes, so that the description always matches the encoding derived by
+//! `parity-scale-codec`. Types with a custom `Encode` implementation must implement `TypeInfo`
+//! by hand.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+// Make the derive usable in this crate's tests.
+extern crate self as sp_type_info;
+
+mod form;
+mod impls;
+mod meta_type;
+mod registry;
+mod ty;
+
+pub use form::{Form, MetaForm, PortableForm};
+pub use meta_type::MetaType;
+pub use registry::{IntoPortable, PortableRegistry, Registry};
+pub use ty::{Field, Path, Type, TypeDef, TypeDefPrimitive, Variant};
+
+pub use sp_type_info_derive::TypeInfo;
+
+#[doc(hidden)]
+pub use codec;
+#[doc(hidden)]
+pub use sp_std;
+
+/// A string of the portable form: owned in `std` so that it can be decoded, static otherwise.
+#[cfg(feature = "std")]
+pub type StringBuf = String;
+
+/// A string of the portable form: owned in `std` so that it can be decoded, static otherwise.
+#[cfg(not(feature = "std"))]
+pub type StringBuf = &'static str;
+
+/// A type which can describe the shape of its encoding.
+pub trait TypeInfo {
+	/// The type which identifies `Self` in a [`Registry`].
+	///
+	/// This is `Self` for most types. Types which are encoded exactly like another type, such as
+	/// `Box<T>` or `Vec<T>`, use the identity of that type so they share a registry entry.
+	type Identity: ?Sized + 'static;
+
+	/// Returns the description of the type.
+	fn type_info() -> Type;
+}
+
+/// Returns the [`MetaType`] of `T`.
+pub fn meta_type<T: TypeInfo + ?Sized + 'static>() -> MetaType {
+	MetaType::new::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{Compact, Encode};
+
+	#[derive(TypeInfo, Encode)]
+	struct Unit;
+
+	#[derive(TypeInfo, Encode)]
+	struct Tuple(u8, #[codec(skip)] u16, #[codec(compact)] u32);
+
+	/// A struct.
+	#[derive(TypeInfo, Encode)]
+	struct Named<T> {
+		/// A generic field.
+		a: T,
+		b: Vec<T>,
+	}
+
+	#[derive(TypeInfo, Encode)]
+	enum Enum<T> {
+		A,
+		#[codec(index = "5")]
+		B(T),
+		C { c: bool },
+	}
+
+	#[derive(TypeInfo, Encode)]
+	enum CLike {
+		A = 3,
+		// Like `parity-scale-codec`, the index falls back to the position of the variant.
+		B,
+	}
+
+	#[derive(TypeInfo, Encode)]
+	struct Recursive {
+		children: Vec<Recursive>,
+	}
+
+	fn resolve<T: TypeInfo + ?Sized + 'static>() -> (u32, PortableRegistry) {
+		let mut registry = Registry::new();
+		let id = registry.register_type(&meta_type::<T>());
+		(id, registry.into())
+	}
+
+	#[test]
+	fn derive_struct_works() {
+		assert_eq!(Unit::type_info().type_def, TypeDef::Composite(vec![]));
+		assert_eq!(Unit::type_info().path, Path::new("Unit", module_path!()));
+
+		assert_eq!(
+			Tuple::type_info().type_def,
+			TypeDef::Composite(vec![
+				Field::unnamed::<u8>("u8", &[]),
+				Field::unnamed::<Compact<u32>>("u32", &[]),
+			]),
+		);
+
+		let info = Named::<bool>::type_info();
+		assert_eq!(info.docs, vec![" A struct."]);
+		assert_eq!(info.type_params, vec![meta_type::<bool>()]);
+		assert_eq!(
+			info.type_def,
+			TypeDef::Composite(vec![
+				Field::named::<bool>("a", "T", &[" A generic field."]),
+				Field::named::<Vec<bool>>("b", "Vec<T>", &[]),
+			]),
+		);
+	}
+
+	#[test]
+	fn derive_enum_works() {
+		assert_eq!(
+			Enum::<u8>::type_info().type_def,
+			TypeDef::Variant(vec![
+				Variant::new("A", 0, vec![], &[]),
+				Variant::new("B", 5, vec![Field::unnamed::<u8>("T", &[])], &[]),
+				Variant::new("C", 2, vec![Field::named::<bool>("c", "bool", &[])], &[]),
+			]),
+		);
+		assert_eq!(Enum::B(7u8).encode()[0], 5);
+
+		assert_eq!(
+			CLike::type_info().type_def,
+			TypeDef::Variant(vec![
+				Variant::new("A", 3, vec![], &[]),
+				Variant::new("B", 1, vec![], &[]),
+			]),
+		);
+		assert_eq!(CLike::B.encode(), vec![1]);
+	}
+
+	#[test]
+	fn registry_deduplicates_types() {
+		let mut registry = Registry::new();
+		let a = registry.register_type(&meta_type::<Vec<u32>>());
+		let b = registry.register_type(&meta_type::<[u32]>());
+		let c = registry.register_type(&meta_type::<Box<Vec<u32>>>());
+		assert_eq!(a, b);
+		assert_eq!(a, c);
+
+		let registry = PortableRegistry::from(registry);
+		// The sequence and its element.
+		assert_eq!(registry.types.len(), 2);
+		assert_eq!(
+			registry.resolve(a).unwrap().type_def,
+			TypeDef::Sequence(registry.types.iter().position(|t|
+				t.type_def == TypeDef::Primitive(TypeDefPrimitive::U32)
+			).unwrap() as u32),
+		);
+	}
+
+	#[test]
+	fn registry_handles_recursive_types() {
+		let (id, registry) = resolve::<Recursive>();
+		let fields = match &registry.resolve(id).unwrap().type_def {
+			TypeDef::Composite(fields) => fields.clone(),
+			_ => panic!("`Recursive` is a composite"),
+		};
+		let children = registry.resolve(fields[0].ty).unwrap();
+		assert_eq!(children.type_def, TypeDef::Sequence(id));
+	}
+
+	#[test]
+	fn portable_registry_encodes_and_decodes() {
+		use codec::Decode;
+
+		let (_, registry) = resolve::<(Named<Enum<u64>>, Option<CLike>, Result<[u8; 32], ()>)>();
+		let encoded = registry.encode();
+		assert_eq!(PortableRegistry::decode(&mut &encoded[..]).unwrap(), registry);
+		assert!(serde_json::to_string(&registry).is_ok());
+	}
+}
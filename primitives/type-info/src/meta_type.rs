@@ -0,0 +1,74 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The type-erased handle to a type's description.
+
+use sp_std::{any::TypeId, cmp::Ordering, fmt};
+use crate::{Type, TypeInfo};
+
+/// A handle to the description of a type, identified by the `TypeId` of its `Identity`.
+#[derive(Clone, Copy)]
+pub struct MetaType {
+	fn_type_info: fn() -> Type,
+	type_id: TypeId,
+}
+
+impl MetaType {
+	/// Creates the handle of `T`.
+	pub fn new<T: TypeInfo + ?Sized + 'static>() -> Self {
+		Self {
+			fn_type_info: <T as TypeInfo>::type_info,
+			type_id: TypeId::of::<T::Identity>(),
+		}
+	}
+
+	/// Returns the description of the type.
+	pub fn type_info(&self) -> Type {
+		(self.fn_type_info)()
+	}
+
+	/// Returns the `TypeId` identifying the type.
+	pub fn type_id(&self) -> TypeId {
+		self.type_id
+	}
+}
+
+impl PartialEq for MetaType {
+	fn eq(&self, other: &Self) -> bool {
+		self.type_id == other.type_id
+	}
+}
+
+impl Eq for MetaType {}
+
+impl PartialOrd for MetaType {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for MetaType {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.type_id.cmp(&other.type_id)
+	}
+}
+
+impl fmt::Debug for MetaType {
+	// The description is not printed: it may be recursive.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.type_id.fmt(f)
+	}
+}
@@ -0,0 +1,104 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The registry resolving type descriptions into a portable form.
+
+use codec::Encode;
+#[cfg(feature = "std")]
+use codec::Decode;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+use sp_std::{any::TypeId, collections::btree_map::BTreeMap, prelude::*};
+use crate::{MetaType, PortableForm, StringBuf, Type};
+
+/// Conversion of a description into its portable form, registering the types it refers to.
+pub trait IntoPortable {
+	/// The portable form of `Self`.
+	type Output;
+
+	/// Converts `self` into its portable form.
+	fn into_portable(self, registry: &mut Registry) -> Self::Output;
+}
+
+/// The registry of the types referred to by some metadata.
+///
+/// Each type is registered once, at the position of the id returned when first registering it.
+#[derive(Default, Debug)]
+pub struct Registry {
+	ids: BTreeMap<TypeId, u32>,
+	/// The portable types, `None` while a type is being converted.
+	types: Vec<Option<Type<PortableForm>>>,
+}
+
+impl Registry {
+	/// Creates an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the type and the types it refers to, and returns its id.
+	pub fn register_type(&mut self, ty: &MetaType) -> u32 {
+		if let Some(id) = self.ids.get(&ty.type_id()) {
+			return *id
+		}
+
+		// The id is reserved before converting the type, so that recursive types refer to it.
+		let id = self.types.len() as u32;
+		self.ids.insert(ty.type_id(), id);
+		self.types.push(None);
+
+		let portable = ty.type_info().into_portable(self);
+		self.types[id as usize] = Some(portable);
+		id
+	}
+
+	/// Registers the types and returns their ids.
+	pub fn register_types(&mut self, types: &[MetaType]) -> Vec<u32> {
+		types.iter().map(|ty| self.register_type(ty)).collect()
+	}
+
+	/// Converts the strings into their portable form.
+	pub fn map_strings(&self, strings: &[&'static str]) -> Vec<StringBuf> {
+		strings.iter().map(|s| (*s).into()).collect()
+	}
+}
+
+/// A registry of types in their portable form, as stored in the metadata.
+///
+/// A type's id is its position in `types`.
+#[derive(PartialEq, Eq, Clone, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+pub struct PortableRegistry {
+	/// The registered types.
+	pub types: Vec<Type<PortableForm>>,
+}
+
+impl PortableRegistry {
+	/// Returns the type of the given id.
+	pub fn resolve(&self, id: u32) -> Option<&Type<PortableForm>> {
+		self.types.get(id as usize)
+	}
+}
+
+impl From<Registry> for PortableRegistry {
+	fn from(registry: Registry) -> Self {
+		Self {
+			types: registry.types.into_iter()
+				.map(|ty| ty.expect("every type is converted before `register_type` returns; qed"))
+				.collect(),
+		}
+	}
+}
@@ -0,0 +1,299 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The description of a type.
+
+use codec::Encode;
+#[cfg(feature = "std")]
+use codec::Decode;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+use sp_std::{iter, prelude::*};
+use crate::{Form, MetaForm, PortableForm, IntoPortable, Registry, TypeInfo, meta_type};
+
+/// The description of a type.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound(
+	serialize = "F::Type: Serialize, F::String: Serialize",
+	deserialize = "F::Type: Deserialize<'de>, F::String: Deserialize<'de>",
+)))]
+pub struct Type<F: Form = MetaForm> {
+	/// The path of the type, empty for primitive and anonymous types.
+	pub path: Path<F>,
+	/// The types the generic parameters of the type are instantiated with.
+	pub type_params: Vec<F::Type>,
+	/// The definition of the type.
+	pub type_def: TypeDef<F>,
+	/// The documentation of the type.
+	pub docs: Vec<F::String>,
+}
+
+impl Type {
+	/// Creates a description without generic parameters nor documentation.
+	pub fn new(path: Path, type_def: TypeDef) -> Self {
+		Self { path, type_params: Vec::new(), type_def, docs: Vec::new() }
+	}
+
+	/// Sets the types the generic parameters are instantiated with.
+	pub fn type_params(mut self, type_params: Vec<crate::MetaType>) -> Self {
+		self.type_params = type_params;
+		self
+	}
+
+	/// Sets the documentation.
+	pub fn docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
+}
+
+impl From<TypeDef> for Type {
+	fn from(type_def: TypeDef) -> Self {
+		Self::new(Path::default(), type_def)
+	}
+}
+
+impl From<TypeDefPrimitive> for Type {
+	fn from(primitive: TypeDefPrimitive) -> Self {
+		TypeDef::Primitive(primitive).into()
+	}
+}
+
+impl IntoPortable for Type {
+	type Output = Type<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		Type {
+			path: self.path.into_portable(registry),
+			type_params: registry.register_types(&self.type_params),
+			type_def: self.type_def.into_portable(registry),
+			docs: registry.map_strings(&self.docs),
+		}
+	}
+}
+
+/// The path of a type: the segments of the module path it is defined in, followed by its name.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound(
+	serialize = "F::String: Serialize",
+	deserialize = "F::String: Deserialize<'de>",
+)))]
+pub struct Path<F: Form = MetaForm> {
+	/// The segments of the path.
+	pub segments: Vec<F::String>,
+}
+
+impl Default for Path {
+	fn default() -> Self {
+		Self { segments: Vec::new() }
+	}
+}
+
+impl Path {
+	/// Creates the path of the type `ident` defined in `module_path`, as returned by
+	/// `module_path!()`.
+	pub fn new(ident: &'static str, module_path: &'static str) -> Self {
+		Self {
+			segments: module_path.split("::").chain(iter::once(ident)).collect(),
+		}
+	}
+
+	/// Creates the path of a prelude type, such as `Option`.
+	pub fn prelude(ident: &'static str) -> Self {
+		Self { segments: vec![ident] }
+	}
+}
+
+impl IntoPortable for Path {
+	type Output = Path<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		Path { segments: registry.map_strings(&self.segments) }
+	}
+}
+
+/// The definition of a type, which determines its encoding.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound(
+	serialize = "F::Type: Serialize, F::String: Serialize",
+	deserialize = "F::Type: Deserialize<'de>, F::String: Deserialize<'de>",
+)))]
+pub enum TypeDef<F: Form = MetaForm> {
+	/// A struct or a tuple struct, encoded as the concatenation of its fields.
+	Composite(Vec<Field<F>>),
+	/// An enum, encoded as the index of the variant followed by its fields.
+	Variant(Vec<Variant<F>>),
+	/// A sequence of values of the given type, prefixed by its compact encoded length.
+	Sequence(F::Type),
+	/// An array of the given number of values of the given type.
+	Array(u32, F::Type),
+	/// A tuple of values of the given types.
+	Tuple(Vec<F::Type>),
+	/// A primitive type.
+	Primitive(TypeDefPrimitive),
+	/// The compact encoding of the given type.
+	Compact(F::Type),
+}
+
+impl IntoPortable for TypeDef {
+	type Output = TypeDef<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		match self {
+			TypeDef::Composite(fields) => TypeDef::Composite(
+				fields.into_iter().map(|f| f.into_portable(registry)).collect(),
+			),
+			TypeDef::Variant(variants) => TypeDef::Variant(
+				variants.into_iter().map(|v| v.into_portable(registry)).collect(),
+			),
+			TypeDef::Sequence(ty) => TypeDef::Sequence(registry.register_type(&ty)),
+			TypeDef::Array(len, ty) => TypeDef::Array(len, registry.register_type(&ty)),
+			TypeDef::Tuple(types) => TypeDef::Tuple(registry.register_types(&types)),
+			TypeDef::Primitive(primitive) => TypeDef::Primitive(primitive),
+			TypeDef::Compact(ty) => TypeDef::Compact(registry.register_type(&ty)),
+		}
+	}
+}
+
+/// A primitive type.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+pub enum TypeDefPrimitive {
+	/// `bool`, encoded as one byte.
+	Bool,
+	/// `char`, encoded as its `u32` code point.
+	Char,
+	/// A UTF-8 string, encoded as a sequence of bytes.
+	Str,
+	/// `u8`
+	U8,
+	/// `u16`
+	U16,
+	/// `u32`
+	U32,
+	/// `u64`
+	U64,
+	/// `u128`
+	U128,
+	/// A 256-bit unsigned integer.
+	U256,
+	/// `i8`
+	I8,
+	/// `i16`
+	I16,
+	/// `i32`
+	I32,
+	/// `i64`
+	I64,
+	/// `i128`
+	I128,
+}
+
+/// A field of a composite type or of a variant.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound(
+	serialize = "F::Type: Serialize, F::String: Serialize",
+	deserialize = "F::Type: Deserialize<'de>, F::String: Deserialize<'de>",
+)))]
+pub struct Field<F: Form = MetaForm> {
+	/// The name of the field, `None` for the fields of tuple structs and tuple variants.
+	pub name: Option<F::String>,
+	/// The type of the field.
+	pub ty: F::Type,
+	/// The type of the field as written in the source, e.g. `T::Balance`.
+	pub type_name: F::String,
+	/// The documentation of the field.
+	pub docs: Vec<F::String>,
+}
+
+impl Field {
+	/// Creates a named field of type `T`.
+	pub fn named<T: TypeInfo + ?Sized + 'static>(
+		name: &'static str,
+		type_name: &'static str,
+		docs: &[&'static str],
+	) -> Self {
+		Self { name: Some(name), ty: meta_type::<T>(), type_name, docs: docs.to_vec() }
+	}
+
+	/// Creates an unnamed field of type `T`.
+	pub fn unnamed<T: TypeInfo + ?Sized + 'static>(
+		type_name: &'static str,
+		docs: &[&'static str],
+	) -> Self {
+		Self { name: None, ty: meta_type::<T>(), type_name, docs: docs.to_vec() }
+	}
+}
+
+impl IntoPortable for Field {
+	type Output = Field<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		Field {
+			name: self.name.map(Into::into),
+			ty: registry.register_type(&self.ty),
+			type_name: self.type_name.into(),
+			docs: registry.map_strings(&self.docs),
+		}
+	}
+}
+
+/// A variant of an enum.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Encode)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(bound(
+	serialize = "F::Type: Serialize, F::String: Serialize",
+	deserialize = "F::Type: Deserialize<'de>, F::String: Deserialize<'de>",
+)))]
+pub struct Variant<F: Form = MetaForm> {
+	/// The name of the variant.
+	pub name: F::String,
+	/// The fields of the variant.
+	pub fields: Vec<Field<F>>,
+	/// The index encoded before the fields of the variant.
+	pub index: u8,
+	/// The documentation of the variant.
+	pub docs: Vec<F::String>,
+}
+
+impl Variant {
+	/// Creates a variant.
+	pub fn new(
+		name: &'static str,
+		index: u8,
+		fields: Vec<Field>,
+		docs: &[&'static str],
+	) -> Self {
+		Self { name, fields, index, docs: docs.to_vec() }
+	}
+}
+
+impl IntoPortable for Variant {
+	type Output = Variant<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		Variant {
+			name: self.name.into(),
+			fields: self.fields.into_iter().map(|f| f.into_portable(registry)).collect(),
+			index: self.index,
+			docs: registry.map_strings(&self.docs),
+		}
+	}
+}
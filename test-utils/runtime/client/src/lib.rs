@@ -37,7 +37,8 @@ use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Hash as HashT, Numb
 use sc_service::client::{
 	light::fetcher::{
 		Fetcher,
-		RemoteHeaderRequest, RemoteReadRequest, RemoteReadChildRequest,
+		RemoteHeaderRequest, RemoteReadRequest, RemoteReadChildRequest, RemoteReadRangeRequest,
+		StorageRange,
 		RemoteCallRequest, RemoteChangesRequest, RemoteBodyRequest,
 	},
 };
@@ -299,6 +300,7 @@ impl LightFetcher {
 impl Fetcher<substrate_test_runtime::Block> for LightFetcher {
 	type RemoteHeaderResult = FetcherFutureResult<substrate_test_runtime::Header>;
 	type RemoteReadResult = FetcherFutureResult<HashMap<Vec<u8>, Option<Vec<u8>>>>;
+	type RemoteReadRangeResult = FetcherFutureResult<StorageRange>;
 	type RemoteCallResult = FetcherFutureResult<Vec<u8>>;
 	type RemoteChangesResult = FetcherFutureResult<Vec<(NumberFor<substrate_test_runtime::Block>, u32)>>;
 	type RemoteBodyResult = FetcherFutureResult<Vec<substrate_test_runtime::Extrinsic>>;
@@ -318,6 +320,13 @@ impl Fetcher<substrate_test_runtime::Block> for LightFetcher {
 		unimplemented!()
 	}
 
+	fn remote_read_range(
+		&self,
+		_: RemoteReadRangeRequest<substrate_test_runtime::Header>,
+	) -> Self::RemoteReadRangeResult {
+		unimplemented!()
+	}
+
 	fn remote_call(&self, req: RemoteCallRequest<substrate_test_runtime::Header>) -> Self::RemoteCallResult {
 		match self.call {
 			Some(ref call) => futures::future::ready(call(req)),
@@ -25,7 +25,7 @@ pub mod system;
 use sp_std::{prelude::*, marker::PhantomData};
 use codec::{Encode, Decode, Input, Error};
 
-use sp_core::{OpaqueMetadata, RuntimeDebug, ChangesTrieConfiguration};
+use sp_core::{OpaqueMetadata, RuntimeDebug, ChangesTrieConfiguration, crypto::KeyTypeId};
 use sp_application_crypto::{ed25519, sr25519, RuntimeAppPublic};
 use trie_db::{TrieMut, Trie};
 use sp_trie::PrefixedMemoryDB;
@@ -48,7 +48,12 @@ use sp_version::RuntimeVersion;
 pub use sp_core::hash::H256;
 #[cfg(any(feature = "std", test))]
 use sp_version::NativeVersion;
-use frame_support::{impl_outer_origin, parameter_types, weights::{Weight, RuntimeDbWeight}};
+use frame_support::{
+	impl_outer_origin, parameter_types,
+	traits::KeyOwnerProofSystem,
+	weights::{Weight, RuntimeDbWeight},
+	type_info::TypeInfo,
+};
 use sp_inherents::{CheckInherentsResult, InherentData};
 use cfg_if::cfg_if;
 
@@ -368,7 +373,7 @@ impl_outer_origin!{
 	pub enum Origin for Runtime where system = frame_system {}
 }
 
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct Event;
 
 impl From<frame_system::Event<Runtime>> for Event {
@@ -433,6 +438,18 @@ impl pallet_babe::Trait for Runtime {
 	// are manually adding the digests. normally in this situation you'd use
 	// pallet_babe::SameAuthoritiesForever.
 	type EpochChangeTrigger = pallet_babe::ExternalTrigger;
+
+	type KeyOwnerProofSystem = ();
+
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, AuthorityId)>>::Proof;
+
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		AuthorityId,
+	)>>::IdentificationTuple;
+
+	type HandleEquivocation = ();
 }
 
 /// Adds one to the given input and returns the final result.
@@ -508,6 +525,22 @@ cfg_if! {
 				fn metadata() -> OpaqueMetadata {
 					unimplemented!()
 				}
+
+				fn metadata_at_version(_: u32) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
+
+				fn metadata_versions() -> Vec<u32> {
+					unimplemented!()
+				}
+
+				fn constant(_: Vec<u8>, _: Vec<u8>) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
+
+				fn storage_entry(_: Vec<u8>, _: Vec<u8>) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
 			}
 
 			impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -651,6 +684,22 @@ cfg_if! {
 				fn current_epoch_start() -> SlotNumber {
 					<pallet_babe::Module<Runtime>>::current_epoch_start()
 				}
+
+				fn submit_report_equivocation_unsigned_extrinsic(
+					_equivocation_proof: sp_consensus_babe::EquivocationProof<
+						<Block as BlockT>::Header,
+					>,
+					_key_owner_proof: sp_consensus_babe::OpaqueKeyOwnershipProof,
+				) -> Option<()> {
+					None
+				}
+
+				fn generate_key_ownership_proof(
+					_slot_number: sp_consensus_babe::SlotNumber,
+					_authority_id: sp_consensus_babe::AuthorityId,
+				) -> Option<sp_consensus_babe::OpaqueKeyOwnershipProof> {
+					None
+				}
 			}
 
 			impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
@@ -700,6 +749,12 @@ cfg_if! {
 					0
 				}
 			}
+
+			impl frame_system_rpc_runtime_api::ExtrinsicOutcomeApi<Block> for Runtime {
+				fn extrinsic_outcome(_index: u32) -> Option<sp_runtime::DispatchResult> {
+					None
+				}
+			}
 		}
 	} else {
 		impl_runtime_apis! {
@@ -721,6 +776,22 @@ cfg_if! {
 				fn metadata() -> OpaqueMetadata {
 					unimplemented!()
 				}
+
+				fn metadata_at_version(_: u32) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
+
+				fn metadata_versions() -> Vec<u32> {
+					unimplemented!()
+				}
+
+				fn constant(_: Vec<u8>, _: Vec<u8>) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
+
+				fn storage_entry(_: Vec<u8>, _: Vec<u8>) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
 			}
 
 			impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -868,6 +939,22 @@ cfg_if! {
 				fn current_epoch_start() -> SlotNumber {
 					<pallet_babe::Module<Runtime>>::current_epoch_start()
 				}
+
+				fn submit_report_equivocation_unsigned_extrinsic(
+					_equivocation_proof: sp_consensus_babe::EquivocationProof<
+						<Block as BlockT>::Header,
+					>,
+					_key_owner_proof: sp_consensus_babe::OpaqueKeyOwnershipProof,
+				) -> Option<()> {
+					None
+				}
+
+				fn generate_key_ownership_proof(
+					_slot_number: sp_consensus_babe::SlotNumber,
+					_authority_id: sp_consensus_babe::AuthorityId,
+				) -> Option<sp_consensus_babe::OpaqueKeyOwnershipProof> {
+					None
+				}
 			}
 
 			impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
@@ -894,6 +981,12 @@ cfg_if! {
 					0
 				}
 			}
+
+			impl frame_system_rpc_runtime_api::ExtrinsicOutcomeApi<Block> for Runtime {
+				fn extrinsic_outcome(_index: u32) -> Option<sp_runtime::DispatchResult> {
+					None
+				}
+			}
 		}
 	}
 }
@@ -95,6 +95,8 @@ where
 		transaction_pool: Default::default(),
 		wasm_method: Default::default(),
 		max_runtime_instances: 8,
+		wasm_runtime_cache_path: None,
+		wasm_runtime_stack_limit: Some(Default::default()),
 		announce_block: true,
 	};
 
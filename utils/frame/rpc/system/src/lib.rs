@@ -19,7 +19,10 @@
 use std::sync::Arc;
 
 use codec::{self, Codec, Decode, Encode};
-use sc_client_api::light::{future_header, RemoteBlockchain, Fetcher, RemoteCallRequest};
+use sc_client_api::{
+	BlockBackend,
+	light::{future_header, RemoteBlockchain, Fetcher, RemoteCallRequest},
+};
 use jsonrpc_core::{
 	Error, ErrorCode,
 	futures::future::{result, Future},
@@ -32,20 +35,64 @@ use sp_blockchain::{
 };
 use sp_runtime::{
 	generic::BlockId,
-	traits,
+	traits::{self, Hash as _},
+	DispatchError, DispatchOutcome,
 };
-use sp_core::hexdisplay::HexDisplay;
+use sp_core::{Bytes, hexdisplay::HexDisplay};
+use sp_api::{Core, Metadata, RuntimeVersion};
+use sp_block_builder::BlockBuilder;
 use sp_transaction_pool::{TransactionPool, InPoolTransaction};
+use sc_rpc_api::author::outcome::ExtrinsicOutcomeResolver;
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed, RuntimeMetadataV12};
+use serde::{Serialize, Deserialize};
+use parking_lot::Mutex;
 
-pub use frame_system_rpc_runtime_api::AccountNonceApi;
+pub use frame_system_rpc_runtime_api::{AccountNonceApi, ExtrinsicOutcomeApi};
 pub use self::gen_client::Client as SystemClient;
 
 /// Future that resolves to account nonce.
 pub type FutureResult<T> = Box<dyn Future<Item = T, Error = Error> + Send>;
 
+/// A module error, resolved to the names of the module and of the error through the runtime
+/// metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleError {
+	/// The index of the module in the runtime.
+	pub index: u8,
+	/// The index of the error in the module.
+	pub error: u8,
+	/// The name of the module.
+	pub module: String,
+	/// The name of the error.
+	pub name: String,
+	/// The documentation of the error.
+	pub documentation: Vec<String>,
+}
+
+/// The outcome of an extrinsic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExtrinsicOutcome {
+	/// The extrinsic was dispatched successfully.
+	Success,
+	/// The dispatch of the extrinsic failed.
+	Failed {
+		/// The dispatch error.
+		error: String,
+		/// The module error, if the dispatch failed with an error known by the metadata.
+		module: Option<ModuleError>,
+	},
+	/// The extrinsic is invalid and can not be included in a block.
+	Invalid {
+		/// The validity error.
+		error: String,
+	},
+}
+
 /// System RPC methods.
 #[rpc]
-pub trait SystemApi<AccountId, Index> {
+pub trait SystemApi<BlockHash, AccountId, Index> {
 	/// Returns the next valid index (aka nonce) for given account.
 	///
 	/// This method takes into consideration all pending transactions
@@ -53,6 +100,23 @@ pub trait SystemApi<AccountId, Index> {
 	/// it fallbacks to query the index from the runtime (aka. state nonce).
 	#[rpc(name = "system_accountNextIndex", alias("account_nextIndex"))]
 	fn nonce(&self, account: AccountId) -> FutureResult<Index>;
+
+	/// Dry runs the given SCALE encoded extrinsic on top of the given block, or the best block.
+	///
+	/// Module errors are resolved to the names of the module and of the error.
+	#[rpc(name = "system_dryRun")]
+	fn dry_run(&self, extrinsic: Bytes, at: Option<BlockHash>) -> FutureResult<ExtrinsicOutcome>;
+
+	/// Returns the outcome of the extrinsic with the given hash in the given block.
+	///
+	/// Module errors are resolved to the names of the module and of the error. Returns `None` if
+	/// the block does not contain the extrinsic.
+	#[rpc(name = "system_extrinsicOutcome")]
+	fn extrinsic_outcome(
+		&self,
+		block: BlockHash,
+		extrinsic: BlockHash,
+	) -> FutureResult<Option<ExtrinsicOutcome>>;
 }
 
 const RUNTIME_ERROR: i64 = 1;
@@ -61,6 +125,7 @@ const RUNTIME_ERROR: i64 = 1;
 pub struct FullSystem<P: TransactionPool, C, B> {
 	client: Arc<C>,
 	pool: Arc<P>,
+	metadata: MetadataCache,
 	_marker: std::marker::PhantomData<B>,
 }
 
@@ -70,17 +135,20 @@ impl<P: TransactionPool, C, B> FullSystem<P, C, B> {
 		FullSystem {
 			client,
 			pool,
+			metadata: Default::default(),
 			_marker: Default::default(),
 		}
 	}
 }
 
-impl<P, C, Block, AccountId, Index> SystemApi<AccountId, Index> for FullSystem<P, C, Block>
+impl<P, C, Block, AccountId, Index> SystemApi<<Block as traits::Block>::Hash, AccountId, Index>
+	for FullSystem<P, C, Block>
 where
 	C: sp_api::ProvideRuntimeApi<Block>,
-	C: HeaderBackend<Block>,
+	C: HeaderBackend<Block> + BlockBackend<Block>,
 	C: Send + Sync + 'static,
 	C::Api: AccountNonceApi<Block, AccountId, Index>,
+	C::Api: ExtrinsicOutcomeApi<Block> + BlockBuilder<Block> + Core<Block> + Metadata<Block>,
 	P: TransactionPool + 'static,
 	Block: traits::Block,
 	AccountId: Clone + std::fmt::Display + Codec,
@@ -103,6 +171,92 @@ where
 
 		Box::new(result(get_nonce()))
 	}
+
+	fn dry_run(
+		&self,
+		extrinsic: Bytes,
+		at: Option<<Block as traits::Block>::Hash>,
+	) -> FutureResult<ExtrinsicOutcome> {
+		let dry_run = || {
+			let api = self.client.runtime_api();
+			let at = BlockId::<Block>::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+			let uxt: <Block as traits::Block>::Extrinsic = Decode::decode(&mut &*extrinsic)
+				.map_err(|e| Error {
+					code: ErrorCode::InvalidParams,
+					message: "Unable to decode extrinsic.".into(),
+					data: Some(format!("{:?}", e).into()),
+				})?;
+
+			let result = api.apply_extrinsic(&at, uxt).map_err(|e| Error {
+				code: ErrorCode::ServerError(RUNTIME_ERROR),
+				message: "Unable to dry run extrinsic.".into(),
+				data: Some(format!("{:?}", e).into()),
+			})?;
+
+			Ok(match result {
+				Ok(outcome) => dispatch_outcome(&*self.client, &self.metadata, &at, outcome),
+				Err(error) => ExtrinsicOutcome::Invalid { error: format!("{:?}", error) },
+			})
+		};
+
+		Box::new(result(dry_run()))
+	}
+
+	fn extrinsic_outcome(
+		&self,
+		block: <Block as traits::Block>::Hash,
+		extrinsic: <Block as traits::Block>::Hash,
+	) -> FutureResult<Option<ExtrinsicOutcome>> {
+		let outcome = find_extrinsic_outcome(
+			&*self.client,
+			&self.metadata,
+			block,
+			|uxt| traits::HashFor::<Block>::hash_of(uxt) == extrinsic,
+		);
+
+		Box::new(result(outcome))
+	}
+}
+
+/// Resolves the dispatch outcome of the extrinsics watched through
+/// `author_submitAndWatchExtrinsicWithOutcome` to an `ExtrinsicOutcome`.
+pub struct ExtrinsicOutcomes<C, Block> {
+	client: Arc<C>,
+	metadata: MetadataCache,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> ExtrinsicOutcomes<C, Block> {
+	/// Create new `ExtrinsicOutcomes` given client.
+	pub fn new(client: Arc<C>) -> Self {
+		ExtrinsicOutcomes {
+			client,
+			metadata: Default::default(),
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block> ExtrinsicOutcomeResolver<Block> for ExtrinsicOutcomes<C, Block>
+where
+	C: sp_api::ProvideRuntimeApi<Block> + BlockBackend<Block>,
+	C: Send + Sync + 'static,
+	C::Api: ExtrinsicOutcomeApi<Block> + Core<Block> + Metadata<Block>,
+	Block: traits::Block,
+{
+	fn extrinsic_outcome(
+		&self,
+		block: &<Block as traits::Block>::Hash,
+		extrinsic: &<Block as traits::Block>::Extrinsic,
+	) -> Option<serde_json::Value> {
+		let is_extrinsic = |uxt: &<Block as traits::Block>::Extrinsic| uxt == extrinsic;
+		let outcome = find_extrinsic_outcome(&*self.client, &self.metadata, *block, is_extrinsic)
+			.map_err(|e| log::debug!(target: "rpc", "Unable to resolve extrinsic outcome: {:?}", e))
+			.ok()??;
+
+		serde_json::to_value(outcome).ok()
+	}
 }
 
 /// An implementation of System-specific RPC methods on light client.
@@ -130,7 +284,8 @@ impl<P: TransactionPool, C, F, Block> LightSystem<P, C, F, Block> {
 	}
 }
 
-impl<P, C, F, Block, AccountId, Index> SystemApi<AccountId, Index> for LightSystem<P, C, F, Block>
+impl<P, C, F, Block, AccountId, Index> SystemApi<<Block as traits::Block>::Hash, AccountId, Index>
+	for LightSystem<P, C, F, Block>
 where
 	P: TransactionPool + 'static,
 	C: HeaderBackend<Block>,
@@ -175,6 +330,164 @@ where
 
 		Box::new(future_nonce)
 	}
+
+	fn dry_run(
+		&self,
+		_extrinsic: Bytes,
+		_at: Option<<Block as traits::Block>::Hash>,
+	) -> FutureResult<ExtrinsicOutcome> {
+		Box::new(result(Err(not_available_on_light_client())))
+	}
+
+	fn extrinsic_outcome(
+		&self,
+		_block: <Block as traits::Block>::Hash,
+		_extrinsic: <Block as traits::Block>::Hash,
+	) -> FutureResult<Option<ExtrinsicOutcome>> {
+		Box::new(result(Err(not_available_on_light_client())))
+	}
+}
+
+fn not_available_on_light_client() -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Not available on light clients.".into(),
+		data: None,
+	}
+}
+
+/// Returns the outcome of the first extrinsic of the given block matching `is_extrinsic`, `None`
+/// if no extrinsic of the block matches.
+fn find_extrinsic_outcome<Block, C>(
+	client: &C,
+	metadata: &MetadataCache,
+	block: <Block as traits::Block>::Hash,
+	is_extrinsic: impl Fn(&<Block as traits::Block>::Extrinsic) -> bool,
+) -> Result<Option<ExtrinsicOutcome>, Error> where
+	Block: traits::Block,
+	C: sp_api::ProvideRuntimeApi<Block> + BlockBackend<Block>,
+	C::Api: ExtrinsicOutcomeApi<Block> + Core<Block> + Metadata<Block>,
+{
+	let at = BlockId::<Block>::hash(block);
+
+	let body = client.block_body(&at).map_err(|e| Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query block body.".into(),
+		data: Some(format!("{:?}", e).into()),
+	})?;
+	let index = match body.unwrap_or_default().iter().position(is_extrinsic) {
+		Some(index) => index as u32,
+		None => return Ok(None),
+	};
+
+	let outcome = client.runtime_api().extrinsic_outcome(&at, index).map_err(|e| Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Unable to query extrinsic outcome.".into(),
+		data: Some(format!("{:?}", e).into()),
+	})?;
+
+	Ok(outcome.map(|outcome| dispatch_outcome(client, metadata, &at, outcome)))
+}
+
+/// Converts the outcome of a dispatch into an `ExtrinsicOutcome`, resolving a module error
+/// through the metadata of the runtime at `at`.
+fn dispatch_outcome<Block, C>(
+	client: &C,
+	metadata: &MetadataCache,
+	at: &BlockId<Block>,
+	outcome: DispatchOutcome,
+) -> ExtrinsicOutcome where
+	Block: traits::Block,
+	C: sp_api::ProvideRuntimeApi<Block>,
+	C::Api: Core<Block> + Metadata<Block>,
+{
+	let error = match outcome {
+		Ok(()) => return ExtrinsicOutcome::Success,
+		Err(error) => error,
+	};
+	let module = match error {
+		DispatchError::Module { index, error, .. } =>
+			resolve_module_error(client, metadata, at, index, error),
+		_ => None,
+	};
+
+	ExtrinsicOutcome::Failed { error: format!("{:?}", error), module }
+}
+
+/// Resolves a module error to the names of the module and of the error, `None` if the runtime
+/// metadata does not describe it.
+fn resolve_module_error<Block, C>(
+	client: &C,
+	metadata: &MetadataCache,
+	at: &BlockId<Block>,
+	index: u8,
+	error: u8,
+) -> Option<ModuleError> where
+	Block: traits::Block,
+	C: sp_api::ProvideRuntimeApi<Block>,
+	C::Api: Core<Block> + Metadata<Block>,
+{
+	let api = client.runtime_api();
+	let version = api.version(at)
+		.map_err(|e| log::debug!(target: "rpc", "Unable to query runtime version: {:?}", e))
+		.ok()?;
+	let metadata = metadata.get_or_decode(version, || {
+		// Only the V12 metadata describes the errors of a module by its index.
+		let metadata = api.metadata_at_version(at, 12)
+			.map_err(|e| log::debug!(target: "rpc", "Unable to query metadata: {:?}", e))
+			.ok()??;
+		decode_metadata(&metadata)
+	})?;
+
+	module_error(&metadata, index, error)
+}
+
+/// Decodes the given encoded runtime metadata, `None` if it is not V12 metadata.
+fn decode_metadata(metadata: &[u8]) -> Option<RuntimeMetadataV12> {
+	match RuntimeMetadataPrefixed::decode(&mut &metadata[..]).ok()?.1 {
+		RuntimeMetadata::V12(metadata) => Some(metadata),
+		_ => None,
+	}
+}
+
+/// Resolves a module error through the given runtime metadata.
+fn module_error(metadata: &RuntimeMetadataV12, index: u8, error: u8) -> Option<ModuleError> {
+	let (module, module_error) = metadata.module_error(index, error)?;
+
+	Some(ModuleError {
+		index,
+		error,
+		module: module.name.clone(),
+		name: module_error.name.clone(),
+		documentation: module_error.documentation.clone(),
+	})
+}
+
+/// The decoded metadata of the last runtime version a module error was resolved with.
+///
+/// Decoding the metadata of the whole runtime is expensive, so it is only done once per runtime
+/// version instead of once per failed extrinsic.
+#[derive(Default)]
+struct MetadataCache(Mutex<Option<(RuntimeVersion, Arc<RuntimeMetadataV12>)>>);
+
+impl MetadataCache {
+	/// Returns the metadata of the given runtime version, decoding it with `decode` if it is not
+	/// the cached one.
+	fn get_or_decode(
+		&self,
+		version: RuntimeVersion,
+		decode: impl FnOnce() -> Option<RuntimeMetadataV12>,
+	) -> Option<Arc<RuntimeMetadataV12>> {
+		if let Some((cached_version, metadata)) = &*self.0.lock() {
+			if *cached_version == version {
+				return Some(metadata.clone());
+			}
+		}
+
+		let metadata = Arc::new(decode()?);
+		*self.0.lock() = Some((version, metadata.clone()));
+		Some(metadata)
+	}
 }
 
 /// Adjust account nonce from state, so that tx with the nonce will be
@@ -222,7 +535,7 @@ mod tests {
 
 	use futures::executor::block_on;
 	use substrate_test_runtime_client::{
-		runtime::Transfer,
+		runtime::{Transfer, AccountId, Index},
 		AccountKeyring,
 	};
 	use sc_transaction_pool::{BasicPool, FullChainApi};
@@ -264,4 +577,67 @@ mod tests {
 		// then
 		assert_eq!(nonce.wait().unwrap(), 2);
 	}
+
+	#[test]
+	fn dry_run_should_work() {
+		let _ = env_logger::try_init();
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let pool = Arc::new(
+			BasicPool::new(
+				Default::default(),
+				Arc::new(FullChainApi::new(client.clone())),
+				None,
+			).0
+		);
+
+		let accounts = FullSystem::new(client, pool);
+		let transfer = Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Bob.into(),
+			amount: 5,
+			nonce: 0,
+		};
+		let extrinsic = transfer.into_signed_tx().encode();
+
+		let outcome = SystemApi::<_, AccountId, Index>::dry_run(&accounts, extrinsic.into(), None);
+		assert_eq!(outcome.wait().unwrap(), ExtrinsicOutcome::Success);
+
+		let genesis = accounts.client.info().genesis_hash;
+		let outcome = SystemApi::<_, AccountId, Index>::extrinsic_outcome(
+			&accounts,
+			genesis,
+			Default::default(),
+		);
+		assert_eq!(outcome.wait().unwrap(), None);
+	}
+	#[test]
+	fn module_errors_are_resolved_to_their_module_and_name() {
+		let metadata = decode_metadata(&node_runtime::Runtime::metadata_v12().encode()).unwrap();
+		let error: DispatchError =
+			pallet_balances::Error::<node_runtime::Runtime>::InsufficientBalance.into();
+		let (index, error) = match error {
+			DispatchError::Module { index, error, .. } => (index, error),
+			error => panic!("Expected a module error, got {:?}", error),
+		};
+
+		let module_error = module_error(&metadata, index, error).unwrap();
+		assert_eq!(module_error.module, "Balances");
+		assert_eq!(module_error.name, "InsufficientBalance");
+		assert!(!module_error.documentation.is_empty());
+	}
+
+	#[test]
+	fn metadata_is_decoded_once_per_runtime_version() {
+		let metadata = || decode_metadata(&node_runtime::Runtime::metadata_v12().encode());
+		let version = |spec_version| RuntimeVersion { spec_version, ..Default::default() };
+		let cache = MetadataCache::default();
+		let mut decoded = 0;
+
+		assert!(cache.get_or_decode(version(1), || { decoded += 1; metadata() }).is_some());
+		assert!(cache.get_or_decode(version(1), || { decoded += 1; metadata() }).is_some());
+		assert_eq!(decoded, 1);
+
+		assert!(cache.get_or_decode(version(2), || { decoded += 1; metadata() }).is_some());
+		assert_eq!(decoded, 2);
+	}
 }